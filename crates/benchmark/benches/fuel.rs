@@ -0,0 +1,99 @@
+//! Benchmarks for the overhead of fuel metering. Every workload is run without fuel and with enough fuel to finish, so
+//! that the difference between both shows the cost of charging fuel per basic block.
+
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use wasm::{resumable::RunState, validate, Store, Value};
+
+/// Benchmarks the function `entry_function` of the module `wat`, which takes an iteration count, with and without fuel
+fn bench_metered(c: &mut Criterion, name: &str, wat: &str, entry_function: &str) {
+    let wasm_bytes = wat::parse_str(wat).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(());
+    // SAFETY: Only one store is used. Therefore, this must always be the
+    // correct one.
+    let module = unsafe { store.module_instantiate(&validation_info, Vec::new(), None) }
+        .unwrap()
+        .module_addr;
+    // SAFETY: Only one store is used. Therefore, this must always be the
+    // correct one.
+    let function = unsafe { store.instance_export(module, entry_function) }
+        .unwrap()
+        .as_func()
+        .unwrap();
+
+    let mut group = c.benchmark_group(name);
+    for n in (0..=12).map(|p| 1 << p) {
+        group.throughput(Throughput::Elements(n as u64));
+        for (id, maybe_fuel) in [("unmetered", None), ("metered", Some(u64::MAX))] {
+            group.bench_with_input(BenchmarkId::new(id, n), &n, |b, &n| {
+                b.iter(|| {
+                    // SAFETY: Only one store is used. Therefore, this must
+                    // always be the correct one.
+                    let run_state =
+                        unsafe { store.invoke(function, vec![Value::I32(n)], maybe_fuel) }.unwrap();
+                    assert!(matches!(run_state, RunState::Finished { .. }));
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+fn straight_line_loop(c: &mut Criterion) {
+    bench_metered(
+        c,
+        "straight_line_loop",
+        r#"
+(module
+  (func (export "run") (param $n i32) (result i32)
+    (local $i i32) (local $acc i32)
+    (block $done
+      (loop $next
+        (br_if $done (i32.ge_u (local.get $i) (local.get $n)))
+        (local.set $acc (i32.add (local.get $acc) (i32.mul (local.get $i) (i32.const 3))))
+        (local.set $acc (i32.xor (local.get $acc) (i32.shl (local.get $acc) (i32.const 5))))
+        (local.set $acc (i32.sub (local.get $acc) (i32.shr_u (local.get $acc) (i32.const 7))))
+        (local.set $acc (i32.rotl (local.get $acc) (local.get $i)))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next)))
+    (local.get $acc)))
+"#,
+        "run",
+    );
+}
+
+fn calls(c: &mut Criterion) {
+    bench_metered(
+        c,
+        "calls",
+        r#"
+(module
+  (func $square (param $x i32) (result i32)
+    (i32.mul (local.get $x) (local.get $x)))
+
+  (func (export "run") (param $n i32) (result i32)
+    (local $i i32) (local $acc i32)
+    (block $done
+      (loop $next
+        (br_if $done (i32.ge_u (local.get $i) (local.get $n)))
+        (local.set $acc (i32.add (local.get $acc) (call $square (local.get $i))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next)))
+    (local.get $acc)))
+"#,
+        "run",
+    );
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default()
+        .warm_up_time(Duration::from_millis(500))
+        .measurement_time(Duration::from_secs(1));
+    targets = straight_line_loop, calls
+}
+
+criterion_main!(benches);
@@ -0,0 +1,100 @@
+//! Benchmarks for the cache of pre-decoded immediates. The same module is validated and executed with the cache
+//! disabled, i.e. in pure in-place mode, and with the cache enabled.
+
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use wasm::{resumable::RunState, validate_with_options, Store, ValidationOptions, Value};
+
+const WAT: &str = r#"
+(module
+  (memory 1)
+  (global $scale (mut i32) (i32.const 3))
+
+  (func $weight (param $x i32) (result i32)
+    (i32.mul (local.get $x) (global.get $scale)))
+
+  ;; runs $n iterations of a loop full of instructions with multi-byte immediates
+  (func (export "run") (param $n i32) (result i32)
+    (local $i i32) (local $sum i32)
+    (block $done
+      (loop $next
+        (br_if $done (i32.ge_u (local.get $i) (local.get $n)))
+        (i32.store offset=0x1234 (i32.and (local.get $i) (i32.const 0x3ffc)) (call $weight (local.get $i)))
+        (local.set $sum
+          (i32.add
+            (local.get $sum)
+            (i32.load offset=0x1234 (i32.and (local.get $i) (i32.const 0x3ffc)))))
+        (global.set $scale (i32.add (global.get $scale) (i32.const -100000)))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next)))
+    (i32.add (local.get $sum) (i32.const 0x7fffffff)))
+)
+"#;
+
+/// The cache limits every workload is run with, where a limit of zero disables the cache
+const LIMITS: [(&str, usize); 2] = [("in_place", 0), ("cached", usize::MAX)];
+
+/// Benchmarks the validation of [`WAT`], which also builds the cache of pre-decoded immediates
+fn validation(c: &mut Criterion) {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let mut group = c.benchmark_group("validation");
+    group.throughput(Throughput::Bytes(wasm_bytes.len() as u64));
+    for (id, immediates_cache_limit) in LIMITS {
+        let options = ValidationOptions {
+            immediates_cache_limit,
+        };
+        group.bench_function(id, |b| {
+            b.iter(|| validate_with_options(&wasm_bytes, options).unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// Benchmarks `n` iterations of the loop of [`WAT`]
+fn execution(c: &mut Criterion) {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let mut group = c.benchmark_group("execution");
+    for (id, immediates_cache_limit) in LIMITS {
+        let options = ValidationOptions {
+            immediates_cache_limit,
+        };
+        let validation_info = validate_with_options(&wasm_bytes, options).unwrap();
+        let mut store = Store::new(());
+        // SAFETY: Only one store is used. Therefore, this must always be the
+        // correct one.
+        let module = unsafe { store.module_instantiate(&validation_info, Vec::new(), None) }
+            .unwrap()
+            .module_addr;
+        // SAFETY: Only one store is used. Therefore, this must always be the
+        // correct one.
+        let function = unsafe { store.instance_export(module, "run") }
+            .unwrap()
+            .as_func()
+            .unwrap();
+        for n in (0..=12).step_by(4).map(|p| 1 << p) {
+            group.throughput(Throughput::Elements(n as u64));
+            group.bench_with_input(BenchmarkId::new(id, n), &n, |b, &n| {
+                b.iter(|| {
+                    // SAFETY: Only one store is used. Therefore, this must
+                    // always be the correct one.
+                    let run_state =
+                        unsafe { store.invoke(function, vec![Value::I32(n)], None) }.unwrap();
+                    assert!(matches!(run_state, RunState::Finished { .. }));
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default()
+        .warm_up_time(Duration::from_millis(500))
+        .measurement_time(Duration::from_secs(1));
+    targets = validation, execution
+}
+
+criterion_main!(benches);
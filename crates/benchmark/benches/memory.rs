@@ -0,0 +1,128 @@
+//! Benchmarks for accesses to linear memories, both by Wasm code and by the host through the accessors of the
+//! [`Store`]. Every workload is run on a dense and on a sparse memory.
+
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use wasm::{
+    addrs::{MemAddr, ModuleAddr},
+    resumable::RunState,
+    validate, Store, Value,
+};
+
+const WAT: &str = r#"
+(module
+  (memory (export "memory") 2)
+
+  ;; sums $n 64-bit words and writes their running sum back
+  (func (export "words") (param $n i32) (result i64)
+    (local $i i32) (local $acc i64)
+    (block $done
+      (loop $next
+        (br_if $done (i32.ge_u (local.get $i) (local.get $n)))
+        (local.set $acc (i64.add (local.get $acc) (i64.load (i32.shl (local.get $i) (i32.const 3)))))
+        (i64.store (i32.shl (local.get $i) (i32.const 3)) (local.get $acc))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next)))
+    (local.get $acc))
+
+  ;; fills $n bytes of the first page and copies them to the second page
+  (func (export "bulk") (param $n i32) (result i32)
+    (memory.fill (i32.const 0) (i32.const 0x2a) (local.get $n))
+    (memory.copy (i32.const 0x10000) (i32.const 0) (local.get $n))
+    (i32.load8_u (i32.const 0x10000)))
+)
+"#;
+
+/// Instantiates [`WAT`] in a new store with dense or sparse memories and passes the store, the module and its exported
+/// memory to `bench`
+fn with_instance(sparse: bool, bench: impl FnOnce(&mut Store<()>, ModuleAddr, MemAddr)) {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(());
+    store.set_sparse_memories(sparse);
+    // SAFETY: Only one store is used. Therefore, this must always be the
+    // correct one.
+    let module = unsafe { store.module_instantiate(&validation_info, Vec::new(), None) }
+        .unwrap()
+        .module_addr;
+    // SAFETY: Only one store is used. Therefore, this must always be the
+    // correct one.
+    let memory = unsafe { store.instance_export(module, "memory") }
+        .unwrap()
+        .as_mem()
+        .unwrap();
+    bench(&mut store, module, memory);
+}
+
+/// Benchmarks the function `entry_function` of [`WAT`], which takes a count of words or bytes
+fn bench_wasm(c: &mut Criterion, entry_function: &str, max_log_n: u32) {
+    let mut group = c.benchmark_group(entry_function);
+    for (id, sparse) in [("dense", false), ("sparse", true)] {
+        with_instance(sparse, |store, module, _| {
+            // SAFETY: Only one store is used. Therefore, this must always be
+            // the correct one.
+            let function = unsafe { store.instance_export(module, entry_function) }
+                .unwrap()
+                .as_func()
+                .unwrap();
+            for n in (0..=max_log_n).step_by(2).map(|p| 1 << p) {
+                group.throughput(Throughput::Elements(n as u64));
+                group.bench_with_input(BenchmarkId::new(id, n), &n, |b, &n| {
+                    b.iter(|| {
+                        // SAFETY: Only one store is used. Therefore, this must
+                        // always be the correct one.
+                        let run_state =
+                            unsafe { store.invoke(function, vec![Value::I32(n)], None) }.unwrap();
+                        assert!(matches!(run_state, RunState::Finished { .. }));
+                    })
+                });
+            }
+        });
+    }
+    group.finish();
+}
+
+fn words(c: &mut Criterion) {
+    bench_wasm(c, "words", 12);
+}
+
+fn bulk(c: &mut Criterion) {
+    bench_wasm(c, "bulk", 16);
+}
+
+/// Benchmarks writing, reading and copying `n` bytes through the memory accessors of the store
+fn host_accessors(c: &mut Criterion) {
+    let mut group = c.benchmark_group("host_accessors");
+    for (id, sparse) in [("dense", false), ("sparse", true)] {
+        with_instance(sparse, |store, _, memory| {
+            for n in (0..=16).step_by(4).map(|p| 1 << p) {
+                let mut buffer = vec![0x2a; n as usize];
+                group.throughput(Throughput::Bytes(3 * u64::from(n)));
+                group.bench_with_input(BenchmarkId::new(id, n), &n, |b, &n| {
+                    b.iter(|| {
+                        // SAFETY: Only one store is used. Therefore, this must
+                        // always be the correct one.
+                        unsafe {
+                            store.mem_write_bytes(memory, 0, &buffer).unwrap();
+                            store.mem_copy(memory, 0x10000, memory, 0, n).unwrap();
+                            store.mem_read_bytes(memory, 0x10000, &mut buffer).unwrap();
+                        }
+                    })
+                });
+            }
+        });
+    }
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default()
+        .warm_up_time(Duration::from_millis(500))
+        .measurement_time(Duration::from_secs(1));
+    targets = words, bulk, host_accessors
+}
+
+criterion_main!(benches);
@@ -0,0 +1,102 @@
+//! Benchmarks for workloads dominated by value stack traffic, i.e. pushing and popping operands, accessing locals and
+//! passing values between functions. Use `bench-against-main` to compare changes of the value stack representation.
+
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use interop::StoreTypedInvocationExt;
+use wasm::{validate, Store};
+
+/// Benchmarks the function `entry_function` of the module `wat`, which takes an iteration count
+fn bench_iterations(c: &mut Criterion, name: &str, wat: &str, entry_function: &str) {
+    let wasm_bytes = wat::parse_str(wat).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(());
+    // SAFETY: Only one store is used. Therefore, this must always be the
+    // correct one.
+    let module = unsafe { store.module_instantiate(&validation_info, Vec::new(), None) }
+        .unwrap()
+        .module_addr;
+    // SAFETY: Only one store is used. Therefore, this must always be the
+    // correct one.
+    let function = unsafe { store.instance_export(module, entry_function) }
+        .unwrap()
+        .as_func()
+        .unwrap();
+
+    let mut group = c.benchmark_group(name);
+    for n in (0..=12).map(|p| 1 << p) {
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new("our", n), &n, |b, &n| {
+            b.iter(|| {
+                // SAFETY: Only one store is used. Therefore, this must always
+                // be the correct one.
+                unsafe { store.invoke_simple_typed::<i32, i64>(function, n) }.unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn mixed_scalar_operands(c: &mut Criterion) {
+    bench_iterations(
+        c,
+        "mixed_scalar_operands",
+        r#"
+(module
+  (func $step (param $acc i64) (param $x f64) (param $i i32) (result i64 f64)
+    (i64.add (local.get $acc) (i64.extend_i32_u (i32.mul (local.get $i) (local.get $i))))
+    (f64.add (local.get $x) (f64.convert_i32_s (local.get $i))))
+
+  (func (export "run") (param $n i32) (result i64)
+    (local $i i32) (local $acc i64) (local $x f64)
+    (block $done
+      (loop $next
+        (br_if $done (i32.ge_u (local.get $i) (local.get $n)))
+        (call $step (local.get $acc) (local.get $x) (local.get $i))
+        (local.set $x)
+        (local.set $acc)
+        (local.set $acc
+          (select (local.get $acc) (i64.const 0) (f64.lt (local.get $x) (f64.const 1e300))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next)))
+    (i64.add (local.get $acc) (i64.trunc_f64_s (local.get $x)))))
+"#,
+        "run",
+    );
+}
+
+fn v128_operands(c: &mut Criterion) {
+    bench_iterations(
+        c,
+        "v128_operands",
+        r#"
+(module
+  (func $step (param $v v128) (param $i i32) (result v128)
+    (i32x4.add (local.get $v) (i32x4.splat (local.get $i))))
+
+  (func (export "run") (param $n i32) (result i64)
+    (local $i i32) (local $v v128)
+    (block $done
+      (loop $next
+        (br_if $done (i32.ge_u (local.get $i) (local.get $n)))
+        (local.set $v
+          (select (call $step (local.get $v) (local.get $i)) (local.get $v) (i32.const 1)))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next)))
+    (i64.add (i64x2.extract_lane 0 (local.get $v)) (i64x2.extract_lane 1 (local.get $v)))))
+"#,
+        "run",
+    );
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default()
+        .warm_up_time(Duration::from_millis(500))
+        .measurement_time(Duration::from_secs(1));
+    targets = mixed_scalar_operands, v128_operands
+}
+
+criterion_main!(benches);
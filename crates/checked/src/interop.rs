@@ -258,11 +258,10 @@ impl<T: Config> Store<'_, T> {
     /// [`StoreTypedInvocationExt::func_alloc_typed`](interop::StoreTypedInvocationExt::func_alloc_typed).
     /// It is functionally equal, with the only difference being that this
     /// function returns a [`Stored<FuncAddr>`].
-    #[allow(clippy::let_and_return)] // reason = "to follow the 1234 structure"
     pub fn func_alloc_typed<Params: StoredInteropValueList, Returns: StoredInteropValueList>(
         &mut self,
         host_func: Hostcode,
-    ) -> Stored<FuncAddr> {
+    ) -> Result<Stored<FuncAddr>, RuntimeError> {
         let func_type = FuncType {
             params: ResultType {
                 valtypes: Vec::from(Params::TYS),
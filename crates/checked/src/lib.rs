@@ -34,6 +34,7 @@ use alloc::vec::Vec;
 
 mod store;
 mod stored_types;
+pub mod trace;
 mod value;
 
 pub use store::*;
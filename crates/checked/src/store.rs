@@ -1,13 +1,17 @@
-use alloc::{string::String, vec::Vec};
+use alloc::{string::String, vec, vec::Vec};
+use core::ops::Range;
 use wasm::{
-    addrs::{FuncAddr, GlobalAddr, MemAddr, ModuleAddr, TableAddr},
-    config::Config,
+    addrs::{FuelPoolAddr, FuncAddr, GlobalAddr, MemAddr, ModuleAddr, TableAddr},
+    config::{Config, ResourceUsage, StackLimits},
+    memory_backing::MemoryBacking,
     resumable::{HostResumable, WasmResumable},
-    FuncType, GlobalType, Hostcode, MemType, RuntimeError, TableType, ValidationInfo,
+    FuncType, GlobalType, Hostcode, MemType, MemoryProtection, MemoryValue, RuntimeError,
+    TableType, ValidationInfo,
 };
 
 use crate::{
     stored_types::{Stored, StoredExternVal, StoredInstantiationOutcome, StoredRunState},
+    trace::StoreWrite,
     AbstractStored, StoreId, StoredRef, StoredResumable, StoredValue,
 };
 
@@ -17,6 +21,11 @@ pub struct Store<'b, T: Config> {
     /// A unique identifier for this store. This is used to verify that stored
     /// objects belong to the current [`Store`](wasm::Store).
     pub(crate) id: StoreId,
+
+    /// The writes made to this store through its methods, which are only
+    /// recorded while a [`TraceRecorder`](crate::trace::TraceRecorder) is
+    /// recording an execution in it.
+    pub(crate) journal: Option<Vec<StoreWrite>>,
 }
 
 impl<'b, T: Config> Store<'b, T> {
@@ -34,6 +43,24 @@ impl<'b, T: Config> Store<'b, T> {
     pub fn id(&self) -> StoreId {
         self.id
     }
+
+    /// Returns a mutable reference to the user data of the raw store.
+    pub fn user_data_mut(&mut self) -> &mut T {
+        &mut self.inner.user_data
+    }
+
+    /// Returns a digest of the state of this store, see
+    /// [`Store::state_digest`](wasm::Store::state_digest).
+    pub fn state_digest(&self) -> u64 {
+        self.inner.state_digest()
+    }
+
+    /// Records a write to this store, if writes are currently recorded.
+    fn record_write(&mut self, write: impl FnOnce() -> StoreWrite) {
+        if let Some(journal) = &mut self.journal {
+            journal.push(write());
+        }
+    }
 }
 
 // All functions in this impl block must occur in the same order as they are
@@ -49,6 +76,7 @@ impl<'b, T: Config> Store<'b, T> {
         Self {
             inner: wasm::Store::new(user_data),
             id: StoreId::new(),
+            journal: None,
         }
     }
 
@@ -100,16 +128,20 @@ impl<'b, T: Config> Store<'b, T> {
     /// This is a variant of [`Store::func_alloc`](wasm::Store::func_alloc). It
     /// is functionally equal, with the only difference being that this function
     /// returns a [`Stored<FuncAddr>`].
-    #[allow(clippy::let_and_return)] // reason = "to follow the 1234 structure"
-    pub fn func_alloc(&mut self, func_type: FuncType, hostcode: Hostcode) -> Stored<FuncAddr> {
+    pub fn func_alloc(
+        &mut self,
+        func_type: FuncType,
+        hostcode: Hostcode,
+    ) -> Result<Stored<FuncAddr>, RuntimeError> {
         // 1. try unwrap
         // no stored parameters
         // 2. call
-        let func_addr = self.inner.func_alloc(func_type, hostcode);
+        let func_addr = self.inner.func_alloc(func_type, hostcode)?;
         // 3. rewrap
-        // 4. return
         // SAFETY: The function address just came from the current store.
-        unsafe { Stored::from_bare(func_addr, self.id) }
+        let stored_func_addr = unsafe { Stored::from_bare(func_addr, self.id) };
+        // 4. return
+        Ok(stored_func_addr)
     }
 
     /// This is a safe variant of [`Store::func_type`](wasm::Store::func_type).
@@ -215,6 +247,11 @@ impl<'b, T: Config> Store<'b, T> {
         // SAFETY: It was just checked that the `TableAddr` and any address in
         // the reference came from the current store through their store ids.
         unsafe { self.inner.table_write(table_addr, i, r#ref) }?;
+        self.record_write(|| StoreWrite::Table {
+            table_addr,
+            i,
+            r#ref,
+        });
         // 3. rewrap
         // result is the unit type.
         // 4. return
@@ -237,16 +274,35 @@ impl<'b, T: Config> Store<'b, T> {
 
     /// This is a variant of [`Store::mem_alloc`](wasm::Store::mem_alloc) that
     /// returns a stored object.
-    #[allow(clippy::let_and_return)] // reason = "to follow the 1234 structure"
-    pub fn mem_alloc(&mut self, mem_type: MemType) -> Stored<MemAddr> {
+    pub fn mem_alloc(&mut self, mem_type: MemType) -> Result<Stored<MemAddr>, RuntimeError> {
         // 1. try unwrap
         // no stored parameters
         // 2. call
-        let mem_addr = self.inner.mem_alloc(mem_type);
+        let mem_addr = self.inner.mem_alloc(mem_type)?;
         // 3. rewrap
+        // SAFETY: The `MemAddr` just came from the current store.
+        let stored_mem_addr = unsafe { Stored::from_bare(mem_addr, self.id) };
         // 4. return
+        Ok(stored_mem_addr)
+    }
+
+    /// This is a variant of
+    /// [`Store::mem_alloc_with_backing`](wasm::Store::mem_alloc_with_backing)
+    /// that returns a stored object.
+    pub fn mem_alloc_with_backing(
+        &mut self,
+        mem_type: MemType,
+        backing: MemoryBacking,
+    ) -> Result<Stored<MemAddr>, RuntimeError> {
+        // 1. try unwrap
+        // no stored parameters
+        // 2. call
+        let mem_addr = self.inner.mem_alloc_with_backing(mem_type, backing)?;
+        // 3. rewrap
         // SAFETY: The `MemAddr` just came from the current store.
-        unsafe { Stored::from_bare(mem_addr, self.id) }
+        let stored_mem_addr = unsafe { Stored::from_bare(mem_addr, self.id) };
+        // 4. return
+        Ok(stored_mem_addr)
     }
 
     /// This is a safe variant of [`Store::mem_type`](wasm::Store::mem_type).
@@ -263,7 +319,7 @@ impl<'b, T: Config> Store<'b, T> {
     }
 
     /// This is a safe variant of [`Store::mem_read`](wasm::Store::mem_read).
-    pub fn mem_read(&self, mem_addr: Stored<MemAddr>, i: u32) -> Result<u8, RuntimeError> {
+    pub fn mem_read(&mut self, mem_addr: Stored<MemAddr>, i: u32) -> Result<u8, RuntimeError> {
         // 1. try unwrap
         let mem_addr = mem_addr.try_unwrap_into_bare(self.id);
         // 2. call
@@ -289,6 +345,128 @@ impl<'b, T: Config> Store<'b, T> {
         // SAFETY: It was just checked that the `MemAddr` came from the current
         // store through its store id.
         unsafe { self.inner.mem_write(mem_addr, i, byte) }?;
+        self.record_write(|| StoreWrite::Memory {
+            mem_addr,
+            i,
+            bytes: vec![byte],
+        });
+        // 3. rewrap
+        // result is the unit type.
+        // 4. return
+        Ok(())
+    }
+
+    /// This is a safe variant of [`Store::mem_read_bytes`](wasm::Store::mem_read_bytes).
+    pub fn mem_read_bytes(
+        &mut self,
+        mem_addr: Stored<MemAddr>,
+        i: u32,
+        buffer: &mut [u8],
+    ) -> Result<(), RuntimeError> {
+        // 1. try unwrap
+        let mem_addr = mem_addr.try_unwrap_into_bare(self.id);
+        // 2. call
+        // SAFETY: It was just checked that the `MemAddr` came from the current
+        // store through its store id.
+        unsafe { self.inner.mem_read_bytes(mem_addr, i, buffer) }?;
+        // 3. rewrap
+        // result is the unit type.
+        // 4. return
+        Ok(())
+    }
+
+    /// This is a safe variant of [`Store::mem_write_bytes`](wasm::Store::mem_write_bytes).
+    pub fn mem_write_bytes(
+        &mut self,
+        mem_addr: Stored<MemAddr>,
+        i: u32,
+        bytes: &[u8],
+    ) -> Result<(), RuntimeError> {
+        // 1. try unwrap
+        let mem_addr = mem_addr.try_unwrap_into_bare(self.id);
+        // 2. call
+        // SAFETY: It was just checked that the `MemAddr` came from the current
+        // store through its store id.
+        unsafe { self.inner.mem_write_bytes(mem_addr, i, bytes) }?;
+        self.record_write(|| StoreWrite::Memory {
+            mem_addr,
+            i,
+            bytes: bytes.to_vec(),
+        });
+        // 3. rewrap
+        // result is the unit type.
+        // 4. return
+        Ok(())
+    }
+
+    /// This is a safe variant of [`Store::mem_read_typed`](wasm::Store::mem_read_typed).
+    pub fn mem_read_typed<V: MemoryValue>(
+        &mut self,
+        mem_addr: Stored<MemAddr>,
+        i: u32,
+    ) -> Result<V, RuntimeError> {
+        // 1. try unwrap
+        let mem_addr = mem_addr.try_unwrap_into_bare(self.id);
+        // 2. call
+        // SAFETY: It was just checked that the `MemAddr` came from the current
+        // store through its store id.
+        let value = unsafe { self.inner.mem_read_typed(mem_addr, i) }?;
+        // 3. rewrap
+        // plain numbers and bytes do not have a stored variant.
+        // 4. return
+        Ok(value)
+    }
+
+    /// This is a safe variant of [`Store::mem_write_typed`](wasm::Store::mem_write_typed).
+    pub fn mem_write_typed<V: MemoryValue>(
+        &mut self,
+        mem_addr: Stored<MemAddr>,
+        i: u32,
+        value: V,
+    ) -> Result<(), RuntimeError> {
+        // 1. try unwrap
+        let mem_addr = mem_addr.try_unwrap_into_bare(self.id);
+        // 2. call
+        // The value is converted into its bytes first, so that they can be
+        // recorded afterwards.
+        let bytes = value.to_le_bytes();
+        // SAFETY: It was just checked that the `MemAddr` came from the current
+        // store through its store id.
+        unsafe { self.inner.mem_write_bytes(mem_addr, i, bytes.as_ref()) }?;
+        self.record_write(|| StoreWrite::Memory {
+            mem_addr,
+            i,
+            bytes: bytes.as_ref().to_vec(),
+        });
+        // 3. rewrap
+        // result is the unit type.
+        // 4. return
+        Ok(())
+    }
+
+    /// This is a safe variant of [`Store::mem_copy`](wasm::Store::mem_copy).
+    pub fn mem_copy(
+        &mut self,
+        dst_addr: Stored<MemAddr>,
+        dst_i: u32,
+        src_addr: Stored<MemAddr>,
+        src_i: u32,
+        n: u32,
+    ) -> Result<(), RuntimeError> {
+        // 1. try unwrap
+        let dst_addr = dst_addr.try_unwrap_into_bare(self.id);
+        let src_addr = src_addr.try_unwrap_into_bare(self.id);
+        // 2. call
+        // SAFETY: It was just checked that both `MemAddr`s came from the
+        // current store through its store id.
+        unsafe { self.inner.mem_copy(dst_addr, dst_i, src_addr, src_i, n) }?;
+        self.record_write(|| StoreWrite::MemoryCopy {
+            dst_addr,
+            dst_i,
+            src_addr,
+            src_i,
+            n,
+        });
         // 3. rewrap
         // result is the unit type.
         // 4. return
@@ -308,6 +486,19 @@ impl<'b, T: Config> Store<'b, T> {
         unsafe { self.inner.mem_size(mem_addr) }
     }
 
+    /// This is a safe variant of [`Store::mem_allocated_bytes`](wasm::Store::mem_allocated_bytes).
+    pub fn mem_allocated_bytes(&self, mem_addr: Stored<MemAddr>) -> usize {
+        // 1. try unwrap
+        let mem_addr = mem_addr.try_unwrap_into_bare(self.id);
+        // 2. call
+        // 3. rewrap
+        // the number of allocated bytes does not have a stored variant.
+        // 4. return
+        // SAFETY: It was just checked that the `MemAddr` came from the current
+        // store through its store id.
+        unsafe { self.inner.mem_allocated_bytes(mem_addr) }
+    }
+
     /// This is a safe variant of [`Store::mem_grow`](wasm::Store::mem_grow).
     pub fn mem_grow(&mut self, mem_addr: Stored<MemAddr>, n: u32) -> Result<(), RuntimeError> {
         // 1. try unwrap
@@ -316,12 +507,126 @@ impl<'b, T: Config> Store<'b, T> {
         // SAFETY: It was just checked that the `MemAddr` came from the current
         // store through its store id.
         unsafe { self.inner.mem_grow(mem_addr, n) }?;
+        self.record_write(|| StoreWrite::MemoryGrow { mem_addr, n });
         // 3. rewrap
         // result is the unit type.
         // 4. return
         Ok(())
     }
 
+    /// This is a safe variant of
+    /// [`Store::mem_map_mmio`](wasm::Store::mem_map_mmio).
+    pub fn mem_map_mmio(
+        &mut self,
+        mem_addr: Stored<MemAddr>,
+        region: Range<usize>,
+    ) -> Result<(), RuntimeError> {
+        // 1. try unwrap
+        let mem_addr = mem_addr.try_unwrap_into_bare(self.id);
+        // 2. call
+        // SAFETY: It was just checked that the `MemAddr` came from the current
+        // store through its store id.
+        unsafe { self.inner.mem_map_mmio(mem_addr, region) }?;
+        // 3. rewrap
+        // result is the unit type.
+        // 4. return
+        Ok(())
+    }
+
+    /// This is a safe variant of
+    /// [`Store::mem_protect`](wasm::Store::mem_protect).
+    pub fn mem_protect(
+        &mut self,
+        mem_addr: Stored<MemAddr>,
+        region: Range<usize>,
+        protection: MemoryProtection,
+    ) -> Result<(), RuntimeError> {
+        // 1. try unwrap
+        let mem_addr = mem_addr.try_unwrap_into_bare(self.id);
+        // 2. call
+        // SAFETY: It was just checked that the `MemAddr` came from the current
+        // store through its store id.
+        unsafe { self.inner.mem_protect(mem_addr, region, protection) }?;
+        // 3. rewrap
+        // result is the unit type.
+        // 4. return
+        Ok(())
+    }
+
+    /// This is a safe variant of
+    /// [`Store::mem_unprotect`](wasm::Store::mem_unprotect).
+    pub fn mem_unprotect(&mut self, mem_addr: Stored<MemAddr>, region: Range<usize>) {
+        // 1. try unwrap
+        let mem_addr = mem_addr.try_unwrap_into_bare(self.id);
+        // 2. call
+        // SAFETY: It was just checked that the `MemAddr` came from the current
+        // store through its store id.
+        unsafe { self.inner.mem_unprotect(mem_addr, region) };
+        // 3. rewrap
+        // no return value
+        // 4. return
+    }
+
+    /// This is a safe variant of
+    /// [`Store::resource_usage`](wasm::Store::resource_usage).
+    pub fn resource_usage(&self) -> ResourceUsage {
+        // 1. try unwrap
+        // no stored parameters
+        // 2. call
+        // 3. rewrap
+        // resource usage does not have a stored variant.
+        // 4. return
+        self.inner.resource_usage()
+    }
+
+    /// This is a safe variant of
+    /// [`Store::stack_limits`](wasm::Store::stack_limits).
+    pub fn stack_limits(&self) -> StackLimits {
+        // 1. try unwrap
+        // no stored parameters
+        // 2. call
+        // 3. rewrap
+        // stack limits do not have a stored variant.
+        // 4. return
+        self.inner.stack_limits()
+    }
+
+    /// This is a safe variant of
+    /// [`Store::set_stack_limits`](wasm::Store::set_stack_limits).
+    pub fn set_stack_limits(&mut self, stack_limits: StackLimits) {
+        // 1. try unwrap
+        // stack limits do not have a stored variant.
+        // 2. call
+        self.inner.set_stack_limits(stack_limits);
+        // 3. rewrap
+        // no return value
+        // 4. return
+    }
+
+    /// This is a safe variant of
+    /// [`Store::sparse_memories`](wasm::Store::sparse_memories).
+    pub fn sparse_memories(&self) -> bool {
+        // 1. try unwrap
+        // no stored parameters
+        // 2. call
+        // 3. rewrap
+        // booleans do not have a stored variant.
+        // 4. return
+        self.inner.sparse_memories()
+    }
+
+    /// This is a safe variant of
+    /// [`Store::set_sparse_memories`](wasm::Store::set_sparse_memories).
+    pub fn set_sparse_memories(&mut self, sparse: bool) {
+        // 1. try unwrap
+        // booleans do not have a stored variant.
+        // 2. call
+        self.inner.set_sparse_memories(sparse);
+        // 3. rewrap
+        // no return value
+        // 4. return
+    }
+
     /// This is a safe variant of
     /// [`Store::global_alloc`](wasm::Store::global_alloc).
     pub fn global_alloc(
@@ -387,12 +692,77 @@ impl<'b, T: Config> Store<'b, T> {
         // contained in the value came from the current store through their
         // store ids.
         unsafe { self.inner.global_write(global_addr, val) }?;
+        self.record_write(|| StoreWrite::Global { global_addr, val });
         // 3. rewrap
         // result is the unit type.
         // 4. return
         Ok(())
     }
 
+    /// This is a safe variant of [`Store::fuel_pool_alloc`](wasm::Store::fuel_pool_alloc).
+    pub fn fuel_pool_alloc(&mut self, fuel: u64) -> Result<Stored<FuelPoolAddr>, RuntimeError> {
+        // 1. try unwrap
+        // no stored parameters
+        // 2. call
+        let fuel_pool_addr = self.inner.fuel_pool_alloc(fuel)?;
+        // 3. rewrap
+        // SAFETY: The `FuelPoolAddr` just came from the current store.
+        let stored_fuel_pool_addr = unsafe { Stored::from_bare(fuel_pool_addr, self.id) };
+        // 4. return
+        Ok(stored_fuel_pool_addr)
+    }
+
+    /// This is a safe variant of [`Store::fuel_pool_remaining`](wasm::Store::fuel_pool_remaining).
+    pub fn fuel_pool_remaining(&self, fuel_pool_addr: Stored<FuelPoolAddr>) -> u64 {
+        // 1. try unwrap
+        let fuel_pool_addr = fuel_pool_addr.try_unwrap_into_bare(self.id);
+        // 2. call
+        // 3. rewrap
+        // the remaining fuel does not have a stored variant.
+        // 4. return
+        // SAFETY: It was just checked that the `FuelPoolAddr` came from the
+        // current store through its store id.
+        unsafe { self.inner.fuel_pool_remaining(fuel_pool_addr) }
+    }
+
+    /// This is a safe variant of [`Store::fuel_pool_refill`](wasm::Store::fuel_pool_refill).
+    pub fn fuel_pool_refill(&mut self, fuel_pool_addr: Stored<FuelPoolAddr>, fuel: u64) {
+        // 1. try unwrap
+        let fuel_pool_addr = fuel_pool_addr.try_unwrap_into_bare(self.id);
+        // 2. call
+        // SAFETY: It was just checked that the `FuelPoolAddr` came from the
+        // current store through its store id.
+        unsafe { self.inner.fuel_pool_refill(fuel_pool_addr, fuel) };
+        // SAFETY: The `FuelPoolAddr` was checked above.
+        let fuel = unsafe { self.inner.fuel_pool_remaining(fuel_pool_addr) };
+        self.record_write(|| StoreWrite::FuelPool {
+            fuel_pool_addr,
+            fuel,
+        });
+        // 3. rewrap
+        // result is the unit type.
+        // 4. return
+    }
+
+    /// This is a safe variant of [`Store::fuel_pool_set`](wasm::Store::fuel_pool_set).
+    pub fn fuel_pool_set(&mut self, fuel_pool_addr: Stored<FuelPoolAddr>, fuel: u64) {
+        // 1. try unwrap
+        let fuel_pool_addr = fuel_pool_addr.try_unwrap_into_bare(self.id);
+        // 2. call
+        // SAFETY: It was just checked that the `FuelPoolAddr` came from the
+        // current store through its store id.
+        unsafe { self.inner.fuel_pool_set(fuel_pool_addr, fuel) };
+        // SAFETY: The `FuelPoolAddr` was checked above.
+        let fuel = unsafe { self.inner.fuel_pool_remaining(fuel_pool_addr) };
+        self.record_write(|| StoreWrite::FuelPool {
+            fuel_pool_addr,
+            fuel,
+        });
+        // 3. rewrap
+        // result is the unit type.
+        // 4. return
+    }
+
     /// This is a safe variant of
     /// [`Store::create_resumable`](wasm::Store::create_resumable).
     pub fn create_resumable(
@@ -416,6 +786,64 @@ impl<'b, T: Config> Store<'b, T> {
         Ok(stored_resumable)
     }
 
+    /// This is a safe variant of
+    /// [`Store::create_resumable_with_stack_limits`](wasm::Store::create_resumable_with_stack_limits).
+    pub fn create_resumable_with_stack_limits(
+        &self,
+        func_addr: Stored<FuncAddr>,
+        params: Vec<StoredValue>,
+        maybe_fuel: Option<u64>,
+        stack_limits: StackLimits,
+    ) -> Result<StoredResumable, RuntimeError> {
+        // 1. try unwrap
+        let func_addr = func_addr.try_unwrap_into_bare(self.id);
+        let params = params.try_unwrap_into_bare(self.id);
+        // 2. call
+        // SAFETY: It was just checked that the `FuncAddr` any any addresses
+        // contained in the parameters came from the current store through their
+        // store ids.
+        let resumable = unsafe {
+            self.inner.create_resumable_with_stack_limits(
+                func_addr,
+                params,
+                maybe_fuel,
+                stack_limits,
+            )
+        }?;
+        // 3. rewrap
+        // SAFETY: The `Resumable` just came from the current store.
+        let stored_resumable = unsafe { StoredResumable::from_bare(resumable, self.id) };
+        // 4. return
+        Ok(stored_resumable)
+    }
+
+    /// This is a safe variant of
+    /// [`Store::create_resumable_with_fuel_pool`](wasm::Store::create_resumable_with_fuel_pool).
+    pub fn create_resumable_with_fuel_pool(
+        &self,
+        func_addr: Stored<FuncAddr>,
+        params: Vec<StoredValue>,
+        fuel_pool_addr: Stored<FuelPoolAddr>,
+    ) -> Result<StoredResumable, RuntimeError> {
+        // 1. try unwrap
+        let func_addr = func_addr.try_unwrap_into_bare(self.id);
+        let params = params.try_unwrap_into_bare(self.id);
+        let fuel_pool_addr = fuel_pool_addr.try_unwrap_into_bare(self.id);
+        // 2. call
+        // SAFETY: It was just checked that the `FuncAddr`, the `FuelPoolAddr`
+        // and any addresses contained in the parameters came from the current
+        // store through their store ids.
+        let resumable = unsafe {
+            self.inner
+                .create_resumable_with_fuel_pool(func_addr, params, fuel_pool_addr)
+        }?;
+        // 3. rewrap
+        // SAFETY: The `Resumable` just came from the current store.
+        let stored_resumable = unsafe { StoredResumable::from_bare(resumable, self.id) };
+        // 4. return
+        Ok(stored_resumable)
+    }
+
     /// This is a safe variant of [`Store::resume`](wasm::Store::resume).
     pub fn resume(&mut self, resumable: StoredResumable) -> Result<StoredRunState, RuntimeError> {
         // 1. try unwrap
@@ -472,6 +900,33 @@ impl<'b, T: Config> Store<'b, T> {
         Ok(stored_run_state)
     }
 
+    /// This is a safe variant of [`wasm::Store::finish_host_call_with_fuel`].
+    pub fn finish_host_call_with_fuel(
+        &mut self,
+        host_resumable: Stored<HostResumable>,
+        host_call_return_values: Vec<StoredValue>,
+        host_fuel: u64,
+    ) -> Result<StoredRunState, RuntimeError> {
+        // 1. try unwrap
+        let host_resumable = host_resumable.try_unwrap_into_bare(self.id);
+        let host_call_return_values = host_call_return_values.try_unwrap_into_bare(self.id);
+        // 2. call
+        // SAFETY: It was just checked that the `HostResumable` and all `Value`s
+        // came from the current store through their store ids.
+        let run_state = unsafe {
+            self.inner.finish_host_call_with_fuel(
+                host_resumable,
+                host_call_return_values,
+                host_fuel,
+            )
+        }?;
+        // 3. rewrap
+        // SAFETY: The `RunState`s just came from the current store.
+        let stored_run_state = unsafe { StoredRunState::from_bare(run_state, self.id) };
+        // 4. return
+        Ok(stored_run_state)
+    }
+
     /// This is a safe variant of
     /// [`Store::invoke_simple`](wasm::Store::invoke_simple)
     pub fn invoke_simple(
@@ -495,11 +950,14 @@ impl<'b, T: Config> Store<'b, T> {
 
     /// This is a safe variant of
     /// [`Store::mem_access_mut_slice`](crate::Store::mem_access_mut_slice).
+    ///
+    /// Writes through the slice are not recorded by a
+    /// [`TraceRecorder`](crate::trace::TraceRecorder).
     pub fn mem_access_mut_slice<R>(
         &self,
         memory: Stored<MemAddr>,
         accessor: impl FnOnce(&mut [u8]) -> R,
-    ) -> R {
+    ) -> Result<R, RuntimeError> {
         // 1. try unwrap
         let memory = memory.try_unwrap_into_bare(self.id);
         // 2. call
@@ -534,4 +992,29 @@ impl<'b, T: Config> Store<'b, T> {
             })
             .collect()
     }
+
+    /// This is a safe variant of
+    /// [`Store::instance_function_names`](wasm::Store::instance_function_names).
+    pub fn instance_function_names(
+        &self,
+        module_addr: Stored<ModuleAddr>,
+    ) -> Vec<(Stored<FuncAddr>, &'b str)> {
+        // 1. try unwrap
+        let module_addr = module_addr.try_unwrap_into_bare(self.id);
+        // 2. call
+        // SAFETY: We just checked that this module address is valid in the
+        // current store through its store id.
+        let function_names = unsafe { self.inner.instance_function_names(module_addr) };
+        // 3. rewrap
+        // 4. return
+        function_names
+            .into_iter()
+            .map(|(func_addr, name)| {
+                // SAFETY: The function addresses just came from the current
+                // store.
+                let stored_func_addr = unsafe { Stored::from_bare(func_addr, self.id) };
+                (stored_func_addr, name)
+            })
+            .collect()
+    }
 }
@@ -3,7 +3,7 @@ use core::{num::NonZeroU64, ops::Deref};
 use alloc::vec::Vec;
 use wasm::{
     addrs::{FuncAddr, GlobalAddr, MemAddr, ModuleAddr, TableAddr},
-    resumable::{HostCall, HostResumable, Resumable, RunState, WasmResumable},
+    resumable::{HostCall, HostResumable, InterruptReason, Resumable, RunState, WasmResumable},
     ExternVal, Hostcode, InstantiationOutcome,
 };
 
@@ -329,7 +329,11 @@ pub enum StoredRunState {
     },
     Resumable {
         resumable: Stored<WasmResumable>,
-        required_fuel: Option<NonZeroU64>,
+        required_fuel: NonZeroU64,
+    },
+    Interrupted {
+        resumable: Stored<WasmResumable>,
+        reason: InterruptReason,
     },
     HostCalled {
         host_call: StoredHostCall,
@@ -345,6 +349,8 @@ impl AbstractStored for StoredRunState {
             RunState::Finished {
                 values,
                 maybe_remaining_fuel,
+                // the checked API does not support preallocated stack storage
+                stack_storage: _,
             } => Self::Finished {
                 // SAFETY: Upheld by the caller
                 values: unsafe { Vec::from_bare(values, id) },
@@ -358,6 +364,11 @@ impl AbstractStored for StoredRunState {
                 resumable: unsafe { Stored::from_bare(resumable, id) },
                 required_fuel,
             },
+            RunState::Interrupted { resumable, reason } => Self::Interrupted {
+                // SAFETY: Upheld by the caller
+                resumable: unsafe { Stored::from_bare(resumable, id) },
+                reason,
+            },
             RunState::HostCalled {
                 host_call,
                 resumable,
@@ -378,6 +389,7 @@ impl AbstractStored for StoredRunState {
             } => RunState::Finished {
                 values: values.into_bare(),
                 maybe_remaining_fuel,
+                stack_storage: None,
             },
             StoredRunState::Resumable {
                 resumable,
@@ -386,6 +398,10 @@ impl AbstractStored for StoredRunState {
                 resumable: resumable.into_bare(),
                 required_fuel,
             },
+            StoredRunState::Interrupted { resumable, reason } => RunState::Interrupted {
+                resumable: resumable.into_bare(),
+                reason,
+            },
             StoredRunState::HostCalled {
                 host_call,
                 resumable,
@@ -404,6 +420,7 @@ impl AbstractStored for StoredRunState {
             } => RunState::Finished {
                 values: values.try_unwrap_into_bare(expected_store_id),
                 maybe_remaining_fuel,
+                stack_storage: None,
             },
             StoredRunState::Resumable {
                 resumable,
@@ -412,6 +429,10 @@ impl AbstractStored for StoredRunState {
                 resumable: resumable.try_unwrap_into_bare(expected_store_id),
                 required_fuel,
             },
+            StoredRunState::Interrupted { resumable, reason } => RunState::Interrupted {
+                resumable: resumable.try_unwrap_into_bare(expected_store_id),
+                reason,
+            },
             StoredRunState::HostCalled {
                 host_call,
                 resumable,
@@ -0,0 +1,1158 @@
+//! Recording and deterministic replay of executions
+//!
+//! A [`TraceRecorder`] is used in place of the [`Store`] methods that drive execution. It records the invocation
+//! itself together with a [`StateSnapshot`] of the store it starts in, the fuel every resumable is resumed with, all
+//! [`HostCall`](wasm::resumable::HostCall)s together with the values the host returned for them and the final outcome.
+//! While recording, all writes the host makes to memories, globals, tables and fuel pools through the methods of the
+//! [`Store`] are recorded as well. The resulting [`Trace`] can be encoded into bytes, e.g. to be stored on a device,
+//! and decoded again on another machine.
+//!
+//! [`replay`] then restores the recorded snapshot into a [`Store`] and drives the same execution in it without any
+//! live host: Host calls are answered with the recorded return values and the recorded writes are applied to the
+//! store. Because execution is deterministic, this reproduces the original execution exactly. Every observable step is
+//! compared against the trace, so that any divergence is detected at the first event that differs.
+//!
+//! Writes that are not made through the [`Store`] methods, e.g. through [`Store::mem_access_mut_slice`], cannot be
+//! recorded. To detect them, [`TraceRecorder::set_host_return_digests`] records a
+//! [`state_digest`](Store::state_digest) whenever the host returns from a host call, which is compared during replay.
+//! This is opt-in, because computing a digest reads all memories. Changes to the configuration of the store, such as
+//! memory protection, MMIO regions or stack limits, are not recorded either.
+
+use core::{fmt::Display, num::NonZeroU64};
+
+use alloc::{boxed::Box, vec::Vec};
+use wasm::{
+    addrs::{Addr, FuelPoolAddr, FuncAddr, GlobalAddr, MemAddr, TableAddr},
+    config::Config,
+    resumable::{InterruptReason, WasmResumable},
+    value::{ExternAddr, Ref, F32, F64},
+    Hostcode, RefType, RuntimeError, StateSnapshot, Value,
+};
+
+use crate::{
+    AbstractStored, Store, Stored, StoredRef, StoredResumable, StoredRunState, StoredValue,
+};
+
+/// A single recorded event of an execution
+#[derive(Clone, Debug, PartialEq)]
+pub enum TraceEvent {
+    /// A new invocation of the function at `func_addr` was created in a store with the given
+    /// [`state_snapshot`](Store::state_snapshot)
+    Invoke {
+        func_addr: FuncAddr,
+        params: Vec<Value>,
+        maybe_fuel: Option<u64>,
+        initial_state: Box<StateSnapshot>,
+    },
+    /// The host wrote to the store before execution was driven further
+    StoreWrites { writes: Vec<StoreWrite> },
+    /// Wasm execution was resumed with the given amount of fuel
+    Resume { maybe_fuel: Option<u64> },
+    /// Wasm execution was paused, because it ran out of fuel
+    Paused { required_fuel: NonZeroU64 },
+    /// Wasm execution was interrupted for the given reason
+    Interrupted { reason: InterruptReason },
+    /// Wasm code called a host function
+    HostCall {
+        hostcode: Hostcode,
+        params: Vec<Value>,
+    },
+    /// The host returned from the last host call with the given values, charging the given amount of fuel. If enabled
+    /// through [`TraceRecorder::set_host_return_digests`], the [`state_digest`](Store::state_digest) of the store at
+    /// this point is recorded as well.
+    HostReturn {
+        values: Vec<Value>,
+        fuel: u64,
+        state_digest: Option<u64>,
+    },
+    /// The invocation finished
+    Finished {
+        values: Vec<Value>,
+        maybe_remaining_fuel: Option<u64>,
+    },
+}
+
+impl TraceEvent {
+    /// Checks whether two events are identical, with floating point values being compared by their bit patterns
+    fn is_identical_to(&self, other: &TraceEvent) -> bool {
+        let mut lhs = Vec::new();
+        let mut rhs = Vec::new();
+        self.encode(&mut lhs);
+        other.encode(&mut rhs);
+        lhs == rhs
+    }
+
+    fn for_run_state(run_state: &StoredRunState) -> Self {
+        match run_state {
+            StoredRunState::Finished {
+                values,
+                maybe_remaining_fuel,
+            } => TraceEvent::Finished {
+                values: bare_values(values),
+                maybe_remaining_fuel: *maybe_remaining_fuel,
+            },
+            StoredRunState::Resumable { required_fuel, .. } => TraceEvent::Paused {
+                required_fuel: *required_fuel,
+            },
+            StoredRunState::Interrupted { reason, .. } => {
+                TraceEvent::Interrupted { reason: *reason }
+            }
+            StoredRunState::HostCalled { host_call, .. } => TraceEvent::HostCall {
+                hostcode: host_call.hostcode,
+                params: bare_values(&host_call.params),
+            },
+        }
+    }
+}
+
+fn bare_values(values: &[StoredValue]) -> Vec<Value> {
+    values.iter().map(|value| value.into_bare()).collect()
+}
+
+/// A single write the host made to a [`Store`] through one of its methods
+#[derive(Clone, Debug, PartialEq)]
+pub enum StoreWrite {
+    /// [`Store::mem_write`], [`Store::mem_write_bytes`] or [`Store::mem_write_typed`] wrote `bytes` at index `i`
+    Memory {
+        mem_addr: MemAddr,
+        i: u32,
+        bytes: Vec<u8>,
+    },
+    /// [`Store::mem_copy`] copied `n` bytes
+    MemoryCopy {
+        dst_addr: MemAddr,
+        dst_i: u32,
+        src_addr: MemAddr,
+        src_i: u32,
+        n: u32,
+    },
+    /// [`Store::mem_grow`] grew a memory by `n` pages
+    MemoryGrow { mem_addr: MemAddr, n: u32 },
+    /// [`Store::global_write`] set the value of a global
+    Global { global_addr: GlobalAddr, val: Value },
+    /// [`Store::table_write`] set the element at index `i` of a table
+    Table {
+        table_addr: TableAddr,
+        i: u32,
+        r#ref: Ref,
+    },
+    /// [`Store::fuel_pool_refill`] or [`Store::fuel_pool_set`] changed the remaining fuel of a fuel pool to `fuel`
+    FuelPool {
+        fuel_pool_addr: FuelPoolAddr,
+        fuel: u64,
+    },
+}
+
+/// A recorded execution, consisting of a sequence of [`TraceEvent`]s
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Trace {
+    pub events: Vec<TraceEvent>,
+}
+
+/// Records executions driven through its methods into a [`Trace`]
+///
+/// Each method is a drop-in replacement for the [`Store`] method of the same name.
+#[derive(Debug, Default)]
+pub struct TraceRecorder {
+    trace: Trace,
+    host_return_digests: bool,
+}
+
+impl TraceRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the trace recorded so far
+    pub fn trace(&self) -> &Trace {
+        &self.trace
+    }
+
+    /// Consumes this recorder and returns the recorded trace
+    pub fn into_trace(self) -> Trace {
+        self.trace
+    }
+
+    /// Returns whether a [`state_digest`](Store::state_digest) is recorded whenever the host returns from a host call
+    pub fn host_return_digests(&self) -> bool {
+        self.host_return_digests
+    }
+
+    /// Sets whether a [`state_digest`](Store::state_digest) is recorded whenever the host returns from a host call,
+    /// which is disabled by default
+    ///
+    /// The digests allow replaying to detect writes the host made without going through the [`Store`] methods, but
+    /// computing one reads all memories of the store.
+    pub fn set_host_return_digests(&mut self, enabled: bool) {
+        self.host_return_digests = enabled;
+    }
+
+    /// A recording variant of [`Store::create_resumable`]
+    ///
+    /// This records a [`StateSnapshot`] of the store, which requires copying all of its memories. From now on, writes
+    /// to the store are recorded until the invocation finishes or returns an error.
+    pub fn create_resumable<T: Config>(
+        &mut self,
+        store: &mut Store<T>,
+        func_addr: Stored<FuncAddr>,
+        params: Vec<StoredValue>,
+        maybe_fuel: Option<u64>,
+    ) -> Result<StoredResumable, RuntimeError> {
+        self.trace.events.push(TraceEvent::Invoke {
+            func_addr: *func_addr,
+            params: bare_values(&params),
+            maybe_fuel,
+            initial_state: Box::new(store.inner().state_snapshot()?),
+        });
+        store.journal.get_or_insert_with(Vec::new);
+        store.create_resumable(func_addr, params, maybe_fuel)
+    }
+
+    /// Moves the writes to the store that were recorded since execution was last driven into the trace
+    fn record_store_writes<T: Config>(&mut self, store: &mut Store<T>) {
+        if let Some(journal) = &mut store.journal {
+            if !journal.is_empty() {
+                let writes = core::mem::take(journal);
+                self.trace.events.push(TraceEvent::StoreWrites { writes });
+            }
+        }
+    }
+
+    /// Records the outcome of driving execution, and stops recording writes to the store once the invocation is over
+    fn record_run_state<T: Config>(
+        &mut self,
+        store: &mut Store<T>,
+        result: Result<StoredRunState, RuntimeError>,
+    ) -> Result<StoredRunState, RuntimeError> {
+        match &result {
+            Ok(run_state) => {
+                if let StoredRunState::Finished { .. } = run_state {
+                    store.journal = None;
+                }
+                self.trace.events.push(TraceEvent::for_run_state(run_state));
+            }
+            Err(_) => store.journal = None,
+        }
+        result
+    }
+
+    /// A recording variant of [`Store::resume`]
+    pub fn resume<T: Config>(
+        &mut self,
+        store: &mut Store<T>,
+        mut resumable: StoredResumable,
+    ) -> Result<StoredRunState, RuntimeError> {
+        self.record_store_writes(store);
+        if let StoredResumable::Wasm(wasm_resumable) = &mut resumable {
+            self.trace.events.push(TraceEvent::Resume {
+                maybe_fuel: *wasm_resumable.fuel_mut(),
+            });
+        }
+        let result = store.resume(resumable);
+        self.record_run_state(store, result)
+    }
+
+    /// A recording variant of [`Store::resume_wasm`]
+    pub fn resume_wasm<T: Config>(
+        &mut self,
+        store: &mut Store<T>,
+        mut resumable: Stored<WasmResumable>,
+    ) -> Result<StoredRunState, RuntimeError> {
+        self.record_store_writes(store);
+        self.trace.events.push(TraceEvent::Resume {
+            maybe_fuel: *resumable.fuel_mut(),
+        });
+        let result = store.resume_wasm(resumable);
+        self.record_run_state(store, result)
+    }
+
+    /// A recording variant of [`Store::finish_host_call`]
+    pub fn finish_host_call<T: Config>(
+        &mut self,
+        store: &mut Store<T>,
+        host_resumable: Stored<wasm::resumable::HostResumable>,
+        host_call_return_values: Vec<StoredValue>,
+    ) -> Result<StoredRunState, RuntimeError> {
+        self.finish_host_call_with_fuel(store, host_resumable, host_call_return_values, 0)
+    }
+
+    /// A recording variant of [`Store::finish_host_call_with_fuel`]
+    pub fn finish_host_call_with_fuel<T: Config>(
+        &mut self,
+        store: &mut Store<T>,
+        host_resumable: Stored<wasm::resumable::HostResumable>,
+        host_call_return_values: Vec<StoredValue>,
+        host_fuel: u64,
+    ) -> Result<StoredRunState, RuntimeError> {
+        self.record_store_writes(store);
+        self.trace.events.push(TraceEvent::HostReturn {
+            values: bare_values(&host_call_return_values),
+            fuel: host_fuel,
+            state_digest: self.host_return_digests.then(|| store.state_digest()),
+        });
+        let result =
+            store.finish_host_call_with_fuel(host_resumable, host_call_return_values, host_fuel);
+        self.record_run_state(store, result)
+    }
+}
+
+/// An error that occurred while replaying a [`Trace`]
+#[derive(Debug, PartialEq)]
+pub enum ReplayError {
+    /// Execution returned an error
+    RuntimeError(RuntimeError),
+    /// Execution diverged from the trace at the event with index `event_idx`
+    Divergence {
+        event_idx: usize,
+        expected: TraceEvent,
+        found: TraceEvent,
+    },
+    /// The trace ended before execution finished
+    UnexpectedEndOfTrace,
+    /// The trace contains a non-null function reference that would have to be passed into the store. This is not
+    /// supported, because its validity in the store used for replaying cannot be checked.
+    UnsupportedFuncRef,
+    /// The trace contains a write to a memory, global, table or fuel pool that does not exist in the store
+    UnknownAddress,
+    /// The recorded initial state contains a non-null function reference that the store does not already hold at the
+    /// same location. Restoring it is not supported for the same reason as for [`ReplayError::UnsupportedFuncRef`].
+    UnsupportedInitialFuncRef,
+}
+
+impl Display for ReplayError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ReplayError::RuntimeError(err) => write!(f, "runtime error during replay: {err}"),
+            ReplayError::Divergence {
+                event_idx,
+                expected,
+                found,
+            } => write!(
+                f,
+                "execution diverged at trace event {event_idx}: expected {expected:?}, found {found:?}"
+            ),
+            ReplayError::UnexpectedEndOfTrace => {
+                f.write_str("the trace ended before execution finished")
+            }
+            ReplayError::UnsupportedFuncRef => {
+                f.write_str("function references cannot be replayed into a store")
+            }
+            ReplayError::UnknownAddress => {
+                f.write_str("the trace writes to an address that does not exist in the store")
+            }
+            ReplayError::UnsupportedInitialFuncRef => f.write_str(
+                "the initial state of the trace contains function references the store does not hold",
+            ),
+        }
+    }
+}
+
+impl From<RuntimeError> for ReplayError {
+    fn from(value: RuntimeError) -> Self {
+        Self::RuntimeError(value)
+    }
+}
+
+/// Replays a recorded [`Trace`] in the given store, invoking `func_addr`, applying all recorded writes to the store
+/// and answering all host calls with the recorded return values. Returns the final values on success.
+///
+/// Before invoking `func_addr`, the recorded initial state is restored into the store through
+/// [`Store::restore_state_snapshot`](wasm::Store::restore_state_snapshot). For this, the store must have the same
+/// structure as the one the trace was recorded with, i.e. the same modules must have been instantiated in the same
+/// order, and its tables and globals must already hold every function reference of the initial state. `func_addr` must
+/// be the function that was invoked during recording.
+pub fn replay<T: Config>(
+    store: &mut Store<T>,
+    func_addr: Stored<FuncAddr>,
+    trace: &Trace,
+) -> Result<Vec<StoredValue>, ReplayError> {
+    let mut events = trace.events.iter().enumerate();
+
+    let (event_idx, event) = next_event(&mut events, store)?;
+    let TraceEvent::Invoke {
+        func_addr: recorded_func_addr,
+        params,
+        maybe_fuel,
+        initial_state,
+    } = event
+    else {
+        return Err(ReplayError::Divergence {
+            event_idx,
+            expected: event.clone(),
+            found: TraceEvent::Invoke {
+                func_addr: *func_addr,
+                params: Vec::new(),
+                maybe_fuel: None,
+                initial_state: Box::default(),
+            },
+        });
+    };
+    if *recorded_func_addr != *func_addr {
+        return Err(ReplayError::Divergence {
+            event_idx,
+            expected: event.clone(),
+            found: TraceEvent::Invoke {
+                func_addr: *func_addr,
+                params: params.clone(),
+                maybe_fuel: *maybe_fuel,
+                initial_state: initial_state.clone(),
+            },
+        });
+    }
+    restore_initial_state(store, initial_state)?;
+    let params = into_stored_values(params, store)?;
+
+    let mut run_state = match store.create_resumable(func_addr, params, *maybe_fuel)? {
+        StoredResumable::Wasm(resumable) => {
+            let event = next_event(&mut events, store)?;
+            resume_wasm(store, resumable, event)?
+        }
+        StoredResumable::Host {
+            host_call,
+            host_resumable,
+        } => StoredRunState::HostCalled {
+            host_call,
+            resumable: host_resumable,
+        },
+    };
+
+    loop {
+        expect(
+            next_event(&mut events, store)?,
+            TraceEvent::for_run_state(&run_state),
+        )?;
+
+        run_state = match run_state {
+            StoredRunState::Finished { values, .. } => return Ok(values),
+            StoredRunState::Resumable { resumable, .. }
+            | StoredRunState::Interrupted { resumable, .. } => {
+                let event = next_event(&mut events, store)?;
+                resume_wasm(store, resumable, event)?
+            }
+            StoredRunState::HostCalled { resumable, .. } => {
+                let (event_idx, event) = next_event(&mut events, store)?;
+                let TraceEvent::HostReturn {
+                    values,
+                    fuel,
+                    state_digest,
+                } = event
+                else {
+                    return Err(ReplayError::Divergence {
+                        event_idx,
+                        expected: event.clone(),
+                        found: TraceEvent::HostReturn {
+                            values: Vec::new(),
+                            fuel: 0,
+                            state_digest: None,
+                        },
+                    });
+                };
+                expect(
+                    (event_idx, event),
+                    TraceEvent::HostReturn {
+                        values: values.clone(),
+                        fuel: *fuel,
+                        state_digest: state_digest.map(|_| store.state_digest()),
+                    },
+                )?;
+                let values = into_stored_values(values, store)?;
+                store.finish_host_call_with_fuel(resumable, values, *fuel)?
+            }
+        };
+    }
+}
+
+/// Restores the recorded initial state into the store
+///
+/// Function references cannot be checked to come from the store, so a function reference of the snapshot is only
+/// accepted if the store already holds the same reference at the same location, e.g. because it was placed there by
+/// the element segments of the same module.
+fn restore_initial_state<T: Config>(
+    store: &mut Store<T>,
+    initial_state: &StateSnapshot,
+) -> Result<(), ReplayError> {
+    let inner = store.inner();
+    for (i, value) in initial_state.globals.iter().enumerate() {
+        if let Value::Ref(Ref::Func(_)) = value {
+            let global_addr = GlobalAddr::new(i);
+            // SAFETY: It is checked that the global address is valid in the
+            // store before it is used.
+            let held = inner.contains_global(global_addr)
+                && unsafe { inner.global_read(global_addr) } == *value;
+            if !held {
+                return Err(ReplayError::UnsupportedInitialFuncRef);
+            }
+        }
+    }
+    for (i, elem) in initial_state.tables.iter().enumerate() {
+        let table_addr = TableAddr::new(i);
+        for (j, r#ref) in elem.iter().enumerate() {
+            if let Ref::Func(_) = r#ref {
+                let held = inner.contains_table(table_addr)
+                    && u32::try_from(j).is_ok_and(|j| {
+                        // SAFETY: It is checked that the table address is
+                        // valid in the store before it is used.
+                        let held_ref = unsafe { inner.table_read(table_addr, j) };
+                        held_ref == Ok(*r#ref)
+                    });
+                if !held {
+                    return Err(ReplayError::UnsupportedInitialFuncRef);
+                }
+            }
+        }
+    }
+    // SAFETY: All function references of the snapshot were just checked to
+    // already be held by the store, so they come from it. Extern addresses
+    // are not tied to a store.
+    unsafe { store.inner.restore_state_snapshot(initial_state) }?;
+    Ok(())
+}
+
+/// Returns the next event of the trace that is not a [`TraceEvent::StoreWrites`], applying the writes of all such
+/// events to the store
+fn next_event<'t, T: Config>(
+    events: &mut impl Iterator<Item = (usize, &'t TraceEvent)>,
+    store: &mut Store<T>,
+) -> Result<(usize, &'t TraceEvent), ReplayError> {
+    loop {
+        match events.next().ok_or(ReplayError::UnexpectedEndOfTrace)? {
+            (_, TraceEvent::StoreWrites { writes }) => {
+                for write in writes {
+                    apply_store_write(store, write)?;
+                }
+            }
+            event => return Ok(event),
+        }
+    }
+}
+
+/// Compares an event that was observed during replay with the next one in the trace
+fn expect(
+    (event_idx, expected): (usize, &TraceEvent),
+    found: TraceEvent,
+) -> Result<(), ReplayError> {
+    if expected.is_identical_to(&found) {
+        Ok(())
+    } else {
+        Err(ReplayError::Divergence {
+            event_idx,
+            expected: expected.clone(),
+            found,
+        })
+    }
+}
+
+/// Applies a recorded write to the store
+fn apply_store_write<T: Config>(
+    store: &mut Store<T>,
+    write: &StoreWrite,
+) -> Result<(), ReplayError> {
+    let inner = store.inner();
+    let known = match write {
+        StoreWrite::Memory { mem_addr, .. } | StoreWrite::MemoryGrow { mem_addr, .. } => {
+            inner.contains_mem(*mem_addr)
+        }
+        StoreWrite::MemoryCopy {
+            dst_addr, src_addr, ..
+        } => inner.contains_mem(*dst_addr) && inner.contains_mem(*src_addr),
+        StoreWrite::Global { global_addr, .. } => inner.contains_global(*global_addr),
+        StoreWrite::Table { table_addr, .. } => inner.contains_table(*table_addr),
+        StoreWrite::FuelPool { fuel_pool_addr, .. } => inner.contains_fuel_pool(*fuel_pool_addr),
+    };
+    if !known {
+        return Err(ReplayError::UnknownAddress);
+    }
+
+    let id = store.id();
+    // SAFETY: It was just checked that all memory addresses of the write are
+    // valid in the store.
+    let stored = |mem_addr: MemAddr| unsafe { Stored::from_bare(mem_addr, id) };
+    match write {
+        StoreWrite::Memory { mem_addr, i, bytes } => {
+            store.mem_write_bytes(stored(*mem_addr), *i, bytes)?
+        }
+        StoreWrite::MemoryCopy {
+            dst_addr,
+            dst_i,
+            src_addr,
+            src_i,
+            n,
+        } => store.mem_copy(stored(*dst_addr), *dst_i, stored(*src_addr), *src_i, *n)?,
+        StoreWrite::MemoryGrow { mem_addr, n } => store.mem_grow(stored(*mem_addr), *n)?,
+        StoreWrite::Global { global_addr, val } => {
+            let [val] = into_stored_values(&[*val], store)?
+                .try_into()
+                .expect("exactly one value to be converted");
+            // SAFETY: It was just checked that the address is valid in the
+            // store.
+            let global_addr = unsafe { Stored::from_bare(*global_addr, id) };
+            store.global_write(global_addr, val)?
+        }
+        StoreWrite::Table {
+            table_addr,
+            i,
+            r#ref,
+        } => {
+            if let Ref::Func(_) = r#ref {
+                return Err(ReplayError::UnsupportedFuncRef);
+            }
+            // SAFETY: The reference does not contain any function address,
+            // which is the only address type that can be contained in
+            // references.
+            let r#ref = unsafe { StoredRef::from_bare(*r#ref, id) };
+            // SAFETY: It was just checked that the address is valid in the
+            // store.
+            let table_addr = unsafe { Stored::from_bare(*table_addr, id) };
+            store.table_write(table_addr, *i, r#ref)?
+        }
+        StoreWrite::FuelPool {
+            fuel_pool_addr,
+            fuel,
+        } => {
+            // SAFETY: It was just checked that the address is valid in the
+            // store.
+            let fuel_pool_addr = unsafe { Stored::from_bare(*fuel_pool_addr, id) };
+            store.fuel_pool_set(fuel_pool_addr, *fuel)
+        }
+    }
+    Ok(())
+}
+
+/// Resumes a [`WasmResumable`] with the fuel given by the `Resume` event `event`
+fn resume_wasm<T: Config>(
+    store: &mut Store<T>,
+    mut resumable: Stored<WasmResumable>,
+    (event_idx, event): (usize, &TraceEvent),
+) -> Result<StoredRunState, ReplayError> {
+    let TraceEvent::Resume { maybe_fuel } = event else {
+        return Err(ReplayError::Divergence {
+            event_idx,
+            expected: event.clone(),
+            found: TraceEvent::Resume {
+                maybe_fuel: *resumable.fuel_mut(),
+            },
+        });
+    };
+    *resumable.fuel_mut() = *maybe_fuel;
+    Ok(store.resume_wasm(resumable)?)
+}
+
+/// Converts recorded values into stored values for the given store
+fn into_stored_values<T: Config>(
+    values: &[Value],
+    store: &Store<T>,
+) -> Result<Vec<StoredValue>, ReplayError> {
+    values
+        .iter()
+        .map(|value| {
+            if let Value::Ref(Ref::Func(_)) = value {
+                return Err(ReplayError::UnsupportedFuncRef);
+            }
+            // SAFETY: The value does not contain any function address, which
+            // is the only address type that can be contained in values.
+            Ok(unsafe { StoredValue::from_bare(*value, store.id()) })
+        })
+        .collect()
+}
+
+/// The magic bytes at the start of every encoded trace
+const TRACE_MAGIC: [u8; 4] = *b"WTRC";
+/// The version of the trace encoding
+const TRACE_VERSION: u8 = 2;
+
+/// An error that occurred while decoding a [`Trace`]
+#[derive(Debug, PartialEq, Eq)]
+pub enum TraceDecodeError {
+    InvalidMagic,
+    UnsupportedVersion(u8),
+    UnexpectedEof,
+    InvalidTag(u8),
+    ZeroRequiredFuel,
+}
+
+impl Display for TraceDecodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TraceDecodeError::InvalidMagic => f.write_str("the trace has invalid magic bytes"),
+            TraceDecodeError::UnsupportedVersion(version) => {
+                write!(f, "the trace version {version} is not supported")
+            }
+            TraceDecodeError::UnexpectedEof => f.write_str("the trace ended unexpectedly"),
+            TraceDecodeError::InvalidTag(tag) => {
+                write!(f, "the trace contains an invalid tag {tag}")
+            }
+            TraceDecodeError::ZeroRequiredFuel => {
+                f.write_str("the trace contains a pause that requires zero fuel")
+            }
+        }
+    }
+}
+
+impl Trace {
+    /// Encodes this trace into a platform independent byte representation
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&TRACE_MAGIC);
+        bytes.push(TRACE_VERSION);
+        for event in &self.events {
+            event.encode(&mut bytes);
+        }
+        bytes
+    }
+
+    /// Decodes a trace from bytes previously produced by [`Trace::encode`]
+    pub fn decode(bytes: &[u8]) -> Result<Self, TraceDecodeError> {
+        let mut decoder = Decoder { bytes };
+        if decoder.take::<4>()? != TRACE_MAGIC {
+            return Err(TraceDecodeError::InvalidMagic);
+        }
+        let [version] = decoder.take::<1>()?;
+        if version != TRACE_VERSION {
+            return Err(TraceDecodeError::UnsupportedVersion(version));
+        }
+
+        let mut events = Vec::new();
+        while !decoder.bytes.is_empty() {
+            events.push(decoder.event()?);
+        }
+        Ok(Self { events })
+    }
+}
+
+// encoding tags of trace events
+const EVENT_INVOKE: u8 = 0;
+const EVENT_RESUME: u8 = 1;
+const EVENT_PAUSED: u8 = 2;
+const EVENT_HOST_CALL: u8 = 3;
+const EVENT_HOST_RETURN: u8 = 4;
+const EVENT_FINISHED: u8 = 5;
+const EVENT_INTERRUPTED: u8 = 6;
+const EVENT_STORE_WRITES: u8 = 7;
+
+// encoding tags of interrupt reasons
+const REASON_INTERRUPTION_HOOK: u8 = 0;
+const REASON_ACCESS_HOOK: u8 = 1;
+const REASON_HOST_CALL_FINISHED: u8 = 2;
+
+// encoding tags of values
+// store write tags
+const WRITE_MEMORY: u8 = 0;
+const WRITE_MEMORY_COPY: u8 = 1;
+const WRITE_MEMORY_GROW: u8 = 2;
+const WRITE_GLOBAL: u8 = 3;
+const WRITE_TABLE: u8 = 4;
+const WRITE_FUEL_POOL: u8 = 5;
+
+const VALUE_I32: u8 = 0;
+const VALUE_I64: u8 = 1;
+const VALUE_F32: u8 = 2;
+const VALUE_F64: u8 = 3;
+const VALUE_V128: u8 = 4;
+const VALUE_NULL_FUNCREF: u8 = 5;
+const VALUE_NULL_EXTERNREF: u8 = 6;
+const VALUE_FUNCREF: u8 = 7;
+const VALUE_EXTERNREF: u8 = 8;
+
+impl TraceEvent {
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        match self {
+            TraceEvent::Invoke {
+                func_addr,
+                params,
+                maybe_fuel,
+                initial_state,
+            } => {
+                bytes.push(EVENT_INVOKE);
+                encode_u64(bytes, func_addr.into_inner() as u64);
+                encode_values(bytes, params);
+                encode_maybe_u64(bytes, *maybe_fuel);
+                encode_snapshot(bytes, initial_state);
+            }
+            TraceEvent::StoreWrites { writes } => {
+                bytes.push(EVENT_STORE_WRITES);
+                encode_u64(bytes, writes.len() as u64);
+                for write in writes {
+                    write.encode(bytes);
+                }
+            }
+            TraceEvent::Resume { maybe_fuel } => {
+                bytes.push(EVENT_RESUME);
+                encode_maybe_u64(bytes, *maybe_fuel);
+            }
+            TraceEvent::Paused { required_fuel } => {
+                bytes.push(EVENT_PAUSED);
+                encode_u64(bytes, required_fuel.get());
+            }
+            TraceEvent::Interrupted { reason } => {
+                bytes.push(EVENT_INTERRUPTED);
+                bytes.push(match reason {
+                    InterruptReason::InterruptionHook => REASON_INTERRUPTION_HOOK,
+                    InterruptReason::AccessHook => REASON_ACCESS_HOOK,
+                    InterruptReason::HostCallFinished => REASON_HOST_CALL_FINISHED,
+                });
+            }
+            TraceEvent::HostCall { hostcode, params } => {
+                bytes.push(EVENT_HOST_CALL);
+                encode_u64(bytes, *hostcode as u64);
+                encode_values(bytes, params);
+            }
+            TraceEvent::HostReturn {
+                values,
+                fuel,
+                state_digest,
+            } => {
+                bytes.push(EVENT_HOST_RETURN);
+                encode_values(bytes, values);
+                encode_u64(bytes, *fuel);
+                encode_maybe_u64(bytes, *state_digest);
+            }
+            TraceEvent::Finished {
+                values,
+                maybe_remaining_fuel,
+            } => {
+                bytes.push(EVENT_FINISHED);
+                encode_values(bytes, values);
+                encode_maybe_u64(bytes, *maybe_remaining_fuel);
+            }
+        }
+    }
+}
+
+impl StoreWrite {
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        match self {
+            StoreWrite::Memory {
+                mem_addr,
+                i,
+                bytes: written,
+            } => {
+                bytes.push(WRITE_MEMORY);
+                encode_u64(bytes, mem_addr.into_inner() as u64);
+                bytes.extend_from_slice(&i.to_le_bytes());
+                encode_u64(bytes, written.len() as u64);
+                bytes.extend_from_slice(written);
+            }
+            StoreWrite::MemoryCopy {
+                dst_addr,
+                dst_i,
+                src_addr,
+                src_i,
+                n,
+            } => {
+                bytes.push(WRITE_MEMORY_COPY);
+                encode_u64(bytes, dst_addr.into_inner() as u64);
+                bytes.extend_from_slice(&dst_i.to_le_bytes());
+                encode_u64(bytes, src_addr.into_inner() as u64);
+                bytes.extend_from_slice(&src_i.to_le_bytes());
+                bytes.extend_from_slice(&n.to_le_bytes());
+            }
+            StoreWrite::MemoryGrow { mem_addr, n } => {
+                bytes.push(WRITE_MEMORY_GROW);
+                encode_u64(bytes, mem_addr.into_inner() as u64);
+                bytes.extend_from_slice(&n.to_le_bytes());
+            }
+            StoreWrite::Global { global_addr, val } => {
+                bytes.push(WRITE_GLOBAL);
+                encode_u64(bytes, global_addr.into_inner() as u64);
+                encode_value(bytes, val);
+            }
+            StoreWrite::Table {
+                table_addr,
+                i,
+                r#ref,
+            } => {
+                bytes.push(WRITE_TABLE);
+                encode_u64(bytes, table_addr.into_inner() as u64);
+                bytes.extend_from_slice(&i.to_le_bytes());
+                encode_value(bytes, &Value::Ref(*r#ref));
+            }
+            StoreWrite::FuelPool {
+                fuel_pool_addr,
+                fuel,
+            } => {
+                bytes.push(WRITE_FUEL_POOL);
+                encode_u64(bytes, fuel_pool_addr.into_inner() as u64);
+                encode_u64(bytes, *fuel);
+            }
+        }
+    }
+}
+
+fn encode_u64(bytes: &mut Vec<u8>, value: u64) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+fn encode_maybe_u64(bytes: &mut Vec<u8>, value: Option<u64>) {
+    match value {
+        Some(value) => {
+            bytes.push(1);
+            encode_u64(bytes, value);
+        }
+        None => bytes.push(0),
+    }
+}
+
+fn encode_values(bytes: &mut Vec<u8>, values: &[Value]) {
+    encode_u64(bytes, values.len() as u64);
+    for value in values {
+        encode_value(bytes, value);
+    }
+}
+
+fn encode_snapshot(bytes: &mut Vec<u8>, snapshot: &StateSnapshot) {
+    encode_u64(bytes, snapshot.memories.len() as u64);
+    for memory in &snapshot.memories {
+        encode_u64(bytes, memory.len() as u64);
+        bytes.extend_from_slice(memory);
+    }
+    encode_values(bytes, &snapshot.globals);
+    encode_u64(bytes, snapshot.tables.len() as u64);
+    for elem in &snapshot.tables {
+        encode_u64(bytes, elem.len() as u64);
+        for r#ref in elem {
+            encode_value(bytes, &Value::Ref(*r#ref));
+        }
+    }
+    encode_u64(bytes, snapshot.fuel_pools.len() as u64);
+    for fuel in &snapshot.fuel_pools {
+        encode_u64(bytes, *fuel);
+    }
+}
+
+fn encode_value(bytes: &mut Vec<u8>, value: &Value) {
+    match value {
+        Value::I32(x) => {
+            bytes.push(VALUE_I32);
+            bytes.extend_from_slice(&x.to_le_bytes());
+        }
+        Value::I64(x) => {
+            bytes.push(VALUE_I64);
+            bytes.extend_from_slice(&x.to_le_bytes());
+        }
+        Value::F32(x) => {
+            bytes.push(VALUE_F32);
+            bytes.extend_from_slice(&x.to_bits().to_le_bytes());
+        }
+        Value::F64(x) => {
+            bytes.push(VALUE_F64);
+            bytes.extend_from_slice(&x.to_bits().to_le_bytes());
+        }
+        Value::V128(x) => {
+            bytes.push(VALUE_V128);
+            bytes.extend_from_slice(x);
+        }
+        Value::Ref(Ref::Null(RefType::FuncRef)) => bytes.push(VALUE_NULL_FUNCREF),
+        Value::Ref(Ref::Null(RefType::ExternRef)) => bytes.push(VALUE_NULL_EXTERNREF),
+        Value::Ref(Ref::Func(func_addr)) => {
+            bytes.push(VALUE_FUNCREF);
+            encode_u64(bytes, func_addr.into_inner() as u64);
+        }
+        Value::Ref(Ref::Extern(extern_addr)) => {
+            bytes.push(VALUE_EXTERNREF);
+            encode_u64(bytes, extern_addr.0 as u64);
+        }
+    }
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl Decoder<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], TraceDecodeError> {
+        let (taken, rest) = self
+            .bytes
+            .split_first_chunk::<N>()
+            .ok_or(TraceDecodeError::UnexpectedEof)?;
+        self.bytes = rest;
+        Ok(*taken)
+    }
+
+    fn u8(&mut self) -> Result<u8, TraceDecodeError> {
+        let [byte] = self.take::<1>()?;
+        Ok(byte)
+    }
+
+    fn u64(&mut self) -> Result<u64, TraceDecodeError> {
+        self.take::<8>().map(u64::from_le_bytes)
+    }
+
+    fn usize(&mut self) -> Result<usize, TraceDecodeError> {
+        // values that do not fit into usize cannot have been produced on this platform
+        usize::try_from(self.u64()?).map_err(|_| TraceDecodeError::UnexpectedEof)
+    }
+
+    fn maybe_u64(&mut self) -> Result<Option<u64>, TraceDecodeError> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => self.u64().map(Some),
+            tag => Err(TraceDecodeError::InvalidTag(tag)),
+        }
+    }
+
+    fn values(&mut self) -> Result<Vec<Value>, TraceDecodeError> {
+        let len = self.usize()?;
+        // do not trust the length for preallocation, as it could be arbitrarily large
+        let mut values = Vec::new();
+        for _ in 0..len {
+            values.push(self.value()?);
+        }
+        Ok(values)
+    }
+
+    fn value(&mut self) -> Result<Value, TraceDecodeError> {
+        let value = match self.u8()? {
+            VALUE_I32 => Value::I32(u32::from_le_bytes(self.take()?)),
+            VALUE_I64 => Value::I64(u64::from_le_bytes(self.take()?)),
+            VALUE_F32 => Value::F32(F32(f32::from_bits(u32::from_le_bytes(self.take()?)))),
+            VALUE_F64 => Value::F64(F64(f64::from_bits(u64::from_le_bytes(self.take()?)))),
+            VALUE_V128 => Value::V128(self.take()?),
+            tag => Value::Ref(self.reference_with_tag(tag)?),
+        };
+        Ok(value)
+    }
+
+    fn reference_with_tag(&mut self, tag: u8) -> Result<Ref, TraceDecodeError> {
+        let reference = match tag {
+            VALUE_NULL_FUNCREF => Ref::Null(RefType::FuncRef),
+            VALUE_NULL_EXTERNREF => Ref::Null(RefType::ExternRef),
+            VALUE_FUNCREF => Ref::Func(FuncAddr::new(self.usize()?)),
+            VALUE_EXTERNREF => Ref::Extern(ExternAddr(self.usize()?)),
+            tag => return Err(TraceDecodeError::InvalidTag(tag)),
+        };
+        Ok(reference)
+    }
+
+    fn u32(&mut self) -> Result<u32, TraceDecodeError> {
+        self.take::<4>().map(u32::from_le_bytes)
+    }
+
+    fn byte_vec(&mut self) -> Result<Vec<u8>, TraceDecodeError> {
+        let len = self.usize()?;
+        let (bytes, rest) = self
+            .bytes
+            .split_at_checked(len)
+            .ok_or(TraceDecodeError::UnexpectedEof)?;
+        self.bytes = rest;
+        Ok(bytes.to_vec())
+    }
+
+    fn snapshot(&mut self) -> Result<StateSnapshot, TraceDecodeError> {
+        // do not trust the lengths for preallocation, as they could be arbitrarily large
+        let mut memories = Vec::new();
+        for _ in 0..self.usize()? {
+            memories.push(self.byte_vec()?);
+        }
+        let globals = self.values()?;
+        let mut tables = Vec::new();
+        for _ in 0..self.usize()? {
+            let mut elem = Vec::new();
+            for _ in 0..self.usize()? {
+                let tag = self.u8()?;
+                elem.push(self.reference_with_tag(tag)?);
+            }
+            tables.push(elem);
+        }
+        let mut fuel_pools = Vec::new();
+        for _ in 0..self.usize()? {
+            fuel_pools.push(self.u64()?);
+        }
+        Ok(StateSnapshot {
+            memories,
+            globals,
+            tables,
+            fuel_pools,
+        })
+    }
+
+    fn store_write(&mut self) -> Result<StoreWrite, TraceDecodeError> {
+        let write = match self.u8()? {
+            WRITE_MEMORY => StoreWrite::Memory {
+                mem_addr: MemAddr::new(self.usize()?),
+                i: self.u32()?,
+                bytes: self.byte_vec()?,
+            },
+            WRITE_MEMORY_COPY => StoreWrite::MemoryCopy {
+                dst_addr: MemAddr::new(self.usize()?),
+                dst_i: self.u32()?,
+                src_addr: MemAddr::new(self.usize()?),
+                src_i: self.u32()?,
+                n: self.u32()?,
+            },
+            WRITE_MEMORY_GROW => StoreWrite::MemoryGrow {
+                mem_addr: MemAddr::new(self.usize()?),
+                n: self.u32()?,
+            },
+            WRITE_GLOBAL => StoreWrite::Global {
+                global_addr: GlobalAddr::new(self.usize()?),
+                val: self.value()?,
+            },
+            WRITE_TABLE => StoreWrite::Table {
+                table_addr: TableAddr::new(self.usize()?),
+                i: self.u32()?,
+                r#ref: {
+                    let tag = self.u8()?;
+                    self.reference_with_tag(tag)?
+                },
+            },
+            WRITE_FUEL_POOL => StoreWrite::FuelPool {
+                fuel_pool_addr: FuelPoolAddr::new(self.usize()?),
+                fuel: self.u64()?,
+            },
+            tag => return Err(TraceDecodeError::InvalidTag(tag)),
+        };
+        Ok(write)
+    }
+
+    fn event(&mut self) -> Result<TraceEvent, TraceDecodeError> {
+        let event = match self.u8()? {
+            EVENT_INVOKE => TraceEvent::Invoke {
+                func_addr: FuncAddr::new(self.usize()?),
+                params: self.values()?,
+                maybe_fuel: self.maybe_u64()?,
+                initial_state: Box::new(self.snapshot()?),
+            },
+            EVENT_STORE_WRITES => TraceEvent::StoreWrites {
+                writes: {
+                    let len = self.usize()?;
+                    // do not trust the length for preallocation, as it could be arbitrarily large
+                    let mut writes = Vec::new();
+                    for _ in 0..len {
+                        writes.push(self.store_write()?);
+                    }
+                    writes
+                },
+            },
+            EVENT_RESUME => TraceEvent::Resume {
+                maybe_fuel: self.maybe_u64()?,
+            },
+            EVENT_PAUSED => TraceEvent::Paused {
+                required_fuel: NonZeroU64::new(self.u64()?)
+                    .ok_or(TraceDecodeError::ZeroRequiredFuel)?,
+            },
+            EVENT_INTERRUPTED => TraceEvent::Interrupted {
+                reason: match self.u8()? {
+                    REASON_INTERRUPTION_HOOK => InterruptReason::InterruptionHook,
+                    REASON_ACCESS_HOOK => InterruptReason::AccessHook,
+                    REASON_HOST_CALL_FINISHED => InterruptReason::HostCallFinished,
+                    tag => return Err(TraceDecodeError::InvalidTag(tag)),
+                },
+            },
+            EVENT_HOST_CALL => TraceEvent::HostCall {
+                hostcode: self.usize()?,
+                params: self.values()?,
+            },
+            EVENT_HOST_RETURN => TraceEvent::HostReturn {
+                values: self.values()?,
+                fuel: self.u64()?,
+                state_digest: self.maybe_u64()?,
+            },
+            EVENT_FINISHED => TraceEvent::Finished {
+                values: self.values()?,
+                maybe_remaining_fuel: self.maybe_u64()?,
+            },
+            tag => return Err(TraceDecodeError::InvalidTag(tag)),
+        };
+        Ok(event)
+    }
+}
@@ -533,7 +533,7 @@ pub trait StoreTypedInvocationExt<T: Config> {
     fn func_alloc_typed<Params: InteropValueList, Returns: InteropValueList>(
         &mut self,
         hostcode: Hostcode,
-    ) -> FuncAddr;
+    ) -> Result<FuncAddr, RuntimeError>;
 
     /// Invokes a function without support for fuel or host functions but with a
     /// statically known type signature.
@@ -554,7 +554,7 @@ impl<T: Config> StoreTypedInvocationExt<T> for Store<'_, T> {
     fn func_alloc_typed<Params: InteropValueList, Returns: InteropValueList>(
         &mut self,
         hostcode: Hostcode,
-    ) -> FuncAddr {
+    ) -> Result<FuncAddr, RuntimeError> {
         let func_type = FuncType {
             params: ResultType {
                 valtypes: Vec::from(Params::TYS),
@@ -21,7 +21,7 @@ use wasm::{
     FuncType, ResultType, RuntimeError,
 };
 
-type BoxedHostFn<T> = Box<dyn FnMut(&mut T, Vec<StoredValue>) -> Vec<StoredValue>>;
+type BoxedHostFn<T> = Box<dyn FnMut(&mut T, Vec<StoredValue>) -> (Vec<StoredValue>, u64)>;
 
 /// A simple registry for host functions.
 ///
@@ -30,6 +30,7 @@ type BoxedHostFn<T> = Box<dyn FnMut(&mut T, Vec<StoredValue>) -> Vec<StoredValue
 /// - based on the [`checked API`](checked) and its `interop` feature
 /// - host functions may access generic user data `T`
 /// - boxed and dynamically dispatched host functions
+/// - host functions may charge fuel for their work
 pub struct Registry<T> {
     host_functions: Vec<BoxedHostFn<T>>,
     next_hostcode: usize,
@@ -49,21 +50,34 @@ impl<T> Registry<T> {
         &mut self,
         store: &mut Store<C>,
         func_type: FuncType,
-        host_function: impl FnMut(&mut T, Vec<StoredValue>) -> Vec<StoredValue> + 'static,
-    ) -> Stored<FuncAddr> {
+        mut host_function: impl FnMut(&mut T, Vec<StoredValue>) -> Vec<StoredValue> + 'static,
+    ) -> Result<Stored<FuncAddr>, RuntimeError> {
+        self.alloc_host_function_with_fuel(store, func_type, move |user_data, params| {
+            (host_function(user_data, params), 0)
+        })
+    }
+
+    /// Allocates a host function that returns the fuel to charge for each call alongside its return values
+    pub fn alloc_host_function_with_fuel<C: Config>(
+        &mut self,
+        store: &mut Store<C>,
+        func_type: FuncType,
+        host_function: impl FnMut(&mut T, Vec<StoredValue>) -> (Vec<StoredValue>, u64) + 'static,
+    ) -> Result<Stored<FuncAddr>, RuntimeError> {
         let hostcode = self.next_hostcode;
-        self.next_hostcode += 1;
+        let func_addr = store.func_alloc(func_type, hostcode)?;
 
+        self.next_hostcode += 1;
         self.host_functions.push(Box::new(host_function));
 
-        store.func_alloc(func_type, hostcode)
+        Ok(func_addr)
     }
 
     pub fn alloc_host_function_typed<C, Params, Returns, F>(
         &mut self,
         store: &mut Store<C>,
         mut host_function: F,
-    ) -> Stored<FuncAddr>
+    ) -> Result<Stored<FuncAddr>, RuntimeError>
     where
         C: Config,
         Params: StoredInteropValueList,
@@ -98,11 +112,8 @@ impl<T> Registry<T> {
         loop {
             match run_state {
                 StoredRunState::Finished { values, .. } => return Ok(values),
-                StoredRunState::Resumable {
-                    resumable,
-                    required_fuel,
-                } => {
-                    assert!(required_fuel.is_none(), "fuel is disabled");
+                StoredRunState::Resumable { .. } => unreachable!("fuel is disabled"),
+                StoredRunState::Interrupted { resumable, .. } => {
                     run_state = store.resume_wasm(resumable)?;
                 }
                 StoredRunState::HostCalled {
@@ -141,7 +152,7 @@ impl<T> Registry<T> {
         host_resumable: Stored<HostResumable>,
     ) -> Result<StoredRunState, RuntimeError> {
         let host_function = &mut self.host_functions[host_call.hostcode];
-        let returns = host_function(user_data, host_call.params);
-        store.finish_host_call(host_resumable, returns)
+        let (returns, fuel) = host_function(user_data, host_call.params);
+        store.finish_host_call_with_fuel(host_resumable, returns, fuel)
     }
 }
@@ -0,0 +1,284 @@
+//! A cooperative scheduler for multiple guest tasks
+//!
+//! A task is a single invocation of a Wasm or host function in a [`Store`]. The [`Scheduler`] executes one task at a
+//! time for at most its fuel quantum. When the quantum is used up, the task is preempted and the scheduling policy
+//! selects the next task to run. Because the interpreter is stackless, preempting a task only requires keeping its
+//! resumable around, so any number of tasks can share one core and one store.
+//!
+//! Host calls made by a task are routed to the handler the task was spawned with. Execution of the task continues in
+//! the same time slice once the handler returns.
+
+#![no_std]
+#![deny(
+    clippy::missing_safety_doc,
+    clippy::undocumented_unsafe_blocks,
+    unsafe_op_in_unsafe_fn
+)]
+
+extern crate alloc;
+
+use alloc::{boxed::Box, collections::vec_deque::VecDeque, vec::Vec};
+
+use checked::{Store, Stored, StoredHostCall, StoredResumable, StoredRunState, StoredValue};
+use wasm::{
+    addrs::FuncAddr,
+    config::Config,
+    resumable::{HostResumable, InterruptReason, WasmResumable},
+    RuntimeError,
+};
+
+/// A handler for the host calls of a task. It returns the return values of the host call together with the fuel to
+/// charge for it, or an error which terminates the task.
+pub type HostCallHandler<'b, C> = Box<
+    dyn FnMut(&mut Store<'b, C>, StoredHostCall) -> Result<(Vec<StoredValue>, u64), RuntimeError>,
+>;
+
+/// The policy used to select the next task to run
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Policy {
+    /// All tasks run in turn, regardless of their priorities
+    RoundRobin,
+    /// The ready task with the highest priority runs. Tasks of the same priority run in turn, like processes in
+    /// ARINC 653.
+    FixedPriority,
+}
+
+/// An identifier of a task spawned in a [`Scheduler`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TaskId(usize);
+
+/// The scheduling parameters of a task
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TaskConfig {
+    /// The fuel a task may consume per time slice. Unused fuel is not carried over to the next time slice, so the
+    /// quantum must be at least as large as the most expensive instruction or host call of the task. Otherwise the
+    /// task is removed with [`TaskEvent::QuantumTooSmall`] once it reaches such an instruction or host call.
+    pub quantum: u64,
+    /// The priority of a task, where larger values denote higher priorities. This is ignored by
+    /// [`Policy::RoundRobin`].
+    pub priority: u32,
+}
+
+/// The outcome of a single time slice of a task
+#[derive(Debug, PartialEq)]
+pub enum TaskEvent {
+    /// The task used up its quantum and was put back into the ready queue
+    Preempted,
+    /// The task was interrupted by a hook of the [`Config`] and was put back into the ready queue
+    Yielded,
+    /// The task finished with the given return values and was removed
+    Completed(Vec<StoredValue>),
+    /// The task trapped or its host call handler failed, and it was removed
+    Trapped(RuntimeError),
+    /// The next instruction or host call of the task costs `required_fuel`, which exceeds its quantum. The task could
+    /// never make progress, so it was removed.
+    QuantumTooSmall { required_fuel: u64 },
+}
+
+/// The point at which a task continues when it is scheduled the next time
+enum Continuation {
+    Wasm(Stored<WasmResumable>),
+    HostCall {
+        host_call: StoredHostCall,
+        resumable: Stored<HostResumable>,
+    },
+}
+
+struct Task<'b, C: Config> {
+    config: TaskConfig,
+    continuation: Continuation,
+    handler: HostCallHandler<'b, C>,
+}
+
+/// A scheduler that runs many tasks on a single [`Store`] in time slices bounded by fuel
+pub struct Scheduler<'b, C: Config> {
+    policy: Policy,
+    tasks: Vec<Option<Task<'b, C>>>,
+    /// The ids of all tasks that are not running, in the order they became ready
+    ready: VecDeque<TaskId>,
+}
+
+impl<'b, C: Config> Scheduler<'b, C> {
+    /// Creates a new scheduler without any tasks
+    pub fn new(policy: Policy) -> Self {
+        Self {
+            policy,
+            tasks: Vec::new(),
+            ready: VecDeque::new(),
+        }
+    }
+
+    /// Returns the scheduling policy
+    pub fn policy(&self) -> Policy {
+        self.policy
+    }
+
+    /// Spawns a new task, which invokes the function `func_addr` with `params` once it is scheduled. All host calls
+    /// of the task are passed to `handler`.
+    pub fn spawn(
+        &mut self,
+        store: &Store<'b, C>,
+        func_addr: Stored<FuncAddr>,
+        params: Vec<StoredValue>,
+        config: TaskConfig,
+        handler: impl FnMut(
+                &mut Store<'b, C>,
+                StoredHostCall,
+            ) -> Result<(Vec<StoredValue>, u64), RuntimeError>
+            + 'static,
+    ) -> Result<TaskId, RuntimeError> {
+        let continuation = match store.create_resumable(func_addr, params, Some(config.quantum))? {
+            StoredResumable::Wasm(resumable) => Continuation::Wasm(resumable),
+            StoredResumable::Host {
+                host_call,
+                host_resumable,
+            } => Continuation::HostCall {
+                host_call,
+                resumable: host_resumable,
+            },
+        };
+
+        let id = TaskId(self.tasks.len());
+        self.tasks.push(Some(Task {
+            config,
+            continuation,
+            handler: Box::new(handler),
+        }));
+        self.ready.push_back(id);
+        Ok(id)
+    }
+
+    /// Checks whether the task `id` has neither completed nor trapped yet
+    pub fn is_alive(&self, id: TaskId) -> bool {
+        self.tasks.get(id.0).is_some_and(Option::is_some)
+    }
+
+    /// Returns the number of tasks that have neither completed nor trapped yet
+    pub fn alive_tasks(&self) -> usize {
+        self.ready.len()
+    }
+
+    /// Removes the task `id` without running it any further. Returns `false` if the task was not alive.
+    pub fn kill(&mut self, id: TaskId) -> bool {
+        let Some(task) = self.tasks.get_mut(id.0) else {
+            return false;
+        };
+        if task.take().is_none() {
+            return false;
+        }
+        self.ready.retain(|ready| *ready != id);
+        true
+    }
+
+    /// Selects the next task according to the policy and runs it for at most its quantum. Returns the id of the task
+    /// together with the outcome of its time slice, or `None` if there are no tasks left.
+    pub fn run_slice(&mut self, store: &mut Store<'b, C>) -> Option<(TaskId, TaskEvent)> {
+        let position = match self.policy {
+            Policy::RoundRobin => 0,
+            Policy::FixedPriority => {
+                let highest = self
+                    .ready
+                    .iter()
+                    .map(|id| self.task(*id).config.priority)
+                    .max()?;
+                self.ready
+                    .iter()
+                    .position(|id| self.task(*id).config.priority == highest)
+                    .expect("a task with the highest priority to exist")
+            }
+        };
+        let id = self.ready.remove(position)?;
+
+        let Task {
+            config,
+            continuation,
+            mut handler,
+        } = self.tasks[id.0].take().expect("ready tasks to be alive");
+        let (continuation, event) =
+            run_time_slice(store, continuation, config.quantum, &mut handler);
+        if let Some(continuation) = continuation {
+            self.tasks[id.0] = Some(Task {
+                config,
+                continuation,
+                handler,
+            });
+            self.ready.push_back(id);
+        }
+        Some((id, event))
+    }
+
+    /// Runs time slices until all tasks have completed or trapped, passing every event to `on_event`
+    pub fn run_to_completion(
+        &mut self,
+        store: &mut Store<'b, C>,
+        mut on_event: impl FnMut(TaskId, TaskEvent),
+    ) {
+        while let Some((id, event)) = self.run_slice(store) {
+            on_event(id, event);
+        }
+    }
+
+    fn task(&self, id: TaskId) -> &Task<'b, C> {
+        self.tasks[id.0].as_ref().expect("ready tasks to be alive")
+    }
+}
+
+/// Runs a task from `continuation` until it finishes, traps or its `quantum` is used up. Returns the point at which
+/// the task continues, if it is still alive, and the outcome of the time slice. Tasks that cannot make progress within
+/// a single quantum are not continued, so that [`Scheduler::run_to_completion`] terminates.
+fn run_time_slice<'b, C: Config>(
+    store: &mut Store<'b, C>,
+    continuation: Continuation,
+    quantum: u64,
+    handler: &mut HostCallHandler<'b, C>,
+) -> (Option<Continuation>, TaskEvent) {
+    let mut run_state = match continuation {
+        Continuation::Wasm(mut resumable) => {
+            *resumable.fuel_mut() = Some(quantum);
+            store.resume_wasm(resumable)
+        }
+        Continuation::HostCall {
+            host_call,
+            resumable,
+        } => Ok(StoredRunState::HostCalled {
+            host_call,
+            resumable,
+        }),
+    };
+
+    loop {
+        run_state = match run_state {
+            Err(error) => return (None, TaskEvent::Trapped(error)),
+            Ok(StoredRunState::Finished { values, .. }) => {
+                return (None, TaskEvent::Completed(values))
+            }
+            Ok(StoredRunState::Resumable {
+                mut resumable,
+                required_fuel,
+            }) => {
+                // the fuel left in this time slice counts towards the cost of the next instruction or host call
+                let remaining_fuel = resumable.fuel_mut().unwrap_or(0);
+                let required_fuel = required_fuel.get().saturating_add(remaining_fuel);
+                if required_fuel > quantum {
+                    return (None, TaskEvent::QuantumTooSmall { required_fuel });
+                }
+                return (Some(Continuation::Wasm(resumable)), TaskEvent::Preempted);
+            }
+            // execution continues right after the host call
+            Ok(StoredRunState::Interrupted {
+                resumable,
+                reason: InterruptReason::HostCallFinished,
+            }) => store.resume_wasm(resumable),
+            Ok(StoredRunState::Interrupted { resumable, .. }) => {
+                return (Some(Continuation::Wasm(resumable)), TaskEvent::Yielded)
+            }
+            Ok(StoredRunState::HostCalled {
+                host_call,
+                resumable,
+            }) => match handler(store, host_call) {
+                Ok((values, fuel)) => store.finish_host_call_with_fuel(resumable, values, fuel),
+                Err(error) => Err(error),
+            },
+        };
+    }
+}
@@ -84,6 +84,9 @@ pub enum ValidationError {
     ExpectedAnOperand,
     /// The memory size specified by a mem type exceeds the maximum size.
     MemoryTooLarge,
+    /// The custom page size of a mem type is not supported. Only page sizes of 1 byte and 64 KiB are allowed, which
+    /// are encoded by their base-2 logarithm.
+    InvalidPageSize(u32),
     /// An attempt has been made to mutate a const global
     MutationOfConstGlobal,
     /// An alignment of some memory instruction is invalid
@@ -123,6 +126,8 @@ pub enum ValidationError {
     InvalidSelectTypeVectorLength(usize),
     /// A function specifies too many locals, i.e. more than 2^32 - 1
     TooManyLocals(u64),
+    /// There was not enough memory for the call frame layout of a function
+    OutOfMemory,
     /// Multiple exports share the same name
     DuplicateExportName,
     /// Multiple memories are not yet allowed without the proposal.
@@ -212,6 +217,7 @@ impl Display for ValidationError {
             ValidationError::InvalidValidationStackType(ty) => write!(f, "An unexpected type `{ty:?}` was found on the stack"),
             ValidationError::ExpectedAnOperand => write!(f, "Expected a value type operand on the stack"),
             ValidationError::MemoryTooLarge => write!(f, "The size specified by a memory type exceeds the maximum size"),
+            ValidationError::InvalidPageSize(log2) => write!(f, "The page size 2^{log2} of a memory type is neither 1 nor 65536 bytes"),
             ValidationError::MutationOfConstGlobal => write!(f, "An attempt has been made to mutate a const global"),
             ValidationError::ErroneousAlignment {alignment , minimum_required_alignment} => write!(f, "The alignment 2^{alignment} is not less or equal to the required alignment 2^{minimum_required_alignment}"),
             ValidationError::ValidationCtrlStackEmpty => write!(f, "Failed to retrieve last ctrl block because validation ctrl stack is empty"),
@@ -225,6 +231,7 @@ impl Display for ValidationError {
             ValidationError::ReferencingAnUnreferencedFunction(func_idx) => write!(f, "Referenced a function with index {func_idx} that was not referenced in prior validation"),
             ValidationError::InvalidSelectTypeVectorLength(len) => write!(f, "The type vector of a `select` instruction must be of length 1 as of now but it is of length {len} instead"),
             ValidationError::TooManyLocals(n) => write!(f,"There are {n} locals and this exceeds the maximum allowed number of 2^32-1"),
+            ValidationError::OutOfMemory => write!(f,"There was not enough memory for the call frame layout of a function"),
             ValidationError::DuplicateExportName => write!(f,"Multiple exports share the same name"),
             ValidationError::UnsupportedMultipleMemoriesProposal => write!(f,"A memory index other than 1 was used, but the proposal for multiple memories is not yet supported"),
             ValidationError::CodeExprHasTrailingInstructions => write!(f,"A code expression has invalid trailing instructions following its `end` instruction"),
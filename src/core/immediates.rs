@@ -0,0 +1,127 @@
+//! This module contains an optional data structure to avoid re-decoding immediates during in-place interpretation
+//!
+//! Immediates in WASM, such as local indices, memory offsets, constants and call targets, are encoded as variable
+//! length integers (LEB128). An in-place interpreter has to decode them again every time an instruction is executed.
+//! To avoid this, the immediates of frequently executed instructions can be decoded once during validation and stored
+//! in an [`ImmediatesCache`], which is indexed by the location of the first byte of an immediate.
+//!
+//! The cache only holds entries for instructions with immediates. They are stored in the order of their location in
+//! the bytecode, similar to the entries of the sidetable. To find the entry of an immediate without a search, the
+//! cache additionally keeps one bit per byte of the code section marking the first bytes of all pre-decoded immediates,
+//! together with the number of entries preceding every 64 of these bits. The cache therefore only takes up a fraction of
+//! a byte per byte of code plus one entry per immediate.
+//!
+//! The cache is bounded by a configurable byte limit (see [`ValidationOptions`](crate::ValidationOptions)). Immediates
+//! which no longer fit into this limit are decoded in place as usual. A limit of zero disables the cache entirely.
+
+use alloc::vec::Vec;
+
+use crate::core::reader::span::Span;
+use crate::core::reader::WasmReader;
+use crate::core::utils::ToUsizeExt;
+
+/// The number of bytecode locations covered by every word of [`ImmediatesCache::starts`]
+const BITS_PER_WORD: usize = u64::BITS as usize;
+
+/// Pre-decoded immediates of a code section, indexed by the location of their first byte
+#[derive(Debug, Clone, Default)]
+pub struct ImmediatesCache {
+    /// base: the location of the first byte of the code section
+    pub base: usize,
+
+    /// limit: the maximum number of bytes occupied by this cache
+    pub limit: usize,
+
+    /// starts: one bit per byte of the code section starting at `base`, which is set if a pre-decoded immediate
+    /// starts at this byte
+    ///
+    /// Words are only added up to the location of the last pre-decoded immediate.
+    pub starts: Vec<u64>,
+
+    /// ranks: the number of entries preceding the first location covered by the word of `starts` at the same index
+    pub ranks: Vec<u32>,
+
+    /// entries: one entry for every set bit in `starts`, in the order of their location
+    pub entries: Vec<PreDecodedImmediate>,
+}
+
+/// A decoded immediate and the number of bytes it occupies in the bytecode
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PreDecodedImmediate {
+    /// value: the decoded value, signed immediates are stored as their two's complement
+    pub value: u32,
+
+    /// len: the number of bytes to skip in the bytecode after reading this immediate
+    pub len: u32,
+}
+
+impl ImmediatesCache {
+    /// Creates an empty cache for the given code section, which takes up at most `limit` bytes
+    pub fn new(code_section: Span, limit: usize) -> Self {
+        Self {
+            base: code_section.from(),
+            limit,
+            ..Self::default()
+        }
+    }
+
+    /// Records an immediate which was decoded from the bytecode range `pc..end_pc`
+    ///
+    /// Immediates must be recorded in the order of their location. Immediates before the code section or which do not
+    /// fit into the limit of this cache anymore are silently ignored.
+    pub fn insert(&mut self, pc: usize, end_pc: usize, value: u32) {
+        let Some(offset) = pc.checked_sub(self.base) else {
+            return;
+        };
+        let word = offset / BITS_PER_WORD;
+        debug_assert!(
+            word + 1 >= self.starts.len(),
+            "immediates to be recorded in order"
+        );
+
+        let new_words = (word + 1).saturating_sub(self.starts.len());
+        let new_size = self.size_in_bytes()
+            + new_words * (size_of::<u64>() + size_of::<u32>())
+            + size_of::<PreDecodedImmediate>();
+        if new_size > self.limit {
+            return;
+        }
+        let Ok(rank) = u32::try_from(self.entries.len()) else {
+            return;
+        };
+
+        self.starts.resize(word + 1, 0);
+        self.ranks.resize(word + 1, rank);
+        self.starts[word] |= 1 << (offset % BITS_PER_WORD);
+        self.entries.push(PreDecodedImmediate {
+            value,
+            len: u32::try_from(end_pc - pc).expect("immediates to be at most a few bytes long"),
+        });
+    }
+
+    /// Returns the pre-decoded immediate at the current location of `wasm` and advances `wasm` past it
+    ///
+    /// If there is no pre-decoded immediate at this location, `wasm` is left untouched and the immediate has to be
+    /// decoded in place.
+    #[inline(always)]
+    pub fn take(&self, wasm: &mut WasmReader) -> Option<u32> {
+        let offset = wasm.pc.wrapping_sub(self.base);
+        let word = offset / BITS_PER_WORD;
+        let starts = *self.starts.get(word)?;
+        let bit = offset % BITS_PER_WORD;
+        if starts & (1 << bit) == 0 {
+            return None;
+        }
+        let preceding_in_word = (starts & ((1 << bit) - 1)).count_ones();
+        let entry = self.entries[(self.ranks[word] + preceding_in_word).into_usize()];
+        wasm.pc += entry.len.into_usize();
+        Some(entry.value)
+    }
+
+    /// Returns the number of bytes occupied by this cache
+    pub fn size_in_bytes(&self) -> usize {
+        self.starts.len() * size_of::<u64>()
+            + self.ranks.len() * size_of::<u32>()
+            + self.entries.len() * size_of::<PreDecodedImmediate>()
+    }
+}
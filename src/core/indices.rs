@@ -685,6 +685,17 @@ impl LocalIdx {
         let index = wasm.read_var_u32().unwrap();
         Self(index)
     }
+
+    /// Creates a local index from an already decoded value without validating
+    /// it.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the index was validated for the locals of
+    /// the current function.
+    pub unsafe fn new_unchecked(index: u32) -> Self {
+        Self(index)
+    }
 }
 
 /// Reads a label index from Wasm code without validating it.
@@ -1,7 +1,9 @@
 pub mod error;
 
+pub mod immediates;
 pub mod indices;
 pub mod reader;
 pub mod rw_spinlock;
 pub mod sidetable;
 pub mod utils;
+pub mod v128_operands;
@@ -56,7 +56,7 @@ impl ExportDesc {
                 // `ValidationInfo`.  Because the `ValidationInfo` struct
                 // guarantees that all indices contained in it are valid for all
                 // other `IdxVec` vectors in it, this is sound.
-                let func_type = unsafe { validation_info.types.get(*type_idx) };
+                let func_type = unsafe { validation_info.shared.types.get(*type_idx) };
                 // TODO ugly clone that should disappear when types are directly parsed from bytecode instead of vector copies
                 ExternType::Func(func_type.clone())
             }
@@ -75,7 +75,7 @@ impl ImportDesc {
                 // comes from the same `ValidationInfo`. Because all type
                 // indices contained by a `ValidationInfo` must always be valid,
                 // this is safe.
-                let func_type = unsafe { validation_info.types.get(*type_idx) };
+                let func_type = unsafe { validation_info.shared.types.get(*type_idx) };
                 // TODO ugly clone that should disappear when types are directly parsed from bytecode instead of vector copies
                 ExternType::Func(func_type.clone())
             }
@@ -99,6 +99,14 @@ impl ValType {
             Self::RefType(_) => todo!(),
         }
     }
+
+    /// Returns the number of slots a value of this type occupies on the value stack at runtime
+    pub const fn slots(&self) -> usize {
+        match self {
+            Self::VecType => 2,
+            Self::NumType(_) | Self::RefType(_) => 1,
+        }
+    }
 }
 
 impl ValType {
@@ -290,7 +298,13 @@ impl Debug for Limits {
 
 impl Limits {
     pub fn read(wasm: &mut WasmReader) -> Result<Self, ValidationError> {
-        let limits = match wasm.read_u8()? {
+        let flags = wasm.read_u8()?;
+        Self::read_with_flags(wasm, flags)
+    }
+
+    /// Reads the limits following an already read flags byte
+    fn read_with_flags(wasm: &mut WasmReader, flags: u8) -> Result<Self, ValidationError> {
+        let limits = match flags {
             0x00 => {
                 let min = wasm.read_var_u32()?;
                 Self { min, max: None }
@@ -341,22 +355,75 @@ impl TableType {
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct MemType {
     pub limits: Limits,
+    /// The base-2 logarithm of the page size in bytes, see the custom-page-sizes proposal:
+    /// <https://github.com/WebAssembly/custom-page-sizes/blob/main/proposals/custom-page-sizes/Overview.md>
+    ///
+    /// Valid memory types use either [`MemType::DEFAULT_PAGE_SIZE_LOG2`] or 0 for 1-byte pages.
+    pub page_size_log2: u8,
 }
 
 impl MemType {
+    /// The base-2 logarithm of the default page size of 64 KiB
+    pub const DEFAULT_PAGE_SIZE_LOG2: u8 = 16;
+
+    /// The bit of the limits flags byte which signals that a custom page size follows the limits
+    const CUSTOM_PAGE_SIZE_FLAG: u8 = 0x08;
+
+    /// Creates a memory type with the given limits and the default page size of 64 KiB
+    pub const fn new(limits: Limits) -> Self {
+        Self {
+            limits,
+            page_size_log2: Self::DEFAULT_PAGE_SIZE_LOG2,
+        }
+    }
+
+    /// Returns the page size of memories of this type in bytes
+    pub const fn page_size(&self) -> u32 {
+        1 << self.page_size_log2
+    }
+
+    /// Returns the maximum number of pages which memories of this type can ever have, so that their size in bytes
+    /// stays addressable by a 32-bit index.
+    ///
+    /// With 1-byte pages this is `u32::MAX` instead of 2^32, so that the failure result -1 of `memory.grow` remains
+    /// unambiguous.
+    pub const fn max_pages(&self) -> u32 {
+        let max_pages = (1u64 << 32) >> self.page_size_log2;
+        if max_pages > u32::MAX as u64 {
+            u32::MAX
+        } else {
+            max_pages as u32
+        }
+    }
+
     pub fn read(wasm: &mut WasmReader) -> Result<Self, ValidationError> {
-        let limit = Limits::read(wasm)?;
-        // Memory can only grow to 65536 pages of 64kb size (4GiB)
-        if limit.min > (1 << 16) {
+        let flags = wasm.read_u8()?;
+        let limit = Limits::read_with_flags(wasm, flags & !Self::CUSTOM_PAGE_SIZE_FLAG)?;
+        let page_size_log2 = if flags & Self::CUSTOM_PAGE_SIZE_FLAG != 0 {
+            match wasm.read_var_u32()? {
+                0 => 0,
+                16 => Self::DEFAULT_PAGE_SIZE_LOG2,
+                other => return Err(ValidationError::InvalidPageSize(other)),
+            }
+        } else {
+            Self::DEFAULT_PAGE_SIZE_LOG2
+        };
+        let mem_type = Self {
+            limits: limit,
+            page_size_log2,
+        };
+
+        // Memory can only grow to 4GiB, e.g. 65536 pages of 64kb size
+        if limit.min > mem_type.max_pages() {
             return Err(ValidationError::MemoryTooLarge);
         }
         if let Some(max_limit) = limit.max {
-            if max_limit > (1 << 16) {
+            if max_limit > mem_type.max_pages() {
                 return Err(ValidationError::MemoryTooLarge);
             }
         }
 
-        Ok(Self { limits: limit })
+        Ok(mem_type)
     }
 }
 
@@ -384,6 +451,7 @@ impl ImportSubTypeRelation for ExternType {
             ExternType::Mem(self_mem_type) => match other {
                 ExternType::Mem(other_mem_type) => {
                     self_mem_type.limits.is_subtype_of(&other_mem_type.limits)
+                        && self_mem_type.page_size_log2 == other_mem_type.page_size_log2
                 }
                 _ => false,
             },
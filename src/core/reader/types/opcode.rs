@@ -206,8 +206,9 @@ pub mod fc_extensions {
     pub const TABLE_FILL: u32 = 0x11;
 }
 
-pub fn fc_extension_opcode_to_str(instr: u32) -> alloc::borrow::Cow<'static, str> {
-    match instr {
+/// Returns the name of the `0xFC`-prefixed instruction `instr`, or `None` if it is not a known instruction
+pub fn fc_extension_opcode_name(instr: u32) -> Option<&'static str> {
+    let name = match instr {
         0x00 => "I32_TRUNC_SAT_F32_S",
         0x01 => "I32_TRUNC_SAT_F32_U",
         0x02 => "I32_TRUNC_SAT_F64_S",
@@ -226,9 +227,16 @@ pub fn fc_extension_opcode_to_str(instr: u32) -> alloc::borrow::Cow<'static, str
         0x0F => "TABLE_GROW",
         0x10 => "TABLE_SIZE",
         0x11 => "TABLE_FILL",
-        instr => return alloc::format!("UNKNOWN({instr:x})").into(),
+        _ => return None,
+    };
+    Some(name)
+}
+
+pub fn fc_extension_opcode_to_str(instr: u32) -> alloc::borrow::Cow<'static, str> {
+    match fc_extension_opcode_name(instr) {
+        Some(name) => name.into(),
+        None => alloc::format!("UNKNOWN({instr:x})").into(),
     }
-    .into()
 }
 
 pub mod fd_extensions {
@@ -507,10 +515,11 @@ pub mod fd_extensions {
     pub const F64X2_CONVERT_LOW_I32X4_U: u32 = 255;
 }
 
-pub fn fd_extension_opcode_to_str(instr: u32) -> alloc::borrow::Cow<'static, str> {
+/// Returns the name of the `0xFD`-prefixed instruction `instr`, or `None` if it is not a known instruction
+pub fn fd_extension_opcode_name(instr: u32) -> Option<&'static str> {
     use fd_extensions::*;
 
-    match instr {
+    let name = match instr {
         V128_LOAD => "V128_LOAD",
         V128_LOAD8X8_S => "V128_LOAD8X8_S",
         V128_LOAD8X8_U => "V128_LOAD8X8_U",
@@ -764,13 +773,21 @@ pub fn fd_extension_opcode_to_str(instr: u32) -> alloc::borrow::Cow<'static, str
         I32X4_TRUNC_SAT_F64X2_U_ZERO => "I32X4_TRUNC_SAT_F64X2_U_ZERO",
         F64X2_CONVERT_LOW_I32X4_S => "F64X2_CONVERT_LOW_I32X4_S",
         F64X2_CONVERT_LOW_I32X4_U => "F64X2_CONVERT_LOW_I32X4_U",
-        instr => return alloc::format!("UNKNOWN({instr:x})").into(),
+        _ => return None,
+    };
+    Some(name)
+}
+
+pub fn fd_extension_opcode_to_str(instr: u32) -> alloc::borrow::Cow<'static, str> {
+    match fd_extension_opcode_name(instr) {
+        Some(name) => name.into(),
+        None => alloc::format!("UNKNOWN({instr:x})").into(),
     }
-    .into()
 }
 
-pub fn opcode_byte_to_str(byte: u8) -> alloc::borrow::Cow<'static, str> {
-    match byte {
+/// Returns the name of the single-byte instruction `byte`, or `None` if it is not a known instruction
+pub fn opcode_byte_name(byte: u8) -> Option<&'static str> {
+    let name = match byte {
         UNREACHABLE => "UNREACHABLE",
         NOP => "NOP",
         BLOCK => "BLOCK",
@@ -955,7 +972,14 @@ pub fn opcode_byte_to_str(byte: u8) -> alloc::borrow::Cow<'static, str> {
         I64_EXTEND8_S => "I64_EXTEND8_S",
         I64_EXTEND16_S => "I64_EXTEND16_S",
         I64_EXTEND32_S => "I64_EXTEND32_S",
-        instr => return alloc::format!("UNKNOWN({instr:x})").into(),
+        _ => return None,
+    };
+    Some(name)
+}
+
+pub fn opcode_byte_to_str(byte: u8) -> alloc::borrow::Cow<'static, str> {
+    match opcode_byte_name(byte) {
+        Some(name) => name.into(),
+        None => alloc::format!("UNKNOWN({byte:x})").into(),
     }
-    .into()
 }
@@ -46,6 +46,13 @@ impl<T> RwSpinLock<T> {
         }
     }
 
+    /// Get mutable access to the value wrapped in this [`RwSpinLock`]
+    ///
+    /// This does not lock, as the exclusive borrow statically guarantees that no other guard exists.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.inner.get_mut()
+    }
+
     // Get read access to the value wrapped in this [`RwSpinLock`]
     pub fn read(&self) -> ReadLockGuard<'_, T> {
         // get the current state
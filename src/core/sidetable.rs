@@ -51,15 +51,100 @@ pub struct SidetableEntry {
     /// stp: the next value side-table index if the branch is taken
     pub stp: usize,
 
-    /// valcnt: the number of values that will be copied if the branch is taken
+    /// valcnt: the number of value stack slots that will be copied if the branch is taken
     ///
     /// Branches may additionally consume operands themselves, which they push back on the operand
     /// stack after unwinding.
     pub valcnt: usize,
 
-    /// popcnt: the number of values that will be popped if the branch is taken
+    /// popcnt: the number of value stack slots that will be popped if the branch is taken
     ///
     /// Taking a branch unwinds the operand stack down to the height where the targeted structured
     /// control instruction was entered.
     pub popcnt: usize,
 }
+
+/// A basic block, i.e. a straight-line sequence of instructions spanning the bytecode from `pc` up to `end`
+///
+/// Execution can only enter a basic block at its first instruction and leave it after its last instruction, unless
+/// it traps or is interrupted. Therefore, the fuel for all of its instructions can be charged at once when the basic
+/// block is entered. Instructions whose cost depends on their operands are never part of a basic block.
+#[derive(Debug, Clone)]
+pub struct BasicBlock {
+    /// pc: the location of the first instruction of this basic block
+    pub pc: usize,
+
+    /// end: the location right after the last instruction of this basic block
+    pub end: usize,
+}
+
+/// The fuel costs of the basic blocks of a module under a specific [`Config`](crate::Config), indexed by the location
+/// of their first instruction
+///
+/// The interpreter looks up whether an instruction starts a basic block whenever it leaves the previous one, so the
+/// lookup takes constant time: A bitmap with one bit per byte of the code section marks the first instruction of every
+/// basic block, and the number of marked bits before each word of the bitmap gives the index of its first basic block.
+/// The bitmap only covers the code section up to the last basic block.
+#[derive(Debug, Clone, Default)]
+pub struct BlockFuelTable {
+    /// base: the location of the first basic block
+    base: usize,
+
+    /// starts: one bit for every covered byte, starting at `base`, which is set for the first byte of a basic block
+    starts: Vec<u64>,
+
+    /// ranks: the number of set bits in `starts` before each of its words
+    ranks: Vec<u32>,
+
+    /// blocks: the fuel of all basic blocks, sorted by the location of their first instruction
+    blocks: Vec<BlockFuel>,
+}
+
+impl BlockFuelTable {
+    /// An empty table, for modules whose basic blocks were not computed
+    pub const EMPTY: Self = Self {
+        base: 0,
+        starts: Vec::new(),
+        ranks: Vec::new(),
+        blocks: Vec::new(),
+    };
+
+    /// Appends the basic block starting at `pc`, which must lie after all basic blocks that were already added
+    pub fn push(&mut self, pc: usize, block_fuel: BlockFuel) {
+        if self.blocks.is_empty() {
+            self.base = pc;
+        }
+        let index = pc - self.base;
+        let word = index / u64::BITS as usize;
+        let rank = u32::try_from(self.blocks.len())
+            .expect("the number of basic blocks to be smaller than the size of the code section");
+        self.starts.resize(word + 1, 0);
+        self.ranks.resize(word + 1, rank);
+        self.starts[word] |= 1 << (index % u64::BITS as usize);
+        self.blocks.push(block_fuel);
+    }
+
+    /// Returns the fuel of the basic block starting at `pc`, if there is one
+    #[inline(always)]
+    pub fn get(&self, pc: usize) -> Option<&BlockFuel> {
+        let index = pc.wrapping_sub(self.base);
+        let word = index / u64::BITS as usize;
+        let bit = index % u64::BITS as usize;
+        let starts = *self.starts.get(word)?;
+        if starts & (1 << bit) == 0 {
+            return None;
+        }
+        let rank = self.ranks[word] as usize + (starts & ((1 << bit) - 1)).count_ones() as usize;
+        self.blocks.get(rank)
+    }
+}
+
+/// The fuel that is charged when entering a [`BasicBlock`]
+#[derive(Debug, Clone)]
+pub struct BlockFuel {
+    /// end: the location right after the last instruction of the basic block
+    pub end: usize,
+
+    /// fuel: the sum of the flat costs of all instructions of the basic block
+    pub fuel: u64,
+}
@@ -0,0 +1,54 @@
+//! This module contains the data structure which tells the interpreter which `drop` and `select` instructions operate
+//! on `v128` values
+//!
+//! The untyped value stack stores `v128` values in two slots and all other values in one slot. Most instructions
+//! determine the number of slots of their operands from their opcode or immediates, but `drop` and the untyped `select`
+//! are polymorphic. Their operand types are only known during validation, which records the locations of those
+//! operating on `v128` values in a [`V128Operands`] set.
+//!
+//! The set is a bitmap with one bit per byte of the code section, so that the interpreter can look up a location in
+//! constant time. It only covers the code section up to the last recorded location and is therefore empty for modules
+//! without such instructions.
+
+use alloc::vec::Vec;
+
+use crate::core::reader::span::Span;
+
+/// The locations of all `drop` and `select` instructions of a code section whose operands are `v128` values
+#[derive(Debug, Clone, Default)]
+pub struct V128Operands {
+    /// base: the location of the first byte of the code section
+    base: usize,
+
+    /// words: one bit for every covered byte, starting at `base`
+    words: Vec<u64>,
+}
+
+impl V128Operands {
+    /// Creates an empty set for the given code section
+    pub fn new(code_section: Span) -> Self {
+        Self {
+            base: code_section.from(),
+            words: Vec::new(),
+        }
+    }
+
+    /// Records the location `pc` of an instruction within the code section
+    pub fn insert(&mut self, pc: usize) {
+        let index = pc - self.base;
+        let word = index / u64::BITS as usize;
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        self.words[word] |= 1 << (index % u64::BITS as usize);
+    }
+
+    /// Returns whether the instruction at the location `pc` was recorded
+    #[inline(always)]
+    pub fn contains(&self, pc: usize) -> bool {
+        let index = pc.wrapping_sub(self.base);
+        self.words
+            .get(index / u64::BITS as usize)
+            .is_some_and(|word| word & (1 << (index % u64::BITS as usize)) != 0)
+    }
+}
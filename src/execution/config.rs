@@ -1,14 +1,86 @@
+use core::ops::ControlFlow;
+
+use crate::{
+    addrs::{FuncAddr, GlobalAddr, MemAddr, TableAddr},
+    Value,
+};
+
+/// The decision of a resource limiter about growing a memory or a table, see [`Config::memory_grow_hook`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GrowthDecision {
+    /// The memory or table is grown
+    Allow,
+    /// The growth fails, i.e. `memory.grow` and `table.grow` return -1 as if the declared maximum was exceeded
+    Deny,
+    /// Execution traps with [`TrapError::ResourceLimitExceeded`](crate::TrapError::ResourceLimitExceeded)
+    Trap,
+}
+
+/// The resources allocated for all memories and tables of a [`Store`](crate::Store)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ResourceUsage {
+    /// The total size of all linear memories in bytes
+    pub memory_bytes: u64,
+    /// The total number of elements of all tables
+    pub table_elements: u64,
+}
+
+/// The maximum sizes of the stacks of an invocation
+///
+/// Every [`Store`](crate::Store) has stack limits, which are initialized from [`Config::MAX_VALUE_STACK_SIZE`] and
+/// [`Config::MAX_CALL_STACK_SIZE`] and apply to all invocations created by it. They can be changed at runtime with
+/// [`Store::set_stack_limits`](crate::Store::set_stack_limits) or overridden for a single invocation with
+/// [`Store::create_resumable_with_stack_limits`](crate::Store::create_resumable_with_stack_limits) and
+/// [`Store::create_resumable_in_with_stack_limits`](crate::Store::create_resumable_in_with_stack_limits).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StackLimits {
+    /// Maximum number of slots in the value stack, where `v128` values occupy two slots and all other values one
+    pub max_value_stack_size: usize,
+    /// Maximum number of cascading function invocations
+    pub max_call_stack_size: usize,
+}
+
+impl StackLimits {
+    /// Returns the default stack limits of the configuration `C`
+    pub const fn of<C: Config>() -> Self {
+        Self {
+            max_value_stack_size: C::MAX_VALUE_STACK_SIZE,
+            max_call_stack_size: C::MAX_CALL_STACK_SIZE,
+        }
+    }
+}
+
+impl Default for StackLimits {
+    fn default() -> Self {
+        Self::of::<()>()
+    }
+}
+
 /// Trait that allows user specified configuration for various items during interpretation. Additionally, the types
 /// implementing this trait can act as custom user data within an interpreter instance, passed along to each method of
 /// this trait and host functions whenever they are invoked.
 ///
 /// The default implementation of all trait methods have the least overhead, i. e. most can be optimized out fully.
+///
+/// # Fuel costs
+///
+/// The `get_*_cost*` methods take `&self`, so that costs can be read from data of the configuration, e.g. a
+/// [`CostTable`](crate::cost_table::CostTable). Configurations that implemented them as associated functions without a
+/// receiver have to add `&self` to their signatures.
+///
+/// The costs returned by a configuration must stay fixed for the entire lifetime of every module instance of the
+/// [`Store`](crate::Store) using it: The total cost of each basic block of straight-line code is computed once when
+/// fuel-metered execution first reaches a module in a store, shared with the other instances of the module in the same
+/// store and charged at the start of the block. If the costs changed afterwards, basic blocks would still be charged
+/// with the old costs, while instructions outside of basic blocks would be charged with the new ones.
 // It must always be checked that there is no additional performance penalty for the default config!
 pub trait Config {
-    /// Maximum number of values in the value stack
+    /// Maximum number of slots in the value stack, used as the default [`StackLimits`] of a
+    /// [`Store`](crate::Store). `v128` values occupy two slots, all other values one.
     const MAX_VALUE_STACK_SIZE: usize = 0xf0000; // 64 Kibi-Values
 
-    /// Maximum number of cascading function invocations
+    /// Maximum number of cascading function invocations, used as the default [`StackLimits`] of a
+    /// [`Store`](crate::Store)
     const MAX_CALL_STACK_SIZE: usize = 0x1000; // 4 Kibi-Functions
 
     /// A hook which is called before every wasm instruction
@@ -18,12 +90,173 @@ pub trait Config {
     #[inline(always)]
     fn instruction_hook(&mut self, _bytecode: &[u8], _pc: usize) {}
 
+    /// A hook which is called whenever the interpreter starts or resumes executing Wasm code, with the address of the
+    /// function execution continues in. `new_invocation` is `true` if execution starts at the beginning of an invoked
+    /// function, and `false` if a resumable continues an invocation which was executed before.
+    #[inline(always)]
+    fn resume_hook(&mut self, _func_addr: FuncAddr, _new_invocation: bool) {}
+
+    /// A hook which is called whenever Wasm code calls a function, be it a Wasm or a host function. It is called
+    /// before the first instruction of the callee is executed.
+    #[inline(always)]
+    fn call_hook(&mut self, _func_addr: FuncAddr) {}
+
+    /// A hook which is called whenever a function returns, i.e. when a Wasm function reaches its final `end`
+    /// instruction or when a host call is finished through [`Store::finish_host_call`](crate::Store::finish_host_call)
+    #[inline(always)]
+    fn return_hook(&mut self, _func_addr: FuncAddr) {}
+
+    /// A hook which is called whenever `fuel` units of fuel are deducted. This hook is only called for fuel-metered
+    /// execution.
+    ///
+    /// The fuel of a basic block, i.e. a straight-line sequence of instructions, is deducted at once when execution
+    /// enters it, so this hook is called once per basic block rather than once per instruction. If execution traps or
+    /// is interrupted within a basic block, the fuel of its remaining instructions is given back without calling this
+    /// hook again.
+    #[inline(always)]
+    fn fuel_hook(&mut self, _fuel: u64) {}
+
+    /// A hook which handles a load of a single value from a memory-mapped I/O region, see
+    /// [`Store::mem_map_mmio`](crate::Store::mem_map_mmio)
+    ///
+    /// `address` is the effective address of the access within the memory at `mem_addr`. `bytes` initially contains
+    /// the bytes stored in the linear memory at that address and is to be overwritten with the loaded value in little
+    /// endian byte order. Afterwards, [`Config::memory_load_hook`] is called as for every other load.
+    #[inline(always)]
+    fn mmio_load_hook(&mut self, _mem_addr: MemAddr, _address: usize, _bytes: &mut [u8]) {}
+
+    /// A hook which handles a store of a single value to a memory-mapped I/O region, see
+    /// [`Store::mem_map_mmio`](crate::Store::mem_map_mmio)
+    ///
+    /// `address` is the effective address of the access within the memory at `mem_addr`. `bytes` contains the stored
+    /// value in little endian byte order. The linear memory itself is not written to. Afterwards,
+    /// [`Config::memory_store_hook`] is called as for every other store.
+    #[inline(always)]
+    fn mmio_store_hook(&mut self, _mem_addr: MemAddr, _address: usize, _bytes: &[u8]) {}
+
+    /// A hook which is called after every linear memory load of a single value, e.g. by `i32.load` or
+    /// `v128.load8_lane`
+    ///
+    /// `address` is the effective address of the access within the memory at `mem_addr`. `bytes` contains the loaded
+    /// value in little endian byte order, thus its length is the width of the access. Returning
+    /// [`ControlFlow::Break`] interrupts execution right after the current instruction, yielding a resumable in the
+    /// same way as running out of fuel does.
+    #[inline(always)]
+    fn memory_load_hook(
+        &mut self,
+        _mem_addr: MemAddr,
+        _address: usize,
+        _bytes: &[u8],
+    ) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// A hook which is called after every linear memory store of a single value, e.g. by `i32.store` or
+    /// `v128.store8_lane`
+    ///
+    /// The arguments and return value are interpreted just like for [`Config::memory_load_hook`], with `bytes`
+    /// containing the stored value.
+    #[inline(always)]
+    fn memory_store_hook(
+        &mut self,
+        _mem_addr: MemAddr,
+        _address: usize,
+        _bytes: &[u8],
+    ) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// A hook which is called after a bulk memory instruction read the `len` bytes starting at `address` in the
+    /// memory at `mem_addr`. In Wasm 2.0 this applies only to the source of `memory.copy`.
+    ///
+    /// Returning [`ControlFlow::Break`] interrupts execution right after the current instruction.
+    #[inline(always)]
+    fn memory_bulk_load_hook(
+        &mut self,
+        _mem_addr: MemAddr,
+        _address: usize,
+        _len: usize,
+    ) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// A hook which is called after a bulk memory instruction wrote the `len` bytes starting at `address` in the
+    /// memory at `mem_addr`. In Wasm 2.0 this applies to `memory.init`, `memory.fill` and the destination of
+    /// `memory.copy`.
+    ///
+    /// Returning [`ControlFlow::Break`] interrupts execution right after the current instruction.
+    #[inline(always)]
+    fn memory_bulk_store_hook(
+        &mut self,
+        _mem_addr: MemAddr,
+        _address: usize,
+        _len: usize,
+    ) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// A hook which is called after every `global.set` instruction with the address of the global and the value that
+    /// was just written to it
+    ///
+    /// Returning [`ControlFlow::Break`] interrupts execution right after the current instruction.
+    #[inline(always)]
+    fn global_set_hook(&mut self, _global_addr: GlobalAddr, _value: Value) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// A hook which is called after every branch back to a loop and after every call of a Wasm function by Wasm code,
+    /// before the first instruction of the callee is executed
+    ///
+    /// This allows the host to interrupt long-running Wasm code without the overhead of fuel metering, e.g. by
+    /// checking a flag or a counter that is updated asynchronously. Returning [`ControlFlow::Break`] interrupts
+    /// execution right after the current instruction, yielding a resumable in the same way as running out of fuel does.
+    /// See [`EpochDeadline`](crate::epoch::EpochDeadline) for a ready-made implementation.
+    #[inline(always)]
+    fn interruption_hook(&mut self) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// A hook which acts as a resource limiter for linear memories. It is called whenever the memory at `mem_addr`
+    /// with `current_pages` pages is about to grow by `delta_pages` pages, either by `memory.grow` or by
+    /// [`Store::mem_grow`](crate::Store::mem_grow), together with the resources currently allocated by the store.
+    ///
+    /// The hook is only called if the growth stays within the maximum declared by the memory type. It is also called
+    /// before the initial pages of a new memory are allocated, either during instantiation or by
+    /// [`Store::mem_alloc`](crate::Store::mem_alloc), with `current_pages` being zero and `mem_addr` being the address
+    /// the new memory will get. A denied allocation fails with
+    /// [`RuntimeError::GrowthDenied`](crate::RuntimeError::GrowthDenied).
+    #[inline(always)]
+    fn memory_grow_hook(
+        &mut self,
+        _mem_addr: MemAddr,
+        _current_pages: u32,
+        _delta_pages: u32,
+        _usage: &ResourceUsage,
+    ) -> GrowthDecision {
+        GrowthDecision::Allow
+    }
+
+    /// A hook which acts as a resource limiter for tables, just like [`Config::memory_grow_hook`] does for linear
+    /// memories. It is called whenever the table at `table_addr` with `current_elements` elements is about to grow by
+    /// `delta_elements` elements, either by `table.grow` or by [`Store::table_grow`](crate::Store::table_grow). Like
+    /// the memory hook, it is also called before the initial elements of a new table are allocated.
+    #[inline(always)]
+    fn table_grow_hook(
+        &mut self,
+        _table_addr: TableAddr,
+        _current_elements: u32,
+        _delta_elements: u32,
+        _usage: &ResourceUsage,
+    ) -> GrowthDecision {
+        GrowthDecision::Allow
+    }
+
     /// Amount of fuel to be deducted when a single byte `instr` is hit. The cost corresponding to `UNREACHABLE` and
     /// `END` instructions and other bytes that do not correspond to any Wasm instruction are ignored.
     // It must always be checked that the calls to this method fold into a constant if it is just a match statement that
     // yields constants.
     #[inline(always)]
-    fn get_flat_cost(_instr: u8) -> u64 {
+    fn get_flat_cost(&self, _instr: u8) -> u64 {
         1
     }
 
@@ -34,7 +267,7 @@ pub trait Config {
     // It must always be checked that the calls to this method fold into a constant if it is just a match statement that
     // yields constants.
     #[inline(always)]
-    fn get_fc_extension_flat_cost(_instr: u32) -> u64 {
+    fn get_fc_extension_flat_cost(&self, _instr: u32) -> u64 {
         1
     }
 
@@ -45,7 +278,7 @@ pub trait Config {
     // It must always be checked that the calls to this method fold into a constant if it is just a match statement that
     // yields constants.
     #[inline(always)]
-    fn get_fd_extension_flat_cost(_instr: u32) -> u64 {
+    fn get_fd_extension_flat_cost(&self, _instr: u32) -> u64 {
         1
     }
 
@@ -61,7 +294,7 @@ pub trait Config {
     // It must always be checked that the calls to this method fold into a constant if it is just a match statement that
     // yields constants.
     #[inline(always)]
-    fn get_cost_per_element(_instr: u8) -> u64 {
+    fn get_cost_per_element(&self, _instr: u8) -> u64 {
         0
     }
 
@@ -86,10 +319,118 @@ pub trait Config {
     // It must always be checked that the calls to this method fold into a constant if it is just a match statement that
     // yields constants.
     #[inline(always)]
-    fn get_fc_extension_cost_per_element(_instr: u32) -> u64 {
+    fn get_fc_extension_cost_per_element(&self, _instr: u32) -> u64 {
         0
     }
 }
 
 /// Default implementation of the interpreter configuration, with all hooks empty
 impl Config for () {}
+
+/// Generates [`ConfigLayer`] together with its blanket implementation of [`Config`] from a single list of all methods of
+/// [`Config`]. Every method that is added to [`Config`] has to be added here as well, so that wrappers forward it.
+macro_rules! config_layer {
+    (
+        mut { $(fn $mut_name:ident(&mut self $(, $mut_arg:ident: $mut_ty:ty)*) $(-> $mut_ret:ty)?;)* }
+        ref { $(fn $ref_name:ident(&self $(, $ref_arg:ident: $ref_ty:ty)*) $(-> $ref_ret:ty)?;)* }
+    ) => {
+        /// A configuration that wraps an inner [`Config`] and forwards all constants, hooks and cost functions to it,
+        /// except for those it overrides
+        ///
+        /// Every type implementing this trait implements [`Config`] as well. Wrappers like
+        /// [`Watchpoints`](crate::watchpoints::Watchpoints) or [`EpochDeadline`](crate::epoch::EpochDeadline) only
+        /// override the methods they add behavior to, so that they can be stacked on top of each other and forward
+        /// hooks added to [`Config`] in the future without any changes.
+        pub trait ConfigLayer {
+            /// The wrapped configuration
+            type Inner: Config;
+
+            /// See [`Config::MAX_VALUE_STACK_SIZE`]
+            const MAX_VALUE_STACK_SIZE: usize = <Self::Inner as Config>::MAX_VALUE_STACK_SIZE;
+
+            /// See [`Config::MAX_CALL_STACK_SIZE`]
+            const MAX_CALL_STACK_SIZE: usize = <Self::Inner as Config>::MAX_CALL_STACK_SIZE;
+
+            /// Returns a reference to the wrapped configuration
+            fn inner(&self) -> &Self::Inner;
+
+            /// Returns a mutable reference to the wrapped configuration
+            fn inner_mut(&mut self) -> &mut Self::Inner;
+
+            $(
+                #[doc = concat!("See [`Config::", stringify!($mut_name), "`], forwarded to the wrapped configuration by default")]
+                #[inline(always)]
+                fn $mut_name(&mut self $(, $mut_arg: $mut_ty)*) $(-> $mut_ret)? {
+                    self.inner_mut().$mut_name($($mut_arg),*)
+                }
+            )*
+
+            $(
+                #[doc = concat!("See [`Config::", stringify!($ref_name), "`], forwarded to the wrapped configuration by default")]
+                #[inline(always)]
+                fn $ref_name(&self $(, $ref_arg: $ref_ty)*) $(-> $ref_ret)? {
+                    self.inner().$ref_name($($ref_arg),*)
+                }
+            )*
+        }
+
+        impl<L: ConfigLayer> Config for L {
+            const MAX_VALUE_STACK_SIZE: usize = <L as ConfigLayer>::MAX_VALUE_STACK_SIZE;
+
+            const MAX_CALL_STACK_SIZE: usize = <L as ConfigLayer>::MAX_CALL_STACK_SIZE;
+
+            $(
+                #[inline(always)]
+                fn $mut_name(&mut self $(, $mut_arg: $mut_ty)*) $(-> $mut_ret)? {
+                    <L as ConfigLayer>::$mut_name(self $(, $mut_arg)*)
+                }
+            )*
+
+            $(
+                #[inline(always)]
+                fn $ref_name(&self $(, $ref_arg: $ref_ty)*) $(-> $ref_ret)? {
+                    <L as ConfigLayer>::$ref_name(self $(, $ref_arg)*)
+                }
+            )*
+        }
+    };
+}
+
+config_layer! {
+    mut {
+        fn instruction_hook(&mut self, bytecode: &[u8], pc: usize);
+        fn resume_hook(&mut self, func_addr: FuncAddr, new_invocation: bool);
+        fn call_hook(&mut self, func_addr: FuncAddr);
+        fn return_hook(&mut self, func_addr: FuncAddr);
+        fn fuel_hook(&mut self, fuel: u64);
+        fn mmio_load_hook(&mut self, mem_addr: MemAddr, address: usize, bytes: &mut [u8]);
+        fn mmio_store_hook(&mut self, mem_addr: MemAddr, address: usize, bytes: &[u8]);
+        fn memory_load_hook(&mut self, mem_addr: MemAddr, address: usize, bytes: &[u8]) -> ControlFlow<()>;
+        fn memory_store_hook(&mut self, mem_addr: MemAddr, address: usize, bytes: &[u8]) -> ControlFlow<()>;
+        fn memory_bulk_load_hook(&mut self, mem_addr: MemAddr, address: usize, len: usize) -> ControlFlow<()>;
+        fn memory_bulk_store_hook(&mut self, mem_addr: MemAddr, address: usize, len: usize) -> ControlFlow<()>;
+        fn global_set_hook(&mut self, global_addr: GlobalAddr, value: Value) -> ControlFlow<()>;
+        fn interruption_hook(&mut self) -> ControlFlow<()>;
+        fn memory_grow_hook(
+            &mut self,
+            mem_addr: MemAddr,
+            current_pages: u32,
+            delta_pages: u32,
+            usage: &ResourceUsage
+        ) -> GrowthDecision;
+        fn table_grow_hook(
+            &mut self,
+            table_addr: TableAddr,
+            current_elements: u32,
+            delta_elements: u32,
+            usage: &ResourceUsage
+        ) -> GrowthDecision;
+    }
+    ref {
+        fn get_flat_cost(&self, instr: u8) -> u64;
+        fn get_fc_extension_flat_cost(&self, instr: u32) -> u64;
+        fn get_fd_extension_flat_cost(&self, instr: u32) -> u64;
+        fn get_cost_per_element(&self, instr: u8) -> u64;
+        fn get_fc_extension_cost_per_element(&self, instr: u32) -> u64;
+    }
+}
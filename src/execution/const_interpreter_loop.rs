@@ -6,15 +6,11 @@ use crate::{
     config::Config,
     core::{
         indices::{FuncIdx, GlobalIdx},
-        reader::{
-            span::Span,
-            types::{FuncType, ResultType},
-            WasmReader,
-        },
+        reader::{span::Span, WasmReader},
     },
+    execution::fallible_alloc::TryVecExt,
     unreachable_validated,
     value::{self, Ref},
-    value_stack::Stack,
     RefType, RuntimeError, Store, Value,
 };
 
@@ -33,7 +29,7 @@ use crate::{
 // TODO this signature might change to support hooks or match the spec better
 pub(crate) unsafe fn run_const<T: Config>(
     wasm: &mut WasmReader,
-    stack: &mut Stack,
+    stack: &mut Vec<Value>,
     module: ModuleAddr,
     store: &Store<T>,
 ) -> Result<(), RuntimeError> {
@@ -70,32 +66,32 @@ pub(crate) unsafe fn run_const<T: Config>(
                     "Constant instruction: global.get [{global_idx}] -> [{:?}]",
                     global
                 );
-                stack.push_value::<T>(global.value)?;
+                stack.try_push(global.value)?;
             }
             I32_CONST => {
                 let constant = wasm.read_var_i32().unwrap_validated();
                 trace!("Constant instruction: i32.const [] -> [{constant}]");
-                stack.push_value::<T>(constant.into())?;
+                stack.try_push(constant.into())?;
             }
             F32_CONST => {
                 let constant = value::F32::from_bits(wasm.read_f32().unwrap_validated());
                 trace!("Constanting instruction: f32.const [] -> [{constant}]");
-                stack.push_value::<T>(constant.into())?;
+                stack.try_push(constant.into())?;
             }
             F64_CONST => {
                 let constant = value::F64::from_bits(wasm.read_f64().unwrap_validated());
                 trace!("Constanting instruction: f64.const [] -> [{constant}]");
-                stack.push_value::<T>(constant.into())?;
+                stack.try_push(constant.into())?;
             }
             I64_CONST => {
                 let constant = wasm.read_var_i64().unwrap_validated();
                 trace!("Constant instruction: i64.const [] -> [{constant}]");
-                stack.push_value::<T>(constant.into())?;
+                stack.try_push(constant.into())?;
             }
             REF_NULL => {
                 let reftype = RefType::read(wasm).unwrap_validated();
 
-                stack.push_value::<T>(Value::Ref(Ref::Null(reftype)))?;
+                stack.try_push(Value::Ref(Ref::Null(reftype)))?;
                 trace!("Instruction: ref.null '{:?}' -> [{:?}]", reftype, reftype);
             }
             REF_FUNC => {
@@ -105,7 +101,7 @@ pub(crate) unsafe fn run_const<T: Config>(
                 // SAFETY: Validation guarantees the function index to be valid
                 // in the current module.
                 let func_addr = unsafe { store.modules.get(module).func_addrs.get(func_idx) };
-                stack.push_value::<T>(Value::Ref(Ref::Func(*func_addr)))?;
+                stack.try_push(Value::Ref(Ref::Func(*func_addr)))?;
             }
 
             FD_EXTENSIONS => {
@@ -118,7 +114,7 @@ pub(crate) unsafe fn run_const<T: Config>(
                             *byte_ref = wasm.read_u8().unwrap_validated();
                         }
 
-                        stack.push_value::<T>(Value::V128(data))?;
+                        stack.try_push(Value::V128(data))?;
                     }
                     0x00..=0x0B | 0x0D.. => unreachable_validated!(),
                 }
@@ -153,20 +149,9 @@ pub(crate) unsafe fn run_const_span<T: Config>(
 
     wasm.move_start_to(*span).unwrap_validated();
 
-    let mut stack = Stack::new::<T>(
-        Vec::new(),
-        &FuncType {
-            params: ResultType {
-                valtypes: Vec::new(),
-            },
-            returns: ResultType {
-                valtypes: Vec::new(),
-            },
-        },
-        &[],
-    )?;
+    let mut stack = Vec::new();
     // SAFETY: The current caller makes the same safety guarantees.
     unsafe { run_const(&mut wasm, &mut stack, module, store)? };
 
-    Ok(stack.peek_value())
+    Ok(stack.last().copied())
 }
@@ -0,0 +1,379 @@
+//! Fuel costs that are loaded at runtime instead of being compiled into a [`Config`]
+//!
+//! A [`CostTable`] holds a flat cost for every instruction, including the 0xFC and 0xFD extension spaces, and a cost
+//! per element for every instruction that is charged per element. Cost tables can be encoded into a compact binary
+//! format and decoded again, which validates them. [`CostTableConfig`] is a [`ConfigLayer`] around an arbitrary inner
+//! [`Config`], forwards all constants and hooks to it and answers all cost functions from its cost table.
+//!
+//! # Binary format
+//!
+//! All integers are encoded as unsigned LEB128, just like in Wasm bytecode.
+//!
+//! - the magic bytes `WFCT`, followed by the version byte `0x01`
+//! - the default flat cost of all instructions that are not listed explicitly
+//! - the number of entries, followed by the entries themselves. Every entry consists of the opcode of an instruction,
+//!   encoded just like in Wasm bytecode, and its flat cost. Instructions that are charged per element are followed by
+//!   their cost per element.
+//!
+//! Every instruction may be listed at most once and no bytes may follow the last entry.
+
+use core::fmt::Display;
+
+use alloc::vec::Vec;
+
+use crate::{
+    config::{Config, ConfigLayer},
+    core::{
+        reader::types::opcode::{
+            self, fc_extension_opcode_name, fd_extension_opcode_name, opcode_byte_name,
+            FC_EXTENSIONS, FD_EXTENSIONS,
+        },
+        utils::ToUsizeExt,
+    },
+    instructions::is_charged_per_element,
+    profiler::Opcode,
+};
+
+const MAGIC: &[u8; 4] = b"WFCT";
+const VERSION: u8 = 1;
+
+/// The number of instructions in the 0xFC extension space
+const NUM_FC_EXTENSIONS: usize = opcode::fc_extensions::TABLE_FILL as usize + 1;
+/// An upper bound on the number of instructions in the 0xFD extension space
+const NUM_FD_EXTENSIONS: usize = 256;
+
+/// An error that occurred while decoding or modifying a [`CostTable`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CostTableError {
+    /// The encoded cost table does not start with the expected magic bytes
+    InvalidMagic,
+    /// The encoded cost table has a version which is not supported
+    UnsupportedVersion(u8),
+    /// The encoded cost table ended in the middle of a value
+    UnexpectedEof,
+    /// An integer is encoded with more bytes than its type allows or overflows its type
+    MalformedInteger,
+    /// There are bytes left after the last entry
+    TrailingBytes,
+    /// The opcode does not belong to any instruction
+    UnknownInstruction(Opcode),
+    /// The instruction is listed more than once
+    DuplicateEntry(Opcode),
+    /// A cost per element was given for an instruction that is not charged per element
+    NotChargedPerElement(Opcode),
+}
+
+impl Display for CostTableError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CostTableError::InvalidMagic => f.write_str("invalid magic bytes"),
+            CostTableError::UnsupportedVersion(version) => {
+                write!(f, "unsupported cost table version {version}")
+            }
+            CostTableError::UnexpectedEof => f.write_str("unexpected end of cost table"),
+            CostTableError::MalformedInteger => f.write_str("malformed integer"),
+            CostTableError::TrailingBytes => f.write_str("trailing bytes after the last entry"),
+            CostTableError::UnknownInstruction(opcode) => {
+                write!(f, "{opcode} is not an instruction")
+            }
+            CostTableError::DuplicateEntry(opcode) => {
+                write!(f, "{opcode} is listed more than once")
+            }
+            CostTableError::NotChargedPerElement(opcode) => {
+                write!(f, "{opcode} is not charged per element")
+            }
+        }
+    }
+}
+
+/// The fuel costs of all instructions
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CostTable {
+    default_flat_cost: u64,
+    flat_costs: [u64; 256],
+    fc_extension_flat_costs: [u64; NUM_FC_EXTENSIONS],
+    fd_extension_flat_costs: [u64; NUM_FD_EXTENSIONS],
+    memory_grow_cost_per_element: u64,
+    fc_extension_costs_per_element: [u64; NUM_FC_EXTENSIONS],
+}
+
+impl Default for CostTable {
+    /// Returns the cost table matching the default costs of [`Config`]
+    fn default() -> Self {
+        Self::uniform(1)
+    }
+}
+
+impl CostTable {
+    /// Creates a cost table in which every instruction has the flat cost `flat_cost` and no cost per element
+    pub fn uniform(flat_cost: u64) -> Self {
+        Self {
+            default_flat_cost: flat_cost,
+            flat_costs: [flat_cost; 256],
+            fc_extension_flat_costs: [flat_cost; NUM_FC_EXTENSIONS],
+            fd_extension_flat_costs: [flat_cost; NUM_FD_EXTENSIONS],
+            memory_grow_cost_per_element: 0,
+            fc_extension_costs_per_element: [0; NUM_FC_EXTENSIONS],
+        }
+    }
+
+    /// Returns the flat cost of the instruction `opcode`
+    pub fn flat_cost(&self, opcode: Opcode) -> u64 {
+        match opcode {
+            Opcode::Single(byte) => Some(&self.flat_costs[usize::from(byte)]),
+            Opcode::FcExtension(instr) => self.fc_extension_flat_costs.get(instr.into_usize()),
+            Opcode::FdExtension(instr) => self.fd_extension_flat_costs.get(instr.into_usize()),
+        }
+        .copied()
+        .unwrap_or(self.default_flat_cost)
+    }
+
+    /// Returns the cost per element of the instruction `opcode`, which is zero for all instructions that are not
+    /// charged per element
+    pub fn cost_per_element(&self, opcode: Opcode) -> u64 {
+        match opcode {
+            _ if !is_charged_per_element(opcode) => 0,
+            Opcode::Single(_) => self.memory_grow_cost_per_element,
+            Opcode::FcExtension(instr) => self.fc_extension_costs_per_element[instr.into_usize()],
+            Opcode::FdExtension(_) => 0,
+        }
+    }
+
+    /// Sets the flat cost of the instruction `opcode`
+    pub fn set_flat_cost(&mut self, opcode: Opcode, cost: u64) -> Result<(), CostTableError> {
+        if !is_instruction(opcode) {
+            return Err(CostTableError::UnknownInstruction(opcode));
+        }
+        let entry = match opcode {
+            Opcode::Single(byte) => &mut self.flat_costs[usize::from(byte)],
+            Opcode::FcExtension(instr) => &mut self.fc_extension_flat_costs[instr.into_usize()],
+            Opcode::FdExtension(instr) => &mut self.fd_extension_flat_costs[instr.into_usize()],
+        };
+        *entry = cost;
+        Ok(())
+    }
+
+    /// Sets the cost per element of the instruction `opcode`, which must be charged per element
+    pub fn set_cost_per_element(
+        &mut self,
+        opcode: Opcode,
+        cost: u64,
+    ) -> Result<(), CostTableError> {
+        if !is_charged_per_element(opcode) {
+            return Err(CostTableError::NotChargedPerElement(opcode));
+        }
+        let entry = match opcode {
+            Opcode::FcExtension(instr) => {
+                &mut self.fc_extension_costs_per_element[instr.into_usize()]
+            }
+            _ => &mut self.memory_grow_cost_per_element,
+        };
+        *entry = cost;
+        Ok(())
+    }
+
+    /// Encodes this cost table into its binary format, listing only the instructions whose costs differ from the
+    /// default flat cost
+    pub fn encode(&self) -> Vec<u8> {
+        let entries: Vec<Opcode> = all_instructions()
+            .filter(|opcode| {
+                self.flat_cost(*opcode) != self.default_flat_cost
+                    || self.cost_per_element(*opcode) != 0
+            })
+            .collect();
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        encode_leb128(&mut bytes, self.default_flat_cost);
+        encode_leb128(&mut bytes, entries.len() as u64);
+        for opcode in entries {
+            match opcode {
+                Opcode::Single(byte) => bytes.push(byte),
+                Opcode::FcExtension(instr) => {
+                    bytes.push(FC_EXTENSIONS);
+                    encode_leb128(&mut bytes, instr.into());
+                }
+                Opcode::FdExtension(instr) => {
+                    bytes.push(FD_EXTENSIONS);
+                    encode_leb128(&mut bytes, instr.into());
+                }
+            }
+            encode_leb128(&mut bytes, self.flat_cost(opcode));
+            if is_charged_per_element(opcode) {
+                encode_leb128(&mut bytes, self.cost_per_element(opcode));
+            }
+        }
+        bytes
+    }
+
+    /// Decodes and validates a cost table from its binary format
+    pub fn decode(bytes: &[u8]) -> Result<Self, CostTableError> {
+        let mut decoder = Decoder { bytes };
+        if decoder.take::<4>()? != *MAGIC {
+            return Err(CostTableError::InvalidMagic);
+        }
+        let version = decoder.u8()?;
+        if version != VERSION {
+            return Err(CostTableError::UnsupportedVersion(version));
+        }
+
+        let mut table = Self::uniform(decoder.u64()?);
+        let mut listed = Vec::new();
+        let num_entries = decoder.u32()?;
+        for _ in 0..num_entries {
+            let opcode = match decoder.u8()? {
+                FC_EXTENSIONS => Opcode::FcExtension(decoder.u32()?),
+                FD_EXTENSIONS => Opcode::FdExtension(decoder.u32()?),
+                byte => Opcode::Single(byte),
+            };
+            if listed.contains(&opcode) {
+                return Err(CostTableError::DuplicateEntry(opcode));
+            }
+            listed.push(opcode);
+
+            table.set_flat_cost(opcode, decoder.u64()?)?;
+            if is_charged_per_element(opcode) {
+                table.set_cost_per_element(opcode, decoder.u64()?)?;
+            }
+        }
+
+        if !decoder.bytes.is_empty() {
+            return Err(CostTableError::TrailingBytes);
+        }
+        Ok(table)
+    }
+}
+
+/// Checks whether `opcode` belongs to an instruction, i.e. whether it is listed in the opcode tables
+fn is_instruction(opcode: Opcode) -> bool {
+    match opcode {
+        Opcode::Single(FC_EXTENSIONS | FD_EXTENSIONS) => false,
+        Opcode::Single(byte) => opcode_byte_name(byte).is_some(),
+        Opcode::FcExtension(instr) => fc_extension_opcode_name(instr).is_some(),
+        Opcode::FdExtension(instr) => fd_extension_opcode_name(instr).is_some(),
+    }
+}
+
+/// Returns all instructions in ascending order of their opcodes
+fn all_instructions() -> impl Iterator<Item = Opcode> {
+    let singles = (0..=u8::MAX).map(Opcode::Single);
+    let fc_extensions = (0..NUM_FC_EXTENSIONS as u32).map(Opcode::FcExtension);
+    let fd_extensions = (0..NUM_FD_EXTENSIONS as u32).map(Opcode::FdExtension);
+    singles
+        .chain(fc_extensions)
+        .chain(fd_extensions)
+        .filter(|opcode| is_instruction(*opcode))
+}
+
+fn encode_leb128(bytes: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(byte);
+            return;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl Decoder<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], CostTableError> {
+        let (taken, rest) = self
+            .bytes
+            .split_first_chunk::<N>()
+            .ok_or(CostTableError::UnexpectedEof)?;
+        self.bytes = rest;
+        Ok(*taken)
+    }
+
+    fn u8(&mut self) -> Result<u8, CostTableError> {
+        let [byte] = self.take::<1>()?;
+        Ok(byte)
+    }
+
+    fn u64(&mut self) -> Result<u64, CostTableError> {
+        let mut value: u64 = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.u8()?;
+            let bits = u64::from(byte & 0x7F);
+            if bits << shift >> shift != bits {
+                return Err(CostTableError::MalformedInteger);
+            }
+            value |= bits << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(CostTableError::MalformedInteger)
+    }
+
+    fn u32(&mut self) -> Result<u32, CostTableError> {
+        u32::try_from(self.u64()?).map_err(|_| CostTableError::MalformedInteger)
+    }
+}
+
+/// A [`Config`] that takes all fuel costs from a [`CostTable`]
+///
+/// The cost table cannot be changed after creation, because stores cache the costs of instructions when modules are
+/// instantiated.
+#[derive(Debug, Default)]
+pub struct CostTableConfig<C: Config = ()> {
+    cost_table: CostTable,
+    /// The wrapped configuration
+    pub inner: C,
+}
+
+impl<C: Config> CostTableConfig<C> {
+    /// Creates a new configuration with the costs of `cost_table`, wrapping the given configuration
+    pub fn new(inner: C, cost_table: CostTable) -> Self {
+        Self { cost_table, inner }
+    }
+
+    /// Returns the cost table all costs are taken from
+    pub fn cost_table(&self) -> &CostTable {
+        &self.cost_table
+    }
+}
+
+impl<C: Config> ConfigLayer for CostTableConfig<C> {
+    type Inner = C;
+
+    fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    fn inner_mut(&mut self) -> &mut C {
+        &mut self.inner
+    }
+
+    #[inline(always)]
+    fn get_flat_cost(&self, instr: u8) -> u64 {
+        self.cost_table.flat_cost(Opcode::Single(instr))
+    }
+
+    #[inline(always)]
+    fn get_fc_extension_flat_cost(&self, instr: u32) -> u64 {
+        self.cost_table.flat_cost(Opcode::FcExtension(instr))
+    }
+
+    #[inline(always)]
+    fn get_fd_extension_flat_cost(&self, instr: u32) -> u64 {
+        self.cost_table.flat_cost(Opcode::FdExtension(instr))
+    }
+
+    #[inline(always)]
+    fn get_cost_per_element(&self, instr: u8) -> u64 {
+        self.cost_table.cost_per_element(Opcode::Single(instr))
+    }
+
+    #[inline(always)]
+    fn get_fc_extension_cost_per_element(&self, instr: u32) -> u64 {
+        self.cost_table.cost_per_element(Opcode::FcExtension(instr))
+    }
+}
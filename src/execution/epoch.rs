@@ -0,0 +1,119 @@
+//! Epoch-based interruption, implemented on top of [`Config::interruption_hook`]
+//!
+//! An [`Epoch`] is a counter that the host advances asynchronously, e.g. from a timer interrupt. [`EpochDeadline`] is a
+//! [`ConfigLayer`] around an arbitrary inner [`Config`], forwards all constants, cost functions and hooks to it and
+//! interrupts execution at the next loop back-edge or call once the epoch has reached a deadline. Execution can then be
+//! continued through the [`RunState::Interrupted`](crate::resumable::RunState::Interrupted) returned by the interpreter
+//! with [`InterruptReason::InterruptionHook`](crate::resumable::InterruptReason::InterruptionHook). Every deadline
+//! interrupts execution only once, so the host sets the next deadline before continuing if it wants to be interrupted
+//! again. In contrast to fuel, this bounds the latency of interruptions without the overhead of metering every
+//! instruction.
+
+use core::{
+    ops::ControlFlow,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use crate::config::{Config, ConfigLayer};
+
+/// A counter of elapsed time periods, which may be shared between the interpreter and asynchronous contexts
+///
+/// The counter wraps around on overflow. Deadlines are compared with wrapping arithmetic, so they work correctly as
+/// long as they lie less than `2^31` increments in the future.
+#[derive(Debug, Default)]
+pub struct Epoch(AtomicU32);
+
+impl Epoch {
+    /// Creates a new epoch counter starting at zero. This is a `const fn`, so that epochs can be placed in statics.
+    pub const fn new() -> Self {
+        Self(AtomicU32::new(0))
+    }
+
+    /// Returns the current value of this epoch counter
+    pub fn current(&self) -> u32 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Advances this epoch counter by one
+    ///
+    /// This only requires atomic loads and stores, which are available on all targets, but it must not be called
+    /// concurrently from multiple contexts. Usually there is a single context that advances the epoch, such as a timer
+    /// interrupt service routine.
+    pub fn increment(&self) {
+        self.0
+            .store(self.current().wrapping_add(1), Ordering::Relaxed);
+    }
+}
+
+/// A [`Config`] that interrupts execution once an [`Epoch`] has reached a deadline
+///
+/// Initially no deadline is set. Once the deadline has been reached, the next loop back-edge or call interrupts
+/// execution and clears the deadline, so that continuing execution does not interrupt it again right away.
+#[derive(Debug)]
+pub struct EpochDeadline<'a, C: Config = ()> {
+    epoch: &'a Epoch,
+    deadline: Option<u32>,
+    /// The wrapped configuration
+    pub inner: C,
+}
+
+impl<'a, C: Config> EpochDeadline<'a, C> {
+    /// Creates a new configuration observing `epoch` without a deadline, wrapping the given configuration
+    pub fn new(inner: C, epoch: &'a Epoch) -> Self {
+        Self {
+            epoch,
+            deadline: None,
+            inner,
+        }
+    }
+
+    /// Returns the epoch counter this configuration observes
+    pub fn epoch(&self) -> &'a Epoch {
+        self.epoch
+    }
+
+    /// Sets the deadline to `ticks` increments of the epoch after its current value
+    pub fn set_deadline(&mut self, ticks: u32) {
+        self.deadline = Some(self.epoch.current().wrapping_add(ticks));
+    }
+
+    /// Removes the deadline, so that execution is not interrupted anymore
+    pub fn clear_deadline(&mut self) {
+        self.deadline = None;
+    }
+
+    /// Returns the value of the epoch at which execution is interrupted, if a deadline is set
+    pub fn deadline(&self) -> Option<u32> {
+        self.deadline
+    }
+
+    /// Checks whether the epoch has reached the deadline
+    pub fn deadline_reached(&self) -> bool {
+        self.deadline.is_some_and(|deadline| {
+            // the epoch is past the deadline if it is less than half of the value range ahead of it
+            self.epoch.current().wrapping_sub(deadline) < 1 << 31
+        })
+    }
+}
+
+impl<C: Config> ConfigLayer for EpochDeadline<'_, C> {
+    type Inner = C;
+
+    fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    fn inner_mut(&mut self) -> &mut C {
+        &mut self.inner
+    }
+
+    fn interruption_hook(&mut self) -> ControlFlow<()> {
+        let inner_flow = self.inner.interruption_hook();
+        if self.deadline_reached() {
+            self.deadline = None;
+            ControlFlow::Break(())
+        } else {
+            inner_flow
+        }
+    }
+}
@@ -38,6 +38,21 @@ pub enum RuntimeError {
     /// A host function was called from a start function during module
     /// instantiation.
     UnsupportedHostCallDuringInstantiation,
+    /// An allocation failed because the memory of the host is exhausted. The
+    /// operation was aborted, but the [`Store`](crate::Store) remains usable.
+    OutOfMemory,
+    /// A [`MemoryBacking`](crate::memory_backing::MemoryBacking) is too small for the initial pages of the memory it
+    /// was provided for.
+    MemoryBackingTooSmall,
+    /// The resource limiter of the [`Config`](crate::config::Config) denied the allocation or growth of a memory or
+    /// table with [`GrowthDecision::Deny`](crate::config::GrowthDecision::Deny).
+    GrowthDenied,
+    /// A [`StateSnapshot`](crate::StateSnapshot) does not fit the store it is restored into, see
+    /// [`Store::restore_state_snapshot`](crate::Store::restore_state_snapshot).
+    StateSnapshotMismatch,
+    /// A memory region passed to [`Store::mem_protect`](crate::Store::mem_protect) or
+    /// [`Store::mem_map_mmio`](crate::Store::mem_map_mmio) is empty, reversed or does not lie within the memory.
+    InvalidMemoryRegion,
 }
 
 impl Display for RuntimeError {
@@ -88,6 +103,19 @@ impl Display for RuntimeError {
             }
             RuntimeError::UnexpectedHostCall => f.write_str(" The Store::invoke_simple method was used to execute some Wasm code but this resulted a host call, which is not supported in this mode"),
             RuntimeError::UnsupportedHostCallDuringInstantiation => f.write_str("A host function was called from a start function during module instantiation"),
+            RuntimeError::OutOfMemory => f.write_str("An allocation failed because the memory of the host is exhausted"),
+            RuntimeError::MemoryBackingTooSmall => {
+                f.write_str("A memory backing is too small for the initial pages of its memory")
+            }
+            RuntimeError::GrowthDenied => f.write_str(
+                "The resource limiter denied the allocation or growth of a memory or table",
+            ),
+            RuntimeError::StateSnapshotMismatch => {
+                f.write_str("A state snapshot does not fit the store it is restored into")
+            }
+            RuntimeError::InvalidMemoryRegion => {
+                f.write_str("A memory region is empty, reversed or does not lie within its memory")
+            }
         }
     }
 }
@@ -116,6 +144,15 @@ pub enum TrapError {
     IndirectCallNullFuncRef,
     TableAccessOutOfBounds,
     ReachedUnreachable,
+    /// The resource limiter of the [`Config`](crate::config::Config) rejected the growth of a memory or table with
+    /// [`GrowthDecision::Trap`](crate::config::GrowthDecision::Trap).
+    ResourceLimitExceeded,
+    /// An access violated the protection of a memory range, i.e. it wrote to a read-only range or accessed a no-access
+    /// range. See [`Store::mem_protect`](crate::Store::mem_protect).
+    MemoryProtectionViolation,
+    /// A bulk memory instruction, i.e. `memory.copy`, `memory.fill` or `memory.init`, accessed a memory-mapped I/O
+    /// range. See [`Store::mem_map_mmio`](crate::Store::mem_map_mmio).
+    MmioBulkAccess,
 }
 
 impl Display for TrapError {
@@ -139,6 +176,15 @@ impl Display for TrapError {
                 f.write_str("Indirect call: table index out of bounds")
             }
             TrapError::ReachedUnreachable => f.write_str("An unreachable statement was reached"),
+            TrapError::ResourceLimitExceeded => {
+                f.write_str("Growth of a memory or table exceeded a resource limit")
+            }
+            TrapError::MemoryProtectionViolation => {
+                f.write_str("A memory access violated the protection of a memory range")
+            }
+            TrapError::MmioBulkAccess => {
+                f.write_str("A bulk memory instruction accessed a memory-mapped I/O range")
+            }
         }
     }
 }
@@ -0,0 +1,79 @@
+//! Helpers for allocating without aborting when the memory of the host is exhausted.
+//!
+//! Allocations of instances, value stacks, call stacks, tables and linear memories go through these helpers, so that
+//! allocation failures surface as [`RuntimeError::OutOfMemory`] instead of aborting the whole program. Small
+//! allocations of module metadata, such as the export names of a module instance, still use the infallible APIs of
+//! [`alloc`], because collections like [`BTreeMap`](alloc::collections::BTreeMap) do not offer fallible alternatives.
+
+use alloc::vec::Vec;
+
+use crate::RuntimeError;
+
+pub(crate) trait TryVecExt<T> {
+    /// Appends an element, returning [`RuntimeError::OutOfMemory`] if the vector cannot grow
+    fn try_push(&mut self, value: T) -> Result<(), RuntimeError>;
+
+    /// Resizes the vector to `new_len` elements, filling new slots with values returned by `f`. Returns
+    /// [`RuntimeError::OutOfMemory`] if the vector cannot grow, in which case it is left unchanged.
+    fn try_resize_with(&mut self, new_len: usize, f: impl FnMut() -> T)
+        -> Result<(), RuntimeError>;
+}
+
+impl<T> TryVecExt<T> for Vec<T> {
+    #[inline(always)]
+    fn try_push(&mut self, value: T) -> Result<(), RuntimeError> {
+        self.try_reserve(1).map_err(|_| RuntimeError::OutOfMemory)?;
+        self.push(value);
+        Ok(())
+    }
+
+    fn try_resize_with(
+        &mut self,
+        new_len: usize,
+        f: impl FnMut() -> T,
+    ) -> Result<(), RuntimeError> {
+        self.try_reserve_exact(new_len.saturating_sub(self.len()))
+            .map_err(|_| RuntimeError::OutOfMemory)?;
+        // the capacity was reserved above, so this does not allocate
+        self.resize_with(new_len, f);
+        Ok(())
+    }
+}
+
+pub(crate) trait TryCollectExt: Iterator + Sized {
+    /// Collects all elements into a new vector, returning [`RuntimeError::OutOfMemory`] if it cannot be allocated
+    fn try_collect_vec(self) -> Result<Vec<Self::Item>, RuntimeError> {
+        let mut vec = Vec::new();
+        vec.try_reserve(self.size_hint().0)
+            .map_err(|_| RuntimeError::OutOfMemory)?;
+        for item in self {
+            vec.try_push(item)?;
+        }
+        Ok(vec)
+    }
+}
+
+impl<I: Iterator> TryCollectExt for I {}
+
+pub(crate) trait TryCollectResultsExt<T>:
+    Iterator<Item = Result<T, RuntimeError>> + Sized
+{
+    /// Collects all elements into a new vector, stopping at the first error. Returns [`RuntimeError::OutOfMemory`] if
+    /// the vector cannot be allocated.
+    fn try_collect_results(self) -> Result<Vec<T>, RuntimeError> {
+        let mut vec = Vec::new();
+        vec.try_reserve(self.size_hint().0)
+            .map_err(|_| RuntimeError::OutOfMemory)?;
+        for item in self {
+            vec.try_push(item?)?;
+        }
+        Ok(vec)
+    }
+}
+
+impl<T, I: Iterator<Item = Result<T, RuntimeError>>> TryCollectResultsExt<T> for I {}
+
+/// Clones a slice into a new vector, returning [`RuntimeError::OutOfMemory`] if it cannot be allocated
+pub(crate) fn try_to_vec<T: Clone>(slice: &[T]) -> Result<Vec<T>, RuntimeError> {
+    slice.iter().cloned().try_collect_vec()
+}
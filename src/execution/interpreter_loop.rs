@@ -11,7 +11,7 @@ use core::{
     num::NonZeroU64,
     {
         array,
-        ops::{Add, Div, Mul, Neg, Sub},
+        ops::{Add, ControlFlow, Div, Mul, Neg, Sub},
     },
 };
 
@@ -19,6 +19,7 @@ use crate::{
     addrs::{AddrVec, DataAddr, ElemAddr, FuncAddr, MemAddr, ModuleAddr, TableAddr},
     assert_validated::UnwrapValidatedExt,
     core::{
+        immediates::ImmediatesCache,
         indices::{
             read_label_idx_unchecked, DataIdx, ElemIdx, FuncIdx, GlobalIdx, Idx, LocalIdx, MemIdx,
             TableIdx, TypeIdx,
@@ -27,21 +28,23 @@ use crate::{
             types::{memarg::MemArg, BlockType},
             WasmReader,
         },
-        sidetable::Sidetable,
+        sidetable::{BlockFuelTable, Sidetable},
         utils::ToUsizeExt,
+        v128_operands::V128Operands,
     },
     execution::store::Hostcode,
     instances::{DataInst, ElemInst, FuncInst, MemInst, ModuleInst, TableInst},
-    resumable::WasmResumable,
+    resumable::{InterruptReason, WasmResumable},
     unreachable_validated,
     value::{self, Ref, F32, F64},
-    value_stack::Stack,
+    value_stack::{FrameLayout, Stack},
     RefType, RuntimeError, TrapError, ValType, Value,
 };
 
-use crate::execution::config::Config;
+use crate::execution::config::{Config, GrowthDecision, ResourceUsage};
 
 use super::{little_endian::LittleEndianBytes, store::Store};
+use crate::basic_blocks::unused_block_fuel;
 
 /// A non-error outcome of execution of the interpreter loop
 pub enum InterpreterLoopOutcome {
@@ -56,10 +59,13 @@ pub enum InterpreterLoopOutcome {
         /// instruction.
         required_fuel: NonZeroU64,
     },
+    /// Execution was interrupted after an instruction completed, because one
+    /// of the hooks of the [`Config`] returned [`ControlFlow::Break`].
+    Interrupted(InterruptReason),
     HostCalled {
         func_addr: FuncAddr,
-        // TODO this allocation might be preventable. mutably borrow the stack
-        // instead
+        /// The parameters of the host call. For stacks with a fixed capacity, this is the host call buffer of their
+        /// [`StackStorage`](crate::value_stack::StackStorage), so that no allocation is required.
         params: Vec<Value>,
         hostcode: Hostcode,
     },
@@ -73,9 +79,66 @@ pub enum InterpreterLoopOutcome {
 /// # Safety
 ///
 /// The given resumable must be valid in the given [`Store`].
-pub(super) fn run<T: Config>(
+pub(super) fn run<'b, T: Config>(
     resumable: &mut WasmResumable,
-    store: &mut Store<T>,
+    store: &mut Store<'b, T>,
+) -> Result<InterpreterLoopOutcome, RuntimeError> {
+    let mut wasm = WasmReader::new(&[]);
+    let mut prepaid_block = PrepaidBlock::default();
+    let result = run_loop(resumable, store, &mut wasm, &mut prepaid_block);
+    if result.is_err() {
+        // the remaining instructions of the current basic block are never executed, because execution trapped
+        if let Some(fuel) = &mut resumable.maybe_fuel {
+            *fuel += prepaid_block.take_unused_fuel(&store.user_data, &wasm);
+        }
+    }
+    result
+}
+
+/// The basic block whose fuel was charged at once when execution entered it
+///
+/// The instructions of the basic block after its first one are not charged individually. Execution can only enter a
+/// basic block at its first instruction, and all other branch targets and functions start basic blocks of their own.
+/// Therefore, an instruction is covered by the current basic block exactly if it lies after the first instruction of
+/// the basic block and before its end, as long as the code of the same module is executed.
+#[derive(Debug, Default)]
+struct PrepaidBlock {
+    pc: usize,
+    end: usize,
+}
+
+impl PrepaidBlock {
+    /// Returns whether the instruction at `pc` was paid for when the basic block was entered
+    #[inline(always)]
+    fn covers(&self, pc: usize) -> bool {
+        self.pc < pc && pc < self.end
+    }
+
+    /// Returns the fuel charged for the instructions of the basic block that follow the instruction `wasm` stopped in
+    /// or after, because these are never executed. Afterwards, no basic block is prepaid anymore.
+    fn take_unused_fuel<C: Config>(&mut self, config: &C, wasm: &WasmReader) -> u64 {
+        let block = core::mem::take(self);
+        if block.pc < wasm.pc && wasm.pc <= block.end {
+            unused_block_fuel(config, wasm.full_wasm_binary, block.pc, block.end, wasm.pc)
+        } else {
+            0
+        }
+    }
+}
+
+/// Returns the fuel of the basic blocks of a module instance, which is empty unless fuel-metered execution started
+fn block_fuel_of<'a>(module: &'a ModuleInst) -> &'a BlockFuelTable {
+    static EMPTY: BlockFuelTable = BlockFuelTable::EMPTY;
+    module.block_fuel.as_deref().unwrap_or(&EMPTY)
+}
+
+/// The interpreter loop of [`run`], which keeps the basic block whose fuel was paid in advance in `prepaid_block` and
+/// reads the code through `wasm`, so that the fuel of the remaining instructions can be refunded if execution traps.
+fn run_loop<'b, T: Config>(
+    resumable: &mut WasmResumable,
+    store: &mut Store<'b, T>,
+    wasm: &mut WasmReader<'b>,
+    prepaid_block: &mut PrepaidBlock,
 ) -> Result<InterpreterLoopOutcome, RuntimeError> {
     let stack = &mut resumable.stack;
     let mut current_func_addr = resumable.current_func_addr;
@@ -95,9 +158,13 @@ pub(super) fn run<T: Config>(
     // SAFETY: This module address was just read from the current store. Every
     // store guarantees all addresses contained in it to be valid within itself.
     let module = unsafe { store.modules.get(current_module) };
-    let wasm = &mut WasmReader::new(module.wasm_bytecode);
+    *wasm = WasmReader::new(module.wasm_bytecode);
 
-    let mut current_sidetable: &Sidetable = &module.sidetable;
+    let mut current_sidetable: &Sidetable = &module.shared.sidetable;
+    let mut current_block_fuel: &BlockFuelTable = block_fuel_of(module);
+    let mut current_immediates: &ImmediatesCache = &module.shared.immediates;
+    let mut current_v128_operands: &V128Operands = &module.shared.v128_operands;
+    let mut current_frame_layout: &FrameLayout = &wasm_func_inst.local_func.frame_layout;
 
     // local variable for holding where the function code ends (last END instr address + 1) to avoid lookup at every END instr
     let mut current_function_end_marker =
@@ -105,12 +172,32 @@ pub(super) fn run<T: Config>(
 
     wasm.pc = pc;
 
+    store
+        .user_data
+        .resume_hook(current_func_addr, !resumable.started);
+    resumable.started = true;
+
+    // pay for the last host call before continuing, if this was not possible when it finished
+    if resumable.pending_fuel > 0 {
+        if let Some(fuel) = &mut resumable.maybe_fuel {
+            if *fuel >= resumable.pending_fuel {
+                *fuel -= resumable.pending_fuel;
+                store.user_data.fuel_hook(resumable.pending_fuel);
+            } else {
+                return Ok(InterpreterLoopOutcome::OutOfFuel {
+                    required_fuel: NonZeroU64::new(resumable.pending_fuel - *fuel).expect("the last check guarantees that the current fuel is smaller than the pending fuel"),
+                });
+            }
+        }
+        resumable.pending_fuel = 0;
+    }
+
     use crate::core::reader::types::opcode::*;
     loop {
         // call the instruction hook
         store
             .user_data
-            .instruction_hook(module.wasm_bytecode, wasm.pc);
+            .instruction_hook(wasm.full_wasm_binary, wasm.pc);
 
         // convenience macro for fuel metering. records the interpreter state within resumable and returns with
         // Ok(required_fuel) if the fuel to execute the instruction is not enough
@@ -118,20 +205,54 @@ pub(super) fn run<T: Config>(
         macro_rules! decrement_fuel {
             ($cost:expr) => {
                 if let Some(fuel) = &mut resumable.maybe_fuel {
-                    if *fuel >= $cost {
-                        *fuel -= $cost;
-                    } else {
-                        resumable.current_func_addr = current_func_addr;
-                        resumable.pc = prev_pc; // the instruction was fetched already, we roll this back
-                        resumable.stp = stp;
-                        return Ok(InterpreterLoopOutcome::OutOfFuel {
-                            required_fuel: NonZeroU64::new($cost - *fuel).expect("the last check guarantees that the current fuel is smaller than cost"),
-                        });
+                    // the instructions of a basic block after its first one were paid for when it was entered
+                    if !prepaid_block.covers(prev_pc) {
+                        if let Some(block) = current_block_fuel.get(prev_pc).filter(|block| *fuel >= block.fuel) {
+                            // this instruction starts a basic block, so all of its instructions are paid for at once
+                            *fuel -= block.fuel;
+                            store.user_data.fuel_hook(block.fuel);
+                            *prepaid_block = PrepaidBlock { pc: prev_pc, end: block.end };
+                        } else {
+                            *prepaid_block = PrepaidBlock::default();
+                            let cost = $cost;
+                            if *fuel >= cost {
+                                *fuel -= cost;
+                                store.user_data.fuel_hook(cost);
+                            } else {
+                                resumable.current_func_addr = current_func_addr;
+                                resumable.pc = prev_pc; // the instruction was fetched already, we roll this back
+                                resumable.stp = stp;
+                                return Ok(InterpreterLoopOutcome::OutOfFuel {
+                                    required_fuel: NonZeroU64::new(cost - *fuel).expect("the last check guarantees that the current fuel is smaller than cost"),
+                                });
+                            }
+                        }
                     }
                 }
             }
         }
 
+        // convenience macro for access hooks. records the interpreter state within resumable and returns with
+        // Ok(Interrupted) if the hook requested to break. this must only be used after the current instruction was
+        // executed completely, as execution resumes at the next instruction.
+        macro_rules! interrupt_on_break {
+            ($control_flow:expr) => {
+                interrupt_on_break!($control_flow, InterruptReason::AccessHook)
+            };
+            ($control_flow:expr, $reason:expr) => {
+                if let ControlFlow::Break(()) = $control_flow {
+                    // the remaining instructions of the current basic block are not executed yet
+                    if let Some(fuel) = &mut resumable.maybe_fuel {
+                        *fuel += prepaid_block.take_unused_fuel(&store.user_data, wasm);
+                    }
+                    resumable.current_func_addr = current_func_addr;
+                    resumable.pc = wasm.pc;
+                    resumable.stp = stp;
+                    return Ok(InterpreterLoopOutcome::Interrupted($reason));
+                }
+            };
+        }
+
         let first_instr_byte = wasm.read_u8().unwrap_validated();
 
         #[cfg(debug_assertions)]
@@ -142,7 +263,7 @@ pub(super) fn run<T: Config>(
 
         match first_instr_byte {
             NOP => {
-                decrement_fuel!(T::get_flat_cost(NOP));
+                decrement_fuel!(store.user_data.get_flat_cost(NOP));
                 trace!("Instruction: NOP");
             }
             END => {
@@ -152,10 +273,13 @@ pub(super) fn run<T: Config>(
                     continue;
                 }
 
+                store.user_data.return_hook(current_func_addr);
+
                 let Some((maybe_return_func_addr, maybe_return_address, maybe_return_stp)) =
                     stack.pop_call_frame()
                 else {
                     // We finished this entire invocation if this was the base call frame.
+                    resumable.current_func_addr = current_func_addr;
                     break;
                 };
                 // If there are one or more call frames, we need to continue
@@ -186,7 +310,12 @@ pub(super) fn run<T: Config>(
                 wasm.pc = maybe_return_address;
                 stp = maybe_return_stp;
 
-                current_sidetable = &module.sidetable;
+                current_sidetable = &module.shared.sidetable;
+                current_block_fuel = block_fuel_of(module);
+                *prepaid_block = PrepaidBlock::default();
+                current_immediates = &module.shared.immediates;
+                current_v128_operands = &module.shared.v128_operands;
+                current_frame_layout = &current_wasm_func_inst.local_func.frame_layout;
 
                 current_function_end_marker = current_wasm_func_inst.code_expr.from()
                     + current_wasm_func_inst.code_expr.len();
@@ -194,12 +323,12 @@ pub(super) fn run<T: Config>(
                 trace!("Instruction: END");
             }
             IF => {
-                decrement_fuel!(T::get_flat_cost(IF));
+                decrement_fuel!(store.user_data.get_flat_cost(IF));
                 // SAFETY: Validation guarantees there to be a valid block type
                 // next.
                 let _block_type = unsafe { BlockType::read_unchecked(wasm) };
 
-                let test_val: i32 = stack.pop_value().try_into().unwrap_validated();
+                let test_val: i32 = stack.pop();
 
                 if test_val != 0 {
                     stp += 1;
@@ -209,80 +338,96 @@ pub(super) fn run<T: Config>(
                 trace!("Instruction: IF");
             }
             ELSE => {
-                decrement_fuel!(T::get_flat_cost(ELSE));
+                decrement_fuel!(store.user_data.get_flat_cost(ELSE));
                 do_sidetable_control_transfer(wasm, stack, &mut stp, current_sidetable)?;
             }
             BR_IF => {
-                decrement_fuel!(T::get_flat_cost(BR_IF));
+                decrement_fuel!(store.user_data.get_flat_cost(BR_IF));
 
                 // SAFETY: Validation guarantees there to be a valid label index
                 // next.
                 let _label_idx = unsafe { read_label_idx_unchecked(wasm) };
 
-                let test_val: i32 = stack.pop_value().try_into().unwrap_validated();
+                let test_val: i32 = stack.pop();
 
                 if test_val != 0 {
                     do_sidetable_control_transfer(wasm, stack, &mut stp, current_sidetable)?;
+                    if wasm.pc <= prev_pc {
+                        interrupt_on_break!(
+                            store.user_data.interruption_hook(),
+                            InterruptReason::InterruptionHook
+                        );
+                    }
                 } else {
                     stp += 1;
                 }
                 trace!("Instruction: BR_IF");
             }
             BR_TABLE => {
-                decrement_fuel!(T::get_flat_cost(BR_TABLE));
-                let label_vec = wasm
-                    .read_vec(|wasm| {
-                        // SAFETY: Validation guarantees that there is a
-                        // valid vec of label indices.
-                        Ok(unsafe { read_label_idx_unchecked(wasm) })
-                    })
-                    .unwrap_validated();
-
-                // SAFETY: Validation guarantees there to be another label index
-                // for the default case.
-                let _default_label_idx = unsafe { read_label_idx_unchecked(wasm) };
+                decrement_fuel!(store.user_data.get_flat_cost(BR_TABLE));
+                // the branch targets are taken from the sidetable, so the label indices are only skipped without
+                // collecting them
+                let label_count = wasm.read_var_u32().unwrap_validated().into_usize();
+                // the default label index follows the vec of label indices
+                for _ in 0..=label_count {
+                    // SAFETY: Validation guarantees that there is a valid vec
+                    // of label indices and another label index for the
+                    // default case.
+                    let _label_idx = unsafe { read_label_idx_unchecked(wasm) };
+                }
 
                 // TODO is this correct?
-                let case_val_i32: i32 = stack.pop_value().try_into().unwrap_validated();
+                let case_val_i32: i32 = stack.pop();
                 let case_val = case_val_i32.cast_unsigned().into_usize();
 
-                if case_val >= label_vec.len() {
-                    stp += label_vec.len();
-                } else {
-                    stp += case_val;
-                }
+                stp += case_val.min(label_count);
 
                 do_sidetable_control_transfer(wasm, stack, &mut stp, current_sidetable)?;
+                if wasm.pc <= prev_pc {
+                    interrupt_on_break!(
+                        store.user_data.interruption_hook(),
+                        InterruptReason::InterruptionHook
+                    );
+                }
             }
             BR => {
-                decrement_fuel!(T::get_flat_cost(BR));
+                decrement_fuel!(store.user_data.get_flat_cost(BR));
                 // SAFETY: Validation guarantees there to be a valid label index
                 // next.
                 let _label_idx = unsafe { read_label_idx_unchecked(wasm) };
                 do_sidetable_control_transfer(wasm, stack, &mut stp, current_sidetable)?;
+                if wasm.pc <= prev_pc {
+                    interrupt_on_break!(
+                        store.user_data.interruption_hook(),
+                        InterruptReason::InterruptionHook
+                    );
+                }
             }
             BLOCK => {
-                decrement_fuel!(T::get_flat_cost(BLOCK));
+                decrement_fuel!(store.user_data.get_flat_cost(BLOCK));
                 // SAFETY: Validation guarantess there to be a valid block type
                 // next.
                 let _ = unsafe { BlockType::read_unchecked(wasm) };
             }
             LOOP => {
-                decrement_fuel!(T::get_flat_cost(LOOP));
+                decrement_fuel!(store.user_data.get_flat_cost(LOOP));
                 // SAFETY: Validation guarantees there to be a valid block type
                 // next.
                 let _ = unsafe { BlockType::read_unchecked(wasm) };
             }
             RETURN => {
-                decrement_fuel!(T::get_flat_cost(RETURN));
+                decrement_fuel!(store.user_data.get_flat_cost(RETURN));
                 // same as BR
                 do_sidetable_control_transfer(wasm, stack, &mut stp, current_sidetable)?;
             }
             CALL => {
-                decrement_fuel!(T::get_flat_cost(CALL));
-                // SAFETY: Validation guarantees there to be a valid function
-                // index next.
-                let func_idx = unsafe { FuncIdx::read_unchecked(wasm) };
+                decrement_fuel!(store.user_data.get_flat_cost(CALL));
+                let func_idx = match current_immediates.take(wasm) {
+                    Some(func_idx) => FuncIdx::new(func_idx),
+                    // SAFETY: Validation guarantees there to be a valid
+                    // function index next.
+                    None => unsafe { FuncIdx::read_unchecked(wasm) },
+                };
 
                 // SAFETY: The current function address must come from the given
                 // resumable or the current store, because these are the only
@@ -312,14 +457,13 @@ pub(super) fn run<T: Config>(
                 let func_to_call_inst = unsafe { store.functions.get(*func_to_call_addr) };
 
                 trace!("Instruction: call [{func_to_call_addr:?}]");
+                store.user_data.call_hook(*func_to_call_addr);
 
                 match func_to_call_inst {
                     FuncInst::HostFunc(host_func_to_call_inst) => {
-                        let params = stack
-                            .pop_tail_iter(
-                                host_func_to_call_inst.function_type.params.valtypes.len(),
-                            )
-                            .collect();
+                        let params = stack.pop_host_call_params(
+                            &host_func_to_call_inst.function_type.params.valtypes,
+                        )?;
 
                         resumable.current_func_addr = current_func_addr;
                         resumable.pc = wasm.pc;
@@ -331,12 +475,9 @@ pub(super) fn run<T: Config>(
                         });
                     }
                     FuncInst::WasmFunc(wasm_func_to_call_inst) => {
-                        let remaining_locals = &wasm_func_to_call_inst.locals;
-
-                        stack.push_call_frame::<T>(
+                        stack.push_call_frame(
                             current_func_addr,
-                            &wasm_func_to_call_inst.function_type,
-                            remaining_locals,
+                            &wasm_func_to_call_inst.local_func.frame_layout,
                             wasm.pc,
                             stp,
                         )?;
@@ -355,9 +496,18 @@ pub(super) fn run<T: Config>(
                             .expect("code expression spans to always be valid");
 
                         stp = wasm_func_to_call_inst.stp;
-                        current_sidetable = &module.sidetable;
+                        current_sidetable = &module.shared.sidetable;
+                        current_block_fuel = block_fuel_of(module);
+                        *prepaid_block = PrepaidBlock::default();
+                        current_immediates = &module.shared.immediates;
+                        current_v128_operands = &module.shared.v128_operands;
+                        current_frame_layout = &wasm_func_to_call_inst.local_func.frame_layout;
                         current_function_end_marker = wasm_func_to_call_inst.code_expr.from()
                             + wasm_func_to_call_inst.code_expr.len();
+                        interrupt_on_break!(
+                            store.user_data.interruption_hook(),
+                            InterruptReason::InterruptionHook
+                        );
                     }
                 }
                 trace!("Instruction: CALL");
@@ -365,7 +515,7 @@ pub(super) fn run<T: Config>(
 
             // TODO: fix push_call_frame, because the func idx that you get from the table is global func idx
             CALL_INDIRECT => {
-                decrement_fuel!(T::get_flat_cost(CALL_INDIRECT));
+                decrement_fuel!(store.user_data.get_flat_cost(CALL_INDIRECT));
                 // SAFETY: Validation guarantees there to be a valid type index
                 // next.
                 let given_type_idx = unsafe { TypeIdx::read_unchecked(wasm) };
@@ -387,9 +537,9 @@ pub(super) fn run<T: Config>(
                 let tab = unsafe { store.tables.get(*table_addr) };
                 // SAFETY: Validation guarantees the type index to be valid in
                 // the current module.
-                let func_ty = unsafe { module.types.get(given_type_idx) };
+                let func_ty = unsafe { module.shared.types.get(given_type_idx) };
 
-                let i: u32 = stack.pop_value().try_into().unwrap_validated();
+                let i: u32 = stack.pop();
 
                 let r = tab
                     .elem
@@ -420,14 +570,13 @@ pub(super) fn run<T: Config>(
                 }
 
                 trace!("Instruction: call [{func_to_call_addr:?}]");
+                store.user_data.call_hook(func_to_call_addr);
 
                 match func_to_call_inst {
                     FuncInst::HostFunc(host_func_to_call_inst) => {
-                        let params = stack
-                            .pop_tail_iter(
-                                host_func_to_call_inst.function_type.params.valtypes.len(),
-                            )
-                            .collect();
+                        let params = stack.pop_host_call_params(
+                            &host_func_to_call_inst.function_type.params.valtypes,
+                        )?;
 
                         resumable.current_func_addr = current_func_addr;
                         resumable.pc = wasm.pc;
@@ -439,12 +588,9 @@ pub(super) fn run<T: Config>(
                         });
                     }
                     FuncInst::WasmFunc(wasm_func_to_call_inst) => {
-                        let remaining_locals = &wasm_func_to_call_inst.locals;
-
-                        stack.push_call_frame::<T>(
+                        stack.push_call_frame(
                             current_func_addr,
-                            &wasm_func_to_call_inst.function_type,
-                            remaining_locals,
+                            &wasm_func_to_call_inst.local_func.frame_layout,
                             wasm.pc,
                             stp,
                         )?;
@@ -462,75 +608,102 @@ pub(super) fn run<T: Config>(
                             .expect("code expression spans to always be valid");
 
                         stp = wasm_func_to_call_inst.stp;
-                        current_sidetable = &module.sidetable;
+                        current_sidetable = &module.shared.sidetable;
+                        current_block_fuel = block_fuel_of(module);
+                        *prepaid_block = PrepaidBlock::default();
+                        current_immediates = &module.shared.immediates;
+                        current_v128_operands = &module.shared.v128_operands;
+                        current_frame_layout = &wasm_func_to_call_inst.local_func.frame_layout;
                         current_function_end_marker = wasm_func_to_call_inst.code_expr.from()
                             + wasm_func_to_call_inst.code_expr.len();
+                        interrupt_on_break!(
+                            store.user_data.interruption_hook(),
+                            InterruptReason::InterruptionHook
+                        );
                     }
                 }
                 trace!("Instruction: CALL_INDIRECT");
             }
             DROP => {
-                decrement_fuel!(T::get_flat_cost(DROP));
-                stack.pop_value();
+                decrement_fuel!(store.user_data.get_flat_cost(DROP));
+                let slots = if current_v128_operands.contains(prev_pc) {
+                    2
+                } else {
+                    1
+                };
+                stack.drop_value(slots);
                 trace!("Instruction: DROP");
             }
             SELECT => {
-                decrement_fuel!(T::get_flat_cost(SELECT));
-                let test_val: i32 = stack.pop_value().try_into().unwrap_validated();
-                let val2 = stack.pop_value();
-                let val1 = stack.pop_value();
-                if test_val != 0 {
-                    stack.push_value::<T>(val1)?;
+                decrement_fuel!(store.user_data.get_flat_cost(SELECT));
+                let slots = if current_v128_operands.contains(prev_pc) {
+                    2
                 } else {
-                    stack.push_value::<T>(val2)?;
-                }
+                    1
+                };
+                let test_val: i32 = stack.pop();
+                stack.select(slots, test_val != 0);
                 trace!("Instruction: SELECT");
             }
             SELECT_T => {
-                decrement_fuel!(T::get_flat_cost(SELECT_T));
-                let _type_vec = wasm.read_vec(ValType::read).unwrap_validated();
-                let test_val: i32 = stack.pop_value().try_into().unwrap_validated();
-                let val2 = stack.pop_value();
-                let val1 = stack.pop_value();
-                if test_val != 0 {
-                    stack.push_value::<T>(val1)?;
-                } else {
-                    stack.push_value::<T>(val2)?;
-                }
+                decrement_fuel!(store.user_data.get_flat_cost(SELECT_T));
+                // validation guarantees the vec of types to contain exactly one type
+                let _type_count = wasm.read_var_u32().unwrap_validated();
+                let ty = ValType::read(wasm).unwrap_validated();
+                let test_val: i32 = stack.pop();
+                stack.select(ty.slots(), test_val != 0);
                 trace!("Instruction: SELECT_T");
             }
             LOCAL_GET => {
-                decrement_fuel!(T::get_flat_cost(LOCAL_GET));
-                // SAFETY: Validation guarantees there to be a valid local index
-                // next.
-                let local_idx = unsafe { LocalIdx::read_unchecked(wasm) };
-                let value = *stack.get_local(local_idx);
-                stack.push_value::<T>(value)?;
+                decrement_fuel!(store.user_data.get_flat_cost(LOCAL_GET));
+                let local_idx = match current_immediates.take(wasm) {
+                    // SAFETY: Pre-decoded local indices were validated together
+                    // with the rest of the bytecode.
+                    Some(local_idx) => unsafe { LocalIdx::new_unchecked(local_idx) },
+                    // SAFETY: Validation guarantees there to be a valid local
+                    // index next.
+                    None => unsafe { LocalIdx::read_unchecked(wasm) },
+                };
+                let (offset, slots) = current_frame_layout.local(local_idx);
+                stack.push_local(offset, slots)?;
                 trace!("Instruction: local.get {} [] -> [t]", local_idx);
             }
             LOCAL_SET => {
-                decrement_fuel!(T::get_flat_cost(LOCAL_SET));
-                // SAFETY: Validation guarantees there to be a valid local index
-                // next.
-                let local_idx = unsafe { LocalIdx::read_unchecked(wasm) };
-                let value = stack.pop_value();
-                *stack.get_local_mut(local_idx) = value;
+                decrement_fuel!(store.user_data.get_flat_cost(LOCAL_SET));
+                let local_idx = match current_immediates.take(wasm) {
+                    // SAFETY: Pre-decoded local indices were validated together
+                    // with the rest of the bytecode.
+                    Some(local_idx) => unsafe { LocalIdx::new_unchecked(local_idx) },
+                    // SAFETY: Validation guarantees there to be a valid local
+                    // index next.
+                    None => unsafe { LocalIdx::read_unchecked(wasm) },
+                };
+                let (offset, slots) = current_frame_layout.local(local_idx);
+                stack.set_local(offset, slots);
                 trace!("Instruction: local.set {} [t] -> []", local_idx);
             }
             LOCAL_TEE => {
-                decrement_fuel!(T::get_flat_cost(LOCAL_TEE));
-                // SAFETY: Validation guarantees there to be a valid local index
-                // next.
-                let local_idx = unsafe { LocalIdx::read_unchecked(wasm) };
-                let value = stack.peek_value().unwrap_validated();
-                *stack.get_local_mut(local_idx) = value;
+                decrement_fuel!(store.user_data.get_flat_cost(LOCAL_TEE));
+                let local_idx = match current_immediates.take(wasm) {
+                    // SAFETY: Pre-decoded local indices were validated together
+                    // with the rest of the bytecode.
+                    Some(local_idx) => unsafe { LocalIdx::new_unchecked(local_idx) },
+                    // SAFETY: Validation guarantees there to be a valid local
+                    // index next.
+                    None => unsafe { LocalIdx::read_unchecked(wasm) },
+                };
+                let (offset, slots) = current_frame_layout.local(local_idx);
+                stack.tee_local(offset, slots);
                 trace!("Instruction: local.tee {} [t] -> [t]", local_idx);
             }
             GLOBAL_GET => {
-                decrement_fuel!(T::get_flat_cost(GLOBAL_GET));
-                // SAFETY: Validation guarantees there to be a valid global
-                // index next.
-                let global_idx = unsafe { GlobalIdx::read_unchecked(wasm) };
+                decrement_fuel!(store.user_data.get_flat_cost(GLOBAL_GET));
+                let global_idx = match current_immediates.take(wasm) {
+                    Some(global_idx) => GlobalIdx::new(global_idx),
+                    // SAFETY: Validation guarantees there to be a valid global
+                    // index next.
+                    None => unsafe { GlobalIdx::read_unchecked(wasm) },
+                };
                 // SAFETY: The current module address must come from the current
                 // store, because it is the only parameter to this function that
                 // can contain module addresses. All stores guarantee all
@@ -544,7 +717,7 @@ pub(super) fn run<T: Config>(
                 // store. Therefore, it is valid in the current store.
                 let global = unsafe { store.globals.get(global_addr) };
 
-                stack.push_value::<T>(global.value)?;
+                stack.push_value(global.value)?;
 
                 trace!(
                     "Instruction: global.get '{}' [<GLOBAL>] -> [{:?}]",
@@ -553,10 +726,13 @@ pub(super) fn run<T: Config>(
                 );
             }
             GLOBAL_SET => {
-                decrement_fuel!(T::get_flat_cost(GLOBAL_SET));
-                // SAFETY: Validation guarantees there to be a valid global
-                // index next.
-                let global_idx = unsafe { GlobalIdx::read_unchecked(wasm) };
+                decrement_fuel!(store.user_data.get_flat_cost(GLOBAL_SET));
+                let global_idx = match current_immediates.take(wasm) {
+                    Some(global_idx) => GlobalIdx::new(global_idx),
+                    // SAFETY: Validation guarantees there to be a valid global
+                    // index next.
+                    None => unsafe { GlobalIdx::read_unchecked(wasm) },
+                };
                 // SAFETY: The current module address must come from the current
                 // store, because it is the only parameter to this function that
                 // can contain module addresses. All stores guarantee all
@@ -569,11 +745,12 @@ pub(super) fn run<T: Config>(
                 // store. Therefore, it is valid in the current store.
                 let global = unsafe { store.globals.get_mut(global_addr) };
 
-                global.value = stack.pop_value();
+                global.value = stack.pop_value(global.ty.ty);
                 trace!("Instruction: GLOBAL_SET");
+                interrupt_on_break!(store.user_data.global_set_hook(global_addr, global.value));
             }
             TABLE_GET => {
-                decrement_fuel!(T::get_flat_cost(TABLE_GET));
+                decrement_fuel!(store.user_data.get_flat_cost(TABLE_GET));
                 // SAFETY: Validation guarantees there to be a valid table index
                 // next.
                 let table_idx = unsafe { TableIdx::read_unchecked(wasm) };
@@ -590,14 +767,14 @@ pub(super) fn run<T: Config>(
                 // store. Therefore, it is valid in the current store.
                 let tab = unsafe { store.tables.get(table_addr) };
 
-                let i: i32 = stack.pop_value().try_into().unwrap_validated();
+                let i: i32 = stack.pop();
 
                 let val = tab
                     .elem
                     .get(i.cast_unsigned().into_usize())
                     .ok_or(TrapError::TableOrElementAccessOutOfBounds)?;
 
-                stack.push_value::<T>((*val).into())?;
+                stack.push_value((*val).into())?;
                 trace!(
                     "Instruction: table.get '{}' [{}] -> [{}]",
                     table_idx,
@@ -606,7 +783,7 @@ pub(super) fn run<T: Config>(
                 );
             }
             TABLE_SET => {
-                decrement_fuel!(T::get_flat_cost(TABLE_SET));
+                decrement_fuel!(store.user_data.get_flat_cost(TABLE_SET));
                 // SAFETY: Validation guarantees there to be valid table index
                 // next.
                 let table_idx = unsafe { TableIdx::read_unchecked(wasm) };
@@ -623,8 +800,8 @@ pub(super) fn run<T: Config>(
                 // store. Therefore, it is valid in the current store.
                 let tab = unsafe { store.tables.get_mut(table_addr) };
 
-                let val: Ref = stack.pop_value().try_into().unwrap_validated();
-                let i: i32 = stack.pop_value().try_into().unwrap_validated();
+                let val: Ref = stack.pop();
+                let i: i32 = stack.pop();
 
                 tab.elem
                     .get_mut(i.cast_unsigned().into_usize())
@@ -641,9 +818,9 @@ pub(super) fn run<T: Config>(
                 return Err(TrapError::ReachedUnreachable.into());
             }
             I32_LOAD => {
-                decrement_fuel!(T::get_flat_cost(I32_LOAD));
-                let memarg = MemArg::read(wasm).unwrap_validated();
-                let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_LOAD));
+                let offset = read_memarg_offset(wasm, current_immediates);
+                let relative_address: u32 = stack.pop();
 
                 // SAFETY: The current module address must come from the current
                 // store, because it is the only parameter to this function that
@@ -655,18 +832,23 @@ pub(super) fn run<T: Config>(
                 let mem_addr = *unsafe { module.mem_addrs.get(MemIdx::new(0)) };
                 // SAFETY: This memory address was just read from the current
                 // store. Therefore, it is valid in the current store.
-                let mem_inst = unsafe { store.memories.get(mem_addr) };
+                let mem_inst = unsafe { store.memories.get_mut(mem_addr) };
 
-                let idx = calculate_mem_address(&memarg, relative_address)?;
-                let data = mem_inst.mem.load(idx)?;
+                let idx = calculate_mem_address(offset, relative_address)?;
+                let data = mem_load(mem_inst, &mut store.user_data, mem_addr, idx)?;
 
-                stack.push_value::<T>(Value::I32(data))?;
+                stack.push_value(Value::I32(data))?;
                 trace!("Instruction: i32.load [{relative_address}] -> [{data}]");
+                interrupt_on_break!(store.user_data.memory_load_hook(
+                    mem_addr,
+                    idx,
+                    &data.to_le_bytes()
+                ));
             }
             I64_LOAD => {
-                decrement_fuel!(T::get_flat_cost(I64_LOAD));
-                let memarg = MemArg::read(wasm).unwrap_validated();
-                let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_LOAD));
+                let offset = read_memarg_offset(wasm, current_immediates);
+                let relative_address: u32 = stack.pop();
 
                 // SAFETY: The current module address must come from the current
                 // store, because it is the only parameter to this function that
@@ -678,18 +860,23 @@ pub(super) fn run<T: Config>(
                 let mem_addr = *unsafe { module.mem_addrs.get(MemIdx::new(0)) };
                 // SAFETY: This memory address was just read from the current
                 // store. Therefore, it is valid in the current store.
-                let mem = unsafe { store.memories.get(mem_addr) };
+                let mem = unsafe { store.memories.get_mut(mem_addr) };
 
-                let idx = calculate_mem_address(&memarg, relative_address)?;
-                let data = mem.mem.load(idx)?;
+                let idx = calculate_mem_address(offset, relative_address)?;
+                let data = mem_load(mem, &mut store.user_data, mem_addr, idx)?;
 
-                stack.push_value::<T>(Value::I64(data))?;
+                stack.push_value(Value::I64(data))?;
                 trace!("Instruction: i64.load [{relative_address}] -> [{data}]");
+                interrupt_on_break!(store.user_data.memory_load_hook(
+                    mem_addr,
+                    idx,
+                    &data.to_le_bytes()
+                ));
             }
             F32_LOAD => {
-                decrement_fuel!(T::get_flat_cost(F32_LOAD));
-                let memarg = MemArg::read(wasm).unwrap_validated();
-                let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_LOAD));
+                let offset = read_memarg_offset(wasm, current_immediates);
+                let relative_address: u32 = stack.pop();
 
                 // SAFETY: The current module address must come from the current
                 // store, because it is the only parameter to this function that
@@ -701,18 +888,23 @@ pub(super) fn run<T: Config>(
                 let mem_addr = *unsafe { module.mem_addrs.get(MemIdx::new(0)) };
                 // SAFETY: This memory address was just read from the current
                 // store. Therefore, it is valid in the current store.
-                let mem = unsafe { store.memories.get(mem_addr) };
+                let mem = unsafe { store.memories.get_mut(mem_addr) };
 
-                let idx = calculate_mem_address(&memarg, relative_address)?;
-                let data = mem.mem.load(idx)?;
+                let idx = calculate_mem_address(offset, relative_address)?;
+                let data = mem_load(mem, &mut store.user_data, mem_addr, idx)?;
 
-                stack.push_value::<T>(Value::F32(data))?;
+                stack.push_value(Value::F32(data))?;
                 trace!("Instruction: f32.load [{relative_address}] -> [{data}]");
+                interrupt_on_break!(store.user_data.memory_load_hook(
+                    mem_addr,
+                    idx,
+                    &data.to_le_bytes()
+                ));
             }
             F64_LOAD => {
-                decrement_fuel!(T::get_flat_cost(F64_LOAD));
-                let memarg = MemArg::read(wasm).unwrap_validated();
-                let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_LOAD));
+                let offset = read_memarg_offset(wasm, current_immediates);
+                let relative_address: u32 = stack.pop();
 
                 // SAFETY: The current module address must come from the current
                 // store, because it is the only parameter to this function that
@@ -724,18 +916,23 @@ pub(super) fn run<T: Config>(
                 let mem_addr = *unsafe { module.mem_addrs.get(MemIdx::new(0)) };
                 // SAFETY: This memory address was just read from the current
                 // store. Therefore, it is valid in the current store.
-                let mem = unsafe { store.memories.get(mem_addr) };
+                let mem = unsafe { store.memories.get_mut(mem_addr) };
 
-                let idx = calculate_mem_address(&memarg, relative_address)?;
-                let data = mem.mem.load(idx)?;
+                let idx = calculate_mem_address(offset, relative_address)?;
+                let data = mem_load(mem, &mut store.user_data, mem_addr, idx)?;
 
-                stack.push_value::<T>(Value::F64(data))?;
+                stack.push_value(Value::F64(data))?;
                 trace!("Instruction: f64.load [{relative_address}] -> [{data}]");
+                interrupt_on_break!(store.user_data.memory_load_hook(
+                    mem_addr,
+                    idx,
+                    &data.to_le_bytes()
+                ));
             }
             I32_LOAD8_S => {
-                decrement_fuel!(T::get_flat_cost(I32_LOAD8_S));
-                let memarg = MemArg::read(wasm).unwrap_validated();
-                let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_LOAD8_S));
+                let offset = read_memarg_offset(wasm, current_immediates);
+                let relative_address: u32 = stack.pop();
 
                 // SAFETY: The current module address must come from the current
                 // store, because it is the only parameter to this function that
@@ -747,18 +944,23 @@ pub(super) fn run<T: Config>(
                 let mem_addr = *unsafe { module.mem_addrs.get(MemIdx::new(0)) };
                 // SAFETY: This memory address was just read from the current
                 // store. Therefore, it is valid in the current store.
-                let mem = unsafe { store.memories.get(mem_addr) };
+                let mem = unsafe { store.memories.get_mut(mem_addr) };
 
-                let idx = calculate_mem_address(&memarg, relative_address)?;
-                let data: i8 = mem.mem.load(idx)?;
+                let idx = calculate_mem_address(offset, relative_address)?;
+                let data: i8 = mem_load(mem, &mut store.user_data, mem_addr, idx)?;
 
-                stack.push_value::<T>(Value::I32(data as u32))?;
+                stack.push_value(Value::I32(data as u32))?;
                 trace!("Instruction: i32.load8_s [{relative_address}] -> [{data}]");
+                interrupt_on_break!(store.user_data.memory_load_hook(
+                    mem_addr,
+                    idx,
+                    &data.to_le_bytes()
+                ));
             }
             I32_LOAD8_U => {
-                decrement_fuel!(T::get_flat_cost(I32_LOAD8_U));
-                let memarg = MemArg::read(wasm).unwrap_validated();
-                let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_LOAD8_U));
+                let offset = read_memarg_offset(wasm, current_immediates);
+                let relative_address: u32 = stack.pop();
 
                 // SAFETY: The current module address must come from the current
                 // store, because it is the only parameter to this function that
@@ -770,18 +972,23 @@ pub(super) fn run<T: Config>(
                 let mem_addr = *unsafe { module.mem_addrs.get(MemIdx::new(0)) };
                 // SAFETY: This memory address was just read from the current
                 // store. Therefore, it is valid in the current store.
-                let mem = unsafe { store.memories.get(mem_addr) };
+                let mem = unsafe { store.memories.get_mut(mem_addr) };
 
-                let idx = calculate_mem_address(&memarg, relative_address)?;
-                let data: u8 = mem.mem.load(idx)?;
+                let idx = calculate_mem_address(offset, relative_address)?;
+                let data: u8 = mem_load(mem, &mut store.user_data, mem_addr, idx)?;
 
-                stack.push_value::<T>(Value::I32(data as u32))?;
+                stack.push_value(Value::I32(data as u32))?;
                 trace!("Instruction: i32.load8_u [{relative_address}] -> [{data}]");
+                interrupt_on_break!(store.user_data.memory_load_hook(
+                    mem_addr,
+                    idx,
+                    &data.to_le_bytes()
+                ));
             }
             I32_LOAD16_S => {
-                decrement_fuel!(T::get_flat_cost(I32_LOAD16_S));
-                let memarg = MemArg::read(wasm).unwrap_validated();
-                let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_LOAD16_S));
+                let offset = read_memarg_offset(wasm, current_immediates);
+                let relative_address: u32 = stack.pop();
 
                 // SAFETY: The current module address must come from the current
                 // store, because it is the only parameter to this function that
@@ -793,18 +1000,23 @@ pub(super) fn run<T: Config>(
                 let mem_addr = *unsafe { module.mem_addrs.get(MemIdx::new(0)) };
                 // SAFETY: This memory address was just read from the current
                 // store. Therefore, it is valid in the current store.
-                let mem = unsafe { store.memories.get(mem_addr) };
+                let mem = unsafe { store.memories.get_mut(mem_addr) };
 
-                let idx = calculate_mem_address(&memarg, relative_address)?;
-                let data: i16 = mem.mem.load(idx)?;
+                let idx = calculate_mem_address(offset, relative_address)?;
+                let data: i16 = mem_load(mem, &mut store.user_data, mem_addr, idx)?;
 
-                stack.push_value::<T>(Value::I32(data as u32))?;
+                stack.push_value(Value::I32(data as u32))?;
                 trace!("Instruction: i32.load16_s [{relative_address}] -> [{data}]");
+                interrupt_on_break!(store.user_data.memory_load_hook(
+                    mem_addr,
+                    idx,
+                    &data.to_le_bytes()
+                ));
             }
             I32_LOAD16_U => {
-                decrement_fuel!(T::get_flat_cost(I32_LOAD16_U));
-                let memarg = MemArg::read(wasm).unwrap_validated();
-                let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_LOAD16_U));
+                let offset = read_memarg_offset(wasm, current_immediates);
+                let relative_address: u32 = stack.pop();
 
                 // SAFETY: The current module address must come from the current
                 // store, because it is the only parameter to this function that
@@ -816,18 +1028,23 @@ pub(super) fn run<T: Config>(
                 let mem_addr = *unsafe { module.mem_addrs.get(MemIdx::new(0)) };
                 // SAFETY: This memory address was just read from the current
                 // store. Therefore, it is valid in the current store.
-                let mem = unsafe { store.memories.get(mem_addr) };
+                let mem = unsafe { store.memories.get_mut(mem_addr) };
 
-                let idx = calculate_mem_address(&memarg, relative_address)?;
-                let data: u16 = mem.mem.load(idx)?;
+                let idx = calculate_mem_address(offset, relative_address)?;
+                let data: u16 = mem_load(mem, &mut store.user_data, mem_addr, idx)?;
 
-                stack.push_value::<T>(Value::I32(data as u32))?;
+                stack.push_value(Value::I32(data as u32))?;
                 trace!("Instruction: i32.load16_u [{relative_address}] -> [{data}]");
+                interrupt_on_break!(store.user_data.memory_load_hook(
+                    mem_addr,
+                    idx,
+                    &data.to_le_bytes()
+                ));
             }
             I64_LOAD8_S => {
-                decrement_fuel!(T::get_flat_cost(I64_LOAD8_S));
-                let memarg = MemArg::read(wasm).unwrap_validated();
-                let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_LOAD8_S));
+                let offset = read_memarg_offset(wasm, current_immediates);
+                let relative_address: u32 = stack.pop();
 
                 // SAFETY: The current module address must come from the current
                 // store, because it is the only parameter to this function that
@@ -839,18 +1056,23 @@ pub(super) fn run<T: Config>(
                 let mem_addr = *unsafe { module.mem_addrs.get(MemIdx::new(0)) };
                 // SAFETY: This memory address was just read from the current
                 // store. Therefore, it is valid in the current store.
-                let mem = unsafe { store.memories.get(mem_addr) };
+                let mem = unsafe { store.memories.get_mut(mem_addr) };
 
-                let idx = calculate_mem_address(&memarg, relative_address)?;
-                let data: i8 = mem.mem.load(idx)?;
+                let idx = calculate_mem_address(offset, relative_address)?;
+                let data: i8 = mem_load(mem, &mut store.user_data, mem_addr, idx)?;
 
-                stack.push_value::<T>(Value::I64(data as u64))?;
+                stack.push_value(Value::I64(data as u64))?;
                 trace!("Instruction: i64.load8_s [{relative_address}] -> [{data}]");
+                interrupt_on_break!(store.user_data.memory_load_hook(
+                    mem_addr,
+                    idx,
+                    &data.to_le_bytes()
+                ));
             }
             I64_LOAD8_U => {
-                decrement_fuel!(T::get_flat_cost(I64_LOAD8_U));
-                let memarg = MemArg::read(wasm).unwrap_validated();
-                let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_LOAD8_U));
+                let offset = read_memarg_offset(wasm, current_immediates);
+                let relative_address: u32 = stack.pop();
 
                 // SAFETY: The current module address must come from the current
                 // store, because it is the only parameter to this function that
@@ -862,18 +1084,23 @@ pub(super) fn run<T: Config>(
                 let mem_addr = *unsafe { module.mem_addrs.get(MemIdx::new(0)) };
                 // SAFETY: This memory address was just read from the current
                 // store. Therefore, it is valid in the current store.
-                let mem = unsafe { store.memories.get(mem_addr) };
+                let mem = unsafe { store.memories.get_mut(mem_addr) };
 
-                let idx = calculate_mem_address(&memarg, relative_address)?;
-                let data: u8 = mem.mem.load(idx)?;
+                let idx = calculate_mem_address(offset, relative_address)?;
+                let data: u8 = mem_load(mem, &mut store.user_data, mem_addr, idx)?;
 
-                stack.push_value::<T>(Value::I64(data as u64))?;
+                stack.push_value(Value::I64(data as u64))?;
                 trace!("Instruction: i64.load8_u [{relative_address}] -> [{data}]");
+                interrupt_on_break!(store.user_data.memory_load_hook(
+                    mem_addr,
+                    idx,
+                    &data.to_le_bytes()
+                ));
             }
             I64_LOAD16_S => {
-                decrement_fuel!(T::get_flat_cost(I64_LOAD16_S));
-                let memarg = MemArg::read(wasm).unwrap_validated();
-                let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_LOAD16_S));
+                let offset = read_memarg_offset(wasm, current_immediates);
+                let relative_address: u32 = stack.pop();
 
                 // SAFETY: The current module address must come from the current
                 // store, because it is the only parameter to this function that
@@ -885,18 +1112,23 @@ pub(super) fn run<T: Config>(
                 let mem_addr = *unsafe { module.mem_addrs.get(MemIdx::new(0)) };
                 // SAFETY: This memory address was just read from the current
                 // store. Therefore, it is valid in the current store.
-                let mem = unsafe { store.memories.get(mem_addr) };
+                let mem = unsafe { store.memories.get_mut(mem_addr) };
 
-                let idx = calculate_mem_address(&memarg, relative_address)?;
-                let data: i16 = mem.mem.load(idx)?;
+                let idx = calculate_mem_address(offset, relative_address)?;
+                let data: i16 = mem_load(mem, &mut store.user_data, mem_addr, idx)?;
 
-                stack.push_value::<T>(Value::I64(data as u64))?;
+                stack.push_value(Value::I64(data as u64))?;
                 trace!("Instruction: i64.load16_s [{relative_address}] -> [{data}]");
+                interrupt_on_break!(store.user_data.memory_load_hook(
+                    mem_addr,
+                    idx,
+                    &data.to_le_bytes()
+                ));
             }
             I64_LOAD16_U => {
-                decrement_fuel!(T::get_flat_cost(I64_LOAD16_U));
-                let memarg = MemArg::read(wasm).unwrap_validated();
-                let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_LOAD16_U));
+                let offset = read_memarg_offset(wasm, current_immediates);
+                let relative_address: u32 = stack.pop();
 
                 // SAFETY: The current module address must come from the current
                 // store, because it is the only parameter to this function that
@@ -908,18 +1140,23 @@ pub(super) fn run<T: Config>(
                 let mem_addr = *unsafe { module.mem_addrs.get(MemIdx::new(0)) };
                 // SAFETY: This memory address was just read from the current
                 // store. Therefore, it is valid in the current store.
-                let mem = unsafe { store.memories.get(mem_addr) };
+                let mem = unsafe { store.memories.get_mut(mem_addr) };
 
-                let idx = calculate_mem_address(&memarg, relative_address)?;
-                let data: u16 = mem.mem.load(idx)?;
+                let idx = calculate_mem_address(offset, relative_address)?;
+                let data: u16 = mem_load(mem, &mut store.user_data, mem_addr, idx)?;
 
-                stack.push_value::<T>(Value::I64(data as u64))?;
+                stack.push_value(Value::I64(data as u64))?;
                 trace!("Instruction: i64.load16_u [{relative_address}] -> [{data}]");
+                interrupt_on_break!(store.user_data.memory_load_hook(
+                    mem_addr,
+                    idx,
+                    &data.to_le_bytes()
+                ));
             }
             I64_LOAD32_S => {
-                decrement_fuel!(T::get_flat_cost(I64_LOAD32_S));
-                let memarg = MemArg::read(wasm).unwrap_validated();
-                let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_LOAD32_S));
+                let offset = read_memarg_offset(wasm, current_immediates);
+                let relative_address: u32 = stack.pop();
 
                 // SAFETY: The current module address must come from the current
                 // store, because it is the only parameter to this function that
@@ -931,18 +1168,23 @@ pub(super) fn run<T: Config>(
                 let mem_addr = *unsafe { module.mem_addrs.get(MemIdx::new(0)) };
                 // SAFETY: This memory address was just read from the current
                 // store. Therefore, it is valid in the current store.
-                let mem = unsafe { store.memories.get(mem_addr) };
+                let mem = unsafe { store.memories.get_mut(mem_addr) };
 
-                let idx = calculate_mem_address(&memarg, relative_address)?;
-                let data: i32 = mem.mem.load(idx)?;
+                let idx = calculate_mem_address(offset, relative_address)?;
+                let data: i32 = mem_load(mem, &mut store.user_data, mem_addr, idx)?;
 
-                stack.push_value::<T>(Value::I64(data as u64))?;
+                stack.push_value(Value::I64(data as u64))?;
                 trace!("Instruction: i64.load32_s [{relative_address}] -> [{data}]");
+                interrupt_on_break!(store.user_data.memory_load_hook(
+                    mem_addr,
+                    idx,
+                    &data.to_le_bytes()
+                ));
             }
             I64_LOAD32_U => {
-                decrement_fuel!(T::get_flat_cost(I64_LOAD32_U));
-                let memarg = MemArg::read(wasm).unwrap_validated();
-                let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_LOAD32_U));
+                let offset = read_memarg_offset(wasm, current_immediates);
+                let relative_address: u32 = stack.pop();
 
                 // SAFETY: The current module address must come from the current
                 // store, because it is the only parameter to this function that
@@ -954,20 +1196,25 @@ pub(super) fn run<T: Config>(
                 let mem_addr = *unsafe { module.mem_addrs.get(MemIdx::new(0)) };
                 // SAFETY: This memory address was just read from the current
                 // store. Therefore, it is valid in the current store.
-                let mem = unsafe { store.memories.get(mem_addr) };
+                let mem = unsafe { store.memories.get_mut(mem_addr) };
 
-                let idx = calculate_mem_address(&memarg, relative_address)?;
-                let data: u32 = mem.mem.load(idx)?;
+                let idx = calculate_mem_address(offset, relative_address)?;
+                let data: u32 = mem_load(mem, &mut store.user_data, mem_addr, idx)?;
 
-                stack.push_value::<T>(Value::I64(data as u64))?;
+                stack.push_value(Value::I64(data as u64))?;
                 trace!("Instruction: i64.load32_u [{relative_address}] -> [{data}]");
+                interrupt_on_break!(store.user_data.memory_load_hook(
+                    mem_addr,
+                    idx,
+                    &data.to_le_bytes()
+                ));
             }
             I32_STORE => {
-                decrement_fuel!(T::get_flat_cost(I32_STORE));
-                let memarg = MemArg::read(wasm).unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_STORE));
+                let offset = read_memarg_offset(wasm, current_immediates);
 
-                let data_to_store: u32 = stack.pop_value().try_into().unwrap_validated();
-                let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                let data_to_store: u32 = stack.pop();
+                let relative_address: u32 = stack.pop();
 
                 // SAFETY: The current module address must come from the current
                 // store, because it is the only parameter to this function that
@@ -979,19 +1226,24 @@ pub(super) fn run<T: Config>(
                 let mem_addr = *unsafe { module.mem_addrs.get(MemIdx::new(0)) };
                 // SAFETY: This memory address was just read from the current
                 // store. Therefore, it is valid in the current store.
-                let mem = unsafe { store.memories.get(mem_addr) };
+                let mem = unsafe { store.memories.get_mut(mem_addr) };
 
-                let idx = calculate_mem_address(&memarg, relative_address)?;
-                mem.mem.store(idx, data_to_store)?;
+                let idx = calculate_mem_address(offset, relative_address)?;
+                mem_store(mem, &mut store.user_data, mem_addr, idx, data_to_store)?;
 
                 trace!("Instruction: i32.store [{relative_address} {data_to_store}] -> []");
+                interrupt_on_break!(store.user_data.memory_store_hook(
+                    mem_addr,
+                    idx,
+                    &data_to_store.to_le_bytes()
+                ));
             }
             I64_STORE => {
-                decrement_fuel!(T::get_flat_cost(I64_STORE));
-                let memarg = MemArg::read(wasm).unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_STORE));
+                let offset = read_memarg_offset(wasm, current_immediates);
 
-                let data_to_store: u64 = stack.pop_value().try_into().unwrap_validated();
-                let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                let data_to_store: u64 = stack.pop();
+                let relative_address: u32 = stack.pop();
 
                 // SAFETY: The current module address must come from the current
                 // store, because it is the only parameter to this function that
@@ -1003,19 +1255,24 @@ pub(super) fn run<T: Config>(
                 let mem_addr = *unsafe { module.mem_addrs.get(MemIdx::new(0)) };
                 // SAFETY: This memory address was just read from the current
                 // store. Therefore, it is valid in the current store.
-                let mem = unsafe { store.memories.get(mem_addr) };
+                let mem = unsafe { store.memories.get_mut(mem_addr) };
 
-                let idx = calculate_mem_address(&memarg, relative_address)?;
-                mem.mem.store(idx, data_to_store)?;
+                let idx = calculate_mem_address(offset, relative_address)?;
+                mem_store(mem, &mut store.user_data, mem_addr, idx, data_to_store)?;
 
                 trace!("Instruction: i64.store [{relative_address} {data_to_store}] -> []");
+                interrupt_on_break!(store.user_data.memory_store_hook(
+                    mem_addr,
+                    idx,
+                    &data_to_store.to_le_bytes()
+                ));
             }
             F32_STORE => {
-                decrement_fuel!(T::get_flat_cost(F32_STORE));
-                let memarg = MemArg::read(wasm).unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_STORE));
+                let offset = read_memarg_offset(wasm, current_immediates);
 
-                let data_to_store: F32 = stack.pop_value().try_into().unwrap_validated();
-                let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                let data_to_store: F32 = stack.pop();
+                let relative_address: u32 = stack.pop();
 
                 // SAFETY: The current module address must come from the current
                 // store, because it is the only parameter to this function that
@@ -1027,19 +1284,24 @@ pub(super) fn run<T: Config>(
                 let mem_addr = *unsafe { module.mem_addrs.get(MemIdx::new(0)) };
                 // SAFETY: This memory address was just read from the current
                 // store. Therefore, it is valid in the current store.
-                let mem = unsafe { store.memories.get(mem_addr) };
+                let mem = unsafe { store.memories.get_mut(mem_addr) };
 
-                let idx = calculate_mem_address(&memarg, relative_address)?;
-                mem.mem.store(idx, data_to_store)?;
+                let idx = calculate_mem_address(offset, relative_address)?;
+                mem_store(mem, &mut store.user_data, mem_addr, idx, data_to_store)?;
 
                 trace!("Instruction: f32.store [{relative_address} {data_to_store}] -> []");
+                interrupt_on_break!(store.user_data.memory_store_hook(
+                    mem_addr,
+                    idx,
+                    &data_to_store.to_le_bytes()
+                ));
             }
             F64_STORE => {
-                decrement_fuel!(T::get_flat_cost(F64_STORE));
-                let memarg = MemArg::read(wasm).unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_STORE));
+                let offset = read_memarg_offset(wasm, current_immediates);
 
-                let data_to_store: F64 = stack.pop_value().try_into().unwrap_validated();
-                let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                let data_to_store: F64 = stack.pop();
+                let relative_address: u32 = stack.pop();
 
                 // SAFETY: The current module address must come from the current
                 // store, because it is the only parameter to this function that
@@ -1051,19 +1313,24 @@ pub(super) fn run<T: Config>(
                 let mem_addr = *unsafe { module.mem_addrs.get(MemIdx::new(0)) };
                 // SAFETY: This memory address was just read from the current
                 // store. Therefore, it is valid in the current store.
-                let mem = unsafe { store.memories.get(mem_addr) };
+                let mem = unsafe { store.memories.get_mut(mem_addr) };
 
-                let idx = calculate_mem_address(&memarg, relative_address)?;
-                mem.mem.store(idx, data_to_store)?;
+                let idx = calculate_mem_address(offset, relative_address)?;
+                mem_store(mem, &mut store.user_data, mem_addr, idx, data_to_store)?;
 
                 trace!("Instruction: f64.store [{relative_address} {data_to_store}] -> []");
+                interrupt_on_break!(store.user_data.memory_store_hook(
+                    mem_addr,
+                    idx,
+                    &data_to_store.to_le_bytes()
+                ));
             }
             I32_STORE8 => {
-                decrement_fuel!(T::get_flat_cost(I32_STORE8));
-                let memarg = MemArg::read(wasm).unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_STORE8));
+                let offset = read_memarg_offset(wasm, current_immediates);
 
-                let data_to_store: i32 = stack.pop_value().try_into().unwrap_validated();
-                let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                let data_to_store: i32 = stack.pop();
+                let relative_address: u32 = stack.pop();
 
                 let wrapped_data = data_to_store as i8;
 
@@ -1077,19 +1344,24 @@ pub(super) fn run<T: Config>(
                 let mem_addr = *unsafe { module.mem_addrs.get(MemIdx::new(0)) };
                 // SAFETY: This memory address was just read from the current
                 // store. Therefore, it is valid in the current store.
-                let mem = unsafe { store.memories.get(mem_addr) };
+                let mem = unsafe { store.memories.get_mut(mem_addr) };
 
-                let idx = calculate_mem_address(&memarg, relative_address)?;
-                mem.mem.store(idx, wrapped_data)?;
+                let idx = calculate_mem_address(offset, relative_address)?;
+                mem_store(mem, &mut store.user_data, mem_addr, idx, wrapped_data)?;
 
                 trace!("Instruction: i32.store8 [{relative_address} {wrapped_data}] -> []");
+                interrupt_on_break!(store.user_data.memory_store_hook(
+                    mem_addr,
+                    idx,
+                    &wrapped_data.to_le_bytes()
+                ));
             }
             I32_STORE16 => {
-                decrement_fuel!(T::get_flat_cost(I32_STORE16));
-                let memarg = MemArg::read(wasm).unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_STORE16));
+                let offset = read_memarg_offset(wasm, current_immediates);
 
-                let data_to_store: i32 = stack.pop_value().try_into().unwrap_validated();
-                let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                let data_to_store: i32 = stack.pop();
+                let relative_address: u32 = stack.pop();
 
                 let wrapped_data = data_to_store as i16;
 
@@ -1103,19 +1375,24 @@ pub(super) fn run<T: Config>(
                 let mem_addr = *unsafe { module.mem_addrs.get(MemIdx::new(0)) };
                 // SAFETY: This memory address was just read from the current
                 // store. Therefore, it is valid in the current store.
-                let mem = unsafe { store.memories.get(mem_addr) };
+                let mem = unsafe { store.memories.get_mut(mem_addr) };
 
-                let idx = calculate_mem_address(&memarg, relative_address)?;
-                mem.mem.store(idx, wrapped_data)?;
+                let idx = calculate_mem_address(offset, relative_address)?;
+                mem_store(mem, &mut store.user_data, mem_addr, idx, wrapped_data)?;
 
                 trace!("Instruction: i32.store16 [{relative_address} {data_to_store}] -> []");
+                interrupt_on_break!(store.user_data.memory_store_hook(
+                    mem_addr,
+                    idx,
+                    &wrapped_data.to_le_bytes()
+                ));
             }
             I64_STORE8 => {
-                decrement_fuel!(T::get_flat_cost(I64_STORE8));
-                let memarg = MemArg::read(wasm).unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_STORE8));
+                let offset = read_memarg_offset(wasm, current_immediates);
 
-                let data_to_store: i64 = stack.pop_value().try_into().unwrap_validated();
-                let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                let data_to_store: i64 = stack.pop();
+                let relative_address: u32 = stack.pop();
 
                 let wrapped_data = data_to_store as i8;
 
@@ -1129,19 +1406,24 @@ pub(super) fn run<T: Config>(
                 let mem_addr = *unsafe { module.mem_addrs.get(MemIdx::new(0)) };
                 // SAFETY: This memory address was just read from the current
                 // store. Therefore, it is valid in the current store.
-                let mem = unsafe { store.memories.get(mem_addr) };
+                let mem = unsafe { store.memories.get_mut(mem_addr) };
 
-                let idx = calculate_mem_address(&memarg, relative_address)?;
-                mem.mem.store(idx, wrapped_data)?;
+                let idx = calculate_mem_address(offset, relative_address)?;
+                mem_store(mem, &mut store.user_data, mem_addr, idx, wrapped_data)?;
 
                 trace!("Instruction: i64.store8 [{relative_address} {data_to_store}] -> []");
+                interrupt_on_break!(store.user_data.memory_store_hook(
+                    mem_addr,
+                    idx,
+                    &wrapped_data.to_le_bytes()
+                ));
             }
             I64_STORE16 => {
-                decrement_fuel!(T::get_flat_cost(I64_STORE16));
-                let memarg = MemArg::read(wasm).unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_STORE16));
+                let offset = read_memarg_offset(wasm, current_immediates);
 
-                let data_to_store: i64 = stack.pop_value().try_into().unwrap_validated();
-                let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                let data_to_store: i64 = stack.pop();
+                let relative_address: u32 = stack.pop();
 
                 let wrapped_data = data_to_store as i16;
 
@@ -1155,19 +1437,24 @@ pub(super) fn run<T: Config>(
                 let mem_addr = *unsafe { module.mem_addrs.get(MemIdx::new(0)) };
                 // SAFETY: This memory address was just read from the current
                 // store. Therefore, it is valid in the current store.
-                let mem = unsafe { store.memories.get(mem_addr) };
+                let mem = unsafe { store.memories.get_mut(mem_addr) };
 
-                let idx = calculate_mem_address(&memarg, relative_address)?;
-                mem.mem.store(idx, wrapped_data)?;
+                let idx = calculate_mem_address(offset, relative_address)?;
+                mem_store(mem, &mut store.user_data, mem_addr, idx, wrapped_data)?;
 
                 trace!("Instruction: i64.store16 [{relative_address} {data_to_store}] -> []");
+                interrupt_on_break!(store.user_data.memory_store_hook(
+                    mem_addr,
+                    idx,
+                    &wrapped_data.to_le_bytes()
+                ));
             }
             I64_STORE32 => {
-                decrement_fuel!(T::get_flat_cost(I64_STORE32));
-                let memarg = MemArg::read(wasm).unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_STORE32));
+                let offset = read_memarg_offset(wasm, current_immediates);
 
-                let data_to_store: i64 = stack.pop_value().try_into().unwrap_validated();
-                let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                let data_to_store: i64 = stack.pop();
+                let relative_address: u32 = stack.pop();
 
                 let wrapped_data = data_to_store as i32;
 
@@ -1181,15 +1468,20 @@ pub(super) fn run<T: Config>(
                 let mem_addr = *unsafe { module.mem_addrs.get(MemIdx::new(0)) };
                 // SAFETY: This memory address was just read from the current
                 // store. Therefore, it is valid in the current store.
-                let mem = unsafe { store.memories.get(mem_addr) };
+                let mem = unsafe { store.memories.get_mut(mem_addr) };
 
-                let idx = calculate_mem_address(&memarg, relative_address)?;
-                mem.mem.store(idx, wrapped_data)?;
+                let idx = calculate_mem_address(offset, relative_address)?;
+                mem_store(mem, &mut store.user_data, mem_addr, idx, wrapped_data)?;
 
                 trace!("Instruction: i64.store32 [{relative_address} {data_to_store}] -> []");
+                interrupt_on_break!(store.user_data.memory_store_hook(
+                    mem_addr,
+                    idx,
+                    &wrapped_data.to_le_bytes()
+                ));
             }
             MEMORY_SIZE => {
-                decrement_fuel!(T::get_flat_cost(MEMORY_SIZE));
+                decrement_fuel!(store.user_data.get_flat_cost(MEMORY_SIZE));
                 // Note: This zero byte is reserved for the multiple memories
                 // proposal.
                 let _zero = wasm.read_u8().unwrap_validated();
@@ -1205,7 +1497,7 @@ pub(super) fn run<T: Config>(
                 // store. Therefore, it is valid in the current store.
                 let mem = unsafe { store.memories.get(mem_addr) };
                 let size = mem.size() as u32;
-                stack.push_value::<T>(Value::I32(size))?;
+                stack.push_value(Value::I32(size))?;
                 trace!("Instruction: memory.size [] -> [{}]", size);
             }
             MEMORY_GROW => {
@@ -1222,459 +1514,480 @@ pub(super) fn run<T: Config>(
                 let mem_addr = *unsafe { module.mem_addrs.get(MemIdx::new(0)) };
                 // SAFETY: This memory address was just read from the current
                 // store. Therefore, it is valid in the current store.
-                let mem = unsafe { store.memories.get_mut(mem_addr) };
+                let mem = unsafe { store.memories.get(mem_addr) };
 
                 let sz: u32 = mem.size() as u32;
 
-                let n: u32 = stack.pop_value().try_into().unwrap_validated();
+                let n: u32 = stack.pop();
                 // decrement fuel, but push n back if it fails
-                let cost = T::get_flat_cost(MEMORY_GROW)
-                    + u64::from(n) * T::get_cost_per_element(MEMORY_GROW);
+                let cost = per_element_cost(
+                    store.user_data.get_flat_cost(MEMORY_GROW),
+                    store.user_data.get_cost_per_element(MEMORY_GROW),
+                    n,
+                );
                 if let Some(fuel) = &mut resumable.maybe_fuel {
-                    if *fuel >= cost {
+                    if let Some(cost) = cost.filter(|cost| *fuel >= *cost) {
                         *fuel -= cost;
+                        store.user_data.fuel_hook(cost);
                     } else {
-                        stack.push_value::<T>(Value::I32(n)).unwrap_validated(); // we are pushing back what was just popped, this can't panic.
+                        stack.push_value(Value::I32(n)).unwrap_validated(); // we are pushing back what was just popped, this can't panic.
                         resumable.current_func_addr = current_func_addr;
                         resumable.pc = prev_pc; // the instruction was fetched already, we roll this back
                         resumable.stp = stp;
                         return Ok(InterpreterLoopOutcome::OutOfFuel {
-                            required_fuel: NonZeroU64::new(cost - *fuel).expect("the last check guarantees that the current fuel is smaller than cost"),
+                            required_fuel: NonZeroU64::new(cost.map_or(u64::MAX, |cost| cost - *fuel)).expect("the last check guarantees that the current fuel is smaller than cost"),
                         });
                     }
                 }
 
-                // TODO this instruction is non-deterministic w.r.t. spec, and can fail if the embedder wills it.
-                // for now we execute it always according to the following match expr.
-                // if the grow operation fails, err := Value::I32(2^32-1) is pushed to the stack per spec
-                let pushed_value = match mem.grow(n) {
-                    Ok(_) => sz,
-                    Err(_) => u32::MAX,
+                // growth may fail non-deterministically if the resource limiter of the config denies it or the
+                // host is out of memory. if the grow operation fails, err := Value::I32(2^32-1) is pushed to the
+                // stack per spec
+                // SAFETY: The memory address was read from the current store above.
+                let pushed_value = match unsafe {
+                    grow_memory(
+                        &mut store.memories,
+                        &mut store.user_data,
+                        &mut store.resource_usage,
+                        mem_addr,
+                        n,
+                    )
+                } {
+                    Ok(()) => sz,
+                    Err(
+                        GrowthFailure::MaximumExceeded
+                        | GrowthFailure::Denied
+                        | GrowthFailure::OutOfMemory,
+                    ) => u32::MAX,
+                    Err(GrowthFailure::Trap) => return Err(TrapError::ResourceLimitExceeded.into()),
                 };
-                stack.push_value::<T>(Value::I32(pushed_value))?;
+                stack.push_value(Value::I32(pushed_value))?;
                 trace!("Instruction: memory.grow [{}] -> [{}]", n, pushed_value);
             }
             I32_CONST => {
-                decrement_fuel!(T::get_flat_cost(I32_CONST));
-                let constant = wasm.read_var_i32().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_CONST));
+                let constant = match current_immediates.take(wasm) {
+                    Some(constant) => constant.cast_signed(),
+                    None => wasm.read_var_i32().unwrap_validated(),
+                };
                 trace!("Instruction: i32.const [] -> [{constant}]");
-                stack.push_value::<T>(constant.into())?;
+                stack.push_value(constant.into())?;
             }
             F32_CONST => {
-                decrement_fuel!(T::get_flat_cost(F32_CONST));
+                decrement_fuel!(store.user_data.get_flat_cost(F32_CONST));
                 let constant = F32::from_bits(wasm.read_f32().unwrap_validated());
                 trace!("Instruction: f32.const [] -> [{constant:.7}]");
-                stack.push_value::<T>(constant.into())?;
+                stack.push_value(constant.into())?;
             }
             I32_EQZ => {
-                decrement_fuel!(T::get_flat_cost(I32_EQZ));
-                let v1: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_EQZ));
+                let v1: i32 = stack.pop();
 
                 let res = if v1 == 0 { 1 } else { 0 };
 
                 trace!("Instruction: i32.eqz [{v1}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_EQ => {
-                decrement_fuel!(T::get_flat_cost(I32_EQ));
-                let v2: i32 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_EQ));
+                let v2: i32 = stack.pop();
+                let v1: i32 = stack.pop();
 
                 let res = if v1 == v2 { 1 } else { 0 };
 
                 trace!("Instruction: i32.eq [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_NE => {
-                decrement_fuel!(T::get_flat_cost(I32_NE));
-                let v2: i32 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_NE));
+                let v2: i32 = stack.pop();
+                let v1: i32 = stack.pop();
 
                 let res = if v1 != v2 { 1 } else { 0 };
 
                 trace!("Instruction: i32.ne [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_LT_S => {
-                decrement_fuel!(T::get_flat_cost(I32_LT_S));
-                let v2: i32 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_LT_S));
+                let v2: i32 = stack.pop();
+                let v1: i32 = stack.pop();
 
                 let res = if v1 < v2 { 1 } else { 0 };
 
                 trace!("Instruction: i32.lt_s [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
 
             I32_LT_U => {
-                decrement_fuel!(T::get_flat_cost(I32_LT_U));
-                let v2: i32 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_LT_U));
+                let v2: i32 = stack.pop();
+                let v1: i32 = stack.pop();
 
                 let res = if (v1 as u32) < (v2 as u32) { 1 } else { 0 };
 
                 trace!("Instruction: i32.lt_u [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_GT_S => {
-                decrement_fuel!(T::get_flat_cost(I32_GT_S));
-                let v2: i32 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_GT_S));
+                let v2: i32 = stack.pop();
+                let v1: i32 = stack.pop();
 
                 let res = if v1 > v2 { 1 } else { 0 };
 
                 trace!("Instruction: i32.gt_s [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_GT_U => {
-                decrement_fuel!(T::get_flat_cost(I32_GT_U));
-                let v2: i32 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_GT_U));
+                let v2: i32 = stack.pop();
+                let v1: i32 = stack.pop();
 
                 let res = if (v1 as u32) > (v2 as u32) { 1 } else { 0 };
 
                 trace!("Instruction: i32.gt_u [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_LE_S => {
-                decrement_fuel!(T::get_flat_cost(I32_LE_S));
-                let v2: i32 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_LE_S));
+                let v2: i32 = stack.pop();
+                let v1: i32 = stack.pop();
 
                 let res = if v1 <= v2 { 1 } else { 0 };
 
                 trace!("Instruction: i32.le_s [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_LE_U => {
-                decrement_fuel!(T::get_flat_cost(I32_LE_U));
-                let v2: i32 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_LE_U));
+                let v2: i32 = stack.pop();
+                let v1: i32 = stack.pop();
 
                 let res = if (v1 as u32) <= (v2 as u32) { 1 } else { 0 };
 
                 trace!("Instruction: i32.le_u [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_GE_S => {
-                decrement_fuel!(T::get_flat_cost(I32_GE_S));
-                let v2: i32 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_GE_S));
+                let v2: i32 = stack.pop();
+                let v1: i32 = stack.pop();
 
                 let res = if v1 >= v2 { 1 } else { 0 };
 
                 trace!("Instruction: i32.ge_s [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_GE_U => {
-                decrement_fuel!(T::get_flat_cost(I32_GE_U));
-                let v2: i32 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_GE_U));
+                let v2: i32 = stack.pop();
+                let v1: i32 = stack.pop();
 
                 let res = if (v1 as u32) >= (v2 as u32) { 1 } else { 0 };
 
                 trace!("Instruction: i32.ge_u [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_EQZ => {
-                decrement_fuel!(T::get_flat_cost(I64_EQZ));
-                let v1: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_EQZ));
+                let v1: i64 = stack.pop();
 
                 let res = if v1 == 0 { 1 } else { 0 };
 
                 trace!("Instruction: i64.eqz [{v1}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_EQ => {
-                decrement_fuel!(T::get_flat_cost(I64_EQ));
-                let v2: i64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_EQ));
+                let v2: i64 = stack.pop();
+                let v1: i64 = stack.pop();
 
                 let res = if v1 == v2 { 1 } else { 0 };
 
                 trace!("Instruction: i64.eq [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_NE => {
-                decrement_fuel!(T::get_flat_cost(I64_NE));
-                let v2: i64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_NE));
+                let v2: i64 = stack.pop();
+                let v1: i64 = stack.pop();
 
                 let res = if v1 != v2 { 1 } else { 0 };
 
                 trace!("Instruction: i64.ne [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_LT_S => {
-                decrement_fuel!(T::get_flat_cost(I64_LT_S));
-                let v2: i64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_LT_S));
+                let v2: i64 = stack.pop();
+                let v1: i64 = stack.pop();
 
                 let res = if v1 < v2 { 1 } else { 0 };
 
                 trace!("Instruction: i64.lt_s [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
 
             I64_LT_U => {
-                decrement_fuel!(T::get_flat_cost(I64_LT_U));
-                let v2: i64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_LT_U));
+                let v2: i64 = stack.pop();
+                let v1: i64 = stack.pop();
 
                 let res = if (v1 as u64) < (v2 as u64) { 1 } else { 0 };
 
                 trace!("Instruction: i64.lt_u [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_GT_S => {
-                decrement_fuel!(T::get_flat_cost(I64_GT_S));
-                let v2: i64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_GT_S));
+                let v2: i64 = stack.pop();
+                let v1: i64 = stack.pop();
 
                 let res = if v1 > v2 { 1 } else { 0 };
 
                 trace!("Instruction: i64.gt_s [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_GT_U => {
-                decrement_fuel!(T::get_flat_cost(I64_GT_U));
-                let v2: i64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_GT_U));
+                let v2: i64 = stack.pop();
+                let v1: i64 = stack.pop();
 
                 let res = if (v1 as u64) > (v2 as u64) { 1 } else { 0 };
 
                 trace!("Instruction: i64.gt_u [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_LE_S => {
-                decrement_fuel!(T::get_flat_cost(I64_LE_S));
-                let v2: i64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_LE_S));
+                let v2: i64 = stack.pop();
+                let v1: i64 = stack.pop();
 
                 let res = if v1 <= v2 { 1 } else { 0 };
 
                 trace!("Instruction: i64.le_s [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_LE_U => {
-                decrement_fuel!(T::get_flat_cost(I64_LE_U));
-                let v2: i64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_LE_U));
+                let v2: i64 = stack.pop();
+                let v1: i64 = stack.pop();
 
                 let res = if (v1 as u64) <= (v2 as u64) { 1 } else { 0 };
 
                 trace!("Instruction: i64.le_u [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_GE_S => {
-                decrement_fuel!(T::get_flat_cost(I64_GE_S));
-                let v2: i64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_GE_S));
+                let v2: i64 = stack.pop();
+                let v1: i64 = stack.pop();
 
                 let res = if v1 >= v2 { 1 } else { 0 };
 
                 trace!("Instruction: i64.ge_s [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_GE_U => {
-                decrement_fuel!(T::get_flat_cost(I64_GE_U));
-                let v2: i64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_GE_U));
+                let v2: i64 = stack.pop();
+                let v1: i64 = stack.pop();
 
                 let res = if (v1 as u64) >= (v2 as u64) { 1 } else { 0 };
 
                 trace!("Instruction: i64.ge_u [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F32_EQ => {
-                decrement_fuel!(T::get_flat_cost(F32_EQ));
-                let v2: value::F32 = stack.pop_value().try_into().unwrap_validated();
-                let v1: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_EQ));
+                let v2: value::F32 = stack.pop();
+                let v1: value::F32 = stack.pop();
 
                 let res = if v1 == v2 { 1 } else { 0 };
 
                 trace!("Instruction: f32.eq [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F32_NE => {
-                decrement_fuel!(T::get_flat_cost(F32_NE));
-                let v2: value::F32 = stack.pop_value().try_into().unwrap_validated();
-                let v1: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_NE));
+                let v2: value::F32 = stack.pop();
+                let v1: value::F32 = stack.pop();
 
                 let res = if v1 != v2 { 1 } else { 0 };
 
                 trace!("Instruction: f32.ne [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F32_LT => {
-                decrement_fuel!(T::get_flat_cost(F32_LT));
-                let v2: value::F32 = stack.pop_value().try_into().unwrap_validated();
-                let v1: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_LT));
+                let v2: value::F32 = stack.pop();
+                let v1: value::F32 = stack.pop();
 
                 let res = if v1 < v2 { 1 } else { 0 };
 
                 trace!("Instruction: f32.lt [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F32_GT => {
-                decrement_fuel!(T::get_flat_cost(F32_GT));
-                let v2: value::F32 = stack.pop_value().try_into().unwrap_validated();
-                let v1: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_GT));
+                let v2: value::F32 = stack.pop();
+                let v1: value::F32 = stack.pop();
 
                 let res = if v1 > v2 { 1 } else { 0 };
 
                 trace!("Instruction: f32.gt [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F32_LE => {
-                decrement_fuel!(T::get_flat_cost(F32_LE));
-                let v2: value::F32 = stack.pop_value().try_into().unwrap_validated();
-                let v1: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_LE));
+                let v2: value::F32 = stack.pop();
+                let v1: value::F32 = stack.pop();
 
                 let res = if v1 <= v2 { 1 } else { 0 };
 
                 trace!("Instruction: f32.le [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F32_GE => {
-                decrement_fuel!(T::get_flat_cost(F32_GE));
-                let v2: value::F32 = stack.pop_value().try_into().unwrap_validated();
-                let v1: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_GE));
+                let v2: value::F32 = stack.pop();
+                let v1: value::F32 = stack.pop();
 
                 let res = if v1 >= v2 { 1 } else { 0 };
 
                 trace!("Instruction: f32.ge [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
 
             F64_EQ => {
-                decrement_fuel!(T::get_flat_cost(F64_EQ));
-                let v2: value::F64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_EQ));
+                let v2: value::F64 = stack.pop();
+                let v1: value::F64 = stack.pop();
 
                 let res = if v1 == v2 { 1 } else { 0 };
 
                 trace!("Instruction: f64.eq [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F64_NE => {
-                decrement_fuel!(T::get_flat_cost(F64_NE));
-                let v2: value::F64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_NE));
+                let v2: value::F64 = stack.pop();
+                let v1: value::F64 = stack.pop();
 
                 let res = if v1 != v2 { 1 } else { 0 };
 
                 trace!("Instruction: f64.ne [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F64_LT => {
-                decrement_fuel!(T::get_flat_cost(F64_LT));
-                let v2: value::F64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_LT));
+                let v2: value::F64 = stack.pop();
+                let v1: value::F64 = stack.pop();
 
                 let res = if v1 < v2 { 1 } else { 0 };
 
                 trace!("Instruction: f64.lt [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F64_GT => {
-                decrement_fuel!(T::get_flat_cost(F64_GT));
-                let v2: value::F64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_GT));
+                let v2: value::F64 = stack.pop();
+                let v1: value::F64 = stack.pop();
 
                 let res = if v1 > v2 { 1 } else { 0 };
 
                 trace!("Instruction: f64.gt [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F64_LE => {
-                decrement_fuel!(T::get_flat_cost(F64_LE));
-                let v2: value::F64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_LE));
+                let v2: value::F64 = stack.pop();
+                let v1: value::F64 = stack.pop();
 
                 let res = if v1 <= v2 { 1 } else { 0 };
 
                 trace!("Instruction: f64.le [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F64_GE => {
-                decrement_fuel!(T::get_flat_cost(F64_GE));
-                let v2: value::F64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_GE));
+                let v2: value::F64 = stack.pop();
+                let v1: value::F64 = stack.pop();
 
                 let res = if v1 >= v2 { 1 } else { 0 };
 
                 trace!("Instruction: f64.ge [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
 
             I32_CLZ => {
-                decrement_fuel!(T::get_flat_cost(I32_CLZ));
-                let v1: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_CLZ));
+                let v1: i32 = stack.pop();
                 let res = v1.leading_zeros() as i32;
 
                 trace!("Instruction: i32.clz [{v1}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_CTZ => {
-                decrement_fuel!(T::get_flat_cost(I32_CTZ));
-                let v1: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_CTZ));
+                let v1: i32 = stack.pop();
                 let res = v1.trailing_zeros() as i32;
 
                 trace!("Instruction: i32.ctz [{v1}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_POPCNT => {
-                decrement_fuel!(T::get_flat_cost(I32_POPCNT));
-                let v1: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_POPCNT));
+                let v1: i32 = stack.pop();
                 let res = v1.count_ones() as i32;
 
                 trace!("Instruction: i32.popcnt [{v1}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_CONST => {
-                decrement_fuel!(T::get_flat_cost(I64_CONST));
+                decrement_fuel!(store.user_data.get_flat_cost(I64_CONST));
                 let constant = wasm.read_var_i64().unwrap_validated();
                 trace!("Instruction: i64.const [] -> [{constant}]");
-                stack.push_value::<T>(constant.into())?;
+                stack.push_value(constant.into())?;
             }
             F64_CONST => {
-                decrement_fuel!(T::get_flat_cost(F64_CONST));
+                decrement_fuel!(store.user_data.get_flat_cost(F64_CONST));
                 let constant = F64::from_bits(wasm.read_f64().unwrap_validated());
                 trace!("Instruction: f64.const [] -> [{constant}]");
-                stack.push_value::<T>(constant.into())?;
+                stack.push_value(constant.into())?;
             }
             I32_ADD => {
-                decrement_fuel!(T::get_flat_cost(I32_ADD));
-                let v1: i32 = stack.pop_value().try_into().unwrap_validated();
-                let v2: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_ADD));
+                let v1: i32 = stack.pop();
+                let v2: i32 = stack.pop();
                 let res = v1.wrapping_add(v2);
 
                 trace!("Instruction: i32.add [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_SUB => {
-                decrement_fuel!(T::get_flat_cost(I32_SUB));
-                let v2: i32 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_SUB));
+                let v2: i32 = stack.pop();
+                let v1: i32 = stack.pop();
                 let res = v1.wrapping_sub(v2);
 
                 trace!("Instruction: i32.sub [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_MUL => {
-                decrement_fuel!(T::get_flat_cost(I32_MUL));
-                let v1: i32 = stack.pop_value().try_into().unwrap_validated();
-                let v2: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_MUL));
+                let v1: i32 = stack.pop();
+                let v2: i32 = stack.pop();
                 let res = v1.wrapping_mul(v2);
 
                 trace!("Instruction: i32.mul [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_DIV_S => {
-                decrement_fuel!(T::get_flat_cost(I32_DIV_S));
-                let dividend: i32 = stack.pop_value().try_into().unwrap_validated();
-                let divisor: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_DIV_S));
+                let dividend: i32 = stack.pop();
+                let divisor: i32 = stack.pop();
 
                 if dividend == 0 {
                     return Err(TrapError::DivideBy0.into());
@@ -1686,12 +1999,12 @@ pub(super) fn run<T: Config>(
                 let res = divisor / dividend;
 
                 trace!("Instruction: i32.div_s [{divisor} {dividend}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_DIV_U => {
-                decrement_fuel!(T::get_flat_cost(I32_DIV_U));
-                let dividend: i32 = stack.pop_value().try_into().unwrap_validated();
-                let divisor: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_DIV_U));
+                let dividend: i32 = stack.pop();
+                let divisor: i32 = stack.pop();
 
                 let dividend = dividend as u32;
                 let divisor = divisor as u32;
@@ -1703,12 +2016,12 @@ pub(super) fn run<T: Config>(
                 let res = (divisor / dividend) as i32;
 
                 trace!("Instruction: i32.div_u [{divisor} {dividend}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_REM_S => {
-                decrement_fuel!(T::get_flat_cost(I32_REM_S));
-                let dividend: i32 = stack.pop_value().try_into().unwrap_validated();
-                let divisor: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_REM_S));
+                let dividend: i32 = stack.pop();
+                let divisor: i32 = stack.pop();
 
                 if dividend == 0 {
                     return Err(TrapError::DivideBy0.into());
@@ -1718,63 +2031,63 @@ pub(super) fn run<T: Config>(
                 let res = res.unwrap_or_default();
 
                 trace!("Instruction: i32.rem_s [{divisor} {dividend}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_CLZ => {
-                decrement_fuel!(T::get_flat_cost(I64_CLZ));
-                let v1: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_CLZ));
+                let v1: i64 = stack.pop();
                 let res = v1.leading_zeros() as i64;
 
                 trace!("Instruction: i64.clz [{v1}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_CTZ => {
-                decrement_fuel!(T::get_flat_cost(I64_CTZ));
-                let v1: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_CTZ));
+                let v1: i64 = stack.pop();
                 let res = v1.trailing_zeros() as i64;
 
                 trace!("Instruction: i64.ctz [{v1}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_POPCNT => {
-                decrement_fuel!(T::get_flat_cost(I64_POPCNT));
-                let v1: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_POPCNT));
+                let v1: i64 = stack.pop();
                 let res = v1.count_ones() as i64;
 
                 trace!("Instruction: i64.popcnt [{v1}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_ADD => {
-                decrement_fuel!(T::get_flat_cost(I64_ADD));
-                let v1: i64 = stack.pop_value().try_into().unwrap_validated();
-                let v2: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_ADD));
+                let v1: i64 = stack.pop();
+                let v2: i64 = stack.pop();
                 let res = v1.wrapping_add(v2);
 
                 trace!("Instruction: i64.add [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_SUB => {
-                decrement_fuel!(T::get_flat_cost(I64_SUB));
-                let v2: i64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_SUB));
+                let v2: i64 = stack.pop();
+                let v1: i64 = stack.pop();
                 let res = v1.wrapping_sub(v2);
 
                 trace!("Instruction: i64.sub [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_MUL => {
-                decrement_fuel!(T::get_flat_cost(I64_MUL));
-                let v1: i64 = stack.pop_value().try_into().unwrap_validated();
-                let v2: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_MUL));
+                let v1: i64 = stack.pop();
+                let v2: i64 = stack.pop();
                 let res = v1.wrapping_mul(v2);
 
                 trace!("Instruction: i64.mul [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_DIV_S => {
-                decrement_fuel!(T::get_flat_cost(I64_DIV_S));
-                let dividend: i64 = stack.pop_value().try_into().unwrap_validated();
-                let divisor: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_DIV_S));
+                let dividend: i64 = stack.pop();
+                let divisor: i64 = stack.pop();
 
                 if dividend == 0 {
                     return Err(TrapError::DivideBy0.into());
@@ -1786,12 +2099,12 @@ pub(super) fn run<T: Config>(
                 let res = divisor / dividend;
 
                 trace!("Instruction: i64.div_s [{divisor} {dividend}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_DIV_U => {
-                decrement_fuel!(T::get_flat_cost(I64_DIV_U));
-                let dividend: i64 = stack.pop_value().try_into().unwrap_validated();
-                let divisor: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_DIV_U));
+                let dividend: i64 = stack.pop();
+                let divisor: i64 = stack.pop();
 
                 let dividend = dividend as u64;
                 let divisor = divisor as u64;
@@ -1803,12 +2116,12 @@ pub(super) fn run<T: Config>(
                 let res = (divisor / dividend) as i64;
 
                 trace!("Instruction: i64.div_u [{divisor} {dividend}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_REM_S => {
-                decrement_fuel!(T::get_flat_cost(I64_REM_S));
-                let dividend: i64 = stack.pop_value().try_into().unwrap_validated();
-                let divisor: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_REM_S));
+                let dividend: i64 = stack.pop();
+                let divisor: i64 = stack.pop();
 
                 if dividend == 0 {
                     return Err(TrapError::DivideBy0.into());
@@ -1818,12 +2131,12 @@ pub(super) fn run<T: Config>(
                 let res = res.unwrap_or_default();
 
                 trace!("Instruction: i64.rem_s [{divisor} {dividend}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_REM_U => {
-                decrement_fuel!(T::get_flat_cost(I64_REM_U));
-                let dividend: i64 = stack.pop_value().try_into().unwrap_validated();
-                let divisor: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_REM_U));
+                let dividend: i64 = stack.pop();
+                let divisor: i64 = stack.pop();
 
                 let dividend = dividend as u64;
                 let divisor = divisor as u64;
@@ -1835,92 +2148,92 @@ pub(super) fn run<T: Config>(
                 let res = (divisor % dividend) as i64;
 
                 trace!("Instruction: i64.rem_u [{divisor} {dividend}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_AND => {
-                decrement_fuel!(T::get_flat_cost(I64_AND));
-                let v2: i64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_AND));
+                let v2: i64 = stack.pop();
+                let v1: i64 = stack.pop();
 
                 let res = v1 & v2;
 
                 trace!("Instruction: i64.and [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_OR => {
-                decrement_fuel!(T::get_flat_cost(I64_OR));
-                let v2: i64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_OR));
+                let v2: i64 = stack.pop();
+                let v1: i64 = stack.pop();
 
                 let res = v1 | v2;
 
                 trace!("Instruction: i64.or [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_XOR => {
-                decrement_fuel!(T::get_flat_cost(I64_XOR));
-                let v2: i64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_XOR));
+                let v2: i64 = stack.pop();
+                let v1: i64 = stack.pop();
 
                 let res = v1 ^ v2;
 
                 trace!("Instruction: i64.xor [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_SHL => {
-                decrement_fuel!(T::get_flat_cost(I64_SHL));
-                let v2: i64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_SHL));
+                let v2: i64 = stack.pop();
+                let v1: i64 = stack.pop();
 
                 let res = v1.wrapping_shl((v2 & 63) as u32);
 
                 trace!("Instruction: i64.shl [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_SHR_S => {
-                decrement_fuel!(T::get_flat_cost(I64_SHR_S));
-                let v2: i64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_SHR_S));
+                let v2: i64 = stack.pop();
+                let v1: i64 = stack.pop();
 
                 let res = v1.wrapping_shr((v2 & 63) as u32);
 
                 trace!("Instruction: i64.shr_s [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_SHR_U => {
-                decrement_fuel!(T::get_flat_cost(I64_SHR_U));
-                let v2: i64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_SHR_U));
+                let v2: i64 = stack.pop();
+                let v1: i64 = stack.pop();
 
                 let res = (v1 as u64).wrapping_shr((v2 & 63) as u32);
 
                 trace!("Instruction: i64.shr_u [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_ROTL => {
-                decrement_fuel!(T::get_flat_cost(I64_ROTL));
-                let v2: i64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_ROTL));
+                let v2: i64 = stack.pop();
+                let v1: i64 = stack.pop();
 
                 let res = v1.rotate_left((v2 & 63) as u32);
 
                 trace!("Instruction: i64.rotl [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_ROTR => {
-                decrement_fuel!(T::get_flat_cost(I64_ROTR));
-                let v2: i64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_ROTR));
+                let v2: i64 = stack.pop();
+                let v1: i64 = stack.pop();
 
                 let res = v1.rotate_right((v2 & 63) as u32);
 
                 trace!("Instruction: i64.rotr [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_REM_U => {
-                decrement_fuel!(T::get_flat_cost(I32_REM_U));
-                let dividend: i32 = stack.pop_value().try_into().unwrap_validated();
-                let divisor: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_REM_U));
+                let dividend: i32 = stack.pop();
+                let divisor: i32 = stack.pop();
 
                 let dividend = dividend as u32;
                 let divisor = divisor as u32;
@@ -1933,335 +2246,335 @@ pub(super) fn run<T: Config>(
                 let res = res.unwrap_or_default() as i32;
 
                 trace!("Instruction: i32.rem_u [{divisor} {dividend}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_AND => {
-                decrement_fuel!(T::get_flat_cost(I32_AND));
-                let v1: i32 = stack.pop_value().try_into().unwrap_validated();
-                let v2: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_AND));
+                let v1: i32 = stack.pop();
+                let v2: i32 = stack.pop();
                 let res = v1 & v2;
 
                 trace!("Instruction: i32.and [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_OR => {
-                decrement_fuel!(T::get_flat_cost(I32_OR));
-                let v1: i32 = stack.pop_value().try_into().unwrap_validated();
-                let v2: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_OR));
+                let v1: i32 = stack.pop();
+                let v2: i32 = stack.pop();
                 let res = v1 | v2;
 
                 trace!("Instruction: i32.or [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_XOR => {
-                decrement_fuel!(T::get_flat_cost(I32_XOR));
-                let v1: i32 = stack.pop_value().try_into().unwrap_validated();
-                let v2: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_XOR));
+                let v1: i32 = stack.pop();
+                let v2: i32 = stack.pop();
                 let res = v1 ^ v2;
 
                 trace!("Instruction: i32.xor [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_SHL => {
-                decrement_fuel!(T::get_flat_cost(I32_SHL));
-                let v1: i32 = stack.pop_value().try_into().unwrap_validated();
-                let v2: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_SHL));
+                let v1: i32 = stack.pop();
+                let v2: i32 = stack.pop();
                 let res = v2.wrapping_shl(v1 as u32);
 
                 trace!("Instruction: i32.shl [{v2} {v1}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_SHR_S => {
-                decrement_fuel!(T::get_flat_cost(I32_SHR_S));
-                let v1: i32 = stack.pop_value().try_into().unwrap_validated();
-                let v2: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_SHR_S));
+                let v1: i32 = stack.pop();
+                let v2: i32 = stack.pop();
 
                 let res = v2.wrapping_shr(v1 as u32);
 
                 trace!("Instruction: i32.shr_s [{v2} {v1}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_SHR_U => {
-                decrement_fuel!(T::get_flat_cost(I32_SHR_U));
-                let v1: i32 = stack.pop_value().try_into().unwrap_validated();
-                let v2: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_SHR_U));
+                let v1: i32 = stack.pop();
+                let v2: i32 = stack.pop();
 
                 let res = (v2 as u32).wrapping_shr(v1 as u32) as i32;
 
                 trace!("Instruction: i32.shr_u [{v2} {v1}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_ROTL => {
-                decrement_fuel!(T::get_flat_cost(I32_ROTL));
-                let v1: i32 = stack.pop_value().try_into().unwrap_validated();
-                let v2: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_ROTL));
+                let v1: i32 = stack.pop();
+                let v2: i32 = stack.pop();
 
                 let res = v2.rotate_left(v1 as u32);
 
                 trace!("Instruction: i32.rotl [{v2} {v1}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_ROTR => {
-                decrement_fuel!(T::get_flat_cost(I32_ROTR));
-                let v1: i32 = stack.pop_value().try_into().unwrap_validated();
-                let v2: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_ROTR));
+                let v1: i32 = stack.pop();
+                let v2: i32 = stack.pop();
 
                 let res = v2.rotate_right(v1 as u32);
 
                 trace!("Instruction: i32.rotr [{v2} {v1}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
 
             F32_ABS => {
-                decrement_fuel!(T::get_flat_cost(F32_ABS));
-                let v1: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_ABS));
+                let v1: value::F32 = stack.pop();
                 let res: value::F32 = v1.abs();
 
                 trace!("Instruction: f32.abs [{v1}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F32_NEG => {
-                decrement_fuel!(T::get_flat_cost(F32_NEG));
-                let v1: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_NEG));
+                let v1: value::F32 = stack.pop();
                 let res: value::F32 = v1.neg();
 
                 trace!("Instruction: f32.neg [{v1}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F32_CEIL => {
-                decrement_fuel!(T::get_flat_cost(F32_CEIL));
-                let v1: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_CEIL));
+                let v1: value::F32 = stack.pop();
                 let res: value::F32 = v1.ceil();
 
                 trace!("Instruction: f32.ceil [{v1}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F32_FLOOR => {
-                decrement_fuel!(T::get_flat_cost(F32_FLOOR));
-                let v1: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_FLOOR));
+                let v1: value::F32 = stack.pop();
                 let res: value::F32 = v1.floor();
 
                 trace!("Instruction: f32.floor [{v1}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F32_TRUNC => {
-                decrement_fuel!(T::get_flat_cost(F32_TRUNC));
-                let v1: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_TRUNC));
+                let v1: value::F32 = stack.pop();
                 let res: value::F32 = v1.trunc();
 
                 trace!("Instruction: f32.trunc [{v1}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F32_NEAREST => {
-                decrement_fuel!(T::get_flat_cost(F32_NEAREST));
-                let v1: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_NEAREST));
+                let v1: value::F32 = stack.pop();
                 let res: value::F32 = v1.nearest();
 
                 trace!("Instruction: f32.nearest [{v1}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F32_SQRT => {
-                decrement_fuel!(T::get_flat_cost(F32_SQRT));
-                let v1: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_SQRT));
+                let v1: value::F32 = stack.pop();
                 let res: value::F32 = v1.sqrt();
 
                 trace!("Instruction: f32.sqrt [{v1}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F32_ADD => {
-                decrement_fuel!(T::get_flat_cost(F32_ADD));
-                let v2: value::F32 = stack.pop_value().try_into().unwrap_validated();
-                let v1: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_ADD));
+                let v2: value::F32 = stack.pop();
+                let v1: value::F32 = stack.pop();
                 let res: value::F32 = v1 + v2;
 
                 trace!("Instruction: f32.add [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F32_SUB => {
-                decrement_fuel!(T::get_flat_cost(F32_SUB));
-                let v2: value::F32 = stack.pop_value().try_into().unwrap_validated();
-                let v1: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_SUB));
+                let v2: value::F32 = stack.pop();
+                let v1: value::F32 = stack.pop();
                 let res: value::F32 = v1 - v2;
 
                 trace!("Instruction: f32.sub [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F32_MUL => {
-                decrement_fuel!(T::get_flat_cost(F32_MUL));
-                let v2: value::F32 = stack.pop_value().try_into().unwrap_validated();
-                let v1: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_MUL));
+                let v2: value::F32 = stack.pop();
+                let v1: value::F32 = stack.pop();
                 let res: value::F32 = v1 * v2;
 
                 trace!("Instruction: f32.mul [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F32_DIV => {
-                decrement_fuel!(T::get_flat_cost(F32_DIV));
-                let v2: value::F32 = stack.pop_value().try_into().unwrap_validated();
-                let v1: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_DIV));
+                let v2: value::F32 = stack.pop();
+                let v1: value::F32 = stack.pop();
                 let res: value::F32 = v1 / v2;
 
                 trace!("Instruction: f32.div [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F32_MIN => {
-                decrement_fuel!(T::get_flat_cost(F32_MIN));
-                let v2: value::F32 = stack.pop_value().try_into().unwrap_validated();
-                let v1: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_MIN));
+                let v2: value::F32 = stack.pop();
+                let v1: value::F32 = stack.pop();
                 let res: value::F32 = v1.min(v2);
 
                 trace!("Instruction: f32.min [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F32_MAX => {
-                decrement_fuel!(T::get_flat_cost(F32_MAX));
-                let v2: value::F32 = stack.pop_value().try_into().unwrap_validated();
-                let v1: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_MAX));
+                let v2: value::F32 = stack.pop();
+                let v1: value::F32 = stack.pop();
                 let res: value::F32 = v1.max(v2);
 
                 trace!("Instruction: f32.max [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F32_COPYSIGN => {
-                decrement_fuel!(T::get_flat_cost(F32_COPYSIGN));
-                let v2: value::F32 = stack.pop_value().try_into().unwrap_validated();
-                let v1: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_COPYSIGN));
+                let v2: value::F32 = stack.pop();
+                let v1: value::F32 = stack.pop();
                 let res: value::F32 = v1.copysign(v2);
 
                 trace!("Instruction: f32.copysign [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
 
             F64_ABS => {
-                decrement_fuel!(T::get_flat_cost(F64_ABS));
-                let v1: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_ABS));
+                let v1: value::F64 = stack.pop();
                 let res: value::F64 = v1.abs();
 
                 trace!("Instruction: f64.abs [{v1}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F64_NEG => {
-                decrement_fuel!(T::get_flat_cost(F64_NEG));
-                let v1: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_NEG));
+                let v1: value::F64 = stack.pop();
                 let res: value::F64 = v1.neg();
 
                 trace!("Instruction: f64.neg [{v1}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F64_CEIL => {
-                decrement_fuel!(T::get_flat_cost(F64_CEIL));
-                let v1: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_CEIL));
+                let v1: value::F64 = stack.pop();
                 let res: value::F64 = v1.ceil();
 
                 trace!("Instruction: f64.ceil [{v1}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F64_FLOOR => {
-                decrement_fuel!(T::get_flat_cost(F64_FLOOR));
-                let v1: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_FLOOR));
+                let v1: value::F64 = stack.pop();
                 let res: value::F64 = v1.floor();
 
                 trace!("Instruction: f64.floor [{v1}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F64_TRUNC => {
-                decrement_fuel!(T::get_flat_cost(F64_TRUNC));
-                let v1: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_TRUNC));
+                let v1: value::F64 = stack.pop();
                 let res: value::F64 = v1.trunc();
 
                 trace!("Instruction: f64.trunc [{v1}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F64_NEAREST => {
-                decrement_fuel!(T::get_flat_cost(F64_NEAREST));
-                let v1: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_NEAREST));
+                let v1: value::F64 = stack.pop();
                 let res: value::F64 = v1.nearest();
 
                 trace!("Instruction: f64.nearest [{v1}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F64_SQRT => {
-                decrement_fuel!(T::get_flat_cost(F64_SQRT));
-                let v1: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_SQRT));
+                let v1: value::F64 = stack.pop();
                 let res: value::F64 = v1.sqrt();
 
                 trace!("Instruction: f64.sqrt [{v1}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F64_ADD => {
-                decrement_fuel!(T::get_flat_cost(F64_ADD));
-                let v2: value::F64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_ADD));
+                let v2: value::F64 = stack.pop();
+                let v1: value::F64 = stack.pop();
                 let res: value::F64 = v1 + v2;
 
                 trace!("Instruction: f64.add [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F64_SUB => {
-                decrement_fuel!(T::get_flat_cost(F64_SUB));
-                let v2: value::F64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_SUB));
+                let v2: value::F64 = stack.pop();
+                let v1: value::F64 = stack.pop();
                 let res: value::F64 = v1 - v2;
 
                 trace!("Instruction: f64.sub [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F64_MUL => {
-                decrement_fuel!(T::get_flat_cost(F64_MUL));
-                let v2: value::F64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_MUL));
+                let v2: value::F64 = stack.pop();
+                let v1: value::F64 = stack.pop();
                 let res: value::F64 = v1 * v2;
 
                 trace!("Instruction: f64.mul [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F64_DIV => {
-                decrement_fuel!(T::get_flat_cost(F64_DIV));
-                let v2: value::F64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_DIV));
+                let v2: value::F64 = stack.pop();
+                let v1: value::F64 = stack.pop();
                 let res: value::F64 = v1 / v2;
 
                 trace!("Instruction: f64.div [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F64_MIN => {
-                decrement_fuel!(T::get_flat_cost(F64_MIN));
-                let v2: value::F64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_MIN));
+                let v2: value::F64 = stack.pop();
+                let v1: value::F64 = stack.pop();
                 let res: value::F64 = v1.min(v2);
 
                 trace!("Instruction: f64.min [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F64_MAX => {
-                decrement_fuel!(T::get_flat_cost(F64_MAX));
-                let v2: value::F64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_MAX));
+                let v2: value::F64 = stack.pop();
+                let v1: value::F64 = stack.pop();
                 let res: value::F64 = v1.max(v2);
 
                 trace!("Instruction: f64.max [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F64_COPYSIGN => {
-                decrement_fuel!(T::get_flat_cost(F64_COPYSIGN));
-                let v2: value::F64 = stack.pop_value().try_into().unwrap_validated();
-                let v1: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_COPYSIGN));
+                let v2: value::F64 = stack.pop();
+                let v1: value::F64 = stack.pop();
                 let res: value::F64 = v1.copysign(v2);
 
                 trace!("Instruction: f64.copysign [{v1} {v2}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_WRAP_I64 => {
-                decrement_fuel!(T::get_flat_cost(I32_WRAP_I64));
-                let v: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_WRAP_I64));
+                let v: i64 = stack.pop();
                 let res: i32 = v as i32;
 
                 trace!("Instruction: i32.wrap_i64 [{v}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_TRUNC_F32_S => {
-                decrement_fuel!(T::get_flat_cost(I32_TRUNC_F32_S));
-                let v: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_TRUNC_F32_S));
+                let v: value::F32 = stack.pop();
                 if v.is_infinity() {
                     return Err(TrapError::UnrepresentableResult.into());
                 }
@@ -2275,11 +2588,11 @@ pub(super) fn run<T: Config>(
                 let res: i32 = v.as_i32();
 
                 trace!("Instruction: i32.trunc_f32_s [{v:.7}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_TRUNC_F32_U => {
-                decrement_fuel!(T::get_flat_cost(I32_TRUNC_F32_U));
-                let v: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_TRUNC_F32_U));
+                let v: value::F32 = stack.pop();
                 if v.is_infinity() {
                     return Err(TrapError::UnrepresentableResult.into());
                 }
@@ -2293,12 +2606,12 @@ pub(super) fn run<T: Config>(
                 let res: i32 = v.as_u32() as i32;
 
                 trace!("Instruction: i32.trunc_f32_u [{v:.7}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
 
             I32_TRUNC_F64_S => {
-                decrement_fuel!(T::get_flat_cost(I32_TRUNC_F64_S));
-                let v: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_TRUNC_F64_S));
+                let v: value::F64 = stack.pop();
                 if v.is_infinity() {
                     return Err(TrapError::UnrepresentableResult.into());
                 }
@@ -2312,11 +2625,11 @@ pub(super) fn run<T: Config>(
                 let res: i32 = v.as_i32();
 
                 trace!("Instruction: i32.trunc_f64_s [{v:.7}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_TRUNC_F64_U => {
-                decrement_fuel!(T::get_flat_cost(I32_TRUNC_F64_U));
-                let v: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_TRUNC_F64_U));
+                let v: value::F64 = stack.pop();
                 if v.is_infinity() {
                     return Err(TrapError::UnrepresentableResult.into());
                 }
@@ -2330,32 +2643,32 @@ pub(super) fn run<T: Config>(
                 let res: i32 = v.as_u32() as i32;
 
                 trace!("Instruction: i32.trunc_f32_u [{v:.7}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
 
             I64_EXTEND_I32_S => {
-                decrement_fuel!(T::get_flat_cost(I64_EXTEND_I32_S));
-                let v: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_EXTEND_I32_S));
+                let v: i32 = stack.pop();
 
                 let res: i64 = v as i64;
 
                 trace!("Instruction: i64.extend_i32_s [{v}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
 
             I64_EXTEND_I32_U => {
-                decrement_fuel!(T::get_flat_cost(I64_EXTEND_I32_U));
-                let v: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_EXTEND_I32_U));
+                let v: i32 = stack.pop();
 
                 let res: i64 = v as u32 as i64;
 
                 trace!("Instruction: i64.extend_i32_u [{v}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
 
             I64_TRUNC_F32_S => {
-                decrement_fuel!(T::get_flat_cost(I64_TRUNC_F32_S));
-                let v: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_TRUNC_F32_S));
+                let v: value::F32 = stack.pop();
                 if v.is_infinity() {
                     return Err(TrapError::UnrepresentableResult.into());
                 }
@@ -2370,11 +2683,11 @@ pub(super) fn run<T: Config>(
                 let res: i64 = v.as_i64();
 
                 trace!("Instruction: i64.trunc_f32_s [{v:.7}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_TRUNC_F32_U => {
-                decrement_fuel!(T::get_flat_cost(I64_TRUNC_F32_U));
-                let v: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_TRUNC_F32_U));
+                let v: value::F32 = stack.pop();
                 if v.is_infinity() {
                     return Err(TrapError::UnrepresentableResult.into());
                 }
@@ -2388,12 +2701,12 @@ pub(super) fn run<T: Config>(
                 let res: i64 = v.as_u64() as i64;
 
                 trace!("Instruction: i64.trunc_f32_u [{v:.7}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
 
             I64_TRUNC_F64_S => {
-                decrement_fuel!(T::get_flat_cost(I64_TRUNC_F64_S));
-                let v: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_TRUNC_F64_S));
+                let v: value::F64 = stack.pop();
                 if v.is_infinity() {
                     return Err(TrapError::UnrepresentableResult.into());
                 }
@@ -2408,11 +2721,11 @@ pub(super) fn run<T: Config>(
                 let res: i64 = v.as_i64();
 
                 trace!("Instruction: i64.trunc_f64_s [{v:.17}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_TRUNC_F64_U => {
-                decrement_fuel!(T::get_flat_cost(I64_TRUNC_F64_U));
-                let v: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_TRUNC_F64_U));
+                let v: value::F64 = stack.pop();
                 if v.is_infinity() {
                     return Err(TrapError::UnrepresentableResult.into());
                 }
@@ -2426,139 +2739,139 @@ pub(super) fn run<T: Config>(
                 let res: i64 = v.as_u64() as i64;
 
                 trace!("Instruction: i64.trunc_f64_u [{v:.17}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F32_CONVERT_I32_S => {
-                decrement_fuel!(T::get_flat_cost(F32_CONVERT_I32_S));
-                let v: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_CONVERT_I32_S));
+                let v: i32 = stack.pop();
                 let res: value::F32 = value::F32(v as f32);
 
                 trace!("Instruction: f32.convert_i32_s [{v}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F32_CONVERT_I32_U => {
-                decrement_fuel!(T::get_flat_cost(F32_CONVERT_I32_U));
-                let v: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_CONVERT_I32_U));
+                let v: i32 = stack.pop();
                 let res: value::F32 = value::F32(v as u32 as f32);
 
                 trace!("Instruction: f32.convert_i32_u [{v}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F32_CONVERT_I64_S => {
-                decrement_fuel!(T::get_flat_cost(F32_CONVERT_I64_S));
-                let v: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_CONVERT_I64_S));
+                let v: i64 = stack.pop();
                 let res: value::F32 = value::F32(v as f32);
 
                 trace!("Instruction: f32.convert_i64_s [{v}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F32_CONVERT_I64_U => {
-                decrement_fuel!(T::get_flat_cost(F32_CONVERT_I64_U));
-                let v: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_CONVERT_I64_U));
+                let v: i64 = stack.pop();
                 let res: value::F32 = value::F32(v as u64 as f32);
 
                 trace!("Instruction: f32.convert_i64_u [{v}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F32_DEMOTE_F64 => {
-                decrement_fuel!(T::get_flat_cost(F32_DEMOTE_F64));
-                let v: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_DEMOTE_F64));
+                let v: value::F64 = stack.pop();
                 let res: value::F32 = v.as_f32();
 
                 trace!("Instruction: f32.demote_f64 [{v:.17}] -> [{res:.7}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F64_CONVERT_I32_S => {
-                decrement_fuel!(T::get_flat_cost(F64_CONVERT_I32_S));
-                let v: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_CONVERT_I32_S));
+                let v: i32 = stack.pop();
                 let res: value::F64 = value::F64(v as f64);
 
                 trace!("Instruction: f64.convert_i32_s [{v}] -> [{res:.17}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F64_CONVERT_I32_U => {
-                decrement_fuel!(T::get_flat_cost(F64_CONVERT_I32_U));
-                let v: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_CONVERT_I32_U));
+                let v: i32 = stack.pop();
                 let res: value::F64 = value::F64(v as u32 as f64);
 
                 trace!("Instruction: f64.convert_i32_u [{v}] -> [{res:.17}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F64_CONVERT_I64_S => {
-                decrement_fuel!(T::get_flat_cost(F64_CONVERT_I64_S));
-                let v: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_CONVERT_I64_S));
+                let v: i64 = stack.pop();
                 let res: value::F64 = value::F64(v as f64);
 
                 trace!("Instruction: f64.convert_i64_s [{v}] -> [{res:.17}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F64_CONVERT_I64_U => {
-                decrement_fuel!(T::get_flat_cost(F64_CONVERT_I64_U));
-                let v: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_CONVERT_I64_U));
+                let v: i64 = stack.pop();
                 let res: value::F64 = value::F64(v as u64 as f64);
 
                 trace!("Instruction: f64.convert_i64_u [{v}] -> [{res:.17}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F64_PROMOTE_F32 => {
-                decrement_fuel!(T::get_flat_cost(F64_PROMOTE_F32));
-                let v: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_PROMOTE_F32));
+                let v: value::F32 = stack.pop();
                 let res: value::F64 = v.as_f64();
 
                 trace!("Instruction: f64.promote_f32 [{v:.7}] -> [{res:.17}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I32_REINTERPRET_F32 => {
-                decrement_fuel!(T::get_flat_cost(I32_REINTERPRET_F32));
-                let v: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_REINTERPRET_F32));
+                let v: value::F32 = stack.pop();
                 let res: i32 = v.reinterpret_as_i32();
 
                 trace!("Instruction: i32.reinterpret_f32 [{v:.7}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             I64_REINTERPRET_F64 => {
-                decrement_fuel!(T::get_flat_cost(I64_REINTERPRET_F64));
-                let v: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_REINTERPRET_F64));
+                let v: value::F64 = stack.pop();
                 let res: i64 = v.reinterpret_as_i64();
 
                 trace!("Instruction: i64.reinterpret_f64 [{v:.17}] -> [{res}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F32_REINTERPRET_I32 => {
-                decrement_fuel!(T::get_flat_cost(F32_REINTERPRET_I32));
-                let v1: i32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F32_REINTERPRET_I32));
+                let v1: i32 = stack.pop();
                 let res: value::F32 = value::F32::from_bits(v1 as u32);
 
                 trace!("Instruction: f32.reinterpret_i32 [{v1}] -> [{res:.7}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             F64_REINTERPRET_I64 => {
-                decrement_fuel!(T::get_flat_cost(F64_REINTERPRET_I64));
-                let v1: i64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(F64_REINTERPRET_I64));
+                let v1: i64 = stack.pop();
                 let res: value::F64 = value::F64::from_bits(v1 as u64);
 
                 trace!("Instruction: f64.reinterpret_i64 [{v1}] -> [{res:.17}]");
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
             }
             REF_NULL => {
-                decrement_fuel!(T::get_flat_cost(REF_NULL));
+                decrement_fuel!(store.user_data.get_flat_cost(REF_NULL));
                 let reftype = RefType::read(wasm).unwrap_validated();
 
-                stack.push_value::<T>(Value::Ref(Ref::Null(reftype)))?;
+                stack.push_value(Value::Ref(Ref::Null(reftype)))?;
                 trace!("Instruction: ref.null '{:?}' -> [{:?}]", reftype, reftype);
             }
             REF_IS_NULL => {
-                decrement_fuel!(T::get_flat_cost(REF_IS_NULL));
-                let rref: Ref = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(REF_IS_NULL));
+                let rref: Ref = stack.pop();
                 let is_null = matches!(rref, Ref::Null(_));
 
                 let res = if is_null { 1 } else { 0 };
                 trace!("Instruction: ref.is_null [{}] -> [{}]", rref, res);
-                stack.push_value::<T>(Value::I32(res))?;
+                stack.push_value(Value::I32(res))?;
             }
             // https://webassembly.github.io/spec/core/exec/instructions.html#xref-syntax-instructions-syntax-instr-ref-mathsf-ref-func-x
             REF_FUNC => {
-                decrement_fuel!(T::get_flat_cost(REF_FUNC));
+                decrement_fuel!(store.user_data.get_flat_cost(REF_FUNC));
                 // SAFETY: Validation guarantees a valid function index to be
                 // next.
                 let func_idx = unsafe { FuncIdx::read_unchecked(wasm) };
@@ -2571,7 +2884,7 @@ pub(super) fn run<T: Config>(
                 // SAFETY: Validation guarantees the function index to be valid
                 // in the current module.
                 let func_addr = unsafe { current_module.func_addrs.get(func_idx) };
-                stack.push_value::<T>(Value::Ref(Ref::Func(*func_addr)))?;
+                stack.push_value(Value::Ref(Ref::Func(*func_addr)))?;
             }
             FC_EXTENSIONS => {
                 // Should we call instruction hook here as well? Multibyte instruction
@@ -2580,8 +2893,10 @@ pub(super) fn run<T: Config>(
                 use crate::core::reader::types::opcode::fc_extensions::*;
                 match second_instr {
                     I32_TRUNC_SAT_F32_S => {
-                        decrement_fuel!(T::get_fc_extension_flat_cost(I32_TRUNC_SAT_F32_S));
-                        let v1: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fc_extension_flat_cost(I32_TRUNC_SAT_F32_S));
+                        let v1: value::F32 = stack.pop();
                         let res = {
                             if v1.is_nan() {
                                 0
@@ -2595,11 +2910,13 @@ pub(super) fn run<T: Config>(
                         };
 
                         trace!("Instruction: i32.trunc_sat_f32_s [{v1}] -> [{res}]");
-                        stack.push_value::<T>(res.into())?;
+                        stack.push_value(res.into())?;
                     }
                     I32_TRUNC_SAT_F32_U => {
-                        decrement_fuel!(T::get_fc_extension_flat_cost(I32_TRUNC_SAT_F32_U));
-                        let v1: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fc_extension_flat_cost(I32_TRUNC_SAT_F32_U));
+                        let v1: value::F32 = stack.pop();
                         let res = {
                             if v1.is_nan() || v1.is_negative_infinity() {
                                 0
@@ -2611,11 +2928,13 @@ pub(super) fn run<T: Config>(
                         };
 
                         trace!("Instruction: i32.trunc_sat_f32_u [{v1}] -> [{res}]");
-                        stack.push_value::<T>(res.into())?;
+                        stack.push_value(res.into())?;
                     }
                     I32_TRUNC_SAT_F64_S => {
-                        decrement_fuel!(T::get_fc_extension_flat_cost(I32_TRUNC_SAT_F64_S));
-                        let v1: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fc_extension_flat_cost(I32_TRUNC_SAT_F64_S));
+                        let v1: value::F64 = stack.pop();
                         let res = {
                             if v1.is_nan() {
                                 0
@@ -2629,11 +2948,13 @@ pub(super) fn run<T: Config>(
                         };
 
                         trace!("Instruction: i32.trunc_sat_f64_s [{v1}] -> [{res}]");
-                        stack.push_value::<T>(res.into())?;
+                        stack.push_value(res.into())?;
                     }
                     I32_TRUNC_SAT_F64_U => {
-                        decrement_fuel!(T::get_fc_extension_flat_cost(I32_TRUNC_SAT_F64_U));
-                        let v1: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fc_extension_flat_cost(I32_TRUNC_SAT_F64_U));
+                        let v1: value::F64 = stack.pop();
                         let res = {
                             if v1.is_nan() || v1.is_negative_infinity() {
                                 0
@@ -2645,11 +2966,13 @@ pub(super) fn run<T: Config>(
                         };
 
                         trace!("Instruction: i32.trunc_sat_f64_u [{v1}] -> [{res}]");
-                        stack.push_value::<T>(res.into())?;
+                        stack.push_value(res.into())?;
                     }
                     I64_TRUNC_SAT_F32_S => {
-                        decrement_fuel!(T::get_fc_extension_flat_cost(I64_TRUNC_SAT_F32_S));
-                        let v1: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fc_extension_flat_cost(I64_TRUNC_SAT_F32_S));
+                        let v1: value::F32 = stack.pop();
                         let res = {
                             if v1.is_nan() {
                                 0
@@ -2663,11 +2986,13 @@ pub(super) fn run<T: Config>(
                         };
 
                         trace!("Instruction: i64.trunc_sat_f32_s [{v1}] -> [{res}]");
-                        stack.push_value::<T>(res.into())?;
+                        stack.push_value(res.into())?;
                     }
                     I64_TRUNC_SAT_F32_U => {
-                        decrement_fuel!(T::get_fc_extension_flat_cost(I64_TRUNC_SAT_F32_U));
-                        let v1: value::F32 = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fc_extension_flat_cost(I64_TRUNC_SAT_F32_U));
+                        let v1: value::F32 = stack.pop();
                         let res = {
                             if v1.is_nan() || v1.is_negative_infinity() {
                                 0
@@ -2679,11 +3004,13 @@ pub(super) fn run<T: Config>(
                         };
 
                         trace!("Instruction: i64.trunc_sat_f32_u [{v1}] -> [{res}]");
-                        stack.push_value::<T>(res.into())?;
+                        stack.push_value(res.into())?;
                     }
                     I64_TRUNC_SAT_F64_S => {
-                        decrement_fuel!(T::get_fc_extension_flat_cost(I64_TRUNC_SAT_F64_S));
-                        let v1: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fc_extension_flat_cost(I64_TRUNC_SAT_F64_S));
+                        let v1: value::F64 = stack.pop();
                         let res = {
                             if v1.is_nan() {
                                 0
@@ -2697,11 +3024,13 @@ pub(super) fn run<T: Config>(
                         };
 
                         trace!("Instruction: i64.trunc_sat_f64_s [{v1}] -> [{res}]");
-                        stack.push_value::<T>(res.into())?;
+                        stack.push_value(res.into())?;
                     }
                     I64_TRUNC_SAT_F64_U => {
-                        decrement_fuel!(T::get_fc_extension_flat_cost(I64_TRUNC_SAT_F64_U));
-                        let v1: value::F64 = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fc_extension_flat_cost(I64_TRUNC_SAT_F64_U));
+                        let v1: value::F64 = stack.pop();
                         let res = {
                             if v1.is_nan() || v1.is_negative_infinity() {
                                 0
@@ -2713,7 +3042,7 @@ pub(super) fn run<T: Config>(
                         };
 
                         trace!("Instruction: i64.trunc_sat_f64_u [{v1}] -> [{res}]");
-                        stack.push_value::<T>(res.into())?;
+                        stack.push_value(res.into())?;
                     }
                     // See https://webassembly.github.io/bulk-memory-operations/core/exec/instructions.html#xref-syntax-instructions-syntax-instr-memory-mathsf-memory-init-x
                     // Copy a region from a data segment into memory
@@ -2730,26 +3059,32 @@ pub(super) fn run<T: Config>(
                         // proposal.
                         let _zero = wasm.read_u8().unwrap_validated();
 
-                        let n: u32 = stack.pop_value().try_into().unwrap_validated();
+                        let n: u32 = stack.pop();
                         // decrement fuel, but push n back if it fails
-                        let cost = T::get_fc_extension_flat_cost(MEMORY_INIT)
-                            + u64::from(n) * T::get_fc_extension_cost_per_element(MEMORY_INIT);
+                        let cost = per_element_cost(
+                            store.user_data.get_fc_extension_flat_cost(MEMORY_INIT),
+                            store
+                                .user_data
+                                .get_fc_extension_cost_per_element(MEMORY_INIT),
+                            n,
+                        );
                         if let Some(fuel) = &mut resumable.maybe_fuel {
-                            if *fuel >= cost {
+                            if let Some(cost) = cost.filter(|cost| *fuel >= *cost) {
                                 *fuel -= cost;
+                                store.user_data.fuel_hook(cost);
                             } else {
-                                stack.push_value::<T>(Value::I32(n)).unwrap_validated(); // we are pushing back what was just popped, this can't panic.
+                                stack.push_value(Value::I32(n)).unwrap_validated(); // we are pushing back what was just popped, this can't panic.
                                 resumable.current_func_addr = current_func_addr;
                                 resumable.pc = prev_pc; // the instruction was fetched already, we roll this back
                                 resumable.stp = stp;
                                 return Ok(InterpreterLoopOutcome::OutOfFuel {
-                                    required_fuel: NonZeroU64::new(cost - *fuel).expect("the last check guarantees that the current fuel is smaller than cost"),
+                                    required_fuel: NonZeroU64::new(cost.map_or(u64::MAX, |cost| cost - *fuel)).expect("the last check guarantees that the current fuel is smaller than cost"),
                                 });
                             }
                         }
 
-                        let s: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let d: u32 = stack.pop_value().try_into().unwrap_validated();
+                        let s: u32 = stack.pop();
+                        let d: u32 = stack.pop();
 
                         // SAFETY: All requirements are met:
                         // 1. The current module address must come from the
@@ -2777,9 +3112,21 @@ pub(super) fn run<T: Config>(
                                 d,
                             )?
                         };
+
+                        // SAFETY: The current module address is valid in the
+                        // current store for the same reason as above.
+                        let module = unsafe { store.modules.get(current_module) };
+                        // SAFETY: Validation guarantees at least one memory to
+                        // exist.
+                        let mem_addr = *unsafe { module.mem_addrs.get(MemIdx::new(0)) };
+                        interrupt_on_break!(store.user_data.memory_bulk_store_hook(
+                            mem_addr,
+                            d.into_usize(),
+                            n.into_usize()
+                        ));
                     }
                     DATA_DROP => {
-                        decrement_fuel!(T::get_fc_extension_flat_cost(DATA_DROP));
+                        decrement_fuel!(store.user_data.get_fc_extension_flat_cost(DATA_DROP));
                         // SAFETY: Validation guarantees there to be a valid
                         // data index next.
                         let data_idx = unsafe { DataIdx::read_unchecked(wasm) };
@@ -2823,43 +3170,78 @@ pub(super) fn run<T: Config>(
                         // exist.
                         let dst_addr = *unsafe { module.mem_addrs.get(MemIdx::new(0)) };
 
-                        let n: u32 = stack.pop_value().try_into().unwrap_validated();
+                        let n: u32 = stack.pop();
                         // decrement fuel, but push n back if it fails
-                        let cost = T::get_fc_extension_flat_cost(MEMORY_COPY)
-                            + u64::from(n) * T::get_fc_extension_cost_per_element(MEMORY_COPY);
+                        let cost = per_element_cost(
+                            store.user_data.get_fc_extension_flat_cost(MEMORY_COPY),
+                            store
+                                .user_data
+                                .get_fc_extension_cost_per_element(MEMORY_COPY),
+                            n,
+                        );
                         if let Some(fuel) = &mut resumable.maybe_fuel {
-                            if *fuel >= cost {
+                            if let Some(cost) = cost.filter(|cost| *fuel >= *cost) {
                                 *fuel -= cost;
+                                store.user_data.fuel_hook(cost);
                             } else {
-                                stack.push_value::<T>(Value::I32(n)).unwrap_validated(); // we are pushing back what was just popped, this can't panic.
+                                stack.push_value(Value::I32(n)).unwrap_validated(); // we are pushing back what was just popped, this can't panic.
                                 resumable.current_func_addr = current_func_addr;
                                 resumable.pc = prev_pc; // the instruction was fetched already, we roll this back
                                 resumable.stp = stp;
                                 return Ok(InterpreterLoopOutcome::OutOfFuel {
-                                    required_fuel: NonZeroU64::new(cost - *fuel).expect("the last check guarantees that the current fuel is smaller than cost"),
+                                    required_fuel: NonZeroU64::new(cost.map_or(u64::MAX, |cost| cost - *fuel)).expect("the last check guarantees that the current fuel is smaller than cost"),
                                 });
                             }
                         }
 
-                        let s: i32 = stack.pop_value().try_into().unwrap_validated();
-                        let d: i32 = stack.pop_value().try_into().unwrap_validated();
-
-                        // SAFETY: This source memory address was just read from
-                        // the current store. Therefore, it must also be valid
-                        // in the current store.
-                        let src_mem = unsafe { store.memories.get(src_addr) };
-                        // SAFETY: This destination memory address was just read
-                        // from the current store. Therefore, it must also be
-                        // valid in the current store.
-                        let dest_mem = unsafe { store.memories.get(dst_addr) };
+                        let s: i32 = stack.pop();
+                        let d: i32 = stack.pop();
 
-                        dest_mem.mem.copy(
-                            d.cast_unsigned().into_usize(),
-                            &src_mem.mem,
+                        let (s, d) = (
                             s.cast_unsigned().into_usize(),
-                            n.into_usize(),
-                        )?;
+                            d.cast_unsigned().into_usize(),
+                        );
+                        // SAFETY: Both memory addresses were just read from the
+                        // current store. Therefore, they must also be valid in
+                        // the current store.
+                        match unsafe { store.memories.get_two_mut(dst_addr, src_addr) } {
+                            Some((dest_mem, src_mem)) => {
+                                src_mem.mem.check_bounds_exclusive(s, n.into_usize())?;
+                                dest_mem.mem.check_bounds_exclusive(d, n.into_usize())?;
+                                src_mem.check_read_protection(s, n.into_usize())?;
+                                dest_mem.check_write_protection(d, n.into_usize())?;
+                                src_mem.check_bulk_mmio(s, n.into_usize())?;
+                                dest_mem.check_bulk_mmio(d, n.into_usize())?;
+                                dest_mem.mem.copy_exclusive(
+                                    d,
+                                    &mut src_mem.mem,
+                                    s,
+                                    n.into_usize(),
+                                )?;
+                            }
+                            None => {
+                                // SAFETY: See above.
+                                let mem = unsafe { store.memories.get_mut(dst_addr) };
+                                mem.mem.check_bounds_exclusive(s, n.into_usize())?;
+                                mem.mem.check_bounds_exclusive(d, n.into_usize())?;
+                                mem.check_read_protection(s, n.into_usize())?;
+                                mem.check_write_protection(d, n.into_usize())?;
+                                mem.check_bulk_mmio(s, n.into_usize())?;
+                                mem.check_bulk_mmio(d, n.into_usize())?;
+                                mem.mem.copy_within_exclusive(d, s, n.into_usize())?;
+                            }
+                        }
                         trace!("Instruction: memory.copy");
+                        let src_flow =
+                            store
+                                .user_data
+                                .memory_bulk_load_hook(src_addr, s, n.into_usize());
+                        let dst_flow =
+                            store
+                                .user_data
+                                .memory_bulk_store_hook(dst_addr, d, n.into_usize());
+                        interrupt_on_break!(src_flow);
+                        interrupt_on_break!(dst_flow);
                     }
                     // See https://webassembly.github.io/bulk-memory-operations/core/exec/instructions.html#xref-syntax-instructions-syntax-instr-memory-mathsf-memory-fill
                     MEMORY_FILL => {
@@ -2880,41 +3262,60 @@ pub(super) fn run<T: Config>(
 
                         // SAFETY: Validation guarantees at least one memory to exist.
                         let mem_addr = *unsafe { module.mem_addrs.get(MemIdx::new(0)) };
-                        // SAFETY: This memory address was just read from the
-                        // current store. Therefore, it is valid in the current
-                        // store.
-                        let mem = unsafe { store.memories.get(mem_addr) };
-
-                        let n: u32 = stack.pop_value().try_into().unwrap_validated();
+                        let n: u32 = stack.pop();
                         // decrement fuel, but push n back if it fails
-                        let cost = T::get_fc_extension_flat_cost(MEMORY_FILL)
-                            + u64::from(n) * T::get_fc_extension_cost_per_element(MEMORY_FILL);
+                        let cost = per_element_cost(
+                            store.user_data.get_fc_extension_flat_cost(MEMORY_FILL),
+                            store
+                                .user_data
+                                .get_fc_extension_cost_per_element(MEMORY_FILL),
+                            n,
+                        );
                         if let Some(fuel) = &mut resumable.maybe_fuel {
-                            if *fuel >= cost {
+                            if let Some(cost) = cost.filter(|cost| *fuel >= *cost) {
                                 *fuel -= cost;
+                                store.user_data.fuel_hook(cost);
                             } else {
-                                stack.push_value::<T>(Value::I32(n)).unwrap_validated(); // we are pushing back what was just popped, this can't panic.
+                                stack.push_value(Value::I32(n)).unwrap_validated(); // we are pushing back what was just popped, this can't panic.
                                 resumable.current_func_addr = current_func_addr;
                                 resumable.pc = prev_pc; // the instruction was fetched already, we roll this back
                                 resumable.stp = stp;
                                 return Ok(InterpreterLoopOutcome::OutOfFuel {
-                                    required_fuel: NonZeroU64::new(cost - *fuel).expect("the last check guarantees that the current fuel is smaller than cost"),
+                                    required_fuel: NonZeroU64::new(cost.map_or(u64::MAX, |cost| cost - *fuel)).expect("the last check guarantees that the current fuel is smaller than cost"),
                                 });
                             }
                         }
 
-                        let val: i32 = stack.pop_value().try_into().unwrap_validated();
+                        let val: i32 = stack.pop();
 
                         if !(0..=255).contains(&val) {
                             warn!("Value for memory.fill does not fit in a byte ({val})");
                         }
 
-                        let d: i32 = stack.pop_value().try_into().unwrap_validated();
+                        let d: i32 = stack.pop();
 
-                        mem.mem
-                            .fill(d.cast_unsigned().into_usize(), val as u8, n.into_usize())?;
+                        // SAFETY: This memory address was just read from the
+                        // current store. Therefore, it is valid in the current
+                        // store.
+                        let mem = unsafe { store.memories.get_mut(mem_addr) };
+                        mem.mem.check_bounds_exclusive(
+                            d.cast_unsigned().into_usize(),
+                            n.into_usize(),
+                        )?;
+                        mem.check_write_protection(d.cast_unsigned().into_usize(), n.into_usize())?;
+                        mem.check_bulk_mmio(d.cast_unsigned().into_usize(), n.into_usize())?;
+                        mem.mem.fill_exclusive(
+                            d.cast_unsigned().into_usize(),
+                            val as u8,
+                            n.into_usize(),
+                        )?;
 
                         trace!("Instruction: memory.fill");
+                        interrupt_on_break!(store.user_data.memory_bulk_store_hook(
+                            mem_addr,
+                            d.cast_unsigned().into_usize(),
+                            n.into_usize()
+                        ));
                     }
                     // https://webassembly.github.io/spec/core/exec/instructions.html#xref-syntax-instructions-syntax-instr-table-mathsf-table-init-x-y
                     // https://webassembly.github.io/spec/core/binary/instructions.html#table-instructions
@@ -2928,25 +3329,31 @@ pub(super) fn run<T: Config>(
                         // table index next.
                         let table_idx = unsafe { TableIdx::read_unchecked(wasm) };
 
-                        let n: u32 = stack.pop_value().try_into().unwrap_validated(); // size
-                        let cost = T::get_fc_extension_flat_cost(TABLE_INIT)
-                            + u64::from(n) * T::get_fc_extension_cost_per_element(TABLE_INIT);
+                        let n: u32 = stack.pop(); // size
+                        let cost = per_element_cost(
+                            store.user_data.get_fc_extension_flat_cost(TABLE_INIT),
+                            store
+                                .user_data
+                                .get_fc_extension_cost_per_element(TABLE_INIT),
+                            n,
+                        );
                         if let Some(fuel) = &mut resumable.maybe_fuel {
-                            if *fuel >= cost {
+                            if let Some(cost) = cost.filter(|cost| *fuel >= *cost) {
                                 *fuel -= cost;
+                                store.user_data.fuel_hook(cost);
                             } else {
-                                stack.push_value::<T>(Value::I32(n)).unwrap_validated(); // we are pushing back what was just popped, this can't panic.
+                                stack.push_value(Value::I32(n)).unwrap_validated(); // we are pushing back what was just popped, this can't panic.
                                 resumable.current_func_addr = current_func_addr;
                                 resumable.pc = prev_pc; // the instruction was fetched already, we roll this back
                                 resumable.stp = stp;
                                 return Ok(InterpreterLoopOutcome::OutOfFuel {
-                                    required_fuel: NonZeroU64::new(cost - *fuel).expect("the last check guarantees that the current fuel is smaller than cost"),
+                                    required_fuel: NonZeroU64::new(cost.map_or(u64::MAX, |cost| cost - *fuel)).expect("the last check guarantees that the current fuel is smaller than cost"),
                                 });
                             }
                         }
 
-                        let s: i32 = stack.pop_value().try_into().unwrap_validated(); // offset
-                        let d: i32 = stack.pop_value().try_into().unwrap_validated(); // dst
+                        let s: i32 = stack.pop(); // offset
+                        let d: i32 = stack.pop(); // dst
 
                         // SAFETY: All requirements are met:
                         // 1. The current module address must come from the
@@ -2977,7 +3384,7 @@ pub(super) fn run<T: Config>(
                         };
                     }
                     ELEM_DROP => {
-                        decrement_fuel!(T::get_fc_extension_flat_cost(ELEM_DROP));
+                        decrement_fuel!(store.user_data.get_fc_extension_flat_cost(ELEM_DROP));
                         // SAFETY: Validation guarantees there a valid element
                         // index next.
                         let elem_idx = unsafe { ElemIdx::read_unchecked(wasm) };
@@ -3005,7 +3412,7 @@ pub(super) fn run<T: Config>(
                     }
                     // https://webassembly.github.io/spec/core/exec/instructions.html#xref-syntax-instructions-syntax-instr-table-mathsf-table-copy-x-y
                     TABLE_COPY => {
-                        decrement_fuel!(T::get_fc_extension_flat_cost(TABLE_COPY));
+                        decrement_fuel!(store.user_data.get_fc_extension_flat_cost(TABLE_COPY));
                         // SAFETY: Validation guarantees there to be a valid
                         // table index next.
                         let table_x_idx = unsafe { TableIdx::read_unchecked(wasm) };
@@ -3035,25 +3442,31 @@ pub(super) fn run<T: Config>(
                         // store.
                         let tab_y_elem_len = unsafe { store.tables.get(table_addr_y) }.elem.len();
 
-                        let n: u32 = stack.pop_value().try_into().unwrap_validated(); // size
-                        let cost = T::get_fc_extension_flat_cost(TABLE_COPY)
-                            + u64::from(n) * T::get_fc_extension_cost_per_element(TABLE_COPY);
+                        let n: u32 = stack.pop(); // size
+                        let cost = per_element_cost(
+                            store.user_data.get_fc_extension_flat_cost(TABLE_COPY),
+                            store
+                                .user_data
+                                .get_fc_extension_cost_per_element(TABLE_COPY),
+                            n,
+                        );
                         if let Some(fuel) = &mut resumable.maybe_fuel {
-                            if *fuel >= cost {
+                            if let Some(cost) = cost.filter(|cost| *fuel >= *cost) {
                                 *fuel -= cost;
+                                store.user_data.fuel_hook(cost);
                             } else {
-                                stack.push_value::<T>(Value::I32(n)).unwrap_validated(); // we are pushing back what was just popped, this can't panic.
+                                stack.push_value(Value::I32(n)).unwrap_validated(); // we are pushing back what was just popped, this can't panic.
                                 resumable.current_func_addr = current_func_addr;
                                 resumable.pc = prev_pc; // the instruction was fetched already, we roll this back
                                 resumable.stp = stp;
                                 return Ok(InterpreterLoopOutcome::OutOfFuel {
-                                    required_fuel: NonZeroU64::new(cost - *fuel).expect("the last check guarantees that the current fuel is smaller than cost"),
+                                    required_fuel: NonZeroU64::new(cost.map_or(u64::MAX, |cost| cost - *fuel)).expect("the last check guarantees that the current fuel is smaller than cost"),
                                 });
                             }
                         }
 
-                        let s: u32 = stack.pop_value().try_into().unwrap_validated(); // source
-                        let d: u32 = stack.pop_value().try_into().unwrap_validated(); // destination
+                        let s: u32 = stack.pop(); // source
+                        let d: u32 = stack.pop(); // destination
 
                         let src_res = match s.checked_add(n) {
                             Some(res) => {
@@ -3109,7 +3522,7 @@ pub(super) fn run<T: Config>(
                         );
                     }
                     TABLE_GROW => {
-                        decrement_fuel!(T::get_fc_extension_flat_cost(TABLE_GROW));
+                        decrement_fuel!(store.user_data.get_fc_extension_flat_cost(TABLE_GROW));
                         // SAFETY: Validation guarantees there to be a valid
                         // table index next.
                         let table_idx = unsafe { TableIdx::read_unchecked(wasm) };
@@ -3126,43 +3539,67 @@ pub(super) fn run<T: Config>(
                         // SAFETY: This table address was just read from the
                         // current store. Therefore, it is valid in the current
                         // store.
-                        let tab = unsafe { store.tables.get_mut(table_addr) };
+                        let tab = unsafe { store.tables.get(table_addr) };
 
                         let sz = tab.elem.len() as u32;
 
-                        let n: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let cost = T::get_fc_extension_flat_cost(TABLE_GROW)
-                            + u64::from(n) * T::get_fc_extension_cost_per_element(TABLE_GROW);
+                        let n: u32 = stack.pop();
+                        let cost = per_element_cost(
+                            store.user_data.get_fc_extension_flat_cost(TABLE_GROW),
+                            store
+                                .user_data
+                                .get_fc_extension_cost_per_element(TABLE_GROW),
+                            n,
+                        );
                         if let Some(fuel) = &mut resumable.maybe_fuel {
-                            if *fuel >= cost {
+                            if let Some(cost) = cost.filter(|cost| *fuel >= *cost) {
                                 *fuel -= cost;
+                                store.user_data.fuel_hook(cost);
                             } else {
-                                stack.push_value::<T>(Value::I32(n)).unwrap_validated(); // we are pushing back what was just popped, this can't panic.
+                                stack.push_value(Value::I32(n)).unwrap_validated(); // we are pushing back what was just popped, this can't panic.
                                 resumable.current_func_addr = current_func_addr;
                                 resumable.pc = prev_pc; // the instruction was fetched already, we roll this back
                                 resumable.stp = stp;
                                 return Ok(InterpreterLoopOutcome::OutOfFuel {
-                                    required_fuel: NonZeroU64::new(cost - *fuel).expect("the last check guarantees that the current fuel is smaller than cost"),
+                                    required_fuel: NonZeroU64::new(cost.map_or(u64::MAX, |cost| cost - *fuel)).expect("the last check guarantees that the current fuel is smaller than cost"),
                                 });
                             }
                         }
 
-                        let val: Ref = stack.pop_value().try_into().unwrap_validated();
+                        let val: Ref = stack.pop();
 
-                        // TODO this instruction is non-deterministic w.r.t. spec, and can fail if the embedder wills it.
-                        // for now we execute it always according to the following match expr.
-                        // if the grow operation fails, err := Value::I32(2^32-1) is pushed to the stack per spec
-                        match tab.grow(n, val) {
-                            Ok(_) => {
-                                stack.push_value::<T>(Value::I32(sz))?;
+                        // growth may fail non-deterministically if the resource limiter of the config denies it or
+                        // the host is out of memory. if the grow operation fails, err := Value::I32(2^32-1) is pushed
+                        // to the stack per spec
+                        // SAFETY: The table address was read from the current store above and the reference was
+                        // produced by Wasm code running in the current store.
+                        match unsafe {
+                            grow_table(
+                                &mut store.tables,
+                                &mut store.user_data,
+                                &mut store.resource_usage,
+                                table_addr,
+                                n,
+                                val,
+                            )
+                        } {
+                            Ok(()) => {
+                                stack.push_value(Value::I32(sz))?;
+                            }
+                            Err(
+                                GrowthFailure::MaximumExceeded
+                                | GrowthFailure::Denied
+                                | GrowthFailure::OutOfMemory,
+                            ) => {
+                                stack.push_value(Value::I32(u32::MAX))?;
                             }
-                            Err(_) => {
-                                stack.push_value::<T>(Value::I32(u32::MAX))?;
+                            Err(GrowthFailure::Trap) => {
+                                return Err(TrapError::ResourceLimitExceeded.into())
                             }
                         }
                     }
                     TABLE_SIZE => {
-                        decrement_fuel!(T::get_fc_extension_flat_cost(TABLE_SIZE));
+                        decrement_fuel!(store.user_data.get_fc_extension_flat_cost(TABLE_SIZE));
                         // SAFETY: Validation guarantees there to be valid table
                         // index next.
                         let table_idx = unsafe { TableIdx::read_unchecked(wasm) };
@@ -3183,12 +3620,12 @@ pub(super) fn run<T: Config>(
 
                         let sz = tab.elem.len() as u32;
 
-                        stack.push_value::<T>(Value::I32(sz))?;
+                        stack.push_value(Value::I32(sz))?;
 
                         trace!("Instruction: table.size '{}' [] -> [{}]", table_idx, sz);
                     }
                     TABLE_FILL => {
-                        decrement_fuel!(T::get_fc_extension_flat_cost(TABLE_FILL));
+                        decrement_fuel!(store.user_data.get_fc_extension_flat_cost(TABLE_FILL));
                         // SAFETY: Validation guarantees there to be a valid
                         // table index next.
                         let table_idx = unsafe { TableIdx::read_unchecked(wasm) };
@@ -3207,25 +3644,31 @@ pub(super) fn run<T: Config>(
                         // store.
                         let tab = unsafe { store.tables.get_mut(table_addr) };
 
-                        let len: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let cost = T::get_fc_extension_flat_cost(TABLE_FILL)
-                            + u64::from(len) * T::get_fc_extension_cost_per_element(TABLE_FILL);
+                        let len: u32 = stack.pop();
+                        let cost = per_element_cost(
+                            store.user_data.get_fc_extension_flat_cost(TABLE_FILL),
+                            store
+                                .user_data
+                                .get_fc_extension_cost_per_element(TABLE_FILL),
+                            len,
+                        );
                         if let Some(fuel) = &mut resumable.maybe_fuel {
-                            if *fuel >= cost {
+                            if let Some(cost) = cost.filter(|cost| *fuel >= *cost) {
                                 *fuel -= cost;
+                                store.user_data.fuel_hook(cost);
                             } else {
-                                stack.push_value::<T>(Value::I32(len)).unwrap_validated(); // we are pushing back what was just popped, this can't panic.
+                                stack.push_value(Value::I32(len)).unwrap_validated(); // we are pushing back what was just popped, this can't panic.
                                 resumable.current_func_addr = current_func_addr;
                                 resumable.pc = prev_pc; // the instruction was fetched already, we roll this back
                                 resumable.stp = stp;
                                 return Ok(InterpreterLoopOutcome::OutOfFuel {
-                                    required_fuel: NonZeroU64::new(cost - *fuel).expect("the last check guarantees that the current fuel is smaller than cost"),
+                                    required_fuel: NonZeroU64::new(cost.map_or(u64::MAX, |cost| cost - *fuel)).expect("the last check guarantees that the current fuel is smaller than cost"),
                                 });
                             }
                         }
 
-                        let val: Ref = stack.pop_value().try_into().unwrap_validated();
-                        let dst: u32 = stack.pop_value().try_into().unwrap_validated();
+                        let val: Ref = stack.pop();
+                        let dst: u32 = stack.pop();
 
                         let end = (dst.into_usize())
                             .checked_add(len.into_usize())
@@ -3249,8 +3692,8 @@ pub(super) fn run<T: Config>(
             }
 
             I32_EXTEND8_S => {
-                decrement_fuel!(T::get_flat_cost(I32_EXTEND8_S));
-                let mut v: u32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_EXTEND8_S));
+                let mut v: u32 = stack.pop();
 
                 if v | 0xFF != 0xFF {
                     trace!("Number v ({}) not contained in 8 bits, truncating", v);
@@ -3259,13 +3702,13 @@ pub(super) fn run<T: Config>(
 
                 let res = if v | 0x7F != 0x7F { v | 0xFFFFFF00 } else { v };
 
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
 
                 trace!("Instruction i32.extend8_s [{}] -> [{}]", v, res);
             }
             I32_EXTEND16_S => {
-                decrement_fuel!(T::get_flat_cost(I32_EXTEND16_S));
-                let mut v: u32 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I32_EXTEND16_S));
+                let mut v: u32 = stack.pop();
 
                 if v | 0xFFFF != 0xFFFF {
                     trace!("Number v ({}) not contained in 16 bits, truncating", v);
@@ -3278,13 +3721,13 @@ pub(super) fn run<T: Config>(
                     v
                 };
 
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
 
                 trace!("Instruction i32.extend16_s [{}] -> [{}]", v, res);
             }
             I64_EXTEND8_S => {
-                decrement_fuel!(T::get_flat_cost(I64_EXTEND8_S));
-                let mut v: u64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_EXTEND8_S));
+                let mut v: u64 = stack.pop();
 
                 if v | 0xFF != 0xFF {
                     trace!("Number v ({}) not contained in 8 bits, truncating", v);
@@ -3297,13 +3740,13 @@ pub(super) fn run<T: Config>(
                     v
                 };
 
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
 
                 trace!("Instruction i64.extend8_s [{}] -> [{}]", v, res);
             }
             I64_EXTEND16_S => {
-                decrement_fuel!(T::get_flat_cost(I64_EXTEND16_S));
-                let mut v: u64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_EXTEND16_S));
+                let mut v: u64 = stack.pop();
 
                 if v | 0xFFFF != 0xFFFF {
                     trace!("Number v ({}) not contained in 16 bits, truncating", v);
@@ -3316,13 +3759,13 @@ pub(super) fn run<T: Config>(
                     v
                 };
 
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
 
                 trace!("Instruction i64.extend16_s [{}] -> [{}]", v, res);
             }
             I64_EXTEND32_S => {
-                decrement_fuel!(T::get_flat_cost(I64_EXTEND32_S));
-                let mut v: u64 = stack.pop_value().try_into().unwrap_validated();
+                decrement_fuel!(store.user_data.get_flat_cost(I64_EXTEND32_S));
+                let mut v: u64 = stack.pop();
 
                 if v | 0xFFFF_FFFF != 0xFFFF_FFFF {
                     trace!("Number v ({}) not contained in 32 bits, truncating", v);
@@ -3335,7 +3778,7 @@ pub(super) fn run<T: Config>(
                     v
                 };
 
-                stack.push_value::<T>(res.into())?;
+                stack.push_value(res.into())?;
 
                 trace!("Instruction i64.extend32_s [{}] -> [{}]", v, res);
             }
@@ -3346,7 +3789,7 @@ pub(super) fn run<T: Config>(
                 use crate::core::reader::types::opcode::fd_extensions::*;
                 match second_instr {
                     V128_LOAD => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_LOAD));
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(V128_LOAD));
                         let memarg = MemArg::read(wasm).unwrap_validated();
                         // SAFETY: The current module address must come from the current
                         // store, because it is the only parameter to this function that
@@ -3360,16 +3803,21 @@ pub(super) fn run<T: Config>(
                         // SAFETY: This memory address was just read from the
                         // current store. Therefore, it is valid in the current
                         // store.
-                        let memory = unsafe { store.memories.get(mem_addr) };
+                        let memory = unsafe { store.memories.get_mut(mem_addr) };
 
-                        let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let idx = calculate_mem_address(&memarg, relative_address)?;
+                        let relative_address: u32 = stack.pop();
+                        let idx = calculate_mem_address(memarg.offset, relative_address)?;
 
-                        let data: u128 = memory.mem.load(idx)?;
-                        stack.push_value::<T>(data.to_le_bytes().into())?;
+                        let data: u128 = mem_load(memory, &mut store.user_data, mem_addr, idx)?;
+                        stack.push_value(data.to_le_bytes().into())?;
+                        interrupt_on_break!(store.user_data.memory_load_hook(
+                            mem_addr,
+                            idx,
+                            &data.to_le_bytes()
+                        ));
                     }
                     V128_STORE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_STORE));
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(V128_STORE));
                         let memarg = MemArg::read(wasm).unwrap_validated();
                         // SAFETY: The current module address must come from the current
                         // store, because it is the only parameter to this function that
@@ -3383,18 +3831,27 @@ pub(super) fn run<T: Config>(
                         // SAFETY: This memory address was just read from the
                         // current store. Therefore, it is valid in the current
                         // store.
-                        let memory = unsafe { store.memories.get(mem_addr) };
-
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let idx = calculate_mem_address(&memarg, relative_address)?;
-
-                        memory.mem.store(idx, u128::from_le_bytes(data))?;
+                        let memory = unsafe { store.memories.get_mut(mem_addr) };
+
+                        let data: [u8; 16] = stack.pop();
+                        let relative_address: u32 = stack.pop();
+                        let idx = calculate_mem_address(memarg.offset, relative_address)?;
+
+                        mem_store(
+                            memory,
+                            &mut store.user_data,
+                            mem_addr,
+                            idx,
+                            u128::from_le_bytes(data),
+                        )?;
+                        interrupt_on_break!(store
+                            .user_data
+                            .memory_store_hook(mem_addr, idx, &data));
                     }
 
                     // v128.loadNxM_sx
                     V128_LOAD8X8_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_LOAD8X8_S));
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(V128_LOAD8X8_S));
                         let memarg = MemArg::read(wasm).unwrap_validated();
                         // SAFETY: The current module address must come from the current
                         // store, because it is the only parameter to this function that
@@ -3408,12 +3865,13 @@ pub(super) fn run<T: Config>(
                         // SAFETY: This memory address was just read from the
                         // current store. Therefore, it is valid in the current
                         // store.
-                        let memory = unsafe { store.memories.get(mem_addr) };
+                        let memory = unsafe { store.memories.get_mut(mem_addr) };
 
-                        let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let idx = calculate_mem_address(&memarg, relative_address)?;
+                        let relative_address: u32 = stack.pop();
+                        let idx = calculate_mem_address(memarg.offset, relative_address)?;
 
-                        let half_data: [u8; 8] = memory.mem.load_bytes::<8>(idx)?; // v128 load always loads half of a v128
+                        let half_data: [u8; 8] =
+                            mem_load_bytes::<_, 8>(memory, &mut store.user_data, mem_addr, idx)?; // v128 load always loads half of a v128
 
                         // Special case where we have only half of a v128. To convert it to lanes via `to_lanes`, pad the data with zeros
                         let data: [u8; 16] = array::from_fn(|i| *half_data.get(i).unwrap_or(&0));
@@ -3422,10 +3880,13 @@ pub(super) fn run<T: Config>(
 
                         let extended_lanes = half_lanes.map(|lane| lane as i16);
 
-                        stack.push_value::<T>(Value::V128(from_lanes(extended_lanes)))?;
+                        stack.push_value(Value::V128(from_lanes(extended_lanes)))?;
+                        interrupt_on_break!(store
+                            .user_data
+                            .memory_load_hook(mem_addr, idx, &half_data));
                     }
                     V128_LOAD8X8_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_LOAD8X8_U));
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(V128_LOAD8X8_U));
                         let memarg = MemArg::read(wasm).unwrap_validated();
                         // SAFETY: The current module address must come from the current
                         // store, because it is the only parameter to this function that
@@ -3439,12 +3900,13 @@ pub(super) fn run<T: Config>(
                         // SAFETY: This memory address was just read from the
                         // current store. Therefore, it is valid in the current
                         // store.
-                        let memory = unsafe { store.memories.get(mem_addr) };
+                        let memory = unsafe { store.memories.get_mut(mem_addr) };
 
-                        let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let idx = calculate_mem_address(&memarg, relative_address)?;
+                        let relative_address: u32 = stack.pop();
+                        let idx = calculate_mem_address(memarg.offset, relative_address)?;
 
-                        let half_data: [u8; 8] = memory.mem.load_bytes::<8>(idx)?; // v128 load always loads half of a v128
+                        let half_data: [u8; 8] =
+                            mem_load_bytes::<_, 8>(memory, &mut store.user_data, mem_addr, idx)?; // v128 load always loads half of a v128
 
                         // Special case where we have only half of a v128. To convert it to lanes via `to_lanes`, pad the data with zeros
                         let data: [u8; 16] = array::from_fn(|i| *half_data.get(i).unwrap_or(&0));
@@ -3453,10 +3915,15 @@ pub(super) fn run<T: Config>(
 
                         let extended_lanes = half_lanes.map(|lane| lane as u16);
 
-                        stack.push_value::<T>(Value::V128(from_lanes(extended_lanes)))?;
+                        stack.push_value(Value::V128(from_lanes(extended_lanes)))?;
+                        interrupt_on_break!(store
+                            .user_data
+                            .memory_load_hook(mem_addr, idx, &half_data));
                     }
                     V128_LOAD16X4_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_LOAD16X4_S));
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(V128_LOAD16X4_S));
                         let memarg = MemArg::read(wasm).unwrap_validated();
                         // SAFETY: The current module address must come from the current
                         // store, because it is the only parameter to this function that
@@ -3470,12 +3937,13 @@ pub(super) fn run<T: Config>(
                         // SAFETY: This memory address was just read from the
                         // current store. Therefore, it is valid in the current
                         // store.
-                        let memory = unsafe { store.memories.get(mem_addr) };
+                        let memory = unsafe { store.memories.get_mut(mem_addr) };
 
-                        let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let idx = calculate_mem_address(&memarg, relative_address)?;
+                        let relative_address: u32 = stack.pop();
+                        let idx = calculate_mem_address(memarg.offset, relative_address)?;
 
-                        let half_data: [u8; 8] = memory.mem.load_bytes::<8>(idx)?; // v128 load always loads half of a v128
+                        let half_data: [u8; 8] =
+                            mem_load_bytes::<_, 8>(memory, &mut store.user_data, mem_addr, idx)?; // v128 load always loads half of a v128
 
                         // Special case where we have only half of a v128. To convert it to lanes via `to_lanes`, pad the data with zeros
                         let data: [u8; 16] = array::from_fn(|i| *half_data.get(i).unwrap_or(&0));
@@ -3484,10 +3952,15 @@ pub(super) fn run<T: Config>(
 
                         let extended_lanes = half_lanes.map(|lane| lane as i32);
 
-                        stack.push_value::<T>(Value::V128(from_lanes(extended_lanes)))?;
+                        stack.push_value(Value::V128(from_lanes(extended_lanes)))?;
+                        interrupt_on_break!(store
+                            .user_data
+                            .memory_load_hook(mem_addr, idx, &half_data));
                     }
                     V128_LOAD16X4_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_LOAD16X4_U));
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(V128_LOAD16X4_U));
                         let memarg = MemArg::read(wasm).unwrap_validated();
                         // SAFETY: The current module address must come from the current
                         // store, because it is the only parameter to this function that
@@ -3501,12 +3974,13 @@ pub(super) fn run<T: Config>(
                         // SAFETY: This memory address was just read from the
                         // current store. Therefore, it is valid in the current
                         // store.
-                        let memory = unsafe { store.memories.get(mem_addr) };
+                        let memory = unsafe { store.memories.get_mut(mem_addr) };
 
-                        let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let idx = calculate_mem_address(&memarg, relative_address)?;
+                        let relative_address: u32 = stack.pop();
+                        let idx = calculate_mem_address(memarg.offset, relative_address)?;
 
-                        let half_data: [u8; 8] = memory.mem.load_bytes::<8>(idx)?; // v128 load always loads half of a v128
+                        let half_data: [u8; 8] =
+                            mem_load_bytes::<_, 8>(memory, &mut store.user_data, mem_addr, idx)?; // v128 load always loads half of a v128
 
                         // Special case where we have only half of a v128. To convert it to lanes via `to_lanes`, pad the data with zeros
                         let data: [u8; 16] = array::from_fn(|i| *half_data.get(i).unwrap_or(&0));
@@ -3515,10 +3989,15 @@ pub(super) fn run<T: Config>(
 
                         let extended_lanes = half_lanes.map(|lane| lane as u32);
 
-                        stack.push_value::<T>(Value::V128(from_lanes(extended_lanes)))?;
+                        stack.push_value(Value::V128(from_lanes(extended_lanes)))?;
+                        interrupt_on_break!(store
+                            .user_data
+                            .memory_load_hook(mem_addr, idx, &half_data));
                     }
                     V128_LOAD32X2_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_LOAD32X2_S));
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(V128_LOAD32X2_S));
                         let memarg = MemArg::read(wasm).unwrap_validated();
                         // SAFETY: The current module address must come from the current
                         // store, because it is the only parameter to this function that
@@ -3532,12 +4011,13 @@ pub(super) fn run<T: Config>(
                         // SAFETY: This memory address was just read from the
                         // current store. Therefore, it is valid in the current
                         // store.
-                        let memory = unsafe { store.memories.get(mem_addr) };
+                        let memory = unsafe { store.memories.get_mut(mem_addr) };
 
-                        let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let idx = calculate_mem_address(&memarg, relative_address)?;
+                        let relative_address: u32 = stack.pop();
+                        let idx = calculate_mem_address(memarg.offset, relative_address)?;
 
-                        let half_data: [u8; 8] = memory.mem.load_bytes::<8>(idx)?; // v128 load always loads half of a v128
+                        let half_data: [u8; 8] =
+                            mem_load_bytes::<_, 8>(memory, &mut store.user_data, mem_addr, idx)?; // v128 load always loads half of a v128
 
                         // Special case where we have only half of a v128. To convert it to lanes via `to_lanes`, pad the data with zeros
                         let data: [u8; 16] = array::from_fn(|i| *half_data.get(i).unwrap_or(&0));
@@ -3546,10 +4026,15 @@ pub(super) fn run<T: Config>(
 
                         let extended_lanes = half_lanes.map(|lane| lane as i64);
 
-                        stack.push_value::<T>(Value::V128(from_lanes(extended_lanes)))?;
+                        stack.push_value(Value::V128(from_lanes(extended_lanes)))?;
+                        interrupt_on_break!(store
+                            .user_data
+                            .memory_load_hook(mem_addr, idx, &half_data));
                     }
                     V128_LOAD32X2_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_LOAD32X2_U));
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(V128_LOAD32X2_U));
                         let memarg = MemArg::read(wasm).unwrap_validated();
                         // SAFETY: The current module address must come from the current
                         // store, because it is the only parameter to this function that
@@ -3563,12 +4048,13 @@ pub(super) fn run<T: Config>(
                         // SAFETY: This memory address was just read from the
                         // current store. Therefore, it is valid in the current
                         // store.
-                        let memory = unsafe { store.memories.get(mem_addr) };
+                        let memory = unsafe { store.memories.get_mut(mem_addr) };
 
-                        let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let idx = calculate_mem_address(&memarg, relative_address)?;
+                        let relative_address: u32 = stack.pop();
+                        let idx = calculate_mem_address(memarg.offset, relative_address)?;
 
-                        let half_data: [u8; 8] = memory.mem.load_bytes::<8>(idx)?; // v128 load always loads half of a v128
+                        let half_data: [u8; 8] =
+                            mem_load_bytes::<_, 8>(memory, &mut store.user_data, mem_addr, idx)?; // v128 load always loads half of a v128
 
                         // Special case where we have only half of a v128. To convert it to lanes via `to_lanes`, pad the data with zeros
                         let data: [u8; 16] = array::from_fn(|i| *half_data.get(i).unwrap_or(&0));
@@ -3577,12 +4063,17 @@ pub(super) fn run<T: Config>(
 
                         let extended_lanes = half_lanes.map(|lane| lane as u64);
 
-                        stack.push_value::<T>(Value::V128(from_lanes(extended_lanes)))?;
+                        stack.push_value(Value::V128(from_lanes(extended_lanes)))?;
+                        interrupt_on_break!(store
+                            .user_data
+                            .memory_load_hook(mem_addr, idx, &half_data));
                     }
 
                     // v128.loadN_splat
                     V128_LOAD8_SPLAT => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_LOAD8_SPLAT));
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(V128_LOAD8_SPLAT));
                         let memarg = MemArg::read(wasm).unwrap_validated();
                         // SAFETY: The current module address must come from the current
                         // store, because it is the only parameter to this function that
@@ -3596,15 +4087,23 @@ pub(super) fn run<T: Config>(
                         // SAFETY: This memory address was just read from the
                         // current store. Therefore, it is valid in the current
                         // store.
-                        let memory = unsafe { store.memories.get(mem_addr) };
-                        let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let idx = calculate_mem_address(&memarg, relative_address)?;
-
-                        let lane = memory.mem.load::<1, u8>(idx)?;
-                        stack.push_value::<T>(Value::V128(from_lanes([lane; 16])))?;
+                        let memory = unsafe { store.memories.get_mut(mem_addr) };
+                        let relative_address: u32 = stack.pop();
+                        let idx = calculate_mem_address(memarg.offset, relative_address)?;
+
+                        let lane =
+                            mem_load::<_, 1, u8>(memory, &mut store.user_data, mem_addr, idx)?;
+                        stack.push_value(Value::V128(from_lanes([lane; 16])))?;
+                        interrupt_on_break!(store.user_data.memory_load_hook(
+                            mem_addr,
+                            idx,
+                            &lane.to_le_bytes()
+                        ));
                     }
                     V128_LOAD16_SPLAT => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_LOAD16_SPLAT));
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(V128_LOAD16_SPLAT));
                         let memarg = MemArg::read(wasm).unwrap_validated();
                         // SAFETY: The current module address must come from the current
                         // store, because it is the only parameter to this function that
@@ -3618,15 +4117,23 @@ pub(super) fn run<T: Config>(
                         // SAFETY: This memory address was just read from the
                         // current store. Therefore, it is valid in the current
                         // store.
-                        let memory = unsafe { store.memories.get(mem_addr) };
-                        let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let idx = calculate_mem_address(&memarg, relative_address)?;
-
-                        let lane = memory.mem.load::<2, u16>(idx)?;
-                        stack.push_value::<T>(Value::V128(from_lanes([lane; 8])))?;
+                        let memory = unsafe { store.memories.get_mut(mem_addr) };
+                        let relative_address: u32 = stack.pop();
+                        let idx = calculate_mem_address(memarg.offset, relative_address)?;
+
+                        let lane =
+                            mem_load::<_, 2, u16>(memory, &mut store.user_data, mem_addr, idx)?;
+                        stack.push_value(Value::V128(from_lanes([lane; 8])))?;
+                        interrupt_on_break!(store.user_data.memory_load_hook(
+                            mem_addr,
+                            idx,
+                            &lane.to_le_bytes()
+                        ));
                     }
                     V128_LOAD32_SPLAT => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_LOAD32_SPLAT));
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(V128_LOAD32_SPLAT));
                         let memarg = MemArg::read(wasm).unwrap_validated();
                         // SAFETY: The current module address must come from the current
                         // store, because it is the only parameter to this function that
@@ -3640,15 +4147,23 @@ pub(super) fn run<T: Config>(
                         // SAFETY: This memory address was just read from the
                         // current store. Therefore, it is valid in the current
                         // store.
-                        let memory = unsafe { store.memories.get(mem_addr) };
-                        let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let idx = calculate_mem_address(&memarg, relative_address)?;
-
-                        let lane = memory.mem.load::<4, u32>(idx)?;
-                        stack.push_value::<T>(Value::V128(from_lanes([lane; 4])))?;
+                        let memory = unsafe { store.memories.get_mut(mem_addr) };
+                        let relative_address: u32 = stack.pop();
+                        let idx = calculate_mem_address(memarg.offset, relative_address)?;
+
+                        let lane =
+                            mem_load::<_, 4, u32>(memory, &mut store.user_data, mem_addr, idx)?;
+                        stack.push_value(Value::V128(from_lanes([lane; 4])))?;
+                        interrupt_on_break!(store.user_data.memory_load_hook(
+                            mem_addr,
+                            idx,
+                            &lane.to_le_bytes()
+                        ));
                     }
                     V128_LOAD64_SPLAT => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_LOAD64_SPLAT));
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(V128_LOAD64_SPLAT));
                         let memarg = MemArg::read(wasm).unwrap_validated();
                         // SAFETY: The current module address must come from the current
                         // store, because it is the only parameter to this function that
@@ -3662,17 +4177,25 @@ pub(super) fn run<T: Config>(
                         // SAFETY: This memory address was just read from the
                         // current store. Therefore, it is valid in the current
                         // store.
-                        let memory = unsafe { store.memories.get(mem_addr) };
-                        let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let idx = calculate_mem_address(&memarg, relative_address)?;
-
-                        let lane = memory.mem.load::<8, u64>(idx)?;
-                        stack.push_value::<T>(Value::V128(from_lanes([lane; 2])))?;
+                        let memory = unsafe { store.memories.get_mut(mem_addr) };
+                        let relative_address: u32 = stack.pop();
+                        let idx = calculate_mem_address(memarg.offset, relative_address)?;
+
+                        let lane =
+                            mem_load::<_, 8, u64>(memory, &mut store.user_data, mem_addr, idx)?;
+                        stack.push_value(Value::V128(from_lanes([lane; 2])))?;
+                        interrupt_on_break!(store.user_data.memory_load_hook(
+                            mem_addr,
+                            idx,
+                            &lane.to_le_bytes()
+                        ));
                     }
 
                     // v128.loadN_zero
                     V128_LOAD32_ZERO => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_LOAD32_ZERO));
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(V128_LOAD32_ZERO));
                         let memarg = MemArg::read(wasm).unwrap_validated();
 
                         // SAFETY: The current module address must come from the current
@@ -3687,16 +4210,25 @@ pub(super) fn run<T: Config>(
                         // SAFETY: This memory address was just read from the
                         // current store. Therefore, it is valid in the current
                         // store.
-                        let memory = unsafe { store.memories.get(mem_addr) };
-
-                        let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let idx = calculate_mem_address(&memarg, relative_address)?;
-
-                        let data = memory.mem.load::<4, u32>(idx)? as u128;
-                        stack.push_value::<T>(Value::V128(data.to_le_bytes()))?;
+                        let memory = unsafe { store.memories.get_mut(mem_addr) };
+
+                        let relative_address: u32 = stack.pop();
+                        let idx = calculate_mem_address(memarg.offset, relative_address)?;
+
+                        let data =
+                            mem_load::<_, 4, u32>(memory, &mut store.user_data, mem_addr, idx)?
+                                as u128;
+                        stack.push_value(Value::V128(data.to_le_bytes()))?;
+                        interrupt_on_break!(store.user_data.memory_load_hook(
+                            mem_addr,
+                            idx,
+                            &data.to_le_bytes()[..4]
+                        ));
                     }
                     V128_LOAD64_ZERO => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_LOAD64_ZERO));
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(V128_LOAD64_ZERO));
                         let memarg = MemArg::read(wasm).unwrap_validated();
                         // SAFETY: The current module address must come from the current
                         // store, because it is the only parameter to this function that
@@ -3710,20 +4242,29 @@ pub(super) fn run<T: Config>(
                         // SAFETY: This memory address was just read from the
                         // current store. Therefore, it is valid in the current
                         // store.
-                        let memory = unsafe { store.memories.get(mem_addr) };
-
-                        let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let idx = calculate_mem_address(&memarg, relative_address)?;
-
-                        let data = memory.mem.load::<8, u64>(idx)? as u128;
-                        stack.push_value::<T>(Value::V128(data.to_le_bytes()))?;
+                        let memory = unsafe { store.memories.get_mut(mem_addr) };
+
+                        let relative_address: u32 = stack.pop();
+                        let idx = calculate_mem_address(memarg.offset, relative_address)?;
+
+                        let data =
+                            mem_load::<_, 8, u64>(memory, &mut store.user_data, mem_addr, idx)?
+                                as u128;
+                        stack.push_value(Value::V128(data.to_le_bytes()))?;
+                        interrupt_on_break!(store.user_data.memory_load_hook(
+                            mem_addr,
+                            idx,
+                            &data.to_le_bytes()[..8]
+                        ));
                     }
 
                     // v128.loadN_lane
                     V128_LOAD8_LANE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_LOAD8_LANE));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(V128_LOAD8_LANE));
+                        let data: [u8; 16] = stack.pop();
+                        let relative_address: u32 = stack.pop();
                         let memarg = MemArg::read(wasm).unwrap_validated();
                         // SAFETY: The current module address must come from the current
                         // store, because it is the only parameter to this function that
@@ -3737,19 +4278,26 @@ pub(super) fn run<T: Config>(
                         // SAFETY: This memory address was just read from the
                         // current store. Therefore, it is valid in the current
                         // store.
-                        let memory = unsafe { store.memories.get(mem_addr) };
-                        let idx = calculate_mem_address(&memarg, relative_address)?;
+                        let memory = unsafe { store.memories.get_mut(mem_addr) };
+                        let idx = calculate_mem_address(memarg.offset, relative_address)?;
                         let lane_idx = usize::from(wasm.read_u8().unwrap_validated());
                         let mut lanes: [u8; 16] = to_lanes(data);
                         *lanes.get_mut(lane_idx).unwrap_validated() =
-                            memory.mem.load::<1, u8>(idx)?;
-                        stack.push_value::<T>(Value::V128(from_lanes(lanes)))?;
+                            mem_load::<_, 1, u8>(memory, &mut store.user_data, mem_addr, idx)?;
+                        stack.push_value(Value::V128(from_lanes(lanes)))?;
+                        interrupt_on_break!(store.user_data.memory_load_hook(
+                            mem_addr,
+                            idx,
+                            &lanes.get(lane_idx).unwrap_validated().to_le_bytes()
+                        ));
                     }
 
                     V128_LOAD16_LANE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_LOAD16_LANE));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(V128_LOAD16_LANE));
+                        let data: [u8; 16] = stack.pop();
+                        let relative_address: u32 = stack.pop();
                         let memarg = MemArg::read(wasm).unwrap_validated();
                         // SAFETY: The current module address must come from the current
                         // store, because it is the only parameter to this function that
@@ -3763,18 +4311,25 @@ pub(super) fn run<T: Config>(
                         // SAFETY: This memory address was just read from the
                         // current store. Therefore, it is valid in the current
                         // store.
-                        let memory = unsafe { store.memories.get(mem_addr) };
-                        let idx = calculate_mem_address(&memarg, relative_address)?;
+                        let memory = unsafe { store.memories.get_mut(mem_addr) };
+                        let idx = calculate_mem_address(memarg.offset, relative_address)?;
                         let lane_idx = usize::from(wasm.read_u8().unwrap_validated());
                         let mut lanes: [u16; 8] = to_lanes(data);
                         *lanes.get_mut(lane_idx).unwrap_validated() =
-                            memory.mem.load::<2, u16>(idx)?;
-                        stack.push_value::<T>(Value::V128(from_lanes(lanes)))?;
+                            mem_load::<_, 2, u16>(memory, &mut store.user_data, mem_addr, idx)?;
+                        stack.push_value(Value::V128(from_lanes(lanes)))?;
+                        interrupt_on_break!(store.user_data.memory_load_hook(
+                            mem_addr,
+                            idx,
+                            &lanes.get(lane_idx).unwrap_validated().to_le_bytes()
+                        ));
                     }
                     V128_LOAD32_LANE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_LOAD32_LANE));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(V128_LOAD32_LANE));
+                        let data: [u8; 16] = stack.pop();
+                        let relative_address: u32 = stack.pop();
                         let memarg = MemArg::read(wasm).unwrap_validated();
                         // SAFETY: The current module address must come from the current
                         // store, because it is the only parameter to this function that
@@ -3788,18 +4343,25 @@ pub(super) fn run<T: Config>(
                         // SAFETY: This memory address was just read from the
                         // current store. Therefore, it is valid in the current
                         // store.
-                        let memory = unsafe { store.memories.get(mem_addr) };
-                        let idx = calculate_mem_address(&memarg, relative_address)?;
+                        let memory = unsafe { store.memories.get_mut(mem_addr) };
+                        let idx = calculate_mem_address(memarg.offset, relative_address)?;
                         let lane_idx = usize::from(wasm.read_u8().unwrap_validated());
                         let mut lanes: [u32; 4] = to_lanes(data);
                         *lanes.get_mut(lane_idx).unwrap_validated() =
-                            memory.mem.load::<4, u32>(idx)?;
-                        stack.push_value::<T>(Value::V128(from_lanes(lanes)))?;
+                            mem_load::<_, 4, u32>(memory, &mut store.user_data, mem_addr, idx)?;
+                        stack.push_value(Value::V128(from_lanes(lanes)))?;
+                        interrupt_on_break!(store.user_data.memory_load_hook(
+                            mem_addr,
+                            idx,
+                            &lanes.get(lane_idx).unwrap_validated().to_le_bytes()
+                        ));
                     }
                     V128_LOAD64_LANE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_LOAD64_LANE));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(V128_LOAD64_LANE));
+                        let data: [u8; 16] = stack.pop();
+                        let relative_address: u32 = stack.pop();
                         let memarg = MemArg::read(wasm).unwrap_validated();
                         // SAFETY: The current module address must come from the current
                         // store, because it is the only parameter to this function that
@@ -3813,20 +4375,27 @@ pub(super) fn run<T: Config>(
                         // SAFETY: This memory address was just read from the
                         // current store. Therefore, it is valid in the current
                         // store.
-                        let memory = unsafe { store.memories.get(mem_addr) };
-                        let idx = calculate_mem_address(&memarg, relative_address)?;
+                        let memory = unsafe { store.memories.get_mut(mem_addr) };
+                        let idx = calculate_mem_address(memarg.offset, relative_address)?;
                         let lane_idx = usize::from(wasm.read_u8().unwrap_validated());
                         let mut lanes: [u64; 2] = to_lanes(data);
                         *lanes.get_mut(lane_idx).unwrap_validated() =
-                            memory.mem.load::<8, u64>(idx)?;
-                        stack.push_value::<T>(Value::V128(from_lanes(lanes)))?;
+                            mem_load::<_, 8, u64>(memory, &mut store.user_data, mem_addr, idx)?;
+                        stack.push_value(Value::V128(from_lanes(lanes)))?;
+                        interrupt_on_break!(store.user_data.memory_load_hook(
+                            mem_addr,
+                            idx,
+                            &lanes.get(lane_idx).unwrap_validated().to_le_bytes()
+                        ));
                     }
 
                     // v128.storeN_lane
                     V128_STORE8_LANE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_STORE8_LANE));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(V128_STORE8_LANE));
+                        let data: [u8; 16] = stack.pop();
+                        let relative_address: u32 = stack.pop();
                         let memarg = MemArg::read(wasm).unwrap_validated();
                         // SAFETY: The current module address must come from the current
                         // store, because it is the only parameter to this function that
@@ -3840,18 +4409,25 @@ pub(super) fn run<T: Config>(
                         // SAFETY: This memory address was just read from the
                         // current store. Therefore, it is valid in the current
                         // store.
-                        let memory = unsafe { store.memories.get(mem_addr) };
-                        let idx = calculate_mem_address(&memarg, relative_address)?;
+                        let memory = unsafe { store.memories.get_mut(mem_addr) };
+                        let idx = calculate_mem_address(memarg.offset, relative_address)?;
                         let lane_idx = usize::from(wasm.read_u8().unwrap_validated());
 
                         let lane = *to_lanes::<1, 16, u8>(data).get(lane_idx).unwrap_validated();
 
-                        memory.mem.store::<1, u8>(idx, lane)?;
+                        mem_store::<_, 1, u8>(memory, &mut store.user_data, mem_addr, idx, lane)?;
+                        interrupt_on_break!(store.user_data.memory_store_hook(
+                            mem_addr,
+                            idx,
+                            &lane.to_le_bytes()
+                        ));
                     }
                     V128_STORE16_LANE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_STORE16_LANE));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(V128_STORE16_LANE));
+                        let data: [u8; 16] = stack.pop();
+                        let relative_address: u32 = stack.pop();
                         let memarg = MemArg::read(wasm).unwrap_validated();
                         // SAFETY: The current module address must come from the current
                         // store, because it is the only parameter to this function that
@@ -3865,18 +4441,25 @@ pub(super) fn run<T: Config>(
                         // SAFETY: This memory address was just read from the
                         // current store. Therefore, it is valid in the current
                         // store.
-                        let memory = unsafe { store.memories.get(mem_addr) };
-                        let idx = calculate_mem_address(&memarg, relative_address)?;
+                        let memory = unsafe { store.memories.get_mut(mem_addr) };
+                        let idx = calculate_mem_address(memarg.offset, relative_address)?;
                         let lane_idx = usize::from(wasm.read_u8().unwrap_validated());
 
                         let lane = *to_lanes::<2, 8, u16>(data).get(lane_idx).unwrap_validated();
 
-                        memory.mem.store::<2, u16>(idx, lane)?;
+                        mem_store::<_, 2, u16>(memory, &mut store.user_data, mem_addr, idx, lane)?;
+                        interrupt_on_break!(store.user_data.memory_store_hook(
+                            mem_addr,
+                            idx,
+                            &lane.to_le_bytes()
+                        ));
                     }
                     V128_STORE32_LANE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_STORE32_LANE));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(V128_STORE32_LANE));
+                        let data: [u8; 16] = stack.pop();
+                        let relative_address: u32 = stack.pop();
                         let memarg = MemArg::read(wasm).unwrap_validated();
                         // SAFETY: The current module address must come from the current
                         // store, because it is the only parameter to this function that
@@ -3890,18 +4473,25 @@ pub(super) fn run<T: Config>(
                         // SAFETY: This memory address was just read from the
                         // current store. Therefore, it is valid in the current
                         // store.
-                        let memory = unsafe { store.memories.get(mem_addr) };
-                        let idx = calculate_mem_address(&memarg, relative_address)?;
+                        let memory = unsafe { store.memories.get_mut(mem_addr) };
+                        let idx = calculate_mem_address(memarg.offset, relative_address)?;
                         let lane_idx = usize::from(wasm.read_u8().unwrap_validated());
 
                         let lane = *to_lanes::<4, 4, u32>(data).get(lane_idx).unwrap_validated();
 
-                        memory.mem.store::<4, u32>(idx, lane)?;
+                        mem_store::<_, 4, u32>(memory, &mut store.user_data, mem_addr, idx, lane)?;
+                        interrupt_on_break!(store.user_data.memory_store_hook(
+                            mem_addr,
+                            idx,
+                            &lane.to_le_bytes()
+                        ));
                     }
                     V128_STORE64_LANE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_STORE64_LANE));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let relative_address: u32 = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(V128_STORE64_LANE));
+                        let data: [u8; 16] = stack.pop();
+                        let relative_address: u32 = stack.pop();
                         let memarg = MemArg::read(wasm).unwrap_validated();
                         // SAFETY: The current module address must come from the current
                         // store, because it is the only parameter to this function that
@@ -3915,94 +4505,99 @@ pub(super) fn run<T: Config>(
                         // SAFETY: This memory address was just read from the
                         // current store. Therefore, it is valid in the current
                         // store.
-                        let memory = unsafe { store.memories.get(mem_addr) };
-                        let idx = calculate_mem_address(&memarg, relative_address)?;
+                        let memory = unsafe { store.memories.get_mut(mem_addr) };
+                        let idx = calculate_mem_address(memarg.offset, relative_address)?;
                         let lane_idx = usize::from(wasm.read_u8().unwrap_validated());
 
                         let lane = *to_lanes::<8, 2, u64>(data).get(lane_idx).unwrap_validated();
 
-                        memory.mem.store::<8, u64>(idx, lane)?;
+                        mem_store::<_, 8, u64>(memory, &mut store.user_data, mem_addr, idx, lane)?;
+                        interrupt_on_break!(store.user_data.memory_store_hook(
+                            mem_addr,
+                            idx,
+                            &lane.to_le_bytes()
+                        ));
                     }
 
                     V128_CONST => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_CONST));
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(V128_CONST));
                         let mut data = [0; 16];
                         for byte_ref in &mut data {
                             *byte_ref = wasm.read_u8().unwrap_validated();
                         }
 
-                        stack.push_value::<T>(Value::V128(data))?;
+                        stack.push_value(Value::V128(data))?;
                     }
 
                     // vvunop <https://webassembly.github.io/spec/core/syntax/instructions.html#syntax-vvunop>
                     V128_NOT => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_NOT));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        stack.push_value::<T>(Value::V128(data.map(|byte| !byte)))?;
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(V128_NOT));
+                        let data: [u8; 16] = stack.pop();
+                        stack.push_value(Value::V128(data.map(|byte| !byte)))?;
                     }
 
                     // vvbinop <https://webassembly.github.io/spec/core/syntax/instructions.html#syntax-vvbinop>
                     V128_AND => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_AND));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(V128_AND));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let result = array::from_fn(|i| data1[i] & data2[i]);
-                        stack.push_value::<T>(Value::V128(result))?;
+                        stack.push_value(Value::V128(result))?;
                     }
                     V128_ANDNOT => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_ANDNOT));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(V128_ANDNOT));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let result = array::from_fn(|i| data1[i] & !data2[i]);
-                        stack.push_value::<T>(Value::V128(result))?;
+                        stack.push_value(Value::V128(result))?;
                     }
                     V128_OR => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_OR));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(V128_OR));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let result = array::from_fn(|i| data1[i] | data2[i]);
-                        stack.push_value::<T>(Value::V128(result))?;
+                        stack.push_value(Value::V128(result))?;
                     }
                     V128_XOR => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_XOR));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(V128_XOR));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let result = array::from_fn(|i| data1[i] ^ data2[i]);
-                        stack.push_value::<T>(Value::V128(result))?;
+                        stack.push_value(Value::V128(result))?;
                     }
 
                     // vvternop <https://webassembly.github.io/spec/core/syntax/instructions.html#syntax-vvternop>
                     V128_BITSELECT => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_BITSELECT));
-                        let data3: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(V128_BITSELECT));
+                        let data3: [u8; 16] = stack.pop();
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let result =
                             array::from_fn(|i| (data1[i] & data3[i]) | (data2[i] & !data3[i]));
-                        stack.push_value::<T>(Value::V128(result))?;
+                        stack.push_value(Value::V128(result))?;
                     }
 
                     // vvtestop <https://webassembly.github.io/spec/core/syntax/instructions.html#syntax-vvtestop>
                     V128_ANY_TRUE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(V128_ANY_TRUE));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(V128_ANY_TRUE));
+                        let data: [u8; 16] = stack.pop();
                         let any_true = data.into_iter().any(|byte| byte > 0);
-                        stack.push_value::<T>(Value::I32(any_true as u32))?;
+                        stack.push_value(Value::I32(any_true as u32))?;
                     }
 
                     I8X16_SWIZZLE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_SWIZZLE));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_SWIZZLE));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let result =
                             array::from_fn(|i| *data1.get(usize::from(data2[i])).unwrap_or(&0));
-                        stack.push_value::<T>(Value::V128(result))?;
+                        stack.push_value(Value::V128(result))?;
                     }
 
                     I8X16_SHUFFLE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_SHUFFLE));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_SHUFFLE));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
 
                         let lane_selector_indices: [u8; 16] =
                             array::from_fn(|_| wasm.read_u8().unwrap_validated());
@@ -4014,534 +4609,562 @@ pub(super) fn run<T: Config>(
                                 .unwrap_validated()
                         });
 
-                        stack.push_value::<T>(Value::V128(result))?;
+                        stack.push_value(Value::V128(result))?;
                     }
 
                     // shape.splat
                     I8X16_SPLAT => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_SPLAT));
-                        let value: u32 = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_SPLAT));
+                        let value: u32 = stack.pop();
                         let lane = value as u8;
                         let data = from_lanes([lane; 16]);
-                        stack.push_value::<T>(Value::V128(data))?;
+                        stack.push_value(Value::V128(data))?;
                     }
                     I16X8_SPLAT => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_SPLAT));
-                        let value: u32 = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I16X8_SPLAT));
+                        let value: u32 = stack.pop();
                         let lane = value as u16;
                         let data = from_lanes([lane; 8]);
-                        stack.push_value::<T>(Value::V128(data))?;
+                        stack.push_value(Value::V128(data))?;
                     }
                     I32X4_SPLAT => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_SPLAT));
-                        let lane: u32 = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I32X4_SPLAT));
+                        let lane: u32 = stack.pop();
                         let data = from_lanes([lane; 4]);
-                        stack.push_value::<T>(Value::V128(data))?;
+                        stack.push_value(Value::V128(data))?;
                     }
                     I64X2_SPLAT => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I64X2_SPLAT));
-                        let lane: u64 = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I64X2_SPLAT));
+                        let lane: u64 = stack.pop();
                         let data = from_lanes([lane; 2]);
-                        stack.push_value::<T>(Value::V128(data))?;
+                        stack.push_value(Value::V128(data))?;
                     }
                     F32X4_SPLAT => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F32X4_SPLAT));
-                        let lane: F32 = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F32X4_SPLAT));
+                        let lane: F32 = stack.pop();
                         let data = from_lanes([lane; 4]);
-                        stack.push_value::<T>(Value::V128(data))?;
+                        stack.push_value(Value::V128(data))?;
                     }
                     F64X2_SPLAT => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F64X2_SPLAT));
-                        let lane: F64 = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F64X2_SPLAT));
+                        let lane: F64 = stack.pop();
                         let data = from_lanes([lane; 2]);
-                        stack.push_value::<T>(Value::V128(data))?;
+                        stack.push_value(Value::V128(data))?;
                     }
 
                     // shape.extract_lane
                     I8X16_EXTRACT_LANE_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_EXTRACT_LANE_S));
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I8X16_EXTRACT_LANE_S));
                         let lane_idx = usize::from(wasm.read_u8().unwrap_validated());
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i8; 16] = to_lanes(data);
                         let lane = *lanes.get(lane_idx).unwrap_validated();
-                        stack.push_value::<T>(Value::I32(lane as u32))?;
+                        stack.push_value(Value::I32(lane as u32))?;
                     }
                     I8X16_EXTRACT_LANE_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_EXTRACT_LANE_U));
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I8X16_EXTRACT_LANE_U));
                         let lane_idx = usize::from(wasm.read_u8().unwrap_validated());
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [u8; 16] = to_lanes(data);
                         let lane = *lanes.get(lane_idx).unwrap_validated();
-                        stack.push_value::<T>(Value::I32(lane as u32))?;
+                        stack.push_value(Value::I32(lane as u32))?;
                     }
                     I16X8_EXTRACT_LANE_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_EXTRACT_LANE_S));
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I16X8_EXTRACT_LANE_S));
                         let lane_idx = usize::from(wasm.read_u8().unwrap_validated());
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i16; 8] = to_lanes(data);
                         let lane = *lanes.get(lane_idx).unwrap_validated();
-                        stack.push_value::<T>(Value::I32(lane as u32))?;
+                        stack.push_value(Value::I32(lane as u32))?;
                     }
                     I16X8_EXTRACT_LANE_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_EXTRACT_LANE_U));
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I16X8_EXTRACT_LANE_U));
                         let lane_idx = usize::from(wasm.read_u8().unwrap_validated());
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [u16; 8] = to_lanes(data);
                         let lane = *lanes.get(lane_idx).unwrap_validated();
-                        stack.push_value::<T>(Value::I32(lane as u32))?;
+                        stack.push_value(Value::I32(lane as u32))?;
                     }
                     I32X4_EXTRACT_LANE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_EXTRACT_LANE));
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I32X4_EXTRACT_LANE));
                         let lane_idx = usize::from(wasm.read_u8().unwrap_validated());
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [u32; 4] = to_lanes(data);
                         let lane = *lanes.get(lane_idx).unwrap_validated();
-                        stack.push_value::<T>(Value::I32(lane))?;
+                        stack.push_value(Value::I32(lane))?;
                     }
                     I64X2_EXTRACT_LANE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I64X2_EXTRACT_LANE));
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I64X2_EXTRACT_LANE));
                         let lane_idx = usize::from(wasm.read_u8().unwrap_validated());
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [u64; 2] = to_lanes(data);
                         let lane = *lanes.get(lane_idx).unwrap_validated();
-                        stack.push_value::<T>(Value::I64(lane))?;
+                        stack.push_value(Value::I64(lane))?;
                     }
                     F32X4_EXTRACT_LANE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F32X4_EXTRACT_LANE));
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(F32X4_EXTRACT_LANE));
                         let lane_idx = usize::from(wasm.read_u8().unwrap_validated());
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [F32; 4] = to_lanes(data);
                         let lane = *lanes.get(lane_idx).unwrap_validated();
-                        stack.push_value::<T>(Value::F32(lane))?;
+                        stack.push_value(Value::F32(lane))?;
                     }
                     F64X2_EXTRACT_LANE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F64X2_EXTRACT_LANE));
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(F64X2_EXTRACT_LANE));
                         let lane_idx = usize::from(wasm.read_u8().unwrap_validated());
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [F64; 2] = to_lanes(data);
                         let lane = *lanes.get(lane_idx).unwrap_validated();
-                        stack.push_value::<T>(Value::F64(lane))?;
+                        stack.push_value(Value::F64(lane))?;
                     }
 
                     // shape.replace_lane
                     I8X16_REPLACE_LANE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_REPLACE_LANE));
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I8X16_REPLACE_LANE));
                         let lane_idx = usize::from(wasm.read_u8().unwrap_validated());
-                        let value: u32 = stack.pop_value().try_into().unwrap_validated();
+                        let value: u32 = stack.pop();
                         let new_lane = value as u8;
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        let data: [u8; 16] = stack.pop();
                         let mut lanes: [u8; 16] = to_lanes(data);
                         *lanes.get_mut(lane_idx).unwrap_validated() = new_lane;
-                        stack.push_value::<T>(Value::V128(from_lanes(lanes)))?;
+                        stack.push_value(Value::V128(from_lanes(lanes)))?;
                     }
                     I16X8_REPLACE_LANE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_REPLACE_LANE));
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I16X8_REPLACE_LANE));
                         let lane_idx = usize::from(wasm.read_u8().unwrap_validated());
-                        let value: u32 = stack.pop_value().try_into().unwrap_validated();
+                        let value: u32 = stack.pop();
                         let new_lane = value as u16;
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        let data: [u8; 16] = stack.pop();
                         let mut lanes: [u16; 8] = to_lanes(data);
                         *lanes.get_mut(lane_idx).unwrap_validated() = new_lane;
-                        stack.push_value::<T>(Value::V128(from_lanes(lanes)))?;
+                        stack.push_value(Value::V128(from_lanes(lanes)))?;
                     }
                     I32X4_REPLACE_LANE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_REPLACE_LANE));
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I32X4_REPLACE_LANE));
                         let lane_idx = usize::from(wasm.read_u8().unwrap_validated());
-                        let new_lane: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        let new_lane: u32 = stack.pop();
+                        let data: [u8; 16] = stack.pop();
                         let mut lanes: [u32; 4] = to_lanes(data);
                         *lanes.get_mut(lane_idx).unwrap_validated() = new_lane;
-                        stack.push_value::<T>(Value::V128(from_lanes(lanes)))?;
+                        stack.push_value(Value::V128(from_lanes(lanes)))?;
                     }
                     I64X2_REPLACE_LANE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I64X2_REPLACE_LANE));
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I64X2_REPLACE_LANE));
                         let lane_idx = usize::from(wasm.read_u8().unwrap_validated());
-                        let new_lane: u64 = stack.pop_value().try_into().unwrap_validated();
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        let new_lane: u64 = stack.pop();
+                        let data: [u8; 16] = stack.pop();
                         let mut lanes: [u64; 2] = to_lanes(data);
                         *lanes.get_mut(lane_idx).unwrap_validated() = new_lane;
-                        stack.push_value::<T>(Value::V128(from_lanes(lanes)))?;
+                        stack.push_value(Value::V128(from_lanes(lanes)))?;
                     }
                     F32X4_REPLACE_LANE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F32X4_REPLACE_LANE));
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(F32X4_REPLACE_LANE));
                         let lane_idx = usize::from(wasm.read_u8().unwrap_validated());
-                        let new_lane: F32 = stack.pop_value().try_into().unwrap_validated();
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        let new_lane: F32 = stack.pop();
+                        let data: [u8; 16] = stack.pop();
                         let mut lanes: [F32; 4] = to_lanes(data);
                         *lanes.get_mut(lane_idx).unwrap_validated() = new_lane;
-                        stack.push_value::<T>(Value::V128(from_lanes(lanes)))?;
+                        stack.push_value(Value::V128(from_lanes(lanes)))?;
                     }
                     F64X2_REPLACE_LANE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F64X2_REPLACE_LANE));
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(F64X2_REPLACE_LANE));
                         let lane_idx = usize::from(wasm.read_u8().unwrap_validated());
-                        let new_lane: F64 = stack.pop_value().try_into().unwrap_validated();
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        let new_lane: F64 = stack.pop();
+                        let data: [u8; 16] = stack.pop();
                         let mut lanes: [F64; 2] = to_lanes(data);
                         *lanes.get_mut(lane_idx).unwrap_validated() = new_lane;
-                        stack.push_value::<T>(Value::V128(from_lanes(lanes)))?;
+                        stack.push_value(Value::V128(from_lanes(lanes)))?;
                     }
 
                     // Group vunop <https://webassembly.github.io/spec/core/syntax/instructions.html#syntax-vunop>
                     // viunop
                     I8X16_ABS => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_ABS));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_ABS));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i8; 16] = to_lanes(data);
                         let result: [i8; 16] = lanes.map(i8::wrapping_abs);
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_ABS => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_ABS));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I16X8_ABS));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i16; 8] = to_lanes(data);
                         let result: [i16; 8] = lanes.map(i16::wrapping_abs);
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_ABS => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_ABS));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I32X4_ABS));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i32; 4] = to_lanes(data);
                         let result: [i32; 4] = lanes.map(i32::wrapping_abs);
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I64X2_ABS => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I64X2_ABS));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I64X2_ABS));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i64; 2] = to_lanes(data);
                         let result: [i64; 2] = lanes.map(i64::wrapping_abs);
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I8X16_NEG => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_NEG));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_NEG));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i8; 16] = to_lanes(data);
                         let result: [i8; 16] = lanes.map(i8::wrapping_neg);
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_NEG => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_NEG));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I16X8_NEG));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i16; 8] = to_lanes(data);
                         let result: [i16; 8] = lanes.map(i16::wrapping_neg);
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_NEG => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_NEG));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I32X4_NEG));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i32; 4] = to_lanes(data);
                         let result: [i32; 4] = lanes.map(i32::wrapping_neg);
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I64X2_NEG => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I64X2_NEG));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I64X2_NEG));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i64; 2] = to_lanes(data);
                         let result: [i64; 2] = lanes.map(i64::wrapping_neg);
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     // vfunop
                     F32X4_ABS => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F32X4_ABS));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F32X4_ABS));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [F32; 4] = to_lanes(data);
                         let result: [F32; 4] = lanes.map(|lane| lane.abs());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F64X2_ABS => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F64X2_ABS));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F64X2_ABS));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [F64; 2] = to_lanes(data);
                         let result: [F64; 2] = lanes.map(|lane| lane.abs());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F32X4_NEG => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F32X4_NEG));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F32X4_NEG));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [F32; 4] = to_lanes(data);
                         let result: [F32; 4] = lanes.map(|lane| lane.neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F64X2_NEG => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F64X2_NEG));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F64X2_NEG));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [F64; 2] = to_lanes(data);
                         let result: [F64; 2] = lanes.map(|lane| lane.neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F32X4_SQRT => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F32X4_SQRT));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F32X4_SQRT));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [F32; 4] = to_lanes(data);
                         let result: [F32; 4] = lanes.map(|lane| lane.sqrt());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F64X2_SQRT => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F64X2_SQRT));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F64X2_SQRT));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [F64; 2] = to_lanes(data);
                         let result: [F64; 2] = lanes.map(|lane| lane.sqrt());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F32X4_CEIL => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F32X4_CEIL));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F32X4_CEIL));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [F32; 4] = to_lanes(data);
                         let result: [F32; 4] = lanes.map(|lane| lane.ceil());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F64X2_CEIL => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F64X2_CEIL));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F64X2_CEIL));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [F64; 2] = to_lanes(data);
                         let result: [F64; 2] = lanes.map(|lane| lane.ceil());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F32X4_FLOOR => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F32X4_FLOOR));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F32X4_FLOOR));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [F32; 4] = to_lanes(data);
                         let result: [F32; 4] = lanes.map(|lane| lane.floor());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F64X2_FLOOR => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F64X2_FLOOR));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F64X2_FLOOR));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [F64; 2] = to_lanes(data);
                         let result: [F64; 2] = lanes.map(|lane| lane.floor());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F32X4_TRUNC => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F32X4_TRUNC));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F32X4_TRUNC));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [F32; 4] = to_lanes(data);
                         let result: [F32; 4] = lanes.map(|lane| lane.trunc());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F64X2_TRUNC => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F64X2_TRUNC));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F64X2_TRUNC));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [F64; 2] = to_lanes(data);
                         let result: [F64; 2] = lanes.map(|lane| lane.trunc());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F32X4_NEAREST => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F32X4_NEAREST));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F32X4_NEAREST));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [F32; 4] = to_lanes(data);
                         let result: [F32; 4] = lanes.map(|lane| lane.nearest());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F64X2_NEAREST => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F64X2_NEAREST));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F64X2_NEAREST));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [F64; 2] = to_lanes(data);
                         let result: [F64; 2] = lanes.map(|lane| lane.nearest());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     // others
                     I8X16_POPCNT => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_POPCNT));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_POPCNT));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [u8; 16] = to_lanes(data);
                         let result: [u8; 16] = lanes.map(|lane| lane.count_ones() as u8);
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
 
                     // Group vbinop <https://webassembly.github.io/spec/core/syntax/instructions.html#syntax-vbinop>
                     // vibinop
                     I8X16_ADD => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_ADD));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_ADD));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u8; 16] = to_lanes(data2);
                         let lanes1: [u8; 16] = to_lanes(data1);
                         let result: [u8; 16] =
                             array::from_fn(|i| lanes1[i].wrapping_add(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_ADD => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_ADD));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I16X8_ADD));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u16; 8] = to_lanes(data2);
                         let lanes1: [u16; 8] = to_lanes(data1);
                         let result: [u16; 8] =
                             array::from_fn(|i| lanes1[i].wrapping_add(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_ADD => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_ADD));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I32X4_ADD));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u32; 4] = to_lanes(data2);
                         let lanes1: [u32; 4] = to_lanes(data1);
                         let result: [u32; 4] =
                             array::from_fn(|i| lanes1[i].wrapping_add(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I64X2_ADD => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I64X2_ADD));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I64X2_ADD));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u64; 2] = to_lanes(data2);
                         let lanes1: [u64; 2] = to_lanes(data1);
                         let result: [u64; 2] =
                             array::from_fn(|i| lanes1[i].wrapping_add(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I8X16_SUB => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_SUB));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_SUB));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u8; 16] = to_lanes(data2);
                         let lanes1: [u8; 16] = to_lanes(data1);
                         let result: [u8; 16] =
                             array::from_fn(|i| lanes1[i].wrapping_sub(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_SUB => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_SUB));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I16X8_SUB));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u16; 8] = to_lanes(data2);
                         let lanes1: [u16; 8] = to_lanes(data1);
                         let result: [u16; 8] =
                             array::from_fn(|i| lanes1[i].wrapping_sub(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_SUB => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_SUB));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I32X4_SUB));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u32; 4] = to_lanes(data2);
                         let lanes1: [u32; 4] = to_lanes(data1);
                         let result: [u32; 4] =
                             array::from_fn(|i| lanes1[i].wrapping_sub(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I64X2_SUB => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I64X2_SUB));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I64X2_SUB));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u64; 2] = to_lanes(data2);
                         let lanes1: [u64; 2] = to_lanes(data1);
                         let result: [u64; 2] =
                             array::from_fn(|i| lanes1[i].wrapping_sub(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     // vfbinop
                     F32X4_ADD => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F32X4_ADD));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F32X4_ADD));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F32; 4] = to_lanes(data2);
                         let lanes1: [F32; 4] = to_lanes(data1);
                         let result: [F32; 4] = array::from_fn(|i| lanes1[i].add(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F64X2_ADD => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F64X2_ADD));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F64X2_ADD));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F64; 2] = to_lanes(data2);
                         let lanes1: [F64; 2] = to_lanes(data1);
                         let result: [F64; 2] = array::from_fn(|i| lanes1[i].add(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F32X4_SUB => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F32X4_SUB));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F32X4_SUB));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F32; 4] = to_lanes(data2);
                         let lanes1: [F32; 4] = to_lanes(data1);
                         let result: [F32; 4] = array::from_fn(|i| lanes1[i].sub(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F64X2_SUB => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F64X2_SUB));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F64X2_SUB));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F64; 2] = to_lanes(data2);
                         let lanes1: [F64; 2] = to_lanes(data1);
                         let result: [F64; 2] = array::from_fn(|i| lanes1[i].sub(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F32X4_MUL => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F32X4_MUL));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F32X4_MUL));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F32; 4] = to_lanes(data2);
                         let lanes1: [F32; 4] = to_lanes(data1);
                         let result: [F32; 4] = array::from_fn(|i| lanes1[i].mul(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F64X2_MUL => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F64X2_MUL));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F64X2_MUL));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F64; 2] = to_lanes(data2);
                         let lanes1: [F64; 2] = to_lanes(data1);
                         let result: [F64; 2] = array::from_fn(|i| lanes1[i].mul(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F32X4_DIV => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F32X4_DIV));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F32X4_DIV));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F32; 4] = to_lanes(data2);
                         let lanes1: [F32; 4] = to_lanes(data1);
                         let result: [F32; 4] = array::from_fn(|i| lanes1[i].div(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F64X2_DIV => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F64X2_DIV));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F64X2_DIV));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F64; 2] = to_lanes(data2);
                         let lanes1: [F64; 2] = to_lanes(data1);
                         let result: [F64; 2] = array::from_fn(|i| lanes1[i].div(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F32X4_MIN => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F32X4_MIN));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F32X4_MIN));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F32; 4] = to_lanes(data2);
                         let lanes1: [F32; 4] = to_lanes(data1);
                         let result: [F32; 4] = array::from_fn(|i| lanes1[i].min(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F64X2_MIN => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F64X2_MIN));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F64X2_MIN));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F64; 2] = to_lanes(data2);
                         let lanes1: [F64; 2] = to_lanes(data1);
                         let result: [F64; 2] = array::from_fn(|i| lanes1[i].min(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F32X4_MAX => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F32X4_MAX));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F32X4_MAX));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F32; 4] = to_lanes(data2);
                         let lanes1: [F32; 4] = to_lanes(data1);
                         let result: [F32; 4] = array::from_fn(|i| lanes1[i].max(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F64X2_MAX => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F64X2_MAX));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F64X2_MAX));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F64; 2] = to_lanes(data2);
                         let lanes1: [F64; 2] = to_lanes(data1);
                         let result: [F64; 2] = array::from_fn(|i| lanes1[i].max(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F32X4_PMIN => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F32X4_PMIN));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F32X4_PMIN));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F32; 4] = to_lanes(data2);
                         let lanes1: [F32; 4] = to_lanes(data1);
                         let result: [F32; 4] = array::from_fn(|i| {
@@ -4553,12 +5176,12 @@ pub(super) fn run<T: Config>(
                                 v1
                             }
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F64X2_PMIN => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F64X2_PMIN));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F64X2_PMIN));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F64; 2] = to_lanes(data2);
                         let lanes1: [F64; 2] = to_lanes(data1);
                         let result: [F64; 2] = array::from_fn(|i| {
@@ -4570,12 +5193,12 @@ pub(super) fn run<T: Config>(
                                 v1
                             }
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F32X4_PMAX => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F32X4_PMAX));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F32X4_PMAX));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F32; 4] = to_lanes(data2);
                         let lanes1: [F32; 4] = to_lanes(data1);
                         let result: [F32; 4] = array::from_fn(|i| {
@@ -4587,12 +5210,12 @@ pub(super) fn run<T: Config>(
                                 v1
                             }
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F64X2_PMAX => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F64X2_PMAX));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F64X2_PMAX));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F64; 2] = to_lanes(data2);
                         let lanes1: [F64; 2] = to_lanes(data1);
                         let result: [F64; 2] = array::from_fn(|i| {
@@ -4604,256 +5227,274 @@ pub(super) fn run<T: Config>(
                                 v1
                             }
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     // viminmaxop
                     I8X16_MIN_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_MIN_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_MIN_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i8; 16] = to_lanes(data2);
                         let lanes1: [i8; 16] = to_lanes(data1);
                         let result: [i8; 16] = array::from_fn(|i| lanes1[i].min(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_MIN_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_MIN_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I16X8_MIN_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i16; 8] = to_lanes(data2);
                         let lanes1: [i16; 8] = to_lanes(data1);
                         let result: [i16; 8] = array::from_fn(|i| lanes1[i].min(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_MIN_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_MIN_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I32X4_MIN_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i32; 4] = to_lanes(data2);
                         let lanes1: [i32; 4] = to_lanes(data1);
                         let result: [i32; 4] = array::from_fn(|i| lanes1[i].min(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I8X16_MIN_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_MIN_U));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_MIN_U));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u8; 16] = to_lanes(data2);
                         let lanes1: [u8; 16] = to_lanes(data1);
                         let result: [u8; 16] = array::from_fn(|i| lanes1[i].min(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_MIN_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_MIN_U));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I16X8_MIN_U));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u16; 8] = to_lanes(data2);
                         let lanes1: [u16; 8] = to_lanes(data1);
                         let result: [u16; 8] = array::from_fn(|i| lanes1[i].min(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_MIN_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_MIN_U));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I32X4_MIN_U));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u32; 4] = to_lanes(data2);
                         let lanes1: [u32; 4] = to_lanes(data1);
                         let result: [u32; 4] = array::from_fn(|i| lanes1[i].min(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I8X16_MAX_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_MAX_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_MAX_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i8; 16] = to_lanes(data2);
                         let lanes1: [i8; 16] = to_lanes(data1);
                         let result: [i8; 16] = array::from_fn(|i| lanes1[i].max(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_MAX_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_MAX_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I16X8_MAX_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i16; 8] = to_lanes(data2);
                         let lanes1: [i16; 8] = to_lanes(data1);
                         let result: [i16; 8] = array::from_fn(|i| lanes1[i].max(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_MAX_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_MAX_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I32X4_MAX_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i32; 4] = to_lanes(data2);
                         let lanes1: [i32; 4] = to_lanes(data1);
                         let result: [i32; 4] = array::from_fn(|i| lanes1[i].max(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I8X16_MAX_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_MAX_U));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_MAX_U));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u8; 16] = to_lanes(data2);
                         let lanes1: [u8; 16] = to_lanes(data1);
                         let result: [u8; 16] = array::from_fn(|i| lanes1[i].max(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_MAX_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_MAX_U));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I16X8_MAX_U));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u16; 8] = to_lanes(data2);
                         let lanes1: [u16; 8] = to_lanes(data1);
                         let result: [u16; 8] = array::from_fn(|i| lanes1[i].max(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_MAX_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_MAX_U));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I32X4_MAX_U));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u32; 4] = to_lanes(data2);
                         let lanes1: [u32; 4] = to_lanes(data1);
                         let result: [u32; 4] = array::from_fn(|i| lanes1[i].max(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
 
                     // visatbinop
                     I8X16_ADD_SAT_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_ADD_SAT_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I8X16_ADD_SAT_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i8; 16] = to_lanes(data2);
                         let lanes1: [i8; 16] = to_lanes(data1);
                         let result: [i8; 16] =
                             array::from_fn(|i| lanes1[i].saturating_add(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_ADD_SAT_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_ADD_SAT_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I16X8_ADD_SAT_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i16; 8] = to_lanes(data2);
                         let lanes1: [i16; 8] = to_lanes(data1);
                         let result: [i16; 8] =
                             array::from_fn(|i| lanes1[i].saturating_add(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I8X16_ADD_SAT_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_ADD_SAT_U));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I8X16_ADD_SAT_U));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u8; 16] = to_lanes(data2);
                         let lanes1: [u8; 16] = to_lanes(data1);
                         let result: [u8; 16] =
                             array::from_fn(|i| lanes1[i].saturating_add(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_ADD_SAT_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_ADD_SAT_U));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I16X8_ADD_SAT_U));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u16; 8] = to_lanes(data2);
                         let lanes1: [u16; 8] = to_lanes(data1);
                         let result: [u16; 8] =
                             array::from_fn(|i| lanes1[i].saturating_add(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I8X16_SUB_SAT_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_SUB_SAT_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I8X16_SUB_SAT_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i8; 16] = to_lanes(data2);
                         let lanes1: [i8; 16] = to_lanes(data1);
                         let result: [i8; 16] =
                             array::from_fn(|i| lanes1[i].saturating_sub(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_SUB_SAT_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_SUB_SAT_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I16X8_SUB_SAT_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i16; 8] = to_lanes(data2);
                         let lanes1: [i16; 8] = to_lanes(data1);
                         let result: [i16; 8] =
                             array::from_fn(|i| lanes1[i].saturating_sub(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I8X16_SUB_SAT_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_SUB_SAT_U));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I8X16_SUB_SAT_U));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u8; 16] = to_lanes(data2);
                         let lanes1: [u8; 16] = to_lanes(data1);
                         let result: [u8; 16] =
                             array::from_fn(|i| lanes1[i].saturating_sub(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_SUB_SAT_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_SUB_SAT_U));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I16X8_SUB_SAT_U));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u16; 8] = to_lanes(data2);
                         let lanes1: [u16; 8] = to_lanes(data1);
                         let result: [u16; 8] =
                             array::from_fn(|i| lanes1[i].saturating_sub(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     // others
                     I16X8_MUL => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_MUL));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I16X8_MUL));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u16; 8] = to_lanes(data2);
                         let lanes1: [u16; 8] = to_lanes(data1);
                         let result: [u16; 8] =
                             array::from_fn(|i| lanes1[i].wrapping_mul(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_MUL => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_MUL));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I32X4_MUL));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u32; 4] = to_lanes(data2);
                         let lanes1: [u32; 4] = to_lanes(data1);
                         let result: [u32; 4] =
                             array::from_fn(|i| lanes1[i].wrapping_mul(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I64X2_MUL => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I64X2_MUL));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I64X2_MUL));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u64; 2] = to_lanes(data2);
                         let lanes1: [u64; 2] = to_lanes(data1);
                         let result: [u64; 2] =
                             array::from_fn(|i| lanes1[i].wrapping_mul(lanes2[i]));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I8X16_AVGR_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_AVGR_U));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_AVGR_U));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u8; 16] = to_lanes(data2);
                         let lanes1: [u8; 16] = to_lanes(data1);
                         let result: [u8; 16] = array::from_fn(|i| {
                             (lanes1[i] as u16 + lanes2[i] as u16).div_ceil(2) as u8
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_AVGR_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_AVGR_U));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I16X8_AVGR_U));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u16; 8] = to_lanes(data2);
                         let lanes1: [u16; 8] = to_lanes(data1);
                         let result: [u16; 8] = array::from_fn(|i| {
                             (lanes1[i] as u32 + lanes2[i] as u32).div_ceil(2) as u16
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_Q15MULRSAT_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_Q15MULRSAT_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I16X8_Q15MULRSAT_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i16; 8] = to_lanes(data2);
                         let lanes1: [i16; 8] = to_lanes(data1);
                         let result: [i16; 8] = array::from_fn(|i| {
@@ -4861,723 +5502,749 @@ pub(super) fn run<T: Config>(
                                 .clamp(i16::MIN as i64, i16::MAX as i64)
                                 as i16
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
 
                     // Group vrelop <https://webassembly.github.io/spec/core/syntax/instructions.html#syntax-vrelop>
                     // virelop
                     I8X16_EQ => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_EQ));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_EQ));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u8; 16] = to_lanes(data2);
                         let lanes1: [u8; 16] = to_lanes(data1);
                         let result: [i8; 16] =
                             array::from_fn(|i| ((lanes1[i] == lanes2[i]) as i8).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_EQ => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_EQ));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I16X8_EQ));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u16; 8] = to_lanes(data2);
                         let lanes1: [u16; 8] = to_lanes(data1);
                         let result: [i16; 8] =
                             array::from_fn(|i| ((lanes1[i] == lanes2[i]) as i16).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_EQ => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_EQ));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I32X4_EQ));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u32; 4] = to_lanes(data2);
                         let lanes1: [u32; 4] = to_lanes(data1);
                         let result: [i32; 4] =
                             array::from_fn(|i| ((lanes1[i] == lanes2[i]) as i32).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I64X2_EQ => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I64X2_EQ));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I64X2_EQ));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u64; 2] = to_lanes(data2);
                         let lanes1: [u64; 2] = to_lanes(data1);
                         let result: [i64; 2] =
                             array::from_fn(|i| ((lanes1[i] == lanes2[i]) as i64).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I8X16_NE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_NE));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_NE));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u8; 16] = to_lanes(data2);
                         let lanes1: [u8; 16] = to_lanes(data1);
                         let result: [i8; 16] =
                             array::from_fn(|i| ((lanes1[i] != lanes2[i]) as i8).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_NE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_NE));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I16X8_NE));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u16; 8] = to_lanes(data2);
                         let lanes1: [u16; 8] = to_lanes(data1);
                         let result: [i16; 8] =
                             array::from_fn(|i| ((lanes1[i] != lanes2[i]) as i16).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_NE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_NE));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I32X4_NE));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u32; 4] = to_lanes(data2);
                         let lanes1: [u32; 4] = to_lanes(data1);
                         let result: [i32; 4] =
                             array::from_fn(|i| ((lanes1[i] != lanes2[i]) as i32).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I64X2_NE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I64X2_NE));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I64X2_NE));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u64; 2] = to_lanes(data2);
                         let lanes1: [u64; 2] = to_lanes(data1);
                         let result: [i64; 2] =
                             array::from_fn(|i| ((lanes1[i] != lanes2[i]) as i64).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I8X16_LT_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_LT_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_LT_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i8; 16] = to_lanes(data2);
                         let lanes1: [i8; 16] = to_lanes(data1);
                         let result: [i8; 16] =
                             array::from_fn(|i| ((lanes1[i] < lanes2[i]) as i8).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_LT_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_LT_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I16X8_LT_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i16; 8] = to_lanes(data2);
                         let lanes1: [i16; 8] = to_lanes(data1);
                         let result: [i16; 8] =
                             array::from_fn(|i| ((lanes1[i] < lanes2[i]) as i16).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_LT_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_LT_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I32X4_LT_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i32; 4] = to_lanes(data2);
                         let lanes1: [i32; 4] = to_lanes(data1);
                         let result: [i32; 4] =
                             array::from_fn(|i| ((lanes1[i] < lanes2[i]) as i32).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I64X2_LT_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I64X2_LT_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I64X2_LT_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i64; 2] = to_lanes(data2);
                         let lanes1: [i64; 2] = to_lanes(data1);
                         let result: [i64; 2] =
                             array::from_fn(|i| ((lanes1[i] < lanes2[i]) as i64).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I8X16_LT_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_LT_U));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_LT_U));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u8; 16] = to_lanes(data2);
                         let lanes1: [u8; 16] = to_lanes(data1);
                         let result: [i8; 16] =
                             array::from_fn(|i| ((lanes1[i] < lanes2[i]) as i8).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_LT_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_LT_U));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I16X8_LT_U));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u16; 8] = to_lanes(data2);
                         let lanes1: [u16; 8] = to_lanes(data1);
                         let result: [i16; 8] =
                             array::from_fn(|i| ((lanes1[i] < lanes2[i]) as i16).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_LT_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_LT_U));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I32X4_LT_U));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u32; 4] = to_lanes(data2);
                         let lanes1: [u32; 4] = to_lanes(data1);
                         let result: [i32; 4] =
                             array::from_fn(|i| ((lanes1[i] < lanes2[i]) as i32).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I8X16_GT_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_GT_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_GT_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i8; 16] = to_lanes(data2);
                         let lanes1: [i8; 16] = to_lanes(data1);
                         let result: [i8; 16] =
                             array::from_fn(|i| ((lanes1[i] > lanes2[i]) as i8).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_GT_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_GT_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I16X8_GT_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i16; 8] = to_lanes(data2);
                         let lanes1: [i16; 8] = to_lanes(data1);
                         let result: [i16; 8] =
                             array::from_fn(|i| ((lanes1[i] > lanes2[i]) as i16).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_GT_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_GT_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I32X4_GT_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i32; 4] = to_lanes(data2);
                         let lanes1: [i32; 4] = to_lanes(data1);
                         let result: [i32; 4] =
                             array::from_fn(|i| ((lanes1[i] > lanes2[i]) as i32).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I64X2_GT_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I64X2_GT_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I64X2_GT_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i64; 2] = to_lanes(data2);
                         let lanes1: [i64; 2] = to_lanes(data1);
                         let result: [i64; 2] =
                             array::from_fn(|i| ((lanes1[i] > lanes2[i]) as i64).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I8X16_GT_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_GT_U));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_GT_U));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u8; 16] = to_lanes(data2);
                         let lanes1: [u8; 16] = to_lanes(data1);
                         let result: [i8; 16] =
                             array::from_fn(|i| ((lanes1[i] > lanes2[i]) as i8).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_GT_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_GT_U));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I16X8_GT_U));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u16; 8] = to_lanes(data2);
                         let lanes1: [u16; 8] = to_lanes(data1);
                         let result: [i16; 8] =
                             array::from_fn(|i| ((lanes1[i] > lanes2[i]) as i16).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_GT_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_GT_U));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I32X4_GT_U));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u32; 4] = to_lanes(data2);
                         let lanes1: [u32; 4] = to_lanes(data1);
                         let result: [i32; 4] =
                             array::from_fn(|i| ((lanes1[i] > lanes2[i]) as i32).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I8X16_LE_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_LE_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_LE_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i8; 16] = to_lanes(data2);
                         let lanes1: [i8; 16] = to_lanes(data1);
                         let result: [i8; 16] =
                             array::from_fn(|i| ((lanes1[i] <= lanes2[i]) as i8).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_LE_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_LE_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I16X8_LE_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i16; 8] = to_lanes(data2);
                         let lanes1: [i16; 8] = to_lanes(data1);
                         let result: [i16; 8] =
                             array::from_fn(|i| ((lanes1[i] <= lanes2[i]) as i16).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_LE_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_LE_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I32X4_LE_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i32; 4] = to_lanes(data2);
                         let lanes1: [i32; 4] = to_lanes(data1);
                         let result: [i32; 4] =
                             array::from_fn(|i| ((lanes1[i] <= lanes2[i]) as i32).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I64X2_LE_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I64X2_LE_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I64X2_LE_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i64; 2] = to_lanes(data2);
                         let lanes1: [i64; 2] = to_lanes(data1);
                         let result: [i64; 2] =
                             array::from_fn(|i| ((lanes1[i] <= lanes2[i]) as i64).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I8X16_LE_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_LE_U));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_LE_U));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u8; 16] = to_lanes(data2);
                         let lanes1: [u8; 16] = to_lanes(data1);
                         let result: [i8; 16] =
                             array::from_fn(|i| ((lanes1[i] <= lanes2[i]) as i8).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_LE_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_LE_U));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I16X8_LE_U));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u16; 8] = to_lanes(data2);
                         let lanes1: [u16; 8] = to_lanes(data1);
                         let result: [i16; 8] =
                             array::from_fn(|i| ((lanes1[i] <= lanes2[i]) as i16).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_LE_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_LE_U));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I32X4_LE_U));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u32; 4] = to_lanes(data2);
                         let lanes1: [u32; 4] = to_lanes(data1);
                         let result: [i32; 4] =
                             array::from_fn(|i| ((lanes1[i] <= lanes2[i]) as i32).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
 
                     I8X16_GE_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_GE_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_GE_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i8; 16] = to_lanes(data2);
                         let lanes1: [i8; 16] = to_lanes(data1);
                         let result: [i8; 16] =
                             array::from_fn(|i| ((lanes1[i] >= lanes2[i]) as i8).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_GE_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_GE_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I16X8_GE_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i16; 8] = to_lanes(data2);
                         let lanes1: [i16; 8] = to_lanes(data1);
                         let result: [i16; 8] =
                             array::from_fn(|i| ((lanes1[i] >= lanes2[i]) as i16).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_GE_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_GE_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I32X4_GE_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i32; 4] = to_lanes(data2);
                         let lanes1: [i32; 4] = to_lanes(data1);
                         let result: [i32; 4] =
                             array::from_fn(|i| ((lanes1[i] >= lanes2[i]) as i32).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I64X2_GE_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I64X2_GE_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I64X2_GE_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i64; 2] = to_lanes(data2);
                         let lanes1: [i64; 2] = to_lanes(data1);
                         let result: [i64; 2] =
                             array::from_fn(|i| ((lanes1[i] >= lanes2[i]) as i64).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I8X16_GE_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_GE_U));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_GE_U));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u8; 16] = to_lanes(data2);
                         let lanes1: [u8; 16] = to_lanes(data1);
                         let result: [i8; 16] =
                             array::from_fn(|i| ((lanes1[i] >= lanes2[i]) as i8).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_GE_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_GE_U));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I16X8_GE_U));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u16; 8] = to_lanes(data2);
                         let lanes1: [u16; 8] = to_lanes(data1);
                         let result: [i16; 8] =
                             array::from_fn(|i| ((lanes1[i] >= lanes2[i]) as i16).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_GE_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_GE_U));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I32X4_GE_U));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [u32; 4] = to_lanes(data2);
                         let lanes1: [u32; 4] = to_lanes(data1);
                         let result: [i32; 4] =
                             array::from_fn(|i| ((lanes1[i] >= lanes2[i]) as i32).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     // vfrelop
                     F32X4_EQ => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F32X4_EQ));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F32X4_EQ));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F32; 4] = to_lanes(data2);
                         let lanes1: [F32; 4] = to_lanes(data1);
                         let result: [i32; 4] =
                             array::from_fn(|i| ((lanes1[i] == lanes2[i]) as i32).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F64X2_EQ => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F64X2_EQ));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F64X2_EQ));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F64; 2] = to_lanes(data2);
                         let lanes1: [F64; 2] = to_lanes(data1);
                         let result: [i64; 2] =
                             array::from_fn(|i| ((lanes1[i] == lanes2[i]) as i64).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F32X4_NE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F32X4_NE));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F32X4_NE));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F32; 4] = to_lanes(data2);
                         let lanes1: [F32; 4] = to_lanes(data1);
                         let result: [i32; 4] =
                             array::from_fn(|i| ((lanes1[i] != lanes2[i]) as i32).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F64X2_NE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F64X2_NE));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F64X2_NE));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F64; 2] = to_lanes(data2);
                         let lanes1: [F64; 2] = to_lanes(data1);
                         let result: [i64; 2] =
                             array::from_fn(|i| ((lanes1[i] != lanes2[i]) as i64).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F32X4_LT => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F32X4_LT));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F32X4_LT));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F32; 4] = to_lanes(data2);
                         let lanes1: [F32; 4] = to_lanes(data1);
                         let result: [i32; 4] =
                             array::from_fn(|i| ((lanes1[i] < lanes2[i]) as i32).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F64X2_LT => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F64X2_LT));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F64X2_LT));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F64; 2] = to_lanes(data2);
                         let lanes1: [F64; 2] = to_lanes(data1);
                         let result: [i64; 2] =
                             array::from_fn(|i| ((lanes1[i] < lanes2[i]) as i64).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F32X4_GT => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F32X4_GT));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F32X4_GT));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F32; 4] = to_lanes(data2);
                         let lanes1: [F32; 4] = to_lanes(data1);
                         let result: [i32; 4] =
                             array::from_fn(|i| ((lanes1[i] > lanes2[i]) as i32).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F64X2_GT => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F64X2_GT));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F64X2_GT));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F64; 2] = to_lanes(data2);
                         let lanes1: [F64; 2] = to_lanes(data1);
                         let result: [i64; 2] =
                             array::from_fn(|i| ((lanes1[i] > lanes2[i]) as i64).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F32X4_LE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F32X4_LE));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F32X4_LE));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F32; 4] = to_lanes(data2);
                         let lanes1: [F32; 4] = to_lanes(data1);
                         let result: [i32; 4] =
                             array::from_fn(|i| ((lanes1[i] <= lanes2[i]) as i32).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F64X2_LE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F64X2_LE));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F64X2_LE));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F64; 2] = to_lanes(data2);
                         let lanes1: [F64; 2] = to_lanes(data1);
                         let result: [i64; 2] =
                             array::from_fn(|i| ((lanes1[i] <= lanes2[i]) as i64).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F32X4_GE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F32X4_GE));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F32X4_GE));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F32; 4] = to_lanes(data2);
                         let lanes1: [F32; 4] = to_lanes(data1);
                         let result: [i32; 4] =
                             array::from_fn(|i| ((lanes1[i] >= lanes2[i]) as i32).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F64X2_GE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F64X2_GE));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(F64X2_GE));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [F64; 2] = to_lanes(data2);
                         let lanes1: [F64; 2] = to_lanes(data1);
                         let result: [i64; 2] =
                             array::from_fn(|i| ((lanes1[i] >= lanes2[i]) as i64).neg());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
 
                     // Group vishiftop
                     I8X16_SHL => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_SHL));
-                        let shift: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_SHL));
+                        let shift: u32 = stack.pop();
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [u8; 16] = to_lanes(data);
                         let result: [u8; 16] = lanes.map(|lane| lane.wrapping_shl(shift));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_SHL => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_SHL));
-                        let shift: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I16X8_SHL));
+                        let shift: u32 = stack.pop();
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [u16; 8] = to_lanes(data);
                         let result: [u16; 8] = lanes.map(|lane| lane.wrapping_shl(shift));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_SHL => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_SHL));
-                        let shift: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I32X4_SHL));
+                        let shift: u32 = stack.pop();
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [u32; 4] = to_lanes(data);
                         let result: [u32; 4] = lanes.map(|lane| lane.wrapping_shl(shift));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I64X2_SHL => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I64X2_SHL));
-                        let shift: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I64X2_SHL));
+                        let shift: u32 = stack.pop();
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [u64; 2] = to_lanes(data);
                         let result: [u64; 2] = lanes.map(|lane| lane.wrapping_shl(shift));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I8X16_SHR_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_SHR_S));
-                        let shift: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_SHR_S));
+                        let shift: u32 = stack.pop();
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i8; 16] = to_lanes(data);
                         let result: [i8; 16] = lanes.map(|lane| lane.wrapping_shr(shift));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I8X16_SHR_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_SHR_U));
-                        let shift: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_SHR_U));
+                        let shift: u32 = stack.pop();
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [u8; 16] = to_lanes(data);
                         let result: [u8; 16] = lanes.map(|lane| lane.wrapping_shr(shift));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_SHR_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_SHR_S));
-                        let shift: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I16X8_SHR_S));
+                        let shift: u32 = stack.pop();
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i16; 8] = to_lanes(data);
                         let result: [i16; 8] = lanes.map(|lane| lane.wrapping_shr(shift));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_SHR_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_SHR_U));
-                        let shift: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I16X8_SHR_U));
+                        let shift: u32 = stack.pop();
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [u16; 8] = to_lanes(data);
                         let result: [u16; 8] = lanes.map(|lane| lane.wrapping_shr(shift));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_SHR_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_SHR_S));
-                        let shift: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I32X4_SHR_S));
+                        let shift: u32 = stack.pop();
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i32; 4] = to_lanes(data);
                         let result: [i32; 4] = lanes.map(|lane| lane.wrapping_shr(shift));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_SHR_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_SHR_U));
-                        let shift: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I32X4_SHR_U));
+                        let shift: u32 = stack.pop();
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [u32; 4] = to_lanes(data);
                         let result: [u32; 4] = lanes.map(|lane| lane.wrapping_shr(shift));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I64X2_SHR_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I64X2_SHR_S));
-                        let shift: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I64X2_SHR_S));
+                        let shift: u32 = stack.pop();
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i64; 2] = to_lanes(data);
                         let result: [i64; 2] = lanes.map(|lane| lane.wrapping_shr(shift));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I64X2_SHR_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I64X2_SHR_U));
-                        let shift: u32 = stack.pop_value().try_into().unwrap_validated();
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I64X2_SHR_U));
+                        let shift: u32 = stack.pop();
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [u64; 2] = to_lanes(data);
                         let result: [u64; 2] = lanes.map(|lane| lane.wrapping_shr(shift));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
 
                     // Group vtestop <https://webassembly.github.io/spec/core/syntax/instructions.html#syntax-vtestop>
                     // vitestop
                     I8X16_ALL_TRUE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_ALL_TRUE));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_ALL_TRUE));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [u8; 16] = to_lanes(data);
                         let all_true = lanes.into_iter().all(|lane| lane != 0);
-                        stack.push_value::<T>(Value::I32(all_true as u32))?;
+                        stack.push_value(Value::I32(all_true as u32))?;
                     }
                     I16X8_ALL_TRUE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_ALL_TRUE));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I16X8_ALL_TRUE));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [u16; 8] = to_lanes(data);
                         let all_true = lanes.into_iter().all(|lane| lane != 0);
-                        stack.push_value::<T>(Value::I32(all_true as u32))?;
+                        stack.push_value(Value::I32(all_true as u32))?;
                     }
                     I32X4_ALL_TRUE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_ALL_TRUE));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I32X4_ALL_TRUE));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [u32; 4] = to_lanes(data);
                         let all_true = lanes.into_iter().all(|lane| lane != 0);
-                        stack.push_value::<T>(Value::I32(all_true as u32))?;
+                        stack.push_value(Value::I32(all_true as u32))?;
                     }
                     I64X2_ALL_TRUE => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I64X2_ALL_TRUE));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I64X2_ALL_TRUE));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [u64; 2] = to_lanes(data);
                         let all_true = lanes.into_iter().all(|lane| lane != 0);
-                        stack.push_value::<T>(Value::I32(all_true as u32))?;
+                        stack.push_value(Value::I32(all_true as u32))?;
                     }
 
                     // Group vcvtop <https://webassembly.github.io/spec/core/syntax/instructions.html#syntax-vcvtop>
                     I16X8_EXTEND_HIGH_I8X16_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_EXTEND_HIGH_I8X16_S));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I16X8_EXTEND_HIGH_I8X16_S));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i8; 16] = to_lanes(data);
                         let high_lanes: [i8; 8] = lanes[8..].try_into().unwrap();
                         let result = high_lanes.map(|lane| lane as i16);
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_EXTEND_HIGH_I8X16_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_EXTEND_HIGH_I8X16_U));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I16X8_EXTEND_HIGH_I8X16_U));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [u8; 16] = to_lanes(data);
                         let high_lanes: [u8; 8] = lanes[8..].try_into().unwrap();
                         let result = high_lanes.map(|lane| lane as u16);
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_EXTEND_LOW_I8X16_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_EXTEND_LOW_I8X16_S));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I16X8_EXTEND_LOW_I8X16_S));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i8; 16] = to_lanes(data);
                         let low_lanes: [i8; 8] = lanes[..8].try_into().unwrap();
                         let result = low_lanes.map(|lane| lane as i16);
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_EXTEND_LOW_I8X16_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_EXTEND_LOW_I8X16_U));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I16X8_EXTEND_LOW_I8X16_U));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [u8; 16] = to_lanes(data);
                         let low_lanes: [u8; 8] = lanes[..8].try_into().unwrap();
                         let result = low_lanes.map(|lane| lane as u16);
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_EXTEND_HIGH_I16X8_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_EXTEND_HIGH_I16X8_S));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I32X4_EXTEND_HIGH_I16X8_S));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i16; 8] = to_lanes(data);
                         let high_lanes: [i16; 4] = lanes[4..].try_into().unwrap();
                         let result = high_lanes.map(|lane| lane as i32);
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_EXTEND_HIGH_I16X8_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_EXTEND_HIGH_I16X8_U));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I32X4_EXTEND_HIGH_I16X8_U));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [u16; 8] = to_lanes(data);
                         let high_lanes: [u16; 4] = lanes[4..].try_into().unwrap();
                         let result = high_lanes.map(|lane| lane as u32);
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_EXTEND_LOW_I16X8_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_EXTEND_LOW_I16X8_S));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I32X4_EXTEND_LOW_I16X8_S));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i16; 8] = to_lanes(data);
                         let low_lanes: [i16; 4] = lanes[..4].try_into().unwrap();
                         let result = low_lanes.map(|lane| lane as i32);
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_EXTEND_LOW_I16X8_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_EXTEND_LOW_I16X8_U));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I32X4_EXTEND_LOW_I16X8_U));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [u16; 8] = to_lanes(data);
                         let low_lanes: [u16; 4] = lanes[..4].try_into().unwrap();
                         let result = low_lanes.map(|lane| lane as u32);
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I64X2_EXTEND_HIGH_I32X4_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I64X2_EXTEND_HIGH_I32X4_S));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I64X2_EXTEND_HIGH_I32X4_S));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i32; 4] = to_lanes(data);
                         let high_lanes: [i32; 2] = lanes[2..].try_into().unwrap();
                         let result = high_lanes.map(|lane| lane as i64);
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I64X2_EXTEND_HIGH_I32X4_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I64X2_EXTEND_HIGH_I32X4_U));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I64X2_EXTEND_HIGH_I32X4_U));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [u32; 4] = to_lanes(data);
                         let high_lanes: [u32; 2] = lanes[2..].try_into().unwrap();
                         let result = high_lanes.map(|lane| lane as u64);
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I64X2_EXTEND_LOW_I32X4_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I64X2_EXTEND_LOW_I32X4_S));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I64X2_EXTEND_LOW_I32X4_S));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i32; 4] = to_lanes(data);
                         let low_lanes: [i32; 2] = lanes[..2].try_into().unwrap();
                         let result = low_lanes.map(|lane| lane as i64);
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I64X2_EXTEND_LOW_I32X4_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I64X2_EXTEND_LOW_I32X4_U));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I64X2_EXTEND_LOW_I32X4_U));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [u32; 4] = to_lanes(data);
                         let low_lanes: [u32; 2] = lanes[..2].try_into().unwrap();
                         let result = low_lanes.map(|lane| lane as u64);
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_TRUNC_SAT_F32X4_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_TRUNC_SAT_F32X4_S));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I32X4_TRUNC_SAT_F32X4_S));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [F32; 4] = to_lanes(data);
                         let result = lanes.map(|lane| {
                             if lane.is_nan() {
@@ -5590,11 +6257,13 @@ pub(super) fn run<T: Config>(
                                 lane.as_i32()
                             }
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_TRUNC_SAT_F32X4_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_TRUNC_SAT_F32X4_U));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I32X4_TRUNC_SAT_F32X4_U));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [F32; 4] = to_lanes(data);
                         let result = lanes.map(|lane| {
                             if lane.is_nan() || lane.is_negative_infinity() {
@@ -5605,13 +6274,13 @@ pub(super) fn run<T: Config>(
                                 lane.as_u32()
                             }
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I32X4_TRUNC_SAT_F64X2_S_ZERO => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(
-                            I32X4_TRUNC_SAT_F64X2_S_ZERO
-                        ));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I32X4_TRUNC_SAT_F64X2_S_ZERO));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [F64; 2] = to_lanes(data);
                         let result = lanes.map(|lane| {
                             if lane.is_nan() {
@@ -5624,15 +6293,13 @@ pub(super) fn run<T: Config>(
                                 lane.as_i32()
                             }
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes([
-                            result[0], result[1], 0, 0,
-                        ])))?;
+                        stack.push_value(Value::V128(from_lanes([result[0], result[1], 0, 0])))?;
                     }
                     I32X4_TRUNC_SAT_F64X2_U_ZERO => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(
-                            I32X4_TRUNC_SAT_F64X2_U_ZERO
-                        ));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I32X4_TRUNC_SAT_F64X2_U_ZERO));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [F64; 2] = to_lanes(data);
                         let result = lanes.map(|lane| {
                             if lane.is_nan() || lane.is_negative_infinity() {
@@ -5643,62 +6310,74 @@ pub(super) fn run<T: Config>(
                                 lane.as_u32()
                             }
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes([
-                            result[0], result[1], 0, 0,
-                        ])))?;
+                        stack.push_value(Value::V128(from_lanes([result[0], result[1], 0, 0])))?;
                     }
                     F32X4_CONVERT_I32X4_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F32X4_CONVERT_I32X4_S));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(F32X4_CONVERT_I32X4_S));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i32; 4] = to_lanes(data);
                         let result: [F32; 4] = lanes.map(|lane| F32(lane as f32));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F32X4_CONVERT_I32X4_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F32X4_CONVERT_I32X4_U));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(F32X4_CONVERT_I32X4_U));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [u32; 4] = to_lanes(data);
                         let result: [F32; 4] = lanes.map(|lane| F32(lane as f32));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F64X2_CONVERT_LOW_I32X4_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F64X2_CONVERT_LOW_I32X4_S));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(F64X2_CONVERT_LOW_I32X4_S));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i32; 4] = to_lanes(data);
                         let low_lanes: [i32; 2] = lanes[..2].try_into().unwrap();
                         let result = low_lanes.map(|lane| F64(lane as f64));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F64X2_CONVERT_LOW_I32X4_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F64X2_CONVERT_LOW_I32X4_U));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(F64X2_CONVERT_LOW_I32X4_U));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [u32; 4] = to_lanes(data);
                         let low_lanes: [u32; 2] = lanes[..2].try_into().unwrap();
                         let result = low_lanes.map(|lane| F64(lane as f64));
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F32X4_DEMOTE_F64X2_ZERO => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F32X4_DEMOTE_F64X2_ZERO));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(F32X4_DEMOTE_F64X2_ZERO));
+                        let data: [u8; 16] = stack.pop();
                         let lanes = to_lanes::<8, 2, F64>(data);
                         let half_lanes = lanes.map(|lane| lane.as_f32());
                         let result = [half_lanes[0], half_lanes[1], F32(0.0), F32(0.0)];
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     F64X2_PROMOTE_LOW_F32X4 => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(F64X2_PROMOTE_LOW_F32X4));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(F64X2_PROMOTE_LOW_F32X4));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [F32; 4] = to_lanes(data);
                         let half_lanes: [F32; 2] = lanes[..2].try_into().unwrap();
                         let result = half_lanes.map(|lane| lane.as_f64());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
 
                     // ishape.narrow_ishape_sx
                     I8X16_NARROW_I16X8_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_NARROW_I16X8_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I8X16_NARROW_I16X8_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i16; 8] = to_lanes(data2);
                         let lanes1: [i16; 8] = to_lanes(data1);
                         let mut concatenated_narrowed_lanes = lanes1
@@ -5707,12 +6386,14 @@ pub(super) fn run<T: Config>(
                             .map(|lane| lane.clamp(i8::MIN as i16, i8::MAX as i16) as i8);
                         let result: [i8; 16] =
                             array::from_fn(|_| concatenated_narrowed_lanes.next().unwrap());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I8X16_NARROW_I16X8_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_NARROW_I16X8_U));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I8X16_NARROW_I16X8_U));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i16; 8] = to_lanes(data2);
                         let lanes1: [i16; 8] = to_lanes(data1);
                         let mut concatenated_narrowed_lanes = lanes1
@@ -5721,12 +6402,14 @@ pub(super) fn run<T: Config>(
                             .map(|lane| lane.clamp(u8::MIN as i16, u8::MAX as i16) as u8);
                         let result: [u8; 16] =
                             array::from_fn(|_| concatenated_narrowed_lanes.next().unwrap());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_NARROW_I32X4_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_NARROW_I32X4_S));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I16X8_NARROW_I32X4_S));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i32; 4] = to_lanes(data2);
                         let lanes1: [i32; 4] = to_lanes(data1);
                         let mut concatenated_narrowed_lanes = lanes1
@@ -5735,12 +6418,14 @@ pub(super) fn run<T: Config>(
                             .map(|lane| lane.clamp(i16::MIN as i32, i16::MAX as i32) as i16);
                         let result: [i16; 8] =
                             array::from_fn(|_| concatenated_narrowed_lanes.next().unwrap());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
                     I16X8_NARROW_I32X4_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_NARROW_I32X4_U));
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I16X8_NARROW_I32X4_U));
+                        let data2: [u8; 16] = stack.pop();
+                        let data1: [u8; 16] = stack.pop();
                         let lanes2: [i32; 4] = to_lanes(data2);
                         let lanes1: [i32; 4] = to_lanes(data1);
                         let mut concatenated_narrowed_lanes = lanes1
@@ -5749,60 +6434,62 @@ pub(super) fn run<T: Config>(
                             .map(|lane| lane.clamp(u16::MIN as i32, u16::MAX as i32) as u16);
                         let result: [u16; 8] =
                             array::from_fn(|_| concatenated_narrowed_lanes.next().unwrap());
-                        stack.push_value::<T>(Value::V128(from_lanes(result)))?;
+                        stack.push_value(Value::V128(from_lanes(result)))?;
                     }
 
                     // ishape.bitmask
                     I8X16_BITMASK => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I8X16_BITMASK));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I8X16_BITMASK));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i8; 16] = to_lanes(data);
                         let bits = lanes.map(|lane| lane < 0);
                         let bitmask = bits
                             .into_iter()
                             .enumerate()
                             .fold(0u32, |acc, (i, bit)| acc | ((bit as u32) << i));
-                        stack.push_value::<T>(Value::I32(bitmask))?;
+                        stack.push_value(Value::I32(bitmask))?;
                     }
                     I16X8_BITMASK => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_BITMASK));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I16X8_BITMASK));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i16; 8] = to_lanes(data);
                         let bits = lanes.map(|lane| lane < 0);
                         let bitmask = bits
                             .into_iter()
                             .enumerate()
                             .fold(0u32, |acc, (i, bit)| acc | ((bit as u32) << i));
-                        stack.push_value::<T>(Value::I32(bitmask))?;
+                        stack.push_value(Value::I32(bitmask))?;
                     }
                     I32X4_BITMASK => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_BITMASK));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I32X4_BITMASK));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i32; 4] = to_lanes(data);
                         let bits = lanes.map(|lane| lane < 0);
                         let bitmask = bits
                             .into_iter()
                             .enumerate()
                             .fold(0u32, |acc, (i, bit)| acc | ((bit as u32) << i));
-                        stack.push_value::<T>(Value::I32(bitmask))?;
+                        stack.push_value(Value::I32(bitmask))?;
                     }
                     I64X2_BITMASK => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I64X2_BITMASK));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store.user_data.get_fd_extension_flat_cost(I64X2_BITMASK));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i64; 2] = to_lanes(data);
                         let bits = lanes.map(|lane| lane < 0);
                         let bitmask = bits
                             .into_iter()
                             .enumerate()
                             .fold(0u32, |acc, (i, bit)| acc | ((bit as u32) << i));
-                        stack.push_value::<T>(Value::I32(bitmask))?;
+                        stack.push_value(Value::I32(bitmask))?;
                     }
 
                     // ishape.dot_ishape_s
                     I32X4_DOT_I16X8_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_DOT_I16X8_S));
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I32X4_DOT_I16X8_S));
+                        let data1: [u8; 16] = stack.pop();
+                        let data2: [u8; 16] = stack.pop();
                         let lanes1: [i16; 8] = to_lanes(data1);
                         let lanes2: [i16; 8] = to_lanes(data2);
                         let multiplied: [i32; 8] = array::from_fn(|i| {
@@ -5815,14 +6502,16 @@ pub(super) fn run<T: Config>(
                             let v2 = multiplied[2 * i + 1];
                             v1.wrapping_add(v2)
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes(added)))?;
+                        stack.push_value(Value::V128(from_lanes(added)))?;
                     }
 
                     // ishape.extmul_half_ishape_sx
                     I16X8_EXTMUL_HIGH_I8X16_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_EXTMUL_HIGH_I8X16_S));
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I16X8_EXTMUL_HIGH_I8X16_S));
+                        let data1: [u8; 16] = stack.pop();
+                        let data2: [u8; 16] = stack.pop();
                         let lanes1: [i8; 16] = to_lanes(data1);
                         let lanes2: [i8; 16] = to_lanes(data2);
                         let high_lanes1: [i8; 8] = lanes1[8..].try_into().unwrap();
@@ -5832,12 +6521,14 @@ pub(super) fn run<T: Config>(
                             let v2 = high_lanes2[i] as i16;
                             v1.wrapping_mul(v2)
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes(multiplied)))?;
+                        stack.push_value(Value::V128(from_lanes(multiplied)))?;
                     }
                     I16X8_EXTMUL_HIGH_I8X16_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_EXTMUL_HIGH_I8X16_U));
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I16X8_EXTMUL_HIGH_I8X16_U));
+                        let data1: [u8; 16] = stack.pop();
+                        let data2: [u8; 16] = stack.pop();
                         let lanes1: [u8; 16] = to_lanes(data1);
                         let lanes2: [u8; 16] = to_lanes(data2);
                         let high_lanes1: [u8; 8] = lanes1[8..].try_into().unwrap();
@@ -5847,12 +6538,14 @@ pub(super) fn run<T: Config>(
                             let v2 = high_lanes2[i] as u16;
                             v1.wrapping_mul(v2)
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes(multiplied)))?;
+                        stack.push_value(Value::V128(from_lanes(multiplied)))?;
                     }
                     I16X8_EXTMUL_LOW_I8X16_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_EXTMUL_LOW_I8X16_S));
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I16X8_EXTMUL_LOW_I8X16_S));
+                        let data1: [u8; 16] = stack.pop();
+                        let data2: [u8; 16] = stack.pop();
                         let lanes1: [i8; 16] = to_lanes(data1);
                         let lanes2: [i8; 16] = to_lanes(data2);
                         let high_lanes1: [i8; 8] = lanes1[..8].try_into().unwrap();
@@ -5862,12 +6555,14 @@ pub(super) fn run<T: Config>(
                             let v2 = high_lanes2[i] as i16;
                             v1.wrapping_mul(v2)
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes(multiplied)))?;
+                        stack.push_value(Value::V128(from_lanes(multiplied)))?;
                     }
                     I16X8_EXTMUL_LOW_I8X16_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I16X8_EXTMUL_LOW_I8X16_U));
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I16X8_EXTMUL_LOW_I8X16_U));
+                        let data1: [u8; 16] = stack.pop();
+                        let data2: [u8; 16] = stack.pop();
                         let lanes1: [u8; 16] = to_lanes(data1);
                         let lanes2: [u8; 16] = to_lanes(data2);
                         let high_lanes1: [u8; 8] = lanes1[..8].try_into().unwrap();
@@ -5877,12 +6572,14 @@ pub(super) fn run<T: Config>(
                             let v2 = high_lanes2[i] as u16;
                             v1.wrapping_mul(v2)
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes(multiplied)))?;
+                        stack.push_value(Value::V128(from_lanes(multiplied)))?;
                     }
                     I32X4_EXTMUL_HIGH_I16X8_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_EXTMUL_HIGH_I16X8_S));
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I32X4_EXTMUL_HIGH_I16X8_S));
+                        let data1: [u8; 16] = stack.pop();
+                        let data2: [u8; 16] = stack.pop();
                         let lanes1: [i16; 8] = to_lanes(data1);
                         let lanes2: [i16; 8] = to_lanes(data2);
                         let high_lanes1: [i16; 4] = lanes1[4..].try_into().unwrap();
@@ -5892,12 +6589,14 @@ pub(super) fn run<T: Config>(
                             let v2 = high_lanes2[i] as i32;
                             v1.wrapping_mul(v2)
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes(multiplied)))?;
+                        stack.push_value(Value::V128(from_lanes(multiplied)))?;
                     }
                     I32X4_EXTMUL_HIGH_I16X8_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_EXTMUL_HIGH_I16X8_U));
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I32X4_EXTMUL_HIGH_I16X8_U));
+                        let data1: [u8; 16] = stack.pop();
+                        let data2: [u8; 16] = stack.pop();
                         let lanes1: [u16; 8] = to_lanes(data1);
                         let lanes2: [u16; 8] = to_lanes(data2);
                         let high_lanes1: [u16; 4] = lanes1[4..].try_into().unwrap();
@@ -5907,12 +6606,14 @@ pub(super) fn run<T: Config>(
                             let v2 = high_lanes2[i] as u32;
                             v1.wrapping_mul(v2)
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes(multiplied)))?;
+                        stack.push_value(Value::V128(from_lanes(multiplied)))?;
                     }
                     I32X4_EXTMUL_LOW_I16X8_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_EXTMUL_LOW_I16X8_S));
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I32X4_EXTMUL_LOW_I16X8_S));
+                        let data1: [u8; 16] = stack.pop();
+                        let data2: [u8; 16] = stack.pop();
                         let lanes1: [i16; 8] = to_lanes(data1);
                         let lanes2: [i16; 8] = to_lanes(data2);
                         let high_lanes1: [i16; 4] = lanes1[..4].try_into().unwrap();
@@ -5922,12 +6623,14 @@ pub(super) fn run<T: Config>(
                             let v2 = high_lanes2[i] as i32;
                             v1.wrapping_mul(v2)
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes(multiplied)))?;
+                        stack.push_value(Value::V128(from_lanes(multiplied)))?;
                     }
                     I32X4_EXTMUL_LOW_I16X8_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I32X4_EXTMUL_LOW_I16X8_U));
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I32X4_EXTMUL_LOW_I16X8_U));
+                        let data1: [u8; 16] = stack.pop();
+                        let data2: [u8; 16] = stack.pop();
                         let lanes1: [u16; 8] = to_lanes(data1);
                         let lanes2: [u16; 8] = to_lanes(data2);
                         let high_lanes1: [u16; 4] = lanes1[..4].try_into().unwrap();
@@ -5937,12 +6640,14 @@ pub(super) fn run<T: Config>(
                             let v2 = high_lanes2[i] as u32;
                             v1.wrapping_mul(v2)
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes(multiplied)))?;
+                        stack.push_value(Value::V128(from_lanes(multiplied)))?;
                     }
                     I64X2_EXTMUL_HIGH_I32X4_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I64X2_EXTMUL_HIGH_I32X4_S));
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I64X2_EXTMUL_HIGH_I32X4_S));
+                        let data1: [u8; 16] = stack.pop();
+                        let data2: [u8; 16] = stack.pop();
                         let lanes1: [i32; 4] = to_lanes(data1);
                         let lanes2: [i32; 4] = to_lanes(data2);
                         let high_lanes1: [i32; 2] = lanes1[2..].try_into().unwrap();
@@ -5952,12 +6657,14 @@ pub(super) fn run<T: Config>(
                             let v2 = high_lanes2[i] as i64;
                             v1.wrapping_mul(v2)
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes(multiplied)))?;
+                        stack.push_value(Value::V128(from_lanes(multiplied)))?;
                     }
                     I64X2_EXTMUL_HIGH_I32X4_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I64X2_EXTMUL_HIGH_I32X4_U));
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I64X2_EXTMUL_HIGH_I32X4_U));
+                        let data1: [u8; 16] = stack.pop();
+                        let data2: [u8; 16] = stack.pop();
                         let lanes1: [u32; 4] = to_lanes(data1);
                         let lanes2: [u32; 4] = to_lanes(data2);
                         let high_lanes1: [u32; 2] = lanes1[2..].try_into().unwrap();
@@ -5967,12 +6674,14 @@ pub(super) fn run<T: Config>(
                             let v2 = high_lanes2[i] as u64;
                             v1.wrapping_mul(v2)
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes(multiplied)))?;
+                        stack.push_value(Value::V128(from_lanes(multiplied)))?;
                     }
                     I64X2_EXTMUL_LOW_I32X4_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I64X2_EXTMUL_LOW_I32X4_S));
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I64X2_EXTMUL_LOW_I32X4_S));
+                        let data1: [u8; 16] = stack.pop();
+                        let data2: [u8; 16] = stack.pop();
                         let lanes1: [i32; 4] = to_lanes(data1);
                         let lanes2: [i32; 4] = to_lanes(data2);
                         let high_lanes1: [i32; 2] = lanes1[..2].try_into().unwrap();
@@ -5982,12 +6691,14 @@ pub(super) fn run<T: Config>(
                             let v2 = high_lanes2[i] as i64;
                             v1.wrapping_mul(v2)
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes(multiplied)))?;
+                        stack.push_value(Value::V128(from_lanes(multiplied)))?;
                     }
                     I64X2_EXTMUL_LOW_I32X4_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(I64X2_EXTMUL_LOW_I32X4_U));
-                        let data1: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
-                        let data2: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I64X2_EXTMUL_LOW_I32X4_U));
+                        let data1: [u8; 16] = stack.pop();
+                        let data2: [u8; 16] = stack.pop();
                         let lanes1: [u32; 4] = to_lanes(data1);
                         let lanes2: [u32; 4] = to_lanes(data2);
                         let high_lanes1: [u32; 2] = lanes1[..2].try_into().unwrap();
@@ -5997,61 +6708,61 @@ pub(super) fn run<T: Config>(
                             let v2 = high_lanes2[i] as u64;
                             v1.wrapping_mul(v2)
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes(multiplied)))?;
+                        stack.push_value(Value::V128(from_lanes(multiplied)))?;
                     }
 
                     // ishape.extadd_pairwise_ishape_sx
                     I16X8_EXTADD_PAIRWISE_I8X16_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(
-                            I16X8_EXTADD_PAIRWISE_I8X16_S
-                        ));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I16X8_EXTADD_PAIRWISE_I8X16_S));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i8; 16] = to_lanes(data);
                         let added_pairwise: [i16; 8] = array::from_fn(|i| {
                             let v1 = lanes[2 * i] as i16;
                             let v2 = lanes[2 * i + 1] as i16;
                             v1.wrapping_add(v2)
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes(added_pairwise)))?;
+                        stack.push_value(Value::V128(from_lanes(added_pairwise)))?;
                     }
                     I16X8_EXTADD_PAIRWISE_I8X16_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(
-                            I16X8_EXTADD_PAIRWISE_I8X16_U
-                        ));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I16X8_EXTADD_PAIRWISE_I8X16_U));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [u8; 16] = to_lanes(data);
                         let added_pairwise: [u16; 8] = array::from_fn(|i| {
                             let v1 = lanes[2 * i] as u16;
                             let v2 = lanes[2 * i + 1] as u16;
                             v1.wrapping_add(v2)
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes(added_pairwise)))?;
+                        stack.push_value(Value::V128(from_lanes(added_pairwise)))?;
                     }
                     I32X4_EXTADD_PAIRWISE_I16X8_S => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(
-                            I32X4_EXTADD_PAIRWISE_I16X8_S
-                        ));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I32X4_EXTADD_PAIRWISE_I16X8_S));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [i16; 8] = to_lanes(data);
                         let added_pairwise: [i32; 4] = array::from_fn(|i| {
                             let v1 = lanes[2 * i] as i32;
                             let v2 = lanes[2 * i + 1] as i32;
                             v1.wrapping_add(v2)
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes(added_pairwise)))?;
+                        stack.push_value(Value::V128(from_lanes(added_pairwise)))?;
                     }
                     I32X4_EXTADD_PAIRWISE_I16X8_U => {
-                        decrement_fuel!(T::get_fd_extension_flat_cost(
-                            I32X4_EXTADD_PAIRWISE_I16X8_U
-                        ));
-                        let data: [u8; 16] = stack.pop_value().try_into().unwrap_validated();
+                        decrement_fuel!(store
+                            .user_data
+                            .get_fd_extension_flat_cost(I32X4_EXTADD_PAIRWISE_I16X8_U));
+                        let data: [u8; 16] = stack.pop();
                         let lanes: [u16; 8] = to_lanes(data);
                         let added_pairwise: [u32; 4] = array::from_fn(|i| {
                             let v1 = lanes[2 * i] as u32;
                             let v2 = lanes[2 * i + 1] as u32;
                             v1.wrapping_add(v2)
                         });
-                        stack.push_value::<T>(Value::V128(from_lanes(added_pairwise)))?;
+                        stack.push_value(Value::V128(from_lanes(added_pairwise)))?;
                     }
 
                     // Unimplemented or invalid instructions
@@ -6112,10 +6823,18 @@ fn do_sidetable_control_transfer(
     Ok(())
 }
 
+/// Reads the offset of the [`MemArg`] of a scalar load or store, preferably from the pre-decoded immediates
+#[inline(always)]
+fn read_memarg_offset(wasm: &mut WasmReader, immediates: &ImmediatesCache) -> u32 {
+    match immediates.take(wasm) {
+        Some(offset) => offset,
+        None => MemArg::read(wasm).unwrap_validated().offset,
+    }
+}
+
 #[inline(always)]
-fn calculate_mem_address(memarg: &MemArg, relative_address: u32) -> Result<usize, RuntimeError> {
-    memarg
-        .offset
+fn calculate_mem_address(offset: u32, relative_address: u32) -> Result<usize, RuntimeError> {
+    offset
         // The spec states that this should be a 33 bit integer, e.g. it is not legal to wrap if the
         // sum of offset and relative_address exceeds u32::MAX. To emulate this behavior, we use a
         // checked addition.
@@ -6253,7 +6972,7 @@ pub(super) unsafe fn memory_init(
     let mem_addr = *unsafe { module_inst.mem_addrs.get(mem_idx) };
     // SAFETY: The caller ensures that this memory address is valid in this
     // address vector (3).
-    let mem = unsafe { store_memories.get(mem_addr) };
+    let mem = unsafe { store_memories.get_mut(mem_addr) };
     // SAFETY: The caller ensures that `data_idx` is valid for this specific
     // `IdxVec` (4).
     let data_addr = *unsafe { module_inst.data_addrs.get(data_idx) };
@@ -6261,7 +6980,13 @@ pub(super) unsafe fn memory_init(
     // address vector (5).
     let data = unsafe { store_data.get(data_addr) };
 
-    mem.mem.init(d, &data.data, s, n)?;
+    if n > data.data.len() || s > data.data.len() - n {
+        return Err(TrapError::MemoryOrDataAccessOutOfBounds.into());
+    }
+    mem.mem.check_bounds_exclusive(d, n)?;
+    mem.check_write_protection(d, n)?;
+    mem.check_bulk_mmio(d, n)?;
+    mem.mem.init_exclusive(d, &data.data, s, n)?;
 
     trace!("Instruction: memory.init");
     Ok(())
@@ -6299,6 +7024,141 @@ pub(super) unsafe fn data_drop(
     data.data.clear();
 }
 
+/// The reason why [`grow_memory`] or [`grow_table`] did not grow a memory or table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum GrowthFailure {
+    /// The new size would exceed the maximum size of the memory or table
+    MaximumExceeded,
+    /// The resource limiter of the config denied the growth
+    Denied,
+    /// The new pages or elements could not be allocated
+    OutOfMemory,
+    /// The resource limiter of the config requested a trap
+    Trap,
+}
+
+/// Grows the memory at `mem_addr` by `n` pages, if its maximum size and the resource limiter of the config allow it.
+/// Otherwise the memory is left unchanged and the reason is returned.
+///
+/// # Safety
+///
+/// The memory address `mem_addr` must be valid in `store_memories`.
+#[inline(always)]
+pub(super) unsafe fn grow_memory<T: Config>(
+    store_memories: &mut AddrVec<MemAddr, MemInst>,
+    user_data: &mut T,
+    resource_usage: &mut ResourceUsage,
+    mem_addr: MemAddr,
+    n: u32,
+) -> Result<(), GrowthFailure> {
+    // SAFETY: The caller ensures that the given memory address is valid in
+    // this address vector.
+    let memory = unsafe { store_memories.get_mut(mem_addr) };
+    if !memory.can_grow(n) {
+        return Err(GrowthFailure::MaximumExceeded);
+    }
+
+    let current_pages = memory.size() as u32;
+    match user_data.memory_grow_hook(mem_addr, current_pages, n, resource_usage) {
+        GrowthDecision::Allow => {}
+        GrowthDecision::Deny => return Err(GrowthFailure::Denied),
+        GrowthDecision::Trap => return Err(GrowthFailure::Trap),
+    }
+
+    // the maximum size was checked above, so growing can only fail if the new pages cannot be allocated
+    memory.grow(n).map_err(|_| GrowthFailure::OutOfMemory)?;
+    resource_usage.memory_bytes += u64::from(n) * u64::from(memory.ty.page_size());
+    Ok(())
+}
+
+/// Grows the table at `table_addr` by `n` elements initialized to `r#ref`, if its maximum size and the resource
+/// limiter of the config allow it. Otherwise the table is left unchanged and the reason is returned.
+///
+/// # Safety
+///
+/// 1. The table address `table_addr` must be valid in `store_tables`.
+/// 2. Any address contained in `r#ref` must come from the same store as `store_tables`.
+#[inline(always)]
+pub(super) unsafe fn grow_table<T: Config>(
+    store_tables: &mut AddrVec<TableAddr, TableInst>,
+    user_data: &mut T,
+    resource_usage: &mut ResourceUsage,
+    table_addr: TableAddr,
+    n: u32,
+    r#ref: Ref,
+) -> Result<(), GrowthFailure> {
+    // SAFETY: The caller ensures that the given table address is valid in
+    // this address vector (1).
+    let table = unsafe { store_tables.get_mut(table_addr) };
+    if !table.can_grow(n) {
+        return Err(GrowthFailure::MaximumExceeded);
+    }
+
+    let current_elements = table.len() as u32;
+    match user_data.table_grow_hook(table_addr, current_elements, n, resource_usage) {
+        GrowthDecision::Allow => {}
+        GrowthDecision::Deny => return Err(GrowthFailure::Denied),
+        GrowthDecision::Trap => return Err(GrowthFailure::Trap),
+    }
+
+    // the maximum size was checked above, so growing can only fail if the new elements cannot be allocated
+    table
+        .grow(n, r#ref)
+        .map_err(|_| GrowthFailure::OutOfMemory)?;
+    resource_usage.table_elements += u64::from(n);
+    Ok(())
+}
+
+/// Loads a value from the linear memory of `mem_inst`, forwarding the access to [`Config::mmio_load_hook`] if it
+/// overlaps a memory-mapped I/O region. Traps if the access touches a no-access region.
+#[inline(always)]
+fn mem_load<T: Config, const N: usize, V: LittleEndianBytes<N>>(
+    mem_inst: &mut MemInst,
+    user_data: &mut T,
+    mem_addr: MemAddr,
+    idx: usize,
+) -> Result<V, RuntimeError> {
+    mem_load_bytes::<T, N>(mem_inst, user_data, mem_addr, idx).map(V::from_le_bytes)
+}
+
+/// Loads `N` bytes from the linear memory of `mem_inst`, see [`mem_load`]
+#[inline(always)]
+fn mem_load_bytes<T: Config, const N: usize>(
+    mem_inst: &mut MemInst,
+    user_data: &mut T,
+    mem_addr: MemAddr,
+    idx: usize,
+) -> Result<[u8; N], RuntimeError> {
+    // the linear memory is accessed even for memory-mapped I/O to check the bounds of the access
+    let mut bytes = mem_inst.mem.load_bytes_exclusive::<N>(idx)?;
+    mem_inst.check_read_protection(idx, N)?;
+    if mem_inst.is_mmio(idx, N) {
+        user_data.mmio_load_hook(mem_addr, idx, &mut bytes);
+    }
+    Ok(bytes)
+}
+
+/// Stores a value to the linear memory of `mem_inst`, forwarding the access to [`Config::mmio_store_hook`] instead if
+/// it overlaps a memory-mapped I/O region. Traps if the access touches a read-only or no-access region.
+#[inline(always)]
+fn mem_store<T: Config, const N: usize, V: LittleEndianBytes<N>>(
+    mem_inst: &mut MemInst,
+    user_data: &mut T,
+    mem_addr: MemAddr,
+    idx: usize,
+    value: V,
+) -> Result<(), RuntimeError> {
+    // out-of-bounds accesses trap as such, even if they also violate a protection, just like loads
+    mem_inst.mem.check_bounds_exclusive(idx, N)?;
+    mem_inst.check_write_protection(idx, N)?;
+    if mem_inst.is_mmio(idx, N) {
+        user_data.mmio_store_hook(mem_addr, idx, &value.to_le_bytes());
+        Ok(())
+    } else {
+        mem_inst.mem.store_bytes_exclusive(idx, value.to_le_bytes())
+    }
+}
+
 #[inline(always)]
 fn to_lanes<const M: usize, const N: usize, T: LittleEndianBytes<M>>(data: [u8; 16]) -> [T; N] {
     assert_eq!(M * N, 16);
@@ -6316,3 +7176,11 @@ fn from_lanes<const M: usize, const N: usize, T: LittleEndianBytes<M>>(lanes: [T
     let mut bytes = lanes.into_iter().flat_map(T::to_le_bytes);
     array::from_fn(|_| bytes.next().unwrap())
 }
+
+/// Returns the fuel consumed by an instruction with the given costs that processes `n` elements, or `None` if it
+/// overflows. Such an instruction can never be paid for, so it runs out of fuel with a required fuel of [`u64::MAX`].
+fn per_element_cost(flat_cost: u64, cost_per_element: u64, n: u32) -> Option<u64> {
+    u64::from(n)
+        .checked_mul(cost_per_element)
+        .and_then(|cost| cost.checked_add(flat_cost))
+}
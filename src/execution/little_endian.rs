@@ -36,7 +36,18 @@ pub trait LittleEndianBytes<const N: usize> {
 }
 
 // implements the [`LittleEndianBytes`]
-impl_LittleEndianBytes![i8, i16, i32, i64, i128, u8, u16, u32, u64, u128];
+impl_LittleEndianBytes![i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, f32, f64];
+
+/// The bytes of a `v128` value, which are already in little endian byte order
+impl LittleEndianBytes<16> for [u8; 16] {
+    fn from_le_bytes(bytes: [u8; 16]) -> Self {
+        bytes
+    }
+
+    fn to_le_bytes(self) -> [u8; 16] {
+        self
+    }
+}
 
 impl LittleEndianBytes<4> for F32 {
     fn from_le_bytes(bytes: [u8; 4]) -> Self {
@@ -57,3 +68,47 @@ impl LittleEndianBytes<8> for F64 {
         self.0.to_le_bytes()
     }
 }
+
+mod sealed {
+    /// Prevents [`MemoryValue`](super::MemoryValue) from being implemented outside of this crate
+    pub trait Sealed {}
+}
+
+/// A value which can be read from and written to a memory in little endian byte order through
+/// [`Store::mem_read_typed`](crate::Store::mem_read_typed) and [`Store::mem_write_typed`](crate::Store::mem_write_typed)
+///
+/// This trait is sealed. It is implemented for all primitive integer and floating point types, [`F32`], [`F64`] and
+/// `[u8; 16]`, which holds the bytes of a `v128`.
+pub trait MemoryValue: sealed::Sealed + Sized {
+    /// The little endian byte representation of a value, which is a byte array of the size of the value
+    type Bytes: AsRef<[u8]> + AsMut<[u8]> + Default;
+
+    /// Convert from a byte array to Self
+    fn from_le_bytes(bytes: Self::Bytes) -> Self;
+
+    /// Convert from self to a byte array
+    fn to_le_bytes(self) -> Self::Bytes;
+}
+
+/// This macro implements the [`MemoryValue`] trait for a provided list of types, which all have to implement
+/// [`LittleEndianBytes`] for their size.
+macro_rules! impl_MemoryValue{
+        [$($type:ty),+] => {
+
+            $(impl sealed::Sealed for $type {}
+
+            impl MemoryValue for $type {
+                type Bytes = [u8; ::core::mem::size_of::<$type>()];
+
+                fn from_le_bytes(bytes: Self::Bytes) -> Self {
+                    <Self as LittleEndianBytes<{ ::core::mem::size_of::<$type>() }>>::from_le_bytes(bytes)
+                }
+
+                fn to_le_bytes(self) -> Self::Bytes {
+                    <Self as LittleEndianBytes<{ ::core::mem::size_of::<$type>() }>>::to_le_bytes(self)
+                }
+            })+
+        }
+    }
+
+impl_MemoryValue![i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, f32, f64, [u8; 16], F32, F64];
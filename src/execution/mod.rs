@@ -1,15 +1,22 @@
 use const_interpreter_loop::run_const_span;
 use value_stack::Stack;
 
+pub use little_endian::MemoryValue;
+
 use crate::execution::assert_validated::UnwrapValidatedExt;
 
 pub(crate) mod assert_validated;
 pub mod config;
 pub mod const_interpreter_loop;
+pub mod cost_table;
+pub mod epoch;
 pub mod error;
+pub(crate) mod fallible_alloc;
 mod interpreter_loop;
 pub(crate) mod little_endian;
+pub mod profiler;
 pub mod resumable;
 pub mod store;
 pub mod value;
 pub mod value_stack;
+pub mod watchpoints;
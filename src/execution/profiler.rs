@@ -0,0 +1,280 @@
+//! A built-in per-function profiler, implemented on top of the hooks of [`Config`]
+//!
+//! [`Profiler`] is a [`ConfigLayer`] around an arbitrary inner [`Config`] and forwards all constants, cost functions
+//! and hooks to it. While executing, it tracks a shadow call stack through the call and return hooks, and attributes
+//! every executed instruction and all consumed fuel to the function on top of it.
+//!
+//! The collected data can be exported as per-function profiles, per-opcode histograms and in the folded stack format
+//! used by flamegraph tools.
+
+use core::fmt::Write;
+
+use alloc::{borrow::ToOwned, collections::btree_map::BTreeMap, format, string::String, vec::Vec};
+
+use crate::{
+    addrs::{Addr, FuncAddr},
+    config::{Config, ConfigLayer},
+    core::reader::{
+        types::opcode::{
+            fc_extension_opcode_to_str, fd_extension_opcode_to_str, opcode_byte_to_str,
+            FC_EXTENSIONS, FD_EXTENSIONS,
+        },
+        WasmReader,
+    },
+};
+
+/// The opcode of a single Wasm instruction
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Opcode {
+    /// A single byte instruction
+    Single(u8),
+    /// A multi-byte instruction starting with the byte 0xFC
+    FcExtension(u32),
+    /// A multi-byte instruction starting with the byte 0xFD
+    FdExtension(u32),
+}
+
+impl Opcode {
+    /// Reads the opcode of the instruction at `pc` in `bytecode`. Returns `None` if the bytecode ends prematurely.
+    fn read(bytecode: &[u8], pc: usize) -> Option<Self> {
+        let mut wasm = WasmReader::new(bytecode);
+        wasm.pc = pc;
+
+        let opcode = match wasm.read_u8().ok()? {
+            FC_EXTENSIONS => Opcode::FcExtension(wasm.read_var_u32().ok()?),
+            FD_EXTENSIONS => Opcode::FdExtension(wasm.read_var_u32().ok()?),
+            byte => Opcode::Single(byte),
+        };
+        Some(opcode)
+    }
+}
+
+impl core::fmt::Display for Opcode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let name = match *self {
+            Opcode::Single(byte) => opcode_byte_to_str(byte),
+            Opcode::FcExtension(instr) => fc_extension_opcode_to_str(instr),
+            Opcode::FdExtension(instr) => fd_extension_opcode_to_str(instr),
+        };
+        f.write_str(&name)
+    }
+}
+
+/// The profile of a single function
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FunctionProfile {
+    /// How often this function was called or invoked
+    pub calls: u64,
+    /// The number of instructions executed in this function itself
+    pub self_instructions: u64,
+    /// The number of instructions executed in this function and all functions called by it
+    pub inclusive_instructions: u64,
+    /// The amount of fuel consumed by this function itself
+    pub self_fuel: u64,
+    /// The amount of fuel consumed by this function and all functions called by it
+    pub inclusive_fuel: u64,
+    /// How often each opcode was executed in this function itself
+    pub opcodes: BTreeMap<Opcode, u64>,
+}
+
+/// The metric used as weight of a stack in [`Profiler::folded_stacks`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProfileWeight {
+    Instructions,
+    Fuel,
+}
+
+/// The samples collected for a single call stack
+#[derive(Clone, Copy, Debug, Default)]
+struct StackSamples {
+    instructions: u64,
+    fuel: u64,
+}
+
+/// A [`Config`] that profiles the executed Wasm code per function
+///
+/// If execution is aborted through a trap, the shadow call stack does not match the actual call stack anymore. It is
+/// reset automatically when the next invocation starts.
+#[derive(Debug, Default)]
+pub struct Profiler<C: Config = ()> {
+    call_stack: Vec<FuncAddr>,
+    stacks: BTreeMap<Vec<FuncAddr>, StackSamples>,
+    functions: BTreeMap<FuncAddr, FunctionProfile>,
+    names: BTreeMap<FuncAddr, String>,
+    /// The wrapped configuration
+    pub inner: C,
+}
+
+impl<C: Config> Profiler<C> {
+    /// Creates a new profiler without any samples, wrapping the given configuration
+    pub fn new(inner: C) -> Self {
+        Self {
+            call_stack: Vec::new(),
+            stacks: BTreeMap::new(),
+            functions: BTreeMap::new(),
+            names: BTreeMap::new(),
+            inner,
+        }
+    }
+
+    /// Sets the symbol names used for functions in all exported data. Functions without a name are referred to by
+    /// their address.
+    ///
+    /// The names of a module instance can be retrieved through
+    /// [`Store::instance_function_names`](crate::Store::instance_function_names).
+    pub fn set_function_names<'a>(&mut self, names: impl IntoIterator<Item = (FuncAddr, &'a str)>) {
+        self.names.extend(
+            names
+                .into_iter()
+                .map(|(func_addr, name)| (func_addr, name.to_owned())),
+        );
+    }
+
+    /// Returns the symbol name of a function
+    pub fn function_name(&self, func_addr: FuncAddr) -> String {
+        match self.names.get(&func_addr) {
+            Some(name) => name.clone(),
+            None => format!("func[{}]", func_addr.into_inner()),
+        }
+    }
+
+    /// Discards all samples collected so far
+    pub fn clear(&mut self) {
+        self.stacks.clear();
+        self.functions.clear();
+    }
+
+    /// Returns the profiles of all functions that were executed so far
+    pub fn function_profiles(&self) -> BTreeMap<FuncAddr, FunctionProfile> {
+        let mut profiles = self.functions.clone();
+
+        for (stack, samples) in &self.stacks {
+            // recursive functions must be counted only once per stack
+            let mut functions_in_stack = stack.clone();
+            functions_in_stack.sort_unstable();
+            functions_in_stack.dedup();
+
+            for func_addr in functions_in_stack {
+                let profile = profiles.entry(func_addr).or_default();
+                profile.inclusive_instructions += samples.instructions;
+                profile.inclusive_fuel += samples.fuel;
+            }
+        }
+
+        profiles
+    }
+
+    /// Returns how often each opcode was executed across all functions
+    pub fn opcode_histogram(&self) -> BTreeMap<Opcode, u64> {
+        let mut histogram = BTreeMap::new();
+        for profile in self.functions.values() {
+            for (opcode, count) in &profile.opcodes {
+                *histogram.entry(*opcode).or_default() += count;
+            }
+        }
+        histogram
+    }
+
+    /// Exports all collected call stacks in the folded stack format, i.e. one line per call stack consisting of the
+    /// `;`-separated function names from the outermost to the innermost function, followed by the weight of that stack.
+    /// Stacks with a weight of zero are omitted.
+    pub fn folded_stacks(&self, weight: ProfileWeight) -> String {
+        let mut folded = String::new();
+        for (stack, samples) in &self.stacks {
+            let weight = match weight {
+                ProfileWeight::Instructions => samples.instructions,
+                ProfileWeight::Fuel => samples.fuel,
+            };
+            if weight == 0 {
+                continue;
+            }
+
+            let frames: Vec<String> = stack
+                .iter()
+                .map(|func_addr| self.function_name(*func_addr).replace(';', ":"))
+                .collect();
+            writeln!(folded, "{} {weight}", frames.join(";"))
+                .expect("writing to a string to never fail");
+        }
+        folded
+    }
+
+    /// Returns the samples of the current call stack, inserting them if they do not exist yet
+    fn current_stack_samples(&mut self) -> &mut StackSamples {
+        if !self.stacks.contains_key(self.call_stack.as_slice()) {
+            self.stacks
+                .insert(self.call_stack.clone(), StackSamples::default());
+        }
+        self.stacks
+            .get_mut(self.call_stack.as_slice())
+            .expect("the samples of the current stack to have just been inserted")
+    }
+}
+
+impl<C: Config> ConfigLayer for Profiler<C> {
+    type Inner = C;
+
+    fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    fn inner_mut(&mut self) -> &mut C {
+        &mut self.inner
+    }
+
+    fn instruction_hook(&mut self, bytecode: &[u8], pc: usize) {
+        self.inner.instruction_hook(bytecode, pc);
+
+        let Some(&func_addr) = self.call_stack.last() else {
+            return;
+        };
+
+        self.current_stack_samples().instructions += 1;
+        let profile = self.functions.entry(func_addr).or_default();
+        profile.self_instructions += 1;
+        if let Some(opcode) = Opcode::read(bytecode, pc) {
+            *profile.opcodes.entry(opcode).or_default() += 1;
+        }
+    }
+
+    fn resume_hook(&mut self, func_addr: FuncAddr, new_invocation: bool) {
+        self.inner.resume_hook(func_addr, new_invocation);
+
+        // A new invocation discards the frames left on the shadow call stack by an invocation that trapped or was
+        // never finished. Otherwise, execution continues in the function on top of the shadow call stack.
+        if new_invocation {
+            self.call_stack.clear();
+            self.call_stack.push(func_addr);
+            self.functions.entry(func_addr).or_default().calls += 1;
+        } else if self.call_stack.last() != Some(&func_addr) {
+            self.call_stack.clear();
+            self.call_stack.push(func_addr);
+        }
+    }
+
+    fn call_hook(&mut self, func_addr: FuncAddr) {
+        self.inner.call_hook(func_addr);
+
+        self.call_stack.push(func_addr);
+        self.functions.entry(func_addr).or_default().calls += 1;
+    }
+
+    fn return_hook(&mut self, func_addr: FuncAddr) {
+        self.inner.return_hook(func_addr);
+
+        if self.call_stack.last() == Some(&func_addr) {
+            self.call_stack.pop();
+        }
+    }
+
+    fn fuel_hook(&mut self, fuel: u64) {
+        self.inner.fuel_hook(fuel);
+
+        let Some(&func_addr) = self.call_stack.last() else {
+            return;
+        };
+
+        self.current_stack_samples().fuel += fuel;
+        self.functions.entry(func_addr).or_default().self_fuel += fuel;
+    }
+}
@@ -4,7 +4,11 @@ use core::num::NonZeroU64;
 
 use alloc::vec::Vec;
 
-use crate::{addrs::FuncAddr, value_stack::Stack, Hostcode, Value};
+use crate::{
+    addrs::{FuelPoolAddr, FuncAddr},
+    value_stack::{Stack, StackStorage},
+    Hostcode, RuntimeError, Value,
+};
 
 /// A [`WasmResumable`] is an object used to resume execution of Wasm code.
 ///
@@ -23,16 +27,39 @@ pub struct WasmResumable {
     pub(crate) stp: usize,
     pub(crate) current_func_addr: FuncAddr,
     pub(crate) maybe_fuel: Option<u64>,
+    /// The fuel pool this resumable draws its fuel from. If this is set, `maybe_fuel` only holds the fuel of the pool
+    /// while this resumable is being executed and is `None` otherwise.
+    pub(crate) fuel_pool: Option<FuelPoolAddr>,
+    /// Fuel charged for a finished host call, which could not be paid yet. It is deducted before execution continues.
+    pub(crate) pending_fuel: u64,
+    /// Whether this resumable was executed before, i.e. whether resuming it continues an invocation instead of
+    /// starting a new one
+    pub(crate) started: bool,
 }
 
 impl WasmResumable {
+    /// Returns the fuel of this resumable. This is always `None` for resumables that draw their fuel from a fuel
+    /// pool, see [`WasmResumable::fuel_pool`].
     pub fn fuel(&self) -> Option<u64> {
         self.maybe_fuel
     }
 
+    /// Returns a mutable reference to the fuel of this resumable. Changing it has no effect for resumables that draw
+    /// their fuel from a fuel pool, instead the pool has to be refilled.
     pub fn fuel_mut(&mut self) -> &mut Option<u64> {
         &mut self.maybe_fuel
     }
+
+    /// Returns the fuel pool this resumable draws its fuel from, if any
+    pub fn fuel_pool(&self) -> Option<FuelPoolAddr> {
+        self.fuel_pool
+    }
+
+    /// Discards this resumable and returns the storage of its stack for reuse, if it was created with
+    /// [`Store::create_resumable_in`](crate::Store::create_resumable_in)
+    pub fn into_stack_storage(self) -> Option<StackStorage> {
+        self.stack.into_storage()
+    }
 }
 
 /// A [`HostCall`] object contains information required for executing a specific
@@ -60,6 +87,11 @@ pub struct HostResumable {
     /// it is used to store the maybe_fuel, so it can be returned in
     /// [`RunState::Finished`] later.
     pub(crate) maybe_fuel: Option<Option<u64>>,
+    /// The fuel pool that is charged for the host call, only used if `inner_resumable` is `None`.
+    pub(crate) fuel_pool: Option<FuelPoolAddr>,
+    /// The preallocated storage the resumable was created in, only used if `inner_resumable` is `None`. It receives
+    /// the return values of the host call, so that they can be returned without allocating.
+    pub(crate) stack_storage: Option<StackStorage>,
 }
 
 #[derive(Debug)]
@@ -93,19 +125,58 @@ impl Resumable {
     }
 }
 
+/// The reason why execution was interrupted without running out of fuel, see [`RunState::Interrupted`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InterruptReason {
+    /// [`Config::interruption_hook`](crate::config::Config::interruption_hook) requested an interruption at a loop
+    /// back-edge or call, e.g. because an [`EpochDeadline`](crate::epoch::EpochDeadline) was reached
+    InterruptionHook,
+    /// one of the memory or global access hooks of the [`Config`](crate::config::Config) requested an interruption
+    /// after the current instruction
+    AccessHook,
+    /// a host function called by Wasm code has finished and Wasm execution continues right after the call
+    HostCallFinished,
+}
+
+/// The error returned by [`Store::resume_wasm_or_reclaim`](crate::Store::resume_wasm_or_reclaim), which carries the
+/// [`StackStorage`] of the failed resumable
+#[derive(Debug)]
+pub struct ResumeError {
+    pub error: RuntimeError,
+    /// The storage of the resumable, if it was created with
+    /// [`Store::create_resumable_in`](crate::Store::create_resumable_in)
+    pub stack_storage: Option<StackStorage>,
+}
+
+impl From<ResumeError> for RuntimeError {
+    fn from(resume_error: ResumeError) -> Self {
+        resume_error.error
+    }
+}
+
 /// Represents the state of a possibly interrupted resumable.
 pub enum RunState {
     /// represents a resumable that has executed completely with return values `values` and possibly remaining fuel
-    /// `maybe_remaining_fuel` (has `Some(remaining_fuel)` for fuel-metered operations and `None` otherwise)
+    /// `maybe_remaining_fuel` (has `Some(remaining_fuel)` for fuel-metered operations and `None` otherwise). If the
+    /// resumable was created with [`Store::create_resumable_in`](crate::Store::create_resumable_in), the rest of its
+    /// [`StackStorage`] is returned in `stack_storage`. `values` can be put back into it with
+    /// [`StackStorage::reclaim`].
     Finished {
         values: Vec<Value>,
         maybe_remaining_fuel: Option<u64>,
+        stack_storage: Option<StackStorage>,
     },
     /// represents a resumable that has ran out of fuel during execution, missing at least `required_fuel` units of fuel
-    /// to continue further execution (this is None if unknown).
+    /// to continue further execution
     Resumable {
         resumable: WasmResumable,
-        required_fuel: Option<NonZeroU64>,
+        required_fuel: NonZeroU64,
+    },
+    /// represents a resumable whose execution was interrupted for the given `reason` and which can be resumed right
+    /// away
+    Interrupted {
+        resumable: WasmResumable,
+        reason: InterruptReason,
     },
     /// A host function was called by Wasm code. Use the [`HostCall`] to execute
     /// the host function and resume execution using the [`HostResumable`] and
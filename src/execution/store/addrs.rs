@@ -23,6 +23,8 @@ use core::{cmp::Ordering, marker::PhantomData};
 
 use alloc::vec::Vec;
 
+use crate::{execution::fallible_alloc::TryVecExt, RuntimeError};
+
 /// A trait for all address types.
 pub trait Addr: Copy + core::fmt::Debug + core::fmt::Display + Eq {
     fn new(inner: usize) -> Self;
@@ -69,13 +71,29 @@ impl<A: Addr, Inst> AddrVec<A, Inst> {
             .expect("addrs to always be valid")
     }
 
+    /// Returns whether the given address is valid in this vector.
+    pub(crate) fn contains(&self, addr: A) -> bool {
+        addr.into_inner() < self.inner.len()
+    }
+
+    /// Returns the address which the next inserted instance will get
+    pub(crate) fn next_addr(&self) -> A {
+        A::new(self.inner.len())
+    }
+
+    /// Returns an iterator over all instances in the order of their addresses
+    pub(crate) fn iter(&self) -> core::slice::Iter<'_, Inst> {
+        self.inner.iter()
+    }
+
     /// Inserts a new instance into the current [`Store`](crate::Store) and returns its address.
     ///
-    /// This method should always be used to insert new instances, as it is the only safe way of creating addrs.
-    pub(crate) fn insert(&mut self, instance: Inst) -> A {
+    /// This method should always be used to insert new instances, as it is the only safe way of creating addrs. If
+    /// the vector cannot grow, [`RuntimeError::OutOfMemory`] is returned and the vector is left unchanged.
+    pub(crate) fn insert(&mut self, instance: Inst) -> Result<A, RuntimeError> {
         let new_addr = self.inner.len();
-        self.inner.push(instance);
-        A::new(new_addr)
+        self.inner.try_push(instance)?;
+        Ok(A::new(new_addr))
     }
 
     /// Mutably borrows two instances by their addresses and returns those
@@ -123,7 +141,7 @@ impl<A: Addr, Inst> AddrVec<A, Inst> {
 }
 
 /// An address to a function instance that lives in a specific [`Store`](crate::execution::store::Store).
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct FuncAddr(usize);
 
 impl core::fmt::Display for FuncAddr {
@@ -143,7 +161,7 @@ impl Addr for FuncAddr {
 }
 
 /// An address to a table instance that lives in a specific [`Store`](crate::Store).
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct TableAddr(usize);
 
 impl core::fmt::Display for TableAddr {
@@ -163,7 +181,7 @@ impl Addr for TableAddr {
 }
 
 /// An address to a memory instance that lives in a specific [`Store`](crate::Store).
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct MemAddr(usize);
 
 impl core::fmt::Display for MemAddr {
@@ -183,7 +201,7 @@ impl Addr for MemAddr {
 }
 
 /// An address to a global instance that lives in a specific [`Store`](crate::Store).
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct GlobalAddr(usize);
 
 impl core::fmt::Display for GlobalAddr {
@@ -204,7 +222,7 @@ impl Addr for GlobalAddr {
 }
 
 /// An address to an element instance that lives in a specific [`Store`](crate::Store).
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ElemAddr(usize);
 
 impl core::fmt::Display for ElemAddr {
@@ -224,7 +242,7 @@ impl Addr for ElemAddr {
 }
 
 /// An address to a data instance that lives in a specific [`Store`](crate::Store).
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct DataAddr(usize);
 
 impl core::fmt::Display for DataAddr {
@@ -244,7 +262,7 @@ impl Addr for DataAddr {
 }
 
 /// An address to a module instance that lives in a specific [`Store`](crate::Store).
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ModuleAddr(usize);
 
 impl core::fmt::Display for ModuleAddr {
@@ -262,3 +280,23 @@ impl Addr for ModuleAddr {
         self.0
     }
 }
+
+/// An address to a fuel pool that lives in a specific [`Store`](crate::Store).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FuelPoolAddr(usize);
+
+impl core::fmt::Display for FuelPoolAddr {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "fuel pool address {}", self.0)
+    }
+}
+
+impl Addr for FuelPoolAddr {
+    fn new(inner: usize) -> Self {
+        Self(inner)
+    }
+
+    fn into_inner(self) -> usize {
+        self.0
+    }
+}
@@ -1,4 +1,6 @@
-use alloc::{collections::btree_map::BTreeMap, string::String, vec, vec::Vec};
+use core::ops::Range;
+
+use alloc::{collections::btree_map::BTreeMap, string::String, sync::Arc, vec::Vec};
 
 use crate::{
     core::{
@@ -7,17 +9,19 @@ use crate::{
             span::Span,
             types::{FuncType, MemType, TableType},
         },
-        sidetable::Sidetable,
+        sidetable::BlockFuelTable,
         utils::ToUsizeExt,
     },
+    execution::fallible_alloc::TryVecExt,
     linear_memory::LinearMemory,
+    validation::{LocalFuncData, SharedModuleData},
     value::Ref,
-    GlobalType, Hostcode, Limits, RefType, RuntimeError, TrapError, ValType, Value,
+    GlobalType, Hostcode, Limits, RefType, RuntimeError, TrapError, Value,
 };
 
 use super::{
     addrs::{DataAddr, ElemAddr, FuncAddr, GlobalAddr, MemAddr, ModuleAddr, TableAddr},
-    ExternVal,
+    ExternVal, MemoryProtection,
 };
 
 #[derive(Debug)]
@@ -29,9 +33,9 @@ pub enum FuncInst {
 
 #[derive(Debug)]
 pub struct WasmFuncInst {
-    pub function_type: FuncType,
+    /// The type and call frame layout of this function, which are shared with all other instances of this function
+    pub(crate) local_func: Arc<LocalFuncData>,
     pub _ty: TypeIdx,
-    pub locals: Vec<ValType>,
     pub code_expr: Span,
     ///index of the sidetable corresponding to the beginning of this functions code
     pub stp: usize,
@@ -50,7 +54,7 @@ pub struct HostFuncInst {
 impl FuncInst {
     pub fn ty(&self) -> &FuncType {
         match self {
-            FuncInst::WasmFunc(wasm_func_inst) => &wasm_func_inst.function_type,
+            FuncInst::WasmFunc(wasm_func_inst) => &wasm_func_inst.local_func.function_type,
             FuncInst::HostFunc(host_func_inst) => &host_func_inst.function_type,
         }
     }
@@ -83,6 +87,12 @@ impl TableInst {
         self.elem.len()
     }
 
+    /// Checks whether growing this table by `n` elements stays within its maximum size
+    pub fn can_grow(&self, n: u32) -> bool {
+        n.checked_add(self.elem.len() as u32)
+            .is_some_and(|len| self.ty.lim.max.is_none_or(|max| len <= max))
+    }
+
     /// <https://webassembly.github.io/spec/core/exec/modules.html#growing-tables>
     pub fn grow(&mut self, n: u32, reff: Ref) -> Result<(), RuntimeError> {
         // TODO refactor error, the spec Table.grow raises Table.{SizeOverflow, SizeLimit, OutOfMemory}
@@ -101,7 +111,8 @@ impl TableInst {
             max: self.ty.lim.max,
         };
 
-        self.elem.extend(vec![reff; n.into_usize()]);
+        let new_len = self.elem.len() + n.into_usize();
+        self.elem.try_resize_with(new_len, || reff)?;
 
         self.ty.lim = limits_prime;
         Ok(())
@@ -111,6 +122,11 @@ impl TableInst {
 pub struct MemInst {
     pub ty: MemType,
     pub mem: LinearMemory,
+    /// Address ranges whose loads and stores are forwarded to the
+    /// [`Config`](crate::config::Config) instead of the linear memory
+    pub mmio_regions: Vec<Range<usize>>,
+    /// Address ranges which Wasm code may not write to or not access at all
+    pub protected_regions: Vec<(Range<usize>, MemoryProtection)>,
 }
 impl core::fmt::Debug for MemInst {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -121,13 +137,20 @@ impl core::fmt::Debug for MemInst {
 }
 
 impl MemInst {
+    /// Checks whether growing this memory by `n` pages stays within its maximum size
+    pub fn can_grow(&self, n: u32) -> bool {
+        n.checked_add(self.mem.pages()).is_some_and(|len| {
+            len <= self.ty.max_pages() && self.ty.limits.max.is_none_or(|max| len <= max)
+        })
+    }
+
     /// <https://webassembly.github.io/spec/core/exec/modules.html#growing-memories>
     pub fn grow(&mut self, n: u32) -> Result<(), RuntimeError> {
         // TODO refactor error, the spec Table.grow raises Memory.{SizeOverflow, SizeLimit, OutOfMemory}
-        let len = n + self.mem.pages() as u32;
-        if len > Limits::MAX_MEM_PAGES {
-            return Err(TrapError::MemoryOrDataAccessOutOfBounds.into());
-        }
+        let len = n
+            .checked_add(self.mem.pages())
+            .filter(|len| *len <= self.ty.max_pages())
+            .ok_or(TrapError::MemoryOrDataAccessOutOfBounds)?;
 
         // roughly matches step 4,5,6
         // checks limits_prime.valid() for limits_prime := { min: len, max: self.ty.lim.max }
@@ -140,18 +163,68 @@ impl MemInst {
             max: self.ty.limits.max,
         };
 
-        self.mem.grow(n.try_into().unwrap());
+        self.mem.grow(n)?;
 
         self.ty.limits = limits_prime;
         Ok(())
     }
 
-    /// Can never be bigger than 65,356 pages
+    /// Checks whether an access of `len` bytes at `address` overlaps a memory-mapped I/O region
+    #[inline(always)]
+    pub fn is_mmio(&self, address: usize, len: usize) -> bool {
+        self.mmio_regions
+            .iter()
+            .any(|region| overlaps(region, address, len))
+    }
+
+    /// Checks that a bulk memory instruction accessing `len` bytes at `address` does not overlap a memory-mapped I/O
+    /// region, which only supports loads and stores of single values
+    #[inline(always)]
+    pub fn check_bulk_mmio(&self, address: usize, len: usize) -> Result<(), RuntimeError> {
+        if self.is_mmio(address, len) {
+            return Err(TrapError::MmioBulkAccess.into());
+        }
+        Ok(())
+    }
+
+    /// Checks that reading `len` bytes at `address` does not touch a no-access region
+    #[inline(always)]
+    pub fn check_read_protection(&self, address: usize, len: usize) -> Result<(), RuntimeError> {
+        let violated = self.protected_regions.iter().any(|(region, protection)| {
+            *protection == MemoryProtection::NoAccess && overlaps(region, address, len)
+        });
+        if violated {
+            return Err(TrapError::MemoryProtectionViolation.into());
+        }
+        Ok(())
+    }
+
+    /// Checks that writing `len` bytes at `address` does not touch a read-only or no-access region
+    #[inline(always)]
+    pub fn check_write_protection(&self, address: usize, len: usize) -> Result<(), RuntimeError> {
+        let violated = self
+            .protected_regions
+            .iter()
+            .any(|(region, _)| overlaps(region, address, len));
+        if violated {
+            return Err(TrapError::MemoryProtectionViolation.into());
+        }
+        Ok(())
+    }
+
+    /// Returns the size in pages of [`MemType::page_size`] bytes, which can never be bigger than
+    /// [`MemType::max_pages`]
     pub fn size(&self) -> usize {
-        self.mem.len() / (crate::Limits::MEM_PAGE_SIZE.into_usize())
+        self.mem.len() / self.mem.page_size()
     }
 }
 
+/// Checks whether the `len` bytes at `address` overlap `region`
+#[inline(always)]
+fn overlaps(region: &Range<usize>, address: usize, len: usize) -> bool {
+    len > 0 && address < region.end && region.start < address.saturating_add(len)
+}
+
 // pub struct GlobalInstV2 {
 //     Local(LocalGlobalInst),
 //     Imported(ImportedGlobalInst)
@@ -182,7 +255,6 @@ impl core::fmt::Debug for DataInst {
 /// index vectors from the same module instance.
 #[derive(Debug)]
 pub struct ModuleInst<'b> {
-    pub types: IdxVec<TypeIdx, FuncType>,
     pub func_addrs: IdxVec<FuncIdx, FuncAddr>,
     pub table_addrs: IdxVec<TableIdx, TableAddr>,
     pub mem_addrs: IdxVec<MemIdx, MemAddr>,
@@ -198,6 +270,13 @@ pub struct ModuleInst<'b> {
     // TODO the bytecode is not in the spec, but required for re-parsing
     pub wasm_bytecode: &'b [u8],
 
-    // sidetable is not in the spec, but required for control flow
-    pub sidetable: Sidetable,
+    // the types are part of the spec, while the sidetable, pre-decoded immediates, locations of v128 operands and
+    // function names are additional data of the interpreter. All of them are shared with the `ValidationInfo` and other
+    // instances of the same module.
+    pub(crate) shared: Arc<SharedModuleData<'b>>,
+
+    // the fuel of the basic blocks is not in the spec, but allows charging fuel once per basic block. It depends on the
+    // config of the store and is shared with other instances of the same module in the same store. It is only computed
+    // once fuel-metered execution starts.
+    pub block_fuel: Option<Arc<BlockFuelTable>>,
 }
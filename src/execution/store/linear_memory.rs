@@ -1,12 +1,14 @@
 use core::{
     iter,
+    ops::Range,
     sync::atomic::{AtomicU8, Ordering},
 };
 
 use alloc::vec::Vec;
 
 use crate::{
-    execution::little_endian::LittleEndianBytes,
+    execution::fallible_alloc::TryVecExt,
+    memory_backing::MemoryBacking,
     rw_spinlock::{ReadLockGuard, RwSpinLock},
     RuntimeError, TrapError,
 };
@@ -16,14 +18,20 @@ use crate::{
 /// Implements the base for the instructions described in
 /// <https://webassembly.github.io/spec/core/exec/instructions.html#memory-instructions>.
 ///
-/// This linear memory implementation internally relies on a [`Vec<AtomicU8>`]. Thus, the atomic unit
+/// This linear memory implementation internally relies on a [`Vec<AtomicU8>`] or a caller-provided
+/// [`MemoryBacking`]. Thus, the atomic unit
 /// of information for it is a byte (`u8`). All access to the linear memory internally occur through
 /// [`AtomicU8::load`] and [`AtomicU8::store`], avoiding the creation of shared and `mut ref`s to
 /// the internal data completely. This avoids undefined behavior. Racy multibyte writes to the same
 /// data however may tear (e.g. for any number of concurrent writes to a given byte, only one is
-/// effectively written). Because of this, the [`LinearMemory::store`] function does not require
+/// effectively written). Because of this, the [`LinearMemory::store_bytes`] function does not require
 /// `&mut self` -- `&self` suffices.
 ///
+/// Accesses through an exclusive borrow, i.e. the methods with an `_exclusive` suffix such as
+/// [`LinearMemory::load_bytes_exclusive`], are the exception: as nothing else can access the
+/// memory concurrently, they operate on plain bytes. This turns loads and stores into word-sized
+/// accesses and bulk operations into `memcpy`/`memset`, without any locking.
+///
 /// The implementation of atomic stores to multibyte values requires a global write lock. Rust's
 /// memory model considers partially overlapping atomic operations involving a write as undefined
 /// behavior. As there is no way to predict if an atomic multibyte store operation might overlap
@@ -66,7 +74,7 @@ use crate::{
 /// or atomic single-byte writes are implemented through a shared ref to the internal vector, with
 /// [`AtomicU8`] to achieve interior mutability without undefined behavior.
 ///
-/// However, linear memory can grow. As the linear memory is implemented via a [`Vec`], a `grow`
+/// However, linear memory can grow. If the linear memory is implemented via a [`Vec`], a `grow`
 /// can result in the vector's internal data buffer to be copied over to a bigger, fresh allocation.
 /// The old buffer is then freed. Combined with concurrent access, this can cause use-after-free.
 /// To avoid this, a `grow` operation of the linear memory acquires a write lock, blocking all
@@ -85,49 +93,297 @@ use crate::{
 /// cargo miri test # thorough
 /// ```
 // TODO if a memmap like operation is available, the linear memory implementation can be optimized brutally. Out-of-bound access can be mapped to userspace handled page-faults, e.g. the MMU takes over that responsibility of catching out of bounds. Grow can happen without copying of data, by mapping new pages consecutively after the current final page of the linear memory.
-pub struct LinearMemory<const PAGE_SIZE: usize = { crate::Limits::MEM_PAGE_SIZE as usize }> {
-    inner_data: RwSpinLock<Vec<AtomicU8>>,
+pub struct LinearMemory {
+    inner_data: RwSpinLock<MemoryData>,
+    /// Size of a page in the linear memory, measured in bytes
+    ///
+    /// The WASM specification demands a page size of 64 KiB, that is `65536` bytes:
+    /// <https://webassembly.github.io/spec/core/exec/runtime.html?highlight=page#memory-instances>. The
+    /// custom-page-sizes proposal additionally allows pages of a single byte.
+    page_size: usize,
 }
 
-/// Type to express the page count
-pub type PageCountTy = u16;
+/// The bytes of a [`LinearMemory`]
+enum MemoryData {
+    /// A heap allocation, which is reallocated on growth
+    Owned(Vec<AtomicU8>),
+    /// Caller-provided storage with a fixed capacity, of which the first `len` bytes are accessible
+    Backed { backing: MemoryBacking, len: usize },
+    /// Heap-allocated chunks of [`SPARSE_CHUNK_SIZE`] bytes, of which the first `len` bytes are accessible. A chunk is
+    /// only allocated when it is first written to, until then all of its bytes read as zero.
+    Sparse {
+        chunks: Vec<Option<Vec<AtomicU8>>>,
+        len: usize,
+    },
+}
 
-impl<const PAGE_SIZE: usize> LinearMemory<PAGE_SIZE> {
-    /// Size of a page in the linear memory, measured in bytes
+/// The size of the chunks of a sparse [`LinearMemory`], measured in bytes
+const SPARSE_CHUNK_SIZE: usize = 1 << 12;
+
+/// The byte which all unallocated chunks of a sparse [`LinearMemory`] read as. It is never written to.
+static UNALLOCATED_BYTE: AtomicU8 = AtomicU8::new(0);
+
+impl MemoryData {
+    /// Returns the number of accessible bytes
+    fn len(&self) -> usize {
+        match self {
+            MemoryData::Owned(vec) => vec.len(),
+            MemoryData::Backed { len, .. } | MemoryData::Sparse { len, .. } => *len,
+        }
+    }
+
+    /// Returns the byte at `index` without bounds checking
     ///
-    /// The WASM specification demands a page size of 64 KiB, that is `65536` bytes:
-    /// <https://webassembly.github.io/spec/core/exec/runtime.html?highlight=page#memory-instances>
-    const PAGE_SIZE: usize = PAGE_SIZE;
+    /// # Safety
+    ///
+    /// `index` must be smaller than [`MemoryData::len`]. If the byte is written to, its chunk must have been allocated
+    /// by [`MemoryData::allocate_chunks`] before, because bytes of unallocated chunks share [`UNALLOCATED_BYTE`].
+    unsafe fn get_unchecked(&self, index: usize) -> &AtomicU8 {
+        match self {
+            // SAFETY: The caller guarantees that the index is in bounds.
+            MemoryData::Owned(vec) => unsafe { vec.get_unchecked(index) },
+            // SAFETY: The caller guarantees that the index is smaller than `len`, which never exceeds the capacity of
+            // the backing.
+            MemoryData::Backed { backing, .. } => unsafe { backing.bytes().get_unchecked(index) },
+            MemoryData::Sparse { chunks, .. } => {
+                // SAFETY: The caller guarantees that the index is smaller than `len`, and there is a chunk slot for
+                // every accessible byte.
+                match unsafe { chunks.get_unchecked(index / SPARSE_CHUNK_SIZE) } {
+                    // SAFETY: Every allocated chunk has `SPARSE_CHUNK_SIZE` bytes.
+                    Some(chunk) => unsafe { chunk.get_unchecked(index % SPARSE_CHUNK_SIZE) },
+                    None => &UNALLOCATED_BYTE,
+                }
+            }
+        }
+    }
 
-    /// Create a new, empty [`LinearMemory`]
-    pub fn new() -> Self {
-        Self {
-            inner_data: RwSpinLock::new(Vec::new()),
+    /// Returns the accessible bytes as plain bytes, unless the memory is sparse and thus not contiguous
+    fn contiguous_bytes_mut(&mut self) -> Option<&mut [u8]> {
+        match self {
+            MemoryData::Owned(vec) => Some(atomic_u8_get_mut_slice(vec)),
+            MemoryData::Backed { backing, len } => {
+                Some(atomic_u8_get_mut_slice(&mut backing.bytes_mut()[..*len]))
+            }
+            MemoryData::Sparse { .. } => None,
+        }
+    }
+
+    /// Returns whether the chunks of the `count` bytes starting at `index` are allocated, which is always the case for
+    /// non-sparse memories and out-of-bounds accesses
+    fn chunks_allocated(&self, index: usize, count: usize) -> bool {
+        match self {
+            MemoryData::Sparse { chunks, .. } => chunks
+                .get(Self::chunk_range(index, count, self.len()))
+                .is_some_and(|chunks| chunks.iter().all(Option::is_some)),
+            _ => true,
         }
     }
 
-    /// Create a new, empty [`LinearMemory`]
-    pub fn new_with_initial_pages(pages: PageCountTy) -> Self {
-        let size_bytes = Self::PAGE_SIZE * usize::from(pages);
-        let mut data = Vec::with_capacity(size_bytes);
-        data.resize_with(size_bytes, || AtomicU8::new(0));
+    /// Allocates the chunks of the `count` bytes starting at `index`, if all of them are in bounds
+    ///
+    /// Returns [`RuntimeError::OutOfMemory`] if a chunk cannot be allocated.
+    fn allocate_chunks(&mut self, index: usize, count: usize) -> Result<(), RuntimeError> {
+        let len = self.len();
+        if let MemoryData::Sparse { chunks, .. } = self {
+            for chunk in &mut chunks[Self::chunk_range(index, count, len)] {
+                if chunk.is_none() {
+                    let mut bytes = Vec::new();
+                    bytes.try_resize_with(SPARSE_CHUNK_SIZE, || AtomicU8::new(0))?;
+                    *chunk = Some(bytes);
+                }
+            }
+        }
+        Ok(())
+    }
 
+    /// Returns the indices of the chunks containing the `count` bytes starting at `index`, or an empty range if the
+    /// bytes are not in bounds of the first `len` bytes
+    fn chunk_range(index: usize, count: usize, len: usize) -> Range<usize> {
+        match index.checked_add(count) {
+            Some(end) if count > 0 && end <= len => {
+                index / SPARSE_CHUNK_SIZE..(end - 1) / SPARSE_CHUNK_SIZE + 1
+            }
+            // out-of-bounds accesses trap before anything is written
+            _ => 0..0,
+        }
+    }
+
+    /// Overwrites all accessible bytes with `bytes`, which must have the same length. Chunks of a sparse memory are
+    /// only allocated if they receive a non-zero byte, so untouched parts of the memory stay unallocated.
+    ///
+    /// Returns [`RuntimeError::OutOfMemory`] if a chunk cannot be allocated, in which case no byte is written.
+    fn overwrite(&mut self, bytes: &[u8]) -> Result<(), RuntimeError> {
+        debug_assert_eq!(bytes.len(), self.len());
+        if let Some(data) = self.contiguous_bytes_mut() {
+            data.copy_from_slice(bytes);
+            return Ok(());
+        }
+        let MemoryData::Sparse { chunks, .. } = self else {
+            unreachable!("only sparse memories are not contiguous")
+        };
+        // allocate all required chunks first, so that nothing is written if an allocation fails
+        for (chunk, copy) in chunks.iter_mut().zip(bytes.chunks(SPARSE_CHUNK_SIZE)) {
+            if chunk.is_none() && copy.iter().any(|byte| *byte != 0) {
+                let mut new_chunk = Vec::new();
+                new_chunk.try_resize_with(SPARSE_CHUNK_SIZE, || AtomicU8::new(0))?;
+                *chunk = Some(new_chunk);
+            }
+        }
+        for (chunk, copy) in chunks.iter_mut().zip(bytes.chunks(SPARSE_CHUNK_SIZE)) {
+            if let Some(chunk) = chunk {
+                atomic_u8_get_mut_slice(chunk)[..copy.len()].copy_from_slice(copy);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the number of bytes allocated for this memory, which are all accessible bytes unless the memory is
+    /// sparse
+    fn allocated_len(&self) -> usize {
+        match self {
+            MemoryData::Sparse { chunks, .. } => {
+                chunks.iter().filter(|chunk| chunk.is_some()).count() * SPARSE_CHUNK_SIZE
+            }
+            _ => self.len(),
+        }
+    }
+
+    /// Resizes the accessible part of the memory to `new_len` bytes, zeroing all new bytes
+    fn grow(&mut self, new_len: usize) -> Result<(), RuntimeError> {
+        match self {
+            MemoryData::Owned(vec) => vec.try_resize_with(new_len, || AtomicU8::new(0)),
+            MemoryData::Backed { backing, len } => {
+                let new_bytes = backing
+                    .bytes()
+                    .get(*len..new_len)
+                    .ok_or(RuntimeError::OutOfMemory)?;
+                // the backing may contain stale data from before it was used for this memory
+                for byte in new_bytes {
+                    byte.store(0, Ordering::Relaxed);
+                }
+                *len = new_len;
+                Ok(())
+            }
+            MemoryData::Sparse { chunks, len } => {
+                // bytes beyond `len` are never written to, so the new bytes of an allocated last chunk are already zero
+                chunks.try_resize_with(new_len.div_ceil(SPARSE_CHUNK_SIZE), || None)?;
+                *len = new_len;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Converts an exclusively borrowed slice of atomic `u8`s to a slice of non-atomic `u8`s
+// TODO when `atomic_from_mut` is stabilized, replace this function with
+// `Atomic::U8::get_mut_slice`
+fn atomic_u8_get_mut_slice(slice: &mut [AtomicU8]) -> &mut [u8] {
+    // SAFETY: the mutable reference guarantees unique ownership, and `u8` has the same size, alignment and bit
+    // validity as `AtomicU8`
+    unsafe { &mut *(slice as *mut [AtomicU8] as *mut [u8]) }
+}
+
+/// Type to express the page count
+pub type PageCountTy = u32;
+
+impl LinearMemory {
+    /// Create a new, empty [`LinearMemory`] with pages of `page_size` bytes
+    pub fn new(page_size: usize) -> Self {
         Self {
-            inner_data: RwSpinLock::new(data),
+            inner_data: RwSpinLock::new(MemoryData::Owned(Vec::new())),
+            page_size,
         }
     }
 
+    /// Create a new [`LinearMemory`] with `pages` zeroed pages of `page_size` bytes
+    ///
+    /// Returns [`RuntimeError::OutOfMemory`] if the pages cannot be allocated.
+    pub fn new_with_initial_pages(
+        page_size: usize,
+        pages: PageCountTy,
+    ) -> Result<Self, RuntimeError> {
+        let size_bytes = pages_to_bytes(page_size, pages)?;
+        let mut data = Vec::new();
+        data.try_resize_with(size_bytes, || AtomicU8::new(0))?;
+
+        Ok(Self {
+            inner_data: RwSpinLock::new(MemoryData::Owned(data)),
+            page_size,
+        })
+    }
+
+    /// Create a new [`LinearMemory`] with `pages` zeroed pages of `page_size` bytes in the given caller-provided
+    /// storage
+    ///
+    /// Returns [`RuntimeError::MemoryBackingTooSmall`] if the backing cannot hold the initial pages.
+    pub fn new_in_backing(
+        backing: MemoryBacking,
+        page_size: usize,
+        pages: PageCountTy,
+    ) -> Result<Self, RuntimeError> {
+        let mut data = MemoryData::Backed { backing, len: 0 };
+        pages_to_bytes(page_size, pages)
+            .and_then(|size_bytes| data.grow(size_bytes))
+            .map_err(|_| RuntimeError::MemoryBackingTooSmall)?;
+
+        Ok(Self {
+            inner_data: RwSpinLock::new(data),
+            page_size,
+        })
+    }
+
+    /// Create a new sparse [`LinearMemory`] with `pages` zeroed pages of `page_size` bytes
+    ///
+    /// The memory is split into chunks of 4 KiB, which are only allocated when they are first written to. Until then,
+    /// they read as zeros. This saves memory for large linear memories of which only a small part is used.
+    ///
+    /// Returns [`RuntimeError::OutOfMemory`] if the chunk table cannot be allocated.
+    pub fn new_sparse(page_size: usize, pages: PageCountTy) -> Result<Self, RuntimeError> {
+        let mut data = MemoryData::Sparse {
+            chunks: Vec::new(),
+            len: 0,
+        };
+        data.grow(pages_to_bytes(page_size, pages)?)?;
+
+        Ok(Self {
+            inner_data: RwSpinLock::new(data),
+            page_size,
+        })
+    }
+
+    /// Returns the size of a page of this [`LinearMemory`] in bytes
+    pub fn page_size(&self) -> usize {
+        self.page_size
+    }
+
     /// Grow the [`LinearMemory`] by a number of pages
-    pub fn grow(&self, pages_to_add: PageCountTy) {
+    ///
+    /// Returns [`RuntimeError::OutOfMemory`] if the new pages cannot be allocated or do not fit into the backing of
+    /// this linear memory, in which case the linear memory is left unchanged.
+    pub fn grow(&self, pages_to_add: PageCountTy) -> Result<(), RuntimeError> {
         let mut lock_guard = self.inner_data.write();
         let prior_length_bytes = lock_guard.len();
-        let new_length_bytes = prior_length_bytes + Self::PAGE_SIZE * usize::from(pages_to_add);
-        lock_guard.resize_with(new_length_bytes, || AtomicU8::new(0));
+        let new_length_bytes = pages_to_bytes(self.page_size, pages_to_add)?
+            .checked_add(prior_length_bytes)
+            .ok_or(RuntimeError::OutOfMemory)?;
+        lock_guard.grow(new_length_bytes)
     }
 
     /// Get the number of pages currently allocated to this [`LinearMemory`]
     pub fn pages(&self) -> PageCountTy {
-        PageCountTy::try_from(self.inner_data.read().len() / PAGE_SIZE).unwrap()
+        PageCountTy::try_from(self.inner_data.read().len() / self.page_size).unwrap()
+    }
+
+    /// Allocates the chunks of a sparse [`LinearMemory`] which the `count` bytes starting at `index` lie in, so that
+    /// they can be written to. Does nothing for other linear memories or out-of-bounds accesses.
+    ///
+    /// Returns [`RuntimeError::OutOfMemory`] if a chunk cannot be allocated.
+    fn allocate_for_write(&self, index: usize, count: usize) -> Result<(), RuntimeError> {
+        if self.inner_data.read().chunks_allocated(index, count) {
+            return Ok(());
+        }
+        // chunks are never deallocated, so they stay allocated once the write lock is released
+        self.inner_data.write().allocate_chunks(index, count)
     }
 
     /// Get the length in bytes currently allocated to this [`LinearMemory`]
@@ -136,21 +392,13 @@ impl<const PAGE_SIZE: usize> LinearMemory<PAGE_SIZE> {
         self.inner_data.read().len()
     }
 
-    /// At a given index, store a datum in the [`LinearMemory`]
-    pub fn store<const N: usize, T: LittleEndianBytes<N>>(
-        &self,
-        index: usize,
-        value: T,
-    ) -> Result<(), RuntimeError> {
-        self.store_bytes::<N>(index, value.to_le_bytes())
-    }
-
     /// At a given index, store a number of bytes `N` in the [`LinearMemory`]
     pub fn store_bytes<const N: usize>(
         &self,
         index: usize,
         bytes: [u8; N],
     ) -> Result<(), RuntimeError> {
+        self.allocate_for_write(index, N)?;
         let lock_guard = self.inner_data.read();
 
         /* check destination for out of bounds access */
@@ -181,6 +429,8 @@ impl<const PAGE_SIZE: usize> LinearMemory<PAGE_SIZE> {
             // - the second if statement in this function guarantees that even with the offset
             //   `index`, writing all of `value`'s bytes does not extend beyond the last byte in
             //   the `LinearMemory` `&self`
+            //
+            // The chunks of sparse memories were allocated at the beginning of this function.
             let dst = unsafe { lock_guard.get_unchecked(i + index) };
             dst.store(byte, Ordering::Relaxed);
         }
@@ -188,14 +438,6 @@ impl<const PAGE_SIZE: usize> LinearMemory<PAGE_SIZE> {
         Ok(())
     }
 
-    /// From a given index, load a datum from the [`LinearMemory`]
-    pub fn load<const N: usize, T: LittleEndianBytes<N>>(
-        &self,
-        index: usize,
-    ) -> Result<T, RuntimeError> {
-        self.load_bytes::<N>(index).map(T::from_le_bytes)
-    }
-
     /// From a given index, load a number of bytes `N` from the [`LinearMemory`]
     pub fn load_bytes<const N: usize>(&self, index: usize) -> Result<[u8; N], RuntimeError> {
         let lock_guard = self.inner_data.read();
@@ -237,6 +479,41 @@ impl<const PAGE_SIZE: usize> LinearMemory<PAGE_SIZE> {
         Ok(bytes)
     }
 
+    /// From a given index, load `buffer.len()` bytes from the [`LinearMemory`] into `buffer`
+    pub fn load_into(&self, index: usize, buffer: &mut [u8]) -> Result<(), RuntimeError> {
+        let lock_guard = self.inner_data.read();
+        let count = buffer.len();
+
+        /* check source for out of bounds access */
+        if count > lock_guard.len() {
+            error!("buffer is bigger than the linear memory");
+            return Err(TrapError::MemoryOrDataAccessOutOfBounds.into());
+        }
+
+        // The subtraction can not underflow because of the previous check.
+        if index > lock_guard.len() - count {
+            error!("buffer read would extend beyond the end of the linear memory");
+            return Err(TrapError::MemoryOrDataAccessOutOfBounds.into());
+        }
+
+        /* do the load */
+        for (i, byte) in buffer.iter_mut().enumerate() {
+            // SAFETY:
+            // The safety of this `unsafe` block depends on the index being valid, which it is
+            // because:
+            //
+            // - the first if statement in this function guarantees that `count` bytes can fit
+            //   into the `LinearMemory` `&self`
+            // - the second if statement in this function guarantees that even with the offset
+            //   `index`, reading all `count` bytes does not extend beyond the last byte in
+            //   the `LinearMemory` `&self`
+            let src = unsafe { lock_guard.get_unchecked(i + index) };
+            *byte = src.load(Ordering::Relaxed);
+        }
+
+        Ok(())
+    }
+
     /// Implementation of the behavior described in
     /// <https://webassembly.github.io/spec/core/exec/instructions.html#xref-syntax-instructions-syntax-instr-memory-mathsf-memory-fill>.
     /// Note, that the WASM spec defines the behavior by recursion, while our implementation uses
@@ -244,6 +521,7 @@ impl<const PAGE_SIZE: usize> LinearMemory<PAGE_SIZE> {
     ///
     /// <https://webassembly.github.io/spec/core/exec/instructions.html#xref-syntax-instructions-syntax-instr-memory-mathsf-memory-fill>
     pub fn fill(&self, index: usize, data_byte: u8, count: usize) -> Result<(), RuntimeError> {
+        self.allocate_for_write(index, count)?;
         let lock_guard = self.inner_data.read();
 
         /* check destination for out of bounds access */
@@ -277,6 +555,8 @@ impl<const PAGE_SIZE: usize> LinearMemory<PAGE_SIZE> {
             // - the second if statement in this function guarantees that even with the offset
             //   `index`, writing all `count`'s bytes does not extend beyond the last byte in
             //   the `LinearMemory` `&self`
+            //
+            // The chunks of sparse memories were allocated at the beginning of this function.
             let lin_mem_byte = unsafe { lock_guard.get_unchecked(i) };
             lin_mem_byte.store(data_byte, Ordering::Relaxed);
         }
@@ -299,6 +579,9 @@ impl<const PAGE_SIZE: usize> LinearMemory<PAGE_SIZE> {
         source_index: usize,
         count: usize,
     ) -> Result<(), RuntimeError> {
+        // this has to happen before any lock is held, as the source may be the same memory as the destination
+        self.allocate_for_write(destination_index, count)?;
+
         // self is the destination
         let lock_guard_self = self.inner_data.read();
 
@@ -358,6 +641,8 @@ impl<const PAGE_SIZE: usize> LinearMemory<PAGE_SIZE> {
             // - the fourth if statement in this function guarantees that even with the offset
             //   `destination_index`, writing all `count`'s bytes does not extend beyond the last byte in
             //   the `LinearMemory` `&self`
+            //
+            // The chunks of sparse memories were allocated at the beginning of this function.
             let dst_byte: &AtomicU8 =
                 unsafe { lock_guard_self.get_unchecked(i + destination_index) };
 
@@ -394,6 +679,8 @@ impl<const PAGE_SIZE: usize> LinearMemory<PAGE_SIZE> {
         source_index: usize,
         count: usize,
     ) -> Result<(), RuntimeError> {
+        self.allocate_for_write(destination_index, count)?;
+
         // self is the destination
         let lock_guard_self = self.inner_data.read();
         let data_len = source_data.len();
@@ -452,6 +739,8 @@ impl<const PAGE_SIZE: usize> LinearMemory<PAGE_SIZE> {
             // - the fourth if statement in this function guarantees that even with the offset
             //   `destination_index`, writing all `count`'s bytes does not extend beyond the last byte in
             //   the `LinearMemory` `&self`
+            //
+            // The chunks of sparse memories were allocated at the beginning of this function.
             let dst_byte = unsafe { lock_guard_self.get_unchecked(i + destination_index) };
             dst_byte.store(*src_byte, Ordering::Relaxed);
         }
@@ -467,27 +756,209 @@ impl<const PAGE_SIZE: usize> LinearMemory<PAGE_SIZE> {
     /// This operation exclusively locks the entire linear memory for the
     /// duration of this function call. To acquire the lock, this function may
     /// also block until the lock is available.
-    pub fn access_mut_slice<R>(&self, accessor: impl FnOnce(&mut [u8]) -> R) -> R {
-        /// Converts an exclusively borrowed slice of atomic `u8`s to a slice of
-        /// non-atomic `u8`s
-        // TODO when `atomic_from_mut` is stabilized, replace this function with
-        // `Atomic::U8::get_mut_slice`
-        fn atomic_u8_get_mut_slice(slice: &mut [AtomicU8]) -> &mut [u8] {
-            // SAFETY: the mutable reference guarantees unique ownership
-            unsafe { &mut *(slice as *mut [AtomicU8] as *mut [u8]) }
+    ///
+    /// # Note on sparse memories
+    ///
+    /// The chunks of a sparse linear memory are not contiguous. Therefore, the
+    /// closure accesses a copy of the entire memory, whose changes are written
+    /// back afterwards. Only chunks containing non-zero bytes are allocated.
+    /// If the copy or a chunk cannot be allocated,
+    /// [`RuntimeError::OutOfMemory`] is returned and the changes of the closure
+    /// are discarded.
+    pub fn access_mut_slice<R>(
+        &self,
+        accessor: impl FnOnce(&mut [u8]) -> R,
+    ) -> Result<R, RuntimeError> {
+        let mut write_lock_guard = self.inner_data.write();
+        let data = &mut *write_lock_guard;
+        if let Some(bytes) = data.contiguous_bytes_mut() {
+            return Ok(accessor(bytes));
+        }
+        let MemoryData::Sparse { chunks, len } = data else {
+            unreachable!("only sparse memories are not contiguous")
+        };
+        let mut bytes = Vec::new();
+        bytes.try_resize_with(*len, || 0)?;
+        for (chunk, copy) in chunks.iter_mut().zip(bytes.chunks_mut(SPARSE_CHUNK_SIZE)) {
+            if let Some(chunk) = chunk {
+                copy.copy_from_slice(&atomic_u8_get_mut_slice(chunk)[..copy.len()]);
+            }
         }
 
-        let mut write_lock_guard = self.inner_data.write();
-        let non_atomic_slice = atomic_u8_get_mut_slice(&mut write_lock_guard);
-        accessor(non_atomic_slice)
+        let result = accessor(&mut bytes);
+
+        data.overwrite(&bytes)?;
+        Ok(result)
+    }
+
+    /// Returns the number of bytes allocated for this [`LinearMemory`]. For a sparse memory, these are the bytes of
+    /// its allocated chunks, otherwise all bytes of the memory.
+    pub fn allocated_len(&self) -> usize {
+        self.inner_data.read().allocated_len()
     }
 }
 
-impl<const PAGE_SIZE: usize> core::fmt::Debug for LinearMemory<PAGE_SIZE> {
+/// Accesses through an exclusive borrow
+///
+/// When a [`LinearMemory`] is borrowed exclusively, no other access can happen concurrently. Therefore, these methods
+/// neither lock nor go through [`AtomicU8`], but operate on plain bytes instead. This allows the compiler to turn
+/// loads and stores into single word-sized accesses and bulk operations into `memcpy` and `memset`. The interpreter
+/// uses these methods, as it has exclusive access to the [`Store`](crate::Store) and thus to all memories.
+///
+/// Sparse memories are not contiguous, so their accesses fall back to the shared, atomic accessors.
+impl LinearMemory {
+    /// Checks that the `count` bytes starting at `index` lie within this [`LinearMemory`], without accessing them
+    #[inline(always)]
+    pub fn check_bounds_exclusive(
+        &mut self,
+        index: usize,
+        count: usize,
+    ) -> Result<(), RuntimeError> {
+        checked_range(self.inner_data.get_mut().len(), index, count).map(|_| ())
+    }
+
+    /// Like [`LinearMemory::load_bytes`], but with an exclusive borrow
+    #[inline(always)]
+    pub fn load_bytes_exclusive<const N: usize>(
+        &mut self,
+        index: usize,
+    ) -> Result<[u8; N], RuntimeError> {
+        let Some(bytes) = self.inner_data.get_mut().contiguous_bytes_mut() else {
+            return self.load_bytes(index);
+        };
+        bytes
+            .get(index..)
+            .and_then(<[u8]>::first_chunk::<N>)
+            .copied()
+            .ok_or(TrapError::MemoryOrDataAccessOutOfBounds.into())
+    }
+
+    /// Like [`LinearMemory::load_into`], but with an exclusive borrow
+    pub fn load_into_exclusive(
+        &mut self,
+        index: usize,
+        buffer: &mut [u8],
+    ) -> Result<(), RuntimeError> {
+        let Some(bytes) = self.inner_data.get_mut().contiguous_bytes_mut() else {
+            return self.load_into(index, buffer);
+        };
+        let range = checked_range(bytes.len(), index, buffer.len())?;
+        buffer.copy_from_slice(&bytes[range]);
+        Ok(())
+    }
+
+    /// Like [`LinearMemory::store_bytes`], but with an exclusive borrow
+    #[inline(always)]
+    pub fn store_bytes_exclusive<const N: usize>(
+        &mut self,
+        index: usize,
+        bytes: [u8; N],
+    ) -> Result<(), RuntimeError> {
+        let Some(data) = self.inner_data.get_mut().contiguous_bytes_mut() else {
+            return self.store_bytes(index, bytes);
+        };
+        let dst = data
+            .get_mut(index..)
+            .and_then(<[u8]>::first_chunk_mut::<N>)
+            .ok_or(TrapError::MemoryOrDataAccessOutOfBounds)?;
+        *dst = bytes;
+        Ok(())
+    }
+
+    /// Like [`LinearMemory::fill`], but with an exclusive borrow
+    pub fn fill_exclusive(
+        &mut self,
+        index: usize,
+        data_byte: u8,
+        count: usize,
+    ) -> Result<(), RuntimeError> {
+        let Some(bytes) = self.inner_data.get_mut().contiguous_bytes_mut() else {
+            return self.fill(index, data_byte, count);
+        };
+        let range = checked_range(bytes.len(), index, count)?;
+        bytes[range].fill(data_byte);
+        Ok(())
+    }
+
+    /// Like [`LinearMemory::copy`] with the same memory as source and destination, but with an exclusive borrow
+    pub fn copy_within_exclusive(
+        &mut self,
+        destination_index: usize,
+        source_index: usize,
+        count: usize,
+    ) -> Result<(), RuntimeError> {
+        let Some(bytes) = self.inner_data.get_mut().contiguous_bytes_mut() else {
+            return self.copy(destination_index, self, source_index, count);
+        };
+        let source = checked_range(bytes.len(), source_index, count)?;
+        checked_range(bytes.len(), destination_index, count)?;
+        // handles overlapping ranges just like `memmove`
+        bytes.copy_within(source, destination_index);
+        Ok(())
+    }
+
+    /// Like [`LinearMemory::copy`] with two different memories, but with exclusive borrows
+    pub fn copy_exclusive(
+        &mut self,
+        destination_index: usize,
+        source_mem: &mut Self,
+        source_index: usize,
+        count: usize,
+    ) -> Result<(), RuntimeError> {
+        let (Some(destination), Some(source)) = (
+            self.inner_data.get_mut().contiguous_bytes_mut(),
+            source_mem.inner_data.get_mut().contiguous_bytes_mut(),
+        ) else {
+            return self.copy(destination_index, source_mem, source_index, count);
+        };
+        let source_range = checked_range(source.len(), source_index, count)?;
+        let destination_range = checked_range(destination.len(), destination_index, count)?;
+        destination[destination_range].copy_from_slice(&source[source_range]);
+        Ok(())
+    }
+
+    /// Overwrites the whole [`LinearMemory`] with `bytes`, which must have the same length as the memory, e.g. to
+    /// restore it from a snapshot. Chunks of a sparse memory which would only receive zeros stay unallocated.
+    ///
+    /// Returns [`RuntimeError::OutOfMemory`] if a chunk cannot be allocated, in which case no byte is written.
+    pub fn overwrite_exclusive(&mut self, bytes: &[u8]) -> Result<(), RuntimeError> {
+        self.inner_data.get_mut().overwrite(bytes)
+    }
+
+    /// Like [`LinearMemory::init`], but with an exclusive borrow
+    pub fn init_exclusive(
+        &mut self,
+        destination_index: usize,
+        source_data: &[u8],
+        source_index: usize,
+        count: usize,
+    ) -> Result<(), RuntimeError> {
+        let Some(bytes) = self.inner_data.get_mut().contiguous_bytes_mut() else {
+            return self.init(destination_index, source_data, source_index, count);
+        };
+        let source_range = checked_range(source_data.len(), source_index, count)?;
+        let destination_range = checked_range(bytes.len(), destination_index, count)?;
+        bytes[destination_range].copy_from_slice(&source_data[source_range]);
+        Ok(())
+    }
+}
+
+/// Returns the range of the `count` bytes starting at `index`, if they lie within the first `len` bytes
+///
+/// The checks are ordered to avoid overflows, see the notes on overflowing of [`LinearMemory`].
+#[inline(always)]
+fn checked_range(len: usize, index: usize, count: usize) -> Result<Range<usize>, RuntimeError> {
+    if count > len || index > len - count {
+        return Err(TrapError::MemoryOrDataAccessOutOfBounds.into());
+    }
+    Ok(index..index + count)
+}
+
+impl core::fmt::Debug for LinearMemory {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        /// A helper struct for formatting a [`Vec<UnsafeCell<u8>>`] which is guarded by a [`ReadLockGuard`].
+        /// A helper struct for formatting a [`MemoryData`] which is guarded by a [`ReadLockGuard`].
         /// This formatter is able to detect and format byte repetitions in a compact way.
-        struct RepetitionDetectingMemoryWriter<'a>(ReadLockGuard<'a, Vec<AtomicU8>>);
+        struct RepetitionDetectingMemoryWriter<'a>(ReadLockGuard<'a, MemoryData>);
         impl core::fmt::Debug for RepetitionDetectingMemoryWriter<'_> {
             fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
                 /// The number of repetitions required for successive elements to be grouped
@@ -495,7 +966,10 @@ impl<const PAGE_SIZE: usize> core::fmt::Debug for LinearMemory<PAGE_SIZE> {
                 const MIN_REPETITIONS_FOR_GROUP: usize = 8;
 
                 // First we create an iterator over all bytes
-                let mut bytes = self.0.iter().map(|x| x.load(Ordering::Relaxed));
+                let mut bytes = (0..self.0.len()).map(|i| {
+                    // SAFETY: The index is smaller than the length of the memory data, and the byte is only read.
+                    unsafe { self.0.get_unchecked(i) }.load(Ordering::Relaxed)
+                });
 
                 // Then we iterate over all bytes and deduplicate repetitions. This produces an
                 // iterator of pairs, consisting of the number of repetitions and the repeated byte
@@ -543,12 +1017,21 @@ impl<const PAGE_SIZE: usize> core::fmt::Debug for LinearMemory<PAGE_SIZE> {
     }
 }
 
-impl<const PAGE_SIZE: usize> Default for LinearMemory<PAGE_SIZE> {
+impl Default for LinearMemory {
     fn default() -> Self {
-        Self::new()
+        Self::new(crate::Limits::MEM_PAGE_SIZE as usize)
     }
 }
 
+/// Computes the number of bytes of `pages` pages of `page_size` bytes, which may not be addressable on 16-bit or 32-bit
+/// targets
+fn pages_to_bytes(page_size: usize, pages: PageCountTy) -> Result<usize, RuntimeError> {
+    usize::try_from(pages)
+        .ok()
+        .and_then(|pages| pages.checked_mul(page_size))
+        .ok_or(RuntimeError::OutOfMemory)
+}
+
 #[cfg(test)]
 mod test {
     use core::f64;
@@ -556,6 +1039,7 @@ mod test {
     use alloc::format;
     use core::mem;
 
+    use crate::execution::little_endian::LittleEndianBytes;
     use crate::value::{F32, F64};
 
     use super::*;
@@ -563,22 +1047,42 @@ mod test {
     const PAGE_SIZE: usize = 1 << 8;
     const PAGES: PageCountTy = 2;
 
+    /// Typed accesses, which only the tests use, as the interpreter and the store access bytes
+    impl LinearMemory {
+        /// At a given index, store a datum in the [`LinearMemory`]
+        fn store<const N: usize, T: LittleEndianBytes<N>>(
+            &self,
+            index: usize,
+            value: T,
+        ) -> Result<(), RuntimeError> {
+            self.store_bytes::<N>(index, value.to_le_bytes())
+        }
+
+        /// From a given index, load a datum from the [`LinearMemory`]
+        fn load<const N: usize, T: LittleEndianBytes<N>>(
+            &self,
+            index: usize,
+        ) -> Result<T, RuntimeError> {
+            self.load_bytes::<N>(index).map(T::from_le_bytes)
+        }
+    }
+
     #[test]
     fn new_constructor() {
-        let lin_mem = LinearMemory::<PAGE_SIZE>::new();
+        let lin_mem = LinearMemory::new(PAGE_SIZE);
         assert_eq!(lin_mem.pages(), 0);
     }
 
     #[test]
     fn new_grow() {
-        let lin_mem = LinearMemory::<PAGE_SIZE>::new();
-        lin_mem.grow(1);
+        let lin_mem = LinearMemory::new(PAGE_SIZE);
+        lin_mem.grow(1).unwrap();
         assert_eq!(lin_mem.pages(), 1);
     }
 
     #[test]
     fn debug_print_simple() {
-        let lin_mem = LinearMemory::<PAGE_SIZE>::new_with_initial_pages(1);
+        let lin_mem = LinearMemory::new_with_initial_pages(PAGE_SIZE, 1).unwrap();
         assert_eq!(lin_mem.pages(), 1);
 
         let expected = format!("LinearMemory {{ inner_data: [#{PAGE_SIZE} × 0] }}");
@@ -590,7 +1094,7 @@ mod test {
     #[test]
     fn debug_print_complex() {
         let page_count = 2;
-        let lin_mem = LinearMemory::<PAGE_SIZE>::new_with_initial_pages(page_count);
+        let lin_mem = LinearMemory::new_with_initial_pages(PAGE_SIZE, page_count).unwrap();
         assert_eq!(lin_mem.pages(), page_count);
 
         lin_mem.store(1, 0xffu8).unwrap();
@@ -605,7 +1109,7 @@ mod test {
 
     #[test]
     fn debug_print_empty() {
-        let lin_mem = LinearMemory::<PAGE_SIZE>::new_with_initial_pages(0);
+        let lin_mem = LinearMemory::new_with_initial_pages(PAGE_SIZE, 0).unwrap();
         assert_eq!(lin_mem.pages(), 0);
 
         let expected = "LinearMemory { inner_data: [] }";
@@ -619,7 +1123,7 @@ mod test {
         let x: i8 = -127;
         let highest_legal_offset = PAGE_SIZE - mem::size_of::<i8>();
         for offset in 0..highest_legal_offset {
-            let lin_mem = LinearMemory::<PAGE_SIZE>::new_with_initial_pages(PAGES);
+            let lin_mem = LinearMemory::new_with_initial_pages(PAGE_SIZE, PAGES).unwrap();
 
             lin_mem.store(offset, x).unwrap();
 
@@ -638,7 +1142,7 @@ mod test {
         let x = F32(13.0);
         let highest_legal_offset = PAGE_SIZE - mem::size_of::<F32>();
         for offset in 0..highest_legal_offset {
-            let lin_mem = LinearMemory::<PAGE_SIZE>::new_with_initial_pages(PAGES);
+            let lin_mem = LinearMemory::new_with_initial_pages(PAGE_SIZE, PAGES).unwrap();
 
             lin_mem.store(offset, x).unwrap();
 
@@ -657,7 +1161,7 @@ mod test {
         let x = F64(f64::MIN);
         let highest_legal_offset = PAGE_SIZE - mem::size_of::<F64>();
         for offset in 0..highest_legal_offset {
-            let lin_mem = LinearMemory::<PAGE_SIZE>::new_with_initial_pages(PAGES);
+            let lin_mem = LinearMemory::new_with_initial_pages(PAGE_SIZE, PAGES).unwrap();
 
             lin_mem.store(offset, x).unwrap();
 
@@ -676,7 +1180,7 @@ mod test {
         let x = F64(f64::NAN);
         let highest_legal_offset = PAGE_SIZE - mem::size_of::<f64>();
         for offset in 0..highest_legal_offset {
-            let lin_mem = LinearMemory::<PAGE_SIZE>::new_with_initial_pages(PAGES);
+            let lin_mem = LinearMemory::new_with_initial_pages(PAGE_SIZE, PAGES).unwrap();
 
             lin_mem.store(offset, x).unwrap();
 
@@ -698,7 +1202,7 @@ mod test {
         let x: u128 = u128::MAX;
         let pages = 1;
         let lowest_illegal_offset = PAGE_SIZE - mem::size_of::<u128>() + 1;
-        let lin_mem = LinearMemory::<PAGE_SIZE>::new_with_initial_pages(pages);
+        let lin_mem = LinearMemory::new_with_initial_pages(PAGE_SIZE, pages).unwrap();
 
         lin_mem.store(lowest_illegal_offset, x).unwrap();
     }
@@ -711,7 +1215,7 @@ mod test {
         let x: u8 = u8::MAX;
         let pages = 0;
         let lowest_illegal_offset = PAGE_SIZE - mem::size_of::<u8>() + 1;
-        let lin_mem = LinearMemory::<PAGE_SIZE>::new_with_initial_pages(pages);
+        let lin_mem = LinearMemory::new_with_initial_pages(PAGE_SIZE, pages).unwrap();
 
         lin_mem.store(lowest_illegal_offset, x).unwrap();
     }
@@ -723,7 +1227,7 @@ mod test {
     fn load_out_of_range_u128_max() {
         let pages = 1;
         let lowest_illegal_offset = PAGE_SIZE - mem::size_of::<u128>() + 1;
-        let lin_mem = LinearMemory::<PAGE_SIZE>::new_with_initial_pages(pages);
+        let lin_mem = LinearMemory::new_with_initial_pages(PAGE_SIZE, pages).unwrap();
 
         let _x: u128 = lin_mem.load(lowest_illegal_offset).unwrap();
     }
@@ -735,7 +1239,7 @@ mod test {
     fn load_empty_lineaer_memory_u8() {
         let pages = 0;
         let lowest_illegal_offset = PAGE_SIZE - mem::size_of::<u8>() + 1;
-        let lin_mem = LinearMemory::<PAGE_SIZE>::new_with_initial_pages(pages);
+        let lin_mem = LinearMemory::new_with_initial_pages(PAGE_SIZE, pages).unwrap();
 
         let _x: u8 = lin_mem.load(lowest_illegal_offset).unwrap();
     }
@@ -743,8 +1247,144 @@ mod test {
     #[test]
     #[should_panic]
     fn copy_out_of_bounds() {
-        let lin_mem_0 = LinearMemory::<PAGE_SIZE>::new_with_initial_pages(2);
-        let lin_mem_1 = LinearMemory::<PAGE_SIZE>::new_with_initial_pages(1);
+        let lin_mem_0 = LinearMemory::new_with_initial_pages(PAGE_SIZE, 2).unwrap();
+        let lin_mem_1 = LinearMemory::new_with_initial_pages(PAGE_SIZE, 1).unwrap();
         lin_mem_0.copy(0, &lin_mem_1, 0, PAGE_SIZE + 1).unwrap();
     }
+
+    /// Returns the number of allocated chunks of a sparse linear memory
+    fn allocated_chunks(lin_mem: &LinearMemory) -> usize {
+        match &*lin_mem.inner_data.read() {
+            MemoryData::Sparse { chunks, .. } => chunks.iter().flatten().count(),
+            _ => panic!("expected a sparse linear memory"),
+        }
+    }
+
+    #[test]
+    fn sparse_allocates_chunks_on_write() {
+        let pages_per_chunk = SPARSE_CHUNK_SIZE / PAGE_SIZE;
+        let lin_mem = LinearMemory::new_sparse(PAGE_SIZE, 4 * pages_per_chunk as u32).unwrap();
+        assert_eq!(lin_mem.len(), 4 * SPARSE_CHUNK_SIZE);
+
+        let x: u64 = lin_mem.load(SPARSE_CHUNK_SIZE + 8).unwrap();
+        assert_eq!(x, 0);
+        assert_eq!(allocated_chunks(&lin_mem), 0);
+
+        // a store spanning two chunks allocates both
+        lin_mem.store(SPARSE_CHUNK_SIZE - 2, u32::MAX).unwrap();
+        assert_eq!(allocated_chunks(&lin_mem), 2);
+        assert_eq!(lin_mem.load::<4, u32>(SPARSE_CHUNK_SIZE - 2), Ok(u32::MAX));
+
+        // out-of-bounds and empty writes do not allocate anything
+        lin_mem.store(4 * SPARSE_CHUNK_SIZE - 2, 0u32).unwrap_err();
+        lin_mem.fill(3 * SPARSE_CHUNK_SIZE, 1, 0).unwrap();
+        assert_eq!(allocated_chunks(&lin_mem), 2);
+
+        // copying within the same memory reads zeros from unallocated chunks
+        lin_mem
+            .copy(3 * SPARSE_CHUNK_SIZE, &lin_mem, SPARSE_CHUNK_SIZE - 4, 8)
+            .unwrap();
+        assert_eq!(allocated_chunks(&lin_mem), 3);
+        assert_eq!(
+            lin_mem.load::<8, u64>(3 * SPARSE_CHUNK_SIZE),
+            Ok(0x0000_FFFF_FFFF_0000)
+        );
+
+        // growing zeroes the new bytes of the partially used last chunk
+        lin_mem.grow(1).unwrap();
+        assert_eq!(allocated_chunks(&lin_mem), 3);
+        assert_eq!(lin_mem.load::<1, u8>(4 * SPARSE_CHUNK_SIZE), Ok(0));
+    }
+
+    #[test]
+    fn sparse_access_mut_slice() {
+        let lin_mem = LinearMemory::new_sparse(PAGE_SIZE, 40).unwrap();
+        lin_mem.store(4, 1u8).unwrap();
+
+        lin_mem
+            .access_mut_slice(|bytes| {
+                assert_eq!(bytes.len(), 40 * PAGE_SIZE);
+                assert_eq!(bytes[4], 1);
+                bytes[4] = 2;
+                bytes[2 * SPARSE_CHUNK_SIZE + 1] = 3;
+            })
+            .unwrap();
+
+        // only chunks containing non-zero bytes are allocated
+        assert_eq!(allocated_chunks(&lin_mem), 2);
+        assert_eq!(lin_mem.load::<1, u8>(4), Ok(2));
+        assert_eq!(lin_mem.load::<1, u8>(2 * SPARSE_CHUNK_SIZE + 1), Ok(3));
+    }
+
+    #[test]
+    fn exclusive_accesses_match_shared_ones() {
+        let mut lin_mem = LinearMemory::new_with_initial_pages(PAGE_SIZE, PAGES).unwrap();
+        let end = PAGE_SIZE * PAGES as usize;
+
+        lin_mem
+            .store_bytes_exclusive(8, 0x0102_0304_0506_0708u64.to_le_bytes())
+            .unwrap();
+        assert_eq!(lin_mem.load::<8, u64>(8), Ok(0x0102_0304_0506_0708));
+        assert_eq!(lin_mem.load_bytes_exclusive::<2>(8), Ok([8, 7]));
+        assert_eq!(
+            lin_mem.store_bytes_exclusive(end - 3, [0; 4]),
+            Err(TrapError::MemoryOrDataAccessOutOfBounds.into())
+        );
+        assert_eq!(
+            lin_mem.load_bytes_exclusive::<4>(usize::MAX),
+            Err(TrapError::MemoryOrDataAccessOutOfBounds.into())
+        );
+
+        // overlapping copies in both directions
+        lin_mem.copy_within_exclusive(10, 8, 8).unwrap();
+        assert_eq!(
+            lin_mem.load_bytes_exclusive::<10>(8),
+            Ok([8, 7, 8, 7, 6, 5, 4, 3, 2, 1])
+        );
+        lin_mem.copy_within_exclusive(8, 10, 8).unwrap();
+        assert_eq!(
+            lin_mem.load_bytes_exclusive::<10>(8),
+            Ok([8, 7, 6, 5, 4, 3, 2, 1, 2, 1])
+        );
+
+        lin_mem.fill_exclusive(end - 2, 0xAA, 2).unwrap();
+        assert_eq!(lin_mem.load::<2, u16>(end - 2), Ok(0xAAAA));
+        // empty accesses at the end are in bounds, but not beyond it
+        lin_mem.fill_exclusive(end, 0, 0).unwrap();
+        assert_eq!(
+            lin_mem.fill_exclusive(end + 1, 0, 0),
+            Err(TrapError::MemoryOrDataAccessOutOfBounds.into())
+        );
+
+        lin_mem.init_exclusive(0, b"data", 1, 3).unwrap();
+        assert_eq!(lin_mem.load_bytes_exclusive::<3>(0), Ok(*b"ata"));
+        assert_eq!(
+            lin_mem.init_exclusive(0, b"data", 2, 3),
+            Err(TrapError::MemoryOrDataAccessOutOfBounds.into())
+        );
+
+        let mut other = LinearMemory::new_with_initial_pages(PAGE_SIZE, 1).unwrap();
+        other.copy_exclusive(0, &mut lin_mem, 0, 3).unwrap();
+        assert_eq!(other.load_bytes_exclusive::<3>(0), Ok(*b"ata"));
+        assert_eq!(
+            other.copy_exclusive(0, &mut lin_mem, 0, PAGE_SIZE + 1),
+            Err(TrapError::MemoryOrDataAccessOutOfBounds.into())
+        );
+    }
+
+    #[test]
+    fn exclusive_accesses_on_sparse_memories() {
+        let mut lin_mem = LinearMemory::new_sparse(PAGE_SIZE, 64).unwrap();
+        lin_mem
+            .store_bytes_exclusive(SPARSE_CHUNK_SIZE, [1, 2])
+            .unwrap();
+        lin_mem
+            .copy_within_exclusive(2 * SPARSE_CHUNK_SIZE, SPARSE_CHUNK_SIZE, 2)
+            .unwrap();
+        assert_eq!(
+            lin_mem.load_bytes_exclusive::<2>(2 * SPARSE_CHUNK_SIZE),
+            Ok([1, 2])
+        );
+        assert_eq!(allocated_chunks(&lin_mem), 2);
+    }
 }
@@ -0,0 +1,59 @@
+//! Caller-provided storage for linear memories
+//!
+//! By default, every linear memory owns a heap allocation, which is reallocated when the memory grows. On targets
+//! without a heap, or when Wasm memory has to be placed in a specific region such as a dedicated SRAM bank, a static
+//! buffer or a shared-memory segment, a [`MemoryBacking`] can be passed to
+//! [`Store::mem_alloc_with_backing`](crate::Store::mem_alloc_with_backing) instead. The memory then lives inside the
+//! given storage and can grow up to its capacity, after which `memory.grow` fails by returning -1.
+//!
+//! Memories defined by a module are always heap-allocated. To run a module in caller-provided storage, allocate the
+//! memory with a backing and provide it as an import during instantiation.
+
+use core::sync::atomic::AtomicU8;
+
+/// Caller-owned storage with a fixed capacity for a linear memory
+///
+/// All accesses to the storage go through [`AtomicU8`], so the bounds checking and atomicity guarantees of linear
+/// memories are the same as for heap-allocated ones.
+#[derive(Debug)]
+pub struct MemoryBacking {
+    bytes: &'static mut [AtomicU8],
+}
+
+impl MemoryBacking {
+    /// Creates a backing from an exclusively borrowed buffer, e.g. one obtained from
+    /// [`Box::leak`](alloc::boxed::Box::leak) or from a `static` cell that hands out its buffer once
+    pub fn new(bytes: &'static mut [u8]) -> Self {
+        // SAFETY: `AtomicU8` has the same size, alignment and bit validity as `u8`. The exclusive borrow guarantees
+        // that no other references to these bytes exist for the rest of the program.
+        let bytes = unsafe { &mut *(bytes as *mut [u8] as *mut [AtomicU8]) };
+        Self { bytes }
+    }
+
+    /// Creates a backing from a raw memory region, e.g. an SRAM bank or a shared-memory segment
+    ///
+    /// # Safety
+    ///
+    /// The caller has to guarantee that `ptr` is non-null and valid for reads and writes of `len` bytes for the rest
+    /// of the program, and that the region is not accessed through any other pointer or reference while the backing
+    /// or the linear memory using it exists.
+    pub unsafe fn from_raw_parts(ptr: *mut u8, len: usize) -> Self {
+        // SAFETY: The caller guarantees that the region is valid and exclusively owned by this backing. `AtomicU8` has
+        // the same size, alignment and bit validity as `u8`.
+        let bytes = unsafe { core::slice::from_raw_parts_mut(ptr.cast::<AtomicU8>(), len) };
+        Self { bytes }
+    }
+
+    /// Returns the number of bytes of this backing
+    pub fn capacity(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub(crate) fn bytes(&self) -> &[AtomicU8] {
+        self.bytes
+    }
+
+    pub(crate) fn bytes_mut(&mut self) -> &mut [AtomicU8] {
+        self.bytes
+    }
+}
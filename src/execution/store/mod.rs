@@ -1,9 +1,12 @@
-use core::convert::Infallible;
+use core::num::NonZeroU64;
+use core::ops::Range;
 
 use crate::addrs::{
-    AddrVec, DataAddr, ElemAddr, FuncAddr, GlobalAddr, MemAddr, ModuleAddr, TableAddr,
+    Addr, AddrVec, DataAddr, ElemAddr, FuelPoolAddr, FuncAddr, GlobalAddr, MemAddr, ModuleAddr,
+    TableAddr,
 };
-use crate::config::Config;
+use crate::basic_blocks::{block_fuel_table, find_basic_blocks};
+use crate::config::{Config, GrowthDecision, ResourceUsage, StackLimits};
 use crate::core::indices::{ElemIdx, IdxVec, TypeIdx};
 use crate::core::reader::span::Span;
 use crate::core::reader::types::data::{DataModeActive, DataSegment};
@@ -11,23 +14,34 @@ use crate::core::reader::types::element::{ActiveElem, ElemItems, ElemMode, ElemT
 use crate::core::reader::types::export::ExportDesc;
 use crate::core::reader::types::global::GlobalType;
 use crate::core::reader::types::{ExternType, FuncType, ImportSubTypeRelation, MemType, TableType};
-use crate::core::reader::WasmReader;
+use crate::core::sidetable::BlockFuelTable;
 use crate::core::utils::ToUsizeExt;
-use crate::execution::interpreter_loop::{self, memory_init, table_init, InterpreterLoopOutcome};
+use crate::execution::fallible_alloc::{
+    try_to_vec, TryCollectExt, TryCollectResultsExt, TryVecExt,
+};
+use crate::execution::interpreter_loop::{
+    self, grow_memory, grow_table, memory_init, table_init, GrowthFailure, InterpreterLoopOutcome,
+};
+use crate::execution::little_endian::MemoryValue;
 use crate::execution::value::{Ref, Value};
 use crate::execution::{run_const_span, Stack};
-use crate::resumable::{HostCall, HostResumable, Resumable, RunState, WasmResumable};
-use crate::{RefType, RuntimeError, ValidationInfo};
+use crate::resumable::{
+    HostCall, HostResumable, InterruptReason, Resumable, ResumeError, RunState, WasmResumable,
+};
+use crate::validation::LocalFuncData;
+use crate::value_stack::StackStorage;
+use crate::{RefType, RuntimeError, TrapError, ValidationInfo};
 use alloc::borrow::ToOwned;
 use alloc::collections::btree_map::BTreeMap;
 use alloc::string::String;
-use alloc::vec;
+use alloc::sync::Arc;
 use alloc::vec::Vec;
 use instances::{
     DataInst, ElemInst, FuncInst, GlobalInst, HostFuncInst, MemInst, ModuleInst, TableInst,
     WasmFuncInst,
 };
 use linear_memory::LinearMemory;
+use memory_backing::MemoryBacking;
 
 use super::interpreter_loop::{data_drop, elem_drop};
 use super::UnwrapValidatedExt;
@@ -35,6 +49,7 @@ use super::UnwrapValidatedExt;
 pub mod addrs;
 pub(crate) mod instances;
 pub(crate) mod linear_memory;
+pub mod memory_backing;
 
 /// The store represents all global state that can be manipulated by WebAssembly programs. It
 /// consists of the runtime representation of all instances of functions, tables, memories, and
@@ -63,6 +78,26 @@ pub struct Store<'b, T: Config> {
     /// space along with a `ModuleAddr` index type.
     pub(crate) modules: AddrVec<ModuleAddr, ModuleInst<'b>>,
 
+    /// An address space of fuel pools, each holding the remaining fuel shared by all resumables drawing from it.
+    pub(crate) fuel_pools: AddrVec<FuelPoolAddr, u64>,
+
+    /// The resources allocated for all memories and tables of this [`Store`].
+    pub(crate) resource_usage: ResourceUsage,
+
+    /// The stack limits of invocations created by this [`Store`].
+    pub(crate) stack_limits: StackLimits,
+
+    /// Whether heap-allocated memories of this [`Store`] are sparse, see [`Store::set_sparse_memories`].
+    pub(crate) sparse_memories: bool,
+
+    /// The number of modules, in the order of their addresses, whose [`ModuleInst::block_fuel`] was computed.
+    pub(crate) modules_with_block_fuel: usize,
+
+    /// The fuel of the basic blocks of all modules, keyed by the address of the
+    /// [`SharedModuleData`](crate::validation::SharedModuleData) of their instances. The addresses are never reused,
+    /// because the module instances of this store keep the shared data alive.
+    pub(crate) block_fuel_tables: BTreeMap<usize, Arc<BlockFuelTable>>,
+
     pub user_data: T,
 }
 
@@ -81,6 +116,12 @@ impl<'b, T: Config> Store<'b, T> {
             elements: AddrVec::default(),
             data: AddrVec::default(),
             modules: AddrVec::default(),
+            fuel_pools: AddrVec::default(),
+            resource_usage: ResourceUsage::default(),
+            stack_limits: StackLimits::of::<T>(),
+            sparse_memories: false,
+            modules_with_block_fuel: 0,
+            block_fuel_tables: BTreeMap::new(),
             user_data,
         }
     }
@@ -136,7 +177,6 @@ impl<'b, T: Config> Store<'b, T> {
         // therefore I am mimicking the reference interpreter code here, I will allocate functions in the store in this step instead of step 11.
         // https://github.com/WebAssembly/spec/blob/8d6792e3d6709e8d3e90828f9c8468253287f7ed/interpreter/exec/eval.ml#L789
         let module_inst = ModuleInst {
-            types: validation_info.types.clone(),
             func_addrs: IdxVec::default(),
             table_addrs: IdxVec::default(),
             mem_addrs: IdxVec::default(),
@@ -144,7 +184,7 @@ impl<'b, T: Config> Store<'b, T> {
             // special `moduleinst_init`. Here, we want to use the `IdxVec` type
             // safety, but at the same time only the imports can be populated at
             // this point.
-            global_addrs: IdxVec::new(extern_vals.iter().globals().collect())
+            global_addrs: IdxVec::new(extern_vals.iter().globals().try_collect_vec()?)
                 .expect(
                     "that the number of imports and therefore also the number of imported globals is <= u32::MAX",
                 ),
@@ -152,26 +192,29 @@ impl<'b, T: Config> Store<'b, T> {
             data_addrs: IdxVec::default(),
             exports: BTreeMap::new(),
             wasm_bytecode: validation_info.wasm,
-            sidetable: validation_info.sidetable.clone(),
+            shared: Arc::clone(&validation_info.shared),
+            block_fuel: None,
         };
-        let module_addr = self.modules.insert(module_inst);
+        let module_addr = self.modules.insert(module_inst)?;
 
         let imported_functions = extern_vals.iter().funcs();
         let local_func_addrs = validation_info
             .functions
             .iter_local_definitions()
             .zip(validation_info.func_blocks_stps.iter())
-            .map(|(ty_idx, (span, stp))| {
+            .zip(validation_info.shared.local_funcs.iter())
+            .map(|((ty_idx, (_span, stp)), local_func)| {
                 // SAFETY: The module address is valid for the current store,
                 // because it was just created and the type index is valid for
                 // that same module because it came from that module's
                 // `ValidationInfo`.
-                unsafe { self.alloc_func((*ty_idx, (*span, *stp)), module_addr) }
-            });
+                unsafe { self.alloc_func((*ty_idx, *stp, local_func), module_addr) }
+            })
+            .try_collect_results()?;
 
         let func_addrs = validation_info
             .functions
-            .map(imported_functions.collect(), local_func_addrs.collect())
+            .map(imported_functions.try_collect_vec()?, local_func_addrs)
             .expect(
                 "that the numbers of imported and local functions always \
                 match the respective numbers in the validation info. Step 3 and 4 \
@@ -202,7 +245,7 @@ impl<'b, T: Config> Store<'b, T> {
                 };
                 const_expr_result.transpose().unwrap_validated()
             })
-            .collect::<Result<Vec<Value>, _>>()?;
+            .try_collect_results()?;
 
         // instantiation: this roughly matches step 9,10 and performs allocation
         // step 6,12 already
@@ -225,7 +268,7 @@ impl<'b, T: Config> Store<'b, T> {
 
                             Ref::Func(*func_addr)
                         })
-                        .collect()
+                        .try_collect_vec()?
                 }
                 ElemItems::Exprs(_, exprs) => exprs
                     .iter()
@@ -241,15 +284,14 @@ impl<'b, T: Config> Store<'b, T> {
                         const_expr_result
                             .map(|res| res.unwrap_validated().try_into().unwrap_validated())
                     })
-                    .collect::<Result<Vec<Ref>, RuntimeError>>()?,
+                    .try_collect_results()?,
             };
 
             // SAFETY: The initial values were retrieved by (1) resolving
             // function indices or (2) running constant expressions in the
             // context of the current store. Therefore, their results are also
             // valid in the current store.
-            let elem = unsafe { self.alloc_elem(elem.ty(), refs) };
-            Ok::<_, RuntimeError>(elem)
+            unsafe { self.alloc_elem(elem.ty(), refs) }
         })?;
 
         // instantiation: step 11 - module allocation (except function allocation - which was made in step 5)
@@ -271,13 +313,13 @@ impl<'b, T: Config> Store<'b, T> {
                 // address type and therefore not invalid in the current store.
                 unsafe { self.alloc_table(*table_type, Ref::Null(table_type.et)) }
             })
-            .collect();
+            .try_collect_results()?;
         // allocation: step 4, 10
         let mem_addrs_local: Vec<MemAddr> = module
             .memories
             .iter_local_definitions()
             .map(|mem_type| self.alloc_mem(*mem_type))
-            .collect();
+            .try_collect_results()?;
         // allocation: step 5, 11
         let global_addrs_local: Vec<GlobalAddr> = module
             .globals
@@ -289,21 +331,23 @@ impl<'b, T: Config> Store<'b, T> {
                 // their results are also valid in the current store.
                 unsafe { self.alloc_global(global.ty, val) }
             })
-            .collect();
+            .try_collect_results()?;
         // allocation: skip step 6, 12 as it was done in instantiation step 9, 10
 
         // allocation: step 7, 13
         let data_addrs = module
             .data
-            .map::<DataAddr, Infallible>(|data_segment| Ok(self.alloc_data(&data_segment.init)))
-            .expect("infallible error type to never be constructed");
+            .map(|data_segment| self.alloc_data(&data_segment.init))?;
 
         // allocation: skip step 14 as it was done in instantiation step 5
 
         // allocation: step 15
         let table_addrs = validation_info
             .tables
-            .map(extern_vals.iter().tables().collect(), table_addrs_local)
+            .map(
+                extern_vals.iter().tables().try_collect_vec()?,
+                table_addrs_local,
+            )
             .expect(
                 "that the numbers of imported and local tables always \
                 match the respective numbers in the validation info. Step 3 and 4 \
@@ -316,7 +360,10 @@ impl<'b, T: Config> Store<'b, T> {
         // allocation: step 16
         let mem_addrs = validation_info
             .memories
-            .map(extern_vals.iter().mems().collect(), mem_addrs_local)
+            .map(
+                extern_vals.iter().mems().try_collect_vec()?,
+                mem_addrs_local,
+            )
             .expect(
                 "that the number of imported and local memories always \
             match the respective numbers in the validation info. Step 3 and 4 \
@@ -329,7 +376,10 @@ impl<'b, T: Config> Store<'b, T> {
         // allocation step 17
         let global_addrs = validation_info
             .globals
-            .map(extern_vals.iter().globals().collect(), global_addrs_local)
+            .map(
+                extern_vals.iter().globals().try_collect_vec()?,
+                global_addrs_local,
+            )
             .expect(
                 "that the number of imported and local globals always \
             match the respective numbers in the validation info. Step 3 and 4 \
@@ -611,14 +661,24 @@ impl<'b, T: Config> Store<'b, T> {
 
             // SAFETY: The resumable just came from the current store.
             // Therefore, it is always valid in the current store.
-            match unsafe { self.resume_wasm(resumable) }? {
-                RunState::Finished {
-                    maybe_remaining_fuel,
-                    ..
-                } => maybe_remaining_fuel,
-                RunState::Resumable { .. } => return Err(RuntimeError::OutOfFuel),
-                RunState::HostCalled { .. } => {
-                    return Err(RuntimeError::UnsupportedHostCallDuringInstantiation);
+            let mut run_state = unsafe { self.resume_wasm(resumable) }?;
+            loop {
+                match run_state {
+                    RunState::Finished {
+                        maybe_remaining_fuel,
+                        ..
+                    } => break maybe_remaining_fuel,
+                    // Execution was only interrupted by a hook, so it is simply
+                    // continued.
+                    RunState::Interrupted { resumable, .. } => {
+                        // SAFETY: The resumable just came from the current
+                        // store. Therefore, it is valid in the current store.
+                        run_state = unsafe { self.resume_wasm(resumable) }?;
+                    }
+                    RunState::Resumable { .. } => return Err(RuntimeError::OutOfFuel),
+                    RunState::HostCalled { .. } => {
+                        return Err(RuntimeError::UnsupportedHostCallDuringInstantiation);
+                    }
                 }
             }
         } else {
@@ -676,7 +736,11 @@ impl<'b, T: Config> Store<'b, T> {
     ///
     /// See: <https://webassembly.github.io/spec/core/exec/modules.html#host-functions>
     /// See: WebAssembly Specification 2.0 - 7.1.7 - func_alloc
-    pub fn func_alloc(&mut self, func_type: FuncType, hostcode: Hostcode) -> FuncAddr {
+    pub fn func_alloc(
+        &mut self,
+        func_type: FuncType,
+        hostcode: Hostcode,
+    ) -> Result<FuncAddr, RuntimeError> {
         // 1. Pre-condition: `functype` is valid.
 
         // 2. Let `funcaddr` be the result of allocating a host function in `store` with
@@ -755,7 +819,7 @@ impl<'b, T: Config> Store<'b, T> {
         //    and initialization value `ref`.
         // SAFETY: The caller ensures that the reference is valid in the current
         // store.
-        let table_addr = unsafe { self.alloc_table(table_type, r#ref) };
+        let table_addr = unsafe { self.alloc_table(table_type, r#ref) }?;
 
         // 3. Return the new store paired with `tableaddr`.
         //
@@ -891,14 +955,28 @@ impl<'b, T: Config> Store<'b, T> {
         // Note: Returning the new store is a noop for us because we mutate the store instead.
         // SAFETY: The caller ensures that the given table address is valid in
         // the current store.
-        let table = unsafe { self.tables.get_mut(table_addr) };
-        table.grow(n, r#ref)
+        let result = unsafe {
+            grow_table(
+                &mut self.tables,
+                &mut self.user_data,
+                &mut self.resource_usage,
+                table_addr,
+                n,
+                r#ref,
+            )
+        };
+        result.map_err(|failure| match failure {
+            GrowthFailure::MaximumExceeded => TrapError::TableOrElementAccessOutOfBounds.into(),
+            GrowthFailure::Denied => RuntimeError::GrowthDenied,
+            GrowthFailure::OutOfMemory => RuntimeError::OutOfMemory,
+            GrowthFailure::Trap => TrapError::ResourceLimitExceeded.into(),
+        })
     }
 
     /// Allocates a new linear memory and returns its memory address.
     ///
     /// See: WebAssembly Specification 2.0 - 7.1.9 - mem_alloc
-    pub fn mem_alloc(&mut self, mem_type: MemType) -> MemAddr {
+    pub fn mem_alloc(&mut self, mem_type: MemType) -> Result<MemAddr, RuntimeError> {
         // 1. Pre-condition: `memtype` is valid.
 
         // 2. Let `memaddr` be the result of allocating a memory in `store` with memory type `memtype`.
@@ -908,6 +986,24 @@ impl<'b, T: Config> Store<'b, T> {
         self.alloc_mem(mem_type)
     }
 
+    /// Allocates a new linear memory like [`Store::mem_alloc`], which lives in the caller-provided `backing` instead
+    /// of a heap allocation. The memory can grow up to the capacity of the backing.
+    ///
+    /// Returns [`RuntimeError::MemoryBackingTooSmall`] if the backing cannot hold the initial pages of the memory.
+    pub fn mem_alloc_with_backing(
+        &mut self,
+        mem_type: MemType,
+        backing: MemoryBacking,
+    ) -> Result<MemAddr, RuntimeError> {
+        self.check_initial_memory(mem_type)?;
+        let mem = LinearMemory::new_in_backing(
+            backing,
+            mem_type.page_size().into_usize(),
+            mem_type.limits.min,
+        )?;
+        self.insert_mem(mem_type, mem)
+    }
+
     /// Gets the memory type of some memory by its memory address
     ///
     /// See: WebAssemblySpecification 2.0 - 7.1.9 - mem_type
@@ -928,28 +1024,37 @@ impl<'b, T: Config> Store<'b, T> {
 
     /// Reads a byte from some memory by its memory address and an index into the memory
     ///
+    /// Reading from a no-access range fails with [`TrapError::MemoryProtectionViolation`], see
+    /// [`Store::mem_protect`].
+    ///
     /// See: WebAssemblySpecification 2.0 - 7.1.9 - mem_read
     ///
     /// # Safety
     ///
     /// The caller has to guarantee that the given [`MemAddr`] came from the
     /// current [`Store`] object.
-    pub unsafe fn mem_read(&self, mem_addr: MemAddr, i: u32) -> Result<u8, RuntimeError> {
+    pub unsafe fn mem_read(&mut self, mem_addr: MemAddr, i: u32) -> Result<u8, RuntimeError> {
         // Convert the index type
         let i = i.into_usize();
 
         // 1. Let `mi` be the memory instance `store.mems[memaddr]`.
         // SAFETY: The caller ensures that the given memory address is valid in
         // the current store.
-        let mi = unsafe { self.memories.get(mem_addr) };
+        let mi = unsafe { self.memories.get_mut(mem_addr) };
 
         // 2. If `i` is larger than or equal to the length of `mi.data`, then return `error`.
+        mi.mem.check_bounds_exclusive(i, 1)?;
+        mi.check_read_protection(i, 1)?;
         // 3. Else, return the byte `mi.data[i]`.
-        mi.mem.load(i)
+        let [byte] = mi.mem.load_bytes_exclusive(i)?;
+        Ok(byte)
     }
 
     /// Writes a byte into some memory by its memory address and an index into the memory
     ///
+    /// Writing to a read-only or no-access range fails with [`TrapError::MemoryProtectionViolation`], see
+    /// [`Store::mem_protect`].
+    ///
     /// See: WebAssemblySpecification 2.0 - 7.1.9 - mem_write
     ///
     /// # Safety
@@ -957,7 +1062,7 @@ impl<'b, T: Config> Store<'b, T> {
     /// The caller has to guarantee that the given [`MemAddr`] came from the
     /// current [`Store`] object.
     pub unsafe fn mem_write(
-        &self,
+        &mut self,
         mem_addr: MemAddr,
         i: u32,
         byte: u8,
@@ -968,9 +1073,147 @@ impl<'b, T: Config> Store<'b, T> {
         // 1. Let `mi` be the memory instance `store.mems[memaddr]`.
         // SAFETY: The caller ensures that the given memory address is valid in
         // the current store.
-        let mi = unsafe { self.memories.get(mem_addr) };
+        let mi = unsafe { self.memories.get_mut(mem_addr) };
+
+        mi.mem.check_bounds_exclusive(i, 1)?;
+        mi.check_write_protection(i, 1)?;
+        mi.mem.store_bytes_exclusive(i, [byte])
+    }
+
+    /// Reads `buffer.len()` bytes starting at index `i` from some memory by its memory address into `buffer`
+    ///
+    /// Fails with [`TrapError::MemoryOrDataAccessOutOfBounds`] if the bytes do not lie within the memory and with
+    /// [`TrapError::MemoryProtectionViolation`] if they overlap a no-access range, in which case `buffer` is left
+    /// unchanged.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to guarantee that the given [`MemAddr`] came from the
+    /// current [`Store`] object.
+    pub unsafe fn mem_read_bytes(
+        &mut self,
+        mem_addr: MemAddr,
+        i: u32,
+        buffer: &mut [u8],
+    ) -> Result<(), RuntimeError> {
+        let i = i.into_usize();
+        // SAFETY: The caller ensures that the given memory address is valid in
+        // the current store.
+        let mi = unsafe { self.memories.get_mut(mem_addr) };
+
+        mi.mem.check_bounds_exclusive(i, buffer.len())?;
+        mi.check_read_protection(i, buffer.len())?;
+        mi.mem.load_into_exclusive(i, buffer)
+    }
+
+    /// Writes `bytes` starting at index `i` into some memory by its memory address
+    ///
+    /// Fails with [`TrapError::MemoryOrDataAccessOutOfBounds`] if the bytes do not fit into the memory and with
+    /// [`TrapError::MemoryProtectionViolation`] if they overlap a read-only or no-access range, in which case nothing
+    /// is written.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to guarantee that the given [`MemAddr`] came from the
+    /// current [`Store`] object.
+    pub unsafe fn mem_write_bytes(
+        &mut self,
+        mem_addr: MemAddr,
+        i: u32,
+        bytes: &[u8],
+    ) -> Result<(), RuntimeError> {
+        let i = i.into_usize();
+        // SAFETY: The caller ensures that the given memory address is valid in
+        // the current store.
+        let mi = unsafe { self.memories.get_mut(mem_addr) };
 
-        mi.mem.store(i, byte)
+        mi.mem.check_bounds_exclusive(i, bytes.len())?;
+        mi.check_write_protection(i, bytes.len())?;
+        // writing a byte slice into a memory is exactly what `memory.init` does with a data segment
+        mi.mem.init_exclusive(i, bytes, 0, bytes.len())
+    }
+
+    /// Reads a value of type `V` in little endian byte order starting at index `i` from some memory by its memory
+    /// address, e.g. an `i32`, `f64` or the bytes of a `v128` as `[u8; 16]`
+    ///
+    /// Fails like [`Store::mem_read_bytes`].
+    ///
+    /// # Safety
+    ///
+    /// The caller has to guarantee that the given [`MemAddr`] came from the
+    /// current [`Store`] object.
+    pub unsafe fn mem_read_typed<V: MemoryValue>(
+        &mut self,
+        mem_addr: MemAddr,
+        i: u32,
+    ) -> Result<V, RuntimeError> {
+        let mut bytes = V::Bytes::default();
+        // SAFETY: The caller ensures that the given memory address is valid in
+        // the current store.
+        unsafe { self.mem_read_bytes(mem_addr, i, bytes.as_mut()) }?;
+        Ok(V::from_le_bytes(bytes))
+    }
+
+    /// Writes a value of type `V` in little endian byte order starting at index `i` into some memory by its memory
+    /// address, e.g. an `i32`, `f64` or the bytes of a `v128` as `[u8; 16]`
+    ///
+    /// Fails like [`Store::mem_write_bytes`].
+    ///
+    /// # Safety
+    ///
+    /// The caller has to guarantee that the given [`MemAddr`] came from the
+    /// current [`Store`] object.
+    pub unsafe fn mem_write_typed<V: MemoryValue>(
+        &mut self,
+        mem_addr: MemAddr,
+        i: u32,
+        value: V,
+    ) -> Result<(), RuntimeError> {
+        // SAFETY: The caller ensures that the given memory address is valid in
+        // the current store.
+        unsafe { self.mem_write_bytes(mem_addr, i, value.to_le_bytes().as_ref()) }
+    }
+
+    /// Copies `n` bytes starting at index `src_i` of the memory at `src_addr` to index `dst_i` of the memory at
+    /// `dst_addr`, just like `memory.copy` does. Both memories may be the same and the ranges may overlap.
+    ///
+    /// Fails with [`TrapError::MemoryOrDataAccessOutOfBounds`] if either range does not lie within its memory and with
+    /// [`TrapError::MemoryProtectionViolation`] if the source overlaps a no-access range or the destination overlaps a
+    /// read-only or no-access range, in which case nothing is copied.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to guarantee that both given [`MemAddr`]s came from the
+    /// current [`Store`] object.
+    pub unsafe fn mem_copy(
+        &mut self,
+        dst_addr: MemAddr,
+        dst_i: u32,
+        src_addr: MemAddr,
+        src_i: u32,
+        n: u32,
+    ) -> Result<(), RuntimeError> {
+        let (dst_i, src_i, n) = (dst_i.into_usize(), src_i.into_usize(), n.into_usize());
+        // SAFETY: The caller ensures that both given memory addresses are valid
+        // in the current store.
+        match unsafe { self.memories.get_two_mut(dst_addr, src_addr) } {
+            Some((dst_mi, src_mi)) => {
+                src_mi.mem.check_bounds_exclusive(src_i, n)?;
+                dst_mi.mem.check_bounds_exclusive(dst_i, n)?;
+                src_mi.check_read_protection(src_i, n)?;
+                dst_mi.check_write_protection(dst_i, n)?;
+                dst_mi.mem.copy_exclusive(dst_i, &mut src_mi.mem, src_i, n)
+            }
+            None => {
+                // SAFETY: See above.
+                let mi = unsafe { self.memories.get_mut(dst_addr) };
+                mi.mem.check_bounds_exclusive(src_i, n)?;
+                mi.mem.check_bounds_exclusive(dst_i, n)?;
+                mi.check_read_protection(src_i, n)?;
+                mi.check_write_protection(dst_i, n)?;
+                mi.mem.copy_within_exclusive(dst_i, src_i, n)
+            }
+        }
     }
 
     /// Gets the size of some memory by its memory address in pages.
@@ -993,6 +1236,21 @@ impl<'b, T: Config> Store<'b, T> {
             "the maximum memory length to be smaller than u32::MAX because thats what the specification allows for indexing into the memory. Also the memory size is measured in pages, not bytes.")
     }
 
+    /// Returns the number of bytes allocated for some memory by its memory address. For a sparse memory, these are only
+    /// the bytes of the chunks written to so far, see [`Store::set_sparse_memories`], otherwise all bytes of the
+    /// memory.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to guarantee that the given [`MemAddr`] came from the
+    /// current [`Store`] object.
+    pub unsafe fn mem_allocated_bytes(&self, mem_addr: MemAddr) -> usize {
+        // SAFETY: The caller ensures that the given memory address is valid in
+        // the current store.
+        let memory = unsafe { self.memories.get(mem_addr) };
+        memory.mem.allocated_len()
+    }
+
     /// Grows some memory by its memory address by `n` pages.
     ///
     /// See: WebAssemblySpecification 2.0 - 7.1.9 - mem_grow
@@ -1009,8 +1267,314 @@ impl<'b, T: Config> Store<'b, T> {
         // Note: Returning the new store is a noop for us because we mutate the store instead.
         // SAFETY: The caller ensures that the given memory address is valid in
         // the current store.
-        let memory = unsafe { self.memories.get_mut(mem_addr) };
-        memory.grow(n)
+        let result = unsafe {
+            grow_memory(
+                &mut self.memories,
+                &mut self.user_data,
+                &mut self.resource_usage,
+                mem_addr,
+                n,
+            )
+        };
+        result.map_err(|failure| match failure {
+            GrowthFailure::MaximumExceeded => TrapError::MemoryOrDataAccessOutOfBounds.into(),
+            GrowthFailure::Denied => RuntimeError::GrowthDenied,
+            GrowthFailure::OutOfMemory => RuntimeError::OutOfMemory,
+            GrowthFailure::Trap => TrapError::ResourceLimitExceeded.into(),
+        })
+    }
+
+    /// Maps the byte range `region` of some memory as memory-mapped I/O.
+    ///
+    /// Loads and stores of single values by Wasm code which overlap the region are forwarded to
+    /// [`Config::mmio_load_hook`] and [`Config::mmio_store_hook`] instead of accessing the linear memory. Accesses
+    /// still have to lie within the bounds of the memory.
+    ///
+    /// The bulk memory instructions `memory.copy`, `memory.fill` and `memory.init` trap with
+    /// [`TrapError::MmioBulkAccess`] if they overlap the region, before any byte is accessed. Active data segments of
+    /// modules instantiated afterwards are written like `memory.init`. The memory accessors of the [`Store`] access the
+    /// linear memory directly, even within the region.
+    ///
+    /// The region has to be non-empty and lie within the current size of the memory, otherwise
+    /// [`RuntimeError::InvalidMemoryRegion`] is returned.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to guarantee that the given [`MemAddr`] came from the
+    /// current [`Store`] object.
+    pub unsafe fn mem_map_mmio(
+        &mut self,
+        mem_addr: MemAddr,
+        region: Range<usize>,
+    ) -> Result<(), RuntimeError> {
+        // SAFETY: The caller ensures that the given memory address is valid in
+        // the current store.
+        let mem_inst = unsafe { self.memories.get_mut(mem_addr) };
+        check_memory_region(mem_inst, &region)?;
+        mem_inst.mmio_regions.try_push(region)
+    }
+
+    /// Protects the byte range `region` of some memory from accesses by Wasm code.
+    ///
+    /// Loads, stores and bulk memory instructions as well as active data segments that violate the protection trap
+    /// with [`TrapError::MemoryProtectionViolation`]. [`Store::mem_read`] and [`Store::mem_write`] respect the
+    /// protection, too. The host can bypass it explicitly through [`Store::mem_access_mut_slice`] or remove it with
+    /// [`Store::mem_unprotect`].
+    ///
+    /// The region has to be non-empty and lie within the current size of the memory, otherwise
+    /// [`RuntimeError::InvalidMemoryRegion`] is returned.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to guarantee that the given [`MemAddr`] came from the
+    /// current [`Store`] object.
+    pub unsafe fn mem_protect(
+        &mut self,
+        mem_addr: MemAddr,
+        region: Range<usize>,
+        protection: MemoryProtection,
+    ) -> Result<(), RuntimeError> {
+        // SAFETY: The caller ensures that the given memory address is valid in
+        // the current store.
+        let mem_inst = unsafe { self.memories.get_mut(mem_addr) };
+        check_memory_region(mem_inst, &region)?;
+        mem_inst.protected_regions.try_push((region, protection))
+    }
+
+    /// Removes all protections of some memory which were added for exactly the byte range `region` through
+    /// [`Store::mem_protect`].
+    ///
+    /// # Safety
+    ///
+    /// The caller has to guarantee that the given [`MemAddr`] came from the
+    /// current [`Store`] object.
+    pub unsafe fn mem_unprotect(&mut self, mem_addr: MemAddr, region: Range<usize>) {
+        // SAFETY: The caller ensures that the given memory address is valid in
+        // the current store.
+        let mem_inst = unsafe { self.memories.get_mut(mem_addr) };
+        mem_inst
+            .protected_regions
+            .retain(|(protected_region, _)| *protected_region != region);
+    }
+
+    /// Returns the resources allocated for all memories and tables of this store
+    pub fn resource_usage(&self) -> ResourceUsage {
+        self.resource_usage
+    }
+
+    /// Returns a digest of the contents of all memories, globals, tables and fuel pools of this store
+    ///
+    /// Two stores with equal digests are in the same state with high probability, which allows checking that an
+    /// execution is reproduced from the same state it was recorded in. The digest is independent of the platform, but
+    /// computing it requires reading all memories.
+    pub fn state_digest(&self) -> u64 {
+        let mut digest = StateDigest::default();
+        for mem_inst in self.memories.iter() {
+            digest.write_usize(mem_inst.mem.len());
+            let mut buffer = [0; 256];
+            let mut address = 0;
+            while address < mem_inst.mem.len() {
+                let chunk = &mut buffer[..(mem_inst.mem.len() - address).min(256)];
+                mem_inst
+                    .mem
+                    .load_into(address, chunk)
+                    .expect("the chunk to lie within the memory");
+                digest.write(chunk);
+                address += chunk.len();
+            }
+        }
+        for global_inst in self.globals.iter() {
+            digest.write_value(global_inst.value);
+        }
+        for table_inst in self.tables.iter() {
+            digest.write_usize(table_inst.len());
+            for reference in &table_inst.elem {
+                digest.write_value(Value::Ref(*reference));
+            }
+        }
+        for fuel in self.fuel_pools.iter() {
+            digest.write(&fuel.to_le_bytes());
+        }
+        digest.0
+    }
+
+    /// Returns a copy of the contents of all memories, globals, tables and fuel pools of this store
+    ///
+    /// Unlike [`Store::state_digest`], the snapshot can be used to bring another store into the same state through
+    /// [`Store::restore_state_snapshot`]. Copying a memory requires as many bytes as the memory is large, even if it is
+    /// sparse.
+    pub fn state_snapshot(&self) -> Result<StateSnapshot, RuntimeError> {
+        let memories = self
+            .memories
+            .iter()
+            .map(|mem_inst| {
+                let mut bytes = Vec::new();
+                bytes.try_resize_with(mem_inst.mem.len(), || 0)?;
+                mem_inst.mem.load_into(0, &mut bytes)?;
+                Ok(bytes)
+            })
+            .try_collect_results()?;
+        let globals = self
+            .globals
+            .iter()
+            .map(|global_inst| global_inst.value)
+            .try_collect_vec()?;
+        let tables = self
+            .tables
+            .iter()
+            .map(|table_inst| try_to_vec(&table_inst.elem))
+            .try_collect_results()?;
+        let fuel_pools = self.fuel_pools.iter().copied().try_collect_vec()?;
+        Ok(StateSnapshot {
+            memories,
+            globals,
+            tables,
+            fuel_pools,
+        })
+    }
+
+    /// Restores the contents of all memories, globals, tables and fuel pools of this store from a snapshot taken by
+    /// [`Store::state_snapshot`]
+    ///
+    /// The store must contain as many memories, globals, tables and fuel pools of the same types as the store the
+    /// snapshot was taken of, e.g. because the same modules were instantiated in the same order. Memories and tables
+    /// that are smaller than in the snapshot are grown, which is subject to the resource limiter of the [`Config`].
+    /// Fails with [`RuntimeError::StateSnapshotMismatch`] before changing anything if the snapshot does not fit the
+    /// store. If growing a memory or table fails, the store may be left partially restored.
+    ///
+    /// Memory protection and MMIO regions do not apply to restoring the contents of memories, and immutable globals are
+    /// restored as well.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to guarantee that any [`FuncAddr`] or
+    /// [`ExternAddr`](crate::execution::value::ExternAddr) values contained in
+    /// the snapshot came from the current [`Store`] object.
+    pub unsafe fn restore_state_snapshot(
+        &mut self,
+        snapshot: &StateSnapshot,
+    ) -> Result<(), RuntimeError> {
+        let fits = snapshot.memories.len() == self.memories.iter().len()
+            && snapshot.globals.len() == self.globals.iter().len()
+            && snapshot.tables.len() == self.tables.iter().len()
+            && snapshot.fuel_pools.len() == self.fuel_pools.iter().len()
+            && self
+                .memories
+                .iter()
+                .zip(&snapshot.memories)
+                .all(|(mem_inst, bytes)| {
+                    let page_size = mem_inst.mem.page_size();
+                    bytes.len() >= mem_inst.mem.len()
+                        && (bytes.len() - mem_inst.mem.len()) % page_size == 0
+                        && u32::try_from((bytes.len() - mem_inst.mem.len()) / page_size).is_ok()
+                })
+            && self
+                .globals
+                .iter()
+                .zip(&snapshot.globals)
+                .all(|(global_inst, value)| global_inst.ty.ty == value.to_ty())
+            && self
+                .tables
+                .iter()
+                .zip(&snapshot.tables)
+                .all(|(table_inst, elem)| {
+                    elem.len() >= table_inst.len()
+                        && u32::try_from(elem.len() - table_inst.len()).is_ok()
+                        && elem.iter().all(|r#ref| r#ref.ty() == table_inst.ty.et)
+                });
+        if !fits {
+            return Err(RuntimeError::StateSnapshotMismatch);
+        }
+
+        for (i, bytes) in snapshot.memories.iter().enumerate() {
+            let mem_addr = MemAddr::new(i);
+            // SAFETY: The memory address was just created from an index below
+            // the number of memories of this store.
+            let mem_inst = unsafe { self.memories.get(mem_addr) };
+            let missing_pages = (bytes.len() - mem_inst.mem.len()) / mem_inst.mem.page_size();
+            if missing_pages > 0 {
+                // SAFETY: see above
+                unsafe { self.mem_grow(mem_addr, missing_pages as u32) }?;
+            }
+            // SAFETY: see above
+            let mem_inst = unsafe { self.memories.get_mut(mem_addr) };
+            mem_inst.mem.overwrite_exclusive(bytes)?;
+        }
+        for (i, value) in snapshot.globals.iter().enumerate() {
+            // SAFETY: The global address was just created from an index below
+            // the number of globals of this store.
+            unsafe { self.globals.get_mut(GlobalAddr::new(i)) }.value = *value;
+        }
+        for (i, elem) in snapshot.tables.iter().enumerate() {
+            let table_addr = TableAddr::new(i);
+            // SAFETY: The table address was just created from an index below
+            // the number of tables of this store.
+            let table_inst = unsafe { self.tables.get(table_addr) };
+            let missing_elements = elem.len() - table_inst.len();
+            if missing_elements > 0 {
+                let null = Ref::Null(table_inst.ty.et);
+                // SAFETY: see above. A null reference does not contain any
+                // address.
+                unsafe { self.table_grow(table_addr, missing_elements as u32, null) }?;
+            }
+            // SAFETY: see above
+            unsafe { self.tables.get_mut(table_addr) }
+                .elem
+                .copy_from_slice(elem);
+        }
+        for (i, fuel) in snapshot.fuel_pools.iter().enumerate() {
+            // SAFETY: The fuel pool address was just created from an index
+            // below the number of fuel pools of this store.
+            unsafe { self.fuel_pool_set(FuelPoolAddr::new(i), *fuel) };
+        }
+        Ok(())
+    }
+
+    /// Returns whether `mem_addr` is the address of a memory in this store
+    pub fn contains_mem(&self, mem_addr: MemAddr) -> bool {
+        self.memories.contains(mem_addr)
+    }
+
+    /// Returns whether `global_addr` is the address of a global in this store
+    pub fn contains_global(&self, global_addr: GlobalAddr) -> bool {
+        self.globals.contains(global_addr)
+    }
+
+    /// Returns whether `table_addr` is the address of a table in this store
+    pub fn contains_table(&self, table_addr: TableAddr) -> bool {
+        self.tables.contains(table_addr)
+    }
+
+    /// Returns whether `fuel_pool_addr` is the address of a fuel pool in this store
+    pub fn contains_fuel_pool(&self, fuel_pool_addr: FuelPoolAddr) -> bool {
+        self.fuel_pools.contains(fuel_pool_addr)
+    }
+
+    /// Returns the stack limits of invocations created by this store
+    pub fn stack_limits(&self) -> StackLimits {
+        self.stack_limits
+    }
+
+    /// Sets the stack limits of invocations created by this store from now on. Existing resumables keep the limits
+    /// they were created with.
+    pub fn set_stack_limits(&mut self, stack_limits: StackLimits) {
+        self.stack_limits = stack_limits;
+    }
+
+    /// Returns whether memories allocated by this store are sparse
+    pub fn sparse_memories(&self) -> bool {
+        self.sparse_memories
+    }
+
+    /// Sets whether memories allocated by this store from now on are sparse, both memories defined by modules and
+    /// those allocated through [`Store::mem_alloc`]. Existing memories and memories in caller-provided storage are not
+    /// affected.
+    ///
+    /// A sparse memory only allocates its bytes in chunks of 4 KiB when they are first written to, while untouched
+    /// chunks read as zeros. This saves memory for modules declaring large memories of which they only use a small
+    /// part, at the cost of slightly slower memory accesses.
+    pub fn set_sparse_memories(&mut self, sparse: bool) {
+        self.sparse_memories = sparse;
     }
 
     /// Allocates a new global and returns its global address.
@@ -1037,7 +1601,7 @@ impl<'b, T: Config> Store<'b, T> {
         // 2. Let `globaladdr` be the result of allocating a global with global type `globaltype` and initialization value `val`.
         // SAFETY: The caller ensures that any address types contained in the
         // initial value are valid in the current store.
-        let global_addr = unsafe { self.alloc_global(global_type, val) };
+        let global_addr = unsafe { self.alloc_global(global_type, val) }?;
 
         // 3. Return the new store paired with `globaladdr`.
         //
@@ -1128,6 +1692,53 @@ impl<'b, T: Config> Store<'b, T> {
         Ok(())
     }
 
+    /// Allocates a new fuel pool that initially holds `fuel` units of fuel.
+    ///
+    /// Resumables created through [`Store::create_resumable_with_fuel_pool`] draw their fuel from a shared pool
+    /// instead of carrying their own fuel. Because only one resumable can execute on a store at a time, the order in
+    /// which fuel is drawn from a pool is fully determined by the order in which the resumables are resumed.
+    pub fn fuel_pool_alloc(&mut self, fuel: u64) -> Result<FuelPoolAddr, RuntimeError> {
+        self.fuel_pools.insert(fuel)
+    }
+
+    /// Returns the fuel remaining in a fuel pool.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to guarantee that the given [`FuelPoolAddr`] came from
+    /// the current [`Store`] object.
+    pub unsafe fn fuel_pool_remaining(&self, fuel_pool_addr: FuelPoolAddr) -> u64 {
+        // SAFETY: The caller ensures that the given fuel pool address is valid
+        // in the current store.
+        unsafe { *self.fuel_pools.get(fuel_pool_addr) }
+    }
+
+    /// Adds `fuel` units of fuel to a fuel pool, saturating at [`u64::MAX`].
+    ///
+    /// # Safety
+    ///
+    /// The caller has to guarantee that the given [`FuelPoolAddr`] came from
+    /// the current [`Store`] object.
+    pub unsafe fn fuel_pool_refill(&mut self, fuel_pool_addr: FuelPoolAddr, fuel: u64) {
+        // SAFETY: The caller ensures that the given fuel pool address is valid
+        // in the current store.
+        let pool = unsafe { self.fuel_pools.get_mut(fuel_pool_addr) };
+        *pool = pool.saturating_add(fuel);
+    }
+
+    /// Replaces the fuel of a fuel pool with `fuel` units of fuel, discarding any fuel that remained in it, e.g. at
+    /// the start of a new scheduling period.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to guarantee that the given [`FuelPoolAddr`] came from
+    /// the current [`Store`] object.
+    pub unsafe fn fuel_pool_set(&mut self, fuel_pool_addr: FuelPoolAddr, fuel: u64) {
+        // SAFETY: The caller ensures that the given fuel pool address is valid
+        // in the current store.
+        unsafe { *self.fuel_pools.get_mut(fuel_pool_addr) = fuel };
+    }
+
     /// roughly matches <https://webassembly.github.io/spec/core/exec/modules.html#functions> with the addition of sidetable pointer to the input signature
     ///
     /// # Safety
@@ -1135,73 +1746,161 @@ impl<'b, T: Config> Store<'b, T> {
     /// The caller has to guarantee that
     /// - the given [`ModuleAddr`] came from the current [`Store`] object.
     /// - the given [`TypeIdx`] is valid in the module for the given [`ModuleAddr`].
+    ///
+    /// The type, call frame layout and code of the function were computed during validation and are shared with all
+    /// other instances of this function.
     // TODO refactor the type of func
     unsafe fn alloc_func(
         &mut self,
-        func: (TypeIdx, (Span, usize)),
+        func: (TypeIdx, usize, &Arc<LocalFuncData>),
         module_addr: ModuleAddr,
-    ) -> FuncAddr {
-        let (ty, (span, stp)) = func;
-
-        // TODO rewrite this huge chunk of parsing after generic way to re-parse(?) structs lands
-        // SAFETY: The caller ensures that the given module address is valid in
-        // the current store.
-        let module = unsafe { self.modules.get(module_addr) };
-        let mut wasm_reader = WasmReader::new(module.wasm_bytecode);
-        wasm_reader.move_start_to(span).unwrap_validated();
-
-        let (locals, bytes_read) = wasm_reader
-            .measure_num_read_bytes(crate::code::read_declared_locals)
-            .unwrap_validated();
-
-        let code_expr = wasm_reader
-            .make_span(span.len() - bytes_read)
-            .unwrap_validated();
-
-        // core of the method below
-
-        // validation guarantees func_ty_idx exists within module_inst.types
-        // TODO fix clone
-        // SAFETY: The caller guarantees that the given type index is valid for
-        // this module.
-        let function_type = unsafe { module.types.get(ty).clone() };
+    ) -> Result<FuncAddr, RuntimeError> {
+        let (ty, stp, local_func) = func;
         let func_inst = FuncInst::WasmFunc(WasmFuncInst {
-            function_type,
+            local_func: Arc::clone(local_func),
             _ty: ty,
-            locals,
-            code_expr,
+            code_expr: local_func.code_expr,
             stp,
             module_addr,
         });
         self.functions.insert(func_inst)
     }
 
-    /// <https://webassembly.github.io/spec/core/exec/modules.html#tables>
+    /// Computes the fuel of the basic blocks of all modules that were instantiated since the last fuel-metered
+    /// execution
+    ///
+    /// The basic blocks are only needed for fuel-metered execution, so they are neither computed during validation nor
+    /// during instantiation.
+    fn compute_block_fuel(&mut self) {
+        let modules = self.modules.iter().len();
+        for i in self.modules_with_block_fuel..modules {
+            let module_addr = ModuleAddr::new(i);
+            // SAFETY: The module address was just created from an index below
+            // the number of modules of this store.
+            let block_fuel = unsafe { self.shared_block_fuel(module_addr) };
+            // SAFETY: see above
+            unsafe { self.modules.get_mut(module_addr) }.block_fuel = Some(block_fuel);
+        }
+        self.modules_with_block_fuel = modules;
+    }
+
+    /// Returns the fuel of the basic blocks of the module instance at `module_addr` for the config of this store
+    ///
+    /// The fuel is computed only once per module and then shared with all of its instances in this store, which is
+    /// possible because the costs of the config stay fixed.
     ///
     /// # Safety
     ///
-    /// The caller has to guarantee that any [`FuncAddr`] or
-    /// [`ExternAddr`](crate::execution::value::ExternAddr) values contained in
-    /// the [`Ref`] came from the current [`Store`] object.
-    unsafe fn alloc_table(&mut self, table_type: TableType, reff: Ref) -> TableAddr {
-        let table_inst = TableInst {
-            ty: table_type,
-            elem: vec![reff; table_type.lim.min.into_usize()],
-        };
-
-        self.tables.insert(table_inst)
+    /// The caller has to guarantee that the given [`ModuleAddr`] came from the
+    /// current [`Store`] object.
+    unsafe fn shared_block_fuel(&mut self, module_addr: ModuleAddr) -> Arc<BlockFuelTable> {
+        // SAFETY: The caller ensures that the module address is valid in the
+        // current store.
+        let module = unsafe { self.modules.get(module_addr) };
+        let shared_addr = Arc::as_ptr(&module.shared).addr();
+        if let Some(block_fuel) = self.block_fuel_tables.get(&shared_addr) {
+            return Arc::clone(block_fuel);
+        }
+
+        let code_exprs: Vec<Span> = module
+            .shared
+            .local_funcs
+            .iter()
+            .map(|local_func| local_func.code_expr)
+            .collect();
+        let basic_blocks =
+            find_basic_blocks(module.wasm_bytecode, &code_exprs, &module.shared.sidetable);
+        let block_fuel = Arc::new(block_fuel_table(
+            &self.user_data,
+            module.wasm_bytecode,
+            &basic_blocks,
+        ));
+        self.block_fuel_tables
+            .insert(shared_addr, Arc::clone(&block_fuel));
+        block_fuel
+    }
+
+    /// <https://webassembly.github.io/spec/core/exec/modules.html#tables>
+    ///
+    /// # Safety
+    ///
+    /// The caller has to guarantee that any [`FuncAddr`] or
+    /// [`ExternAddr`](crate::execution::value::ExternAddr) values contained in
+    /// the [`Ref`] came from the current [`Store`] object.
+    unsafe fn alloc_table(
+        &mut self,
+        table_type: TableType,
+        reff: Ref,
+    ) -> Result<TableAddr, RuntimeError> {
+        // the initial elements are subject to the resource limiter, as if the table grew from zero elements
+        let decision = self.user_data.table_grow_hook(
+            self.tables.next_addr(),
+            0,
+            table_type.lim.min,
+            &self.resource_usage,
+        );
+        Self::check_growth_decision(decision)?;
+
+        let mut elem = Vec::new();
+        elem.try_resize_with(table_type.lim.min.into_usize(), || reff)?;
+        let table_inst = TableInst {
+            ty: table_type,
+            elem,
+        };
+
+        let table_addr = self.tables.insert(table_inst)?;
+        self.resource_usage.table_elements += u64::from(table_type.lim.min);
+        Ok(table_addr)
     }
 
     /// <https://webassembly.github.io/spec/core/exec/modules.html#memories>
-    fn alloc_mem(&mut self, mem_type: MemType) -> MemAddr {
+    fn alloc_mem(&mut self, mem_type: MemType) -> Result<MemAddr, RuntimeError> {
+        self.check_initial_memory(mem_type)?;
+        let page_size = mem_type.page_size().into_usize();
+        let mem = if self.sparse_memories {
+            LinearMemory::new_sparse(page_size, mem_type.limits.min)?
+        } else {
+            LinearMemory::new_with_initial_pages(page_size, mem_type.limits.min)?
+        };
+        self.insert_mem(mem_type, mem)
+    }
+
+    /// Consults the resource limiter of the config before the initial pages of a new memory are allocated, as if the
+    /// memory grew from zero pages
+    fn check_initial_memory(&mut self, mem_type: MemType) -> Result<(), RuntimeError> {
+        let decision = self.user_data.memory_grow_hook(
+            self.memories.next_addr(),
+            0,
+            mem_type.limits.min,
+            &self.resource_usage,
+        );
+        Self::check_growth_decision(decision)
+    }
+
+    fn check_growth_decision(decision: GrowthDecision) -> Result<(), RuntimeError> {
+        match decision {
+            GrowthDecision::Allow => Ok(()),
+            GrowthDecision::Deny => Err(RuntimeError::GrowthDenied),
+            GrowthDecision::Trap => Err(TrapError::ResourceLimitExceeded.into()),
+        }
+    }
+
+    fn insert_mem(
+        &mut self,
+        mem_type: MemType,
+        mem: LinearMemory,
+    ) -> Result<MemAddr, RuntimeError> {
         let mem_inst = MemInst {
             ty: mem_type,
-            mem: LinearMemory::new_with_initial_pages(
-                mem_type.limits.min.try_into().unwrap_validated(),
-            ),
+            mem,
+            mmio_regions: Vec::new(),
+            protected_regions: Vec::new(),
         };
 
-        self.memories.insert(mem_inst)
+        let mem_addr = self.memories.insert(mem_inst)?;
+        self.resource_usage.memory_bytes +=
+            u64::from(mem_type.limits.min) * u64::from(mem_type.page_size());
+        Ok(mem_addr)
     }
 
     /// <https://webassembly.github.io/spec/core/exec/modules.html#globals>
@@ -1211,7 +1910,11 @@ impl<'b, T: Config> Store<'b, T> {
     /// The caller has to guarantee that any [`FuncAddr`] or
     /// [`ExternAddr`](crate::execution::value::ExternAddr) values contained in
     /// the [`Value`] came from the current [`Store`] object.
-    unsafe fn alloc_global(&mut self, global_type: GlobalType, val: Value) -> GlobalAddr {
+    unsafe fn alloc_global(
+        &mut self,
+        global_type: GlobalType,
+        val: Value,
+    ) -> Result<GlobalAddr, RuntimeError> {
         let global_inst = GlobalInst {
             ty: global_type,
             value: val,
@@ -1227,7 +1930,11 @@ impl<'b, T: Config> Store<'b, T> {
     /// The caller has to guarantee that any [`FuncAddr`] or
     /// [`ExternAddr`](crate::execution::value::ExternAddr) values contained in
     /// the [`Ref`]s came from the current [`Store`] object.
-    unsafe fn alloc_elem(&mut self, ref_type: RefType, refs: Vec<Ref>) -> ElemAddr {
+    unsafe fn alloc_elem(
+        &mut self,
+        ref_type: RefType,
+        refs: Vec<Ref>,
+    ) -> Result<ElemAddr, RuntimeError> {
         let elem_inst = ElemInst {
             _ty: ref_type,
             references: refs,
@@ -1237,9 +1944,9 @@ impl<'b, T: Config> Store<'b, T> {
     }
 
     /// <https://webassembly.github.io/spec/core/exec/modules.html#data-segments>
-    fn alloc_data(&mut self, bytes: &[u8]) -> DataAddr {
+    fn alloc_data(&mut self, bytes: &[u8]) -> Result<DataAddr, RuntimeError> {
         let data_inst = DataInst {
-            data: Vec::from(bytes),
+            data: try_to_vec(bytes)?,
         };
 
         self.data.insert(data_inst)
@@ -1259,36 +1966,48 @@ impl<'b, T: Config> Store<'b, T> {
         func_addr: FuncAddr,
         params: Vec<Value>,
         maybe_fuel: Option<u64>,
+    ) -> Result<Resumable, RuntimeError> {
+        // SAFETY: The caller ensures that the function address and all
+        // addresses in the parameters are valid in the current store.
+        unsafe {
+            self.create_resumable_with_stack_limits(
+                func_addr,
+                params,
+                maybe_fuel,
+                self.stack_limits,
+            )
+        }
+    }
+
+    /// Creates a new resumable like [`Store::create_resumable`], whose stacks are bounded by `stack_limits` instead of
+    /// the stack limits of this store.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to guarantee that the [`FuncAddr`] and any [`FuncAddr`]
+    /// or [`ExternAddr`](crate::execution::value::ExternAddr) values contained
+    /// in the parameter values came from the current [`Store`] object.
+    pub unsafe fn create_resumable_with_stack_limits(
+        &self,
+        func_addr: FuncAddr,
+        params: Vec<Value>,
+        maybe_fuel: Option<u64>,
+        stack_limits: StackLimits,
     ) -> Result<Resumable, RuntimeError> {
         // SAFETY: The caller ensures that this function address is valid in the
         // current store.
         let func_inst = unsafe { self.functions.get(func_addr) };
 
-        let func_ty = func_inst.ty();
-
         // Verify that the given parameter types match the function parameter types
-        if func_ty.params.valtypes.len() != params.len() {
-            return Err(RuntimeError::FunctionInvocationSignatureMismatch);
-        }
-
-        let type_mismatch = func_ty
-            .params
-            .valtypes
-            .iter()
-            .zip(&params)
-            .any(|(func_val_ty, param_val)| func_val_ty != &param_val.to_ty());
-
-        if type_mismatch {
-            return Err(RuntimeError::FunctionInvocationSignatureMismatch);
-        };
+        verify_params(func_inst.ty(), &params)?;
 
         let resumable = match func_inst {
             FuncInst::WasmFunc(wasm_func_inst) => {
                 // Prepare a new stack with the locals for the entry function
-                let stack = Stack::new::<T>(
-                    params,
-                    &wasm_func_inst.function_type,
-                    &wasm_func_inst.locals,
+                let stack = Stack::new(
+                    stack_limits,
+                    &params,
+                    &wasm_func_inst.local_func.frame_layout,
                 )?;
 
                 Resumable::Wasm(WasmResumable {
@@ -1297,6 +2016,9 @@ impl<'b, T: Config> Store<'b, T> {
                     pc: wasm_func_inst.code_expr.from,
                     stp: wasm_func_inst.stp,
                     maybe_fuel,
+                    fuel_pool: None,
+                    pending_fuel: 0,
+                    started: false,
                 })
             }
             FuncInst::HostFunc(host_func_inst) => Resumable::Host {
@@ -1308,6 +2030,8 @@ impl<'b, T: Config> Store<'b, T> {
                     host_func_addr: func_addr,
                     inner_resumable: None,
                     maybe_fuel: Some(maybe_fuel),
+                    fuel_pool: None,
+                    stack_storage: None,
                 },
             },
         };
@@ -1315,6 +2039,168 @@ impl<'b, T: Config> Store<'b, T> {
         Ok(resumable)
     }
 
+    /// Creates a new resumable like [`Store::create_resumable`], which draws its fuel from the fuel pool
+    /// `fuel_pool_addr` instead of carrying its own fuel.
+    ///
+    /// Execution of the resumable is always fuel-metered. If the pool runs out of fuel, execution pauses with a
+    /// [`RunState::Resumable`] as usual and may be resumed once the pool has been refilled.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to guarantee that the [`FuncAddr`], the [`FuelPoolAddr`]
+    /// and any [`FuncAddr`] or
+    /// [`ExternAddr`](crate::execution::value::ExternAddr) values contained in
+    /// the parameter values came from the current [`Store`] object.
+    pub unsafe fn create_resumable_with_fuel_pool(
+        &self,
+        func_addr: FuncAddr,
+        params: Vec<Value>,
+        fuel_pool_addr: FuelPoolAddr,
+    ) -> Result<Resumable, RuntimeError> {
+        // SAFETY: The caller ensures that the function address and all
+        // addresses in the parameters are valid in the current store.
+        let mut resumable = unsafe { self.create_resumable(func_addr, params, None) }?;
+        match &mut resumable {
+            Resumable::Wasm(wasm_resumable) => wasm_resumable.fuel_pool = Some(fuel_pool_addr),
+            Resumable::Host { host_resumable, .. } => {
+                host_resumable.fuel_pool = Some(fuel_pool_addr)
+            }
+        }
+        Ok(resumable)
+    }
+
+    /// Creates a new resumable like [`Store::create_resumable`], whose stack uses the given preallocated storage
+    /// instead of the heap. The parameters are copied into the storage.
+    ///
+    /// Neither creating nor executing the returned resumable allocates, see [`StackStorage`] for details. If the
+    /// storage is too small for the parameters and locals of the function, [`RuntimeError::StackExhaustion`] is
+    /// returned. Once execution has finished, the storage is returned in [`RunState::Finished`]. The storage of a
+    /// resumable that is not needed anymore can be recovered through [`WasmResumable::into_stack_storage`], and the
+    /// storage of a resumable whose execution failed through [`Store::resume_wasm_or_reclaim`].
+    ///
+    /// # Safety
+    ///
+    /// The caller has to guarantee that the [`FuncAddr`] and any [`FuncAddr`]
+    /// or [`ExternAddr`](crate::execution::value::ExternAddr) values contained
+    /// in the parameter values came from the current [`Store`] object.
+    pub unsafe fn create_resumable_in(
+        &self,
+        func_addr: FuncAddr,
+        params: &[Value],
+        maybe_fuel: Option<u64>,
+        storage: StackStorage,
+    ) -> Result<Resumable, RuntimeError> {
+        // SAFETY: The caller ensures that the function address and all
+        // addresses in the parameters are valid in the current store.
+        unsafe {
+            self.create_resumable_in_with_stack_limits(
+                func_addr,
+                params,
+                maybe_fuel,
+                storage,
+                self.stack_limits,
+            )
+        }
+    }
+
+    /// Creates a new resumable like [`Store::create_resumable_in`], whose stacks are bounded by `stack_limits` instead
+    /// of the stack limits of this store. The stacks are bounded by the capacity of the storage as well.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to guarantee that the [`FuncAddr`] and any [`FuncAddr`]
+    /// or [`ExternAddr`](crate::execution::value::ExternAddr) values contained
+    /// in the parameter values came from the current [`Store`] object.
+    pub unsafe fn create_resumable_in_with_stack_limits(
+        &self,
+        func_addr: FuncAddr,
+        params: &[Value],
+        maybe_fuel: Option<u64>,
+        storage: StackStorage,
+        stack_limits: StackLimits,
+    ) -> Result<Resumable, RuntimeError> {
+        // SAFETY: The caller ensures that this function address is valid in the
+        // current store.
+        let func_inst = unsafe { self.functions.get(func_addr) };
+
+        verify_params(func_inst.ty(), params)?;
+
+        let resumable = match func_inst {
+            FuncInst::WasmFunc(wasm_func_inst) => {
+                let stack = Stack::new_in(
+                    stack_limits,
+                    storage,
+                    params,
+                    &wasm_func_inst.local_func.frame_layout,
+                )?;
+
+                Resumable::Wasm(WasmResumable {
+                    current_func_addr: func_addr,
+                    stack,
+                    pc: wasm_func_inst.code_expr.from,
+                    stp: wasm_func_inst.stp,
+                    maybe_fuel,
+                    fuel_pool: None,
+                    pending_fuel: 0,
+                    started: false,
+                })
+            }
+            FuncInst::HostFunc(host_func_inst) => {
+                let mut storage = storage;
+                Resumable::Host {
+                    host_call: HostCall {
+                        params: storage.take_host_call_params(params)?,
+                        hostcode: host_func_inst.hostcode,
+                    },
+                    host_resumable: HostResumable {
+                        host_func_addr: func_addr,
+                        inner_resumable: None,
+                        maybe_fuel: Some(maybe_fuel),
+                        fuel_pool: None,
+                        stack_storage: Some(storage),
+                    },
+                }
+            }
+        };
+
+        Ok(resumable)
+    }
+
+    /// Moves the fuel of the fuel pool of `resumable` into it, so that it can be used for execution
+    ///
+    /// # Safety
+    ///
+    /// The caller has to guarantee that the [`WasmResumable`] came from the
+    /// current [`Store`] object.
+    unsafe fn take_pool_fuel(&mut self, resumable: &mut WasmResumable) {
+        if let Some(fuel_pool_addr) = resumable.fuel_pool {
+            // SAFETY: Fuel pool addresses of resumables are only set through
+            // `create_resumable_with_fuel_pool`, where the caller ensures that
+            // they are valid in the current store.
+            let pool = unsafe { self.fuel_pools.get_mut(fuel_pool_addr) };
+            resumable.maybe_fuel = Some(core::mem::take(pool));
+        }
+    }
+
+    /// Moves the fuel that remains in `resumable` back into its fuel pool after execution
+    ///
+    /// # Safety
+    ///
+    /// The caller has to guarantee that the [`WasmResumable`] came from the
+    /// current [`Store`] object.
+    unsafe fn return_pool_fuel(&mut self, resumable: &mut WasmResumable) {
+        if let Some(fuel_pool_addr) = resumable.fuel_pool {
+            // SAFETY: Fuel pool addresses of resumables are only set through
+            // `create_resumable_with_fuel_pool`, where the caller ensures that
+            // they are valid in the current store.
+            let pool = unsafe { self.fuel_pools.get_mut(fuel_pool_addr) };
+            *pool = resumable
+                .maybe_fuel
+                .take()
+                .expect("fuel to be taken from the pool before execution");
+        }
+    }
+
     /// Resumes execution of a [`Resumable`], regardless of its inner representation.
     ///
     /// Note: The [`Resumable`] may also be deconstructed by the user and its
@@ -1341,8 +2227,8 @@ impl<'b, T: Config> Store<'b, T> {
     }
 
     /// Resumes the given [`WasmResumable`]. Returns a [`RunState`] that may contain
-    /// a new [`Resumable`] depending on whether execution ran out of fuel or
-    /// finished normally.
+    /// a new [`Resumable`] depending on whether execution ran out of fuel, was
+    /// interrupted by a hook or finished normally.
     ///
     /// # Safety
     ///
@@ -1350,19 +2236,77 @@ impl<'b, T: Config> Store<'b, T> {
     /// [`Store`] object.
     pub unsafe fn resume_wasm(
         &mut self,
-        mut resumable: WasmResumable,
+        resumable: WasmResumable,
     ) -> Result<RunState, RuntimeError> {
-        let result = interpreter_loop::run(&mut resumable, self)?;
+        // SAFETY: The caller ensures that the resumable came from the current
+        // store.
+        Ok(unsafe { self.resume_wasm_or_reclaim(resumable) }?)
+    }
 
-        let run_state = match result {
-            InterpreterLoopOutcome::ExecutionReturned => RunState::Finished {
-                values: resumable.stack.into_values(),
-                maybe_remaining_fuel: resumable.maybe_fuel,
-            },
+    /// Resumes the given [`WasmResumable`] like [`Store::resume_wasm`]. If execution fails, e.g. because it trapped or
+    /// exhausted the stack, the [`StackStorage`] of a resumable created with [`Store::create_resumable_in`] is
+    /// returned in the [`ResumeError`], so that it can be reused.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to guarantee that the [`Resumable`] came from the current
+    /// [`Store`] object.
+    pub unsafe fn resume_wasm_or_reclaim(
+        &mut self,
+        mut resumable: WasmResumable,
+    ) -> Result<RunState, ResumeError> {
+        // SAFETY: The caller ensures that the resumable came from the current
+        // store.
+        unsafe { self.take_pool_fuel(&mut resumable) };
+        if resumable.stack.call_frame_count() == 0 {
+            // SAFETY: Same as above.
+            return unsafe { self.finish_returned_host_call(resumable) };
+        }
+        if resumable.maybe_fuel.is_some() {
+            self.compute_block_fuel();
+        }
+        let result = interpreter_loop::run(&mut resumable, self);
+        let maybe_remaining_fuel = resumable.maybe_fuel;
+        // SAFETY: Same as above. The fuel is returned even if execution
+        // trapped, so that the fuel consumed until then stays deducted.
+        unsafe { self.return_pool_fuel(&mut resumable) };
+
+        let outcome = match result {
+            Ok(outcome) => outcome,
+            Err(error) => {
+                return Err(ResumeError {
+                    error,
+                    stack_storage: resumable.into_stack_storage(),
+                })
+            }
+        };
+        let run_state = match outcome {
+            InterpreterLoopOutcome::ExecutionReturned => {
+                // SAFETY: The caller ensures that the resumable came from the
+                // current store, and the interpreter loop only ever sets its
+                // function address to addresses from the current store.
+                let function = unsafe { self.functions.get(resumable.current_func_addr) };
+                // a stack with a storage never fails here, because it does not allocate for the return values
+                let (values, stack_storage) = resumable
+                    .stack
+                    .into_finished(&function.ty().returns.valtypes)
+                    .map_err(|error| ResumeError {
+                        error,
+                        stack_storage: None,
+                    })?;
+                RunState::Finished {
+                    values,
+                    maybe_remaining_fuel,
+                    stack_storage,
+                }
+            }
             InterpreterLoopOutcome::OutOfFuel { required_fuel } => RunState::Resumable {
                 resumable,
-                required_fuel: Some(required_fuel),
+                required_fuel,
             },
+            InterpreterLoopOutcome::Interrupted(reason) => {
+                RunState::Interrupted { resumable, reason }
+            }
             InterpreterLoopOutcome::HostCalled {
                 func_addr,
                 params,
@@ -1373,6 +2317,8 @@ impl<'b, T: Config> Store<'b, T> {
                     host_func_addr: func_addr,
                     inner_resumable: Some(resumable),
                     maybe_fuel: None,
+                    fuel_pool: None,
+                    stack_storage: None,
                 },
             },
         };
@@ -1382,6 +2328,8 @@ impl<'b, T: Config> Store<'b, T> {
 
     /// To be executed after executing a [`HostCall`].
     ///
+    /// This is equivalent to [`Store::finish_host_call_with_fuel`] without charging any fuel for the host call.
+    ///
     /// # Safety
     ///
     /// The caller has to guarantee that the [`HostResumable`] and all
@@ -1390,6 +2338,31 @@ impl<'b, T: Config> Store<'b, T> {
         &mut self,
         host_resumable: HostResumable,
         host_call_return_values: Vec<Value>,
+    ) -> Result<RunState, RuntimeError> {
+        // SAFETY: The caller ensures that the `HostResumable` and all addresses
+        // in the return values are valid in the current store.
+        unsafe { self.finish_host_call_with_fuel(host_resumable, host_call_return_values, 0) }
+    }
+
+    /// To be executed after executing a [`HostCall`], charging `host_fuel` units of fuel for the work done by the host
+    /// function.
+    ///
+    /// For fuel-metered execution, the charge is deducted from the fuel of the [`HostResumable`]. If there is not
+    /// enough fuel left, the host call is still finished, but a [`RunState::Resumable`] is returned that requires the
+    /// missing fuel before Wasm execution continues. Otherwise, Wasm execution continues with the
+    /// [`RunState::Interrupted`] that is returned with [`InterruptReason::HostCallFinished`]. If the host function was
+    /// invoked directly instead of being called by Wasm code, the [`RunState::Resumable`] holds its return values and
+    /// finishes with them once it is resumed with enough fuel.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to guarantee that the [`HostResumable`] and all
+    /// addresses in the return values came from the current [`Store`] object.
+    pub unsafe fn finish_host_call_with_fuel(
+        &mut self,
+        host_resumable: HostResumable,
+        mut host_call_return_values: Vec<Value>,
+        host_fuel: u64,
     ) -> Result<RunState, RuntimeError> {
         // Verify that the return parameters match the host function parameters
         // since we have no validation guarantees for host functions
@@ -1402,35 +2375,176 @@ impl<'b, T: Config> Store<'b, T> {
             unreachable!("expected function to be a host function instance")
         };
 
-        let return_types = host_call_return_values
-            .iter()
-            .map(|v| v.to_ty())
-            .collect::<Vec<_>>();
+        let return_types = host_call_return_values.iter().map(|v| v.to_ty());
 
-        if host_func_inst.function_type.returns.valtypes != return_types {
+        if !host_func_inst
+            .function_type
+            .returns
+            .valtypes
+            .iter()
+            .copied()
+            .eq(return_types)
+        {
             return Err(RuntimeError::HostFunctionSignatureMismatch);
         }
 
         if let Some(mut wasm_resumable) = host_resumable.inner_resumable {
-            for return_value in host_call_return_values {
-                wasm_resumable.stack.push_value::<T>(return_value)?;
+            // SAFETY: The caller ensures that the `HostResumable`, and thus
+            // also its inner resumable, came from the current store.
+            unsafe { self.take_pool_fuel(&mut wasm_resumable) };
+
+            // the fuel is charged while the host function is still the current function
+            let mut required_fuel = None;
+            if let Some(fuel) = &mut wasm_resumable.maybe_fuel {
+                if *fuel >= host_fuel {
+                    *fuel -= host_fuel;
+                    if host_fuel > 0 {
+                        self.user_data.fuel_hook(host_fuel);
+                    }
+                } else {
+                    required_fuel = NonZeroU64::new(host_fuel - *fuel);
+                    wasm_resumable.pending_fuel = host_fuel;
+                }
             }
+            // SAFETY: Same as above.
+            unsafe { self.return_pool_fuel(&mut wasm_resumable) };
 
-            Ok(RunState::Resumable {
-                resumable: wasm_resumable,
-                required_fuel: None,
+            self.user_data.return_hook(host_resumable.host_func_addr);
+
+            for return_value in host_call_return_values.drain(..) {
+                wasm_resumable.stack.push_value(return_value)?;
+            }
+            // stacks with a fixed capacity reuse the buffer for the next host call
+            wasm_resumable
+                .stack
+                .recycle_host_values(host_call_return_values);
+
+            Ok(match required_fuel {
+                Some(required_fuel) => RunState::Resumable {
+                    resumable: wasm_resumable,
+                    required_fuel,
+                },
+                None => RunState::Interrupted {
+                    resumable: wasm_resumable,
+                    reason: InterruptReason::HostCallFinished,
+                },
             })
         } else {
+            let mut maybe_remaining_fuel = host_resumable
+                .maybe_fuel
+                .expect("this to be set if the inner WasmResumable is None");
+            let fuel = match host_resumable.fuel_pool {
+                // SAFETY: The caller ensures that the `HostResumable`, and thus
+                // also its fuel pool address, came from the current store.
+                Some(fuel_pool_addr) => Some(unsafe { self.fuel_pools.get_mut(fuel_pool_addr) }),
+                None => maybe_remaining_fuel.as_mut(),
+            };
+            let mut required_fuel = None;
+            if let Some(fuel) = fuel {
+                if *fuel >= host_fuel {
+                    *fuel -= host_fuel;
+                    maybe_remaining_fuel = Some(*fuel);
+                    if host_fuel > 0 {
+                        self.user_data.fuel_hook(host_fuel);
+                    }
+                } else {
+                    required_fuel = NonZeroU64::new(host_fuel - *fuel);
+                }
+            }
+
+            self.user_data.return_hook(host_resumable.host_func_addr);
+
+            if let Some(required_fuel) = required_fuel {
+                // the return values are kept until the host call is paid for, see `Store::finish_returned_host_call`
+                let stack = Stack::new_returned(
+                    self.stack_limits,
+                    host_resumable.stack_storage,
+                    host_call_return_values,
+                )?;
+                let resumable = WasmResumable {
+                    stack,
+                    pc: 0,
+                    stp: 0,
+                    current_func_addr: host_resumable.host_func_addr,
+                    maybe_fuel: maybe_remaining_fuel,
+                    fuel_pool: host_resumable.fuel_pool,
+                    pending_fuel: host_fuel,
+                    started: true,
+                };
+                return Ok(RunState::Resumable {
+                    resumable,
+                    required_fuel,
+                });
+            }
+
+            let mut stack_storage = host_resumable.stack_storage;
+            let values = match &mut stack_storage {
+                Some(stack_storage) => {
+                    stack_storage.take_host_call_return_values(host_call_return_values)?
+                }
+                None => host_call_return_values,
+            };
             Ok(RunState::Finished {
-                values: host_call_return_values,
-                maybe_remaining_fuel: host_resumable
-                    .maybe_fuel
-                    .expect("this to be set if the inner WasmResumable is None"),
+                values,
+                maybe_remaining_fuel,
+                stack_storage,
             })
         }
     }
 
+    /// Finishes a host function that was invoked directly and had too little fuel left to pay for its host call, see
+    /// [`Store::finish_host_call_with_fuel`]. Its stack only holds the return values, which are returned once the
+    /// fuel charged for the host call is paid.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to guarantee that the [`WasmResumable`] came from the
+    /// current [`Store`] object and that its fuel was taken from its fuel
+    /// pool.
+    unsafe fn finish_returned_host_call(
+        &mut self,
+        mut resumable: WasmResumable,
+    ) -> Result<RunState, ResumeError> {
+        if let Some(fuel) = &mut resumable.maybe_fuel {
+            if *fuel < resumable.pending_fuel {
+                let required_fuel = NonZeroU64::new(resumable.pending_fuel - *fuel)
+                    .expect("the last check guarantees that the current fuel is smaller than the pending fuel");
+                // SAFETY: The caller ensures that the resumable came from the
+                // current store.
+                unsafe { self.return_pool_fuel(&mut resumable) };
+                return Ok(RunState::Resumable {
+                    resumable,
+                    required_fuel,
+                });
+            }
+            *fuel -= resumable.pending_fuel;
+            self.user_data.fuel_hook(resumable.pending_fuel);
+        }
+        resumable.pending_fuel = 0;
+        let maybe_remaining_fuel = resumable.maybe_fuel;
+        // SAFETY: Same as above.
+        unsafe { self.return_pool_fuel(&mut resumable) };
+
+        // SAFETY: The caller ensures that the resumable, and thus also the
+        // address of its host function, came from the current store.
+        let function = unsafe { self.functions.get(resumable.current_func_addr) };
+        let (values, stack_storage) = resumable
+            .stack
+            .into_finished(&function.ty().returns.valtypes)
+            .map_err(|error| ResumeError {
+                error,
+                stack_storage: None,
+            })?;
+        Ok(RunState::Finished {
+            values,
+            maybe_remaining_fuel,
+            stack_storage,
+        })
+    }
+
     /// Invokes a function without support for fuel or host functions.
+    /// Interruptions through [`Config`] hooks are ignored and execution is
+    /// continued right away.
     ///
     /// This function wraps [`Store::invoke`].
     ///
@@ -1448,21 +2562,38 @@ impl<'b, T: Config> Store<'b, T> {
         // SAFETY: The caller ensures that the given function address and all
         // address types contained in the parameters are valid in the current
         // store.
-        let run_state = unsafe { self.invoke(function, params, None) }?;
+        let mut run_state = unsafe { self.invoke(function, params, None) }?;
 
-        match run_state {
-            RunState::Finished {
-                values,
-                maybe_remaining_fuel: _,
-            } => Ok(values),
-            RunState::Resumable { .. } => unreachable!("fuel is disabled"),
-            RunState::HostCalled { .. } => Err(RuntimeError::UnexpectedHostCall),
+        loop {
+            match run_state {
+                RunState::Finished {
+                    values,
+                    maybe_remaining_fuel: _,
+                    stack_storage: _,
+                } => return Ok(values),
+                // Execution was only interrupted by a hook, so it is simply
+                // continued.
+                RunState::Interrupted { resumable, .. } => {
+                    // SAFETY: The resumable just came from the current store.
+                    // Therefore, it is valid in the current store.
+                    run_state = unsafe { self.resume_wasm(resumable) }?;
+                }
+                RunState::Resumable { .. } => unreachable!("fuel to be disabled"),
+                RunState::HostCalled { .. } => return Err(RuntimeError::UnexpectedHostCall),
+            }
         }
     }
 
     /// Allows a given closure to temporarily access the entire memory as a
     /// `&mut [u8]`.
     ///
+    /// This bypasses the protection of memory ranges added through
+    /// [`Store::mem_protect`].
+    ///
+    /// For sparse memories, the closure accesses a copy of the memory, see
+    /// [`Store::set_sparse_memories`]. If the copy cannot be allocated,
+    /// [`RuntimeError::OutOfMemory`] is returned.
+    ///
     /// # Safety
     ///
     /// The caller has to guarantee that the given [`MemAddr`] came from the
@@ -1471,7 +2602,7 @@ impl<'b, T: Config> Store<'b, T> {
         &self,
         memory: MemAddr,
         accessor: impl FnOnce(&mut [u8]) -> R,
-    ) -> R {
+    ) -> Result<R, RuntimeError> {
         // SAFETY: The caller ensures that the given memory address is valid in
         // the current store.
         let memory = unsafe { self.memories.get(memory) };
@@ -1498,6 +2629,63 @@ impl<'b, T: Config> Store<'b, T> {
             .map(|(name, externval)| (name.clone(), *externval))
             .collect()
     }
+
+    /// Returns the names of the functions of a module instance by its module
+    /// address, as they are given in the function names subsection of the
+    /// `name` custom section. Functions without a name are omitted.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to guarantee that the given [`ModuleAddr`] came from the
+    /// current [`Store`] object.
+    pub unsafe fn instance_function_names(
+        &self,
+        module_addr: ModuleAddr,
+    ) -> Vec<(FuncAddr, &'b str)> {
+        // SAFETY: The caller ensures that the given module address is valid in
+        // the current store.
+        let module = unsafe { self.modules.get(module_addr) };
+
+        module
+            .shared
+            .function_names
+            .iter()
+            .map(|(func_idx, name)| {
+                // SAFETY: Only valid function indices of the module are kept
+                // in its function names.
+                let func_addr = *unsafe { module.func_addrs.get(*func_idx) };
+                (func_addr, *name)
+            })
+            .collect()
+    }
+}
+
+/// Verifies that `region` is a non-empty byte range within the current size of the memory of `mem_inst`
+fn check_memory_region(mem_inst: &MemInst, region: &Range<usize>) -> Result<(), RuntimeError> {
+    if region.is_empty() || region.end > mem_inst.mem.len() {
+        return Err(RuntimeError::InvalidMemoryRegion);
+    }
+    Ok(())
+}
+
+/// Verifies that the types of the given parameters match the parameter types of a function
+fn verify_params(func_ty: &FuncType, params: &[Value]) -> Result<(), RuntimeError> {
+    if func_ty.params.valtypes.len() != params.len() {
+        return Err(RuntimeError::FunctionInvocationSignatureMismatch);
+    }
+
+    let type_mismatch = func_ty
+        .params
+        .valtypes
+        .iter()
+        .zip(params)
+        .any(|(func_val_ty, param_val)| func_val_ty != &param_val.to_ty());
+
+    if type_mismatch {
+        return Err(RuntimeError::FunctionInvocationSignatureMismatch);
+    }
+
+    Ok(())
 }
 
 ///<https://webassembly.github.io/spec/core/exec/runtime.html#external-values>
@@ -1619,3 +2807,82 @@ pub struct InstantiationOutcome {
 }
 
 pub type Hostcode = usize;
+
+/// The protection of a range of a linear memory, see [`Store::mem_protect`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MemoryProtection {
+    /// The range can be read but not written
+    ReadOnly,
+    /// The range can neither be read nor written
+    NoAccess,
+}
+
+/// A copy of the contents of all memories, globals, tables and fuel pools of a store, see [`Store::state_snapshot`]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StateSnapshot {
+    /// The bytes of every memory, ordered by their addresses
+    pub memories: Vec<Vec<u8>>,
+    /// The value of every global, ordered by their addresses
+    pub globals: Vec<Value>,
+    /// The elements of every table, ordered by their addresses
+    pub tables: Vec<Vec<Ref>>,
+    /// The remaining fuel of every fuel pool, ordered by their addresses
+    pub fuel_pools: Vec<u64>,
+}
+
+/// A 64-bit FNV-1a hash of the state of a store, see [`Store::state_digest`]
+struct StateDigest(u64);
+
+impl Default for StateDigest {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl StateDigest {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write(&(value as u64).to_le_bytes());
+    }
+
+    fn write_value(&mut self, value: Value) {
+        match value {
+            Value::I32(x) => {
+                self.write(&[0]);
+                self.write(&x.to_le_bytes());
+            }
+            Value::I64(x) => {
+                self.write(&[1]);
+                self.write(&x.to_le_bytes());
+            }
+            Value::F32(x) => {
+                self.write(&[2]);
+                self.write(&x.to_bits().to_le_bytes());
+            }
+            Value::F64(x) => {
+                self.write(&[3]);
+                self.write(&x.to_bits().to_le_bytes());
+            }
+            Value::V128(x) => {
+                self.write(&[4]);
+                self.write(&x);
+            }
+            Value::Ref(Ref::Null(RefType::FuncRef)) => self.write(&[5]),
+            Value::Ref(Ref::Null(RefType::ExternRef)) => self.write(&[6]),
+            Value::Ref(Ref::Func(func_addr)) => {
+                self.write(&[7]);
+                self.write_usize(func_addr.into_inner());
+            }
+            Value::Ref(Ref::Extern(extern_addr)) => {
+                self.write(&[8]);
+                self.write_usize(extern_addr.0);
+            }
+        }
+    }
+}
@@ -1,15 +1,364 @@
-use core::mem::MaybeUninit;
+use core::mem::{self, MaybeUninit};
 
-use alloc::vec::{Drain, Vec};
+use alloc::vec::Vec;
 
-use crate::addrs::FuncAddr;
-use crate::config::Config;
+use crate::addrs::{Addr, FuncAddr};
+use crate::config::StackLimits;
 use crate::core::indices::LocalIdx;
 use crate::core::reader::types::{FuncType, ValType};
 use crate::core::utils::ToUsizeExt;
 use crate::execution::assert_validated::UnwrapValidatedExt;
-use crate::execution::value::Value;
-use crate::RuntimeError;
+use crate::execution::fallible_alloc::TryVecExt;
+use crate::execution::value::{ExternAddr, Ref, Value, F32, F64};
+use crate::{NumType, RefType, RuntimeError};
+
+/// A single untyped entry of the value stack
+///
+/// Validation already proves the types of all values on the stack, so they are not stored at runtime. Every value
+/// occupies one slot, except for `v128` values, which occupy two slots holding their lower and upper halves. Values
+/// are only converted back into a typed [`Value`] at host boundaries, i.e. for the parameters and return values of
+/// host calls and invocations.
+#[cfg(not(feature = "typed-value-stack"))]
+pub(crate) type Slot = u64;
+
+/// A single typed entry of the value stack
+///
+/// With the `typed-value-stack` feature, every slot additionally stores the type of the value it belongs to, which is
+/// checked whenever the value is popped. This trades execution speed for a runtime check of the types proven by
+/// validation.
+#[cfg(feature = "typed-value-stack")]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Slot {
+    ty: SlotType,
+    bits: u64,
+}
+
+/// The type of the value a [`Slot`] belongs to, which is only stored with the `typed-value-stack` feature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SlotType {
+    I32,
+    I64,
+    F32,
+    F64,
+    V128,
+    Ref,
+}
+
+/// Creates a slot of a value of type `ty` holding `bits`
+#[inline(always)]
+fn new_slot(ty: SlotType, bits: u64) -> Slot {
+    #[cfg(not(feature = "typed-value-stack"))]
+    {
+        let _ = ty;
+        bits
+    }
+    #[cfg(feature = "typed-value-stack")]
+    Slot { ty, bits }
+}
+
+/// Returns the bits of a slot of a value of type `ty`
+#[inline(always)]
+fn slot_bits(slot: Slot, ty: SlotType) -> u64 {
+    #[cfg(not(feature = "typed-value-stack"))]
+    {
+        let _ = ty;
+        slot
+    }
+    #[cfg(feature = "typed-value-stack")]
+    (slot.ty == ty).then_some(slot.bits).unwrap_validated()
+}
+
+/// The tag in the lowest bits of a reference slot, the remaining bits hold the address of non-null references
+const REF_TAG_MASK: u64 = 0b11;
+const REF_TAG_NULL_FUNC: u64 = 0b00;
+const REF_TAG_NULL_EXTERN: u64 = 0b01;
+const REF_TAG_FUNC: u64 = 0b10;
+const REF_TAG_EXTERN: u64 = 0b11;
+
+/// Encodes a reference as the bits of a single slot
+///
+/// Addresses are shifted by the two tag bits, which never loses information in practice as addresses are indices into
+/// the vectors of a store.
+#[inline(always)]
+fn ref_into_bits(reference: Ref) -> u64 {
+    match reference {
+        Ref::Null(RefType::FuncRef) => REF_TAG_NULL_FUNC,
+        Ref::Null(RefType::ExternRef) => REF_TAG_NULL_EXTERN,
+        Ref::Func(func_addr) => ((func_addr.into_inner() as u64) << 2) | REF_TAG_FUNC,
+        Ref::Extern(ExternAddr(addr)) => ((addr as u64) << 2) | REF_TAG_EXTERN,
+    }
+}
+
+/// Passes the slots of a value to `push`, starting with the lowermost one
+#[inline(always)]
+fn for_each_slot<E>(value: Value, mut push: impl FnMut(Slot) -> Result<(), E>) -> Result<(), E> {
+    match value {
+        Value::I32(x) => push(new_slot(SlotType::I32, u64::from(x))),
+        Value::I64(x) => push(new_slot(SlotType::I64, x)),
+        Value::F32(x) => push(new_slot(SlotType::F32, u64::from(x.to_bits()))),
+        Value::F64(x) => push(new_slot(SlotType::F64, x.to_bits())),
+        Value::V128(bytes) => {
+            let x = u128::from_le_bytes(bytes);
+            push(new_slot(SlotType::V128, x as u64))?;
+            push(new_slot(SlotType::V128, (x >> 64) as u64))
+        }
+        Value::Ref(reference) => push(new_slot(SlotType::Ref, ref_into_bits(reference))),
+    }
+}
+
+/// Converts the slots of a value of type `ty`, starting with the lowermost one, back into a [`Value`]
+fn value_from_slots(ty: ValType, slots: &[Slot]) -> Value {
+    match ty {
+        ValType::NumType(NumType::I32) => Value::I32(u32::from_slots(slots)),
+        ValType::NumType(NumType::I64) => Value::I64(u64::from_slots(slots)),
+        ValType::NumType(NumType::F32) => Value::F32(F32::from_slots(slots)),
+        ValType::NumType(NumType::F64) => Value::F64(F64::from_slots(slots)),
+        ValType::VecType => Value::V128(<[u8; 16]>::from_slots(slots)),
+        ValType::RefType(_) => Value::Ref(Ref::from_slots(slots)),
+    }
+}
+
+/// Returns the number of slots occupied by values of the given types
+pub(crate) fn slot_count(types: &[ValType]) -> usize {
+    types.iter().map(ValType::slots).sum()
+}
+
+/// A Rust type that the topmost value of the stack can be popped as, given that validation proved its type
+pub(crate) trait StackValue: Sized {
+    /// The number of slots occupied by a value of this type
+    const SLOTS: usize;
+
+    /// Converts the slots of a value of this type, starting with the lowermost one
+    fn from_slots(slots: &[Slot]) -> Self;
+}
+
+macro_rules! impl_single_slot_stack_value {
+    ($($ty:ty => $slot_ty:ident |$slot:ident| $from_slot:expr),* $(,)?) => {
+        $(
+            impl StackValue for $ty {
+                const SLOTS: usize = 1;
+
+                #[inline(always)]
+                fn from_slots(slots: &[Slot]) -> Self {
+                    let $slot = slot_bits(slots[0], SlotType::$slot_ty);
+                    $from_slot
+                }
+            }
+        )*
+    };
+}
+
+impl_single_slot_stack_value! {
+    u32 => I32 |slot| slot as u32,
+    i32 => I32 |slot| (slot as u32).cast_signed(),
+    u64 => I64 |slot| slot,
+    i64 => I64 |slot| slot.cast_signed(),
+    F32 => F32 |slot| F32::from_bits(slot as u32),
+    F64 => F64 |slot| F64::from_bits(slot),
+    f32 => F32 |slot| f32::from_bits(slot as u32),
+    f64 => F64 |slot| f64::from_bits(slot),
+    Ref => Ref |slot| {
+        let addr = (slot >> 2) as usize;
+        match slot & REF_TAG_MASK {
+            REF_TAG_NULL_FUNC => Ref::Null(RefType::FuncRef),
+            REF_TAG_NULL_EXTERN => Ref::Null(RefType::ExternRef),
+            REF_TAG_FUNC => Ref::Func(FuncAddr::new(addr)),
+            _ => Ref::Extern(ExternAddr(addr)),
+        }
+    },
+}
+
+impl StackValue for [u8; 16] {
+    const SLOTS: usize = 2;
+
+    #[inline(always)]
+    fn from_slots(slots: &[Slot]) -> Self {
+        let low = slot_bits(slots[0], SlotType::V128);
+        let high = slot_bits(slots[1], SlotType::V128);
+        let x = u128::from(low) | (u128::from(high) << 64);
+        x.to_le_bytes()
+    }
+}
+
+/// The layout of the call frame of a function on the value stack, which is derived from the types of its parameters and
+/// locals when the function is allocated
+#[derive(Debug)]
+pub(crate) struct FrameLayout {
+    /// The number of slots occupied by the parameters
+    param_slots: usize,
+
+    /// The number of slots occupied by the return values
+    return_slots: usize,
+
+    /// The initial slots of the declared locals, which follow the parameters
+    local_defaults: Vec<Slot>,
+
+    /// The offsets of all locals, including parameters, within the call frame followed by the total number of their
+    /// slots
+    ///
+    /// This is empty if there is no `v128` local, in which case the offset of every local is its index.
+    local_offsets: Vec<usize>,
+}
+
+impl FrameLayout {
+    /// Computes the layout of a function of type `func_ty` with the declared locals `locals`
+    pub fn new(func_ty: &FuncType, locals: &[ValType]) -> Result<Self, RuntimeError> {
+        let mut local_defaults = Vec::new();
+        local_defaults
+            .try_reserve_exact(slot_count(locals))
+            .map_err(|_| RuntimeError::OutOfMemory)?;
+        for local in locals {
+            for_each_slot(Value::default_from_ty(*local), |slot| {
+                local_defaults.try_push(slot)
+            })?;
+        }
+
+        let all_locals = func_ty.params.valtypes.iter().chain(locals);
+        let mut local_offsets = Vec::new();
+        if all_locals.clone().any(|ty| *ty == ValType::VecType) {
+            local_offsets
+                .try_reserve_exact(func_ty.params.valtypes.len() + locals.len() + 1)
+                .map_err(|_| RuntimeError::OutOfMemory)?;
+            let mut offset = 0;
+            for ty in all_locals {
+                local_offsets.try_push(offset)?;
+                offset += ty.slots();
+            }
+            local_offsets.try_push(offset)?;
+        }
+
+        Ok(Self {
+            param_slots: slot_count(&func_ty.params.valtypes),
+            return_slots: slot_count(&func_ty.returns.valtypes),
+            local_defaults,
+            local_offsets,
+        })
+    }
+
+    /// Returns the offset of a local within the call frame and the number of slots it occupies
+    #[inline(always)]
+    pub fn local(&self, idx: LocalIdx) -> (usize, usize) {
+        let idx = idx.into_inner().into_usize();
+        if self.local_offsets.is_empty() {
+            return (idx, 1);
+        }
+        let offset = *self.local_offsets.get(idx).unwrap_validated();
+        let end = *self.local_offsets.get(idx + 1).unwrap_validated();
+        (offset, end - offset)
+    }
+}
+
+/// Preallocated storage for the stack of a [`WasmResumable`](crate::resumable::WasmResumable)
+///
+/// A resumable created with [`Store::create_resumable_in`](crate::Store::create_resumable_in) uses this storage
+/// for its values, its call frames and the parameters of host calls. This storage never grows: once it is full,
+/// execution fails with [`RuntimeError::StackExhaustion`], just like when exceeding the [`StackLimits`] of the
+/// resumable. Therefore, executing such a resumable does not allocate, except for instructions which inherently
+/// allocate, i.e. `memory.grow` and `table.grow`.
+///
+/// The storage can be allocated once at startup and be reused for many resumables. After execution has finished, it is
+/// returned in [`RunState::Finished`](crate::resumable::RunState::Finished) together with the return values, whose
+/// buffer is put back with [`StackStorage::reclaim`]. The storage of an unfinished resumable can be recovered through
+/// [`WasmResumable::into_stack_storage`](crate::resumable::WasmResumable::into_stack_storage) and the storage of a
+/// resumable whose execution failed through [`Store::resume_wasm_or_reclaim`](crate::Store::resume_wasm_or_reclaim).
+#[derive(Debug)]
+pub struct StackStorage {
+    slots: Vec<Slot>,
+    values: Vec<Value>,
+    frames: Vec<CallFrame>,
+    host_params: Vec<Value>,
+}
+
+impl StackStorage {
+    /// Allocates storage for `value_capacity` value stack slots, `frame_capacity` call frames and host calls with up
+    /// to `host_params_capacity` parameters or return values
+    ///
+    /// Every value occupies one slot, except for `v128` values, which occupy two. The buffer for the return values
+    /// holds up to `value_capacity` values as well.
+    pub fn new(
+        value_capacity: usize,
+        frame_capacity: usize,
+        host_params_capacity: usize,
+    ) -> Result<Self, RuntimeError> {
+        let mut storage = Self {
+            slots: Vec::new(),
+            values: Vec::new(),
+            frames: Vec::new(),
+            host_params: Vec::new(),
+        };
+        storage
+            .slots
+            .try_reserve_exact(value_capacity)
+            .map_err(|_| RuntimeError::OutOfMemory)?;
+        storage
+            .values
+            .try_reserve_exact(value_capacity)
+            .map_err(|_| RuntimeError::OutOfMemory)?;
+        storage
+            .frames
+            .try_reserve_exact(frame_capacity)
+            .map_err(|_| RuntimeError::OutOfMemory)?;
+        storage
+            .host_params
+            .try_reserve_exact(host_params_capacity)
+            .map_err(|_| RuntimeError::OutOfMemory)?;
+        Ok(storage)
+    }
+
+    /// Returns the number of value stack slots this storage can hold
+    pub fn value_capacity(&self) -> usize {
+        self.slots.capacity()
+    }
+
+    /// Returns the number of call frames this storage can hold
+    pub fn frame_capacity(&self) -> usize {
+        self.frames.capacity()
+    }
+
+    /// Returns the maximum number of parameters and return values of host calls this storage can hold
+    pub fn host_params_capacity(&self) -> usize {
+        self.host_params.capacity()
+    }
+
+    /// Puts the value buffer returned in [`RunState::Finished`](crate::resumable::RunState::Finished) back into this
+    /// storage, so that it can be used for another resumable
+    pub fn reclaim(&mut self, mut values: Vec<Value>) {
+        values.clear();
+        self.values = values;
+    }
+
+    /// Moves `params` into the host call buffer of this storage and returns the buffer
+    pub(crate) fn take_host_call_params(
+        &mut self,
+        params: &[Value],
+    ) -> Result<Vec<Value>, RuntimeError> {
+        if self.host_params.capacity() < params.len() {
+            return Err(RuntimeError::StackExhaustion);
+        }
+        let mut host_params = mem::take(&mut self.host_params);
+        host_params.clear();
+        // the capacity was checked above, so this does not allocate
+        host_params.extend_from_slice(params);
+        Ok(host_params)
+    }
+
+    /// Moves the return values of a host call, which was called without any Wasm code, into the value buffer of this
+    /// storage and returns the value buffer. The buffer of the return values is kept for the next host call.
+    pub(crate) fn take_host_call_return_values(
+        &mut self,
+        mut return_values: Vec<Value>,
+    ) -> Result<Vec<Value>, RuntimeError> {
+        if self.values.capacity() < return_values.len() {
+            return Err(RuntimeError::StackExhaustion);
+        }
+        let mut values = mem::take(&mut self.values);
+        values.clear();
+        // the capacity was checked above, so this does not allocate
+        values.append(&mut return_values);
+        self.host_params = return_values;
+        Ok(values)
+    }
+}
 
 /// The stack at runtime containing
 /// 1. Values
@@ -19,118 +368,303 @@ use crate::RuntimeError;
 /// See <https://webassembly.github.io/spec/core/exec/runtime.html#stack>
 #[derive(Debug)]
 pub(crate) struct Stack {
-    /// WASM values on the stack, i.e. the actual data that instructions operate on
-    values: Vec<Value>,
+    /// WASM values on the stack, i.e. the actual data that instructions operate on, as untyped [`Slot`]s
+    slots: Vec<Slot>,
 
     /// Call frames
     ///
     /// Each time a function is called, a new frame is pushed, whenever a function returns, a frame is popped
     frames: Vec<CallFrame>,
+
+    /// Buffer for the return values, which is only used if the stack has a fixed capacity
+    values: Vec<Value>,
+
+    /// Buffer for the parameters of host calls, which is only used if the stack has a fixed capacity
+    host_params: Vec<Value>,
+
+    /// Whether this stack uses a [`StackStorage`], which must never be reallocated
+    fixed_capacity: bool,
+
+    /// The maximum sizes of the value stack and the call stack
+    limits: StackLimits,
 }
 
 impl Stack {
-    pub fn new<T: Config>(
-        params_to_base_call_frame: Vec<Value>,
-        base_call_frame_func_ty: &FuncType,
-        base_call_frame_remaining_locals: &[ValType],
+    pub fn new(
+        limits: StackLimits,
+        params_to_base_call_frame: &[Value],
+        base_call_frame_layout: &FrameLayout,
     ) -> Result<Self, RuntimeError> {
+        let mut slots = Vec::new();
+        slots
+            .try_reserve(base_call_frame_layout.param_slots)
+            .map_err(|_| RuntimeError::OutOfMemory)?;
         let mut stack = Self {
-            values: params_to_base_call_frame,
+            slots,
             frames: Vec::new(),
+            values: Vec::new(),
+            host_params: Vec::new(),
+            fixed_capacity: false,
+            limits,
         };
+        stack.push_base_call_frame(params_to_base_call_frame, base_call_frame_layout)?;
+        Ok(stack)
+    }
+
+    /// Creates a new stack with a fixed capacity, which uses the given storage
+    pub fn new_in(
+        limits: StackLimits,
+        storage: StackStorage,
+        params_to_base_call_frame: &[Value],
+        base_call_frame_layout: &FrameLayout,
+    ) -> Result<Self, RuntimeError> {
+        let StackStorage {
+            mut slots,
+            values,
+            mut frames,
+            host_params,
+        } = storage;
+        slots.clear();
+        frames.clear();
 
+        let mut stack = Self {
+            slots,
+            frames,
+            values,
+            host_params,
+            fixed_capacity: true,
+            limits,
+        };
+        stack.push_base_call_frame(params_to_base_call_frame, base_call_frame_layout)?;
+        Ok(stack)
+    }
+
+    /// Creates a stack without any call frames, which only holds the return values of a host function that was
+    /// invoked directly instead of being called by Wasm code. Such a stack is finished right away, see
+    /// [`Stack::into_finished`]. Like with [`StackStorage::take_host_call_return_values`], the buffer of the return
+    /// values is kept for the next host call if there is a storage.
+    pub(crate) fn new_returned(
+        limits: StackLimits,
+        storage: Option<StackStorage>,
+        mut return_values: Vec<Value>,
+    ) -> Result<Self, RuntimeError> {
+        let mut stack = match storage {
+            Some(StackStorage {
+                mut slots,
+                values,
+                mut frames,
+                host_params: _,
+            }) => {
+                slots.clear();
+                frames.clear();
+                Self {
+                    slots,
+                    frames,
+                    values,
+                    host_params: Vec::new(),
+                    fixed_capacity: true,
+                    limits,
+                }
+            }
+            None => Self {
+                slots: Vec::new(),
+                frames: Vec::new(),
+                values: Vec::new(),
+                host_params: Vec::new(),
+                fixed_capacity: false,
+                limits,
+            },
+        };
+        for value in &return_values {
+            for_each_slot(*value, |slot| {
+                push_bounded(&mut stack.slots, slot, stack.fixed_capacity)
+            })?;
+        }
+        if stack.fixed_capacity {
+            return_values.clear();
+            stack.host_params = return_values;
+        }
+        Ok(stack)
+    }
+
+    /// Returns the storage of this stack, if it has a fixed capacity
+    pub(crate) fn into_storage(self) -> Option<StackStorage> {
+        self.fixed_capacity.then_some(StackStorage {
+            slots: self.slots,
+            values: self.values,
+            frames: self.frames,
+            host_params: self.host_params,
+        })
+    }
+
+    fn push_base_call_frame(
+        &mut self,
+        params_to_base_call_frame: &[Value],
+        base_call_frame_layout: &FrameLayout,
+    ) -> Result<(), RuntimeError> {
         // The following is a copy of `Stack::push_call_frame` except that it
-        // only partially initializes the call frame's fields.
+        // first pushes the parameters and only partially initializes the call
+        // frame's fields.
 
-        if stack.call_frame_count() > T::MAX_CALL_STACK_SIZE {
+        if self.call_frame_count() > self.limits.max_call_stack_size {
             return Err(RuntimeError::StackExhaustion);
         }
 
-        debug_assert!(
-            stack.values.len() >= base_call_frame_func_ty.params.valtypes.len(),
-            "when pushing a new call frame, at least as many values need to be on the stack as required by the new call frames's function"
-        );
+        for param in params_to_base_call_frame {
+            for_each_slot(*param, |slot| {
+                push_bounded(&mut self.slots, slot, self.fixed_capacity)
+            })?;
+        }
 
-        // the topmost `param_count` values are transferred into/consumed by this new call frame
-        let param_count = base_call_frame_func_ty.params.valtypes.len();
-        let call_frame_base_idx = stack.values.len() - param_count;
+        debug_assert_eq!(
+            self.slots.len(),
+            base_call_frame_layout.param_slots,
+            "the parameters of the base call frame must match its function's type"
+        );
 
         // after the params, put the additional locals
-        for local in base_call_frame_remaining_locals {
-            stack.values.push(Value::default_from_ty(*local));
-        }
+        let call_frame_base_idx = 0;
+        self.push_local_defaults(base_call_frame_layout)?;
 
         // now that the locals are all populated, the actual stack section of this call frame begins
-        let value_stack_base_idx = stack.values.len();
+        let value_stack_base_idx = self.slots.len();
+
+        push_bounded(
+            &mut self.frames,
+            CallFrame {
+                return_func_addr: MaybeUninit::uninit(),
+                return_addr: MaybeUninit::uninit(),
+                value_stack_base_idx,
+                call_frame_base_idx,
+                return_slot_count: base_call_frame_layout.return_slots,
+                return_stp: MaybeUninit::uninit(),
+            },
+            self.fixed_capacity,
+        )
+    }
 
-        stack.frames.push(CallFrame {
-            return_func_addr: MaybeUninit::uninit(),
-            return_addr: MaybeUninit::uninit(),
-            value_stack_base_idx,
-            call_frame_base_idx,
-            return_value_count: base_call_frame_func_ty.returns.valtypes.len(),
-            return_stp: MaybeUninit::uninit(),
-        });
+    /// Returns the return values of type `return_types` on this stack after execution has finished. If this stack has
+    /// a fixed capacity, its storage is returned as well, with the value buffer being moved out of it.
+    pub(crate) fn into_finished(
+        mut self,
+        return_types: &[ValType],
+    ) -> Result<(Vec<Value>, Option<StackStorage>), RuntimeError> {
+        let mut values = mem::take(&mut self.values);
+        values.clear();
+        if !self.fixed_capacity {
+            values
+                .try_reserve_exact(return_types.len())
+                .map_err(|_| RuntimeError::OutOfMemory)?;
+        }
 
-        Ok(stack)
-    }
+        let mut start = self.slots.len() - slot_count(return_types);
+        for ty in return_types {
+            let end = start + ty.slots();
+            // the value buffer of a storage can hold as many values as there are slots, so this does not allocate
+            values.try_push(value_from_slots(*ty, &self.slots[start..end]))?;
+            start = end;
+        }
 
-    pub(super) fn into_values(self) -> Vec<Value> {
-        self.values
+        let storage = self.fixed_capacity.then_some(StackStorage {
+            slots: self.slots,
+            values: Vec::new(),
+            frames: self.frames,
+            host_params: self.host_params,
+        });
+        Ok((values, storage))
     }
 
-    /// Pop a value from the value stack
-    pub fn pop_value(&mut self) -> Value {
+    /// Pops the topmost value from the value stack as a value of type `T`
+    #[inline(always)]
+    pub fn pop<T: StackValue>(&mut self) -> T {
         // If there is at least one call frame, we shall not pop values past the current
         // call frame. However, there is one legitimate reason to pop when there is **no** current
         // call frame: after the outermost function returns, to extract the final return values of
         // this interpreter invocation.
         debug_assert!(
             if !self.frames.is_empty() {
-                self.values.len() > self.current_call_frame().value_stack_base_idx
+                self.slots.len() >= self.current_call_frame().value_stack_base_idx + T::SLOTS
             } else {
                 true
             },
             "can not pop values past the current call frame"
         );
 
-        self.values.pop().unwrap_validated()
+        let start = self.slots.len() - T::SLOTS;
+        let value = T::from_slots(&self.slots[start..]);
+        self.slots.truncate(start);
+        value
     }
 
-    /// Returns a cloned copy of the top value on the stack, or `None` if the stack is empty
-    pub fn peek_value(&self) -> Option<Value> {
-        self.values.last().copied()
+    /// Pops the topmost value of type `ty` from the value stack
+    pub fn pop_value(&mut self, ty: ValType) -> Value {
+        let start = self.slots.len() - ty.slots();
+        let value = value_from_slots(ty, &self.slots[start..]);
+        self.slots.truncate(start);
+        value
     }
 
     /// Push a value to the value stack
-    pub fn push_value<C: Config>(&mut self, value: Value) -> Result<(), RuntimeError> {
+    #[inline(always)]
+    pub fn push_value(&mut self, value: Value) -> Result<(), RuntimeError> {
+        for_each_slot(value, |slot| self.push_slot(slot))
+    }
+
+    /// Push a single slot to the value stack
+    #[inline(always)]
+    fn push_slot(&mut self, slot: Slot) -> Result<(), RuntimeError> {
         // check for value stack exhaustion
-        if self.values.len() > C::MAX_VALUE_STACK_SIZE {
+        if self.slots.len() > self.limits.max_value_stack_size {
             return Err(RuntimeError::StackExhaustion);
         }
 
-        // push the value
-        self.values.push(value);
+        // push the slot
+        push_bounded(&mut self.slots, slot, self.fixed_capacity)
+    }
+
+    /// Drops the topmost value, which occupies `slots` slots
+    #[inline(always)]
+    pub fn drop_value(&mut self, slots: usize) {
+        self.slots.truncate(self.slots.len() - slots);
+    }
+
+    /// Implements `select` for two values occupying `slots` slots each, which are below the already popped condition
+    ///
+    /// Keeps the lower value if `keep_first` is set, otherwise the upper one.
+    #[inline(always)]
+    pub fn select(&mut self, slots: usize, keep_first: bool) {
+        if keep_first {
+            self.drop_value(slots);
+        } else {
+            self.remove_in_between(slots, slots);
+        }
+    }
 
+    /// Pushes a copy of the local at `offset` of the current call frame, which occupies `slots` slots
+    #[inline(always)]
+    pub fn push_local(&mut self, offset: usize, slots: usize) -> Result<(), RuntimeError> {
+        let start = self.current_call_frame().call_frame_base_idx + offset;
+        for idx in start..start + slots {
+            let slot = *self.slots.get(idx).unwrap_validated();
+            self.push_slot(slot)?;
+        }
         Ok(())
     }
 
-    /// Returns a shared reference to a specific local by its index in the current call frame.
-    pub fn get_local(&self, idx: LocalIdx) -> &Value {
-        let idx = idx.into_inner().into_usize();
-        let call_frame_base_idx = self.current_call_frame().call_frame_base_idx;
-        self.values
-            .get(call_frame_base_idx + idx)
-            .unwrap_validated()
+    /// Pops the topmost value into the local at `offset` of the current call frame, which occupies `slots` slots
+    #[inline(always)]
+    pub fn set_local(&mut self, offset: usize, slots: usize) {
+        let top = self.slots.len() - slots;
+        self.tee_local(offset, slots);
+        self.slots.truncate(top);
     }
 
-    /// Returns a mutable reference to a specific local by its index in the current call frame.
-    pub fn get_local_mut(&mut self, idx: LocalIdx) -> &mut Value {
-        let idx = idx.into_inner().into_usize();
-        let call_frame_base_idx = self.current_call_frame().call_frame_base_idx;
-        self.values
-            .get_mut(call_frame_base_idx + idx)
-            .unwrap_validated()
+    /// Copies the topmost value into the local at `offset` of the current call frame, which occupies `slots` slots
+    #[inline(always)]
+    pub fn tee_local(&mut self, offset: usize, slots: usize) {
+        let start = self.current_call_frame().call_frame_base_idx + offset;
+        let top = self.slots.len() - slots;
+        self.slots.copy_within(top.., start);
     }
 
     /// Get a shared reference to the current [`CallFrame`]
@@ -147,19 +681,19 @@ impl Stack {
             return_func_addr,
             return_addr,
             call_frame_base_idx,
-            return_value_count,
+            return_slot_count,
             return_stp,
             ..
         } = self.frames.pop().unwrap_validated();
 
-        let remove_count = self.values.len() - call_frame_base_idx - return_value_count;
+        let remove_count = self.slots.len() - call_frame_base_idx - return_slot_count;
 
-        self.remove_in_between(remove_count, return_value_count);
+        self.remove_in_between(remove_count, return_slot_count);
 
         debug_assert_eq!(
-            self.values.len(),
-            call_frame_base_idx + return_value_count,
-            "after a function call finished, the stack must have exactly as many values as it had before calling the function plus the number of function return values"
+            self.slots.len(),
+            call_frame_base_idx + return_slot_count,
+            "after a function call finished, the stack must have exactly as many slots as it had before calling the function plus the slots of the function return values"
         );
 
         // If this was the base call frame, do not return its uninitialized fields
@@ -181,46 +715,59 @@ impl Stack {
 
     /// Push a call frame to the call stack
     ///
-    /// Takes the current [`Self::values`]'s length as [`CallFrame::value_stack_base_idx`].
-    pub fn push_call_frame<C: Config>(
+    /// Takes the current [`Self::slots`]'s length after pushing the declared locals as
+    /// [`CallFrame::value_stack_base_idx`].
+    pub fn push_call_frame(
         &mut self,
         return_func_addr: FuncAddr,
-        func_ty: &FuncType,
-        remaining_locals: &[ValType],
+        layout: &FrameLayout,
         return_addr: usize,
         return_stp: usize,
     ) -> Result<(), RuntimeError> {
         // check for call stack exhaustion
-        if self.call_frame_count() > C::MAX_CALL_STACK_SIZE {
+        if self.call_frame_count() > self.limits.max_call_stack_size {
             return Err(RuntimeError::StackExhaustion);
         }
 
         debug_assert!(
-            self.values.len() >= func_ty.params.valtypes.len(),
-            "when pushing a new call frame, at least as many values need to be on the stack as required by the new call frames's function"
+            self.slots.len() >= layout.param_slots,
+            "when pushing a new call frame, at least as many slots need to be on the stack as required by the new call frames's function"
         );
 
-        // the topmost `param_count` values are transferred into/consumed by this new call frame
-        let param_count = func_ty.params.valtypes.len();
-        let call_frame_base_idx = self.values.len() - param_count;
+        // the topmost `param_slots` slots are transferred into/consumed by this new call frame
+        let call_frame_base_idx = self.slots.len() - layout.param_slots;
 
         // after the params, put the additional locals
-        for local in remaining_locals {
-            self.values.push(Value::default_from_ty(*local));
-        }
+        self.push_local_defaults(layout)?;
 
         // now that the locals are all populated, the actual stack section of this call frame begins
-        let value_stack_base_idx = self.values.len();
-
-        self.frames.push(CallFrame {
-            return_func_addr: MaybeUninit::new(return_func_addr),
-            return_addr: MaybeUninit::new(return_addr),
-            value_stack_base_idx,
-            call_frame_base_idx,
-            return_value_count: func_ty.returns.valtypes.len(),
-            return_stp: MaybeUninit::new(return_stp),
-        });
+        let value_stack_base_idx = self.slots.len();
+
+        push_bounded(
+            &mut self.frames,
+            CallFrame {
+                return_func_addr: MaybeUninit::new(return_func_addr),
+                return_addr: MaybeUninit::new(return_addr),
+                value_stack_base_idx,
+                call_frame_base_idx,
+                return_slot_count: layout.return_slots,
+                return_stp: MaybeUninit::new(return_stp),
+            },
+            self.fixed_capacity,
+        )
+    }
 
+    /// Pushes the initial slots of the declared locals of a function
+    fn push_local_defaults(&mut self, layout: &FrameLayout) -> Result<(), RuntimeError> {
+        let count = layout.local_defaults.len();
+        if self.fixed_capacity && self.slots.capacity() - self.slots.len() < count {
+            return Err(RuntimeError::StackExhaustion);
+        }
+        self.slots
+            .try_reserve(count)
+            .map_err(|_| RuntimeError::OutOfMemory)?;
+        // the capacity was reserved above, so this does not allocate
+        self.slots.extend_from_slice(&layout.local_defaults);
         Ok(())
     }
 
@@ -229,32 +776,73 @@ impl Stack {
         self.frames.len()
     }
 
-    /// Pop `n` elements from the value stack's tail as an iterator, with the first element being
-    /// closest to the **bottom** of the value stack
+    /// Pops the parameters of a host call with the types `types` as values, with the first element being closest to
+    /// the **bottom** of the value stack
     ///
-    /// Note that this is providing the values in reverse order compared to popping `n` values
-    /// (which would yield the element closest to the **top** of the value stack first).
-    pub fn pop_tail_iter(&mut self, n: usize) -> Drain<'_, Value> {
-        let start = self.values.len() - n;
-        self.values.drain(start..)
+    /// If this stack has a fixed capacity, the values are moved into the host call buffer of its [`StackStorage`]
+    /// instead of a new allocation. The buffer is handed back through [`Stack::recycle_host_values`].
+    pub fn pop_host_call_params(&mut self, types: &[ValType]) -> Result<Vec<Value>, RuntimeError> {
+        let mut params = if self.fixed_capacity {
+            if self.host_params.capacity() < types.len() {
+                return Err(RuntimeError::StackExhaustion);
+            }
+            let mut params = mem::take(&mut self.host_params);
+            params.clear();
+            params
+        } else {
+            let mut params = Vec::new();
+            params
+                .try_reserve_exact(types.len())
+                .map_err(|_| RuntimeError::OutOfMemory)?;
+            params
+        };
+
+        let params_start = self.slots.len() - slot_count(types);
+        let mut start = params_start;
+        for ty in types {
+            let end = start + ty.slots();
+            // the capacity was checked above, so this does not allocate
+            params.push(value_from_slots(*ty, &self.slots[start..end]));
+            start = end;
+        }
+        self.slots.truncate(params_start);
+        Ok(params)
+    }
+
+    /// Takes back a buffer of host call values, so that it can be reused for the parameters of the next host call
+    /// if this stack has a fixed capacity
+    pub fn recycle_host_values(&mut self, mut values: Vec<Value>) {
+        if self.fixed_capacity {
+            values.clear();
+            self.host_params = values;
+        }
     }
 
-    /// Remove `remove_count` values from the stack, keeping the topmost `keep_count` values
+    /// Remove `remove_count` slots from the stack, keeping the topmost `keep_count` slots
     ///
-    /// From the stack, remove `remove_count` elements, by sliding down the `keep_count` topmost
-    /// values `remove_count` positions.
+    /// From the stack, remove `remove_count` slots, by sliding down the `keep_count` topmost
+    /// slots `remove_count` positions.
     ///
     /// **Effects**
     ///
-    /// - after the operation, [`Stack`] will contain `remove_count` fewer elements
-    /// - `keep_count` topmost elements will be identical before and after the operation
-    /// - all elements below the `remove_count + keep_count` topmost stack entry remain
+    /// - after the operation, [`Stack`] will contain `remove_count` fewer slots
+    /// - `keep_count` topmost slots will be identical before and after the operation
+    /// - all slots below the `remove_count + keep_count` topmost stack entry remain
     pub fn remove_in_between(&mut self, remove_count: usize, keep_count: usize) {
-        let len = self.values.len();
-        self.values
+        let len = self.slots.len();
+        self.slots
             .copy_within(len - keep_count.., len - keep_count - remove_count);
-        self.values.truncate(len - remove_count);
+        self.slots.truncate(len - remove_count);
+    }
+}
+
+/// Pushes a value onto a vector, which must not be reallocated if it has a fixed capacity
+#[inline(always)]
+fn push_bounded<T>(vec: &mut Vec<T>, value: T, fixed_capacity: bool) -> Result<(), RuntimeError> {
+    if fixed_capacity && vec.len() == vec.capacity() {
+        return Err(RuntimeError::StackExhaustion);
     }
+    vec.try_push(value)
 }
 
 /// The [WASM spec](https://webassembly.github.io/spec/core/exec/runtime.html#stack) calls this `Activations`, however it refers to the call frames of functions.
@@ -272,23 +860,23 @@ pub(crate) struct CallFrame {
     /// all other call frames.
     pub return_addr: MaybeUninit<usize>,
 
-    /// The index to the lowermost value in [`Stack::values`] belonging to this [`CallFrame`]'s
+    /// The index to the lowermost slot in [`Stack::slots`] belonging to this [`CallFrame`]'s
     /// stack
     ///
-    /// Values below this may still belong to this [`CallFrame`], but they are locals. Consequently,
+    /// Slots below this may still belong to this [`CallFrame`], but they are locals. Consequently,
     /// this is the lowest index down to which the stack may be popped in this [`CallFrame`].
     /// However, clearing up this [`CallFrame`] may require further popping, down to (and
     /// including!) the index stored in [`Self::call_frame_base_idx`].
     pub value_stack_base_idx: usize,
 
-    /// The index to the lowermost value on [`Stack::values`] that belongs to this [`CallFrame`]
+    /// The index to the lowermost slot on [`Stack::slots`] that belongs to this [`CallFrame`]
     ///
-    /// Clearing this [`CallFrame`] requires popping all elements on [`Stack::values`] down to (and
+    /// Clearing this [`CallFrame`] requires popping all slots on [`Stack::slots`] down to (and
     /// including!) this index.
     pub call_frame_base_idx: usize,
 
-    /// Number of return values to retain on [`Stack::values`] when unwinding/popping a [`CallFrame`]
-    pub return_value_count: usize,
+    /// Number of slots of the return values to retain on [`Stack::slots`] when unwinding/popping a [`CallFrame`]
+    pub return_slot_count: usize,
 
     /// Value that the stp has to be set to when this function returns
     ///
@@ -0,0 +1,252 @@
+//! A built-in watchpoint facility, implemented on top of the access hooks of [`Config`]
+//!
+//! [`Watchpoints`] is a [`ConfigLayer`] around an arbitrary inner [`Config`] and forwards all constants, cost functions
+//! and hooks to it. Additionally, it interrupts execution whenever a watched memory range or global is accessed.
+//! Execution can then be continued through the [`RunState::Interrupted`](crate::resumable::RunState::Interrupted)
+//! returned by the interpreter with [`InterruptReason::AccessHook`](crate::resumable::InterruptReason::AccessHook).
+
+use core::ops::{ControlFlow, Range};
+
+use alloc::vec::Vec;
+
+use crate::{
+    addrs::{GlobalAddr, MemAddr},
+    config::{Config, ConfigLayer},
+    Value,
+};
+
+/// The kind of memory accesses a [`Watchpoint::Memory`] triggers on
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WatchKind {
+    Load,
+    Store,
+    LoadOrStore,
+}
+
+impl WatchKind {
+    fn matches_load(self) -> bool {
+        matches!(self, WatchKind::Load | WatchKind::LoadOrStore)
+    }
+
+    fn matches_store(self) -> bool {
+        matches!(self, WatchKind::Store | WatchKind::LoadOrStore)
+    }
+}
+
+/// A single watchpoint
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Watchpoint {
+    /// Triggers on every access of kind `kind` that overlaps with the byte range `range` of the memory at `mem_addr`
+    Memory {
+        mem_addr: MemAddr,
+        range: Range<usize>,
+        kind: WatchKind,
+    },
+    /// Triggers on every `global.set` of the global at the given address
+    Global(GlobalAddr),
+}
+
+/// An identifier for a watchpoint that was added to [`Watchpoints`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WatchpointId(usize);
+
+/// The access that triggered a watchpoint
+#[derive(Clone, Debug, PartialEq)]
+pub enum WatchedAccess {
+    /// `len` bytes starting at `address` were loaded from the memory at `mem_addr`
+    Load {
+        mem_addr: MemAddr,
+        address: usize,
+        len: usize,
+    },
+    /// `len` bytes starting at `address` were stored to the memory at `mem_addr`
+    Store {
+        mem_addr: MemAddr,
+        address: usize,
+        len: usize,
+    },
+    /// `value` was written to the global at `global_addr`
+    GlobalSet {
+        global_addr: GlobalAddr,
+        value: Value,
+    },
+}
+
+/// A record of a watchpoint that was hit
+#[derive(Clone, Debug, PartialEq)]
+pub struct WatchpointHit {
+    pub id: WatchpointId,
+    pub access: WatchedAccess,
+}
+
+/// A [`Config`] that interrupts execution whenever one of its [`Watchpoint`]s is hit
+///
+/// All hits are recorded and can be retrieved through [`Watchpoints::take_hits`] once execution was interrupted.
+#[derive(Debug, Default)]
+pub struct Watchpoints<C: Config = ()> {
+    watchpoints: Vec<Option<Watchpoint>>,
+    hits: Vec<WatchpointHit>,
+    /// The wrapped configuration
+    pub inner: C,
+}
+
+impl<C: Config> Watchpoints<C> {
+    /// Creates a new set of watchpoints without any watchpoints, wrapping the given configuration
+    pub fn new(inner: C) -> Self {
+        Self {
+            watchpoints: Vec::new(),
+            hits: Vec::new(),
+            inner,
+        }
+    }
+
+    /// Adds a new watchpoint and returns its id
+    pub fn add(&mut self, watchpoint: Watchpoint) -> WatchpointId {
+        self.watchpoints.push(Some(watchpoint));
+        WatchpointId(self.watchpoints.len() - 1)
+    }
+
+    /// Removes the watchpoint with the given id and returns it, if it still exists
+    pub fn remove(&mut self, id: WatchpointId) -> Option<Watchpoint> {
+        self.watchpoints.get_mut(id.0)?.take()
+    }
+
+    /// Returns the watchpoint with the given id, if it still exists
+    pub fn get(&self, id: WatchpointId) -> Option<&Watchpoint> {
+        self.watchpoints.get(id.0)?.as_ref()
+    }
+
+    /// Returns all hits recorded since the last call to this method, leaving no recorded hits behind
+    pub fn take_hits(&mut self) -> Vec<WatchpointHit> {
+        core::mem::take(&mut self.hits)
+    }
+
+    fn check_memory_access(
+        &mut self,
+        mem_addr: MemAddr,
+        address: usize,
+        len: usize,
+        is_store: bool,
+    ) -> ControlFlow<()> {
+        let mut flow = ControlFlow::Continue(());
+        for (idx, watchpoint) in self.watchpoints.iter().enumerate() {
+            let Some(Watchpoint::Memory {
+                mem_addr: watched_mem_addr,
+                range,
+                kind,
+            }) = watchpoint
+            else {
+                continue;
+            };
+
+            let kind_matches = if is_store {
+                kind.matches_store()
+            } else {
+                kind.matches_load()
+            };
+            // the access covers the bytes `address..address + len`, which must overlap with the watched range
+            let overlaps = address < range.end && range.start < address.saturating_add(len);
+
+            if *watched_mem_addr == mem_addr && kind_matches && overlaps && len > 0 {
+                let access = if is_store {
+                    WatchedAccess::Store {
+                        mem_addr,
+                        address,
+                        len,
+                    }
+                } else {
+                    WatchedAccess::Load {
+                        mem_addr,
+                        address,
+                        len,
+                    }
+                };
+                self.hits.push(WatchpointHit {
+                    id: WatchpointId(idx),
+                    access,
+                });
+                flow = ControlFlow::Break(());
+            }
+        }
+        flow
+    }
+}
+
+/// Combines two control flows, breaking if any of them breaks
+fn either_breaks(a: ControlFlow<()>, b: ControlFlow<()>) -> ControlFlow<()> {
+    if a.is_break() || b.is_break() {
+        ControlFlow::Break(())
+    } else {
+        ControlFlow::Continue(())
+    }
+}
+
+impl<C: Config> ConfigLayer for Watchpoints<C> {
+    type Inner = C;
+
+    fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    fn inner_mut(&mut self) -> &mut C {
+        &mut self.inner
+    }
+
+    fn memory_load_hook(
+        &mut self,
+        mem_addr: MemAddr,
+        address: usize,
+        bytes: &[u8],
+    ) -> ControlFlow<()> {
+        let inner_flow = self.inner.memory_load_hook(mem_addr, address, bytes);
+        let flow = self.check_memory_access(mem_addr, address, bytes.len(), false);
+        either_breaks(inner_flow, flow)
+    }
+
+    fn memory_store_hook(
+        &mut self,
+        mem_addr: MemAddr,
+        address: usize,
+        bytes: &[u8],
+    ) -> ControlFlow<()> {
+        let inner_flow = self.inner.memory_store_hook(mem_addr, address, bytes);
+        let flow = self.check_memory_access(mem_addr, address, bytes.len(), true);
+        either_breaks(inner_flow, flow)
+    }
+
+    fn memory_bulk_load_hook(
+        &mut self,
+        mem_addr: MemAddr,
+        address: usize,
+        len: usize,
+    ) -> ControlFlow<()> {
+        let inner_flow = self.inner.memory_bulk_load_hook(mem_addr, address, len);
+        let flow = self.check_memory_access(mem_addr, address, len, false);
+        either_breaks(inner_flow, flow)
+    }
+
+    fn memory_bulk_store_hook(
+        &mut self,
+        mem_addr: MemAddr,
+        address: usize,
+        len: usize,
+    ) -> ControlFlow<()> {
+        let inner_flow = self.inner.memory_bulk_store_hook(mem_addr, address, len);
+        let flow = self.check_memory_access(mem_addr, address, len, true);
+        either_breaks(inner_flow, flow)
+    }
+
+    fn global_set_hook(&mut self, global_addr: GlobalAddr, value: Value) -> ControlFlow<()> {
+        let mut flow = self.inner.global_set_hook(global_addr, value);
+        for (idx, watchpoint) in self.watchpoints.iter().enumerate() {
+            if *watchpoint == Some(Watchpoint::Global(global_addr)) {
+                self.hits.push(WatchpointHit {
+                    id: WatchpointId(idx),
+                    access: WatchedAccess::GlobalSet { global_addr, value },
+                });
+                flow = ControlFlow::Break(());
+            }
+        }
+        flow
+    }
+}
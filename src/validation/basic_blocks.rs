@@ -0,0 +1,120 @@
+//! Splits validated code into basic blocks, which allows fuel-metered execution to charge the fuel of many
+//! instructions at once
+//!
+//! A basic block ends after every instruction that may transfer control, i.e. after all control instructions and
+//! calls. Additionally, every branch target recorded in the sidetable and every function entry starts a new basic
+//! block. Instructions that are charged per element are excluded from basic blocks, because their cost is only known
+//! once their operands are available.
+
+use alloc::{collections::btree_set::BTreeSet, vec::Vec};
+
+use crate::{
+    config::Config,
+    core::{
+        reader::{span::Span, WasmReader},
+        sidetable::{BasicBlock, BlockFuel, BlockFuelTable, Sidetable},
+    },
+    instructions::{
+        flat_cost, is_charged_per_element, read_function_body, read_instruction, Control,
+    },
+};
+
+/// Finds all basic blocks of the validated functions whose code expressions, i.e. their bodies without the
+/// declarations of their locals, are `code_exprs`. The basic blocks are sorted by their first instruction if the code
+/// expressions are sorted.
+pub(crate) fn find_basic_blocks(
+    wasm: &[u8],
+    code_exprs: &[Span],
+    sidetable: &Sidetable,
+) -> Vec<BasicBlock> {
+    let branch_targets: BTreeSet<usize> = sidetable.iter().map(|entry| entry.pc).collect();
+
+    let mut wasm = WasmReader::new(wasm);
+    let mut basic_blocks = Vec::new();
+    for code_expr in code_exprs {
+        wasm.move_start_to(*code_expr)
+            .expect("code expression spans to always be valid");
+        let instructions = read_function_body(&mut wasm);
+        let function_end = wasm.pc;
+
+        let mut block_start = None;
+        let mut close_block = |block_start: Option<usize>, end: usize| {
+            if let Some(pc) = block_start {
+                basic_blocks.push(BasicBlock { pc, end });
+            }
+        };
+
+        for (i, instruction) in instructions.iter().enumerate() {
+            let next_offset = instructions
+                .get(i + 1)
+                .map_or(function_end, |next| next.offset);
+
+            if branch_targets.contains(&instruction.offset) {
+                close_block(block_start.take(), instruction.offset);
+            }
+
+            if is_charged_per_element(instruction.opcode) {
+                close_block(block_start.take(), instruction.offset);
+                continue;
+            }
+
+            block_start.get_or_insert(instruction.offset);
+            if instruction.control != Control::None {
+                close_block(block_start.take(), next_offset);
+            }
+        }
+        close_block(block_start, function_end);
+    }
+    basic_blocks
+}
+
+/// Computes the fuel of all `basic_blocks` using the flat costs of `config`
+///
+/// Basic blocks that cost no fuel at all or whose cost overflows are left out, as these are charged per instruction.
+pub(crate) fn block_fuel_table<C: Config>(
+    config: &C,
+    wasm: &[u8],
+    basic_blocks: &[BasicBlock],
+) -> BlockFuelTable {
+    let mut wasm = WasmReader::new(wasm);
+    let mut table = BlockFuelTable::default();
+    for basic_block in basic_blocks {
+        wasm.pc = basic_block.pc;
+        let mut fuel: Option<u64> = Some(0);
+        while wasm.pc < basic_block.end {
+            let instruction = read_instruction(&mut wasm);
+            fuel = fuel.and_then(|fuel| fuel.checked_add(flat_cost(config, instruction.opcode)));
+        }
+        if let Some(fuel) = fuel.filter(|fuel| *fuel > 0) {
+            table.push(
+                basic_block.pc,
+                BlockFuel {
+                    end: basic_block.end,
+                    fuel,
+                },
+            );
+        }
+    }
+    table
+}
+
+/// Returns the fuel of the instructions of the basic block `pc..end` that follow the instruction which contains or
+/// ends at `executed_until`, i.e. the fuel that was charged for the basic block but not used
+pub(crate) fn unused_block_fuel<C: Config>(
+    config: &C,
+    wasm: &[u8],
+    pc: usize,
+    end: usize,
+    executed_until: usize,
+) -> u64 {
+    let mut wasm = WasmReader::new(wasm);
+    wasm.pc = pc;
+    while wasm.pc < executed_until {
+        read_instruction(&mut wasm);
+    }
+    let mut fuel: u64 = 0;
+    while wasm.pc < end {
+        fuel += flat_cost(config, read_instruction(&mut wasm).opcode);
+    }
+    fuel
+}
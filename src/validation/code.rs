@@ -1,11 +1,13 @@
 use core::iter;
 
 use alloc::collections::btree_set::BTreeSet;
+use alloc::sync::Arc;
 use alloc::vec::Vec;
 
+use crate::core::immediates::ImmediatesCache;
 use crate::core::indices::{
-    read_label_idx, DataIdx, ElemIdx, ExtendedIdxVec, FuncIdx, GlobalIdx, IdxVec, LocalIdx, MemIdx,
-    TableIdx, TypeIdx,
+    read_label_idx, DataIdx, ElemIdx, ExtendedIdxVec, FuncIdx, GlobalIdx, Idx, IdxVec, LocalIdx,
+    MemIdx, TableIdx, TypeIdx,
 };
 use crate::core::reader::section_header::{SectionHeader, SectionTy};
 use crate::core::reader::span::Span;
@@ -16,7 +18,10 @@ use crate::core::reader::types::{BlockType, FuncType, MemType, NumType, TableTyp
 use crate::core::reader::WasmReader;
 use crate::core::sidetable::{Sidetable, SidetableEntry};
 use crate::core::utils::ToUsizeExt;
-use crate::validation_stack::{LabelInfo, ValidationStack};
+use crate::core::v128_operands::V128Operands;
+use crate::validation::LocalFuncData;
+use crate::validation_stack::{LabelInfo, ValidationStack, ValidationStackEntry};
+use crate::value_stack::FrameLayout;
 use crate::{RefType, ValidationError};
 
 /// # Safety
@@ -43,6 +48,10 @@ pub unsafe fn validate_code_section(
     c_elems: &IdxVec<ElemIdx, ElemType>,
     validation_context_refs: &BTreeSet<FuncIdx>,
     sidetable: &mut Sidetable,
+    immediates: &mut ImmediatesCache,
+    v128_operands: &mut V128Operands,
+    max_operand_heights: &mut Vec<usize>,
+    local_funcs: &mut Vec<Arc<LocalFuncData>>,
 ) -> Result<Vec<(Span, usize)>, ValidationError> {
     assert_eq!(section_header.ty, SectionTy::Code);
     let code_block_spans_stps = wasm.read_vec_enumerated(|wasm, idx| {
@@ -62,13 +71,16 @@ pub unsafe fn validate_code_section(
         let func_block = wasm.make_span(func_size.into_usize())?;
         let previous_pc = wasm.pc;
 
+        let declared_locals = read_declared_locals(wasm)?;
+        let code_expr_start = wasm.pc;
+        let frame_layout = FrameLayout::new(&func_ty, &declared_locals)
+            .map_err(|_| ValidationError::OutOfMemory)?;
         let locals = {
             let params = func_ty.params.valtypes.iter().cloned();
-            let declared_locals = read_declared_locals(wasm)?;
             params.chain(declared_locals).collect::<Vec<ValType>>()
         };
 
-        let mut stack = ValidationStack::new_for_func(func_ty);
+        let mut stack = ValidationStack::new_for_func(func_ty.clone());
         let stp = sidetable.len();
 
         // SAFETY: The caller ensures the same safety requirements for the same
@@ -78,6 +90,8 @@ pub unsafe fn validate_code_section(
                 wasm,
                 &mut stack,
                 sidetable,
+                immediates,
+                v128_operands,
                 &locals,
                 c_globals,
                 fn_types,
@@ -95,6 +109,13 @@ pub unsafe fn validate_code_section(
             return Err(ValidationError::CodeExprHasTrailingInstructions);
         }
 
+        max_operand_heights.push(stack.max_slots());
+        local_funcs.push(Arc::new(LocalFuncData {
+            function_type: func_ty,
+            frame_layout,
+            code_expr: Span::new(code_expr_start, wasm.pc - code_expr_start),
+        }));
+
         Ok((func_block, stp))
     })?;
 
@@ -164,11 +185,20 @@ fn validate_branch_and_generate_sidetable_entry(
         .and_then(|i| i.checked_sub(1));
 
     let targeted_ctrl_block_entry = index_of_label_in_ctrl_stack
-        .and_then(|idx| stack.ctrl_stack.get_mut(idx))
+        .and_then(|idx| stack.ctrl_stack.get(idx))
         .ok_or(ValidationError::InvalidLabelIdx(label_idx))?;
 
-    let valcnt = targeted_ctrl_block_entry.label_types().len();
-    let popcnt = stack_len - targeted_ctrl_block_entry.height - valcnt;
+    // Both counts are given in slots of the untyped value stack used at runtime
+    let label_types = targeted_ctrl_block_entry.label_types();
+    let valcnt = label_types.iter().map(ValType::slots).sum();
+    let popcnt = stack.slots_between(
+        targeted_ctrl_block_entry.height,
+        stack_len - label_types.len(),
+    );
+
+    let targeted_ctrl_block_entry = index_of_label_in_ctrl_stack
+        .and_then(|idx| stack.ctrl_stack.get_mut(idx))
+        .expect("the label index to be checked above");
 
     // Now we generate the actual sidetable entry.
     //
@@ -205,6 +235,18 @@ fn validate_branch_and_generate_sidetable_entry(
     Ok(())
 }
 
+/// Reads the [`MemArg`] of a scalar load or store and records its offset as a
+/// pre-decoded immediate covering the entire [`MemArg`]
+fn read_memarg(
+    wasm: &mut WasmReader,
+    immediates: &mut ImmediatesCache,
+) -> Result<MemArg, ValidationError> {
+    let pc = wasm.pc;
+    let memarg = MemArg::read(wasm)?;
+    immediates.insert(pc, wasm.pc, memarg.offset);
+    Ok(memarg)
+}
+
 /// # Safety
 ///
 /// The caller must ensure that all index values passed into this function are
@@ -221,6 +263,8 @@ unsafe fn read_instructions(
     wasm: &mut WasmReader,
     stack: &mut ValidationStack,
     sidetable: &mut Sidetable,
+    immediates: &mut ImmediatesCache,
+    v128_operands: &mut V128Operands,
     locals: &[ValType],
     c_globals: &IdxVec<GlobalIdx, Global>,
     fn_types: &IdxVec<TypeIdx, FuncType>,
@@ -291,7 +335,7 @@ unsafe fn read_instructions(
                     pc: usize::MAX,
                     stp: usize::MAX,
                     popcnt: 0,
-                    valcnt: block_ty.params.valtypes.len(),
+                    valcnt: block_ty.params.valtypes.iter().map(ValType::slots).sum(),
                 });
 
                 let label_info = LabelInfo::If {
@@ -319,7 +363,7 @@ unsafe fn read_instructions(
                         pc: usize::MAX,
                         stp: usize::MAX,
                         popcnt: 0,
-                        valcnt: block_ty.returns.valtypes.len(),
+                        valcnt: block_ty.returns.valtypes.iter().map(ValType::slots).sum(),
                     });
                     stps_to_backpatch.push(stp_here);
 
@@ -478,7 +522,9 @@ unsafe fn read_instructions(
             }
             // call [t1*] -> [t2*]
             CALL => {
+                let pc = wasm.pc;
                 let func_idx = FuncIdx::read_and_validate(wasm, c_funcs)?;
+                immediates.insert(pc, wasm.pc, func_idx.into_inner());
                 // SAFETY: We just validated this function index with the same
                 // `IdxVec`.
                 let type_idx = *unsafe { c_funcs.get(func_idx) };
@@ -526,11 +572,19 @@ unsafe fn read_instructions(
             UNREACHABLE => {
                 stack.make_unspecified()?;
             }
+            // the interpreter needs to know which `drop` and `select` instructions operate on
+            // v128 values, because these occupy two slots on the value stack
             DROP => {
-                stack.drop_val()?;
+                if stack.drop_val()? == ValidationStackEntry::Val(ValType::VecType) {
+                    v128_operands.insert(wasm.pc - 1);
+                }
             }
             SELECT => {
-                stack.validate_polymorphic_select()?;
+                if stack.validate_polymorphic_select()?
+                    == ValidationStackEntry::Val(ValType::VecType)
+                {
+                    v128_operands.insert(wasm.pc - 1);
+                }
             }
             SELECT_T => {
                 let type_vec = wasm.read_vec(ValType::read)?;
@@ -546,7 +600,9 @@ unsafe fn read_instructions(
             }
             // local.get: [] -> [t]
             LOCAL_GET => {
+                let pc = wasm.pc;
                 let local_idx = LocalIdx::read_and_validate(wasm, locals)?;
+                immediates.insert(pc, wasm.pc, local_idx.into_inner());
                 let local_ty = locals
                     .get(local_idx.into_inner().into_usize())
                     .expect("the local index to be valid as this was just checked");
@@ -554,7 +610,9 @@ unsafe fn read_instructions(
             }
             // local.set [t] -> []
             LOCAL_SET => {
+                let pc = wasm.pc;
                 let local_idx = LocalIdx::read_and_validate(wasm, locals)?;
+                immediates.insert(pc, wasm.pc, local_idx.into_inner());
                 let local_ty = locals
                     .get(local_idx.into_inner().into_usize())
                     .expect("the local index to be valid as this was just checked");
@@ -562,7 +620,9 @@ unsafe fn read_instructions(
             }
             // local.set [t] -> [t]
             LOCAL_TEE => {
+                let pc = wasm.pc;
                 let local_idx = LocalIdx::read_and_validate(wasm, locals)?;
+                immediates.insert(pc, wasm.pc, local_idx.into_inner());
                 let local_ty = locals
                     .get(local_idx.into_inner().into_usize())
                     .expect("the local index to be valid as this was just checked");
@@ -570,7 +630,9 @@ unsafe fn read_instructions(
             }
             // global.get [] -> [t]
             GLOBAL_GET => {
+                let pc = wasm.pc;
                 let global_idx = GlobalIdx::read_and_validate(wasm, c_globals)?;
+                immediates.insert(pc, wasm.pc, global_idx.into_inner());
                 // SAFETY: We just validated that this is a valid `TypeIdx` in
                 // this vector.
                 let global = unsafe { c_globals.get(global_idx) };
@@ -585,7 +647,9 @@ unsafe fn read_instructions(
             }
             // global.set [t] -> []
             GLOBAL_SET => {
+                let pc = wasm.pc;
                 let global_idx = GlobalIdx::read_and_validate(wasm, c_globals)?;
+                immediates.insert(pc, wasm.pc, global_idx.into_inner());
                 // SAFETY: We just validated that this is a valid `TypeIdx` in
                 // this vector.
                 let global = unsafe { c_globals.get(global_idx) };
@@ -620,7 +684,7 @@ unsafe fn read_instructions(
             }
             I32_LOAD => {
                 let _mem_idx = MemIdx::validate(0, c_mems)?;
-                let memarg = MemArg::read(wasm)?;
+                let memarg = read_memarg(wasm, immediates)?;
                 if memarg.align > 2 {
                     return Err(ValidationError::ErroneousAlignment {
                         alignment: memarg.align,
@@ -632,7 +696,7 @@ unsafe fn read_instructions(
             }
             I64_LOAD => {
                 let _mem_idx = MemIdx::validate(0, c_mems)?;
-                let memarg = MemArg::read(wasm)?;
+                let memarg = read_memarg(wasm, immediates)?;
                 if memarg.align > 3 {
                     return Err(ValidationError::ErroneousAlignment {
                         alignment: memarg.align,
@@ -644,7 +708,7 @@ unsafe fn read_instructions(
             }
             F32_LOAD => {
                 let _mem_idx = MemIdx::validate(0, c_mems)?;
-                let memarg = MemArg::read(wasm)?;
+                let memarg = read_memarg(wasm, immediates)?;
                 if memarg.align > 2 {
                     return Err(ValidationError::ErroneousAlignment {
                         alignment: memarg.align,
@@ -656,7 +720,7 @@ unsafe fn read_instructions(
             }
             F64_LOAD => {
                 let _mem_idx = MemIdx::validate(0, c_mems)?;
-                let memarg = MemArg::read(wasm)?;
+                let memarg = read_memarg(wasm, immediates)?;
                 if memarg.align > 3 {
                     return Err(ValidationError::ErroneousAlignment {
                         alignment: memarg.align,
@@ -668,7 +732,7 @@ unsafe fn read_instructions(
             }
             I32_LOAD8_S => {
                 let _mem_idx = MemIdx::validate(0, c_mems)?;
-                let memarg = MemArg::read(wasm)?;
+                let memarg = read_memarg(wasm, immediates)?;
                 if memarg.align > 0 {
                     return Err(ValidationError::ErroneousAlignment {
                         alignment: memarg.align,
@@ -680,7 +744,7 @@ unsafe fn read_instructions(
             }
             I32_LOAD8_U => {
                 let _mem_idx = MemIdx::validate(0, c_mems)?;
-                let memarg = MemArg::read(wasm)?;
+                let memarg = read_memarg(wasm, immediates)?;
                 if memarg.align > 0 {
                     return Err(ValidationError::ErroneousAlignment {
                         alignment: memarg.align,
@@ -692,7 +756,7 @@ unsafe fn read_instructions(
             }
             I32_LOAD16_S => {
                 let _mem_idx = MemIdx::validate(0, c_mems)?;
-                let memarg = MemArg::read(wasm)?;
+                let memarg = read_memarg(wasm, immediates)?;
                 if memarg.align > 1 {
                     return Err(ValidationError::ErroneousAlignment {
                         alignment: memarg.align,
@@ -704,7 +768,7 @@ unsafe fn read_instructions(
             }
             I32_LOAD16_U => {
                 let _mem_idx = MemIdx::validate(0, c_mems)?;
-                let memarg = MemArg::read(wasm)?;
+                let memarg = read_memarg(wasm, immediates)?;
                 if memarg.align > 1 {
                     return Err(ValidationError::ErroneousAlignment {
                         alignment: memarg.align,
@@ -716,7 +780,7 @@ unsafe fn read_instructions(
             }
             I64_LOAD8_S => {
                 let _mem_idx = MemIdx::validate(0, c_mems)?;
-                let memarg = MemArg::read(wasm)?;
+                let memarg = read_memarg(wasm, immediates)?;
                 if memarg.align > 0 {
                     return Err(ValidationError::ErroneousAlignment {
                         alignment: memarg.align,
@@ -728,7 +792,7 @@ unsafe fn read_instructions(
             }
             I64_LOAD8_U => {
                 let _mem_idx = MemIdx::validate(0, c_mems)?;
-                let memarg = MemArg::read(wasm)?;
+                let memarg = read_memarg(wasm, immediates)?;
                 if memarg.align > 0 {
                     return Err(ValidationError::ErroneousAlignment {
                         alignment: memarg.align,
@@ -740,7 +804,7 @@ unsafe fn read_instructions(
             }
             I64_LOAD16_S => {
                 let _mem_idx = MemIdx::validate(0, c_mems)?;
-                let memarg = MemArg::read(wasm)?;
+                let memarg = read_memarg(wasm, immediates)?;
                 if memarg.align > 1 {
                     return Err(ValidationError::ErroneousAlignment {
                         alignment: memarg.align,
@@ -752,7 +816,7 @@ unsafe fn read_instructions(
             }
             I64_LOAD16_U => {
                 let _mem_idx = MemIdx::validate(0, c_mems)?;
-                let memarg = MemArg::read(wasm)?;
+                let memarg = read_memarg(wasm, immediates)?;
                 if memarg.align > 1 {
                     return Err(ValidationError::ErroneousAlignment {
                         alignment: memarg.align,
@@ -764,7 +828,7 @@ unsafe fn read_instructions(
             }
             I64_LOAD32_S => {
                 let _mem_idx = MemIdx::validate(0, c_mems)?;
-                let memarg = MemArg::read(wasm)?;
+                let memarg = read_memarg(wasm, immediates)?;
                 if memarg.align > 2 {
                     return Err(ValidationError::ErroneousAlignment {
                         alignment: memarg.align,
@@ -776,7 +840,7 @@ unsafe fn read_instructions(
             }
             I64_LOAD32_U => {
                 let _mem_idx = MemIdx::validate(0, c_mems)?;
-                let memarg = MemArg::read(wasm)?;
+                let memarg = read_memarg(wasm, immediates)?;
                 if memarg.align > 2 {
                     return Err(ValidationError::ErroneousAlignment {
                         alignment: memarg.align,
@@ -788,7 +852,7 @@ unsafe fn read_instructions(
             }
             I32_STORE => {
                 let _mem_idx = MemIdx::validate(0, c_mems)?;
-                let memarg = MemArg::read(wasm)?;
+                let memarg = read_memarg(wasm, immediates)?;
                 if memarg.align > 2 {
                     return Err(ValidationError::ErroneousAlignment {
                         alignment: memarg.align,
@@ -800,7 +864,7 @@ unsafe fn read_instructions(
             }
             I64_STORE => {
                 let _mem_idx = MemIdx::validate(0, c_mems)?;
-                let memarg = MemArg::read(wasm)?;
+                let memarg = read_memarg(wasm, immediates)?;
                 if memarg.align > 3 {
                     return Err(ValidationError::ErroneousAlignment {
                         alignment: memarg.align,
@@ -812,7 +876,7 @@ unsafe fn read_instructions(
             }
             F32_STORE => {
                 let _mem_idx = MemIdx::validate(0, c_mems)?;
-                let memarg = MemArg::read(wasm)?;
+                let memarg = read_memarg(wasm, immediates)?;
                 if memarg.align > 2 {
                     return Err(ValidationError::ErroneousAlignment {
                         alignment: memarg.align,
@@ -824,7 +888,7 @@ unsafe fn read_instructions(
             }
             F64_STORE => {
                 let _mem_idx = MemIdx::validate(0, c_mems)?;
-                let memarg = MemArg::read(wasm)?;
+                let memarg = read_memarg(wasm, immediates)?;
                 if memarg.align > 3 {
                     return Err(ValidationError::ErroneousAlignment {
                         alignment: memarg.align,
@@ -836,7 +900,7 @@ unsafe fn read_instructions(
             }
             I32_STORE8 => {
                 let _mem_idx = MemIdx::validate(0, c_mems)?;
-                let memarg = MemArg::read(wasm)?;
+                let memarg = read_memarg(wasm, immediates)?;
                 if memarg.align > 0 {
                     return Err(ValidationError::ErroneousAlignment {
                         alignment: memarg.align,
@@ -848,7 +912,7 @@ unsafe fn read_instructions(
             }
             I32_STORE16 => {
                 let _mem_idx = MemIdx::validate(0, c_mems)?;
-                let memarg = MemArg::read(wasm)?;
+                let memarg = read_memarg(wasm, immediates)?;
                 if memarg.align > 1 {
                     return Err(ValidationError::ErroneousAlignment {
                         alignment: memarg.align,
@@ -860,7 +924,7 @@ unsafe fn read_instructions(
             }
             I64_STORE8 => {
                 let _mem_idx = MemIdx::validate(0, c_mems)?;
-                let memarg = MemArg::read(wasm)?;
+                let memarg = read_memarg(wasm, immediates)?;
                 if memarg.align > 0 {
                     return Err(ValidationError::ErroneousAlignment {
                         alignment: memarg.align,
@@ -872,7 +936,7 @@ unsafe fn read_instructions(
             }
             I64_STORE16 => {
                 let _mem_idx = MemIdx::validate(0, c_mems)?;
-                let memarg = MemArg::read(wasm)?;
+                let memarg = read_memarg(wasm, immediates)?;
                 if memarg.align > 1 {
                     return Err(ValidationError::ErroneousAlignment {
                         alignment: memarg.align,
@@ -884,7 +948,7 @@ unsafe fn read_instructions(
             }
             I64_STORE32 => {
                 let _mem_idx = MemIdx::validate(0, c_mems)?;
-                let memarg = MemArg::read(wasm)?;
+                let memarg = read_memarg(wasm, immediates)?;
                 if memarg.align > 2 {
                     return Err(ValidationError::ErroneousAlignment {
                         alignment: memarg.align,
@@ -919,7 +983,9 @@ unsafe fn read_instructions(
             }
             // i32.const: [] -> [i32]
             I32_CONST => {
-                let _num = wasm.read_var_i32()?;
+                let pc = wasm.pc;
+                let num = wasm.read_var_i32()?;
+                immediates.insert(pc, wasm.pc, num.cast_unsigned());
                 stack.push_valtype(ValType::NumType(NumType::I32));
             }
             I64_CONST => {
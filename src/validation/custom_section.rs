@@ -1,7 +1,12 @@
+use alloc::vec::Vec;
+
 use crate::{
-    core::reader::{
-        section_header::{SectionHeader, SectionTy},
-        WasmReader,
+    core::{
+        reader::{
+            section_header::{SectionHeader, SectionTy},
+            WasmReader,
+        },
+        utils::ToUsizeExt,
     },
     ValidationError,
 };
@@ -45,4 +50,30 @@ impl<'wasm> CustomSection<'wasm> {
 
         Ok(CustomSection { name, contents })
     }
+
+    /// Reads the function name map of this custom section, assuming it is the
+    /// `name` custom section. Returns an empty map if there is no function
+    /// names subsection.
+    ///
+    /// See: WebAssembly Specification 2.0 - 7.4.1 - Name Section
+    pub(crate) fn read_function_names(&self) -> Result<Vec<(u32, &'wasm str)>, ValidationError> {
+        // namedata ::= modulenamesubsec? funcnamesubsec? localnamesubsec?
+        // namesubsection_N(B) ::= N:byte size:u32 B (if size = ||B||)
+        // funcnamesubsec ::= namesubsection_1(namemap)
+        // namemap ::= vec(nameassoc)
+        // nameassoc ::= idx:funcidx name
+        let mut wasm = WasmReader::new(self.contents);
+        while !wasm.remaining_bytes().is_empty() {
+            let subsection_id = wasm.read_u8()?;
+            let subsection_size = wasm.read_var_u32()?.into_usize();
+
+            if subsection_id == 1 {
+                return wasm.read_vec(|wasm| Ok((wasm.read_var_u32()?, wasm.read_name()?)));
+            }
+
+            wasm.skip(subsection_size)?;
+        }
+
+        Ok(Vec::new())
+    }
 }
@@ -0,0 +1,744 @@
+//! A static analysis that computes upper bounds on the fuel consumed by functions
+//!
+//! The analysis walks the structured control flow of a validated module and uses the fuel costs of a [`Config`] to
+//! compute the most expensive path through a function, i.e. its worst-case execution cost in units of fuel. Loops,
+//! recursive calls, indirect calls and calls to imported functions cannot be bounded statically. For these
+//! [`FuelBoundAnnotations`] must be supplied, otherwise the function is reported as [`FuelBound::Unbounded`] together
+//! with the reasons why.
+//!
+//! Bounded functions come with the critical path that determines their bound, listing the loops, calls and
+//! per-element costs on it. This makes it possible to see which parts of a function dominate its bound.
+//!
+//! All bytecode offsets in this module refer to the first byte of an instruction within the module's bytecode, just
+//! like the `pc` passed to [`Config::instruction_hook`].
+
+use core::fmt::Display;
+
+use alloc::{
+    collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+    vec,
+    vec::Vec,
+};
+
+use crate::{
+    config::Config,
+    core::{
+        indices::Idx,
+        reader::{types::export::ExportDesc, WasmReader},
+        utils::ToUsizeExt,
+    },
+    execution::assert_validated::UnwrapValidatedExt,
+    instructions::{
+        cost_per_element, flat_cost, read_function_body, read_instruction, start_function, Control,
+    },
+    ValidationInfo,
+};
+
+/// User-supplied facts about a module that cannot be derived statically
+#[derive(Clone, Debug, Default)]
+pub struct FuelBoundAnnotations {
+    loop_iterations: BTreeMap<usize, u64>,
+    indirect_call_targets: BTreeMap<usize, Vec<u32>>,
+    element_counts: BTreeMap<usize, u64>,
+    recursion_depths: BTreeMap<u32, u64>,
+    imported_function_fuel: BTreeMap<u32, u64>,
+}
+
+impl FuelBoundAnnotations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of times the body of the `loop` instruction at bytecode offset `offset` is entered
+    /// each time the loop is executed. As the body is always entered at least once, a value of 0 is treated as 1.
+    pub fn set_loop_iterations(&mut self, offset: usize, iterations: u64) {
+        self.loop_iterations.insert(offset, iterations);
+    }
+
+    /// Sets all functions that the `call_indirect` instruction at bytecode offset `offset` may call
+    pub fn set_indirect_call_targets(
+        &mut self,
+        offset: usize,
+        func_idxs: impl IntoIterator<Item = u32>,
+    ) {
+        self.indirect_call_targets
+            .insert(offset, func_idxs.into_iter().collect());
+    }
+
+    /// Sets the maximum number of elements processed by the instruction at bytecode offset `offset`, e.g. the number
+    /// of bytes written by a `memory.fill`. Without this annotation, the maximum value of the operand is assumed.
+    ///
+    /// This only has an effect for instructions with a non-zero cost per element.
+    pub fn set_element_count(&mut self, offset: usize, elements: u64) {
+        self.element_counts.insert(offset, elements);
+    }
+
+    /// Sets the maximum number of activations of the function `func_idx` that can be on the call stack at the same
+    /// time. Every recursive cycle in the call graph must contain at least one function with a recursion depth.
+    pub fn set_recursion_depth(&mut self, func_idx: u32, depth: u64) {
+        self.recursion_depths.insert(func_idx, depth);
+    }
+
+    /// Sets the maximum amount of fuel consumed by a single call of the imported function `func_idx`
+    pub fn set_imported_function_fuel(&mut self, func_idx: u32, fuel: u64) {
+        self.imported_function_fuel.insert(func_idx, fuel);
+    }
+}
+
+/// A single step on the critical path of a function, i.e. the path that determines its fuel bound
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathStep {
+    /// A loop that is executed with `iterations` iterations, each costing at most as much as `worst_iteration`
+    Loop {
+        offset: usize,
+        iterations: u64,
+        /// The fuel consumed by all iterations of the loop together
+        fuel: u64,
+        /// The critical path through a single iteration
+        worst_iteration: Vec<PathStep>,
+    },
+    /// A call of `callee`, consuming at most `fuel` including the fuel for the call instruction
+    Call {
+        offset: usize,
+        callee: u32,
+        fuel: u64,
+    },
+    /// An instruction with a cost per element, processing at most `elements` elements
+    Elements {
+        offset: usize,
+        elements: u64,
+        /// The fuel consumed for all elements together, excluding the flat cost of the instruction
+        fuel: u64,
+    },
+}
+
+/// A reason why a function is unbounded
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum UnboundedReason {
+    /// The loop at bytecode offset `offset` in function `func_idx` has no iteration bound
+    Loop { func_idx: u32, offset: usize },
+    /// The function `func_idx` is part of a recursive cycle without a recursion depth
+    Recursion { func_idx: u32 },
+    /// The `call_indirect` at bytecode offset `offset` in function `func_idx` has no call targets
+    IndirectCall { func_idx: u32, offset: usize },
+    /// The imported function `func_idx` has no fuel bound
+    ImportedFunction { func_idx: u32 },
+}
+
+impl Display for UnboundedReason {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            UnboundedReason::Loop { func_idx, offset } => write!(
+                f,
+                "the loop at {offset:#x} in func[{func_idx}] has no iteration bound"
+            ),
+            UnboundedReason::Recursion { func_idx } => write!(
+                f,
+                "func[{func_idx}] is called recursively without a recursion depth"
+            ),
+            UnboundedReason::IndirectCall { func_idx, offset } => write!(
+                f,
+                "the call_indirect at {offset:#x} in func[{func_idx}] has no call targets"
+            ),
+            UnboundedReason::ImportedFunction { func_idx } => {
+                write!(f, "the imported func[{func_idx}] has no fuel bound")
+            }
+        }
+    }
+}
+
+/// The result of a fuel bound analysis of a single function
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FuelBound {
+    /// Every invocation of the function consumes at most `fuel` units of fuel
+    Bounded {
+        fuel: u64,
+        critical_path: Vec<PathStep>,
+    },
+    /// No bound could be determined for the given reasons
+    Unbounded { reasons: BTreeSet<UnboundedReason> },
+}
+
+impl FuelBound {
+    /// Returns the bound, if there is one
+    pub fn fuel(&self) -> Option<u64> {
+        match self {
+            FuelBound::Bounded { fuel, .. } => Some(*fuel),
+            FuelBound::Unbounded { .. } => None,
+        }
+    }
+}
+
+impl Display for FuelBound {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        fn write_steps(
+            f: &mut core::fmt::Formatter<'_>,
+            steps: &[PathStep],
+            depth: usize,
+        ) -> core::fmt::Result {
+            for step in steps {
+                let indent = depth * 2;
+                match step {
+                    PathStep::Loop {
+                        offset,
+                        iterations,
+                        fuel,
+                        worst_iteration,
+                    } => {
+                        writeln!(
+                            f,
+                            "{:indent$}loop at {offset:#x}: {iterations} iterations, {fuel} fuel",
+                            ""
+                        )?;
+                        write_steps(f, worst_iteration, depth + 1)?;
+                    }
+                    PathStep::Call {
+                        offset,
+                        callee,
+                        fuel,
+                    } => writeln!(
+                        f,
+                        "{:indent$}call of func[{callee}] at {offset:#x}: {fuel} fuel",
+                        ""
+                    )?,
+                    PathStep::Elements {
+                        offset,
+                        elements,
+                        fuel,
+                    } => writeln!(
+                        f,
+                        "{:indent$}{elements} elements at {offset:#x}: {fuel} fuel",
+                        ""
+                    )?,
+                }
+            }
+            Ok(())
+        }
+
+        match self {
+            FuelBound::Bounded {
+                fuel,
+                critical_path,
+            } => {
+                writeln!(f, "bounded by {fuel} fuel")?;
+                write_steps(f, critical_path, 1)
+            }
+            FuelBound::Unbounded { reasons } => {
+                writeln!(f, "unbounded")?;
+                for reason in reasons {
+                    writeln!(f, "  {reason}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Computes an upper bound on the fuel consumed by a single invocation of the function `func_idx`, using the fuel
+/// costs of `config`. Returns `None` if there is no such function.
+///
+/// Fuel is only consumed for Wasm instructions. Therefore, the bound of an imported function is given entirely by its
+/// annotation.
+pub fn fuel_bound<C: Config>(
+    validation_info: &ValidationInfo,
+    config: &C,
+    annotations: &FuelBoundAnnotations,
+    func_idx: u32,
+) -> Option<FuelBound> {
+    if func_idx >= validation_info.functions.inner().len() {
+        return None;
+    }
+
+    let mut analysis = Analysis::new(validation_info, config, annotations);
+    let bound = if analysis.is_imported(func_idx) {
+        match annotations.imported_function_fuel.get(&func_idx) {
+            Some(fuel) => FuelBound::Bounded {
+                fuel: *fuel,
+                critical_path: Vec::new(),
+            },
+            None => FuelBound::Unbounded {
+                reasons: BTreeSet::from([UnboundedReason::ImportedFunction { func_idx }]),
+            },
+        }
+    } else {
+        analysis.active.push(func_idx);
+        match analysis.analyze_body(func_idx) {
+            Ok(path) => {
+                let path = path.unwrap_or_default();
+                FuelBound::Bounded {
+                    fuel: path.fuel,
+                    critical_path: path.steps,
+                }
+            }
+            Err(reasons) => FuelBound::Unbounded { reasons },
+        }
+    };
+    Some(bound)
+}
+
+/// Computes the fuel bounds of all exported functions, see [`fuel_bound`]
+pub fn exported_fuel_bounds<'wasm, C: Config>(
+    validation_info: &ValidationInfo<'wasm>,
+    config: &C,
+    annotations: &FuelBoundAnnotations,
+) -> Vec<(&'wasm str, FuelBound)> {
+    validation_info
+        .exports
+        .iter()
+        .filter_map(|export| match export.desc {
+            ExportDesc::Func(func_idx) => {
+                let bound = fuel_bound(validation_info, config, annotations, func_idx.into_inner())
+                    .expect("exported function indices to be valid");
+                Some((export.name, bound))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// The cost of a path through a function
+#[derive(Clone, Debug, Default)]
+struct Path {
+    fuel: u64,
+    steps: Vec<PathStep>,
+}
+
+impl Path {
+    fn add(&mut self, fuel: u64) {
+        self.fuel = self.fuel.saturating_add(fuel);
+    }
+}
+
+/// Returns the more expensive of two paths, where `None` denotes an infeasible path
+fn max_path(a: Option<Path>, b: Option<Path>) -> Option<Path> {
+    match (a, b) {
+        (Some(a), Some(b)) if b.fuel > a.fuel => Some(b),
+        (Some(a), _) => Some(a),
+        (None, b) => b,
+    }
+}
+
+enum FrameKind {
+    Function,
+    Block,
+    If {
+        /// The path right after the `if` instruction, which is taken if there is no `else` branch
+        entry: Option<Path>,
+        has_else: bool,
+    },
+    Loop {
+        offset: usize,
+        /// The path before the loop was entered. All paths inside the loop are relative to the start of one iteration.
+        entry: Option<Path>,
+        /// The indices of frames outside of the loop that are targeted by branches from inside the loop
+        pending_exits: Vec<usize>,
+    },
+}
+
+struct Frame {
+    kind: FrameKind,
+    /// For loops the most expensive path through a single iteration, otherwise the most expensive path branching to
+    /// the end of this frame
+    exits: Option<Path>,
+}
+
+impl Frame {
+    fn new(kind: FrameKind) -> Self {
+        Self { kind, exits: None }
+    }
+}
+
+/// The cost of a single call
+enum CallCost {
+    Fuel(u64),
+    /// The call cannot happen because of a recursion depth annotation
+    Infeasible,
+    Unbounded(BTreeSet<UnboundedReason>),
+}
+
+struct Analysis<'a, 'wasm, C: Config> {
+    validation_info: &'a ValidationInfo<'wasm>,
+    config: &'a C,
+    annotations: &'a FuelBoundAnnotations,
+    num_imported_functions: u32,
+    /// Whether each function can reach itself in the call graph
+    recursive: Vec<bool>,
+    /// The call costs of functions that are not recursive, as these do not depend on the current call stack
+    call_costs: BTreeMap<u32, (u64, bool)>,
+    /// The functions currently being analyzed, from the outermost to the innermost
+    active: Vec<u32>,
+}
+
+impl<'a, 'wasm, C: Config> Analysis<'a, 'wasm, C> {
+    fn new(
+        validation_info: &'a ValidationInfo<'wasm>,
+        config: &'a C,
+        annotations: &'a FuelBoundAnnotations,
+    ) -> Self {
+        let functions = &validation_info.functions;
+        let num_imported_functions = functions.inner().len() - functions.len_local_definitions();
+
+        let mut analysis = Self {
+            validation_info,
+            config,
+            annotations,
+            num_imported_functions,
+            recursive: Vec::new(),
+            call_costs: BTreeMap::new(),
+            active: Vec::new(),
+        };
+        analysis.recursive = analysis.find_recursive_functions();
+        analysis
+    }
+
+    fn is_imported(&self, func_idx: u32) -> bool {
+        func_idx < self.num_imported_functions
+    }
+
+    fn function_reader(&self, func_idx: u32) -> WasmReader<'wasm> {
+        let local_idx = (func_idx - self.num_imported_functions).into_usize();
+        let (span, _stp) = self.validation_info.func_blocks_stps[local_idx];
+        let mut wasm = WasmReader::new(self.validation_info.wasm);
+        start_function(&mut wasm, span);
+        wasm
+    }
+
+    /// Returns the direct and annotated indirect callees of every function
+    fn call_graph(&self) -> Vec<BTreeSet<u32>> {
+        let num_functions = self.validation_info.functions.inner().len();
+        (0..num_functions)
+            .map(|func_idx| {
+                let mut callees = BTreeSet::new();
+                if self.is_imported(func_idx) {
+                    return callees;
+                }
+
+                let mut wasm = self.function_reader(func_idx);
+                for instruction in read_function_body(&mut wasm) {
+                    match instruction.control {
+                        Control::Call(callee) => {
+                            callees.insert(callee);
+                        }
+                        Control::CallIndirect => {
+                            if let Some(targets) = self
+                                .annotations
+                                .indirect_call_targets
+                                .get(&instruction.offset)
+                            {
+                                callees.extend(targets);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                callees
+            })
+            .collect()
+    }
+
+    fn find_recursive_functions(&self) -> Vec<bool> {
+        let call_graph = self.call_graph();
+        (0..call_graph.len())
+            .map(|func_idx| {
+                let mut visited = vec![false; call_graph.len()];
+                let mut worklist: Vec<usize> = call_graph[func_idx]
+                    .iter()
+                    .map(|callee| callee.into_usize())
+                    .collect();
+                while let Some(next) = worklist.pop() {
+                    if next == func_idx {
+                        return true;
+                    }
+                    // annotated call targets might be invalid
+                    let Some(visited) = visited.get_mut(next) else {
+                        continue;
+                    };
+                    if !*visited {
+                        *visited = true;
+                        worklist.extend(call_graph[next].iter().map(|callee| callee.into_usize()));
+                    }
+                }
+                false
+            })
+            .collect()
+    }
+
+    fn call_cost(&mut self, callee: u32) -> CallCost {
+        if callee >= self.validation_info.functions.inner().len() {
+            // only possible for invalid annotations
+            return CallCost::Infeasible;
+        }
+
+        if self.is_imported(callee) {
+            return match self.annotations.imported_function_fuel.get(&callee) {
+                Some(fuel) => CallCost::Fuel(*fuel),
+                None => CallCost::Unbounded(BTreeSet::from([UnboundedReason::ImportedFunction {
+                    func_idx: callee,
+                }])),
+            };
+        }
+
+        if let Some((fuel, feasible)) = self.call_costs.get(&callee) {
+            return if *feasible {
+                CallCost::Fuel(*fuel)
+            } else {
+                CallCost::Infeasible
+            };
+        }
+
+        if let Some(last_activation) = self.active.iter().rposition(|active| *active == callee) {
+            // Recursion is only bounded if the cycle contains a function with a recursion depth. Its depth is
+            // checked below once it is called again.
+            let cycle_is_bounded = self.active[last_activation..]
+                .iter()
+                .any(|func_idx| self.annotations.recursion_depths.contains_key(func_idx));
+            if !cycle_is_bounded {
+                return CallCost::Unbounded(BTreeSet::from([UnboundedReason::Recursion {
+                    func_idx: callee,
+                }]));
+            }
+        }
+
+        if let Some(depth) = self.annotations.recursion_depths.get(&callee) {
+            let activations = self
+                .active
+                .iter()
+                .filter(|active| **active == callee)
+                .count();
+            if activations as u64 >= *depth {
+                return CallCost::Infeasible;
+            }
+        }
+
+        self.active.push(callee);
+        let result = self.analyze_body(callee);
+        self.active.pop();
+
+        let cost = match result {
+            Ok(Some(path)) => CallCost::Fuel(path.fuel),
+            Ok(None) => CallCost::Infeasible,
+            Err(reasons) => return CallCost::Unbounded(reasons),
+        };
+        if !self.recursive[callee.into_usize()] {
+            let entry = match cost {
+                CallCost::Fuel(fuel) => (fuel, true),
+                _ => (0, false),
+            };
+            self.call_costs.insert(callee, entry);
+        }
+        cost
+    }
+
+    /// Computes the most expensive path through the body of a local function. Returns `Ok(None)` if the function
+    /// cannot be executed at all due to recursion depth annotations.
+    fn analyze_body(&mut self, func_idx: u32) -> Result<Option<Path>, BTreeSet<UnboundedReason>> {
+        let mut wasm = self.function_reader(func_idx);
+        let mut reasons = BTreeSet::new();
+        let mut frames = vec![Frame::new(FrameKind::Function)];
+        let mut current = Some(Path::default());
+
+        loop {
+            let instruction = read_instruction(&mut wasm);
+            let offset = instruction.offset;
+
+            if let Some(path) = &mut current {
+                path.add(flat_cost(self.config, instruction.opcode));
+
+                let cost_per_element = cost_per_element(self.config, instruction.opcode);
+                if cost_per_element > 0 {
+                    let elements = self
+                        .annotations
+                        .element_counts
+                        .get(&offset)
+                        .copied()
+                        .unwrap_or(u32::MAX.into());
+                    let fuel = elements.saturating_mul(cost_per_element);
+                    path.add(fuel);
+                    path.steps.push(PathStep::Elements {
+                        offset,
+                        elements,
+                        fuel,
+                    });
+                }
+            }
+
+            match instruction.control {
+                Control::Block => frames.push(Frame::new(FrameKind::Block)),
+                Control::Loop => {
+                    let iteration = current.as_ref().map(|_| Path::default());
+                    frames.push(Frame::new(FrameKind::Loop {
+                        offset,
+                        entry: current.take(),
+                        pending_exits: Vec::new(),
+                    }));
+                    current = iteration;
+                }
+                Control::If => frames.push(Frame::new(FrameKind::If {
+                    entry: current.clone(),
+                    has_else: false,
+                })),
+                Control::Else => {
+                    let frame = frames.last_mut().unwrap_validated();
+                    frame.exits = max_path(frame.exits.take(), current.take());
+                    let FrameKind::If { entry, has_else } = &mut frame.kind else {
+                        crate::unreachable_validated!()
+                    };
+                    current = entry.clone();
+                    *has_else = true;
+                }
+                Control::End => {
+                    let frame = frames.pop().unwrap_validated();
+                    match frame.kind {
+                        FrameKind::Function => {
+                            let path = max_path(current, frame.exits);
+                            return if reasons.is_empty() {
+                                Ok(path)
+                            } else {
+                                Err(reasons)
+                            };
+                        }
+                        FrameKind::Block => current = max_path(current, frame.exits),
+                        FrameKind::If { entry, has_else } => {
+                            current = max_path(current, frame.exits);
+                            if !has_else {
+                                current = max_path(current, entry);
+                            }
+                        }
+                        FrameKind::Loop {
+                            offset,
+                            entry,
+                            pending_exits,
+                        } => {
+                            let iteration = max_path(frame.exits, current.clone());
+                            let exit = entry.zip(iteration).map(|(mut exit, iteration)| {
+                                let iterations = match self.annotations.loop_iterations.get(&offset)
+                                {
+                                    Some(iterations) => (*iterations).max(1),
+                                    None => {
+                                        reasons.insert(UnboundedReason::Loop { func_idx, offset });
+                                        1
+                                    }
+                                };
+                                let fuel = iteration.fuel.saturating_mul(iterations);
+                                exit.add(fuel);
+                                exit.steps.push(PathStep::Loop {
+                                    offset,
+                                    iterations,
+                                    fuel,
+                                    worst_iteration: iteration.steps,
+                                });
+                                exit
+                            });
+
+                            current = current.and(exit.clone());
+                            for target in pending_exits {
+                                branch(&mut frames, target, exit.clone());
+                            }
+                        }
+                    }
+                }
+                Control::Br(label_idx) => {
+                    let target = frames.len() - 1 - label_idx.into_usize();
+                    branch(&mut frames, target, current.take());
+                }
+                Control::BrIf(label_idx) => {
+                    let target = frames.len() - 1 - label_idx.into_usize();
+                    branch(&mut frames, target, current.clone());
+                }
+                Control::BrTable { labels, default } => {
+                    for label_idx in labels.into_iter().chain([default]) {
+                        let target = frames.len() - 1 - label_idx.into_usize();
+                        branch(&mut frames, target, current.clone());
+                    }
+                    current = None;
+                }
+                // A trap ends execution just like a return, so its path has to be considered as well
+                Control::Return | Control::Unreachable => branch(&mut frames, 0, current.take()),
+                Control::Call(callee) => {
+                    if current.is_some() {
+                        let cost = self.call_cost(callee);
+                        current = self.apply_call_cost(current, offset, callee, cost, &mut reasons);
+                    }
+                }
+                Control::CallIndirect => {
+                    if current.is_some() {
+                        match self.annotations.indirect_call_targets.get(&offset) {
+                            Some(targets) => {
+                                let mut most_expensive = None;
+                                for callee in targets {
+                                    let cost = self.call_cost(*callee);
+                                    let path = self.apply_call_cost(
+                                        current.clone(),
+                                        offset,
+                                        *callee,
+                                        cost,
+                                        &mut reasons,
+                                    );
+                                    most_expensive = max_path(most_expensive, path);
+                                }
+                                current = most_expensive;
+                            }
+                            None => {
+                                reasons.insert(UnboundedReason::IndirectCall { func_idx, offset });
+                            }
+                        }
+                    }
+                }
+                Control::None => {}
+            }
+        }
+    }
+
+    /// Adds the cost of a call to `path`
+    fn apply_call_cost(
+        &self,
+        path: Option<Path>,
+        offset: usize,
+        callee: u32,
+        cost: CallCost,
+        reasons: &mut BTreeSet<UnboundedReason>,
+    ) -> Option<Path> {
+        match cost {
+            CallCost::Fuel(fuel) => path.map(|mut path| {
+                path.add(fuel);
+                path.steps.push(PathStep::Call {
+                    offset,
+                    callee,
+                    fuel,
+                });
+                path
+            }),
+            CallCost::Infeasible => None,
+            CallCost::Unbounded(callee_reasons) => {
+                reasons.extend(callee_reasons);
+                path
+            }
+        }
+    }
+}
+
+/// Records a branch with the cost `path` to the label of the frame at index `target`
+fn branch(frames: &mut [Frame], target: usize, path: Option<Path>) {
+    if path.is_none() {
+        return;
+    }
+
+    // Branches leaving a loop are only known to be complete once the loop has been analyzed. Thus, they are deferred
+    // until the end of the innermost loop that is left.
+    let innermost_left_loop = frames[target + 1..]
+        .iter_mut()
+        .rev()
+        .find(|frame| matches!(frame.kind, FrameKind::Loop { .. }));
+    if let Some(frame) = innermost_left_loop {
+        frame.exits = max_path(frame.exits.take(), path);
+        if let FrameKind::Loop { pending_exits, .. } = &mut frame.kind {
+            if !pending_exits.contains(&target) {
+                pending_exits.push(target);
+            }
+        }
+        return;
+    }
+
+    let frame = &mut frames[target];
+    frame.exits = max_path(frame.exits.take(), path);
+}
@@ -0,0 +1,264 @@
+//! A decoder for the instructions of already validated code, used by static analyses
+//!
+//! Only the immediates required for control flow analysis are retained, all other immediates are skipped.
+
+use alloc::vec::Vec;
+
+use crate::{
+    config::Config,
+    core::{
+        reader::{
+            span::Span,
+            types::{memarg::MemArg, opcode, BlockType, RefType, ValType},
+            WasmReader,
+        },
+        utils::ToUsizeExt,
+    },
+    execution::assert_validated::UnwrapValidatedExt,
+    profiler::Opcode,
+};
+
+/// The control flow relevant part of a single instruction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Control {
+    Block,
+    Loop,
+    If,
+    Else,
+    End,
+    Br(u32),
+    BrIf(u32),
+    BrTable {
+        labels: Vec<u32>,
+        default: u32,
+    },
+    Return,
+    Unreachable,
+    Call(u32),
+    CallIndirect,
+    /// Any instruction without an effect on control flow
+    None,
+}
+
+/// A single decoded instruction
+#[derive(Clone, Debug)]
+pub(crate) struct Instruction {
+    /// The offset of the first byte of this instruction within the bytecode
+    pub offset: usize,
+    pub opcode: Opcode,
+    pub control: Control,
+}
+
+/// Moves `wasm` to the first instruction of the function with the code block `span`, skipping the declarations of
+/// locals. Returns the number of value stack slots occupied by the declared locals, excluding parameters.
+///
+/// The code block must have been validated before.
+pub(crate) fn start_function(wasm: &mut WasmReader, span: Span) -> usize {
+    wasm.move_start_to(span).unwrap_validated();
+    let local_groups = wasm
+        .read_vec(|wasm| {
+            let n = wasm.read_var_u32()?;
+            let valtype = ValType::read(wasm)?;
+            Ok(n.into_usize() * valtype.slots())
+        })
+        .unwrap_validated();
+    local_groups.into_iter().sum()
+}
+
+/// Reads a single instruction from validated code
+pub(crate) fn read_instruction(wasm: &mut WasmReader) -> Instruction {
+    use crate::core::reader::types::opcode::*;
+
+    let offset = wasm.pc;
+    let first_instr_byte = wasm.read_u8().unwrap_validated();
+
+    let (opcode, control) = match first_instr_byte {
+        FC_EXTENSIONS => {
+            let instr = wasm.read_var_u32().unwrap_validated();
+            skip_fc_extension_immediates(wasm, instr);
+            (Opcode::FcExtension(instr), Control::None)
+        }
+        FD_EXTENSIONS => {
+            let instr = wasm.read_var_u32().unwrap_validated();
+            skip_fd_extension_immediates(wasm, instr);
+            (Opcode::FdExtension(instr), Control::None)
+        }
+        byte => {
+            let control = match byte {
+                UNREACHABLE => Control::Unreachable,
+                BLOCK | LOOP | IF => {
+                    // SAFETY: Validation guarantees there to be a valid block
+                    // type next.
+                    let _ = unsafe { BlockType::read_unchecked(wasm) };
+                    match byte {
+                        BLOCK => Control::Block,
+                        LOOP => Control::Loop,
+                        _ => Control::If,
+                    }
+                }
+                ELSE => Control::Else,
+                END => Control::End,
+                BR => Control::Br(wasm.read_var_u32().unwrap_validated()),
+                BR_IF => Control::BrIf(wasm.read_var_u32().unwrap_validated()),
+                BR_TABLE => {
+                    let labels = wasm.read_vec(|wasm| wasm.read_var_u32()).unwrap_validated();
+                    let default = wasm.read_var_u32().unwrap_validated();
+                    Control::BrTable { labels, default }
+                }
+                RETURN => Control::Return,
+                CALL => Control::Call(wasm.read_var_u32().unwrap_validated()),
+                CALL_INDIRECT => {
+                    let _type_idx = wasm.read_var_u32().unwrap_validated();
+                    let _table_idx = wasm.read_var_u32().unwrap_validated();
+                    Control::CallIndirect
+                }
+                SELECT_T => {
+                    let _types = wasm.read_vec(ValType::read).unwrap_validated();
+                    Control::None
+                }
+                LOCAL_GET | LOCAL_SET | LOCAL_TEE | GLOBAL_GET | GLOBAL_SET | TABLE_GET
+                | TABLE_SET | REF_FUNC => {
+                    let _idx = wasm.read_var_u32().unwrap_validated();
+                    Control::None
+                }
+                I32_LOAD..=I64_STORE32 => {
+                    let _memarg = MemArg::read(wasm).unwrap_validated();
+                    Control::None
+                }
+                MEMORY_SIZE | MEMORY_GROW => {
+                    let _zero = wasm.read_u8().unwrap_validated();
+                    Control::None
+                }
+                I32_CONST => {
+                    let _constant = wasm.read_var_i32().unwrap_validated();
+                    Control::None
+                }
+                I64_CONST => {
+                    let _constant = wasm.read_var_i64().unwrap_validated();
+                    Control::None
+                }
+                F32_CONST => {
+                    let _constant = wasm.read_f32().unwrap_validated();
+                    Control::None
+                }
+                F64_CONST => {
+                    let _constant = wasm.read_f64().unwrap_validated();
+                    Control::None
+                }
+                REF_NULL => {
+                    let _reftype = RefType::read(wasm).unwrap_validated();
+                    Control::None
+                }
+                _ => Control::None,
+            };
+            (Opcode::Single(byte), control)
+        }
+    };
+
+    Instruction {
+        offset,
+        opcode,
+        control,
+    }
+}
+
+fn skip_fc_extension_immediates(wasm: &mut WasmReader, instr: u32) {
+    use crate::core::reader::types::opcode::fc_extensions::*;
+
+    match instr {
+        MEMORY_INIT => {
+            let _data_idx = wasm.read_var_u32().unwrap_validated();
+            let _zero = wasm.read_u8().unwrap_validated();
+        }
+        MEMORY_COPY => {
+            let _zeros = wasm.strip_bytes::<2>().unwrap_validated();
+        }
+        MEMORY_FILL => {
+            let _zero = wasm.read_u8().unwrap_validated();
+        }
+        TABLE_INIT | TABLE_COPY => {
+            let _first_idx = wasm.read_var_u32().unwrap_validated();
+            let _second_idx = wasm.read_var_u32().unwrap_validated();
+        }
+        DATA_DROP | ELEM_DROP | TABLE_GROW | TABLE_SIZE | TABLE_FILL => {
+            let _idx = wasm.read_var_u32().unwrap_validated();
+        }
+        _ => {}
+    }
+}
+
+fn skip_fd_extension_immediates(wasm: &mut WasmReader, instr: u32) {
+    use crate::core::reader::types::opcode::fd_extensions::*;
+
+    match instr {
+        V128_LOAD..=V128_STORE | V128_LOAD32_ZERO | V128_LOAD64_ZERO => {
+            let _memarg = MemArg::read(wasm).unwrap_validated();
+        }
+        V128_CONST | I8X16_SHUFFLE => {
+            let _bytes = wasm.strip_bytes::<16>().unwrap_validated();
+        }
+        I8X16_EXTRACT_LANE_S..=F64X2_REPLACE_LANE => {
+            let _lane = wasm.read_u8().unwrap_validated();
+        }
+        V128_LOAD8_LANE..=V128_STORE64_LANE => {
+            let _memarg = MemArg::read(wasm).unwrap_validated();
+            let _lane = wasm.read_u8().unwrap_validated();
+        }
+        _ => {}
+    }
+}
+
+/// Reads all remaining instructions of the function body `wasm` points into, up to and including its final `end`
+pub(crate) fn read_function_body(wasm: &mut WasmReader) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    let mut depth = 1;
+    while depth > 0 {
+        let instruction = read_instruction(wasm);
+        match instruction.control {
+            Control::Block | Control::Loop | Control::If => depth += 1,
+            Control::End => depth -= 1,
+            _ => {}
+        }
+        instructions.push(instruction);
+    }
+    instructions
+}
+
+/// Returns the fuel that is deducted for an instruction independently of its operands
+pub(crate) fn flat_cost<C: Config>(config: &C, opcode: Opcode) -> u64 {
+    match opcode {
+        Opcode::Single(opcode::END | opcode::UNREACHABLE) => 0,
+        Opcode::Single(byte) => config.get_flat_cost(byte),
+        Opcode::FcExtension(instr) => config.get_fc_extension_flat_cost(instr),
+        Opcode::FdExtension(instr) => config.get_fd_extension_flat_cost(instr),
+    }
+}
+
+/// Checks whether the fuel deducted for an instruction depends on the number of elements it processes
+pub(crate) fn is_charged_per_element(opcode: Opcode) -> bool {
+    use opcode::fc_extensions::*;
+
+    matches!(
+        opcode,
+        Opcode::Single(opcode::MEMORY_GROW)
+            | Opcode::FcExtension(
+                MEMORY_INIT
+                    | MEMORY_COPY
+                    | MEMORY_FILL
+                    | TABLE_INIT
+                    | TABLE_COPY
+                    | TABLE_GROW
+                    | TABLE_FILL
+            )
+    )
+}
+
+/// Returns the fuel that is deducted per element for instructions that execute in linear time
+pub(crate) fn cost_per_element<C: Config>(config: &C, opcode: Opcode) -> u64 {
+    match opcode {
+        _ if !is_charged_per_element(opcode) => 0,
+        Opcode::Single(byte) => config.get_cost_per_element(byte),
+        Opcode::FcExtension(instr) => config.get_fc_extension_cost_per_element(instr),
+        Opcode::FdExtension(_) => 0,
+    }
+}
@@ -1,8 +1,10 @@
 use core::iter::Map;
 
 use alloc::collections::btree_set::{self, BTreeSet};
+use alloc::sync::Arc;
 use alloc::vec::Vec;
 
+use crate::core::immediates::ImmediatesCache;
 use crate::core::indices::{
     DataIdx, ElemIdx, ExtendedIdxVec, FuncIdx, GlobalIdx, IdxVec, IdxVecOverflowError, MemIdx,
     TableIdx, TypeIdx,
@@ -18,14 +20,20 @@ use crate::core::reader::types::{ExternType, FuncType, MemType, ResultType, Tabl
 use crate::core::reader::WasmReader;
 use crate::core::sidetable::Sidetable;
 use crate::core::utils::ToUsizeExt;
+use crate::core::v128_operands::V128Operands;
 use crate::custom_section::CustomSection;
+use crate::value_stack::FrameLayout;
 use crate::ValidationError;
 
+pub(crate) mod basic_blocks;
 pub(crate) mod code;
 pub(crate) mod custom_section;
 pub(crate) mod data;
+pub mod fuel_bound;
 pub(crate) mod globals;
+pub(crate) mod instructions;
 pub(crate) mod read_constant_expression;
+pub mod stack_bound;
 pub(crate) mod validation_stack;
 
 /// Information collected from validating a module.
@@ -36,7 +44,8 @@ pub(crate) mod validation_stack;
 #[derive(Clone, Debug)]
 pub struct ValidationInfo<'bytecode> {
     pub(crate) wasm: &'bytecode [u8],
-    pub(crate) types: IdxVec<TypeIdx, FuncType>,
+    /// The immutable data required for execution, which is shared with all instances of this module
+    pub(crate) shared: Arc<SharedModuleData<'bytecode>>,
     pub(crate) imports: Vec<Import<'bytecode>>,
     pub(crate) functions: ExtendedIdxVec<FuncIdx, TypeIdx>,
     pub(crate) tables: ExtendedIdxVec<TableIdx, TableType>,
@@ -48,13 +57,73 @@ pub struct ValidationInfo<'bytecode> {
     /// Each block contains the validated code section and the stp corresponding to
     /// the beginning of that code section
     pub(crate) func_blocks_stps: Vec<(Span, usize)>,
-    pub(crate) sidetable: Sidetable,
+    /// The maximum number of slots occupied by operands on the value stack for each code block, excluding locals
+    pub(crate) func_max_operand_heights: Vec<usize>,
     /// The start function which is automatically executed during instantiation
     pub(crate) start: Option<FuncIdx>,
     pub(crate) custom_sections: Vec<CustomSection<'bytecode>>,
     // pub(crate) exports_length: Exported,
 }
 
+/// The parts of a validated module which the interpreter reads but never modifies
+///
+/// Instantiating a module only increments the reference count of this data instead of copying it, so that many
+/// instances of the same module do not multiply its memory usage.
+#[derive(Debug)]
+pub(crate) struct SharedModuleData<'wasm> {
+    pub(crate) types: IdxVec<TypeIdx, FuncType>,
+    pub(crate) sidetable: Sidetable,
+    /// pre-decoded immediates of hot instructions, which is empty unless enabled through [`ValidationOptions`]
+    pub(crate) immediates: ImmediatesCache,
+    /// The locations of all `drop` and `select` instructions whose operands are v128 values
+    pub(crate) v128_operands: V128Operands,
+    /// The function names from the `name` custom section, see [`read_function_names`]
+    pub(crate) function_names: Vec<(FuncIdx, &'wasm str)>,
+    /// The data of every function defined by this module, in the order of the code section
+    pub(crate) local_funcs: Vec<Arc<LocalFuncData>>,
+}
+
+/// The data of a function defined by a module, which is computed once during validation and referenced by the
+/// instances of this function in all module instances
+#[derive(Debug)]
+pub(crate) struct LocalFuncData {
+    pub(crate) function_type: FuncType,
+    /// The layout of this function's call frame on the value stack
+    pub(crate) frame_layout: FrameLayout,
+    /// The code of this function following its declared locals
+    pub(crate) code_expr: Span,
+}
+
+/// Returns the function names from the `name` custom section, if one is
+/// present. Names for invalid function indices are skipped.
+///
+/// Because custom sections are not validated, a malformed `name` section is
+/// ignored entirely.
+fn read_function_names<'wasm>(
+    custom_sections: &[CustomSection<'wasm>],
+    functions: &ExtendedIdxVec<FuncIdx, TypeIdx>,
+) -> Vec<(FuncIdx, &'wasm str)> {
+    let Some(name_section) = custom_sections
+        .iter()
+        .find(|custom_section| custom_section.name == "name")
+    else {
+        return Vec::new();
+    };
+
+    let names = name_section.read_function_names().unwrap_or_else(|_err| {
+        warn!("Ignoring malformed name section: {_err}");
+        Vec::new()
+    });
+
+    names
+        .into_iter()
+        .filter_map(|(idx, name)| {
+            let func_idx = FuncIdx::validate(idx, functions.inner()).ok()?;
+            Some((func_idx, name))
+        })
+        .collect()
+}
+
 fn validate_no_duplicate_exports(validation_info: &ValidationInfo) -> Result<(), ValidationError> {
     let mut found_export_names: btree_set::BTreeSet<&str> = btree_set::BTreeSet::new();
     for export in &validation_info.exports {
@@ -66,7 +135,28 @@ fn validate_no_duplicate_exports(validation_info: &ValidationInfo) -> Result<(),
     Ok(())
 }
 
+/// Options for the side structures generated while validating a module
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ValidationOptions {
+    /// The maximum number of bytes used for pre-decoded immediates of hot instructions
+    ///
+    /// Pre-decoded immediates save the interpreter from decoding variable length integers every time an instruction is
+    /// executed. The cache requires one entry per immediate and a small index of a fraction of a byte per byte of the
+    /// code section. Immediates which do not fit into this limit anymore are decoded in place. The default of zero
+    /// disables the cache, which keeps validation and instantiation as fast and memory efficient as possible.
+    pub immediates_cache_limit: usize,
+}
+
+/// Validates a module with the default [`ValidationOptions`], i.e. for pure in-place interpretation
 pub fn validate(wasm: &[u8]) -> Result<ValidationInfo<'_>, ValidationError> {
+    validate_with_options(wasm, ValidationOptions::default())
+}
+
+/// Validates a module and generates the side structures selected by `options`
+pub fn validate_with_options(
+    wasm: &[u8],
+    options: ValidationOptions,
+) -> Result<ValidationInfo<'_>, ValidationError> {
     let mut wasm = WasmReader::new(wasm);
 
     // represents C.refs in https://webassembly.github.io/spec/core/valid/conventions.html#context
@@ -277,7 +367,13 @@ pub fn validate(wasm: &[u8]) -> Result<ValidationInfo<'_>, ValidationError> {
     read_all_custom_sections(&mut wasm, &mut header, &mut custom_sections)?;
 
     let mut sidetable = Sidetable::new();
+    let mut immediates = ImmediatesCache::default();
+    let mut v128_operands = V128Operands::default();
+    let mut func_max_operand_heights = Vec::new();
+    let mut local_funcs = Vec::new();
     let func_blocks_stps = handle_section(&mut wasm, &mut header, SectionTy::Code, |wasm, h| {
+        immediates = ImmediatesCache::new(h.contents, options.immediates_cache_limit);
+        v128_operands = V128Operands::new(h.contents);
         // SAFETY: It is required that all passed index values are valid in all
         // passed `IdxVec`s. The current function does not take any index types
         // as arguments and every `IdxVec<..., ...>` is unique because they use
@@ -296,6 +392,10 @@ pub fn validate(wasm: &[u8]) -> Result<ValidationInfo<'_>, ValidationError> {
                 &elements,
                 &validation_context_refs,
                 &mut sidetable,
+                &mut immediates,
+                &mut v128_operands,
+                &mut func_max_operand_heights,
+                &mut local_funcs,
             )
         }
     })?
@@ -329,9 +429,17 @@ pub fn validate(wasm: &[u8]) -> Result<ValidationInfo<'_>, ValidationError> {
     }
 
     debug!("Validation was successful");
+    let function_names = read_function_names(&custom_sections, &functions);
     let validation_info = ValidationInfo {
         wasm: wasm.into_inner(),
-        types,
+        shared: Arc::new(SharedModuleData {
+            types,
+            sidetable,
+            immediates,
+            v128_operands,
+            function_names,
+            local_funcs,
+        }),
         imports,
         functions,
         tables,
@@ -339,7 +447,7 @@ pub fn validate(wasm: &[u8]) -> Result<ValidationInfo<'_>, ValidationError> {
         globals,
         exports,
         func_blocks_stps,
-        sidetable,
+        func_max_operand_heights,
         data: data_section,
         start,
         elements,
@@ -449,4 +557,10 @@ impl<'wasm> ValidationInfo<'wasm> {
     pub fn custom_sections(&self) -> &[CustomSection<'wasm>] {
         &self.custom_sections
     }
+
+    /// Returns the number of bytes used for pre-decoded immediates, see
+    /// [`ValidationOptions::immediates_cache_limit`].
+    pub fn immediates_cache_size(&self) -> usize {
+        self.shared.immediates.size_in_bytes()
+    }
 }
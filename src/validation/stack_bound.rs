@@ -0,0 +1,369 @@
+//! A static analysis that computes upper bounds on the stack usage of functions
+//!
+//! During validation, the maximum number of operands on the value stack is recorded for every function. Together with
+//! the number of locals this gives the size of a function's frame on the value stack. All sizes are given in slots of
+//! the value stack, where `v128` values occupy two slots and all other values one. Combining the frames along the
+//! call graph yields the maximum number of slots on the value stack and the maximum number of call frames an
+//! invocation of a function can require. These can be compared against the [`StackLimits`] of an invocation to rule
+//! out [`RuntimeError::StackExhaustion`](crate::RuntimeError::StackExhaustion) before execution.
+//!
+//! Recursive cycles and indirect calls cannot be bounded statically and are reported as [`StackBound::Unbounded`].
+//! Calls to imported functions are unbounded as well, because an import may be a Wasm function of another module,
+//! unless their stack usage is supplied through [`StackBoundAnnotations`]. Host functions use neither of the stacks,
+//! so they can be annotated with zero values and call frames.
+
+use core::fmt::Display;
+
+use alloc::{
+    collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+    vec,
+    vec::Vec,
+};
+
+use crate::{
+    config::{Config, StackLimits},
+    core::{
+        indices::{FuncIdx, Idx},
+        reader::{types::export::ExportDesc, WasmReader},
+        utils::ToUsizeExt,
+    },
+    instructions::{read_function_body, start_function, Control},
+    ValType, ValidationInfo,
+};
+
+/// User-supplied stack usage of imported functions, which cannot be derived from the importing module
+#[derive(Clone, Debug, Default)]
+pub struct StackBoundAnnotations {
+    imported_function_stacks: BTreeMap<u32, (usize, usize)>,
+}
+
+impl StackBoundAnnotations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of slots on the value stack, including its parameters, and the maximum number of call
+    /// frames used by a single call of the imported function `func_idx`. Both are 0 for host functions, while the
+    /// bounds of a Wasm function can be computed with [`stack_bound`] on the module that defines it.
+    pub fn set_imported_function_stack(
+        &mut self,
+        func_idx: u32,
+        max_slots: usize,
+        max_call_frames: usize,
+    ) {
+        self.imported_function_stacks
+            .insert(func_idx, (max_slots, max_call_frames));
+    }
+}
+
+/// The stack usage of a single function frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FunctionStackUsage {
+    /// The number of slots occupied by locals, including parameters
+    pub locals: usize,
+    /// The maximum number of slots occupied by operands on the value stack, excluding locals
+    pub max_operand_height: usize,
+}
+
+impl FunctionStackUsage {
+    /// Returns the number of slots a frame of this function occupies on the value stack at most
+    pub fn frame_values(&self) -> usize {
+        self.locals + self.max_operand_height
+    }
+}
+
+/// A reason why the stack usage of a function is unbounded
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum UnboundedReason {
+    /// The function `func_idx` is part of a recursive cycle
+    Recursion { func_idx: u32 },
+    /// The function `func_idx` contains a `call_indirect` at bytecode offset `offset`
+    IndirectCall { func_idx: u32, offset: usize },
+    /// The imported function `func_idx` has no annotated stack usage
+    ImportedFunction { func_idx: u32 },
+}
+
+impl Display for UnboundedReason {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            UnboundedReason::Recursion { func_idx } => {
+                write!(f, "func[{func_idx}] is part of a recursive cycle")
+            }
+            UnboundedReason::IndirectCall { func_idx, offset } => write!(
+                f,
+                "func[{func_idx}] contains a call_indirect at {offset:#x}"
+            ),
+            UnboundedReason::ImportedFunction { func_idx } => {
+                write!(f, "the imported func[{func_idx}] has no stack bound")
+            }
+        }
+    }
+}
+
+/// The result of a stack bound analysis of a single function
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StackBound {
+    Bounded {
+        /// The maximum number of slots on the value stack, including parameters and locals
+        max_slots: usize,
+        /// The maximum number of call frames
+        max_call_frames: usize,
+        /// The chain of calls requiring `max_slots` slots, starting with the analyzed function
+        deepest_call_chain: Vec<u32>,
+    },
+    /// No bound could be determined for the given reasons
+    Unbounded { reasons: BTreeSet<UnboundedReason> },
+}
+
+impl StackBound {
+    /// Checks whether an invocation can never exceed the default stack sizes of the configuration `C`
+    pub fn fits<C: Config>(&self) -> bool {
+        self.fits_within(StackLimits::of::<C>())
+    }
+
+    /// Checks whether an invocation can never exceed the given stack limits
+    pub fn fits_within(&self, limits: StackLimits) -> bool {
+        match self {
+            StackBound::Bounded {
+                max_slots,
+                max_call_frames,
+                ..
+            } => {
+                *max_slots <= limits.max_value_stack_size
+                    && *max_call_frames <= limits.max_call_stack_size
+            }
+            StackBound::Unbounded { .. } => false,
+        }
+    }
+}
+
+impl Display for StackBound {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            StackBound::Bounded {
+                max_slots,
+                max_call_frames,
+                deepest_call_chain,
+            } => {
+                write!(
+                    f,
+                    "bounded by {max_slots} slots and {max_call_frames} call frames, deepest call chain:"
+                )?;
+                for func_idx in deepest_call_chain {
+                    write!(f, " func[{func_idx}]")?;
+                }
+                Ok(())
+            }
+            StackBound::Unbounded { reasons } => {
+                f.write_str("unbounded")?;
+                for reason in reasons {
+                    write!(f, "\n  {reason}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Returns the stack usage of a single frame of the local function `func_idx`. Returns `None` if there is no such
+/// function or it is imported.
+pub fn function_stack_usage(
+    validation_info: &ValidationInfo,
+    func_idx: u32,
+) -> Option<FunctionStackUsage> {
+    let local_idx = local_function_idx(validation_info, func_idx)?;
+    let (span, _stp) = validation_info.func_blocks_stps[local_idx];
+
+    let mut wasm = WasmReader::new(validation_info.wasm);
+    let declared_locals = start_function(&mut wasm, span);
+
+    Some(FunctionStackUsage {
+        locals: num_params(validation_info, func_idx) + declared_locals,
+        max_operand_height: validation_info.func_max_operand_heights[local_idx],
+    })
+}
+
+/// Computes an upper bound on the stack usage of a single invocation of the function `func_idx`, using the stack usage
+/// of imported functions given by `annotations`. Returns `None` if there is no such function.
+pub fn stack_bound(
+    validation_info: &ValidationInfo,
+    annotations: &StackBoundAnnotations,
+    func_idx: u32,
+) -> Option<StackBound> {
+    if func_idx >= validation_info.functions.inner().len() {
+        return None;
+    }
+
+    let mut analysis = Analysis {
+        validation_info,
+        annotations,
+        results: vec![None; validation_info.functions.inner().len().into_usize()],
+        active: Vec::new(),
+    };
+    let bound = match analysis.analyze(func_idx) {
+        Ok(usage) => StackBound::Bounded {
+            max_slots: usage.values,
+            max_call_frames: usage.call_frames,
+            deepest_call_chain: usage.call_chain,
+        },
+        Err(reasons) => StackBound::Unbounded { reasons },
+    };
+    Some(bound)
+}
+
+/// Computes the stack bounds of all exported functions, see [`stack_bound`]
+pub fn exported_stack_bounds<'wasm>(
+    validation_info: &ValidationInfo<'wasm>,
+    annotations: &StackBoundAnnotations,
+) -> Vec<(&'wasm str, StackBound)> {
+    validation_info
+        .exports
+        .iter()
+        .filter_map(|export| match export.desc {
+            ExportDesc::Func(func_idx) => {
+                let bound = stack_bound(validation_info, annotations, func_idx.into_inner())
+                    .expect("exported function indices to be valid");
+                Some((export.name, bound))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn local_function_idx(validation_info: &ValidationInfo, func_idx: u32) -> Option<usize> {
+    let functions = &validation_info.functions;
+    let num_imported_functions = functions.inner().len() - functions.len_local_definitions();
+    func_idx
+        .checked_sub(num_imported_functions)
+        .filter(|local_idx| *local_idx < functions.len_local_definitions())
+        .map(|local_idx| local_idx.into_usize())
+}
+
+/// Returns the number of slots occupied by the parameters of the valid function `func_idx`
+fn num_params(validation_info: &ValidationInfo, func_idx: u32) -> usize {
+    let func_idx = FuncIdx::validate(func_idx, validation_info.functions.inner())
+        .expect("the function index to be valid");
+    // SAFETY: The function index was just validated using the same functions
+    // vector.
+    let type_idx = unsafe { validation_info.functions.inner().get(func_idx) };
+    // SAFETY: The type index comes from the same validation info as the types
+    // vector, which was used to validate it.
+    let func_type = unsafe { validation_info.shared.types.get(*type_idx) };
+    func_type.params.valtypes.iter().map(ValType::slots).sum()
+}
+
+/// The stack usage of an invocation, including all calls made by it
+#[derive(Clone, Debug)]
+struct InvocationStackUsage {
+    values: usize,
+    call_frames: usize,
+    /// The number of slots consumed from the caller's operand stack as parameters
+    params: usize,
+    call_chain: Vec<u32>,
+}
+
+type AnalysisResult = Result<InvocationStackUsage, BTreeSet<UnboundedReason>>;
+
+struct Analysis<'a, 'wasm> {
+    validation_info: &'a ValidationInfo<'wasm>,
+    annotations: &'a StackBoundAnnotations,
+    results: Vec<Option<AnalysisResult>>,
+    /// The functions currently being analyzed, from the outermost to the innermost
+    active: Vec<u32>,
+}
+
+impl Analysis<'_, '_> {
+    fn analyze(&mut self, func_idx: u32) -> AnalysisResult {
+        if let Some(result) = &self.results[func_idx.into_usize()] {
+            return result.clone();
+        }
+
+        let Some(local_idx) = local_function_idx(self.validation_info, func_idx) else {
+            let Some(&(max_slots, max_call_frames)) =
+                self.annotations.imported_function_stacks.get(&func_idx)
+            else {
+                return Err(BTreeSet::from([UnboundedReason::ImportedFunction {
+                    func_idx,
+                }]));
+            };
+            // the parameters are part of the caller's operands, even if the callee does not use the stacks itself
+            let params = num_params(self.validation_info, func_idx);
+            return Ok(InvocationStackUsage {
+                values: max_slots.max(params),
+                call_frames: max_call_frames,
+                params,
+                call_chain: vec![func_idx],
+            });
+        };
+
+        if self.active.contains(&func_idx) {
+            // the result is not memoized, because this is only an intermediate result of the function on the cycle
+            return Err(BTreeSet::from([UnboundedReason::Recursion { func_idx }]));
+        }
+
+        let usage = function_stack_usage(self.validation_info, func_idx)
+            .expect("local function indices to be valid");
+        let params = num_params(self.validation_info, func_idx);
+
+        let (span, _stp) = self.validation_info.func_blocks_stps[local_idx];
+        let mut wasm = WasmReader::new(self.validation_info.wasm);
+        start_function(&mut wasm, span);
+
+        self.active.push(func_idx);
+        let mut reasons = BTreeSet::new();
+        let mut deepest_callee: Option<InvocationStackUsage> = None;
+        let mut max_callee_frames = 0;
+        for instruction in read_function_body(&mut wasm) {
+            match instruction.control {
+                Control::Call(callee) => match self.analyze(callee) {
+                    Ok(callee_usage) => {
+                        max_callee_frames = max_callee_frames.max(callee_usage.call_frames);
+                        let additional_values = callee_usage.values - callee_usage.params;
+                        let deepest_values = deepest_callee
+                            .as_ref()
+                            .map(|deepest| deepest.values - deepest.params);
+                        if deepest_values.is_none_or(|values| additional_values > values) {
+                            deepest_callee = Some(callee_usage);
+                        }
+                    }
+                    Err(callee_reasons) => reasons.extend(callee_reasons),
+                },
+                Control::CallIndirect => {
+                    reasons.insert(UnboundedReason::IndirectCall {
+                        func_idx,
+                        offset: instruction.offset,
+                    });
+                }
+                _ => {}
+            }
+        }
+        self.active.pop();
+
+        if !reasons.is_empty() {
+            // Results of functions on a recursive cycle depend on where the cycle was entered
+            let on_cycle = reasons
+                .iter()
+                .any(|reason| matches!(reason, UnboundedReason::Recursion { .. }));
+            if !on_cycle {
+                self.results[func_idx.into_usize()] = Some(Err(reasons.clone()));
+            }
+            return Err(reasons);
+        }
+
+        // The parameters of a callee are part of the caller's operands, which is why they are only counted once
+        let (callee_values, mut call_chain) = match deepest_callee {
+            Some(deepest) => (deepest.values - deepest.params, deepest.call_chain),
+            None => (0, Vec::new()),
+        };
+        call_chain.insert(0, func_idx);
+
+        let result = InvocationStackUsage {
+            values: usage.frame_values() + callee_values,
+            call_frames: 1 + max_callee_frames,
+            params,
+            call_chain,
+        };
+        self.results[func_idx.into_usize()] = Some(Ok(result.clone()));
+        Ok(result)
+    }
+}
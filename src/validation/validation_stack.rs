@@ -14,6 +14,8 @@ use crate::{
 #[derive(Debug, PartialEq, Eq)]
 pub struct ValidationStack {
     stack: Vec<ValidationStackEntry>,
+    /// The number of slots the values on `stack` occupy at runtime
+    slots: SlotCount,
     // TODO hide implementation
     pub ctrl_stack: Vec<CtrlStackEntry>,
 }
@@ -23,6 +25,7 @@ impl ValidationStack {
     pub fn new() -> Self {
         Self {
             stack: Vec::new(),
+            slots: SlotCount::default(),
             ctrl_stack: vec![CtrlStackEntry {
                 label_info: LabelInfo::Untyped,
                 block_ty: FuncType {
@@ -43,6 +46,7 @@ impl ValidationStack {
     pub(super) fn new_for_func(block_ty: FuncType) -> Self {
         Self {
             stack: Vec::new(),
+            slots: SlotCount::default(),
             ctrl_stack: vec![CtrlStackEntry {
                 label_info: LabelInfo::Func {
                     stps_to_backpatch: Vec::new(),
@@ -58,16 +62,30 @@ impl ValidationStack {
         self.stack.len()
     }
 
+    /// Returns the maximum number of slots the values on the stack occupied at the same time at runtime
+    pub fn max_slots(&self) -> usize {
+        self.slots.max
+    }
+
+    /// Returns the number of slots occupied at runtime by the values from index `from` up to, but excluding, index
+    /// `to` of the stack
+    pub(super) fn slots_between(&self, from: usize, to: usize) -> usize {
+        self.stack[from..to]
+            .iter()
+            .map(ValidationStackEntry::slots)
+            .sum()
+    }
+
     pub fn push_valtype(&mut self, valtype: ValType) {
         self.stack.push(ValidationStackEntry::Val(valtype));
+        self.slots.add(valtype.slots());
     }
 
     /// Similar to [`ValidationStack::pop_valtype`], because it pops a value from the stack,
-    /// but more public and doesn't actually return the popped value.
-    pub(super) fn drop_val(&mut self) -> Result<(), ValidationError> {
+    /// but more public.
+    pub(super) fn drop_val(&mut self) -> Result<ValidationStackEntry, ValidationError> {
         self.pop_valtype()
-            .map_err(|_| ValidationError::ExpectedAnOperand)?;
-        Ok(())
+            .map_err(|_| ValidationError::ExpectedAnOperand)
     }
 
     /// Mark the current control block as unreachable, removing all of the types pushed to the stack since the current control block was entered.
@@ -82,7 +100,9 @@ impl ValidationStack {
             .last_mut()
             .ok_or(ValidationError::ValidationCtrlStackEmpty)?;
         last_ctrl_stack_entry.unreachable = true;
-        self.stack.truncate(last_ctrl_stack_entry.height);
+        let height = last_ctrl_stack_entry.height;
+        self.slots.sub(self.slots_between(height, self.stack.len()));
+        self.stack.truncate(height);
         Ok(())
     }
 
@@ -108,9 +128,12 @@ impl ValidationStack {
             }
         } else {
             //empty stack is covered with above check
-            self.stack
+            let entry = self
+                .stack
                 .pop()
-                .ok_or(ValidationError::EndInvalidValueStack)
+                .ok_or(ValidationError::EndInvalidValueStack)?;
+            self.slots.sub(entry.slots());
+            Ok(entry)
         }
     }
 
@@ -157,6 +180,7 @@ impl ValidationStack {
     // TODO ugly but I can't come up with anything else better
     fn assert_val_types_on_top_with_custom_stacks(
         stack: &mut Vec<ValidationStackEntry>,
+        slots: &mut SlotCount,
         ctrl_stack: &[CtrlStackEntry],
         expected_val_types: &[ValType],
         unify_to_expected_types: bool,
@@ -170,22 +194,22 @@ impl ValidationStack {
         for (i, expected_ty) in rev_iterator {
             if stack_len - last_ctrl_stack_entry.height <= i {
                 if last_ctrl_stack_entry.unreachable {
+                    let missing_val_types = &expected_val_types[..expected_val_types.len() - i];
                     if unify_to_expected_types {
                         // Unify(t2*,expected_val_types) := [t2* expected_val_types]
                         stack.splice(
                             stack_len - i..stack_len - i,
-                            expected_val_types[..expected_val_types.len() - i]
+                            missing_val_types
                                 .iter()
                                 .map(|ty| ValidationStackEntry::Val(*ty)),
                         );
+                        slots.add(missing_val_types.iter().map(ValType::slots).sum());
                     } else {
                         stack.splice(
                             stack_len - i..stack_len - i,
-                            iter::repeat_n(
-                                ValidationStackEntry::Bottom,
-                                expected_val_types.len() - i,
-                            ),
+                            iter::repeat_n(ValidationStackEntry::Bottom, missing_val_types.len()),
                         );
+                        slots.add(missing_val_types.len());
                     }
                     return Ok(());
                 } else {
@@ -206,6 +230,8 @@ impl ValidationStack {
                     // Bottom will always unify to the expected ty
                     if unify_to_expected_types {
                         *actual_ty = ValidationStackEntry::Val(*expected_ty);
+                        slots.sub(ValidationStackEntry::Bottom.slots());
+                        slots.add(expected_ty.slots());
                     }
                 }
             }
@@ -216,12 +242,14 @@ impl ValidationStack {
 
     fn assert_val_types_with_custom_stacks(
         stack: &mut Vec<ValidationStackEntry>,
+        slots: &mut SlotCount,
         ctrl_stack: &[CtrlStackEntry],
         expected_val_types: &[ValType],
         unify_to_expected_types: bool,
     ) -> Result<(), ValidationError> {
         ValidationStack::assert_val_types_on_top_with_custom_stacks(
             stack,
+            slots,
             ctrl_stack,
             expected_val_types,
             unify_to_expected_types,
@@ -252,6 +280,7 @@ impl ValidationStack {
     ) -> Result<(), ValidationError> {
         ValidationStack::assert_val_types_on_top_with_custom_stacks(
             &mut self.stack,
+            &mut self.slots,
             &self.ctrl_stack,
             expected_val_types,
             unify_to_expected_types,
@@ -274,6 +303,7 @@ impl ValidationStack {
     ) -> Result<(), ValidationError> {
         ValidationStack::assert_val_types_with_custom_stacks(
             &mut self.stack,
+            &mut self.slots,
             &self.ctrl_stack,
             expected_val_types,
             unify_to_expected_types,
@@ -308,6 +338,7 @@ impl ValidationStack {
             .label_types();
         ValidationStack::assert_val_types_on_top_with_custom_stacks(
             &mut self.stack,
+            &mut self.slots,
             &self.ctrl_stack,
             label_types,
             unify_to_expected_types,
@@ -360,6 +391,7 @@ impl ValidationStack {
             .valtypes;
         ValidationStack::assert_val_types_with_custom_stacks(
             &mut self.stack,
+            &mut self.slots,
             &self.ctrl_stack,
             return_types,
             unify_to_expected_types,
@@ -373,8 +405,9 @@ impl ValidationStack {
         ))
     }
 
-    /// Validate the `SELECT` instruction within the current control block. Returns OK(()) on success, Err(_) otherwise.
-    pub fn validate_polymorphic_select(&mut self) -> Result<(), ValidationError> {
+    /// Validate the `SELECT` instruction within the current control block. Returns the type of the selected value on
+    /// success, Err(_) otherwise.
+    pub fn validate_polymorphic_select(&mut self) -> Result<ValidationStackEntry, ValidationError> {
         //SELECT instruction has the type signature
         //[t t i32] -> [t] where t unifies to a NumType(_) or VecType
 
@@ -397,8 +430,27 @@ impl ValidationStack {
             return Err(ValidationError::InvalidValidationStackValType(None));
         }
 
-        self.stack.push(unified_type);
-        Ok(())
+        self.stack.push(unified_type.clone());
+        self.slots.add(unified_type.slots());
+        Ok(unified_type)
+    }
+}
+
+/// The number of slots occupied at runtime by the values on a [`ValidationStack`], together with its maximum
+#[derive(Debug, Default, PartialEq, Eq)]
+struct SlotCount {
+    current: usize,
+    max: usize,
+}
+
+impl SlotCount {
+    fn add(&mut self, slots: usize) {
+        self.current += slots;
+        self.max = self.max.max(self.current);
+    }
+
+    fn sub(&mut self, slots: usize) {
+        self.current -= slots;
     }
 }
 
@@ -418,6 +470,15 @@ impl ValidationStackEntry {
         }
     }
 
+    /// Returns the number of slots a value of this type occupies at runtime. [`ValidationStackEntry::Bottom`] only
+    /// occurs in unreachable code, where it is counted as a single slot.
+    fn slots(&self) -> usize {
+        match self {
+            ValidationStackEntry::Val(ty) => ty.slots(),
+            ValidationStackEntry::Bottom => 1,
+        }
+    }
+
     /// convenience method that returns `Some(other)` if `self.unifies_to(other)` is true and `None` otherwise
     fn unify(&self, other: &ValidationStackEntry) -> Option<Self> {
         self.unifies_to(other).then(|| other.clone())
@@ -487,6 +548,34 @@ mod tests {
         })
     }
 
+    #[test]
+    fn max_slots() {
+        let mut stack = ValidationStack::new();
+
+        stack.push_valtype(ValType::VecType);
+        stack.push_valtype(ValType::NumType(NumType::I32));
+        assert_eq!(stack.max_slots(), 3);
+
+        stack
+            .assert_pop_val_type(ValType::NumType(NumType::I32))
+            .unwrap();
+        stack.assert_pop_val_type(ValType::VecType).unwrap();
+        stack.push_valtype(ValType::NumType(NumType::I64));
+        stack.push_valtype(ValType::NumType(NumType::I64));
+        assert_eq!(stack.max_slots(), 3);
+
+        stack.push_valtype(ValType::NumType(NumType::F32));
+        stack.push_valtype(ValType::VecType);
+        assert_eq!(stack.max_slots(), 5);
+
+        push_dummy_untyped_label(&mut stack);
+        stack.push_valtype(ValType::VecType);
+        stack.make_unspecified().unwrap();
+        stack.assert_val_types(&[ValType::VecType], true).unwrap();
+        assert_eq!(stack.max_slots(), 7);
+        assert_eq!(stack.slots.current, 7);
+    }
+
     #[test]
     fn push_then_pop() {
         let mut stack = ValidationStack::new();
@@ -0,0 +1,96 @@
+use checked::{Store, StoredRunState, StoredValue};
+use wasm::{
+    addrs::FuncAddr,
+    config::{Config, ConfigLayer},
+    resumable::InterruptReason,
+    validate,
+    watchpoints::{Watchpoint, Watchpoints},
+};
+
+const WAT: &str = r#"
+(module
+    (global $counter (export "counter") (mut i32) (i32.const 0))
+    (func $bump
+        (global.set $counter (i32.add (global.get $counter) (i32.const 1)))
+    )
+    (func (export "run") (result i32)
+        (call $bump)
+        (call $bump)
+        (global.get $counter)
+    )
+)"#;
+
+/// A layer that only counts calls and forwards everything else to the watchpoints it wraps
+#[derive(Default)]
+struct CallCounter<C: Config> {
+    calls: u32,
+    inner: C,
+}
+
+impl<C: Config> ConfigLayer for CallCounter<C> {
+    type Inner = C;
+
+    fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    fn inner_mut(&mut self) -> &mut C {
+        &mut self.inner
+    }
+
+    fn call_hook(&mut self, func_addr: FuncAddr) {
+        self.inner.call_hook(func_addr);
+        self.calls += 1;
+    }
+}
+
+#[test_log::test]
+fn layers_forward_hooks_they_do_not_override() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(CallCounter::<Watchpoints>::default());
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let counter = store
+        .instance_export(module, "counter")
+        .unwrap()
+        .as_global()
+        .unwrap();
+    let run = store
+        .instance_export(module, "run")
+        .unwrap()
+        .as_func()
+        .unwrap();
+    store
+        .user_data_mut()
+        .inner
+        .add(Watchpoint::Global(*counter));
+
+    let resumable = store
+        .create_resumable(run, Vec::new(), None)
+        .unwrap()
+        .as_wasm()
+        .unwrap();
+    let mut run_state = store.resume_wasm(resumable).unwrap();
+    let mut interruptions = 0;
+    let values = loop {
+        run_state = match run_state {
+            StoredRunState::Finished { values, .. } => break values,
+            StoredRunState::Interrupted {
+                resumable,
+                reason: InterruptReason::AccessHook,
+            } => {
+                interruptions += 1;
+                store.resume_wasm(resumable).unwrap()
+            }
+            _ => panic!("unexpected run state"),
+        };
+    };
+
+    assert_eq!(values, [StoredValue::I32(2)]);
+    assert_eq!(interruptions, 2);
+    assert_eq!(store.user_data_mut().inner.take_hits().len(), 2);
+    assert_eq!(store.user_data_mut().calls, 2);
+}
@@ -0,0 +1,187 @@
+use checked::{Store, StoredRunState, StoredValue};
+use wasm::{
+    cost_table::{CostTable, CostTableConfig, CostTableError},
+    fuel_bound::{fuel_bound, FuelBoundAnnotations},
+    opcodes::{fc_extensions::MEMORY_FILL, fd_extensions::V128_CONST, I32_ADD},
+    profiler::Opcode,
+    validate,
+};
+
+fn calibrated_costs() -> CostTable {
+    let mut cost_table = CostTable::uniform(2);
+    cost_table
+        .set_flat_cost(Opcode::Single(I32_ADD), 5)
+        .unwrap();
+    cost_table
+        .set_cost_per_element(Opcode::FcExtension(MEMORY_FILL), 3)
+        .unwrap();
+    cost_table
+        .set_flat_cost(Opcode::FdExtension(V128_CONST), 7)
+        .unwrap();
+    cost_table
+}
+
+#[test_log::test]
+fn cost_table_round_trip() {
+    let cost_table = calibrated_costs();
+    let encoded = cost_table.encode();
+    assert_eq!(CostTable::decode(&encoded), Ok(cost_table.clone()));
+
+    assert_eq!(cost_table.flat_cost(Opcode::Single(I32_ADD)), 5);
+    assert_eq!(cost_table.flat_cost(Opcode::FcExtension(MEMORY_FILL)), 2);
+    assert_eq!(
+        cost_table.cost_per_element(Opcode::FcExtension(MEMORY_FILL)),
+        3
+    );
+    assert_eq!(cost_table.flat_cost(Opcode::FdExtension(V128_CONST)), 7);
+
+    // only the header and three entries are encoded
+    assert!(encoded.len() < 20);
+}
+
+#[test_log::test]
+fn invalid_cost_tables_are_rejected() {
+    let valid = calibrated_costs().encode();
+
+    assert_eq!(
+        CostTable::decode(b"WASM\x01\x01\x00"),
+        Err(CostTableError::InvalidMagic)
+    );
+    assert_eq!(
+        CostTable::decode(b"WFCT\x02\x01\x00"),
+        Err(CostTableError::UnsupportedVersion(2))
+    );
+    assert_eq!(
+        CostTable::decode(&valid[..valid.len() - 1]),
+        Err(CostTableError::UnexpectedEof)
+    );
+    assert_eq!(
+        CostTable::decode(&[valid.as_slice(), &[0]].concat()),
+        Err(CostTableError::TrailingBytes)
+    );
+
+    // an entry for the unknown instruction 0xFC 0x20
+    assert_eq!(
+        CostTable::decode(b"WFCT\x01\x01\x01\xfc\x20\x05"),
+        Err(CostTableError::UnknownInstruction(Opcode::FcExtension(
+            0x20
+        )))
+    );
+    // two entries for i32.add
+    assert_eq!(
+        CostTable::decode(b"WFCT\x01\x01\x02\x6a\x05\x6a\x06"),
+        Err(CostTableError::DuplicateEntry(Opcode::Single(I32_ADD)))
+    );
+    // a cost that does not fit into 64 bits
+    assert_eq!(
+        CostTable::decode(b"WFCT\x01\xff\xff\xff\xff\xff\xff\xff\xff\xff\x7f\x00"),
+        Err(CostTableError::MalformedInteger)
+    );
+
+    let mut cost_table = CostTable::default();
+    assert_eq!(
+        cost_table.set_cost_per_element(Opcode::Single(I32_ADD), 1),
+        Err(CostTableError::NotChargedPerElement(Opcode::Single(
+            I32_ADD
+        )))
+    );
+}
+
+#[test_log::test]
+fn execution_uses_loaded_costs() {
+    let wat = r#"
+    (module
+        (memory 1)
+        (func (export "fill") (param i32)
+            (drop (i32.add (local.get 0) (i32.const 1)))
+            (memory.fill (i32.const 0) (i32.const 0) (local.get 0))
+        )
+    )"#;
+    let wasm_bytes = wat::parse_str(wat).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+
+    let cost_table = CostTable::decode(&calibrated_costs().encode()).unwrap();
+    let analysis_config = CostTableConfig::new((), cost_table.clone());
+
+    let mut store = Store::new(CostTableConfig::new((), cost_table));
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let fill = store
+        .instance_export(module, "fill")
+        .unwrap()
+        .as_func()
+        .unwrap();
+    let resumable = store
+        .create_resumable(fill, vec![StoredValue::I32(10)], Some(100))
+        .unwrap()
+        .as_wasm()
+        .unwrap();
+    let StoredRunState::Finished {
+        maybe_remaining_fuel: Some(remaining_fuel),
+        ..
+    } = store.resume_wasm(resumable).unwrap()
+    else {
+        panic!("expected execution to finish");
+    };
+
+    // six instructions cost 2, i32.add costs 5 and memory.fill costs 2 plus 3 per byte
+    assert_eq!(100 - remaining_fuel, 6 * 2 + 5 + 2 + 10 * 3);
+
+    // static analyses use the same costs
+    let mut annotations = FuelBoundAnnotations::new();
+    annotations.set_element_count(find_memory_fill(&wasm_bytes), 10);
+    assert_eq!(
+        fuel_bound(&validation_info, &analysis_config, &annotations, 0)
+            .unwrap()
+            .fuel(),
+        Some(100 - remaining_fuel)
+    );
+}
+
+#[test_log::test]
+fn overflowing_costs_run_out_of_fuel() {
+    let wat = r#"
+    (module
+        (memory 1)
+        (func (export "fill") (param i32)
+            (memory.fill (i32.const 0) (i32.const 0) (local.get 0))
+        )
+    )"#;
+    let wasm_bytes = wat::parse_str(wat).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+
+    let mut cost_table = CostTable::uniform(1);
+    cost_table
+        .set_cost_per_element(Opcode::FcExtension(MEMORY_FILL), u64::MAX / 2)
+        .unwrap();
+    let mut store = Store::new(CostTableConfig::new((), cost_table));
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let fill = store
+        .instance_export(module, "fill")
+        .unwrap()
+        .as_func()
+        .unwrap();
+    let resumable = store
+        .create_resumable(fill, vec![StoredValue::I32(3)], Some(u64::MAX))
+        .unwrap()
+        .as_wasm()
+        .unwrap();
+    let StoredRunState::Resumable { required_fuel, .. } = store.resume_wasm(resumable).unwrap()
+    else {
+        panic!("expected execution to run out of fuel");
+    };
+    assert_eq!(required_fuel.get(), u64::MAX);
+}
+
+/// Returns the bytecode offset of the only `memory.fill` instruction in `wasm_bytes`
+fn find_memory_fill(wasm_bytes: &[u8]) -> usize {
+    wasm_bytes
+        .windows(3)
+        .position(|window| window == [0xfc, 0x0b, 0x00])
+        .unwrap()
+}
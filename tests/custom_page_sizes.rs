@@ -0,0 +1,103 @@
+use checked::{Store, StoredExternVal};
+use wasm::{validate, Limits, MemType, RuntimeError, TrapError, ValidationError};
+
+const WAT: &str = r#"
+(module
+    (memory (export "memory") 2 5 (pagesize 1))
+    (func (export "size") (result i32)
+        (memory.size)
+    )
+    (func (export "grow") (param $n i32) (result i32)
+        (memory.grow (local.get $n))
+    )
+    (func (export "store") (param $addr i32) (param $value i32)
+        (i32.store8 (local.get $addr) (local.get $value))
+    )
+)"#;
+
+#[test_log::test]
+fn memory_size_and_grow_use_custom_page_size() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(());
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let memory = store
+        .instance_export(module, "memory")
+        .unwrap()
+        .as_mem()
+        .unwrap();
+    let function = |name| {
+        store
+            .instance_export(module, name)
+            .unwrap()
+            .as_func()
+            .unwrap()
+    };
+    let (size, grow, store_fn) = (function("size"), function("grow"), function("store"));
+
+    assert_eq!(store.mem_type(memory).page_size(), 1);
+    assert_eq!(store.invoke_simple_typed::<(), i32>(size, ()).unwrap(), 2);
+    assert_eq!(store.resource_usage().memory_bytes, 2);
+    store
+        .invoke_simple_typed::<(i32, i32), ()>(store_fn, (1, 42))
+        .unwrap();
+    assert_eq!(
+        store.invoke_simple_typed::<(i32, i32), ()>(store_fn, (2, 42)),
+        Err(RuntimeError::Trap(TrapError::MemoryOrDataAccessOutOfBounds))
+    );
+
+    assert_eq!(store.invoke_simple_typed::<i32, i32>(grow, 1).unwrap(), 2);
+    store.mem_grow(memory, 2).unwrap();
+    assert_eq!(store.mem_size(memory), 5);
+    assert_eq!(store.resource_usage().memory_bytes, 5);
+    store
+        .invoke_simple_typed::<(i32, i32), ()>(store_fn, (4, 42))
+        .unwrap();
+
+    // the maximum is given in 1-byte pages as well
+    assert_eq!(store.invoke_simple_typed::<i32, i32>(grow, 1).unwrap(), -1);
+    assert_eq!(store.mem_size(memory), 5);
+}
+
+#[test_log::test]
+fn page_sizes_are_validated_and_matched_on_import() {
+    let too_large = wat::parse_str("(module (memory 0x10001 (pagesize 65536)))").unwrap();
+    assert_eq!(
+        validate(&too_large).err(),
+        Some(ValidationError::MemoryTooLarge)
+    );
+    let byte_pages = wat::parse_str("(module (memory 0x10001 (pagesize 1)))").unwrap();
+    assert!(validate(&byte_pages).is_ok());
+    let unsupported = wat::parse_str("(module (memory 1 (pagesize 256)))").unwrap();
+    assert_eq!(
+        validate(&unsupported).err(),
+        Some(ValidationError::InvalidPageSize(8))
+    );
+
+    let wasm_bytes = wat::parse_str(r#"(module (import "env" "memory" (memory 1)))"#).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(());
+    let byte_memory = store
+        .mem_alloc(MemType {
+            limits: Limits {
+                min: 0x10000,
+                max: None,
+            },
+            page_size_log2: 0,
+        })
+        .unwrap();
+    assert_eq!(store.mem_size(byte_memory), 0x10000);
+    assert_eq!(
+        store
+            .module_instantiate(
+                &validation_info,
+                vec![StoredExternVal::Mem(byte_memory)],
+                None
+            )
+            .err(),
+        Some(RuntimeError::InvalidImportType)
+    );
+}
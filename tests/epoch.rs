@@ -0,0 +1,138 @@
+use checked::{Store, StoredRunState, StoredValue};
+use wasm::{
+    epoch::{Epoch, EpochDeadline},
+    resumable::InterruptReason,
+    validate,
+};
+
+const WAT: &str = r#"
+(module
+    (func $count (export "count") (param $n i32) (result i32)
+        (local $i i32)
+        (loop $loop
+            (local.set $i (i32.add (local.get $i) (i32.const 1)))
+            (br_if $loop (i32.lt_u (local.get $i) (local.get $n)))
+        )
+        (local.get $i)
+    )
+    (func (export "call_count") (result i32)
+        (call $count (i32.const 3))
+    )
+    (func (export "loop_forever")
+        (loop $loop (br $loop))
+    )
+)"#;
+
+#[test_log::test]
+fn deadline_interrupts_at_back_edges_and_calls() {
+    let epoch = Epoch::new();
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(EpochDeadline::new((), &epoch));
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let count = store
+        .instance_export(module, "count")
+        .unwrap()
+        .as_func()
+        .unwrap();
+    let call_count = store
+        .instance_export(module, "call_count")
+        .unwrap()
+        .as_func()
+        .unwrap();
+
+    // without a reached deadline, execution is not interrupted
+    store.user_data_mut().set_deadline(1);
+    let resumable = store
+        .create_resumable(count, vec![StoredValue::I32(3)], None)
+        .unwrap();
+    let StoredRunState::Finished { values, .. } = store.resume(resumable).unwrap() else {
+        panic!("expected execution to finish");
+    };
+    assert_eq!(values, [StoredValue::I32(3)]);
+
+    // once the deadline is reached, the next back-edge interrupts execution a single time
+    epoch.increment();
+    assert!(store.user_data_mut().deadline_reached());
+    let resumable = store
+        .create_resumable(count, vec![StoredValue::I32(3)], None)
+        .unwrap();
+    let mut run_state = store.resume(resumable).unwrap();
+    let mut interruptions = 0;
+    let values = loop {
+        match run_state {
+            StoredRunState::Finished { values, .. } => break values,
+            StoredRunState::Interrupted {
+                resumable,
+                reason: InterruptReason::InterruptionHook,
+            } => {
+                interruptions += 1;
+                run_state = store.resume_wasm(resumable).unwrap();
+            }
+            _ => panic!("expected an interruption"),
+        }
+    };
+    assert_eq!(values, [StoredValue::I32(3)]);
+    assert_eq!(interruptions, 1);
+    assert_eq!(store.user_data_mut().deadline(), None);
+
+    // calls are interrupted before the first instruction of the callee
+    store.user_data_mut().set_deadline(0);
+    let resumable = store
+        .create_resumable(call_count, Vec::new(), None)
+        .unwrap();
+    let StoredRunState::Interrupted {
+        resumable,
+        reason: InterruptReason::InterruptionHook,
+    } = store.resume(resumable).unwrap()
+    else {
+        panic!("expected an interruption");
+    };
+    assert_eq!(resumable.fuel(), None);
+
+    // the interruption disarmed the deadline, so the back-edges of the callee do not interrupt execution
+    let StoredRunState::Finished { values, .. } = store.resume_wasm(resumable).unwrap() else {
+        panic!("expected execution to finish");
+    };
+    assert_eq!(values, [StoredValue::I32(3)]);
+}
+
+#[test_log::test]
+fn epoch_advanced_asynchronously_interrupts_infinite_loop() {
+    static EPOCH: Epoch = Epoch::new();
+
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(EpochDeadline::new((), &EPOCH));
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let loop_forever = store
+        .instance_export(module, "loop_forever")
+        .unwrap()
+        .as_func()
+        .unwrap();
+
+    store.user_data_mut().set_deadline(1);
+    let timer = std::thread::spawn(|| {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        EPOCH.increment();
+    });
+
+    let resumable = store
+        .create_resumable(loop_forever, Vec::new(), None)
+        .unwrap();
+    let run_state = store.resume(resumable).unwrap();
+    assert!(matches!(
+        run_state,
+        StoredRunState::Interrupted {
+            reason: InterruptReason::InterruptionHook,
+            ..
+        }
+    ));
+    timer.join().unwrap();
+}
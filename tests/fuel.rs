@@ -1,7 +1,9 @@
 use checked::{Store, StoredRunState, StoredValue};
-use core::panic;
+use core::{ops::ControlFlow, panic};
 use log::info;
-use wasm::validate;
+use wasm::{
+    addrs::MemAddr, config::Config, resumable::InterruptReason, validate, RuntimeError, TrapError,
+};
 
 #[test_log::test]
 
@@ -122,6 +124,7 @@ fn resumable() {
                 }
                 store.resume_wasm(resumable).unwrap()
             }
+            StoredRunState::Interrupted { .. } => unreachable!("there are no hooks"),
             StoredRunState::HostCalled { .. } => unreachable!("there are no host functions"),
         };
 
@@ -139,6 +142,7 @@ fn resumable() {
                 }
                 store.resume_wasm(resumable).unwrap()
             }
+            StoredRunState::Interrupted { .. } => unreachable!("there are no hooks"),
             StoredRunState::HostCalled { .. } => unreachable!("there are no host functions"),
         };
 
@@ -214,6 +218,7 @@ fn resumable_internal_state() {
                 }
                 store.resume_wasm(resumable).unwrap()
             }
+            StoredRunState::Interrupted { .. } => unreachable!("there are no hooks"),
             StoredRunState::HostCalled { .. } => unreachable!("there are no host functions"),
         }
     }
@@ -285,3 +290,340 @@ fn fueled_initialization_fail() {
     let module = store.module_instantiate(validation_info, Vec::new(), Some(0));
     assert!(matches!(module, Err(wasm::RuntimeError::OutOfFuel)));
 }
+
+/// A config with different costs for different instructions, so that running out of fuel at the wrong instruction
+/// becomes visible in the remaining and required fuel
+struct VariedCosts;
+
+impl Config for VariedCosts {
+    fn get_flat_cost(&self, instr: u8) -> u64 {
+        1 + u64::from(instr % 3)
+    }
+
+    fn get_fc_extension_flat_cost(&self, _instr: u32) -> u64 {
+        2
+    }
+
+    fn get_fc_extension_cost_per_element(&self, _instr: u32) -> u64 {
+        3
+    }
+}
+
+#[test_log::test]
+fn out_of_fuel_is_exact_within_basic_blocks() {
+    let wat = r#"(module
+        (memory 1)
+        (func (export "sum") (param $n i32) (result i32) (local $sum i32)
+            (loop $loop
+                (local.set $sum (i32.add (local.get $sum) (local.get $n)))
+                (memory.fill (i32.const 0) (local.get $n) (i32.const 4))
+                (local.set $n (i32.sub (local.get $n) (i32.const 1)))
+                (br_if $loop (local.get $n))
+            )
+            (local.get $sum)
+        )
+    )"#;
+    let wasm_bytes = wat::parse_str(wat).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(VariedCosts);
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let sum = store
+        .instance_export(module, "sum")
+        .unwrap()
+        .as_func()
+        .unwrap();
+
+    // step through the function one instruction at a time to find the cost of every instruction
+    let mut instruction_costs = Vec::new();
+    let mut resumable = store
+        .create_resumable(sum, vec![StoredValue::I32(3)], Some(0))
+        .unwrap()
+        .as_wasm()
+        .unwrap();
+    loop {
+        match store.resume_wasm(resumable).unwrap() {
+            StoredRunState::Resumable {
+                resumable: mut paused,
+                required_fuel,
+            } => {
+                assert_eq!(*paused.fuel_mut(), Some(0));
+                instruction_costs.push(required_fuel.get());
+                *paused.fuel_mut() = Some(required_fuel.get());
+                resumable = paused;
+            }
+            StoredRunState::Finished {
+                values,
+                maybe_remaining_fuel,
+            } => {
+                assert_eq!(values, [StoredValue::I32(6)]);
+                assert_eq!(maybe_remaining_fuel, Some(0));
+                break;
+            }
+            _ => panic!("expected execution to pause or finish"),
+        }
+    }
+    let total_fuel: u64 = instruction_costs.iter().sum();
+
+    // for any amount of fuel, execution must stop right before the first instruction that cannot be paid for
+    for fuel in 0..total_fuel {
+        let resumable = store
+            .create_resumable(sum, vec![StoredValue::I32(3)], Some(fuel))
+            .unwrap()
+            .as_wasm()
+            .unwrap();
+        let StoredRunState::Resumable {
+            resumable: mut paused,
+            required_fuel,
+        } = store.resume_wasm(resumable).unwrap()
+        else {
+            panic!("expected execution to run out of fuel");
+        };
+
+        let mut consumed = 0;
+        let next_cost = instruction_costs
+            .iter()
+            .find(|cost| {
+                consumed += *cost;
+                consumed > fuel
+            })
+            .unwrap();
+        let expected_remaining = fuel - (consumed - next_cost);
+        assert_eq!(*paused.fuel_mut(), Some(expected_remaining));
+        assert_eq!(required_fuel.get(), next_cost - expected_remaining);
+    }
+}
+
+/// A config that interrupts execution after every memory store
+struct InterruptOnStore;
+
+impl Config for InterruptOnStore {
+    fn memory_store_hook(
+        &mut self,
+        _mem_addr: MemAddr,
+        _address: usize,
+        _bytes: &[u8],
+    ) -> ControlFlow<()> {
+        ControlFlow::Break(())
+    }
+}
+
+#[test_log::test]
+fn interruption_within_basic_block_keeps_unused_fuel() {
+    let wat = r#"(module
+        (memory 1)
+        (func (export "store")
+            (i32.store (i32.const 0) (i32.const 1))
+            (drop (i32.const 2))
+            (drop (i32.const 3))
+        )
+    )"#;
+    let wasm_bytes = wat::parse_str(wat).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(InterruptOnStore);
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let function = store
+        .instance_export(module, "store")
+        .unwrap()
+        .as_func()
+        .unwrap();
+
+    let resumable = store
+        .create_resumable(function, Vec::new(), Some(100))
+        .unwrap()
+        .as_wasm()
+        .unwrap();
+    let StoredRunState::Interrupted {
+        mut resumable,
+        reason: InterruptReason::AccessHook,
+    } = store.resume_wasm(resumable).unwrap()
+    else {
+        panic!("expected execution to be interrupted by the store");
+    };
+    // only the two constants and the store were executed
+    assert_eq!(*resumable.fuel_mut(), Some(97));
+
+    let StoredRunState::Finished {
+        maybe_remaining_fuel,
+        ..
+    } = store.resume_wasm(resumable).unwrap()
+    else {
+        panic!("expected execution to finish");
+    };
+    assert_eq!(maybe_remaining_fuel, Some(93));
+}
+
+#[test_log::test]
+fn trap_within_basic_block_keeps_unused_fuel() {
+    let wat = r#"(module
+        (func (export "divide")
+            (drop (i32.div_u (i32.const 1) (i32.const 0)))
+            (drop (i32.const 2))
+            (drop (i32.const 3))
+        )
+    )"#;
+    let wasm_bytes = wat::parse_str(wat).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(());
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let function = store
+        .instance_export(module, "divide")
+        .unwrap()
+        .as_func()
+        .unwrap();
+
+    let pool = store.fuel_pool_alloc(100).unwrap();
+    let resumable = store
+        .create_resumable_with_fuel_pool(function, Vec::new(), pool)
+        .unwrap();
+    assert!(matches!(
+        store.resume(resumable),
+        Err(RuntimeError::Trap(TrapError::DivideBy0))
+    ));
+    // only the two constants and the division were executed
+    assert_eq!(store.fuel_pool_remaining(pool), 97);
+}
+
+#[test_log::test]
+fn resumables_share_fuel_pool() {
+    let wat = r#"
+    (module
+        (func (export "add") (param i32 i32) (result i32)
+            (i32.add (local.get 0) (local.get 1))
+        )
+    )"#;
+    let wasm_bytes = wat::parse_str(wat).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(());
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let add = store
+        .instance_export(module, "add")
+        .unwrap()
+        .as_func()
+        .unwrap();
+    let params = vec![StoredValue::I32(1), StoredValue::I32(2)];
+
+    // each invocation costs 3 fuel
+    let pool = store.fuel_pool_alloc(5).unwrap();
+    let first = store
+        .create_resumable_with_fuel_pool(add, params.clone(), pool)
+        .unwrap();
+    let StoredRunState::Finished {
+        values,
+        maybe_remaining_fuel,
+    } = store.resume(first).unwrap()
+    else {
+        panic!("expected execution to finish");
+    };
+    assert_eq!(values, [StoredValue::I32(3)]);
+    assert_eq!(maybe_remaining_fuel, Some(2));
+    assert_eq!(store.fuel_pool_remaining(pool), 2);
+
+    // both resumables pause against the same, now empty pool
+    let second = store
+        .create_resumable_with_fuel_pool(add, params.clone(), pool)
+        .unwrap();
+    let StoredRunState::Resumable {
+        resumable: second,
+        required_fuel,
+    } = store.resume(second).unwrap()
+    else {
+        panic!("expected execution to pause");
+    };
+    assert_eq!(required_fuel.get(), 1);
+    assert_eq!(second.fuel(), None);
+    assert_eq!(second.fuel_pool(), Some(*pool));
+    assert_eq!(store.fuel_pool_remaining(pool), 0);
+
+    let third = store
+        .create_resumable_with_fuel_pool(add, params, pool)
+        .unwrap();
+    let StoredRunState::Resumable {
+        resumable: third,
+        required_fuel: _,
+    } = store.resume(third).unwrap()
+    else {
+        panic!("expected execution to pause");
+    };
+
+    // a refill is shared by both resumables in the order they are resumed
+    store.fuel_pool_set(pool, 2);
+    store.fuel_pool_refill(pool, 2);
+    let StoredRunState::Finished {
+        maybe_remaining_fuel,
+        ..
+    } = store.resume_wasm(second).unwrap()
+    else {
+        panic!("expected execution to finish");
+    };
+    assert_eq!(maybe_remaining_fuel, Some(3));
+    let StoredRunState::Finished {
+        maybe_remaining_fuel,
+        ..
+    } = store.resume_wasm(third).unwrap()
+    else {
+        panic!("expected execution to finish");
+    };
+    assert_eq!(maybe_remaining_fuel, Some(0));
+    assert_eq!(store.fuel_pool_remaining(pool), 0);
+}
+
+/// A config that records the amounts of fuel passed to the fuel hook
+#[derive(Default)]
+struct RecordFuel(Vec<u64>);
+
+impl Config for RecordFuel {
+    fn fuel_hook(&mut self, fuel: u64) {
+        self.0.push(fuel);
+    }
+}
+
+#[test_log::test]
+fn fuel_hook_is_called_once_per_basic_block() {
+    let wat = r#"(module
+        (func $double (param i32) (result i32)
+            (i32.add (local.get 0) (local.get 0))
+        )
+        (func (export "run") (result i32)
+            (drop (i32.const 1))
+            (call $double (i32.const 2))
+            (i32.const 3)
+            (i32.add)
+        )
+    )"#;
+    let wasm_bytes = wat::parse_str(wat).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(RecordFuel::default());
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let run = store
+        .instance_export(module, "run")
+        .unwrap()
+        .as_func()
+        .unwrap();
+
+    let StoredRunState::Finished {
+        values,
+        maybe_remaining_fuel,
+    } = store.invoke(run, Vec::new(), Some(100)).unwrap()
+    else {
+        panic!("expected execution to finish");
+    };
+    assert_eq!(values, [StoredValue::I32(7)]);
+    // the caller is split into two basic blocks by the call, the callee is a single basic block
+    assert_eq!(store.user_data_mut().0, [4, 3, 2]);
+    assert_eq!(maybe_remaining_fuel, Some(91));
+}
@@ -0,0 +1,169 @@
+use checked::{Store, StoredRunState, StoredValue};
+use wasm::{
+    fuel_bound::{
+        exported_fuel_bounds, fuel_bound, FuelBound, FuelBoundAnnotations, PathStep,
+        UnboundedReason,
+    },
+    validate,
+};
+
+/// Invokes an exported function with plenty of fuel and returns the amount of fuel it consumed
+fn consumed_fuel(wasm_bytes: &[u8], name: &str, params: Vec<StoredValue>) -> u64 {
+    const FUEL: u64 = 1_000_000;
+
+    let validation_info = validate(wasm_bytes).unwrap();
+    let mut store = Store::new(());
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let function = store
+        .instance_export(module, name)
+        .unwrap()
+        .as_func()
+        .unwrap();
+    let resumable = store
+        .create_resumable(function, params, Some(FUEL))
+        .unwrap()
+        .as_wasm()
+        .unwrap();
+    let StoredRunState::Finished {
+        maybe_remaining_fuel: Some(remaining_fuel),
+        ..
+    } = store.resume_wasm(resumable).unwrap()
+    else {
+        panic!("expected execution to finish");
+    };
+    FUEL - remaining_fuel
+}
+
+#[test_log::test]
+fn branching_function_is_bounded_by_most_expensive_path() {
+    let wat = r#"
+    (module
+        (func (export "select_expensive") (param i32) (result i32)
+            (if (result i32) (local.get 0)
+                (then (i32.add (i32.add (i32.const 1) (i32.const 2)) (i32.const 3)))
+                (else (i32.const 0))
+            )
+        )
+    )"#;
+    let wasm_bytes = wat::parse_str(wat).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+
+    let bounds = exported_fuel_bounds(&validation_info, &(), &FuelBoundAnnotations::new());
+    assert_eq!(bounds.len(), 1);
+    assert_eq!(bounds[0].0, "select_expensive");
+
+    // the bound is exact for the most expensive path
+    let expensive = consumed_fuel(&wasm_bytes, "select_expensive", vec![StoredValue::I32(1)]);
+    let cheap = consumed_fuel(&wasm_bytes, "select_expensive", vec![StoredValue::I32(0)]);
+    assert!(cheap < expensive);
+    assert_eq!(bounds[0].1.fuel(), Some(expensive));
+}
+
+const LOOP_WAT: &str = r#"
+(module
+    (memory 1)
+    (func $body (param i32)
+        (i32.store (local.get 0) (local.get 0))
+    )
+    (func (export "count") (param $n i32)
+        (loop $loop
+            (call $body (local.get $n))
+            (local.set $n (i32.sub (local.get $n) (i32.const 1)))
+            (br_if $loop (local.get $n))
+        )
+        (memory.fill (i32.const 0) (i32.const 0) (i32.const 16))
+    )
+)"#;
+
+#[test_log::test]
+fn loops_require_iteration_bounds() {
+    let wasm_bytes = wat::parse_str(LOOP_WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut annotations = FuelBoundAnnotations::new();
+
+    let Some(FuelBound::Unbounded { reasons }) = fuel_bound(&validation_info, &(), &annotations, 1)
+    else {
+        panic!("expected the loop to be unbounded");
+    };
+    let [UnboundedReason::Loop {
+        func_idx: 1,
+        offset,
+    }] = reasons.into_iter().collect::<Vec<_>>()[..]
+    else {
+        panic!("expected the loop to be the only reason");
+    };
+
+    annotations.set_loop_iterations(offset, 10);
+    let Some(FuelBound::Bounded {
+        fuel,
+        critical_path,
+    }) = fuel_bound(&validation_info, &(), &annotations, 1)
+    else {
+        panic!("expected the annotated loop to be bounded");
+    };
+
+    let PathStep::Loop {
+        offset: loop_offset,
+        iterations: 10,
+        worst_iteration,
+        ..
+    } = &critical_path[0]
+    else {
+        panic!("expected the loop to be on the critical path");
+    };
+    assert_eq!(*loop_offset, offset);
+    assert!(matches!(
+        worst_iteration[..],
+        [PathStep::Call { callee: 0, .. }]
+    ));
+
+    // without a cost per element, memory.fill only costs its flat cost
+    assert_eq!(critical_path.len(), 1);
+    assert_eq!(
+        fuel,
+        consumed_fuel(&wasm_bytes, "count", vec![StoredValue::I32(10)])
+    );
+}
+
+#[test_log::test]
+fn recursion_requires_recursion_depth() {
+    let wat = r#"
+    (module
+        (func $factorial (export "factorial") (param i64) (result i64)
+            (if (result i64) (i64.eqz (local.get 0))
+                (then (i64.const 1))
+                (else
+                    (i64.mul
+                        (local.get 0)
+                        (call $factorial (i64.sub (local.get 0) (i64.const 1)))
+                    )
+                )
+            )
+        )
+    )"#;
+    let wasm_bytes = wat::parse_str(wat).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut annotations = FuelBoundAnnotations::new();
+
+    assert_eq!(
+        fuel_bound(&validation_info, &(), &annotations, 0),
+        Some(FuelBound::Unbounded {
+            reasons: [UnboundedReason::Recursion { func_idx: 0 }].into()
+        })
+    );
+
+    // factorial(5) has 6 activations of factorial on the stack at once
+    annotations.set_recursion_depth(0, 6);
+    let bound = fuel_bound(&validation_info, &(), &annotations, 0)
+        .unwrap()
+        .fuel()
+        .unwrap();
+    assert_eq!(
+        bound,
+        consumed_fuel(&wasm_bytes, "factorial", vec![StoredValue::I64(5)])
+    );
+    assert!(bound > consumed_fuel(&wasm_bytes, "factorial", vec![StoredValue::I64(4)]));
+}
@@ -1,8 +1,9 @@
 use log::info;
 
-use checked::{Store, StoredExternVal, StoredValue};
+use checked::{Store, StoredExternVal, StoredRunState, StoredValue};
 use registry::Registry;
 use wasm::{
+    resumable::InterruptReason,
     validate,
     value::{F32, F64},
     FuncType, NumType, ResultType, RuntimeError, ValType,
@@ -24,9 +25,11 @@ pub fn host_func_call_within_module() {
 
     let mut store = Store::new(());
     let mut registry = Registry::default();
-    let hello = registry.alloc_host_function_typed(&mut store, |(), ()| {
-        info!("Host function says hello from wasm!");
-    });
+    let hello = registry
+        .alloc_host_function_typed(&mut store, |(), ()| {
+            info!("Host function says hello from wasm!");
+        })
+        .unwrap();
     let importing_mod = store
         .module_instantiate(&validation_info, vec![StoredExternVal::Func(hello)], None)
         .unwrap()
@@ -47,9 +50,11 @@ pub fn host_func_call_as_first_func() {
     let mut store = Store::new(());
     let mut registry = Registry::default();
 
-    let hello = registry.alloc_host_function_typed(&mut store, |(), ()| {
-        info!("Host function says hello from wasm!");
-    });
+    let hello = registry
+        .alloc_host_function_typed(&mut store, |(), ()| {
+            info!("Host function says hello from wasm!");
+        })
+        .unwrap();
 
     let result = registry.invoke_without_fuel_typed(&mut (), &mut store, hello, ());
     assert_eq!(Ok(()), result);
@@ -67,9 +72,11 @@ pub fn host_func_call_as_start_func() {
 
     let mut store = Store::new(());
     let mut registry = Registry::default();
-    let hello = registry.alloc_host_function_typed(&mut store, |(), ()| {
-        info!("Host function says hello from wasm!");
-    });
+    let hello = registry
+        .alloc_host_function_typed(&mut store, |(), ()| {
+            info!("Host function says hello from wasm!");
+        })
+        .unwrap();
 
     let _module_addr = store
         .module_instantiate(&validation_info, vec![StoredExternVal::Func(hello)], None)
@@ -94,9 +101,11 @@ pub fn host_func_call_within_start_func() {
     let validation_info = validate(&wasm_bytes).unwrap();
     let mut store = Store::new(());
     let mut registry = Registry::default();
-    let hello = registry.alloc_host_function_typed(&mut store, |(), ()| {
-        info!("Host function says hello from wasm!");
-    });
+    let hello = registry
+        .alloc_host_function_typed(&mut store, |(), ()| {
+            info!("Host function says hello from wasm!");
+        })
+        .unwrap();
     let _module_addr = store
         .module_instantiate(&validation_info, vec![StoredExternVal::Func(hello)], None)
         .expect("instantiation to be successful");
@@ -124,7 +133,9 @@ pub fn simple_multivariate_host_func_within_module() {
 
     let mut store = Store::new(());
     let mut registry = Registry::default();
-    let fancy_add_mult = registry.alloc_host_function_typed(&mut store, fancy_add_mult);
+    let fancy_add_mult = registry
+        .alloc_host_function_typed(&mut store, fancy_add_mult)
+        .unwrap();
 
     let importing_mod = store
         .module_instantiate(
@@ -157,7 +168,9 @@ pub fn simple_multivariate_host_func_with_host_func_wrapper() {
 
     let mut store = Store::new(());
     let mut registry = Registry::default();
-    let wrapped_add_mult = registry.alloc_host_function_typed(&mut store, wrapped_add_mult);
+    let wrapped_add_mult = registry
+        .alloc_host_function_typed(&mut store, wrapped_add_mult)
+        .unwrap();
     let importing_mod = store
         .module_instantiate(
             &validation_info,
@@ -182,7 +195,9 @@ pub fn simple_multivariate_host_func_with_host_func_wrapper() {
 pub fn simple_multivariate_host_func_as_first_func() {
     let mut store = Store::new(());
     let mut registry = Registry::default();
-    let fancy_add_mult = registry.alloc_host_function_typed(&mut store, fancy_add_mult);
+    let fancy_add_mult = registry
+        .alloc_host_function_typed(&mut store, fancy_add_mult)
+        .unwrap();
 
     let result = registry
         .invoke_without_fuel_typed::<_, (i32, f64), (f64, i32)>(
@@ -226,31 +241,35 @@ pub fn weird_multi_typed_host_func() {
     let mut store = Store::new(());
     let mut registry = Registry::default();
 
-    let weird_mult = registry.alloc_host_function(
-        &mut store,
-        FuncType {
-            params: ResultType {
-                valtypes: vec![ValType::NumType(NumType::I32)],
-            },
-            returns: ResultType {
-                valtypes: vec![ValType::NumType(NumType::F64)],
+    let weird_mult = registry
+        .alloc_host_function(
+            &mut store,
+            FuncType {
+                params: ResultType {
+                    valtypes: vec![ValType::NumType(NumType::I32)],
+                },
+                returns: ResultType {
+                    valtypes: vec![ValType::NumType(NumType::F64)],
+                },
             },
-        },
-        weird_add_mult,
-    );
+            weird_add_mult,
+        )
+        .unwrap();
 
-    let weird_add = registry.alloc_host_function(
-        &mut store,
-        FuncType {
-            params: ResultType {
-                valtypes: vec![ValType::NumType(NumType::F32)],
-            },
-            returns: ResultType {
-                valtypes: vec![ValType::NumType(NumType::I64)],
+    let weird_add = registry
+        .alloc_host_function(
+            &mut store,
+            FuncType {
+                params: ResultType {
+                    valtypes: vec![ValType::NumType(NumType::F32)],
+                },
+                returns: ResultType {
+                    valtypes: vec![ValType::NumType(NumType::I64)],
+                },
             },
-        },
-        weird_add_mult,
-    );
+            weird_add_mult,
+        )
+        .unwrap();
 
     let importing_mod = store
         .module_instantiate(
@@ -297,18 +316,20 @@ pub fn host_func_runtime_error() {
 
     let mut store = Store::new(());
     let mut registry = Registry::default();
-    let mult3 = registry.alloc_host_function(
-        &mut store,
-        FuncType {
-            params: ResultType {
-                valtypes: vec![ValType::NumType(NumType::I32)],
-            },
-            returns: ResultType {
-                valtypes: vec![ValType::NumType(NumType::I32)],
+    let mult3 = registry
+        .alloc_host_function(
+            &mut store,
+            FuncType {
+                params: ResultType {
+                    valtypes: vec![ValType::NumType(NumType::I32)],
+                },
+                returns: ResultType {
+                    valtypes: vec![ValType::NumType(NumType::I32)],
+                },
             },
-        },
-        mult3,
-    );
+            mult3,
+        )
+        .unwrap();
     let importing_mod = store
         .module_instantiate(&validation_info, vec![StoredExternVal::Func(mult3)], None)
         .unwrap()
@@ -326,3 +347,160 @@ pub fn host_func_runtime_error() {
     );
     assert_eq!(Err(RuntimeError::HostFunctionSignatureMismatch), result);
 }
+
+#[test_log::test]
+pub fn host_func_charges_fuel() {
+    let wat = r#"(module
+    (import "env" "work" (func $work (param i32) (result i32)))
+    (func (export "run") (result i32)
+        (call $work (i32.const 5))
+        (i32.add (i32.const 1))
+    )
+)"#;
+    let wasm_bytes = wat::parse_str(wat).unwrap();
+    let validation_info = validate(&wasm_bytes).expect("validation failed");
+
+    let mut store = Store::new(());
+    let mut registry = Registry::default();
+    // the host charges 10 fuel per unit of work
+    let work = registry
+        .alloc_host_function_with_fuel(
+            &mut store,
+            FuncType {
+                params: ResultType {
+                    valtypes: vec![ValType::NumType(NumType::I32)],
+                },
+                returns: ResultType {
+                    valtypes: vec![ValType::NumType(NumType::I32)],
+                },
+            },
+            |(), params| {
+                let [StoredValue::I32(units)] = params[..] else {
+                    panic!("expected a single i32 parameter");
+                };
+                (vec![StoredValue::I32(units * 2)], 10 * u64::from(units))
+            },
+        )
+        .unwrap();
+    let module = store
+        .module_instantiate(&validation_info, vec![StoredExternVal::Func(work)], None)
+        .unwrap()
+        .module_addr;
+    let run = store
+        .instance_export(module, "run")
+        .unwrap()
+        .as_func()
+        .unwrap();
+
+    // with enough fuel, the charge is deducted in addition to the four executed instructions
+    let resumable = store.create_resumable(run, Vec::new(), Some(100)).unwrap();
+    let StoredRunState::HostCalled {
+        host_call,
+        resumable,
+    } = store.resume(resumable).unwrap()
+    else {
+        panic!("expected a host call");
+    };
+    let StoredRunState::Interrupted {
+        resumable,
+        reason: InterruptReason::HostCallFinished,
+    } = registry
+        .perform_host_call(&mut (), &mut store, host_call, resumable)
+        .unwrap()
+    else {
+        panic!("expected execution to continue");
+    };
+    let StoredRunState::Finished {
+        values,
+        maybe_remaining_fuel,
+    } = store.resume_wasm(resumable).unwrap()
+    else {
+        panic!("expected execution to finish");
+    };
+    assert_eq!(values, [StoredValue::I32(11)]);
+    assert_eq!(maybe_remaining_fuel, Some(100 - 50 - 4));
+
+    // otherwise, execution pauses after the host call until enough fuel is provided
+    let resumable = store.create_resumable(run, Vec::new(), Some(30)).unwrap();
+    let StoredRunState::HostCalled {
+        host_call,
+        resumable,
+    } = store.resume(resumable).unwrap()
+    else {
+        panic!("expected a host call");
+    };
+    let StoredRunState::Resumable {
+        mut resumable,
+        required_fuel,
+    } = registry
+        .perform_host_call(&mut (), &mut store, host_call, resumable)
+        .unwrap()
+    else {
+        panic!("expected execution to pause");
+    };
+    assert_eq!(required_fuel.get(), 50 - 28);
+
+    *resumable.fuel_mut() = Some(40);
+    let StoredRunState::Resumable {
+        mut resumable,
+        required_fuel,
+    } = store.resume_wasm(resumable).unwrap()
+    else {
+        panic!("expected execution to pause again");
+    };
+    assert_eq!(required_fuel.get(), 10);
+
+    *resumable.fuel_mut() = Some(60);
+    let StoredRunState::Finished {
+        values,
+        maybe_remaining_fuel,
+    } = store.resume_wasm(resumable).unwrap()
+    else {
+        panic!("expected execution to finish");
+    };
+    assert_eq!(values, [StoredValue::I32(11)]);
+    assert_eq!(maybe_remaining_fuel, Some(60 - 50 - 2));
+
+    // a directly invoked host function keeps its return values until enough fuel is provided
+    let resumable = store
+        .create_resumable(work, vec![StoredValue::I32(5)], Some(40))
+        .unwrap();
+    let StoredRunState::HostCalled {
+        host_call,
+        resumable,
+    } = store.resume(resumable).unwrap()
+    else {
+        panic!("expected a host call");
+    };
+    let StoredRunState::Resumable {
+        mut resumable,
+        required_fuel,
+    } = registry
+        .perform_host_call(&mut (), &mut store, host_call, resumable)
+        .unwrap()
+    else {
+        panic!("expected the host call to pause");
+    };
+    assert_eq!(required_fuel.get(), 10);
+
+    *resumable.fuel_mut() = Some(45);
+    let StoredRunState::Resumable {
+        mut resumable,
+        required_fuel,
+    } = store.resume_wasm(resumable).unwrap()
+    else {
+        panic!("expected the host call to pause again");
+    };
+    assert_eq!(required_fuel.get(), 5);
+
+    *resumable.fuel_mut() = Some(60);
+    let StoredRunState::Finished {
+        values,
+        maybe_remaining_fuel,
+    } = store.resume_wasm(resumable).unwrap()
+    else {
+        panic!("expected the host call to finish");
+    };
+    assert_eq!(values, [StoredValue::I32(10)]);
+    assert_eq!(maybe_remaining_fuel, Some(10));
+}
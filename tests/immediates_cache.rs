@@ -0,0 +1,71 @@
+use checked::Store;
+use wasm::{validate, validate_with_options, ValidationOptions};
+
+const WAT: &str = r#"
+(module
+    (memory 1)
+    (global $scale (mut i32) (i32.const 3))
+    (func $weight (param $x i32) (result i32)
+        (i32.mul (local.get $x) (global.get $scale))
+    )
+    (func (export "run") (param $n i32) (result i32)
+        (local $i i32)
+        (local $sum i32)
+        (loop $continue
+            (i32.store offset=0x1234 (i32.shl (local.get $i) (i32.const 2)) (call $weight (local.get $i)))
+            (local.set $sum
+                (i32.add
+                    (local.get $sum)
+                    (i32.load offset=0x1234 (i32.shl (local.get $i) (i32.const 2)))
+                )
+            )
+            (global.set $scale (i32.add (global.get $scale) (i32.const -100000)))
+            (br_if $continue (i32.lt_u (local.tee $i (i32.add (local.get $i) (i32.const 1))) (local.get $n)))
+        )
+        (i32.add (local.get $sum) (i32.const 0x7FFFFFFF))
+    )
+)"#;
+
+fn run(wasm_bytes: &[u8], options: ValidationOptions) -> (usize, i32) {
+    let validation_info = validate_with_options(wasm_bytes, options).unwrap();
+    let mut store = Store::new(());
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let run = store
+        .instance_export(module, "run")
+        .unwrap()
+        .as_func()
+        .unwrap();
+    let result = store.invoke_simple_typed::<i32, i32>(run, 100).unwrap();
+    (validation_info.immediates_cache_size(), result)
+}
+
+#[test_log::test]
+fn pre_decoded_immediates_do_not_change_results() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    assert_eq!(validate(&wasm_bytes).unwrap().immediates_cache_size(), 0);
+
+    let (_, expected) = run(&wasm_bytes, ValidationOptions::default());
+    for limit in [1, 64, 256, usize::MAX] {
+        let (size, result) = run(
+            &wasm_bytes,
+            ValidationOptions {
+                immediates_cache_limit: limit,
+            },
+        );
+        assert!(size <= limit);
+        assert_eq!(result, expected, "limit of {limit} bytes");
+    }
+
+    // the cache only holds entries for instructions with immediates
+    let (unlimited_size, _) = run(
+        &wasm_bytes,
+        ValidationOptions {
+            immediates_cache_limit: usize::MAX,
+        },
+    );
+    assert!(unlimited_size > 0);
+    assert!(unlimited_size < 2 * wasm_bytes.len());
+}
@@ -0,0 +1,84 @@
+use checked::{Store, StoredExternVal};
+use wasm::{memory_backing::MemoryBacking, validate, Limits, MemType, RuntimeError};
+
+const PAGE_SIZE: usize = Limits::MEM_PAGE_SIZE as usize;
+
+const WAT: &str = r#"
+(module
+    (import "env" "memory" (memory 1))
+    (func (export "grow") (param $n i32) (result i32)
+        (memory.grow (local.get $n))
+    )
+    (func (export "load") (param $addr i32) (result i32)
+        (i32.load (local.get $addr))
+    )
+    (func (export "store") (param $addr i32) (param $value i32)
+        (i32.store (local.get $addr) (local.get $value))
+    )
+)"#;
+
+/// Returns a backing of `pages` pages, which is filled with garbage
+fn leaked_backing(pages: usize) -> MemoryBacking {
+    MemoryBacking::new(vec![0xAB; pages * PAGE_SIZE].leak())
+}
+
+#[test_log::test]
+fn backed_memory_grows_up_to_capacity() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(());
+    let backing = leaked_backing(3);
+    assert_eq!(backing.capacity(), 3 * PAGE_SIZE);
+    let memory = store
+        .mem_alloc_with_backing(MemType::new(Limits { min: 1, max: None }), backing)
+        .unwrap();
+
+    let module = store
+        .module_instantiate(&validation_info, vec![StoredExternVal::Mem(memory)], None)
+        .unwrap()
+        .module_addr;
+    let function = |name| {
+        store
+            .instance_export(module, name)
+            .unwrap()
+            .as_func()
+            .unwrap()
+    };
+    let (grow, load, store_fn) = (function("grow"), function("load"), function("store"));
+
+    // the memory is zeroed even though the backing contained garbage
+    assert_eq!(store.invoke_simple_typed::<i32, i32>(load, 0).unwrap(), 0);
+    store
+        .invoke_simple_typed::<(i32, i32), ()>(store_fn, (4, 42))
+        .unwrap();
+    assert_eq!(store.invoke_simple_typed::<i32, i32>(load, 4).unwrap(), 42);
+
+    assert_eq!(store.invoke_simple_typed::<i32, i32>(grow, 2).unwrap(), 1);
+    assert_eq!(store.mem_size(memory), 3);
+    assert_eq!(
+        store
+            .invoke_simple_typed::<i32, i32>(load, 3 * PAGE_SIZE as i32 - 4)
+            .unwrap(),
+        0
+    );
+
+    // the backing is full
+    assert_eq!(store.invoke_simple_typed::<i32, i32>(grow, 1).unwrap(), -1);
+    assert_eq!(store.mem_size(memory), 3);
+    assert_eq!(store.mem_grow(memory, 1), Err(RuntimeError::OutOfMemory));
+    assert_eq!(store.invoke_simple_typed::<i32, i32>(load, 4).unwrap(), 42);
+}
+
+#[test_log::test]
+fn backing_must_fit_initial_pages() {
+    let mut store = Store::new(());
+    assert_eq!(
+        store
+            .mem_alloc_with_backing(
+                MemType::new(Limits { min: 2, max: None }),
+                leaked_backing(1),
+            )
+            .err(),
+        Some(RuntimeError::MemoryBackingTooSmall)
+    );
+}
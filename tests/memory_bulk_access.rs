@@ -0,0 +1,86 @@
+use checked::Store;
+use wasm::{validate, Limits, MemType, MemoryProtection, RuntimeError, TrapError};
+
+const OUT_OF_BOUNDS: RuntimeError = RuntimeError::Trap(TrapError::MemoryOrDataAccessOutOfBounds);
+
+#[test_log::test]
+fn byte_ranges_and_typed_values() {
+    let mut store = Store::new(());
+    let memory = store
+        .mem_alloc(MemType::new(Limits { min: 1, max: None }))
+        .unwrap();
+    let end = Limits::MEM_PAGE_SIZE;
+
+    store.mem_write_bytes(memory, 8, b"hello").unwrap();
+    let mut buffer = [0; 7];
+    store.mem_read_bytes(memory, 7, &mut buffer).unwrap();
+    assert_eq!(&buffer, b"\0hello\0");
+
+    store.mem_write_typed(memory, 0, -2i32).unwrap();
+    assert_eq!(store.mem_read_typed::<u32>(memory, 0), Ok(0xFFFF_FFFE));
+    assert_eq!(store.mem_read(memory, 0), Ok(0xFE));
+    store.mem_write_typed(memory, 16, 1.5f64).unwrap();
+    assert_eq!(store.mem_read_typed(memory, 16), Ok(1.5f64));
+    let v128: [u8; 16] = core::array::from_fn(|i| i as u8);
+    store.mem_write_typed(memory, end - 16, v128).unwrap();
+    assert_eq!(store.mem_read_typed(memory, end - 16), Ok(v128));
+
+    // accesses are bounds checked as a whole and do not partially succeed
+    assert_eq!(
+        store.mem_write_bytes(memory, end - 2, &[1, 2, 3]),
+        Err(OUT_OF_BOUNDS)
+    );
+    assert_eq!(
+        store.mem_read_typed::<u64>(memory, end - 4),
+        Err(OUT_OF_BOUNDS)
+    );
+    assert_eq!(store.mem_read_typed::<u16>(memory, end - 2), Ok(0x0F0E));
+
+    // protected ranges are respected
+    store
+        .mem_protect(memory, 8..9, MemoryProtection::ReadOnly)
+        .unwrap();
+    assert_eq!(
+        store.mem_write_typed(memory, 6, 0u32),
+        Err(RuntimeError::Trap(TrapError::MemoryProtectionViolation))
+    );
+    assert_eq!(store.mem_read_typed::<u8>(memory, 8), Ok(b'h'));
+}
+
+#[test_log::test]
+fn copy_between_memories() {
+    let wasm_bytes = wat::parse_str(
+        r#"(module (memory (export "memory") 1) (data (i32.const 0x100) "guest data"))"#,
+    )
+    .unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(());
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let guest = store
+        .instance_export(module, "memory")
+        .unwrap()
+        .as_mem()
+        .unwrap();
+    let host = store
+        .mem_alloc(MemType::new(Limits { min: 1, max: None }))
+        .unwrap();
+
+    store.mem_copy(host, 0, guest, 0x100, 10).unwrap();
+    let mut buffer = [0; 10];
+    store.mem_read_bytes(host, 0, &mut buffer).unwrap();
+    assert_eq!(&buffer, b"guest data");
+
+    // overlapping copies within the same memory
+    store.mem_copy(guest, 0x102, guest, 0x100, 10).unwrap();
+    let mut buffer = [0; 12];
+    store.mem_read_bytes(guest, 0x100, &mut buffer).unwrap();
+    assert_eq!(&buffer, b"guguest data");
+
+    assert_eq!(
+        store.mem_copy(host, Limits::MEM_PAGE_SIZE - 4, guest, 0, 5),
+        Err(OUT_OF_BOUNDS)
+    );
+}
@@ -105,6 +105,7 @@ fn fill_with_fuel() {
                 *resumable.fuel_mut().as_mut().unwrap() += 1;
                 run_state = store.resume_wasm(resumable).unwrap();
             }
+            StoredRunState::Interrupted { .. } => unreachable!("no hooks interrupt execution"),
             StoredRunState::HostCalled { .. } => unreachable!("no host calls exist"),
         }
     }
@@ -0,0 +1,206 @@
+use checked::Store;
+use wasm::{validate, MemoryProtection, RuntimeError, TrapError};
+
+const WAT: &str = r#"
+(module
+    (memory (export "memory") 1)
+    (data $calibration "\01\02\03\04")
+    (func (export "load") (param $addr i32) (result i32)
+        (i32.load8_u (local.get $addr))
+    )
+    (func (export "store") (param $addr i32) (param $value i32)
+        (i32.store8 (local.get $addr) (local.get $value))
+    )
+    (func (export "fill") (param $addr i32) (param $n i32)
+        (memory.fill (local.get $addr) (i32.const 0xFF) (local.get $n))
+    )
+    (func (export "copy") (param $dst i32) (param $src i32) (param $n i32)
+        (memory.copy (local.get $dst) (local.get $src) (local.get $n))
+    )
+    (func (export "init") (param $addr i32)
+        (memory.init $calibration (local.get $addr) (i32.const 0) (i32.const 4))
+    )
+)"#;
+
+const PROTECTION_VIOLATION: Result<(), RuntimeError> =
+    Err(RuntimeError::Trap(TrapError::MemoryProtectionViolation));
+
+#[test_log::test]
+fn protected_ranges_trap_on_guest_accesses() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(());
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let memory = store
+        .instance_export(module, "memory")
+        .unwrap()
+        .as_mem()
+        .unwrap();
+    let function = |name| {
+        store
+            .instance_export(module, name)
+            .unwrap()
+            .as_func()
+            .unwrap()
+    };
+    let (load, store_fn, fill, copy, init) = (
+        function("load"),
+        function("store"),
+        function("fill"),
+        function("copy"),
+        function("init"),
+    );
+
+    // the calibration table is written before it is protected
+    store.mem_write(memory, 0x1000, 42).unwrap();
+    store
+        .mem_protect(memory, 0x1000..0x2000, MemoryProtection::ReadOnly)
+        .unwrap();
+    store
+        .mem_protect(memory, 0x3000..0x3010, MemoryProtection::NoAccess)
+        .unwrap();
+
+    // reads of read-only ranges are allowed, writes are not
+    assert_eq!(
+        store.invoke_simple_typed::<i32, i32>(load, 0x1000).unwrap(),
+        42
+    );
+    assert_eq!(
+        store.invoke_simple_typed::<(i32, i32), ()>(store_fn, (0x1FFF, 1)),
+        PROTECTION_VIOLATION
+    );
+    assert_eq!(
+        store.invoke_simple_typed::<(i32, i32), ()>(fill, (0x0F00, 0x101)),
+        PROTECTION_VIOLATION
+    );
+    assert_eq!(
+        store.invoke_simple_typed::<(i32, i32, i32), ()>(copy, (0x1800, 0, 4)),
+        PROTECTION_VIOLATION
+    );
+    assert_eq!(
+        store.invoke_simple_typed::<i32, ()>(init, 0x0FFE),
+        PROTECTION_VIOLATION
+    );
+    assert_eq!(store.mem_read(memory, 0x1000), Ok(42));
+    assert_eq!(store.mem_write(memory, 0x1000, 0), PROTECTION_VIOLATION);
+
+    // no-access ranges can neither be read nor written
+    assert_eq!(
+        store.invoke_simple_typed::<i32, i32>(load, 0x3008),
+        Err(RuntimeError::Trap(TrapError::MemoryProtectionViolation))
+    );
+    assert_eq!(
+        store.invoke_simple_typed::<(i32, i32, i32), ()>(copy, (0, 0x300F, 2)),
+        PROTECTION_VIOLATION
+    );
+    assert_eq!(
+        store.mem_read(memory, 0x3000),
+        Err(RuntimeError::Trap(TrapError::MemoryProtectionViolation))
+    );
+
+    // accesses next to protected ranges and empty bulk accesses are unaffected
+    store
+        .invoke_simple_typed::<(i32, i32), ()>(fill, (0x0F00, 0x100))
+        .unwrap();
+    store
+        .invoke_simple_typed::<(i32, i32), ()>(fill, (0x1800, 0))
+        .unwrap();
+    store
+        .invoke_simple_typed::<(i32, i32, i32), ()>(copy, (0x2000, 0x1000, 4))
+        .unwrap();
+    assert_eq!(
+        store.invoke_simple_typed::<i32, i32>(load, 0x2000).unwrap(),
+        42
+    );
+
+    // the host can bypass the protection explicitly
+    store
+        .mem_access_mut_slice(memory, |bytes| bytes[0x1000] = 43)
+        .unwrap();
+    assert_eq!(
+        store.invoke_simple_typed::<i32, i32>(load, 0x1000).unwrap(),
+        43
+    );
+    store.mem_unprotect(memory, 0x1000..0x2000);
+    store
+        .invoke_simple_typed::<(i32, i32), ()>(store_fn, (0x1000, 44))
+        .unwrap();
+    assert_eq!(store.mem_read(memory, 0x1000), Ok(44));
+}
+
+#[test_log::test]
+fn out_of_bounds_accesses_trap_before_protection() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(());
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let memory = store
+        .instance_export(module, "memory")
+        .unwrap()
+        .as_mem()
+        .unwrap();
+    let store_fn = store
+        .instance_export(module, "store")
+        .unwrap()
+        .as_func()
+        .unwrap();
+    let fill = store
+        .instance_export(module, "fill")
+        .unwrap()
+        .as_func()
+        .unwrap();
+    store
+        .mem_protect(memory, 0xFF00..0x10000, MemoryProtection::ReadOnly)
+        .unwrap();
+
+    let out_of_bounds = Err(RuntimeError::Trap(TrapError::MemoryOrDataAccessOutOfBounds));
+    assert_eq!(
+        store.invoke_simple_typed::<(i32, i32), ()>(store_fn, (0x10000, 1)),
+        out_of_bounds
+    );
+    assert_eq!(
+        store.invoke_simple_typed::<(i32, i32), ()>(fill, (0xFFF0, 0x20)),
+        out_of_bounds
+    );
+    assert_eq!(store.mem_write(memory, 0x10000, 1), out_of_bounds);
+}
+
+#[test_log::test]
+fn invalid_regions_are_rejected() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(());
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let memory = store
+        .instance_export(module, "memory")
+        .unwrap()
+        .as_mem()
+        .unwrap();
+
+    #[allow(clippy::reversed_empty_ranges)]
+    for region in [0x100..0x100, 0x200..0x100, 0xFFFF..0x10001] {
+        assert_eq!(
+            store.mem_protect(memory, region.clone(), MemoryProtection::NoAccess),
+            Err(RuntimeError::InvalidMemoryRegion)
+        );
+        assert_eq!(
+            store.mem_map_mmio(memory, region),
+            Err(RuntimeError::InvalidMemoryRegion)
+        );
+    }
+
+    // regions ending at the end of the memory are valid
+    store
+        .mem_protect(memory, 0xFFFF..0x10000, MemoryProtection::NoAccess)
+        .unwrap();
+    store.mem_map_mmio(memory, 0..0x10000).unwrap();
+}
@@ -0,0 +1,215 @@
+use std::ops::ControlFlow;
+
+use checked::Store;
+use wasm::{addrs::MemAddr, config::Config, validate, RuntimeError, TrapError};
+
+const WAT: &str = r#"
+(module
+    (memory (export "memory") 1)
+    (func (export "load") (param $addr i32) (result i32)
+        (i32.load (local.get $addr))
+    )
+    (func (export "load8") (param $addr i32) (result i32)
+        (i32.load8_u (local.get $addr))
+    )
+    (func (export "store") (param $addr i32) (param $value i32)
+        (i32.store (local.get $addr) (local.get $value))
+    )
+    (data $data "\2a\2a\2a\2a")
+    (func (export "fill") (param $addr i32) (param $value i32) (param $n i32)
+        (memory.fill (local.get $addr) (local.get $value) (local.get $n))
+    )
+    (func (export "copy") (param $dst i32) (param $src i32) (param $n i32)
+        (memory.copy (local.get $dst) (local.get $src) (local.get $n))
+    )
+    (func (export "init") (param $addr i32)
+        (memory.init $data (local.get $addr) (i32.const 0) (i32.const 4))
+    )
+)"#;
+
+/// The address of a status register of the emulated device
+const STATUS: usize = 0x1000;
+
+/// The address of a data register of the emulated device
+const DATA: usize = 0x1004;
+
+/// A device with a status register which counts reads and a data register which records all writes
+#[derive(Default)]
+struct Device {
+    status_reads: u32,
+    written: Vec<(usize, Vec<u8>)>,
+    loads: usize,
+}
+
+impl Config for Device {
+    fn mmio_load_hook(&mut self, _mem_addr: MemAddr, address: usize, bytes: &mut [u8]) {
+        if address == STATUS {
+            self.status_reads += 1;
+            bytes.copy_from_slice(&self.status_reads.to_le_bytes()[..bytes.len()]);
+        }
+    }
+
+    fn mmio_store_hook(&mut self, _mem_addr: MemAddr, address: usize, bytes: &[u8]) {
+        self.written.push((address, bytes.to_vec()));
+    }
+
+    fn memory_load_hook(
+        &mut self,
+        _mem_addr: MemAddr,
+        _address: usize,
+        _bytes: &[u8],
+    ) -> ControlFlow<()> {
+        self.loads += 1;
+        ControlFlow::Continue(())
+    }
+}
+
+#[test_log::test]
+fn mmio_accesses_are_forwarded_to_config() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(Device::default());
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let memory = store
+        .instance_export(module, "memory")
+        .unwrap()
+        .as_mem()
+        .unwrap();
+    let function = |name| {
+        store
+            .instance_export(module, name)
+            .unwrap()
+            .as_func()
+            .unwrap()
+    };
+    let (load, load8, store_fn) = (function("load"), function("load8"), function("store"));
+    store.mem_map_mmio(memory, STATUS..DATA + 4).unwrap();
+
+    assert_eq!(
+        store
+            .invoke_simple_typed::<u32, u32>(load, STATUS as u32)
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        store
+            .invoke_simple_typed::<u32, u32>(load8, STATUS as u32)
+            .unwrap(),
+        2
+    );
+    store
+        .invoke_simple_typed::<(u32, u32), ()>(store_fn, (DATA as u32, 0xAABBCCDD))
+        .unwrap();
+    assert_eq!(
+        store.user_data_mut().written,
+        [(DATA, 0xAABBCCDDu32.to_le_bytes().to_vec())]
+    );
+    // the linear memory behind the region is untouched
+    assert_eq!(store.mem_read(memory, DATA as u32), Ok(0));
+
+    // an access partially overlapping the region is forwarded as well
+    store
+        .invoke_simple_typed::<(u32, u32), ()>(store_fn, (STATUS as u32 - 2, 7))
+        .unwrap();
+    assert_eq!(store.user_data_mut().written.len(), 2);
+
+    // other accesses use the linear memory
+    store
+        .invoke_simple_typed::<(u32, u32), ()>(store_fn, (0, 42))
+        .unwrap();
+    assert_eq!(store.invoke_simple_typed::<u32, u32>(load, 0).unwrap(), 42);
+    assert_eq!(store.user_data_mut().written.len(), 2);
+    assert_eq!(store.user_data_mut().status_reads, 2);
+
+    // the access hooks are called for memory-mapped I/O, too
+    assert_eq!(store.user_data_mut().loads, 3);
+}
+
+#[test_log::test]
+fn mmio_accesses_are_bounds_checked() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(Device::default());
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let memory = store
+        .instance_export(module, "memory")
+        .unwrap()
+        .as_mem()
+        .unwrap();
+    let store_fn = store
+        .instance_export(module, "store")
+        .unwrap()
+        .as_func()
+        .unwrap();
+    store.mem_map_mmio(memory, 0xFFF0..0x10000).unwrap();
+
+    assert_eq!(
+        store.invoke_simple_typed::<(u32, u32), ()>(store_fn, (0xFFFE, 1)),
+        Err(RuntimeError::Trap(TrapError::MemoryOrDataAccessOutOfBounds))
+    );
+    assert!(store.user_data_mut().written.is_empty());
+}
+
+#[test_log::test]
+fn bulk_memory_instructions_trap_on_mmio() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(Device::default());
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let memory = store
+        .instance_export(module, "memory")
+        .unwrap()
+        .as_mem()
+        .unwrap();
+    let function = |name| {
+        store
+            .instance_export(module, name)
+            .unwrap()
+            .as_func()
+            .unwrap()
+    };
+    let (fill, copy, init) = (function("fill"), function("copy"), function("init"));
+    store.mem_map_mmio(memory, STATUS..DATA + 4).unwrap();
+
+    let mmio_trap = Err(RuntimeError::Trap(TrapError::MmioBulkAccess));
+    assert_eq!(
+        store.invoke_simple_typed::<(u32, u32, u32), ()>(fill, (DATA as u32 - 2, 0x11, 4)),
+        mmio_trap
+    );
+    assert_eq!(
+        store.invoke_simple_typed::<u32, ()>(init, DATA as u32 + 2),
+        mmio_trap
+    );
+    // copying from and to the region traps
+    assert_eq!(
+        store.invoke_simple_typed::<(u32, u32, u32), ()>(copy, (0, STATUS as u32, 8)),
+        mmio_trap
+    );
+    assert_eq!(
+        store.invoke_simple_typed::<(u32, u32, u32), ()>(copy, (STATUS as u32, 0, 8)),
+        mmio_trap
+    );
+
+    // nothing was written to the linear memory behind the region and the memory-mapped I/O hooks were not called
+    assert_eq!(store.mem_read(memory, DATA as u32 - 2), Ok(0));
+    assert!(store.user_data_mut().written.is_empty());
+    assert_eq!(store.user_data_mut().status_reads, 0);
+
+    // bulk memory instructions right next to the region work as usual
+    store
+        .invoke_simple_typed::<(u32, u32, u32), ()>(fill, (DATA as u32 + 4, 0x11, 4))
+        .unwrap();
+    store
+        .invoke_simple_typed::<(u32, u32, u32), ()>(copy, (0, DATA as u32 + 4, 4))
+        .unwrap();
+    assert_eq!(store.mem_read(memory, 3), Ok(0x11));
+}
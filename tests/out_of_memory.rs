@@ -0,0 +1,149 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    ptr,
+};
+
+use checked::Store;
+use wasm::{validate, Limits, MemType, RuntimeError};
+
+/// The largest allocation this test binary's allocator serves. Larger allocations fail as if the host was out of
+/// memory.
+const MAX_ALLOCATION_SIZE: usize = 256 * 1024 * 1024;
+
+/// The number of pages that is guaranteed to exceed [`MAX_ALLOCATION_SIZE`]
+const TOO_MANY_PAGES: u32 = 2 * (MAX_ALLOCATION_SIZE / Limits::MEM_PAGE_SIZE as usize) as u32;
+
+struct LimitedAllocator;
+
+// SAFETY: All allocations are either forwarded to the system allocator or fail by returning a null pointer.
+unsafe impl GlobalAlloc for LimitedAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if layout.size() > MAX_ALLOCATION_SIZE {
+            return ptr::null_mut();
+        }
+        // SAFETY: The caller upholds the contract of `GlobalAlloc::alloc`.
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: The pointer was allocated by the system allocator, because all other allocations fail.
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: LimitedAllocator = LimitedAllocator;
+
+#[test_log::test]
+fn failed_growth_returns_minus_one() {
+    let wat = r#"
+    (module
+        (memory 1)
+        (table 0 funcref)
+        (func (export "grow_memory") (param $n i32) (result i32)
+            (memory.grow (local.get $n))
+        )
+        (func (export "grow_table") (param $n i32) (result i32)
+            (table.grow (ref.null func) (local.get $n))
+        )
+    )"#;
+    let wasm_bytes = wat::parse_str(wat).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(());
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let grow_memory = store
+        .instance_export(module, "grow_memory")
+        .unwrap()
+        .as_func()
+        .unwrap();
+    let grow_table = store
+        .instance_export(module, "grow_table")
+        .unwrap()
+        .as_func()
+        .unwrap();
+    let usage = store.resource_usage();
+
+    assert_eq!(
+        store
+            .invoke_simple_typed::<u32, i32>(grow_memory, TOO_MANY_PAGES)
+            .unwrap(),
+        -1
+    );
+    assert_eq!(
+        store
+            .invoke_simple_typed::<u32, i32>(grow_table, u32::MAX - 1)
+            .unwrap(),
+        -1
+    );
+    assert_eq!(store.resource_usage(), usage);
+
+    // the store is still usable after allocations failed
+    assert_eq!(
+        store
+            .invoke_simple_typed::<u32, i32>(grow_memory, 1)
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        store
+            .invoke_simple_typed::<u32, i32>(grow_table, 1)
+            .unwrap(),
+        0
+    );
+}
+
+#[test_log::test]
+fn allocation_failure_is_reported_as_error() {
+    let mut store = Store::new(());
+
+    let mem_type = MemType::new(Limits {
+        min: TOO_MANY_PAGES,
+        max: None,
+    });
+    assert_eq!(store.mem_alloc(mem_type), Err(RuntimeError::OutOfMemory));
+
+    let mem_addr = store
+        .mem_alloc(MemType::new(Limits { min: 0, max: None }))
+        .unwrap();
+    assert_eq!(
+        store.mem_grow(mem_addr, TOO_MANY_PAGES),
+        Err(RuntimeError::OutOfMemory)
+    );
+    assert_eq!(store.mem_size(mem_addr), 0);
+    store.mem_grow(mem_addr, 1).unwrap();
+    assert_eq!(store.mem_size(mem_addr), 1);
+}
+
+#[test_log::test]
+fn failed_instantiation_leaves_store_usable() {
+    let too_large = format!("(module (memory {TOO_MANY_PAGES}))");
+    let wasm_bytes = wat::parse_str(too_large).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(());
+    assert_eq!(
+        store
+            .module_instantiate(&validation_info, Vec::new(), None)
+            .err(),
+        Some(RuntimeError::OutOfMemory)
+    );
+
+    let wasm_bytes =
+        wat::parse_str(r#"(module (func (export "answer") (result i32) (i32.const 42)))"#).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let answer = store
+        .instance_export(module, "answer")
+        .unwrap()
+        .as_func()
+        .unwrap();
+    assert_eq!(
+        store.invoke_simple_typed::<(), i32>(answer, ()).unwrap(),
+        42
+    );
+}
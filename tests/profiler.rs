@@ -0,0 +1,182 @@
+use checked::Store;
+use wasm::{
+    opcodes,
+    profiler::{Opcode, ProfileWeight, Profiler},
+    validate,
+};
+
+const WAT: &str = r#"
+(module
+    (func $helper (param i32) (result i32)
+        local.get 0
+        i32.const 1
+        i32.add
+    )
+    (func $main (export "main") (result i32)
+        i32.const 1
+        call $helper
+        call $helper
+    )
+)"#;
+
+#[test_log::test]
+fn per_function_instruction_counts() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(Profiler::<()>::default());
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let main = store
+        .instance_export(module, "main")
+        .unwrap()
+        .as_func()
+        .unwrap();
+
+    let result: i32 = store.invoke_simple_typed(main, ()).unwrap();
+    assert_eq!(result, 3);
+
+    let profiles = store.inner().user_data.function_profiles();
+    assert_eq!(profiles.len(), 2);
+
+    // i32.const, call, call, end
+    let main_profile = &profiles[&*main];
+    assert_eq!(main_profile.calls, 1);
+    assert_eq!(main_profile.self_instructions, 4);
+    assert_eq!(main_profile.inclusive_instructions, 4 + 2 * 4);
+    assert_eq!(main_profile.opcodes[&Opcode::Single(opcodes::CALL)], 2);
+
+    // local.get, i32.const, i32.add, end
+    let (helper, helper_profile) = profiles
+        .iter()
+        .find(|(func_addr, _)| **func_addr != *main)
+        .unwrap();
+    assert_eq!(helper_profile.calls, 2);
+    assert_eq!(helper_profile.self_instructions, 8);
+    assert_eq!(helper_profile.inclusive_instructions, 8);
+
+    let histogram = store.inner().user_data.opcode_histogram();
+    assert_eq!(histogram[&Opcode::Single(opcodes::I32_CONST)], 3);
+    assert_eq!(histogram[&Opcode::Single(opcodes::END)], 3);
+    assert_eq!(Opcode::Single(opcodes::I32_ADD).to_string(), "I32_ADD");
+
+    // without names, functions are referred to by their address
+    let folded = store
+        .inner()
+        .user_data
+        .folded_stacks(ProfileWeight::Instructions);
+    let main_name = store.inner().user_data.function_name(*main);
+    let helper_name = store.inner().user_data.function_name(*helper);
+    assert!(main_name.starts_with("func["));
+    let mut lines: Vec<&str> = folded.lines().collect();
+    lines.sort();
+    assert_eq!(
+        lines,
+        [
+            format!("{main_name} 4"),
+            format!("{main_name};{helper_name} 8")
+        ]
+    );
+}
+
+#[test_log::test]
+fn folded_stacks_use_name_section_and_fuel() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(Profiler::<()>::default());
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let main = store
+        .instance_export(module, "main")
+        .unwrap()
+        .as_func()
+        .unwrap();
+
+    let names: Vec<_> = store
+        .instance_function_names(module)
+        .into_iter()
+        .map(|(func_addr, name)| (*func_addr, name))
+        .collect();
+    assert_eq!(names.len(), 2);
+    store.user_data_mut().set_function_names(names);
+
+    let resumable = store
+        .create_resumable(main, Vec::new(), Some(1000))
+        .unwrap()
+        .as_wasm()
+        .unwrap();
+    let checked::StoredRunState::Finished {
+        maybe_remaining_fuel: Some(remaining_fuel),
+        ..
+    } = store.resume_wasm(resumable).unwrap()
+    else {
+        panic!("expected execution to finish");
+    };
+
+    let profiler = &store.inner().user_data;
+    let folded = profiler.folded_stacks(ProfileWeight::Instructions);
+    let mut lines: Vec<&str> = folded.lines().collect();
+    lines.sort();
+    assert_eq!(lines, ["main 4", "main;helper 8"]);
+
+    // the final end instruction of a function does not consume fuel
+    let consumed_fuel = 1000 - remaining_fuel;
+    let profiles = profiler.function_profiles();
+    assert_eq!(profiles[&*main].inclusive_fuel, consumed_fuel);
+    assert_eq!(profiles[&*main].self_fuel, 3);
+    let folded_fuel = profiler.folded_stacks(ProfileWeight::Fuel);
+    let mut lines: Vec<&str> = folded_fuel.lines().collect();
+    lines.sort();
+    assert_eq!(lines, ["main 3", "main;helper 6"]);
+}
+
+#[test_log::test]
+fn call_stack_is_reset_after_a_trap() {
+    let wasm_bytes = wat::parse_str(
+        r#"
+(module
+    (func $inner (export "inner") (param i32) (result i32)
+        (if (local.get 0) (then unreachable))
+        (i32.const 1)
+    )
+    (func $outer (export "outer") (param i32) (result i32)
+        (call $inner (local.get 0))
+    )
+)"#,
+    )
+    .unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(Profiler::<()>::default());
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let names: Vec<_> = store
+        .instance_function_names(module)
+        .into_iter()
+        .map(|(func_addr, name)| (*func_addr, name))
+        .collect();
+    store.user_data_mut().set_function_names(names);
+    let function = |name| {
+        store
+            .instance_export(module, name)
+            .unwrap()
+            .as_func()
+            .unwrap()
+    };
+    let (inner, outer) = (function("inner"), function("outer"));
+
+    // the trap leaves both functions on the shadow call stack
+    assert!(store.invoke_simple_typed::<i32, i32>(outer, 1).is_err());
+    store.user_data_mut().clear();
+
+    // the next invocation starts with a fresh shadow call stack, even though it starts in the function on top of it
+    assert_eq!(store.invoke_simple_typed::<i32, i32>(inner, 0), Ok(1));
+    let profiler = &store.inner().user_data;
+    let folded = profiler.folded_stacks(ProfileWeight::Instructions);
+    assert_eq!(folded.lines().collect::<Vec<_>>(), ["inner 4"]);
+    assert_eq!(profiler.function_profiles()[&*inner].calls, 1);
+}
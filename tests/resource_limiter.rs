@@ -0,0 +1,198 @@
+use checked::Store;
+use wasm::{
+    addrs::{MemAddr, TableAddr},
+    config::{Config, GrowthDecision, ResourceUsage},
+    validate, Limits, MemType, RuntimeError, TrapError,
+};
+
+const WAT: &str = r#"
+(module
+    (memory 1)
+    (table 2 funcref)
+    (func (export "grow_memory") (param $n i32) (result i32)
+        (memory.grow (local.get $n))
+    )
+    (func (export "grow_table") (param $n i32) (result i32)
+        (table.grow (ref.null func) (local.get $n))
+    )
+)"#;
+
+/// A resource limiter that allows a fixed amount of memory and table elements per store
+struct Limiter {
+    max_memory_bytes: u64,
+    max_table_elements: u64,
+}
+
+impl Config for Limiter {
+    fn memory_grow_hook(
+        &mut self,
+        _mem_addr: MemAddr,
+        _current_pages: u32,
+        delta_pages: u32,
+        usage: &ResourceUsage,
+    ) -> GrowthDecision {
+        let delta_bytes = u64::from(delta_pages) * u64::from(Limits::MEM_PAGE_SIZE);
+        if usage.memory_bytes + delta_bytes <= self.max_memory_bytes {
+            GrowthDecision::Allow
+        } else {
+            GrowthDecision::Deny
+        }
+    }
+
+    fn table_grow_hook(
+        &mut self,
+        _table_addr: TableAddr,
+        _current_elements: u32,
+        delta_elements: u32,
+        usage: &ResourceUsage,
+    ) -> GrowthDecision {
+        if usage.table_elements + u64::from(delta_elements) <= self.max_table_elements {
+            GrowthDecision::Allow
+        } else {
+            GrowthDecision::Trap
+        }
+    }
+}
+
+#[test_log::test]
+fn limiter_denies_memory_and_traps_on_table_growth() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(Limiter {
+        max_memory_bytes: 3 * u64::from(Limits::MEM_PAGE_SIZE),
+        max_table_elements: 4,
+    });
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let grow_memory = store
+        .instance_export(module, "grow_memory")
+        .unwrap()
+        .as_func()
+        .unwrap();
+    let grow_table = store
+        .instance_export(module, "grow_table")
+        .unwrap()
+        .as_func()
+        .unwrap();
+
+    assert_eq!(
+        store.resource_usage(),
+        ResourceUsage {
+            memory_bytes: u64::from(Limits::MEM_PAGE_SIZE),
+            table_elements: 2,
+        }
+    );
+
+    // growth within the limit succeeds and is tracked
+    assert_eq!(
+        store
+            .invoke_simple_typed::<i32, i32>(grow_memory, 2)
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        store.resource_usage().memory_bytes,
+        3 * u64::from(Limits::MEM_PAGE_SIZE)
+    );
+
+    // a denied memory.grow returns -1 without trapping
+    assert_eq!(
+        store
+            .invoke_simple_typed::<i32, i32>(grow_memory, 1)
+            .unwrap(),
+        -1
+    );
+    assert_eq!(
+        store.resource_usage().memory_bytes,
+        3 * u64::from(Limits::MEM_PAGE_SIZE)
+    );
+
+    assert_eq!(
+        store
+            .invoke_simple_typed::<i32, i32>(grow_table, 2)
+            .unwrap(),
+        2
+    );
+    assert_eq!(store.resource_usage().table_elements, 4);
+
+    // a table.grow beyond the limit traps
+    assert_eq!(
+        store.invoke_simple_typed::<i32, i32>(grow_table, 1),
+        Err(RuntimeError::Trap(TrapError::ResourceLimitExceeded))
+    );
+    assert_eq!(store.resource_usage().table_elements, 4);
+}
+
+#[test_log::test]
+fn limiter_applies_to_host_growth() {
+    let mut store = Store::new(Limiter {
+        max_memory_bytes: u64::from(Limits::MEM_PAGE_SIZE),
+        max_table_elements: 0,
+    });
+    let mem_addr = store
+        .mem_alloc(MemType::new(Limits { min: 1, max: None }))
+        .unwrap();
+
+    assert_eq!(store.mem_grow(mem_addr, 1), Err(RuntimeError::GrowthDenied));
+    assert_eq!(store.mem_size(mem_addr), 1);
+    assert_eq!(
+        store.resource_usage().memory_bytes,
+        u64::from(Limits::MEM_PAGE_SIZE)
+    );
+}
+
+#[test_log::test]
+fn limiter_applies_to_initial_allocations() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+
+    // the initial page of the memory is denied
+    let mut store = Store::new(Limiter {
+        max_memory_bytes: 0,
+        max_table_elements: 2,
+    });
+    assert_eq!(
+        store
+            .module_instantiate(&validation_info, Vec::new(), None)
+            .err(),
+        Some(RuntimeError::GrowthDenied)
+    );
+    assert_eq!(
+        store
+            .mem_alloc(MemType::new(Limits { min: 1, max: None }))
+            .err(),
+        Some(RuntimeError::GrowthDenied)
+    );
+    assert_eq!(store.resource_usage().memory_bytes, 0);
+
+    // the initial elements of the table trap
+    let mut store = Store::new(Limiter {
+        max_memory_bytes: u64::from(Limits::MEM_PAGE_SIZE),
+        max_table_elements: 1,
+    });
+    assert_eq!(
+        store
+            .module_instantiate(&validation_info, Vec::new(), None)
+            .err(),
+        Some(RuntimeError::Trap(TrapError::ResourceLimitExceeded))
+    );
+    assert_eq!(store.resource_usage().table_elements, 0);
+}
+
+#[test_log::test]
+fn store_without_limiter_tracks_usage() {
+    let mut store = Store::new(());
+    let mem_addr = store
+        .mem_alloc(MemType::new(Limits { min: 0, max: None }))
+        .unwrap();
+    store.mem_grow(mem_addr, 2).unwrap();
+    assert_eq!(
+        store.resource_usage(),
+        ResourceUsage {
+            memory_bytes: 2 * u64::from(Limits::MEM_PAGE_SIZE),
+            table_elements: 0,
+        }
+    );
+}
@@ -0,0 +1,194 @@
+use std::{cell::RefCell, rc::Rc};
+
+use checked::{Store, StoredExternVal, StoredValue};
+use scheduler::{Policy, Scheduler, TaskConfig, TaskEvent};
+use wasm::{validate, RuntimeError, TrapError};
+
+const WAT: &str = r#"
+(module
+    (import "env" "tick" (func $tick (param i32)))
+    (func (export "work") (param $id i32) (param $n i32) (result i32)
+        (loop $loop
+            (call $tick (local.get $id))
+            (br_if $loop (local.tee $n (i32.sub (local.get $n) (i32.const 1))))
+        )
+        (local.get $id)
+    )
+    (func (export "trap")
+        (unreachable)
+    )
+)"#;
+
+/// Spawns two tasks ticking three times each, where the second one has the higher priority, and runs them to
+/// completion. Returns the ids in the order the tasks ticked.
+fn run_two_tasks(policy: Policy) -> Vec<u32> {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(());
+    let tick = store.func_alloc_typed::<u32, ()>(0).unwrap();
+    let module = store
+        .module_instantiate(&validation_info, vec![StoredExternVal::Func(tick)], None)
+        .unwrap()
+        .module_addr;
+    let work = store
+        .instance_export(module, "work")
+        .unwrap()
+        .as_func()
+        .unwrap();
+
+    let ticks = Rc::new(RefCell::new(Vec::new()));
+    let mut scheduler = Scheduler::new(policy);
+    let mut tasks = Vec::new();
+    for (id, priority) in [(1, 1), (2, 2)] {
+        let ticks = Rc::clone(&ticks);
+        let task = scheduler
+            .spawn(
+                &store,
+                work,
+                vec![StoredValue::I32(id), StoredValue::I32(3)],
+                // enough for a single iteration of the loop
+                TaskConfig {
+                    quantum: 8,
+                    priority,
+                },
+                move |_store, host_call| {
+                    assert_eq!(host_call.hostcode, 0);
+                    let [StoredValue::I32(id)] = host_call.params[..] else {
+                        panic!("expected a single i32 parameter");
+                    };
+                    ticks.borrow_mut().push(id);
+                    Ok((Vec::new(), 0))
+                },
+            )
+            .unwrap();
+        tasks.push(task);
+    }
+
+    let mut completed = Vec::new();
+    scheduler.run_to_completion(&mut store, |task, event| match event {
+        TaskEvent::Preempted => assert!(tasks.contains(&task)),
+        TaskEvent::Completed(values) => completed.push((task, values)),
+        event => panic!("unexpected event {event:?}"),
+    });
+    assert_eq!(completed.len(), 2);
+    for (task, values) in completed {
+        let id = tasks.iter().position(|known| *known == task).unwrap() as u32 + 1;
+        assert_eq!(values, [StoredValue::I32(id)]);
+    }
+    assert_eq!(scheduler.alive_tasks(), 0);
+
+    Rc::try_unwrap(ticks).unwrap().into_inner()
+}
+
+#[test_log::test]
+fn round_robin_interleaves_tasks() {
+    assert_eq!(run_two_tasks(Policy::RoundRobin), [1, 2, 1, 2, 1, 2]);
+}
+
+#[test_log::test]
+fn fixed_priority_runs_highest_priority_first() {
+    assert_eq!(run_two_tasks(Policy::FixedPriority), [2, 2, 2, 1, 1, 1]);
+}
+
+#[test_log::test]
+fn traps_and_handler_errors_are_reported() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(());
+    let tick = store.func_alloc_typed::<u32, ()>(0).unwrap();
+    let module = store
+        .module_instantiate(&validation_info, vec![StoredExternVal::Func(tick)], None)
+        .unwrap()
+        .module_addr;
+    let work = store
+        .instance_export(module, "work")
+        .unwrap()
+        .as_func()
+        .unwrap();
+    let trap = store
+        .instance_export(module, "trap")
+        .unwrap()
+        .as_func()
+        .unwrap();
+    let config = TaskConfig {
+        quantum: 100,
+        priority: 0,
+    };
+
+    let mut scheduler = Scheduler::new(Policy::RoundRobin);
+    let trapping = scheduler
+        .spawn(&store, trap, Vec::new(), config, |_, _| unreachable!())
+        .unwrap();
+    let failing = scheduler
+        .spawn(
+            &store,
+            work,
+            vec![StoredValue::I32(1), StoredValue::I32(1)],
+            config,
+            |_, _| Err(RuntimeError::HostFunctionSignatureMismatch),
+        )
+        .unwrap();
+
+    assert_eq!(
+        scheduler.run_slice(&mut store),
+        Some((
+            trapping,
+            TaskEvent::Trapped(RuntimeError::Trap(TrapError::ReachedUnreachable))
+        ))
+    );
+    assert_eq!(
+        scheduler.run_slice(&mut store),
+        Some((
+            failing,
+            TaskEvent::Trapped(RuntimeError::HostFunctionSignatureMismatch)
+        ))
+    );
+    assert!(!scheduler.is_alive(trapping));
+    assert_eq!(scheduler.run_slice(&mut store), None);
+}
+
+#[test_log::test]
+fn tasks_exceeding_their_quantum_are_removed() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(());
+    let tick = store.func_alloc_typed::<u32, ()>(0).unwrap();
+    let module = store
+        .module_instantiate(&validation_info, vec![StoredExternVal::Func(tick)], None)
+        .unwrap()
+        .module_addr;
+    let work = store
+        .instance_export(module, "work")
+        .unwrap()
+        .as_func()
+        .unwrap();
+    let config = TaskConfig {
+        quantum: 100,
+        priority: 0,
+    };
+
+    // every host call costs more fuel than a whole time slice provides
+    let mut scheduler = Scheduler::new(Policy::RoundRobin);
+    let expensive = scheduler
+        .spawn(
+            &store,
+            work,
+            vec![StoredValue::I32(1), StoredValue::I32(2)],
+            config,
+            |_, _| Ok((Vec::new(), 1000)),
+        )
+        .unwrap();
+
+    let mut events = Vec::new();
+    scheduler.run_to_completion(&mut store, |id, event| events.push((id, event)));
+    assert_eq!(
+        events,
+        [(
+            expensive,
+            TaskEvent::QuantumTooSmall {
+                required_fuel: 1000
+            }
+        )]
+    );
+    assert!(!scheduler.is_alive(expensive));
+}
@@ -0,0 +1,105 @@
+use checked::{Store, StoredRunState, StoredValue};
+use wasm::{config::Config, validate};
+
+const WAT: &str = r#"
+(module
+    (global $counter (mut i32) (i32.const 0))
+    (func $bump (export "bump") (param $by i32) (result i32)
+        (block $done
+            (br_if $done (i32.eqz (local.get $by)))
+            (global.set $counter (i32.add (global.get $counter) (local.get $by)))
+        )
+        (global.get $counter)
+    )
+)"#;
+
+#[test_log::test]
+fn instances_of_the_same_module_keep_their_own_state() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let mut store = Store::new(());
+
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let instances: Vec<_> = (0..3)
+        .map(|_| {
+            store
+                .module_instantiate(&validation_info, Vec::new(), None)
+                .unwrap()
+                .module_addr
+        })
+        .collect();
+    // instances do not depend on the validation info after instantiation
+    drop(validation_info);
+
+    let bump_functions: Vec<_> = instances
+        .iter()
+        .map(|&module| {
+            store
+                .instance_export(module, "bump")
+                .unwrap()
+                .as_func()
+                .unwrap()
+        })
+        .collect();
+    for (i, &bump) in bump_functions.iter().enumerate() {
+        let by = i32::try_from(i).unwrap() + 1;
+        assert_eq!(store.invoke_simple_typed::<i32, i32>(bump, by), Ok(by));
+        assert_eq!(store.invoke_simple_typed::<i32, i32>(bump, by), Ok(2 * by));
+    }
+    assert_eq!(
+        store.invoke_simple_typed::<i32, i32>(bump_functions[0], 0),
+        Ok(2)
+    );
+
+    // function names refer to the functions of the respective instance
+    for (&module, &bump) in instances.iter().zip(&bump_functions) {
+        assert_eq!(store.instance_function_names(module), vec![(bump, "bump")]);
+    }
+}
+
+/// A config which charges the same cost for every instruction
+struct FlatCost(u64);
+
+impl Config for FlatCost {
+    fn get_flat_cost(&self, _instr: u8) -> u64 {
+        self.0
+    }
+}
+
+/// Instantiates the module twice in a store with the given config and returns the fuel consumed by `bump` in each
+fn fuel_of_two_instances(validation_info: &wasm::ValidationInfo, config: FlatCost) -> [u64; 2] {
+    let mut store = Store::new(config);
+    [(); 2].map(|()| {
+        let module = store
+            .module_instantiate(validation_info, Vec::new(), None)
+            .unwrap()
+            .module_addr;
+        let bump = store
+            .instance_export(module, "bump")
+            .unwrap()
+            .as_func()
+            .unwrap();
+        let StoredRunState::Finished {
+            maybe_remaining_fuel: Some(remaining_fuel),
+            ..
+        } = store
+            .invoke(bump, vec![StoredValue::I32(1)], Some(1000))
+            .unwrap()
+        else {
+            panic!("expected execution to finish");
+        };
+        1000 - remaining_fuel
+    })
+}
+
+#[test_log::test]
+fn block_fuel_is_shared_per_store() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+
+    // instances in the same store share the fuel of their basic blocks, while other stores compute it for their config
+    let [first, second] = fuel_of_two_instances(&validation_info, FlatCost(1));
+    assert_eq!(first, second);
+    let [first_expensive, second_expensive] = fuel_of_two_instances(&validation_info, FlatCost(2));
+    assert_eq!(first_expensive, second_expensive);
+    assert_eq!(first_expensive, 2 * first);
+}
@@ -0,0 +1,133 @@
+use checked::Store;
+use wasm::validate;
+
+const WAT: &str = r#"
+(module
+    (memory (export "memory") 256)
+    (data (i32.const 0x800000) "\2A")
+    (func (export "load") (param $addr i32) (result i32)
+        (i32.load (local.get $addr))
+    )
+    (func (export "store") (param $addr i32) (param $value i32)
+        (i32.store (local.get $addr) (local.get $value))
+    )
+    (func (export "fill") (param $addr i32) (param $n i32)
+        (memory.fill (local.get $addr) (i32.const 0xFF) (local.get $n))
+    )
+)"#;
+
+#[test_log::test]
+fn sparse_memories_behave_like_dense_ones() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(());
+    store.set_sparse_memories(true);
+    assert!(store.sparse_memories());
+
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let memory = store
+        .instance_export(module, "memory")
+        .unwrap()
+        .as_mem()
+        .unwrap();
+    let function = |name| {
+        store
+            .instance_export(module, name)
+            .unwrap()
+            .as_func()
+            .unwrap()
+    };
+    let (load, store_fn, fill) = (function("load"), function("store"), function("fill"));
+
+    assert_eq!(store.mem_size(memory), 256);
+    assert_eq!(
+        store
+            .invoke_simple_typed::<i32, i32>(load, 0x800000)
+            .unwrap(),
+        42
+    );
+    assert_eq!(
+        store
+            .invoke_simple_typed::<i32, i32>(load, 0xFFFFFC)
+            .unwrap(),
+        0
+    );
+    assert!(store
+        .invoke_simple_typed::<i32, i32>(load, 0xFFFFFD)
+        .is_err());
+
+    store
+        .invoke_simple_typed::<(i32, i32), ()>(store_fn, (0x10000, 7))
+        .unwrap();
+    store
+        .invoke_simple_typed::<(i32, i32), ()>(fill, (0x20FFE, 4))
+        .unwrap();
+    assert_eq!(
+        store
+            .invoke_simple_typed::<i32, i32>(load, 0x20FFC)
+            .unwrap(),
+        0xFFFF_0000_u32 as i32
+    );
+
+    // the host sees the whole memory, including the untouched parts
+    let (len, sum) = store
+        .mem_access_mut_slice(memory, |bytes| {
+            (
+                bytes.len(),
+                bytes.iter().map(|byte| u32::from(*byte)).sum::<u32>(),
+            )
+        })
+        .unwrap();
+    assert_eq!(len, 256 * 0x10000);
+    assert_eq!(sum, 42 + 7 + 4 * 0xFF);
+}
+
+#[test_log::test]
+fn sparse_memories_are_snapshotted_without_allocating_untouched_chunks() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = wasm::Store::new(());
+    store.set_sparse_memories(true);
+    // SAFETY: No extern values are passed.
+    let module = unsafe { store.module_instantiate(&validation_info, Vec::new(), None) }
+        .unwrap()
+        .module_addr;
+    // SAFETY: This module address just came from the same store.
+    let memory = unsafe { store.instance_export(module, "memory") }
+        .unwrap()
+        .as_mem()
+        .unwrap();
+
+    // only the chunk of the data segment is allocated
+    // SAFETY: This memory address just came from the same store.
+    let allocated = unsafe { store.mem_allocated_bytes(memory) };
+    assert!(allocated < 0x10000);
+
+    let snapshot = store.state_snapshot().unwrap();
+    // SAFETY: This memory address just came from the same store.
+    assert_eq!(unsafe { store.mem_allocated_bytes(memory) }, allocated);
+
+    // restoring the snapshot zeroes the chunk written in between, but does not allocate any other chunk
+    // SAFETY: This memory address just came from the same store.
+    unsafe { store.mem_write(memory, 0x10000, 7) }.unwrap();
+    // SAFETY: The snapshot was taken from the same store.
+    unsafe { store.restore_state_snapshot(&snapshot) }.unwrap();
+    // SAFETY: This memory address just came from the same store.
+    unsafe {
+        assert_eq!(store.mem_allocated_bytes(memory), 2 * allocated);
+        assert_eq!(store.mem_read(memory, 0x10000), Ok(0));
+        assert_eq!(store.mem_read(memory, 0x800000), Ok(42));
+    }
+
+    // neither does accessing the memory through a slice
+    // SAFETY: This memory address just came from the same store.
+    unsafe {
+        store
+            .mem_access_mut_slice(memory, |bytes| assert_eq!(bytes[0x800000], 42))
+            .unwrap();
+        assert_eq!(store.mem_allocated_bytes(memory), 2 * allocated);
+    }
+}
@@ -723,12 +723,12 @@ fn init_spectest(
     linker: &mut Linker,
     registry: &mut Registry<()>,
 ) -> Result<(), RuntimeError> {
-    let memory = store.mem_alloc(MemType {
-        limits: Limits {
+    let memory = store
+        .mem_alloc(MemType::new(Limits {
             min: 1,
             max: Some(2),
-        },
-    });
+        }))
+        .unwrap();
 
     let table = store.table_alloc(
         TableType {
@@ -773,13 +773,27 @@ fn init_spectest(
         StoredValue::F64(F64(666.6)),
     )?;
 
-    let print = registry.alloc_host_function_typed(store, |(), _: ()| {});
-    let print_i32 = registry.alloc_host_function_typed(store, |(), _: i32| {});
-    let print_i64 = registry.alloc_host_function_typed(store, |(), _: i64| {});
-    let print_f32 = registry.alloc_host_function_typed(store, |(), _: f32| {});
-    let print_f64 = registry.alloc_host_function_typed(store, |(), _: f64| {});
-    let print_i32_f32 = registry.alloc_host_function_typed(store, |(), _: (i32, f32)| {});
-    let print_f64_f64 = registry.alloc_host_function_typed(store, |(), _: (f64, f64)| {});
+    let print = registry
+        .alloc_host_function_typed(store, |(), _: ()| {})
+        .unwrap();
+    let print_i32 = registry
+        .alloc_host_function_typed(store, |(), _: i32| {})
+        .unwrap();
+    let print_i64 = registry
+        .alloc_host_function_typed(store, |(), _: i64| {})
+        .unwrap();
+    let print_f32 = registry
+        .alloc_host_function_typed(store, |(), _: f32| {})
+        .unwrap();
+    let print_f64 = registry
+        .alloc_host_function_typed(store, |(), _: f64| {})
+        .unwrap();
+    let print_i32_f32 = registry
+        .alloc_host_function_typed(store, |(), _: (i32, f32)| {})
+        .unwrap();
+    let print_f64_f64 = registry
+        .alloc_host_function_typed(store, |(), _: (f64, f64)| {})
+        .unwrap();
 
     linker.define(
         "spectest".to_owned(),
@@ -0,0 +1,163 @@
+use checked::Store;
+use wasm::{
+    config::Config,
+    stack_bound::{
+        exported_stack_bounds, function_stack_usage, stack_bound, FunctionStackUsage, StackBound,
+        StackBoundAnnotations, UnboundedReason,
+    },
+    validate, RuntimeError,
+};
+
+const WAT: &str = r#"
+(module
+    (func $leaf (param i32 i32) (result i32) (local i64)
+        (i32.add (local.get 0) (local.get 1))
+    )
+    (func (export "main") (result i32)
+        (i32.const 1)
+        (call $leaf (i32.const 2) (i32.const 3))
+        (i32.add)
+    )
+)"#;
+
+struct StackSize<const VALUES: usize>;
+
+impl<const VALUES: usize> Config for StackSize<VALUES> {
+    const MAX_VALUE_STACK_SIZE: usize = VALUES;
+}
+
+fn invoke_main<C: Config>(config: C, wasm_bytes: &[u8]) -> Result<i32, RuntimeError> {
+    let validation_info = validate(wasm_bytes).unwrap();
+    let mut store = Store::new(config);
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let main = store
+        .instance_export(module, "main")
+        .unwrap()
+        .as_func()
+        .unwrap();
+    store.invoke_simple_typed(main, ())
+}
+
+#[test_log::test]
+fn stack_bound_combines_frames_along_call_chain() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+
+    assert_eq!(
+        function_stack_usage(&validation_info, 0),
+        Some(FunctionStackUsage {
+            locals: 3,
+            max_operand_height: 2
+        })
+    );
+    assert_eq!(
+        function_stack_usage(&validation_info, 1),
+        Some(FunctionStackUsage {
+            locals: 0,
+            max_operand_height: 3
+        })
+    );
+
+    // the parameters of $leaf are part of the operands of main
+    let bounds = exported_stack_bounds(&validation_info, &StackBoundAnnotations::new());
+    assert_eq!(
+        bounds,
+        [(
+            "main",
+            StackBound::Bounded {
+                max_slots: 6,
+                max_call_frames: 2,
+                deepest_call_chain: vec![1, 0],
+            }
+        )]
+    );
+
+    let bound = &bounds[0].1;
+    assert!(bound.fits::<StackSize<6>>());
+    assert_eq!(invoke_main(StackSize::<6>, &wasm_bytes), Ok(6));
+
+    assert!(!bound.fits::<StackSize<4>>());
+    assert_eq!(
+        invoke_main(StackSize::<4>, &wasm_bytes),
+        Err(RuntimeError::StackExhaustion)
+    );
+}
+
+#[test_log::test]
+fn recursion_and_indirect_calls_are_unbounded() {
+    let wat = r#"
+    (module
+        (type $t (func))
+        (table 1 funcref)
+        (func $even (param i32)
+            (if (local.get 0) (then (call $odd (i32.sub (local.get 0) (i32.const 1)))))
+        )
+        (func $odd (param i32)
+            (if (local.get 0) (then (call $even (i32.sub (local.get 0) (i32.const 1)))))
+        )
+        (func $dispatch
+            (call_indirect (type $t) (i32.const 0))
+        )
+    )"#;
+    let wasm_bytes = wat::parse_str(wat).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let annotations = StackBoundAnnotations::new();
+
+    let Some(StackBound::Unbounded { reasons }) = stack_bound(&validation_info, &annotations, 0)
+    else {
+        panic!("expected the recursive function to be unbounded");
+    };
+    assert_eq!(
+        reasons.into_iter().collect::<Vec<_>>(),
+        [UnboundedReason::Recursion { func_idx: 0 }]
+    );
+
+    let Some(StackBound::Unbounded { reasons }) = stack_bound(&validation_info, &annotations, 2)
+    else {
+        panic!("expected the indirect call to be unbounded");
+    };
+    assert!(matches!(
+        reasons.into_iter().collect::<Vec<_>>()[..],
+        [UnboundedReason::IndirectCall { func_idx: 2, .. }]
+    ));
+
+    assert_eq!(stack_bound(&validation_info, &annotations, 3), None);
+}
+
+#[test_log::test]
+fn imported_functions_require_annotations() {
+    let wat = r#"
+    (module
+        (import "env" "callback" (func $callback (param i32) (result i32)))
+        (func (export "main") (result i32)
+            (call $callback (i32.const 1))
+        )
+    )"#;
+    let wasm_bytes = wat::parse_str(wat).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+
+    // the import could be a Wasm function of another module with an arbitrary stack usage
+    let mut annotations = StackBoundAnnotations::new();
+    let Some(StackBound::Unbounded { reasons }) = stack_bound(&validation_info, &annotations, 1)
+    else {
+        panic!("expected the call of the import to be unbounded");
+    };
+    assert_eq!(
+        reasons.into_iter().collect::<Vec<_>>(),
+        [UnboundedReason::ImportedFunction { func_idx: 0 }]
+    );
+
+    // a callee using 5 values including its parameter, only 4 of which are added to the caller's operands
+    annotations.set_imported_function_stack(0, 5, 2);
+    assert_eq!(
+        stack_bound(&validation_info, &annotations, 1),
+        Some(StackBound::Bounded {
+            max_slots: 5,
+            max_call_frames: 3,
+            deepest_call_chain: vec![1, 0],
+        })
+    );
+}
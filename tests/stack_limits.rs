@@ -0,0 +1,100 @@
+use checked::{Store, StoredRunState, StoredValue};
+use wasm::{config::StackLimits, validate, RuntimeError};
+
+const WAT: &str = r#"
+(module
+    (func $depth (export "depth") (param $n i32) (result i32)
+        (if (result i32) (i32.eqz (local.get $n))
+            (then (i32.const 0))
+            (else
+                (i32.add
+                    (call $depth (i32.sub (local.get $n) (i32.const 1)))
+                    (i32.const 1)
+                )
+            )
+        )
+    )
+)"#;
+
+#[test_log::test]
+fn store_limits_apply_to_all_invocations() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(());
+    assert_eq!(store.stack_limits(), StackLimits::default());
+
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let depth = store
+        .instance_export(module, "depth")
+        .unwrap()
+        .as_func()
+        .unwrap();
+    assert_eq!(
+        store.invoke_simple_typed::<i32, i32>(depth, 100).unwrap(),
+        100
+    );
+
+    store.set_stack_limits(StackLimits {
+        max_value_stack_size: StackLimits::default().max_value_stack_size,
+        max_call_stack_size: 50,
+    });
+    assert_eq!(
+        store.invoke_simple_typed::<i32, i32>(depth, 100),
+        Err(RuntimeError::StackExhaustion)
+    );
+    assert_eq!(
+        store.invoke_simple_typed::<i32, i32>(depth, 40).unwrap(),
+        40
+    );
+
+    store.set_stack_limits(StackLimits {
+        max_value_stack_size: 16,
+        max_call_stack_size: 4096,
+    });
+    assert_eq!(
+        store.invoke_simple_typed::<i32, i32>(depth, 100),
+        Err(RuntimeError::StackExhaustion)
+    );
+}
+
+#[test_log::test]
+fn invocation_limits_override_store_limits() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(());
+    store.set_stack_limits(StackLimits {
+        max_value_stack_size: 1024,
+        max_call_stack_size: 10,
+    });
+
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let depth = store
+        .instance_export(module, "depth")
+        .unwrap()
+        .as_func()
+        .unwrap();
+
+    let trusted = StackLimits {
+        max_value_stack_size: 0x10000,
+        max_call_stack_size: 1000,
+    };
+    let resumable = store
+        .create_resumable_with_stack_limits(depth, vec![StoredValue::I32(500)], None, trusted)
+        .unwrap();
+    let StoredRunState::Finished { values, .. } = store.resume(resumable).unwrap() else {
+        panic!("expected execution to finish");
+    };
+    assert_eq!(values, [StoredValue::I32(500)]);
+
+    // the store limits are unchanged for other invocations
+    assert_eq!(
+        store.invoke_simple_typed::<i32, i32>(depth, 500),
+        Err(RuntimeError::StackExhaustion)
+    );
+}
@@ -0,0 +1,361 @@
+use wasm::{
+    config::StackLimits,
+    resumable::{Resumable, ResumeError, RunState},
+    validate,
+    value_stack::StackStorage,
+    FuncType, NumType, ResultType, RuntimeError, Store, TrapError, ValType, Value,
+};
+
+const WAT: &str = r#"
+(module
+    (import "env" "double" (func $double (param i32) (result i32)))
+    (func $fib (export "fib") (param $n i32) (result i32)
+        (if (result i32) (i32.lt_u (local.get $n) (i32.const 2))
+            (then (local.get $n))
+            (else
+                (i32.add
+                    (call $fib (i32.sub (local.get $n) (i32.const 1)))
+                    (call $fib (i32.sub (local.get $n) (i32.const 2)))
+                )
+            )
+        )
+    )
+    (func (export "double_twice") (param $x i32) (result i32)
+        (call $double (call $double (local.get $x)))
+    )
+    (func (export "trap") (param $x i32) (result i32)
+        unreachable
+    )
+)"#;
+
+fn i32_to_i32() -> FuncType {
+    FuncType {
+        params: ResultType {
+            valtypes: vec![ValType::NumType(NumType::I32)],
+        },
+        returns: ResultType {
+            valtypes: vec![ValType::NumType(NumType::I32)],
+        },
+    }
+}
+
+#[test_log::test]
+fn storage_is_reused_across_resumables() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(());
+    let double = store.func_alloc(i32_to_i32(), 0).unwrap();
+
+    // SAFETY: The only extern value just came from the same store.
+    let module = unsafe {
+        store.module_instantiate(&validation_info, vec![wasm::ExternVal::Func(double)], None)
+    }
+    .unwrap()
+    .module_addr;
+    // SAFETY: This module address just came from the same store.
+    let fib = unsafe { store.instance_export(module, "fib") }
+        .unwrap()
+        .as_func()
+        .unwrap();
+    // SAFETY: This module address just came from the same store.
+    let double_twice = unsafe { store.instance_export(module, "double_twice") }
+        .unwrap()
+        .as_func()
+        .unwrap();
+
+    let mut storage = StackStorage::new(256, 64, 1).unwrap();
+    for (n, expected) in [(10, 55), (15, 610)] {
+        // SAFETY: The function address just came from the same store and no address type values are used.
+        let resumable =
+            unsafe { store.create_resumable_in(fib, &[Value::I32(n)], None, storage) }.unwrap();
+        let Resumable::Wasm(resumable) = resumable else {
+            panic!("expected a Wasm resumable");
+        };
+        // SAFETY: The resumable just came from the same store.
+        let run_state = unsafe { store.resume_wasm(resumable) }.unwrap();
+        let RunState::Finished {
+            values,
+            stack_storage: Some(stack_storage),
+            ..
+        } = run_state
+        else {
+            panic!("expected execution to finish and return its storage");
+        };
+        assert_eq!(values, [Value::I32(expected)]);
+        storage = stack_storage;
+        storage.reclaim(values);
+        assert_eq!(storage.value_capacity(), 256);
+        assert_eq!(storage.frame_capacity(), 64);
+    }
+
+    // SAFETY: The function address just came from the same store and no address type values are used.
+    let resumable =
+        unsafe { store.create_resumable_in(double_twice, &[Value::I32(3)], None, storage) }
+            .unwrap();
+    // SAFETY: The resumable just came from the same store.
+    let mut run_state = unsafe { store.resume(resumable) }.unwrap();
+    let (values, stack_storage) = loop {
+        match run_state {
+            RunState::HostCalled {
+                host_call,
+                resumable,
+            } => {
+                // the buffer of the parameters is reused for the return values
+                let mut params = host_call.params;
+                let Value::I32(x) = params[0] else {
+                    panic!("expected an i32 parameter");
+                };
+                params.clear();
+                params.push(Value::I32(x * 2));
+                // SAFETY: The host resumable just came from the same store.
+                run_state = unsafe { store.finish_host_call(resumable, params) }.unwrap();
+            }
+            RunState::Finished {
+                values,
+                stack_storage,
+                ..
+            } => break (values, stack_storage),
+            RunState::Interrupted { resumable, .. } => {
+                // SAFETY: The resumable just came from the same store.
+                run_state = unsafe { store.resume_wasm(resumable) }.unwrap();
+            }
+            RunState::Resumable { .. } => panic!("fuel is disabled"),
+        }
+    };
+    assert_eq!(values, [Value::I32(12)]);
+    let mut storage = stack_storage.unwrap();
+    storage.reclaim(values);
+    assert_eq!(storage.value_capacity(), 256);
+    assert_eq!(storage.host_params_capacity(), 1);
+
+    // host functions can also be invoked directly in preallocated storage
+    // SAFETY: The function address just came from the same store and no address type values are used.
+    let resumable =
+        unsafe { store.create_resumable_in(double, &[Value::I32(21)], None, storage) }.unwrap();
+    let (host_call, host_resumable) = resumable.as_host().unwrap();
+    let mut params = host_call.params;
+    params.clear();
+    params.push(Value::I32(42));
+    // SAFETY: The host resumable just came from the same store.
+    let run_state = unsafe { store.finish_host_call(host_resumable, params) }.unwrap();
+    let RunState::Finished {
+        values,
+        stack_storage: Some(stack_storage),
+        ..
+    } = run_state
+    else {
+        panic!("expected execution to finish and return its storage");
+    };
+    assert_eq!(values, [Value::I32(42)]);
+    assert_eq!(stack_storage.host_params_capacity(), 1);
+}
+
+#[test_log::test]
+fn exhausted_storage_is_an_error() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(());
+    let double = store.func_alloc(i32_to_i32(), 0).unwrap();
+
+    // SAFETY: The only extern value just came from the same store.
+    let module = unsafe {
+        store.module_instantiate(&validation_info, vec![wasm::ExternVal::Func(double)], None)
+    }
+    .unwrap()
+    .module_addr;
+    // SAFETY: This module address just came from the same store.
+    let fib = unsafe { store.instance_export(module, "fib") }
+        .unwrap()
+        .as_func()
+        .unwrap();
+
+    // the parameters do not fit
+    let storage = StackStorage::new(0, 1, 0).unwrap();
+    // SAFETY: The function address just came from the same store and no address type values are used.
+    let result = unsafe { store.create_resumable_in(fib, &[Value::I32(20)], None, storage) };
+    assert_eq!(result.err(), Some(RuntimeError::StackExhaustion));
+
+    // the recursion does not fit
+    let storage = StackStorage::new(16, 4, 0).unwrap();
+    // SAFETY: The function address just came from the same store and no address type values are used.
+    let resumable =
+        unsafe { store.create_resumable_in(fib, &[Value::I32(20)], None, storage) }.unwrap();
+    // SAFETY: The resumable just came from the same store.
+    let result = unsafe { store.resume(resumable) };
+    assert_eq!(result.err(), Some(RuntimeError::StackExhaustion));
+}
+
+#[test_log::test]
+fn storage_is_recovered_after_failed_execution() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(());
+    let double = store.func_alloc(i32_to_i32(), 0).unwrap();
+
+    // SAFETY: The only extern value just came from the same store.
+    let module = unsafe {
+        store.module_instantiate(&validation_info, vec![wasm::ExternVal::Func(double)], None)
+    }
+    .unwrap()
+    .module_addr;
+    // SAFETY: This module address just came from the same store.
+    let fib = unsafe { store.instance_export(module, "fib") }
+        .unwrap()
+        .as_func()
+        .unwrap();
+    // SAFETY: This module address just came from the same store.
+    let trap = unsafe { store.instance_export(module, "trap") }
+        .unwrap()
+        .as_func()
+        .unwrap();
+
+    let mut storage = StackStorage::new(16, 4, 0).unwrap();
+    for (func, expected_error) in [
+        (fib, RuntimeError::StackExhaustion),
+        (trap, RuntimeError::Trap(TrapError::ReachedUnreachable)),
+    ] {
+        // SAFETY: The function address just came from the same store and no address type values are used.
+        let resumable =
+            unsafe { store.create_resumable_in(func, &[Value::I32(20)], None, storage) }.unwrap();
+        let Resumable::Wasm(resumable) = resumable else {
+            panic!("expected a Wasm resumable");
+        };
+        // SAFETY: The resumable just came from the same store.
+        let result = unsafe { store.resume_wasm_or_reclaim(resumable) };
+        let Err(ResumeError {
+            error,
+            stack_storage: Some(stack_storage),
+        }) = result
+        else {
+            panic!("expected execution to fail and return its storage");
+        };
+        assert_eq!(error, expected_error);
+        storage = stack_storage;
+    }
+
+    // the recovered storage can be reused
+    // SAFETY: The function address just came from the same store and no address type values are used.
+    let resumable =
+        unsafe { store.create_resumable_in(fib, &[Value::I32(2)], None, storage) }.unwrap();
+    let Resumable::Wasm(resumable) = resumable else {
+        panic!("expected a Wasm resumable");
+    };
+    // SAFETY: The resumable just came from the same store.
+    let run_state = unsafe { store.resume_wasm_or_reclaim(resumable) }.unwrap();
+    let RunState::Finished {
+        values,
+        stack_storage: Some(_),
+        ..
+    } = run_state
+    else {
+        panic!("expected execution to finish and return its storage");
+    };
+    assert_eq!(values, [Value::I32(1)]);
+}
+
+#[test_log::test]
+fn storage_is_kept_while_host_call_waits_for_fuel() {
+    let mut store = Store::new(());
+    let double = store.func_alloc(i32_to_i32(), 0).unwrap();
+
+    let storage = StackStorage::new(16, 4, 1).unwrap();
+    // SAFETY: The function address just came from the same store and no address type values are used.
+    let resumable =
+        unsafe { store.create_resumable_in(double, &[Value::I32(21)], Some(5), storage) }.unwrap();
+    let Resumable::Host {
+        host_call,
+        host_resumable,
+    } = resumable
+    else {
+        panic!("expected a host resumable");
+    };
+    let Value::I32(x) = host_call.params[0] else {
+        panic!("expected an i32 parameter");
+    };
+    let mut return_values = host_call.params;
+    return_values.clear();
+    return_values.push(Value::I32(x * 2));
+
+    // the host call is finished, but its return values are only returned once it is paid for
+    // SAFETY: The host resumable just came from the same store and no address type values are returned.
+    let run_state =
+        unsafe { store.finish_host_call_with_fuel(host_resumable, return_values, 8) }.unwrap();
+    let RunState::Resumable {
+        mut resumable,
+        required_fuel,
+    } = run_state
+    else {
+        panic!("expected the host call to wait for fuel");
+    };
+    assert_eq!(required_fuel.get(), 3);
+
+    *resumable.fuel_mut() = Some(10);
+    // SAFETY: The resumable just came from the same store.
+    let run_state = unsafe { store.resume_wasm_or_reclaim(resumable) }.unwrap();
+    let RunState::Finished {
+        values,
+        maybe_remaining_fuel,
+        stack_storage: Some(stack_storage),
+    } = run_state
+    else {
+        panic!("expected the host call to finish and return its storage");
+    };
+    assert_eq!(values, [Value::I32(42)]);
+    assert_eq!(maybe_remaining_fuel, Some(2));
+    assert_eq!(stack_storage.value_capacity(), 16);
+    assert_eq!(stack_storage.host_params_capacity(), 1);
+}
+
+#[test_log::test]
+fn invocation_limits_apply_to_storage() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(());
+    let double = store.func_alloc(i32_to_i32(), 0).unwrap();
+
+    // SAFETY: The only extern value just came from the same store.
+    let module = unsafe {
+        store.module_instantiate(&validation_info, vec![wasm::ExternVal::Func(double)], None)
+    }
+    .unwrap()
+    .module_addr;
+    // SAFETY: This module address just came from the same store.
+    let fib = unsafe { store.instance_export(module, "fib") }
+        .unwrap()
+        .as_func()
+        .unwrap();
+
+    // the storage is large enough, but the invocation limits are not
+    let storage = StackStorage::new(256, 64, 0).unwrap();
+    let limits = StackLimits {
+        max_value_stack_size: 256,
+        max_call_stack_size: 4,
+    };
+    // SAFETY: The function address just came from the same store and no address type values are used.
+    let resumable = unsafe {
+        store.create_resumable_in_with_stack_limits(fib, &[Value::I32(20)], None, storage, limits)
+    }
+    .unwrap();
+    let Resumable::Wasm(resumable) = resumable else {
+        panic!("expected a Wasm resumable");
+    };
+    // SAFETY: The resumable just came from the same store.
+    let result = unsafe { store.resume_wasm_or_reclaim(resumable) };
+    let Err(ResumeError {
+        error: RuntimeError::StackExhaustion,
+        stack_storage: Some(storage),
+    }) = result
+    else {
+        panic!("expected the stack to be exhausted");
+    };
+
+    // the store limits are used otherwise
+    // SAFETY: The function address just came from the same store and no address type values are used.
+    let resumable =
+        unsafe { store.create_resumable_in(fib, &[Value::I32(20)], None, storage) }.unwrap();
+    // SAFETY: The resumable just came from the same store.
+    let Ok(RunState::Finished { values, .. }) = (unsafe { store.resume(resumable) }) else {
+        panic!("expected execution to finish");
+    };
+    assert_eq!(values, [Value::I32(6765)]);
+}
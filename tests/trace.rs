@@ -0,0 +1,330 @@
+use checked::{
+    trace::{replay, ReplayError, Trace, TraceEvent, TraceRecorder},
+    Store, Stored, StoredExternVal, StoredResumable, StoredRunState, StoredValue,
+};
+use wasm::{addrs::FuncAddr, validate, RuntimeError, ValidationInfo, Value};
+
+const WAT: &str = r#"
+(module
+    (import "env" "random" (func $random (result i32)))
+    (func (export "sum_random") (param $n i32) (result i32)
+        (local $sum i32)
+        (block $done
+            (loop $loop
+                (br_if $done (i32.eqz (local.get $n)))
+                (local.set $sum (i32.add (local.get $sum) (call $random)))
+                (local.set $n (i32.sub (local.get $n) (i32.const 1)))
+                (br $loop)
+            )
+        )
+        (local.get $sum)
+    )
+)"#;
+
+/// Instantiates the module in a fresh store and returns the store together with the exported function
+fn instantiate<'b>(validation_info: &ValidationInfo<'b>) -> (Store<'b, ()>, Stored<FuncAddr>) {
+    let mut store = Store::new(());
+    let random = store.func_alloc_typed::<(), i32>(0).unwrap();
+    let module = store
+        .module_instantiate(validation_info, vec![StoredExternVal::Func(random)], None)
+        .unwrap()
+        .module_addr;
+    let function = store
+        .instance_export(module, "sum_random")
+        .unwrap()
+        .as_func()
+        .unwrap();
+    (store, function)
+}
+
+/// Records an invocation of `sum_random` that is resumed in slices of 10 fuel, with the host returning the given
+/// values. Returns the recorded trace and the result of the invocation.
+fn record(validation_info: &ValidationInfo, host_values: &[u32]) -> (Trace, Vec<StoredValue>) {
+    let (mut store, function) = instantiate(validation_info);
+    let mut recorder = TraceRecorder::new();
+    let mut host_values = host_values.iter();
+
+    let resumable = recorder
+        .create_resumable(
+            &mut store,
+            function,
+            vec![StoredValue::I32(host_values.len() as u32)],
+            Some(10),
+        )
+        .unwrap()
+        .as_wasm()
+        .unwrap();
+    let mut run_state = recorder.resume_wasm(&mut store, resumable).unwrap();
+    let values = loop {
+        run_state = match run_state {
+            StoredRunState::Finished { values, .. } => break values,
+            StoredRunState::Resumable { mut resumable, .. } => {
+                *resumable.fuel_mut() = Some(10);
+                recorder.resume_wasm(&mut store, resumable).unwrap()
+            }
+            StoredRunState::Interrupted { resumable, .. } => {
+                recorder.resume_wasm(&mut store, resumable).unwrap()
+            }
+            StoredRunState::HostCalled {
+                host_call,
+                resumable,
+            } => {
+                assert_eq!(host_call.hostcode, 0);
+                let value = *host_values.next().unwrap();
+                recorder
+                    .finish_host_call(&mut store, resumable, vec![StoredValue::I32(value)])
+                    .unwrap()
+            }
+        };
+    };
+    (recorder.into_trace(), values)
+}
+
+#[test_log::test]
+fn replay_reproduces_recorded_execution() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let (trace, values) = record(&validation_info, &[3, 5, 7, 11]);
+    assert_eq!(values, [StoredValue::I32(26)]);
+
+    let host_returns = trace
+        .events
+        .iter()
+        .filter(|event| matches!(event, TraceEvent::HostReturn { .. }))
+        .count();
+    assert_eq!(host_returns, 4);
+    let pauses = trace
+        .events
+        .iter()
+        .filter(|event| matches!(event, TraceEvent::Paused { .. }))
+        .count();
+    assert!(pauses > 0, "execution should have been sliced by fuel");
+
+    // replay in a fresh store, without any host
+    let trace = Trace::decode(&trace.encode()).unwrap();
+    let (mut store, function) = instantiate(&validation_info);
+    let replayed_values = replay(&mut store, function, &trace).unwrap();
+    assert_eq!(replayed_values, [StoredValue::I32(26)]);
+}
+
+#[test_log::test]
+fn replay_detects_divergence() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let (mut trace, _) = record(&validation_info, &[1, 2]);
+
+    // tamper with the recorded return values of the first host call
+    let host_return = trace
+        .events
+        .iter_mut()
+        .filter_map(|event| match event {
+            TraceEvent::HostReturn { values, .. } => Some(values),
+            _ => None,
+        })
+        .next()
+        .unwrap();
+    *host_return = vec![Value::I32(100)];
+
+    let (mut store, function) = instantiate(&validation_info);
+    let err = replay(&mut store, function, &trace).unwrap_err();
+    let ReplayError::Divergence {
+        expected: TraceEvent::Finished { values, .. },
+        found: TraceEvent::Finished {
+            values: found_values,
+            ..
+        },
+        ..
+    } = err
+    else {
+        panic!("expected a divergence of the final values, got {err:?}");
+    };
+    assert_eq!(values, [Value::I32(3)]);
+    assert_eq!(found_values, [Value::I32(102)]);
+}
+
+#[test_log::test]
+fn truncated_trace_is_rejected() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let (trace, _) = record(&validation_info, &[1]);
+    let bytes = trace.encode();
+    assert!(Trace::decode(&bytes[..bytes.len() - 1]).is_err());
+
+    let mut truncated = trace.clone();
+    truncated.events.pop();
+    let (mut store, function) = instantiate(&validation_info);
+    assert_eq!(
+        replay(&mut store, function, &truncated),
+        Err(ReplayError::UnexpectedEndOfTrace)
+    );
+}
+
+const HOST_WRITES_WAT: &str = r#"
+(module
+    (import "env" "poke" (func $poke))
+    (memory (export "memory") 1)
+    (global (export "global") (mut i32) (i32.const 0))
+    (func (export "run") (result i32)
+        (call $poke)
+        (i32.add (i32.load (i32.const 8)) (global.get 0))
+    )
+)"#;
+
+#[test_log::test]
+fn replay_applies_host_writes() {
+    let wasm_bytes = wat::parse_str(HOST_WRITES_WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let instantiate = || {
+        let mut store = Store::new(());
+        let poke = store.func_alloc_typed::<(), ()>(0).unwrap();
+        let module = store
+            .module_instantiate(&validation_info, vec![StoredExternVal::Func(poke)], None)
+            .unwrap()
+            .module_addr;
+        let export = |name| store.instance_export(module, name).unwrap();
+        let (run, memory, global) = (
+            export("run").as_func().unwrap(),
+            export("memory").as_mem().unwrap(),
+            export("global").as_global().unwrap(),
+        );
+        (store, run, memory, global)
+    };
+
+    let (mut store, run, memory, global) = instantiate();
+    let mut recorder = TraceRecorder::new();
+    let StoredResumable::Wasm(resumable) = recorder
+        .create_resumable(&mut store, run, Vec::new(), None)
+        .unwrap()
+    else {
+        panic!("expected a wasm resumable");
+    };
+    let StoredRunState::HostCalled { resumable, .. } =
+        recorder.resume_wasm(&mut store, resumable).unwrap()
+    else {
+        panic!("expected a host call");
+    };
+    store.mem_write_typed(memory, 8, 40_u32).unwrap();
+    store.global_write(global, StoredValue::I32(2)).unwrap();
+    let StoredRunState::Interrupted { resumable, .. } = recorder
+        .finish_host_call(&mut store, resumable, Vec::new())
+        .unwrap()
+    else {
+        panic!("expected execution to be interrupted after the host call");
+    };
+    let StoredRunState::Finished { values, .. } =
+        recorder.resume_wasm(&mut store, resumable).unwrap()
+    else {
+        panic!("expected the invocation to finish");
+    };
+    assert_eq!(values, [StoredValue::I32(42)]);
+    let trace = Trace::decode(&recorder.into_trace().encode()).unwrap();
+    assert!(trace
+        .events
+        .iter()
+        .any(|event| matches!(event, TraceEvent::StoreWrites { writes } if writes.len() == 2)));
+
+    let (mut store, run, ..) = instantiate();
+    assert_eq!(
+        replay(&mut store, run, &trace),
+        Ok(vec![StoredValue::I32(42)])
+    );
+
+    // a store that starts in a different state is brought into the recorded initial state
+    let (mut store, run, memory, global) = instantiate();
+    store.mem_write_typed(memory, 8, 1000_u32).unwrap();
+    store.global_write(global, StoredValue::I32(7)).unwrap();
+    assert_eq!(
+        replay(&mut store, run, &trace),
+        Ok(vec![StoredValue::I32(42)])
+    );
+
+    // a store with a different structure cannot be restored
+    let mut store = Store::new(());
+    store.func_alloc_typed::<(), ()>(0).unwrap();
+    let run = store.func_alloc_typed::<(), i32>(1).unwrap();
+    assert_eq!(
+        replay(&mut store, run, &trace),
+        Err(ReplayError::RuntimeError(
+            RuntimeError::StateSnapshotMismatch
+        ))
+    );
+}
+
+#[test_log::test]
+fn initial_state_is_recorded() {
+    let wasm_bytes = wat::parse_str(HOST_WRITES_WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(());
+    let poke = store.func_alloc_typed::<(), ()>(0).unwrap();
+    let module = store
+        .module_instantiate(&validation_info, vec![StoredExternVal::Func(poke)], None)
+        .unwrap()
+        .module_addr;
+    let run = store
+        .instance_export(module, "run")
+        .unwrap()
+        .as_func()
+        .unwrap();
+    let memory = store
+        .instance_export(module, "memory")
+        .unwrap()
+        .as_mem()
+        .unwrap();
+    store.mem_write_typed(memory, 8, 40_u32).unwrap();
+    let fuel_pool = store.fuel_pool_alloc(5).unwrap();
+
+    let mut recorder = TraceRecorder::new();
+    recorder
+        .create_resumable(&mut store, run, Vec::new(), None)
+        .unwrap();
+    let trace = Trace::decode(&recorder.into_trace().encode()).unwrap();
+    let TraceEvent::Invoke { initial_state, .. } = &trace.events[0] else {
+        panic!("expected the trace to start with an invocation");
+    };
+    assert_eq!(initial_state.memories.len(), 1);
+    assert_eq!(initial_state.memories[0].len(), 65536);
+    assert_eq!(initial_state.memories[0][8..12], 40_u32.to_le_bytes());
+    assert_eq!(initial_state.globals, [Value::I32(0)]);
+    assert!(initial_state.tables.is_empty());
+    assert_eq!(initial_state.fuel_pools, [5]);
+    assert_eq!(store.fuel_pool_remaining(fuel_pool), 5);
+}
+
+#[test_log::test]
+fn host_return_digests_are_opt_in() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let (trace, _) = record(&validation_info, &[1]);
+    assert!(trace.events.iter().all(|event| !matches!(
+        event,
+        TraceEvent::HostReturn {
+            state_digest: Some(_),
+            ..
+        }
+    )));
+
+    let (mut store, function) = instantiate(&validation_info);
+    let mut recorder = TraceRecorder::new();
+    recorder.set_host_return_digests(true);
+    let resumable = recorder
+        .create_resumable(&mut store, function, vec![StoredValue::I32(1)], None)
+        .unwrap()
+        .as_wasm()
+        .unwrap();
+    let StoredRunState::HostCalled { resumable, .. } =
+        recorder.resume_wasm(&mut store, resumable).unwrap()
+    else {
+        panic!("expected a host call");
+    };
+    let digest = store.state_digest();
+    recorder
+        .finish_host_call(&mut store, resumable, vec![StoredValue::I32(1)])
+        .unwrap();
+    assert!(recorder.trace().events.iter().any(|event| matches!(
+        event,
+        TraceEvent::HostReturn {
+            state_digest: Some(recorded),
+            ..
+        } if *recorded == digest
+    )));
+}
@@ -39,7 +39,7 @@ fn invoke_typed() {
 fn host_function() {
     let mut store = Store::new(());
 
-    let consume_i32 = store.func_alloc_typed::<i32, ()>(123);
+    let consume_i32 = store.func_alloc_typed::<i32, ()>(123).unwrap();
 
     // SAFETY: The function address just came from the same store and no address
     // type values are used.
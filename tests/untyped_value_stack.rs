@@ -0,0 +1,137 @@
+use checked::{Store, StoredExternVal};
+use interop::RefExtern;
+use registry::Registry;
+use wasm::{validate, value::ExternAddr};
+
+const WAT: &str = r#"
+(module
+    (import "env" "swap_lanes" (func $swap_lanes (param v128 i32) (result v128 i32)))
+    (global $last (mut v128) (v128.const i64x2 0 0))
+    (func $add_splat (param $a i32) (param $v v128) (param $b i64) (result v128 i32)
+        (local $sum v128)
+        (local $next i32)
+        (local.set $sum (i64x2.add (local.get $v) (i64x2.splat (local.get $b))))
+        (local.set $next (i32.add (local.get $a) (i32.const 1)))
+        (global.set $last (local.get $sum))
+        (local.get $sum)
+        (local.get $next)
+    )
+    (func (export "run") (param $a i32) (param $v v128) (param $b i64) (result v128 i32)
+        (local $r v128)
+        (drop (local.get $v))
+        (local.set $r (select (local.get $v) (v128.const i64x2 7 7) (local.get $a)))
+        (local.set $r
+            (block $out (result v128)
+                (i32.const 1)
+                (v128.const i64x2 1 1)
+                (local.get $r)
+                (br_if $out (local.get $a))
+                (drop)
+                (drop)
+                (drop)
+                (v128.const i64x2 -1 -1)
+            )
+        )
+        (call $add_splat (local.get $a) (local.get $r) (local.get $b))
+    )
+    (func (export "last") (result v128)
+        (select (result v128) (global.get $last) (v128.const i64x2 0 0) (i32.const 1))
+    )
+    (func (export "host") (param $v v128) (param $x i32) (result v128 i32)
+        (call $swap_lanes (local.get $v) (local.get $x))
+    )
+    (func (export "refs") (param $r externref) (result externref externref i32)
+        (local.get $r)
+        (ref.null extern)
+        (ref.is_null (local.get $r))
+    )
+)"#;
+
+fn i64x2(low: i64, high: i64) -> [u8; 16] {
+    let mut bytes = [0; 16];
+    bytes[..8].copy_from_slice(&low.to_le_bytes());
+    bytes[8..].copy_from_slice(&high.to_le_bytes());
+    bytes
+}
+
+#[test_log::test]
+fn v128_and_scalar_values_share_the_stack() {
+    let wasm_bytes = wat::parse_str(WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(());
+    let mut registry = Registry::default();
+    let swap_lanes = registry
+        .alloc_host_function_typed(&mut store, |(), (v, x): ([u8; 16], i32)| {
+            let (low, high) = v.split_at(8);
+            let mut swapped = [0; 16];
+            swapped[..8].copy_from_slice(high);
+            swapped[8..].copy_from_slice(low);
+            (swapped, x * 2)
+        })
+        .unwrap();
+    let module = store
+        .module_instantiate(
+            &validation_info,
+            vec![StoredExternVal::Func(swap_lanes)],
+            None,
+        )
+        .unwrap()
+        .module_addr;
+    let function = |name| {
+        store
+            .instance_export(module, name)
+            .unwrap()
+            .as_func()
+            .unwrap()
+    };
+    let (run, last, host, refs) = (
+        function("run"),
+        function("last"),
+        function("host"),
+        function("refs"),
+    );
+
+    // the branch is taken and keeps the selected parameter
+    assert_eq!(
+        store.invoke_simple_typed::<(i32, [u8; 16], i64), ([u8; 16], i32)>(
+            run,
+            (1, i64x2(100, -200), 10)
+        ),
+        Ok((i64x2(110, -190), 2))
+    );
+    assert_eq!(
+        store.invoke_simple_typed::<(), [u8; 16]>(last, ()),
+        Ok(i64x2(110, -190))
+    );
+
+    // the branch is not taken and the values below it are dropped
+    assert_eq!(
+        store.invoke_simple_typed::<(i32, [u8; 16], i64), ([u8; 16], i32)>(
+            run,
+            (0, i64x2(100, -200), 10)
+        ),
+        Ok((i64x2(9, 9), 1))
+    );
+
+    // values are converted at the boundary to host functions
+    assert_eq!(
+        registry.invoke_without_fuel_typed::<_, ([u8; 16], i32), ([u8; 16], i32)>(
+            &mut (),
+            &mut store,
+            host,
+            (i64x2(1, 2), 21)
+        ),
+        Ok((i64x2(2, 1), 42))
+    );
+
+    // references keep their type and address
+    let extern_ref = RefExtern(Some(ExternAddr(1234)));
+    assert_eq!(
+        store.invoke_simple_typed::<RefExtern, (RefExtern, RefExtern, i32)>(refs, extern_ref),
+        Ok((extern_ref, RefExtern(None), 0))
+    );
+    assert_eq!(
+        store.invoke_simple_typed::<RefExtern, (RefExtern, RefExtern, i32)>(refs, RefExtern(None)),
+        Ok((RefExtern(None), RefExtern(None), 1))
+    );
+}
@@ -15,7 +15,9 @@ fn counter() {
     let mut my_counter = MyCounter(0);
     let mut store = Store::new(());
     let mut registry = Registry::default();
-    let add_one = registry.alloc_host_function_typed(&mut store, add_one);
+    let add_one = registry
+        .alloc_host_function_typed(&mut store, add_one)
+        .unwrap();
 
     for _ in 0..5 {
         registry
@@ -43,7 +45,9 @@ fn channels() {
         let mut my_sender = MySender(tx);
         let mut store = Store::new(());
         let mut registry = Registry::default();
-        let send_message = registry.alloc_host_function_typed(&mut store, send_message);
+        let send_message = registry
+            .alloc_host_function_typed(&mut store, send_message)
+            .unwrap();
 
         registry
             .invoke_without_fuel_typed::<_, (), ()>(&mut my_sender, &mut store, send_message, ())
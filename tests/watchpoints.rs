@@ -0,0 +1,253 @@
+use core::ops::ControlFlow;
+
+use checked::{Store, StoredRunState, StoredValue};
+use wasm::{
+    addrs::{GlobalAddr, MemAddr},
+    config::Config,
+    resumable::InterruptReason,
+    validate,
+    watchpoints::{WatchKind, WatchedAccess, Watchpoint, Watchpoints},
+    Value,
+};
+
+const MEMORY_WAT: &str = r#"
+(module
+    (memory (export "memory") 1)
+    (func (export "store_and_load") (result i32)
+        (i32.store (i32.const 8) (i32.const 1))
+        (i32.store (i32.const 16) (i32.const 2))
+        (i64.store (i32.const 20) (i64.const 3))
+        (i32.load (i32.const 16))
+    )
+)"#;
+
+#[test_log::test]
+fn memory_watchpoint_interrupts_execution() {
+    let wasm_bytes = wat::parse_str(MEMORY_WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(Watchpoints::<()>::default());
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let memory = store
+        .instance_export(module, "memory")
+        .unwrap()
+        .as_mem()
+        .unwrap();
+    let function = store
+        .instance_export(module, "store_and_load")
+        .unwrap()
+        .as_func()
+        .unwrap();
+
+    let watchpoint = store.user_data_mut().add(Watchpoint::Memory {
+        mem_addr: *memory,
+        range: 16..20,
+        kind: WatchKind::Store,
+    });
+
+    let resumable = store
+        .create_resumable(function, Vec::new(), None)
+        .unwrap()
+        .as_wasm()
+        .unwrap();
+    let StoredRunState::Interrupted {
+        resumable,
+        reason: InterruptReason::AccessHook,
+    } = store.resume_wasm(resumable).unwrap()
+    else {
+        panic!("expected execution to be interrupted by the watchpoint");
+    };
+
+    let hits = store.user_data_mut().take_hits();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].id, watchpoint);
+    assert_eq!(
+        hits[0].access,
+        WatchedAccess::Store {
+            mem_addr: *memory,
+            address: 16,
+            len: 4
+        }
+    );
+    // the instruction that triggered the watchpoint has completed already
+    assert_eq!(store.mem_read(memory, 16).unwrap(), 2);
+    assert_eq!(store.mem_read(memory, 20).unwrap(), 0);
+
+    // the store to 20..28 and the load from 16..20 do not trigger the store watchpoint
+    let StoredRunState::Finished { values, .. } = store.resume_wasm(resumable).unwrap() else {
+        panic!("expected execution to finish");
+    };
+    assert_eq!(values, [StoredValue::I32(2)]);
+    assert!(store.user_data_mut().take_hits().is_empty());
+}
+
+#[test_log::test]
+fn simple_invocation_continues_after_watchpoint_hits() {
+    let wasm_bytes = wat::parse_str(MEMORY_WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(Watchpoints::<()>::default());
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let memory = store
+        .instance_export(module, "memory")
+        .unwrap()
+        .as_mem()
+        .unwrap();
+    let function = store
+        .instance_export(module, "store_and_load")
+        .unwrap()
+        .as_func()
+        .unwrap();
+
+    store.user_data_mut().add(Watchpoint::Memory {
+        mem_addr: *memory,
+        range: 0..32,
+        kind: WatchKind::LoadOrStore,
+    });
+
+    let result: i32 = store.invoke_simple_typed(function, ()).unwrap();
+    assert_eq!(result, 2);
+    assert_eq!(store.user_data_mut().take_hits().len(), 4);
+}
+
+#[test_log::test]
+fn global_watchpoint_interrupts_on_every_set() {
+    let wat = r#"
+    (module
+        (global $counter (export "counter") (mut i32) (i32.const 0))
+        (global $other (mut i32) (i32.const 0))
+        (func (export "count_to_three")
+            (loop
+                (global.set $other (i32.const 42))
+                (global.set $counter (i32.add (global.get $counter) (i32.const 1)))
+                (br_if 0 (i32.lt_u (global.get $counter) (i32.const 3)))
+            )
+        )
+    )"#;
+    let wasm_bytes = wat::parse_str(wat).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(Watchpoints::<()>::default());
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let counter = store
+        .instance_export(module, "counter")
+        .unwrap()
+        .as_global()
+        .unwrap();
+    let function = store
+        .instance_export(module, "count_to_three")
+        .unwrap()
+        .as_func()
+        .unwrap();
+
+    store.user_data_mut().add(Watchpoint::Global(*counter));
+
+    let mut resumable = store
+        .create_resumable(function, Vec::new(), None)
+        .unwrap()
+        .as_wasm()
+        .unwrap();
+    let mut observed_values = Vec::new();
+    loop {
+        match store.resume_wasm(resumable).unwrap() {
+            StoredRunState::Interrupted {
+                resumable: next_resumable,
+                reason: InterruptReason::AccessHook,
+            } => {
+                for hit in store.user_data_mut().take_hits() {
+                    let WatchedAccess::GlobalSet { global_addr, value } = hit.access else {
+                        panic!("unexpected access {:?}", hit.access);
+                    };
+                    assert_eq!(global_addr, *counter);
+                    observed_values.push(value);
+                }
+                resumable = next_resumable;
+            }
+            StoredRunState::Finished { .. } => break,
+            _ => panic!("unexpected run state"),
+        }
+    }
+
+    assert_eq!(
+        observed_values,
+        [Value::I32(1), Value::I32(2), Value::I32(3)]
+    );
+}
+
+#[derive(Default)]
+struct AccessLog {
+    loads: Vec<(MemAddr, usize, Vec<u8>)>,
+    stores: Vec<(MemAddr, usize, Vec<u8>)>,
+    global_sets: Vec<(GlobalAddr, Value)>,
+}
+
+impl Config for AccessLog {
+    fn memory_load_hook(
+        &mut self,
+        mem_addr: MemAddr,
+        address: usize,
+        bytes: &[u8],
+    ) -> ControlFlow<()> {
+        self.loads.push((mem_addr, address, bytes.to_vec()));
+        ControlFlow::Continue(())
+    }
+
+    fn memory_store_hook(
+        &mut self,
+        mem_addr: MemAddr,
+        address: usize,
+        bytes: &[u8],
+    ) -> ControlFlow<()> {
+        self.stores.push((mem_addr, address, bytes.to_vec()));
+        ControlFlow::Continue(())
+    }
+
+    fn global_set_hook(&mut self, global_addr: GlobalAddr, value: Value) -> ControlFlow<()> {
+        self.global_sets.push((global_addr, value));
+        ControlFlow::Continue(())
+    }
+}
+
+#[test_log::test]
+fn access_hooks_observe_address_width_and_value() {
+    let wasm_bytes = wat::parse_str(MEMORY_WAT).unwrap();
+    let validation_info = validate(&wasm_bytes).unwrap();
+    let mut store = Store::new(AccessLog::default());
+    let module = store
+        .module_instantiate(&validation_info, Vec::new(), None)
+        .unwrap()
+        .module_addr;
+    let function = store
+        .instance_export(module, "store_and_load")
+        .unwrap()
+        .as_func()
+        .unwrap();
+
+    let result: i32 = store.invoke_simple_typed(function, ()).unwrap();
+    assert_eq!(result, 2);
+
+    let log = &store.inner().user_data;
+    let stores: Vec<(usize, Vec<u8>)> = log
+        .stores
+        .iter()
+        .map(|(_, address, bytes)| (*address, bytes.clone()))
+        .collect();
+    assert_eq!(
+        stores,
+        [
+            (8, 1u32.to_le_bytes().to_vec()),
+            (16, 2u32.to_le_bytes().to_vec()),
+            (20, 3u64.to_le_bytes().to_vec()),
+        ]
+    );
+    assert_eq!(log.loads.len(), 1);
+    assert_eq!(log.loads[0].1, 16);
+    assert_eq!(log.loads[0].2, 2u32.to_le_bytes());
+    assert!(log.global_sets.is_empty());
+}